@@ -0,0 +1,131 @@
+use anyhow::{anyhow, Result};
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+use std::path::{Component, Path};
+
+/// Compiled `exclude_patterns` for one monitored directory.
+///
+/// Patterns are globs matched against the path relative to the monitored
+/// root, using `/` as the separator on every platform. A pattern without a
+/// `/` (such as `*.tmp`, `.*` or `@eaDir`) matches a single component at any
+/// depth; a pattern with a `/` (such as `**/extras/**` or `Movies/sample-*`)
+/// is anchored at the root. A path is excluded when it, or any of its parent
+/// directories, matches, so excluding a folder also excludes its contents.
+#[derive(Debug, Clone)]
+pub struct ExcludeMatcher {
+    globs: GlobSet,
+}
+
+impl ExcludeMatcher {
+    /// Compile `patterns`, skipping (and logging) any that are not valid
+    /// globs. Configuration validation rejects those before they get here.
+    pub fn new(patterns: &[String], case_sensitive: bool) -> Self {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            match compile(pattern, case_sensitive) {
+                Ok(glob) => {
+                    builder.add(glob);
+                }
+                Err(error) => tracing::warn!("Ignoring exclude pattern: {}", error),
+            }
+        }
+        let globs = builder.build().unwrap_or_else(|error| {
+            tracing::warn!("Failed to build exclude patterns: {}", error);
+            GlobSet::empty()
+        });
+        Self { globs }
+    }
+
+    /// Check that `pattern` is a valid exclude glob.
+    pub fn validate_pattern(pattern: &str) -> Result<()> {
+        compile(pattern, true).map(|_| ())
+    }
+
+    /// Whether `relative`, a path below the monitored root, is excluded.
+    pub fn is_excluded(&self, relative: &Path) -> bool {
+        if self.globs.is_empty() {
+            return false;
+        }
+        let mut prefix = String::new();
+        for component in relative.components() {
+            let Component::Normal(name) = component else {
+                continue;
+            };
+            if !prefix.is_empty() {
+                prefix.push('/');
+            }
+            prefix.push_str(&name.to_string_lossy());
+            if self.globs.is_match(&prefix) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+fn compile(pattern: &str, case_sensitive: bool) -> Result<Glob> {
+    let trimmed = pattern.trim().trim_matches('/');
+    if trimmed.is_empty() {
+        return Err(anyhow!("exclude pattern cannot be empty"));
+    }
+    let glob = if trimmed.contains('/') {
+        trimmed.to_owned()
+    } else {
+        format!("**/{trimmed}")
+    };
+    GlobBuilder::new(&glob)
+        .literal_separator(true)
+        .case_insensitive(!case_sensitive)
+        .build()
+        .map_err(|error| anyhow!("invalid exclude pattern '{}': {}", pattern, error.kind()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher(patterns: &[&str], case_sensitive: bool) -> ExcludeMatcher {
+        let patterns = patterns.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+        ExcludeMatcher::new(&patterns, case_sensitive)
+    }
+
+    #[test]
+    fn simple_patterns_match_any_component() {
+        let excludes = matcher(&["*.tmp", ".*", "Thumbs.db", "@eaDir"], true);
+
+        assert!(excludes.is_excluded(Path::new("temp.tmp")));
+        assert!(excludes.is_excluded(Path::new("Movies/.hidden/film.mp4")));
+        assert!(excludes.is_excluded(Path::new("Photos/Thumbs.db")));
+        assert!(excludes.is_excluded(Path::new("Music/@eaDir/cover.jpg")));
+        assert!(!excludes.is_excluded(Path::new("Movies/film.mp4")));
+        assert!(!excludes.is_excluded(Path::new("Movies/film.tmp.mp4")));
+    }
+
+    #[test]
+    fn globs_match_the_path_below_the_root() {
+        let excludes = matcher(&["**/extras/**", "sample-*", "Movies/[Tt]railers"], true);
+
+        assert!(excludes.is_excluded(Path::new("Show/Season 1/extras/bloopers.mkv")));
+        assert!(excludes.is_excluded(Path::new("extras/intro.mkv")));
+        assert!(excludes.is_excluded(Path::new("Show/sample-episode.mkv")));
+        assert!(excludes.is_excluded(Path::new("Movies/trailers/teaser.mp4")));
+        assert!(!excludes.is_excluded(Path::new("Other/Movies/trailers/teaser.mp4")));
+        assert!(!excludes.is_excluded(Path::new("Show/extras.mkv")));
+        assert!(!excludes.is_excluded(Path::new("Show/my-sample.mkv")));
+    }
+
+    #[test]
+    fn case_insensitive_matching_is_opt_in() {
+        assert!(!matcher(&["**/Extras/**"], true).is_excluded(Path::new("show/EXTRAS/a.mkv")));
+        assert!(matcher(&["**/Extras/**"], false).is_excluded(Path::new("show/EXTRAS/a.mkv")));
+        assert!(matcher(&["*.TMP"], false).is_excluded(Path::new("download.tmp")));
+    }
+
+    #[test]
+    fn invalid_patterns_are_reported_and_skipped() {
+        let error = ExcludeMatcher::validate_pattern("extras/[abc").unwrap_err();
+        assert!(error.to_string().contains("'extras/[abc'"));
+
+        let excludes = matcher(&["extras/[abc", "*.tmp"], true);
+        assert!(excludes.is_excluded(Path::new("a.tmp")));
+    }
+}
@@ -1,4 +1,4 @@
-use super::{AppConfig, MonitoredDirectoryConfig, ValidationMode};
+use super::{AppConfig, DatabaseBackendKind, MonitoredDirectoryConfig, ValidationMode, WatchMode};
 use crate::platform::config::PlatformConfig;
 use anyhow::{Context, Result};
 use toml_edit::{value, Array, DocumentMut, Item, Table};
@@ -37,6 +37,10 @@ impl ConfigGenerator {
 
         self.update_management_config(config)?;
 
+        self.update_renderer_config(config)?;
+
+        self.update_logging_config(config)?;
+
         // Replace platform-specific placeholders
         let mut content = self.template_doc.to_string();
         content = self.replace_platform_placeholders(content, &platform_config)?;
@@ -61,6 +65,28 @@ impl ConfigGenerator {
         } else {
             server_table["ip"] = value("");
         }
+        server_table["rebind_on_change"] = value(config.server.rebind_on_change);
+        server_table["port_fallback"] = value(config.server.port_fallback);
+        if let Some(base) = &config.server.path_base {
+            server_table["path_base"] = value(base.to_string_lossy().as_ref());
+        }
+        server_table["tls_port"] = value(config.server.tls_port as i64);
+        server_table["api_https_only"] = value(config.server.api_https_only);
+        let mut trusted_proxies = Array::new();
+        for network in &config.server.trusted_proxies {
+            trusted_proxies.push(network);
+        }
+        server_table["trusted_proxies"] = value(trusted_proxies);
+        if let Some(base_url) = &config.server.base_url {
+            server_table["base_url"] = value(base_url);
+        }
+        server_table["web_dashboard"] = value(config.server.web_dashboard);
+        if let Some(tls) = &config.server.tls {
+            let mut table = toml_edit::InlineTable::new();
+            table.insert("cert_path", tls.cert_path.to_string_lossy().as_ref().into());
+            table.insert("key_path", tls.key_path.to_string_lossy().as_ref().into());
+            server_table["tls"] = value(table);
+        }
 
         Ok(())
     }
@@ -72,12 +98,14 @@ impl ConfigGenerator {
             .context("Network section not found in template")?;
 
         // Handle interface_selection enum
-        let interface_selection = match &config.network.interface_selection {
-            super::NetworkInterfaceConfig::Auto => "Auto",
-            super::NetworkInterfaceConfig::All => "All",
-            super::NetworkInterfaceConfig::Specific(name) => name,
+        network_table["interface_selection"] = match &config.network.interface_selection {
+            super::NetworkInterfaceConfig::Auto => value("Auto"),
+            super::NetworkInterfaceConfig::All => value("All"),
+            super::NetworkInterfaceConfig::Specific(name) => value(name),
+            super::NetworkInterfaceConfig::Multiple(names) => {
+                value(names.iter().map(String::as_str).collect::<Array>())
+            }
         };
-        network_table["interface_selection"] = value(interface_selection);
 
         network_table["multicast_ttl"] = value(config.network.multicast_ttl as i64);
         network_table["announce_interval_seconds"] =
@@ -87,6 +115,23 @@ impl ConfigGenerator {
             callback_networks.push(network);
         }
         network_table["upnp_callback_allowed_networks"] = value(callback_networks);
+        if let Some(subnet) = &config.network.preferred_subnet {
+            network_table["preferred_subnet"] = value(subnet);
+        }
+        network_table["interface_check_interval_seconds"] =
+            value(config.network.interface_check_interval_seconds as i64);
+
+        network_table.remove("friendly_names");
+        if !config.network.friendly_names.is_empty() {
+            let mut friendly_names = toml_edit::ArrayOfTables::new();
+            for entry in &config.network.friendly_names {
+                let mut table = Table::new();
+                table["interface"] = value(&entry.interface);
+                table["name"] = value(&entry.name);
+                friendly_names.push(table);
+            }
+            network_table["friendly_names"] = Item::ArrayOfTables(friendly_names);
+        }
 
         Ok(())
     }
@@ -100,10 +145,37 @@ impl ConfigGenerator {
         media_table["scan_on_startup"] = value(config.media.scan_on_startup);
         media_table["watch_for_changes"] = value(config.media.watch_for_changes);
         media_table["cleanup_deleted_files"] = value(config.media.cleanup_deleted_files);
+        media_table["cleanup_dry_run"] = value(config.media.cleanup_dry_run);
+        media_table["protect_unmounted_roots"] = value(config.media.protect_unmounted_roots);
+        media_table["dry_run"] = value(config.media.dry_run);
         media_table["autoplay_enabled"] = value(config.media.autoplay_enabled);
         media_table["scan_playlists"] = value(config.media.scan_playlists);
         media_table["unavailable_root_grace_hours"] =
             value(config.media.unavailable_root_grace_hours as i64);
+        media_table["deletion_grace_minutes"] = value(config.media.deletion_grace_minutes as i64);
+        media_table["verify_on_start"] = value(config.media.verify_on_start);
+        media_table["verify_on_start_threshold"] =
+            value(config.media.verify_on_start_threshold as i64);
+        media_table["resume_interrupted_scans"] = value(config.media.resume_interrupted_scans);
+        media_table["settle_time_seconds"] = value(config.media.settle_time_seconds as i64);
+        media_table["natural_sort"] = value(config.media.natural_sort);
+        if let Some(path) = &config.media.playlist_mirror_dir {
+            media_table["playlist_mirror_dir"] = value(path.to_string_lossy().as_ref());
+        }
+        media_table["samsung_bookmarks"] = value(config.media.samsung_bookmarks);
+        media_table["dlna_playlist_editing"] = value(config.media.dlna_playlist_editing);
+        media_table["hide_duplicates"] = value(config.media.hide_duplicates);
+        media_table["browse_default_count"] = value(config.media.browse_default_count as i64);
+        media_table["art_max_dimension"] = value(config.media.art_max_dimension as i64);
+        media_table["stream_chunk_kb"] = value(config.media.stream_chunk_kb as i64);
+        media_table["max_concurrent_streams"] = value(config.media.max_concurrent_streams as i64);
+        media_table["art_cache_max_age_seconds"] =
+            value(config.media.art_cache_max_age_seconds as i64);
+        media_table["scan_progress_interval_files"] =
+            value(config.media.scan_progress_interval_files as i64);
+        media_table["scan_progress_interval_seconds"] =
+            value(config.media.scan_progress_interval_seconds as i64);
+        media_table["iso_mime_type"] = value(&config.media.iso_mime_type);
 
         // Update supported extensions array
         let mut extensions_array = Array::new();
@@ -173,6 +245,43 @@ impl ConfigGenerator {
         };
         dir_table["validation_mode"] = value(validation_mode);
 
+        let watch_mode = match dir_config.watch_mode {
+            WatchMode::Native => "native",
+            WatchMode::Poll => "poll",
+            WatchMode::Auto => "auto",
+        };
+        dir_table["watch_mode"] = value(watch_mode);
+
+        // Merge settings are only written for directories that use them
+        if let Some(group) = &dir_config.merge_group {
+            dir_table["merge_group"] = value(group);
+            dir_table["priority"] = value(dir_config.priority as i64);
+        } else {
+            dir_table.remove("merge_group");
+            dir_table.remove("priority");
+        }
+
+        if let Some(media_types) = &dir_config.media_types {
+            let mut types_array = Array::new();
+            for media_type in media_types {
+                types_array.push(media_type);
+            }
+            dir_table["media_types"] = value(types_array);
+        } else {
+            dir_table.remove("media_types");
+        }
+
+        if let Some(min_file_size) = dir_config.min_file_size {
+            dir_table["min_file_size"] = value(min_file_size as i64);
+        } else {
+            dir_table.remove("min_file_size");
+        }
+        if let Some(min_file_age_seconds) = dir_config.min_file_age_seconds {
+            dir_table["min_file_age_seconds"] = value(min_file_age_seconds as i64);
+        } else {
+            dir_table.remove("min_file_age_seconds");
+        }
+
         // Add to document as array of tables
         if !self.template_doc.contains_key("media") {
             self.template_doc["media"] = Item::Table(Table::new());
@@ -201,6 +310,10 @@ impl ConfigGenerator {
             .as_table_mut()
             .context("Database section not found in template")?;
 
+        database_table["backend"] = value(match config.database.backend {
+            DatabaseBackendKind::Redb => "redb",
+            DatabaseBackendKind::Memory => "memory",
+        });
         if let Some(path) = &config.database.path {
             // Escape backslashes in Windows paths for TOML compatibility
             let escaped_path = path.replace("\\", "\\\\");
@@ -212,6 +325,14 @@ impl ConfigGenerator {
         database_table["vacuum_on_startup"] = value(config.database.vacuum_on_startup);
         database_table["compact_on_shutdown"] = value(config.database.compact_on_shutdown);
         database_table["backup_enabled"] = value(config.database.backup_enabled);
+        database_table["backup_interval_hours"] =
+            value(config.database.backup_interval_hours as i64);
+        if let Some(dir) = &config.database.backup_dir {
+            database_table["backup_dir"] = value(dir);
+        }
+        database_table["backup_keep"] = value(config.database.backup_keep as i64);
+        database_table["auto_restore"] = value(config.database.auto_restore);
+        database_table["max_connections"] = value(config.database.max_connections as i64);
 
         Ok(())
     }
@@ -231,6 +352,47 @@ impl ConfigGenerator {
         Ok(())
     }
 
+    /// Add renderer overrides as an array of tables; omitted entirely when none are configured
+    fn update_renderer_config(&mut self, config: &AppConfig) -> Result<()> {
+        self.template_doc.remove("renderers");
+        if config.renderers.is_empty() {
+            return Ok(());
+        }
+
+        let mut renderers = toml_edit::ArrayOfTables::new();
+        for renderer in &config.renderers {
+            let mut table = Table::new();
+            table["profile"] = value(format!("{:?}", renderer.profile));
+            if let Some(max_bitrate_mbps) = renderer.max_bitrate_mbps {
+                table["max_bitrate_mbps"] = value(max_bitrate_mbps);
+            }
+            table["strict_bitrate"] = value(renderer.strict_bitrate);
+            if let Some(autoplay) = renderer.autoplay {
+                table["autoplay"] = value(autoplay);
+            }
+            renderers.push(table);
+        }
+        self.template_doc["renderers"] = Item::ArrayOfTables(renderers);
+
+        Ok(())
+    }
+
+    /// Add the logging section only when one of its settings is configured
+    fn update_logging_config(&mut self, config: &AppConfig) -> Result<()> {
+        self.template_doc.remove("logging");
+        let mut table = Table::new();
+        if let Some(path) = &config.logging.stream_audit_file {
+            table["stream_audit_file"] = value(path.to_string_lossy().as_ref());
+        }
+        if let Some(filter) = &config.logging.filter {
+            table["filter"] = value(filter.as_str());
+        }
+        if !table.is_empty() {
+            self.template_doc["logging"] = Item::Table(table);
+        }
+        Ok(())
+    }
+
     /// Replace platform-specific placeholders in the generated content
     fn replace_platform_placeholders(
         &self,
@@ -406,9 +568,9 @@ impl ConfigGenerator {
 mod tests {
     use super::*;
     use crate::config::{
-        AppConfig, CastConfig, DatabaseConfig, ManagementConfig, MediaConfig,
-        MonitoredDirectoryConfig, NetworkConfig, NetworkInterfaceConfig, ServerConfig,
-        ValidationMode,
+        AppConfig, CastConfig, DatabaseBackendKind, DatabaseConfig, LoggingConfig,
+        ManagementConfig, MediaConfig, MonitoredDirectoryConfig, NetworkConfig,
+        NetworkInterfaceConfig, ServerConfig, ValidationMode,
     };
     use uuid::Uuid;
 
@@ -424,12 +586,27 @@ mod tests {
                 name: "Test Server".to_string(),
                 uuid: Uuid::new_v4().to_string(),
                 ip: Some("192.168.1.100".to_string()),
+                rebind_on_change: true,
+                port_fallback: false,
+                path_base: Some(std::path::PathBuf::from("/srv/vuio")),
+                tls: Some(crate::config::TlsConfig {
+                    cert_path: std::path::PathBuf::from("/etc/vuio/cert.pem"),
+                    key_path: std::path::PathBuf::from("/etc/vuio/key.pem"),
+                }),
+                tls_port: 8443,
+                api_https_only: true,
+                trusted_proxies: vec!["10.0.0.0/8".to_string()],
+                base_url: Some("http://192.168.1.20:8088".to_string()),
+                web_dashboard: false,
             },
             network: NetworkConfig {
                 interface_selection: NetworkInterfaceConfig::Specific("eth0".to_string()),
                 multicast_ttl: 8,
                 announce_interval_seconds: 60,
                 upnp_callback_allowed_networks: vec!["192.168.1.0/24".to_string()],
+                preferred_subnet: Some("10.0.0.0/8".to_string()),
+                interface_check_interval_seconds: 45,
+                friendly_names: Vec::new(),
             },
             media: MediaConfig {
                 directories: vec![MonitoredDirectoryConfig {
@@ -439,24 +616,59 @@ mod tests {
                     extensions: Some(vec!["mp4".to_string(), "mkv".to_string()]),
                     exclude_patterns: Some(vec!["*.tmp".to_string()]),
                     validation_mode: ValidationMode::Strict,
+                    watch_mode: WatchMode::Poll,
+                    merge_group: None,
+                    priority: 0,
+                    media_types: Some(vec!["audio".to_string()]),
+                    min_file_size: Some(1024),
+                    min_file_age_seconds: Some(60),
                 }],
                 scan_on_startup: false,
                 watch_for_changes: false,
                 cleanup_deleted_files: false,
+                cleanup_dry_run: false,
+                protect_unmounted_roots: false,
+                dry_run: false,
                 autoplay_enabled: false,
                 scan_playlists: false,
                 unavailable_root_grace_hours: 168,
+                deletion_grace_minutes: 30,
+                verify_on_start: false,
+                verify_on_start_threshold: 0,
+                resume_interrupted_scans: false,
+                settle_time_seconds: 3,
+                natural_sort: true,
+                playlist_mirror_dir: Some(std::path::PathBuf::from("/test/playlists")),
+                samsung_bookmarks: false,
+                dlna_playlist_editing: true,
+                hide_duplicates: false,
+                browse_default_count: 50,
+                art_max_dimension: 640,
+                stream_chunk_kb: 256,
+                max_concurrent_streams: 3,
+                art_cache_max_age_seconds: 3600,
+                scan_progress_interval_files: 5000,
+                scan_progress_interval_seconds: 0,
+                iso_mime_type: "video/mpeg".to_string(),
                 supported_extensions: vec!["mp4".to_string(), "avi".to_string()],
             },
             database: DatabaseConfig {
+                backend: DatabaseBackendKind::Memory,
                 path: Some("/test/vuio.redb".to_string()),
                 vacuum_on_startup: true,
                 compact_on_shutdown: true,
                 backup_enabled: false,
+                backup_interval_hours: 6,
+                backup_dir: Some("/test/backups".to_string()),
+                backup_keep: 7,
+                auto_restore: true,
                 redb_cache_mb: 128,
+                max_connections: 4,
             },
             management: ManagementConfig::default(),
             cast: CastConfig::default(),
+            renderers: Vec::new(),
+            logging: LoggingConfig::default(),
         };
 
         // Generate TOML
@@ -469,10 +681,30 @@ mod tests {
         assert!(toml_content.contains("interface = \"127.0.0.1\""));
         assert!(toml_content.contains("name = \"Test Server\""));
         assert!(toml_content.contains("ip = \"192.168.1.100\""));
+        assert!(toml_content.contains("path_base = \"/srv/vuio\""));
+        assert!(toml_content.contains("api_https_only = true"));
+        assert!(toml_content.contains("trusted_proxies = [\"10.0.0.0/8\"]"));
+        assert!(toml_content.contains("base_url = \"http://192.168.1.20:8088\""));
+        assert!(toml_content.contains("web_dashboard = false"));
         assert!(toml_content.contains("interface_selection = \"eth0\""));
         assert!(toml_content.contains("multicast_ttl = 8"));
         assert!(toml_content.contains("announce_interval_seconds = 60"));
         assert!(toml_content.contains("upnp_callback_allowed_networks = [\"192.168.1.0/24\"]"));
+        assert!(toml_content.contains("preferred_subnet = \"10.0.0.0/8\""));
+        assert!(toml_content.contains("interface_check_interval_seconds = 45"));
+        assert!(toml_content.contains("playlist_mirror_dir = \"/test/playlists\""));
+        assert!(toml_content.contains("samsung_bookmarks = false"));
+        assert!(toml_content.contains("dlna_playlist_editing = true"));
+        assert!(toml_content.contains("browse_default_count = 50"));
+        assert!(toml_content.contains("art_max_dimension = 640"));
+        assert!(toml_content.contains("stream_chunk_kb = 256"));
+        assert!(toml_content.contains("max_concurrent_streams = 3"));
+        assert!(toml_content.contains("deletion_grace_minutes = 30"));
+        assert!(toml_content.contains("protect_unmounted_roots = false"));
+        assert!(toml_content.contains("art_cache_max_age_seconds = 3600"));
+        assert!(toml_content.contains("scan_progress_interval_files = 5000"));
+        assert!(toml_content.contains("scan_progress_interval_seconds = 0"));
+        assert!(toml_content.contains("iso_mime_type = \"video/mpeg\""));
         assert!(toml_content.contains("scan_on_startup = false"));
         assert!(toml_content.contains("watch_for_changes = false"));
         assert!(toml_content.contains("cleanup_deleted_files = false"));
@@ -480,10 +712,20 @@ mod tests {
         assert!(toml_content.contains("path = \"/test/media\""));
         assert!(toml_content.contains("recursive = true"));
         assert!(toml_content.contains("validation_mode = \"Strict\""));
+        assert!(toml_content.contains("watch_mode = \"poll\""));
+        assert!(toml_content.contains("media_types = [\"audio\"]"));
+        assert!(toml_content.contains("min_file_size = 1024"));
+        assert!(toml_content.contains("min_file_age_seconds = 60"));
+        assert!(toml_content.contains("backend = \"memory\""));
         assert!(toml_content.contains("path = \"/test/vuio.redb\""));
         assert!(toml_content.contains("vacuum_on_startup = true"));
         assert!(toml_content.contains("compact_on_shutdown = true"));
         assert!(toml_content.contains("backup_enabled = false"));
+        assert!(toml_content.contains("backup_interval_hours = 6"));
+        assert!(toml_content.contains("backup_dir = \"/test/backups\""));
+        assert!(toml_content.contains("backup_keep = 7"));
+        assert!(toml_content.contains("auto_restore = true"));
+        assert!(toml_content.contains("max_connections = 4"));
 
         // Verify comments are preserved
         assert!(toml_content.contains("# VuIO Server Configuration"));
@@ -515,6 +757,11 @@ mod tests {
             parsed_config.media.directories[0].validation_mode,
             ValidationMode::Strict
         );
+        assert_eq!(
+            parsed_config.media.directories[0].watch_mode,
+            WatchMode::Poll
+        );
+        assert_eq!(parsed_config.database.backend, DatabaseBackendKind::Memory);
         assert_eq!(
             parsed_config.database.path,
             Some("/test/vuio.redb".to_string())
@@ -536,12 +783,24 @@ mod tests {
                 name: "VuIO Server".to_string(),
                 uuid: Uuid::new_v4().to_string(),
                 ip: None, // Test None case
+                rebind_on_change: true,
+                port_fallback: false,
+                path_base: None,
+                tls: None,
+                tls_port: 8443,
+                api_https_only: false,
+                trusted_proxies: Vec::new(),
+                base_url: None,
+                web_dashboard: true,
             },
             network: NetworkConfig {
                 interface_selection: NetworkInterfaceConfig::Auto,
                 multicast_ttl: 4,
                 announce_interval_seconds: 30,
                 upnp_callback_allowed_networks: Vec::new(),
+                preferred_subnet: None,
+                interface_check_interval_seconds: 15,
+                friendly_names: Vec::new(),
             },
             media: MediaConfig {
                 directories: vec![MonitoredDirectoryConfig {
@@ -551,24 +810,59 @@ mod tests {
                     extensions: None,       // Test None case
                     exclude_patterns: None, // Test None case
                     validation_mode: ValidationMode::Warn,
+                    watch_mode: WatchMode::Auto,
+                    merge_group: None,
+                    priority: 0,
+                    media_types: None,
+                    min_file_size: None,
+                    min_file_age_seconds: None,
                 }],
                 scan_on_startup: true,
                 watch_for_changes: true,
                 cleanup_deleted_files: true,
+                cleanup_dry_run: false,
+                protect_unmounted_roots: true,
+                dry_run: false,
                 autoplay_enabled: true,
                 scan_playlists: true,
                 unavailable_root_grace_hours: 168,
+                deletion_grace_minutes: 0,
+                verify_on_start: false,
+                verify_on_start_threshold: 0,
+                resume_interrupted_scans: false,
+                settle_time_seconds: 3,
+                natural_sort: true,
+                playlist_mirror_dir: None,
+                samsung_bookmarks: true,
+                dlna_playlist_editing: false,
+                hide_duplicates: false,
+                browse_default_count: 0,
+                art_max_dimension: 0,
+                stream_chunk_kb: 64,
+                max_concurrent_streams: 0,
+                art_cache_max_age_seconds: 86400,
+                scan_progress_interval_files: 25_000,
+                scan_progress_interval_seconds: 30,
+                iso_mime_type: "application/x-iso9660-image".to_string(),
                 supported_extensions: vec!["mp4".to_string()],
             },
             database: DatabaseConfig {
+                backend: DatabaseBackendKind::Redb,
                 path: None, // Test None case
                 vacuum_on_startup: false,
                 compact_on_shutdown: false,
                 backup_enabled: true,
+                backup_interval_hours: 24,
+                backup_dir: None,
+                backup_keep: 3,
+                auto_restore: false,
                 redb_cache_mb: 128,
+                max_connections: 16,
             },
             management: ManagementConfig::default(),
             cast: CastConfig::default(),
+            renderers: Vec::new(),
+            logging: LoggingConfig::default(),
         };
 
         // Generate TOML
@@ -594,4 +888,59 @@ mod tests {
         assert_eq!(parsed_config.media.directories[0].extensions, None); // None for unspecified extensions
         assert_eq!(parsed_config.media.directories[0].exclude_patterns, None); // None for unspecified patterns
     }
+
+    #[test]
+    fn test_config_generator_round_trips_renderer_overrides() {
+        let mut generator = ConfigGenerator::new().expect("Failed to create generator");
+        let mut config = AppConfig::default_for_platform();
+        config.renderers = vec![crate::config::RendererProfileConfig {
+            profile: crate::web::client::DlnaClientProfile::LgTv,
+            max_bitrate_mbps: Some(20.0),
+            strict_bitrate: true,
+            autoplay: Some(false),
+        }];
+
+        let toml_content = generator
+            .generate_config(&config)
+            .expect("Failed to generate config");
+        assert!(toml_content.contains("[[renderers]]"));
+        assert!(toml_content.contains("profile = \"LgTv\""));
+
+        let parsed_config: AppConfig =
+            toml::from_str(&toml_content).expect("Generated TOML should be parseable");
+        assert_eq!(parsed_config.renderers, config.renderers);
+    }
+
+    #[test]
+    fn test_config_generator_round_trips_an_interface_list() {
+        let mut generator = ConfigGenerator::new().expect("Failed to create generator");
+        let mut config = AppConfig::default_for_platform();
+        config.network.interface_selection =
+            NetworkInterfaceConfig::Multiple(vec!["eth0".to_owned(), "192.168.2.10".to_owned()]);
+
+        let toml_content = generator
+            .generate_config(&config)
+            .expect("Failed to generate config");
+        assert!(toml_content.contains(r#"interface_selection = ["eth0", "192.168.2.10"]"#));
+
+        config.network.friendly_names = vec![crate::config::FriendlyNameConfig {
+            interface: "10.0.20.0/24".to_owned(),
+            name: "VuIO (IoT)".to_owned(),
+        }];
+        let toml_content = generator
+            .generate_config(&config)
+            .expect("Failed to generate config");
+        assert!(toml_content.contains("\n[[network.friendly_names]]"));
+
+        let parsed_config: AppConfig =
+            toml::from_str(&toml_content).expect("Generated TOML should be parseable");
+        assert_eq!(
+            parsed_config.network.interface_selection,
+            config.network.interface_selection
+        );
+        assert_eq!(
+            parsed_config.network.friendly_names,
+            config.network.friendly_names
+        );
+    }
 }
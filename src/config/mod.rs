@@ -8,23 +8,120 @@ use tokio::sync::{broadcast, RwLock};
 use uuid::Uuid;
 
 mod diff;
+mod exclude;
 pub mod generator;
 mod model;
 pub mod validation;
 
+pub use exclude::ExcludeMatcher;
 use model::{
-    default_allowed_networks, default_redb_cache_mb, default_session_ttl_hours,
-    default_unavailable_root_grace_hours,
+    default_allowed_networks, default_art_cache_max_age_seconds, default_backup_interval_hours,
+    default_backup_keep, default_deletion_grace_minutes, default_iso_mime_type,
+    default_max_connections, default_redb_cache_mb, default_scan_progress_interval_files,
+    default_scan_progress_interval_seconds, default_session_ttl_hours, default_settle_time_seconds,
+    default_stream_chunk_kb, default_tls_port, default_unavailable_root_grace_hours,
+    default_verify_on_start_threshold,
 };
 pub use model::{
-    AppConfig, CastConfig, DatabaseConfig, ManagementConfig, MediaConfig, MonitoredDirectoryConfig,
-    NetworkConfig, NetworkInterfaceConfig, ServerConfig, ValidationMode,
+    normalize_device_uuid, AppConfig, CastConfig, DatabaseBackendKind, DatabaseConfig,
+    FriendlyNameConfig, LoggingConfig, ManagementConfig, MediaConfig, MonitoredDirectoryConfig,
+    NetworkConfig, NetworkInterfaceConfig, RendererProfileConfig, ServerConfig, TlsConfig,
+    ValidationMode, WatchMode, MEDIA_TYPES,
 };
 
 use crate::platform::config::PlatformConfig;
 use generator::ConfigGenerator;
 use validation::ConfigValidator;
 
+/// `path` made absolute against the working directory, with `.` and `..`
+/// folded away lexically so it passes path validation.
+fn absolute_path(path: PathBuf) -> PathBuf {
+    let path = std::path::absolute(&path).unwrap_or(path);
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                normalized.pop();
+            }
+            _ => normalized.push(component.as_os_str()),
+        }
+    }
+    normalized
+}
+
+/// File next to the database that keeps the device UUID of a server whose
+/// UUID is not configured.
+const DEVICE_UUID_FILE: &str = "device-uuid";
+
+fn device_uuid_file(database_path: &Path) -> PathBuf {
+    database_path
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join(DEVICE_UUID_FILE)
+}
+
+fn kept_device_uuid(uuid_file: &Path) -> Option<String> {
+    std::fs::read_to_string(uuid_file)
+        .ok()
+        .and_then(|content| normalize_device_uuid(content.trim()))
+}
+
+/// Device UUID for a server without a configured one. A UUID kept in
+/// `device-uuid` next to the database wins. Otherwise the UUID is derived from
+/// the hostname and the media directories. Nothing is written here; the
+/// server keeps a derived UUID with [`keep_device_uuid`] once it starts.
+fn stable_device_uuid(
+    database_path: &Path,
+    hostname: &str,
+    directories: &[MonitoredDirectoryConfig],
+) -> String {
+    if let Some(uuid) = kept_device_uuid(&device_uuid_file(database_path)) {
+        return uuid;
+    }
+
+    let mut seed = format!("vuio\0{hostname}").into_bytes();
+    for directory in directories {
+        seed.push(0);
+        seed.extend_from_slice(directory.path.as_bytes());
+    }
+    let high = crate::database::fnv1a(&seed);
+    let low = crate::database::fnv1a_extend(high, &seed);
+    let mut bytes = [0; 16];
+    bytes[..8].copy_from_slice(&high.to_be_bytes());
+    bytes[8..].copy_from_slice(&low.to_be_bytes());
+    uuid::Builder::from_custom_bytes(bytes)
+        .into_uuid()
+        .hyphenated()
+        .to_string()
+}
+
+/// Write `uuid` to `device-uuid` next to the database unless a UUID is
+/// already kept there, so it survives restarts and a later hostname change.
+fn keep_device_uuid(database_path: &Path, uuid: &str) {
+    let uuid_file = device_uuid_file(database_path);
+    if kept_device_uuid(&uuid_file).is_some() {
+        return;
+    }
+    let written = uuid_file
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|()| std::fs::write(&uuid_file, format!("{uuid}\n")));
+    match written {
+        Ok(()) => tracing::info!(
+            "Derived device UUID {} from the hostname and media directories; kept in {}",
+            uuid,
+            uuid_file.display()
+        ),
+        Err(error) => tracing::warn!(
+            "Derived device UUID {} could not be kept in {}: {}. It stays stable while the hostname and media directories do",
+            uuid,
+            uuid_file.display(),
+            error
+        ),
+    }
+}
+
 impl AppConfig {
     /// Check if running in Docker container
     pub fn is_running_in_docker() -> bool {
@@ -39,6 +136,12 @@ impl AppConfig {
 
     /// Create configuration from environment variables (Docker mode)
     pub fn from_env() -> Result<Self> {
+        let uuid = std::env::var("VUIO_UUID")
+            .ok()
+            .map(|value| {
+                normalize_device_uuid(&value).with_context(|| format!("Invalid VUIO_UUID: {value}"))
+            })
+            .transpose()?;
         let server = ServerConfig {
             port: std::env::var("VUIO_PORT")
                 .unwrap_or_else(|_| "8080".to_string())
@@ -47,8 +150,50 @@ impl AppConfig {
             interface: std::env::var("VUIO_INTERFACE").unwrap_or_else(|_| "0.0.0.0".to_string()),
             name: std::env::var("VUIO_SERVER_NAME")
                 .unwrap_or_else(|_| "VuIO DLNA Server".to_string()),
-            uuid: std::env::var("VUIO_UUID").unwrap_or_else(|_| Uuid::new_v4().to_string()),
+            // Derived below once the database path is resolved
+            uuid: uuid.clone().unwrap_or_default(),
             ip: std::env::var("VUIO_IP").ok(),
+            rebind_on_change: std::env::var("VUIO_REBIND_ON_CHANGE")
+                .map(|v| v.to_lowercase() == "true")
+                .unwrap_or(true),
+            port_fallback: std::env::var("VUIO_PORT_FALLBACK")
+                .map(|v| v.to_lowercase() == "true")
+                .unwrap_or(false),
+            path_base: std::env::var("VUIO_PATH_BASE")
+                .ok()
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from),
+            tls: match (
+                std::env::var("VUIO_TLS_CERT"),
+                std::env::var("VUIO_TLS_KEY"),
+            ) {
+                (Ok(cert_path), Ok(key_path)) => Some(TlsConfig {
+                    cert_path: PathBuf::from(cert_path),
+                    key_path: PathBuf::from(key_path),
+                }),
+                (Err(_), Err(_)) => None,
+                _ => anyhow::bail!("VUIO_TLS_CERT and VUIO_TLS_KEY must be set together"),
+            },
+            tls_port: match std::env::var("VUIO_TLS_PORT") {
+                Ok(port) => port.parse().context("Invalid VUIO_TLS_PORT")?,
+                Err(_) => default_tls_port(),
+            },
+            api_https_only: std::env::var("VUIO_API_HTTPS_ONLY")
+                .map(|v| v.to_lowercase() == "true")
+                .unwrap_or(false),
+            trusted_proxies: std::env::var("VUIO_TRUSTED_PROXIES")
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_owned)
+                .collect(),
+            base_url: std::env::var("VUIO_BASE_URL")
+                .ok()
+                .filter(|value| !value.trim().is_empty()),
+            web_dashboard: std::env::var("VUIO_WEB_DASHBOARD")
+                .map(|v| v.to_lowercase() != "false")
+                .unwrap_or(true),
         };
 
         let network = NetworkConfig {
@@ -68,6 +213,22 @@ impl AppConfig {
                 .filter(|value| !value.is_empty())
                 .map(str::to_owned)
                 .collect(),
+            preferred_subnet: std::env::var("VUIO_PREFERRED_SUBNET")
+                .ok()
+                .filter(|value| !value.trim().is_empty()),
+            interface_check_interval_seconds: std::env::var("VUIO_INTERFACE_CHECK_INTERVAL")
+                .unwrap_or_else(|_| "15".to_string())
+                .parse()
+                .context("Invalid VUIO_INTERFACE_CHECK_INTERVAL")?,
+            friendly_names: std::env::var("VUIO_FRIENDLY_NAMES")
+                .unwrap_or_default()
+                .split(',')
+                .filter_map(|entry| entry.split_once('='))
+                .map(|(interface, name)| FriendlyNameConfig {
+                    interface: interface.trim().to_owned(),
+                    name: name.trim().to_owned(),
+                })
+                .collect(),
         };
 
         let media_dirs = std::env::var("VUIO_MEDIA_DIRS")
@@ -84,6 +245,12 @@ impl AppConfig {
                     "*.part".to_string(),
                 ]),
                 validation_mode: ValidationMode::Warn,
+                watch_mode: WatchMode::Auto,
+                merge_group: None,
+                priority: 0,
+                media_types: None,
+                min_file_size: None,
+                min_file_age_seconds: None,
             })
             .collect();
 
@@ -98,6 +265,15 @@ impl AppConfig {
             cleanup_deleted_files: std::env::var("VUIO_CLEANUP_DELETED")
                 .map(|v| v.to_lowercase() == "true")
                 .unwrap_or(true),
+            cleanup_dry_run: std::env::var("VUIO_CLEANUP_DRY_RUN")
+                .map(|v| v.to_lowercase() == "true")
+                .unwrap_or(false),
+            protect_unmounted_roots: std::env::var("VUIO_PROTECT_UNMOUNTED_ROOTS")
+                .map(|v| v.to_lowercase() != "false")
+                .unwrap_or(true),
+            dry_run: std::env::var("VUIO_DRY_RUN")
+                .map(|v| v.to_lowercase() == "true")
+                .unwrap_or(false),
             autoplay_enabled: std::env::var("VUIO_AUTOPLAY")
                 .map(|v| v.to_lowercase() == "true")
                 .unwrap_or(true),
@@ -108,6 +284,72 @@ impl AppConfig {
                 .ok()
                 .and_then(|value| value.parse().ok())
                 .unwrap_or_else(default_unavailable_root_grace_hours),
+            deletion_grace_minutes: std::env::var("VUIO_DELETION_GRACE_MINUTES")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_else(default_deletion_grace_minutes),
+            verify_on_start: std::env::var("VUIO_VERIFY_ON_START")
+                .map(|v| v.to_lowercase() == "true")
+                .unwrap_or(false),
+            verify_on_start_threshold: std::env::var("VUIO_VERIFY_ON_START_THRESHOLD")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_else(default_verify_on_start_threshold),
+            resume_interrupted_scans: std::env::var("VUIO_RESUME_INTERRUPTED_SCANS")
+                .map(|v| v.to_lowercase() == "true")
+                .unwrap_or(false),
+            settle_time_seconds: std::env::var("VUIO_SETTLE_TIME_SECONDS")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_else(default_settle_time_seconds),
+            natural_sort: std::env::var("VUIO_NATURAL_SORT")
+                .map(|v| v.to_lowercase() != "false")
+                .unwrap_or(true),
+            playlist_mirror_dir: std::env::var("VUIO_PLAYLIST_MIRROR_DIR")
+                .ok()
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from),
+            samsung_bookmarks: std::env::var("VUIO_SAMSUNG_BOOKMARKS")
+                .map(|v| v.to_lowercase() != "false")
+                .unwrap_or(true),
+            dlna_playlist_editing: std::env::var("VUIO_DLNA_PLAYLIST_EDITING")
+                .map(|v| v.to_lowercase() == "true")
+                .unwrap_or(false),
+            hide_duplicates: std::env::var("VUIO_HIDE_DUPLICATES")
+                .map(|v| v.to_lowercase() == "true")
+                .unwrap_or(false),
+            scan_progress_interval_files: std::env::var("VUIO_SCAN_PROGRESS_FILES")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_else(default_scan_progress_interval_files),
+            scan_progress_interval_seconds: std::env::var("VUIO_SCAN_PROGRESS_SECONDS")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_else(default_scan_progress_interval_seconds),
+            browse_default_count: std::env::var("VUIO_BROWSE_DEFAULT_COUNT")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(0),
+            art_max_dimension: std::env::var("VUIO_ART_MAX_DIMENSION")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(0),
+            stream_chunk_kb: std::env::var("VUIO_STREAM_CHUNK_KB")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_else(default_stream_chunk_kb),
+            max_concurrent_streams: std::env::var("VUIO_MAX_CONCURRENT_STREAMS")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(0),
+            art_cache_max_age_seconds: std::env::var("VUIO_ART_CACHE_MAX_AGE")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_else(default_art_cache_max_age_seconds),
+            iso_mime_type: std::env::var("VUIO_ISO_MIME_TYPE")
+                .ok()
+                .filter(|value| !value.is_empty())
+                .unwrap_or_else(default_iso_mime_type),
             supported_extensions: vec![
                 "mp4".to_string(),
                 "mkv".to_string(),
@@ -120,6 +362,7 @@ impl AppConfig {
                 "3gp".to_string(),
                 "ts".to_string(),
                 "m2ts".to_string(),
+                "iso".to_string(),
                 "mp3".to_string(),
                 "flac".to_string(),
                 "wav".to_string(),
@@ -139,6 +382,18 @@ impl AppConfig {
         };
 
         let database = DatabaseConfig {
+            backend: match std::env::var("VUIO_DB_BACKEND") {
+                Ok(value) => match value.to_lowercase().as_str() {
+                    "redb" => DatabaseBackendKind::Redb,
+                    "memory" => DatabaseBackendKind::Memory,
+                    other => {
+                        return Err(anyhow::anyhow!(
+                            "VUIO_DB_BACKEND must be \"redb\" or \"memory\", got {other:?}"
+                        ))
+                    }
+                },
+                Err(_) => DatabaseBackendKind::default(),
+            },
             path: Some(
                 std::env::var("VUIO_DB_PATH").unwrap_or_else(|_| "/data/vuio.db".to_string()),
             ),
@@ -149,13 +404,31 @@ impl AppConfig {
             backup_enabled: std::env::var("VUIO_DB_BACKUP")
                 .map(|v| v.to_lowercase() == "true")
                 .unwrap_or(false),
+            backup_interval_hours: std::env::var("VUIO_DB_BACKUP_INTERVAL_HOURS")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_else(default_backup_interval_hours),
+            backup_dir: std::env::var("VUIO_DB_BACKUP_DIR")
+                .ok()
+                .filter(|value| !value.is_empty()),
+            backup_keep: std::env::var("VUIO_DB_BACKUP_KEEP")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_else(default_backup_keep),
+            auto_restore: std::env::var("VUIO_DB_AUTO_RESTORE")
+                .map(|v| v.to_lowercase() == "true")
+                .unwrap_or(false),
             redb_cache_mb: std::env::var("VUIO_REDB_CACHE_MB")
                 .ok()
                 .and_then(|value| value.parse().ok())
                 .unwrap_or_else(default_redb_cache_mb),
+            max_connections: std::env::var("VUIO_DB_MAX_CONNECTIONS")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_else(default_max_connections),
         };
 
-        Ok(AppConfig {
+        let mut config = AppConfig {
             server,
             network,
             media,
@@ -184,7 +457,38 @@ impl AppConfig {
                     .unwrap_or_else(default_allowed_networks),
             },
             cast: CastConfig::default(),
-        })
+            renderers: Vec::new(),
+            logging: LoggingConfig {
+                filter: std::env::var("VUIO_LOG_FILTER")
+                    .ok()
+                    .filter(|filter| !filter.trim().is_empty()),
+                ..LoggingConfig::default()
+            },
+        };
+        // There is no config file; relative paths follow the working directory.
+        let working_dir = std::env::current_dir()
+            .context("Failed to read the working directory to resolve relative paths")?;
+        config.resolve_relative_paths(&working_dir);
+        if uuid.is_none() {
+            let hostname = hostname::get()
+                .map(|hostname| hostname.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            config.server.uuid = stable_device_uuid(
+                &config.get_database_path(),
+                &hostname,
+                &config.media.directories,
+            );
+        }
+        Ok(config)
+    }
+
+    /// Keep the device UUID [`Self::from_env`] derived, so the server keeps
+    /// its identity across restarts. Only the server calls this; loading or
+    /// checking a configuration never writes.
+    pub fn keep_derived_device_uuid(&self) {
+        if std::env::var_os("VUIO_UUID").is_none() {
+            keep_device_uuid(&self.get_database_path(), &self.server.uuid);
+        }
     }
 
     /// Get the primary media directory (for compatibility)
@@ -229,6 +533,17 @@ impl AppConfig {
 
     /// Load configuration from a TOML file
     pub fn load_from_file<P: AsRef<Path>>(config_path: P) -> Result<Self> {
+        let config = Self::read_from_file(config_path)?;
+
+        // Validate the loaded configuration with flexible directory validation
+        ConfigValidator::validate_flexible(&config)?;
+
+        Ok(config)
+    }
+
+    /// Parse a TOML file and resolve its relative paths without validating
+    /// the result.
+    pub fn read_from_file<P: AsRef<Path>>(config_path: P) -> Result<Self> {
         let content = std::fs::read_to_string(config_path.as_ref()).with_context(|| {
             format!(
                 "Failed to read config file: {}",
@@ -236,17 +551,89 @@ impl AppConfig {
             )
         })?;
 
-        let config: AppConfig = toml::from_str(&content).with_context(|| {
+        let mut config: AppConfig = toml::from_str(&content).with_context(|| {
             format!(
                 "Failed to parse config file: {}",
                 config_path.as_ref().display()
             )
         })?;
+        if let Some(uuid) = normalize_device_uuid(&config.server.uuid) {
+            config.server.uuid = uuid;
+        }
+        let config_dir = config_path
+            .as_ref()
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        config.resolve_relative_paths(config_dir);
+        Ok(config)
+    }
 
-        // Validate the loaded configuration with flexible directory validation
-        ConfigValidator::validate_flexible(&config)?;
+    /// Rewrite relative paths against `server.path_base`, or against
+    /// `config_dir` when that is unset, so they do not depend on the working
+    /// directory of the service. A relative `path_base` is itself resolved
+    /// against `config_dir`. Every rewritten path is logged.
+    pub fn resolve_relative_paths(&mut self, config_dir: &Path) {
+        let config_dir = absolute_path(config_dir.to_path_buf());
+        let base = match &self.server.path_base {
+            Some(base) => absolute_path(config_dir.join(base)),
+            None => config_dir,
+        };
+        let resolve = |setting: &str, path: &Path| -> Option<PathBuf> {
+            if path.as_os_str().is_empty() || !path.is_relative() {
+                return None;
+            }
+            let resolved = absolute_path(base.join(path));
+            tracing::info!(
+                "Resolved relative {} {} to {}",
+                setting,
+                path.display(),
+                resolved.display()
+            );
+            Some(resolved)
+        };
 
-        Ok(config)
+        for directory in &mut self.media.directories {
+            if let Some(resolved) = resolve("media directory", Path::new(&directory.path)) {
+                directory.path = resolved.to_string_lossy().into_owned();
+            }
+        }
+        if let Some(path) = &mut self.database.path {
+            if let Some(resolved) = resolve("database.path", Path::new(path.as_str())) {
+                *path = resolved.to_string_lossy().into_owned();
+            }
+        }
+        if let Some(path) = &mut self.database.backup_dir {
+            if let Some(resolved) = resolve("database.backup_dir", Path::new(path.as_str())) {
+                *path = resolved.to_string_lossy().into_owned();
+            }
+        }
+        if let Some(path) = &mut self.management.token_file {
+            if let Some(resolved) = resolve("management.token_file", Path::new(path.as_str())) {
+                *path = resolved.to_string_lossy().into_owned();
+            }
+        }
+        if let Some(path) = &mut self.media.playlist_mirror_dir {
+            if let Some(resolved) = resolve("media.playlist_mirror_dir", path) {
+                *path = resolved;
+            }
+        }
+        if let Some(path) = &mut self.logging.stream_audit_file {
+            if let Some(resolved) = resolve("logging.stream_audit_file", path) {
+                *path = resolved;
+            }
+        }
+        if let Some(tls) = &mut self.server.tls {
+            if let Some(resolved) = resolve("server.tls.cert_path", &tls.cert_path) {
+                tls.cert_path = resolved;
+            }
+            if let Some(resolved) = resolve("server.tls.key_path", &tls.key_path) {
+                tls.key_path = resolved;
+            }
+        }
+        if self.server.path_base.is_some() {
+            self.server.path_base = Some(base);
+        }
     }
 
     /// Save configuration to a TOML file with platform-specific comments
@@ -325,6 +712,12 @@ impl AppConfig {
                 extensions: None,
                 exclude_patterns: Some(platform_config.get_default_exclude_patterns()),
                 validation_mode: ValidationMode::Warn,
+                watch_mode: WatchMode::Auto,
+                merge_group: None,
+                priority: 0,
+                media_types: None,
+                min_file_size: None,
+                min_file_age_seconds: None,
             }]
         } else {
             // Use the primary media directory (first one) as default
@@ -335,6 +728,12 @@ impl AppConfig {
                 extensions: None, // Use global supported_extensions
                 exclude_patterns: Some(platform_config.get_default_exclude_patterns()),
                 validation_mode: ValidationMode::Warn,
+                watch_mode: WatchMode::Auto,
+                merge_group: None,
+                priority: 0,
+                media_types: None,
+                min_file_size: None,
+                min_file_age_seconds: None,
             }]
         };
 
@@ -349,6 +748,15 @@ impl AppConfig {
                 name: Self::get_platform_server_name(&platform_config),
                 uuid: Uuid::new_v4().to_string(),
                 ip: None,
+                rebind_on_change: true,
+                port_fallback: false,
+                path_base: None,
+                tls: None,
+                tls_port: default_tls_port(),
+                api_https_only: false,
+                trusted_proxies: Vec::new(),
+                base_url: None,
+                web_dashboard: true,
             },
             network: NetworkConfig {
                 interface_selection: NetworkInterfaceConfig::Auto,
@@ -357,18 +765,43 @@ impl AppConfig {
                     &platform_config,
                 ),
                 upnp_callback_allowed_networks: Vec::new(),
+                preferred_subnet: None,
+                interface_check_interval_seconds: 15,
+                friendly_names: Vec::new(),
             },
             media: MediaConfig {
                 directories: monitored_dirs,
                 scan_on_startup: true,
                 watch_for_changes: true,
                 cleanup_deleted_files: true,
+                cleanup_dry_run: false,
+                protect_unmounted_roots: true,
+                dry_run: false,
                 autoplay_enabled: true,
                 scan_playlists: true,
                 unavailable_root_grace_hours: default_unavailable_root_grace_hours(),
+                deletion_grace_minutes: default_deletion_grace_minutes(),
+                verify_on_start: false,
+                verify_on_start_threshold: default_verify_on_start_threshold(),
+                resume_interrupted_scans: false,
+                settle_time_seconds: default_settle_time_seconds(),
+                natural_sort: true,
+                playlist_mirror_dir: None,
+                samsung_bookmarks: true,
+                dlna_playlist_editing: false,
+                hide_duplicates: false,
+                browse_default_count: 0,
+                art_max_dimension: 0,
+                stream_chunk_kb: default_stream_chunk_kb(),
+                max_concurrent_streams: 0,
+                art_cache_max_age_seconds: default_art_cache_max_age_seconds(),
+                scan_progress_interval_files: default_scan_progress_interval_files(),
+                scan_progress_interval_seconds: default_scan_progress_interval_seconds(),
+                iso_mime_type: default_iso_mime_type(),
                 supported_extensions: platform_config.get_default_media_extensions(),
             },
             database: DatabaseConfig {
+                backend: DatabaseBackendKind::default(),
                 path: Some(
                     platform_config
                         .get_database_path()
@@ -378,10 +811,17 @@ impl AppConfig {
                 vacuum_on_startup: false,
                 compact_on_shutdown: false,
                 backup_enabled: false,
+                backup_interval_hours: default_backup_interval_hours(),
+                backup_dir: None,
+                backup_keep: default_backup_keep(),
+                auto_restore: false,
                 redb_cache_mb: default_redb_cache_mb(),
+                max_connections: default_max_connections(),
             },
             management: ManagementConfig::default(),
             cast: CastConfig::default(),
+            renderers: Vec::new(),
+            logging: LoggingConfig::default(),
         }
     }
 
@@ -415,6 +855,32 @@ impl AppConfig {
         30
     }
 
+    /// The `friendlyName` for a client at `peer`: the first
+    /// `network.friendly_names` entry whose CIDR contains the peer, or that
+    /// names the interface of `interfaces` on the peer's subnet. Falls back
+    /// to `server.name`.
+    pub fn friendly_name_for(
+        &self,
+        interfaces: &[crate::platform::NetworkInterface],
+        peer: std::net::IpAddr,
+    ) -> &str {
+        let peer = peer.to_canonical();
+        let nearest = crate::platform::nearest_interface(interfaces, peer);
+        self.network
+            .friendly_names
+            .iter()
+            .find(|entry| {
+                let selector = entry.interface.trim();
+                match selector.parse::<ipnet::IpNet>() {
+                    Ok(network) => network.contains(&peer),
+                    Err(_) => nearest.is_some_and(|interface| {
+                        interface.name == selector || interface.ip_address.to_string() == selector
+                    }),
+                }
+            })
+            .map_or(&self.server.name, |entry| &entry.name)
+    }
+
     /// Get the database file path, using platform default if not specified
     pub fn get_database_path(&self) -> PathBuf {
         match &self.database.path {
@@ -426,6 +892,18 @@ impl AppConfig {
         }
     }
 
+    /// Directory database backups are written to.
+    pub fn get_backup_dir(&self) -> PathBuf {
+        match &self.database.backup_dir {
+            Some(dir) => PathBuf::from(dir),
+            None => self
+                .get_database_path()
+                .parent()
+                .map_or_else(|| PathBuf::from("."), Path::to_path_buf)
+                .join("backups"),
+        }
+    }
+
     /// Get all monitored directories as PathBuf objects
     pub fn get_monitored_directories(&self) -> Vec<PathBuf> {
         self.media
@@ -465,29 +943,13 @@ impl AppConfig {
     /// Check if a file should be excluded based on patterns
     pub fn should_exclude_file(&self, file_path: &Path, dir_path: &Path) -> bool {
         let patterns = self.get_exclude_patterns_for_directory(dir_path);
-        let file_name = file_path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-
-        for pattern in &patterns {
-            if Self::matches_pattern(file_name, pattern) {
-                return true;
-            }
-        }
-
-        false
-    }
+        let relative = file_path
+            .strip_prefix(dir_path)
+            .ok()
+            .or_else(|| file_path.file_name().map(Path::new))
+            .unwrap_or(file_path);
 
-    /// Simple pattern matching for exclude patterns
-    fn matches_pattern(filename: &str, pattern: &str) -> bool {
-        if let Some(ext) = pattern.strip_prefix("*.") {
-            // Extension pattern like "*.tmp"
-            filename.ends_with(&format!(".{}", ext))
-        } else if pattern == ".*" {
-            // Hidden file pattern - matches files starting with dot
-            filename.starts_with('.')
-        } else {
-            // Exact match
-            filename == pattern
-        }
+        ExcludeMatcher::new(&patterns, !cfg!(target_os = "windows")).is_excluded(relative)
     }
 
     /// Get the platform configuration file path
@@ -543,6 +1005,19 @@ impl AppConfig {
 
     /// Apply platform-specific defaults to missing or invalid configuration values
     pub fn apply_platform_defaults(&mut self) -> Result<()> {
+        self.fill_platform_defaults();
+
+        // Ensure all platform directories exist
+        PlatformConfig::for_current_platform()
+            .ensure_directories_exist()
+            .context("Failed to create platform directories")?;
+
+        Ok(())
+    }
+
+    /// Fill unset values with platform defaults, as [`Self::apply_platform_defaults`]
+    /// does, without creating any directory.
+    pub fn fill_platform_defaults(&mut self) {
         let platform_config = PlatformConfig::for_current_platform();
 
         // Update database path if not set or invalid
@@ -595,26 +1070,11 @@ impl AppConfig {
                 platform_config.preferred_ports
             );
         }
-
-        // Ensure all platform directories exist
-        platform_config
-            .ensure_directories_exist()
-            .context("Failed to create platform directories")?;
-
-        Ok(())
     }
 
     /// Validate configuration against platform-specific constraints
     pub fn validate_for_platform(&self) -> Result<()> {
-        let platform_config = PlatformConfig::for_current_platform();
-
-        // Validate monitored directories
-        for dir_config in &self.media.directories {
-            let path = PathBuf::from(&dir_config.path);
-            platform_config
-                .validate_path(&path)
-                .with_context(|| format!("Invalid media directory: {}", path.display()))?;
-        }
+        self.validate_settings_for_platform()?;
 
         // Validate database path - ensure parent directory can be created
         let db_path = self.get_database_path();
@@ -643,6 +1103,22 @@ impl AppConfig {
             }
         }
 
+        Ok(())
+    }
+
+    /// The checks of [`Self::validate_for_platform`] that only read the
+    /// configuration: nothing is created or written.
+    pub fn validate_settings_for_platform(&self) -> Result<()> {
+        let platform_config = PlatformConfig::for_current_platform();
+
+        // Validate monitored directories
+        for dir_config in &self.media.directories {
+            let path = PathBuf::from(&dir_config.path);
+            platform_config
+                .validate_path(&path)
+                .with_context(|| format!("Invalid media directory: {}", path.display()))?;
+        }
+
         // Validate server port is in preferred range
         if !platform_config.preferred_ports.contains(&self.server.port) {
             tracing::warn!(
@@ -652,6 +1128,11 @@ impl AppConfig {
             );
         }
 
+        anyhow::ensure!(
+            self.network.multicast_ttl >= 1,
+            "network.multicast_ttl must be between 1 and 255"
+        );
+
         // Validate network interface configuration
         if let NetworkInterfaceConfig::Specific(interface_name) = &self.network.interface_selection
         {
@@ -661,6 +1142,12 @@ impl AppConfig {
                 ));
             }
         }
+        if let NetworkInterfaceConfig::Multiple(interfaces) = &self.network.interface_selection {
+            anyhow::ensure!(
+                !interfaces.is_empty() && interfaces.iter().all(|name| !name.trim().is_empty()),
+                "network.interface_selection must list at least one interface, none of them empty"
+            );
+        }
 
         // Validate server interface address for platform compatibility
         if !self.server.interface.is_empty()
@@ -1359,6 +1846,12 @@ mod tests {
             extensions: None,
             exclude_patterns: None,
             validation_mode: ValidationMode::Strict,
+            watch_mode: WatchMode::Auto,
+            merge_group: None,
+            priority: 0,
+            media_types: None,
+            min_file_size: None,
+            min_file_age_seconds: None,
         }];
 
         // Save the config
@@ -1372,6 +1865,156 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_server_uuid_is_normalized_on_load() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config_path = temp_dir.path().join("config.toml");
+        let mut config = AppConfig::default();
+        config.media.directories = vec![MonitoredDirectoryConfig {
+            path: temp_dir.path().to_string_lossy().to_string(),
+            recursive: true,
+            case_sensitive: None,
+            extensions: None,
+            exclude_patterns: None,
+            validation_mode: ValidationMode::Warn,
+            watch_mode: WatchMode::Auto,
+            merge_group: None,
+            priority: 0,
+            media_types: None,
+            min_file_size: None,
+            min_file_age_seconds: None,
+        }];
+        config.server.uuid = "uuid:6F0C9A3E-2B1D-4E5F-8A7B-1C2D3E4F5A6B".to_string();
+        config.save_to_file(&config_path)?;
+
+        let loaded = AppConfig::load_from_file(&config_path)?;
+        assert_eq!(loaded.server.uuid, "6f0c9a3e-2b1d-4e5f-8a7b-1c2d3e4f5a6b");
+        assert_eq!(
+            loaded.server.udn(),
+            "uuid:6f0c9a3e-2b1d-4e5f-8a7b-1c2d3e4f5a6b"
+        );
+        assert_eq!(normalize_device_uuid("not-a-uuid"), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn unconfigured_device_uuid_is_derived_once_and_kept() {
+        let temp = TempDir::new().unwrap();
+        let directories = |path: &str| {
+            vec![MonitoredDirectoryConfig {
+                path: path.to_string(),
+                recursive: true,
+                case_sensitive: None,
+                extensions: None,
+                exclude_patterns: None,
+                validation_mode: ValidationMode::Warn,
+                watch_mode: WatchMode::Auto,
+                merge_group: None,
+                priority: 0,
+                media_types: None,
+                min_file_size: None,
+                min_file_age_seconds: None,
+            }]
+        };
+        let database = temp.path().join("data").join("vuio.db");
+        let other_database = temp.path().join("other").join("vuio.db");
+
+        let uuid = stable_device_uuid(&database, "nas", &directories("/media"));
+        assert_eq!(normalize_device_uuid(&uuid).as_deref(), Some(uuid.as_str()));
+        // Deriving the UUID writes nothing; the server keeps it.
+        assert!(!temp.path().join("data").exists());
+        keep_device_uuid(&database, &uuid);
+        assert_eq!(
+            std::fs::read_to_string(temp.path().join("data").join(DEVICE_UUID_FILE))
+                .unwrap()
+                .trim(),
+            uuid
+        );
+
+        // The same host and directories derive the same UUID anywhere
+        assert_eq!(
+            stable_device_uuid(&other_database, "nas", &directories("/media")),
+            uuid
+        );
+        assert_ne!(
+            stable_device_uuid(
+                &temp.path().join("a/vuio.db"),
+                "nas2",
+                &directories("/media")
+            ),
+            uuid
+        );
+        assert_ne!(
+            stable_device_uuid(
+                &temp.path().join("b/vuio.db"),
+                "nas",
+                &directories("/music")
+            ),
+            uuid
+        );
+
+        // Once kept, a hostname change no longer changes the identity
+        assert_eq!(
+            stable_device_uuid(&database, "renamed", &directories("/media")),
+            uuid
+        );
+    }
+
+    #[test]
+    fn test_relative_paths_resolve_against_the_config_file() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config_dir = temp_dir.path().join("etc");
+        std::fs::create_dir_all(config_dir.join("media"))?;
+        let config_path = config_dir.join("config.toml");
+        let mut config = AppConfig::default();
+        config.media.directories = vec![MonitoredDirectoryConfig {
+            path: "media".to_string(),
+            recursive: true,
+            case_sensitive: None,
+            extensions: None,
+            exclude_patterns: None,
+            validation_mode: ValidationMode::Warn,
+            watch_mode: WatchMode::Auto,
+            merge_group: None,
+            priority: 0,
+            media_types: None,
+            min_file_size: None,
+            min_file_age_seconds: None,
+        }];
+        config.database.path = Some("./vuio.redb".to_string());
+        config.media.playlist_mirror_dir = None;
+        config.logging.stream_audit_file = Some(PathBuf::from("/var/log/streams.log"));
+        config.save_to_file(&config_path)?;
+
+        let loaded = AppConfig::load_from_file(&config_path)?;
+        assert_eq!(
+            PathBuf::from(&loaded.media.directories[0].path),
+            std::path::absolute(config_dir.join("media"))?
+        );
+        assert_eq!(
+            loaded.get_database_path(),
+            std::path::absolute(config_dir.join("vuio.redb"))?
+        );
+        assert_eq!(
+            loaded.logging.stream_audit_file,
+            Some(PathBuf::from("/var/log/streams.log"))
+        );
+
+        // A relative base is taken from the config file's directory too.
+        config.server.path_base = Some(PathBuf::from("../srv"));
+        config.save_to_file(&config_path)?;
+        let loaded = AppConfig::load_from_file(&config_path)?;
+        let base = std::path::absolute(temp_dir.path().join("srv"))?;
+        assert_eq!(loaded.server.path_base.as_deref(), Some(base.as_path()));
+        assert_eq!(
+            PathBuf::from(&loaded.media.directories[0].path),
+            base.join("media")
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_exclude_patterns() {
         let temp_dir = TempDir::new().unwrap();
@@ -1390,6 +2033,12 @@ mod tests {
                 "*.tmp".to_string(),     // Temporary files
             ]),
             validation_mode: ValidationMode::Strict,
+            watch_mode: WatchMode::Auto,
+            merge_group: None,
+            priority: 0,
+            media_types: None,
+            min_file_size: None,
+            min_file_age_seconds: None,
         }];
 
         // Test hidden file exclusion
@@ -1426,6 +2075,12 @@ mod tests {
             extensions: None,
             exclude_patterns: None,
             validation_mode: ValidationMode::Strict,
+            watch_mode: WatchMode::Auto,
+            merge_group: None,
+            priority: 0,
+            media_types: None,
+            min_file_size: None,
+            min_file_age_seconds: None,
         }];
         config.save_to_file(&config_path)?;
 
@@ -1482,6 +2137,12 @@ mod tests {
             extensions: None,
             exclude_patterns: None,
             validation_mode: ValidationMode::Strict,
+            watch_mode: WatchMode::Auto,
+            merge_group: None,
+            priority: 0,
+            media_types: None,
+            min_file_size: None,
+            min_file_age_seconds: None,
         }];
 
         // Save the config to file
@@ -1599,4 +2260,38 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_friendly_name_follows_the_client_interface() {
+        let interface = |name: &str, ip: &str| crate::platform::NetworkInterface {
+            name: name.to_owned(),
+            ip_address: ip.parse().unwrap(),
+            is_loopback: false,
+            is_up: true,
+            supports_multicast: true,
+            interface_type: crate::platform::InterfaceType::Ethernet,
+        };
+        let interfaces = [
+            interface("eth0", "192.168.1.20"),
+            interface("eth1", "10.0.20.5"),
+            interface("eth2", "10.0.30.5"),
+        ];
+        let mut config = AppConfig::default_for_platform();
+        config.server.name = "VuIO".to_owned();
+        config.network.friendly_names = vec![
+            FriendlyNameConfig {
+                interface: "eth1".to_owned(),
+                name: "VuIO (Guests)".to_owned(),
+            },
+            FriendlyNameConfig {
+                interface: "10.0.30.0/24".to_owned(),
+                name: "VuIO (IoT)".to_owned(),
+            },
+        ];
+
+        let name_for = |peer: &str| config.friendly_name_for(&interfaces, peer.parse().unwrap());
+        assert_eq!(name_for("10.0.20.77"), "VuIO (Guests)");
+        assert_eq!(name_for("::ffff:10.0.30.9"), "VuIO (IoT)");
+        assert_eq!(name_for("192.168.1.50"), "VuIO");
+    }
 }
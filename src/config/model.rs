@@ -28,10 +28,62 @@ pub(super) fn default_unavailable_root_grace_hours() -> u64 {
     168
 }
 
+pub(super) fn default_deletion_grace_minutes() -> u64 {
+    60
+}
+
+pub(super) fn default_verify_on_start_threshold() -> u64 {
+    0
+}
+
+pub(super) fn default_settle_time_seconds() -> u64 {
+    3
+}
+
+pub(super) fn default_scan_progress_interval_files() -> u64 {
+    25_000
+}
+
+pub(super) fn default_scan_progress_interval_seconds() -> u64 {
+    30
+}
+
+pub(super) fn default_iso_mime_type() -> String {
+    "application/x-iso9660-image".to_string()
+}
+
+pub(super) fn default_interface_check_interval_seconds() -> u64 {
+    15
+}
+
+pub(super) fn default_tls_port() -> u16 {
+    8443
+}
+
+pub(super) fn default_stream_chunk_kb() -> u32 {
+    64
+}
+
+pub(super) fn default_art_cache_max_age_seconds() -> u64 {
+    24 * 60 * 60
+}
+
 pub(super) fn default_redb_cache_mb() -> usize {
     128
 }
 
+pub(super) fn default_max_connections() -> usize {
+    crate::database::redb::DEFAULT_MAX_READERS
+}
+
+pub(super) fn default_backup_interval_hours() -> u64 {
+    24
+}
+
+pub(super) fn default_backup_keep() -> usize {
+    3
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     pub server: ServerConfig,
@@ -42,6 +94,40 @@ pub struct AppConfig {
     pub management: ManagementConfig,
     #[serde(default)]
     pub cast: CastConfig,
+    #[serde(default)]
+    pub renderers: Vec<RendererProfileConfig>,
+    #[serde(default)]
+    pub logging: LoggingConfig,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    /// Append one JSON line per media streaming session to this file, rotated
+    /// daily. Unset disables the audit log.
+    #[serde(default)]
+    pub stream_audit_file: Option<std::path::PathBuf>,
+    /// Env-filter for the log file and verbose console, e.g.
+    /// `info,vuio::ssdp=debug`. `VUIO_LOG`, `RUST_LOG`, `--log-level` and
+    /// `--debug` take precedence. Unset keeps `info`.
+    #[serde(default)]
+    pub filter: Option<String>,
+}
+
+/// Per-renderer overrides keyed by the detected DLNA client profile.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RendererProfileConfig {
+    /// Client profile name, e.g. "LgTv", "SamsungTv" or "Standard".
+    pub profile: crate::web::client::DlnaClientProfile,
+    /// Highest sustained bitrate the renderer's link can carry, in Mbit/s.
+    #[serde(default)]
+    pub max_bitrate_mbps: Option<f64>,
+    /// Omit resources above `max_bitrate_mbps` instead of only ranking them last.
+    #[serde(default)]
+    pub strict_bitrate: bool,
+    /// Overrides `media.autoplay_enabled` for this renderer. Some renderers
+    /// play a track twice when the autoplay flags are present.
+    #[serde(default)]
+    pub autoplay: Option<bool>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -120,6 +206,71 @@ pub struct ServerConfig {
     #[serde(default = "default_uuid")]
     pub uuid: String,
     pub ip: Option<String>,
+    /// Rebind the HTTP listener and re-announce over SSDP when `port` or
+    /// `interface` changes in a reloaded config. When false those changes
+    /// wait for a restart.
+    #[serde(default = "default_true")]
+    pub rebind_on_change: bool,
+    /// When `port` is already in use at startup, listen on the first free
+    /// platform preferred port instead and advertise that one.
+    #[serde(default = "default_false")]
+    pub port_fallback: bool,
+    /// Directory relative paths in this file resolve against. A relative
+    /// base resolves against the config file's directory, which is also the
+    /// base when this is unset.
+    #[serde(default)]
+    pub path_base: Option<std::path::PathBuf>,
+    /// Serve the same routes over HTTPS on `tls_port` as well. SSDP keeps
+    /// advertising the plain-HTTP address.
+    #[serde(default)]
+    pub tls: Option<TlsConfig>,
+    #[serde(default = "default_tls_port")]
+    pub tls_port: u16,
+    /// Answer management and REST API requests on the HTTPS listener only.
+    #[serde(default = "default_false")]
+    pub api_https_only: bool,
+    /// Reverse proxies (CIDR networks or addresses) whose `Forwarded` and
+    /// `X-Forwarded-*` headers name the real client, scheme and host.
+    #[serde(default)]
+    pub trusted_proxies: Vec<String>,
+    /// Absolute `http://` URL used verbatim as the prefix of SSDP LOCATION,
+    /// URLBase and every media, art and subtitle link, for setups where the
+    /// detected address is not the one clients can reach.
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Serve the built-in web dashboard at `/`. The JSON API it uses stays
+    /// available either way.
+    #[serde(default = "default_true")]
+    pub web_dashboard: bool,
+}
+
+/// PEM certificate chain and private key for the HTTPS listener. Both are
+/// reloaded on SIGHUP or when either file changes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TlsConfig {
+    pub cert_path: std::path::PathBuf,
+    pub key_path: std::path::PathBuf,
+}
+
+impl ServerConfig {
+    /// Unique device name shared by description.xml and every SSDP USN.
+    pub fn udn(&self) -> String {
+        format!("uuid:{}", self.uuid)
+    }
+}
+
+/// Accept the spellings people paste into `server.uuid` (a `uuid:` or
+/// `urn:uuid:` prefix, braces, upper case, no hyphens) and return the
+/// lowercase hyphenated form used on the wire.
+pub fn normalize_device_uuid(value: &str) -> Option<String> {
+    let trimmed = value.trim();
+    let bare = trimmed
+        .get(..5)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("uuid:"))
+        .map_or(trimmed, |_| &trimmed[5..]);
+    uuid::Uuid::try_parse(bare)
+        .ok()
+        .map(|uuid| uuid.hyphenated().to_string())
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -129,6 +280,28 @@ pub struct NetworkConfig {
     pub announce_interval_seconds: u64,
     #[serde(default)]
     pub upnp_callback_allowed_networks: Vec<String>,
+    /// CIDR whose interface is announced when the host has several private
+    /// subnets, e.g. `10.0.0.0/8`.
+    #[serde(default)]
+    pub preferred_subnet: Option<String>,
+    /// Seconds between network interface re-detections. When the announced
+    /// interface changes or disappears, SSDP restarts on the new address.
+    /// Zero disables the check.
+    #[serde(default = "default_interface_check_interval_seconds")]
+    pub interface_check_interval_seconds: u64,
+    /// Server names shown instead of `server.name` to clients reached
+    /// through particular interfaces or subnets.
+    #[serde(default)]
+    pub friendly_names: Vec<FriendlyNameConfig>,
+}
+
+/// `friendlyName` override for clients on one interface or subnet.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FriendlyNameConfig {
+    /// Interface name, interface IP address, or client CIDR such as
+    /// `10.0.20.0/24`.
+    pub interface: String,
+    pub name: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -139,6 +312,10 @@ pub enum NetworkInterfaceConfig {
     All,
     #[serde(untagged)]
     Specific(String),
+    /// Interface names or IP addresses. SSDP announces on each one with
+    /// that interface's own LOCATION.
+    #[serde(untagged)]
+    Multiple(Vec<String>),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -148,12 +325,102 @@ pub struct MediaConfig {
     pub watch_for_changes: bool,
     #[serde(default = "default_cleanup_deleted_files")]
     pub cleanup_deleted_files: bool,
+    /// Only log the indexed files the deleted-file cleanup would remove.
+    /// Runs the check even when `cleanup_deleted_files` is off.
+    #[serde(default = "default_false")]
+    pub cleanup_dry_run: bool,
+    /// Leave indexed files alone during cleanup and rescans while the mount
+    /// point they live on, listed in `/etc/fstab` or under `/Volumes`, is
+    /// unmounted, whether it is at, above or below the root.
+    #[serde(default = "default_true")]
+    pub protect_unmounted_roots: bool,
+    /// Scans walk the media directories and report what they would add,
+    /// update and remove without writing to the database. The file watcher
+    /// stays off.
+    #[serde(default = "default_false")]
+    pub dry_run: bool,
     #[serde(default = "default_autoplay_enabled")]
     pub autoplay_enabled: bool,
     #[serde(default = "default_scan_playlists")]
     pub scan_playlists: bool,
     #[serde(default = "default_unavailable_root_grace_hours")]
     pub unavailable_root_grace_hours: u64,
+    /// Minutes a deleted file stays indexed, hidden from listings, before it
+    /// is removed. A file that comes back in time keeps its ID, playlist
+    /// entries and bookmarks. Zero removes deleted files at once.
+    #[serde(default = "default_deletion_grace_minutes")]
+    pub deletion_grace_minutes: u64,
+    /// Compare indexed and on-disk file counts per root at startup and rescan
+    /// only the roots that diverge, instead of running a full scan.
+    #[serde(default = "default_false")]
+    pub verify_on_start: bool,
+    /// Largest count difference tolerated before a root is rescanned.
+    #[serde(default = "default_verify_on_start_threshold")]
+    pub verify_on_start_threshold: u64,
+    /// Continue an interrupted startup scan from the last completed directory
+    /// instead of starting over.
+    #[serde(default = "default_false")]
+    pub resume_interrupted_scans: bool,
+    /// Seconds a watched file's size must stay unchanged before it is indexed,
+    /// so copies in progress are not served truncated. Zero disables the wait.
+    #[serde(default = "default_settle_time_seconds")]
+    pub settle_time_seconds: u64,
+    /// Compare digit runs in titles and filenames by value, so "Episode 2"
+    /// lists before "Episode 10". Takes effect on restart.
+    #[serde(default = "default_true")]
+    pub natural_sort: bool,
+    /// Keep one `.m3u8` file per database playlist in this directory,
+    /// rewritten shortly after every playlist change. Playlist scans skip it.
+    #[serde(default)]
+    pub playlist_mirror_dir: Option<std::path::PathBuf>,
+    /// Send Samsung TVs the saved resume position of video items as
+    /// `sec:dcmInfo` and keep the positions they report with `X_SetBookmark`.
+    #[serde(default = "default_true")]
+    pub samsung_bookmarks: bool,
+    /// Let DLNA control points create and delete playlists and add or remove
+    /// their tracks with `CreateObject`, `CreateReference` and
+    /// `DestroyObject`. The control URL has no authentication, so this is off
+    /// unless every device on the network may edit playlists.
+    #[serde(default = "default_false")]
+    pub dlna_playlist_editing: bool,
+    /// Hash the size and the first and last MiB of every file while scanning,
+    /// and list only one copy of files whose hashes match: the one in the
+    /// highest-priority directory. Also accepted as `deduplicate`.
+    #[serde(default = "default_false", alias = "deduplicate")]
+    pub hide_duplicates: bool,
+    /// Page size for Browse requests whose `RequestedCount` is missing or not
+    /// a number. Zero means as many entries as one response carries, like a
+    /// `RequestedCount` of 0.
+    #[serde(default)]
+    pub browse_default_count: u32,
+    /// Downscale album art whose width or height exceeds this many pixels
+    /// before serving it. Zero serves art at its original size.
+    #[serde(default)]
+    pub art_max_dimension: u32,
+    /// KiB read from disk per chunk of a streamed media response. Only one
+    /// chunk per stream is held in memory at a time.
+    #[serde(default = "default_stream_chunk_kb")]
+    pub stream_chunk_kb: u32,
+    /// Media streams sent at once. Further requests get `503` with
+    /// `Retry-After` instead of slowing every stream down. Zero is unlimited.
+    #[serde(default)]
+    pub max_concurrent_streams: usize,
+    /// `max-age` clients may cache album art for. Art ETags change with the
+    /// file's mtime, so long lifetimes are safe. Zero makes clients
+    /// revalidate every time.
+    #[serde(default = "default_art_cache_max_age_seconds")]
+    pub art_cache_max_age_seconds: u64,
+    /// Log scan progress after every this many files. Zero disables it.
+    #[serde(default = "default_scan_progress_interval_files")]
+    pub scan_progress_interval_files: u64,
+    /// Log scan progress at least this often while a scan runs. Zero
+    /// disables it.
+    #[serde(default = "default_scan_progress_interval_seconds")]
+    pub scan_progress_interval_seconds: u64,
+    /// Content type `.iso` images are served with. Renderers that play disc
+    /// images directly differ in which type they expect.
+    #[serde(default = "default_iso_mime_type")]
+    pub iso_mime_type: String,
     pub supported_extensions: Vec<String>,
 }
 
@@ -166,8 +433,23 @@ pub enum ValidationMode {
     Skip,
 }
 
+/// How changes below a monitored directory are detected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WatchMode {
+    /// Operating system change notifications (inotify, FSEvents, ReadDirectoryChangesW).
+    Native,
+    /// Periodic directory polling, for mounts where notifications never arrive.
+    Poll,
+    /// Poll network filesystems and use native notifications everywhere else.
+    #[default]
+    Auto,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MonitoredDirectoryConfig {
+    /// A local directory, or the `http(s)://` URL of a remote JSON index
+    /// (see [`crate::remote`]).
     pub path: String,
     pub recursive: bool,
     #[serde(default)]
@@ -176,15 +458,91 @@ pub struct MonitoredDirectoryConfig {
     pub exclude_patterns: Option<Vec<String>>,
     #[serde(default)]
     pub validation_mode: ValidationMode,
+    #[serde(default)]
+    pub watch_mode: WatchMode,
+    /// Directories sharing a group name are browsed as one merged tree.
+    #[serde(default)]
+    pub merge_group: Option<String>,
+    /// Rank within a merge group; the highest priority copy of a file wins.
+    #[serde(default)]
+    pub priority: i32,
+    /// Top-level MIME types (`audio`, `video`, `image`) indexed from this
+    /// directory; `None` indexes every type its extensions allow.
+    #[serde(default)]
+    pub media_types: Option<Vec<String>>,
+    /// Files smaller than this many bytes are not indexed, e.g. sample clips.
+    #[serde(default)]
+    pub min_file_size: Option<u64>,
+    /// Files modified within this many seconds are not indexed yet, so a
+    /// download without a temporary extension is not picked up half-written.
+    #[serde(default)]
+    pub min_file_age_seconds: Option<u64>,
+}
+
+/// Values accepted in [`MonitoredDirectoryConfig::media_types`].
+pub const MEDIA_TYPES: [&str; 3] = ["audio", "video", "image"];
+
+impl MediaConfig {
+    /// `deletion_grace_minutes` as a duration; `None` when it is zero.
+    pub fn deletion_grace(&self) -> Option<std::time::Duration> {
+        (self.deletion_grace_minutes > 0)
+            .then(|| std::time::Duration::from_secs(self.deletion_grace_minutes * 60))
+    }
+}
+
+impl MonitoredDirectoryConfig {
+    /// Whether files of `mime_type` belong in this directory.
+    pub fn admits_mime_type(&self, mime_type: &str) -> bool {
+        let Some(media_types) = &self.media_types else {
+            return true;
+        };
+        let top_level = mime_type.split('/').next().unwrap_or_default();
+        media_types
+            .iter()
+            .any(|media_type| media_type.trim().eq_ignore_ascii_case(top_level))
+    }
+}
+
+/// Storage engine behind the media index.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DatabaseBackendKind {
+    /// Persistent ReDB file at `database.path`.
+    #[default]
+    Redb,
+    /// Process-local collections; the index is rebuilt on every start.
+    /// Intended for tests, demos, and read-only media shares.
+    Memory,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DatabaseConfig {
+    #[serde(default)]
+    pub backend: DatabaseBackendKind,
     pub path: Option<String>,
     pub vacuum_on_startup: bool,
     #[serde(default)]
     pub compact_on_shutdown: bool,
     pub backup_enabled: bool,
+    /// Hours between scheduled backups while `backup_enabled` is set. Zero
+    /// keeps only the startup and shutdown backups.
+    #[serde(default = "default_backup_interval_hours")]
+    pub backup_interval_hours: u64,
+    /// Directory backups are written to. Unset uses `backups` next to the
+    /// database file.
+    #[serde(default)]
+    pub backup_dir: Option<String>,
+    /// Backups to keep; older ones are deleted after each new one.
+    #[serde(default = "default_backup_keep")]
+    pub backup_keep: usize,
+    /// Restore the newest usable backup at startup when the database file is
+    /// missing or its health check finds damage repair could not fix.
+    #[serde(default)]
+    pub auto_restore: bool,
     #[serde(default = "default_redb_cache_mb")]
     pub redb_cache_mb: usize,
+    /// Read transactions that may run at once; further reads wait for one to
+    /// finish. Writes go through a single writer and are not counted.
+    #[serde(default = "default_max_connections")]
+    pub max_connections: usize,
 }
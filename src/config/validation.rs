@@ -4,7 +4,10 @@ use std::{
     path::Path,
 };
 
-use super::{AppConfig, MonitoredDirectoryConfig, NetworkInterfaceConfig, ValidationMode};
+use super::{
+    AppConfig, ExcludeMatcher, MonitoredDirectoryConfig, NetworkInterfaceConfig, ValidationMode,
+    MEDIA_TYPES,
+};
 use crate::platform::config::PlatformConfig;
 
 /// Configuration validator for ensuring configuration integrity
@@ -17,6 +20,7 @@ impl ConfigValidator {
         Self::validate_network_config(config)?;
         Self::validate_media_config(config)?;
         Self::validate_database_config(config)?;
+        Self::validate_logging_config(config)?;
         Self::validate_platform_specific(config)?;
         Ok(())
     }
@@ -43,9 +47,10 @@ impl ConfigValidator {
             return Err(anyhow!("Server name cannot be empty"));
         }
 
-        // Validate UUID format (basic check)
-        if config.server.uuid.len() != 36
-            || config.server.uuid.chars().filter(|&c| c == '-').count() != 4
+        // The UDN and every SSDP USN are built from this value, so it must
+        // already be in the normalized lowercase hyphenated form.
+        if super::normalize_device_uuid(&config.server.uuid).as_deref()
+            != Some(config.server.uuid.as_str())
         {
             return Err(anyhow!("Invalid UUID format: {}", config.server.uuid));
         }
@@ -61,6 +66,36 @@ impl ConfigValidator {
             }
         }
 
+        if config.server.tls.is_some() {
+            if config.server.tls_port == 0 {
+                return Err(anyhow!("Server TLS port cannot be 0"));
+            }
+            if config.server.tls_port == config.server.port {
+                return Err(anyhow!(
+                    "Server TLS port {} is already the plain HTTP port",
+                    config.server.tls_port
+                ));
+            }
+        } else if config.server.api_https_only {
+            return Err(anyhow!(
+                "server.api_https_only needs a server.tls certificate and key"
+            ));
+        }
+        crate::web::forwarded::parse_trusted_proxies(&config.server.trusted_proxies)?;
+        if let Some(base_url) = &config.server.base_url {
+            let url = reqwest::Url::parse(base_url)
+                .with_context(|| format!("Invalid server.base_url: {base_url}"))?;
+            if url.scheme() != "http"
+                || !url.has_host()
+                || url.query().is_some()
+                || url.fragment().is_some()
+            {
+                return Err(anyhow!(
+                    "server.base_url must be an absolute http:// URL without a query or fragment"
+                ));
+            }
+        }
+
         Ok(())
     }
 
@@ -83,6 +118,18 @@ impl ConfigValidator {
                 .parse::<ipnet::IpNet>()
                 .with_context(|| format!("Invalid UPnP callback network CIDR: {network}"))?;
         }
+        for entry in &config.network.friendly_names {
+            if entry.interface.trim().is_empty() || entry.name.trim().is_empty() {
+                return Err(anyhow!(
+                    "network.friendly_names entries need both an interface and a name"
+                ));
+            }
+        }
+        if let Some(subnet) = &config.network.preferred_subnet {
+            subnet
+                .parse::<ipnet::IpNet>()
+                .with_context(|| format!("Invalid preferred subnet CIDR: {subnet}"))?;
+        }
 
         // Validate interface selection
         match &config.network.interface_selection {
@@ -91,6 +138,17 @@ impl ConfigValidator {
                     return Err(anyhow!("Specific network interface name cannot be empty"));
                 }
             }
+            NetworkInterfaceConfig::Multiple(interfaces) => {
+                if interfaces.is_empty() {
+                    return Err(anyhow!("Network interface list cannot be empty"));
+                }
+                if interfaces
+                    .iter()
+                    .any(|interface| interface.trim().is_empty())
+                {
+                    return Err(anyhow!("Network interface names cannot be empty"));
+                }
+            }
             NetworkInterfaceConfig::Auto | NetworkInterfaceConfig::All => {
                 // These are always valid
             }
@@ -113,6 +171,9 @@ impl ConfigValidator {
             Self::validate_monitored_directory_strict(dir, index)?;
         }
 
+        Self::validate_playlist_mirror_dir(config)?;
+        Self::validate_iso_mime_type(config)?;
+
         // Check for duplicate extensions
         let mut extensions = config.media.supported_extensions.clone();
         extensions.sort();
@@ -126,6 +187,31 @@ impl ConfigValidator {
         Ok(())
     }
 
+    fn validate_iso_mime_type(config: &AppConfig) -> Result<()> {
+        let mime = config.media.iso_mime_type.trim();
+        if mime.is_empty() || !mime.contains('/') {
+            return Err(anyhow!(
+                "media.iso_mime_type must be a MIME type such as application/x-iso9660-image: {:?}",
+                config.media.iso_mime_type
+            ));
+        }
+        Ok(())
+    }
+
+    /// Mirrored playlists hold paths relative to their own location, so the
+    /// directory must not depend on the working directory.
+    fn validate_playlist_mirror_dir(config: &AppConfig) -> Result<()> {
+        if let Some(path) = &config.media.playlist_mirror_dir {
+            if !path.is_absolute() {
+                return Err(anyhow!(
+                    "media.playlist_mirror_dir must be an absolute path: {}",
+                    path.display()
+                ));
+            }
+        }
+        Ok(())
+    }
+
     /// Validate a single monitored directory configuration (strict mode - ignores validation_mode)
     fn validate_monitored_directory_strict(
         dir: &MonitoredDirectoryConfig,
@@ -138,27 +224,29 @@ impl ConfigValidator {
             return Err(anyhow!("{}: path cannot be empty", context));
         }
 
-        let path = Path::new(&dir.path);
+        if !Self::validate_remote_root(dir, &context)? {
+            let path = Path::new(&dir.path);
 
-        // Always perform strict validation regardless of validation_mode
-        if !path.exists() {
-            return Err(anyhow!("{}: path does not exist: {}", context, dir.path));
-        }
+            // Always perform strict validation regardless of validation_mode
+            if !path.exists() {
+                return Err(anyhow!("{}: path does not exist: {}", context, dir.path));
+            }
 
-        if !path.is_dir() {
-            return Err(anyhow!(
-                "{}: path is not a directory: {}",
-                context,
-                dir.path
-            ));
-        }
+            if !path.is_dir() {
+                return Err(anyhow!(
+                    "{}: path is not a directory: {}",
+                    context,
+                    dir.path
+                ));
+            }
 
-        // Platform-specific path validation
-        let platform_config = PlatformConfig::for_current_platform();
-        let path_buf = std::path::PathBuf::from(&dir.path);
-        platform_config
-            .validate_path(&path_buf)
-            .with_context(|| format!("{}: path failed platform validation", context))?;
+            // Platform-specific path validation
+            let platform_config = PlatformConfig::for_current_platform();
+            let path_buf = std::path::PathBuf::from(&dir.path);
+            platform_config
+                .validate_path(&path_buf)
+                .with_context(|| format!("{}: path failed platform validation", context))?;
+        }
 
         // Validate extensions if specified
         if let Some(extensions) = &dir.extensions {
@@ -187,12 +275,75 @@ impl ConfigValidator {
                 if pattern.trim().is_empty() {
                     return Err(anyhow!("{}: exclude pattern cannot be empty", context));
                 }
+                ExcludeMatcher::validate_pattern(pattern)
+                    .map_err(|error| anyhow!("{}: {}", context, error))?;
             }
         }
 
+        if dir
+            .merge_group
+            .as_ref()
+            .is_some_and(|group| group.trim().is_empty())
+        {
+            return Err(anyhow!("{}: merge_group cannot be empty", context));
+        }
+
+        Self::validate_media_types(dir, &context)?;
+
         Ok(())
     }
 
+    fn validate_media_types(dir: &MonitoredDirectoryConfig, context: &str) -> Result<()> {
+        let Some(media_types) = &dir.media_types else {
+            return Ok(());
+        };
+        if media_types.is_empty() {
+            return Err(anyhow!(
+                "{}: media_types list cannot be empty if specified",
+                context
+            ));
+        }
+        for media_type in media_types {
+            if !MEDIA_TYPES
+                .iter()
+                .any(|known| known.eq_ignore_ascii_case(media_type.trim()))
+            {
+                return Err(anyhow!(
+                    "{}: unknown media type '{}' (expected one of: {})",
+                    context,
+                    media_type,
+                    MEDIA_TYPES.join(", ")
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check a root given as a URL. Returns whether `dir` is a remote root,
+    /// whose path is then not checked on the local filesystem.
+    fn validate_remote_root(dir: &MonitoredDirectoryConfig, context: &str) -> Result<bool> {
+        if let Some(scheme) = crate::remote::unsupported_scheme(&dir.path) {
+            return Err(anyhow!(
+                "{}: {}:// roots are not supported; mount the share and monitor the mount point: {}",
+                context,
+                scheme,
+                dir.path
+            ));
+        }
+        if !crate::remote::is_remote_root(&dir.path) {
+            return Ok(false);
+        }
+        crate::remote::validate_root(&dir.path)
+            .map_err(|error| anyhow!("{}: {}", context, error))?;
+        if dir.merge_group.is_some() {
+            return Err(anyhow!(
+                "{}: remote roots cannot join a merge_group",
+                context
+            ));
+        }
+        Ok(true)
+    }
+
     /// Validate a single monitored directory configuration (respects validation_mode)
     fn validate_monitored_directory(dir: &MonitoredDirectoryConfig, index: usize) -> Result<()> {
         let context = format!("monitored directory {}", index);
@@ -202,61 +353,63 @@ impl ConfigValidator {
             return Err(anyhow!("{}: path cannot be empty", context));
         }
 
-        let path = Path::new(&dir.path);
+        if !Self::validate_remote_root(dir, &context)? {
+            let path = Path::new(&dir.path);
 
-        // Handle validation based on validation mode
-        match dir.validation_mode {
-            ValidationMode::Skip => {
-                tracing::debug!("{}: validation skipped", context);
-                // Skip all path validation for this directory
-            }
-            ValidationMode::Warn => {
-                // Check if path exists and is a directory, but only warn
-                if !path.exists() {
-                    tracing::warn!(
-                        "{}: path does not exist: {} (continuing startup)",
-                        context,
-                        dir.path
-                    );
-                } else if !path.is_dir() {
-                    tracing::warn!(
-                        "{}: path is not a directory: {} (continuing startup)",
-                        context,
-                        dir.path
-                    );
-                } else {
-                    // Platform-specific path validation - warn on failure
-                    let platform_config = PlatformConfig::for_current_platform();
-                    let path_buf = std::path::PathBuf::from(&dir.path);
-                    if let Err(e) = platform_config.validate_path(&path_buf) {
+            // Handle validation based on validation mode
+            match dir.validation_mode {
+                ValidationMode::Skip => {
+                    tracing::debug!("{}: validation skipped", context);
+                    // Skip all path validation for this directory
+                }
+                ValidationMode::Warn => {
+                    // Check if path exists and is a directory, but only warn
+                    if !path.exists() {
                         tracing::warn!(
-                            "{}: path failed platform validation: {} (continuing startup)",
+                            "{}: path does not exist: {} (continuing startup)",
                             context,
-                            e
+                            dir.path
                         );
+                    } else if !path.is_dir() {
+                        tracing::warn!(
+                            "{}: path is not a directory: {} (continuing startup)",
+                            context,
+                            dir.path
+                        );
+                    } else {
+                        // Platform-specific path validation - warn on failure
+                        let platform_config = PlatformConfig::for_current_platform();
+                        let path_buf = std::path::PathBuf::from(&dir.path);
+                        if let Err(e) = platform_config.validate_path(&path_buf) {
+                            tracing::warn!(
+                                "{}: path failed platform validation: {} (continuing startup)",
+                                context,
+                                e
+                            );
+                        }
                     }
                 }
-            }
-            ValidationMode::Strict => {
-                // Original strict validation behavior
-                if !path.exists() {
-                    return Err(anyhow!("{}: path does not exist: {}", context, dir.path));
-                }
+                ValidationMode::Strict => {
+                    // Original strict validation behavior
+                    if !path.exists() {
+                        return Err(anyhow!("{}: path does not exist: {}", context, dir.path));
+                    }
 
-                if !path.is_dir() {
-                    return Err(anyhow!(
-                        "{}: path is not a directory: {}",
-                        context,
-                        dir.path
-                    ));
-                }
+                    if !path.is_dir() {
+                        return Err(anyhow!(
+                            "{}: path is not a directory: {}",
+                            context,
+                            dir.path
+                        ));
+                    }
 
-                // Platform-specific path validation
-                let platform_config = PlatformConfig::for_current_platform();
-                let path_buf = std::path::PathBuf::from(&dir.path);
-                platform_config
-                    .validate_path(&path_buf)
-                    .with_context(|| format!("{}: path failed platform validation", context))?;
+                    // Platform-specific path validation
+                    let platform_config = PlatformConfig::for_current_platform();
+                    let path_buf = std::path::PathBuf::from(&dir.path);
+                    platform_config
+                        .validate_path(&path_buf)
+                        .with_context(|| format!("{}: path failed platform validation", context))?;
+                }
             }
         }
 
@@ -287,33 +440,66 @@ impl ConfigValidator {
                 if pattern.trim().is_empty() {
                     return Err(anyhow!("{}: exclude pattern cannot be empty", context));
                 }
+                ExcludeMatcher::validate_pattern(pattern)
+                    .map_err(|error| anyhow!("{}: {}", context, error))?;
             }
         }
 
+        if dir
+            .merge_group
+            .as_ref()
+            .is_some_and(|group| group.trim().is_empty())
+        {
+            return Err(anyhow!("{}: merge_group cannot be empty", context));
+        }
+
+        Self::validate_media_types(dir, &context)?;
+
         Ok(())
     }
 
     /// Validate database configuration
     fn validate_database_config(config: &AppConfig) -> Result<()> {
+        if config.database.backup_keep == 0 {
+            return Err(anyhow!("database.backup_keep must be at least 1"));
+        }
+        if config.database.max_connections == 0 {
+            return Err(anyhow!("database.max_connections must be at least 1"));
+        }
+        if config
+            .database
+            .backup_dir
+            .as_ref()
+            .is_some_and(|dir| dir.trim().is_empty())
+        {
+            return Err(anyhow!("database.backup_dir cannot be empty if specified"));
+        }
+
         // Validate database path if specified
         if let Some(db_path) = &config.database.path {
             if db_path.trim().is_empty() {
                 return Err(anyhow!("Database path cannot be empty if specified"));
             }
+            // A missing parent directory is created at startup by
+            // `AppConfig::validate_for_platform`; validation itself stays
+            // read-only so `vuio check-config` can run it.
+            if Path::new(db_path).is_dir() {
+                return Err(anyhow!("Database path is a directory: {}", db_path));
+            }
+        }
 
-            let path = Path::new(db_path);
+        Ok(())
+    }
 
-            // Check if parent directory exists or can be created
-            if let Some(parent) = path.parent() {
-                if !parent.exists() {
-                    // Try to create the parent directory to validate it's writable
-                    std::fs::create_dir_all(parent).with_context(|| {
-                        format!("Cannot create database directory: {}", parent.display())
-                    })?;
-                }
+    /// Validate logging configuration
+    fn validate_logging_config(config: &AppConfig) -> Result<()> {
+        if let Some(filter) = &config.logging.filter {
+            if filter.trim().is_empty() {
+                return Err(anyhow!("Log filter cannot be empty if specified"));
             }
+            tracing_subscriber::EnvFilter::try_new(filter)
+                .map_err(|error| anyhow!("Invalid log filter '{}': {}", filter, error))?;
         }
-
         Ok(())
     }
 
@@ -322,7 +508,13 @@ impl ConfigValidator {
         let platform_config = PlatformConfig::for_current_platform();
 
         // Validate monitored directories against platform constraints
-        for (index, dir) in config.media.directories.iter().enumerate() {
+        for (index, dir) in config
+            .media
+            .directories
+            .iter()
+            .enumerate()
+            .filter(|(_, dir)| !crate::remote::is_remote_root(&dir.path))
+        {
             let path = std::path::PathBuf::from(&dir.path);
             platform_config.validate_path(&path).with_context(|| {
                 format!(
@@ -403,6 +595,7 @@ impl ConfigValidator {
         Self::validate_network_config(config)?;
         Self::validate_media_config_flexible(config)?;
         Self::validate_database_config(config)?;
+        Self::validate_logging_config(config)?;
         Self::validate_platform_specific(config)?;
         Ok(())
     }
@@ -421,6 +614,9 @@ impl ConfigValidator {
             Self::validate_monitored_directory(dir, index)?;
         }
 
+        Self::validate_playlist_mirror_dir(config)?;
+        Self::validate_iso_mime_type(config)?;
+
         // Check for duplicate extensions
         let mut extensions = config.media.supported_extensions.clone();
         extensions.sort();
@@ -451,7 +647,12 @@ impl ConfigValidator {
         // and we have fallback mechanisms
 
         // Validate directory permissions
-        for dir in &config.media.directories {
+        for dir in config
+            .media
+            .directories
+            .iter()
+            .filter(|dir| !crate::remote::is_remote_root(&dir.path))
+        {
             let path = Path::new(&dir.path);
             Self::validate_directory_permissions(path).with_context(|| {
                 format!("Directory permission validation failed for: {}", dir.path)
@@ -479,7 +680,7 @@ impl ConfigValidator {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::AppConfig;
+    use crate::config::{AppConfig, WatchMode};
     use tempfile::TempDir;
 
     #[test]
@@ -497,6 +698,12 @@ mod tests {
             extensions: None,
             exclude_patterns: None,
             validation_mode: ValidationMode::Strict,
+            watch_mode: WatchMode::Auto,
+            merge_group: None,
+            priority: 0,
+            media_types: None,
+            min_file_size: None,
+            min_file_age_seconds: None,
         }];
 
         assert!(ConfigValidator::validate(&test_config).is_ok());
@@ -519,6 +726,39 @@ mod tests {
         config.server.name = "Test Server".to_string();
         config.server.uuid = "invalid-uuid".to_string();
         assert!(ConfigValidator::validate(&config).is_err());
+
+        // HTTPS-only API without a certificate, and TLS on the HTTP port
+        config.server.uuid = uuid::Uuid::new_v4().to_string();
+        config.server.api_https_only = true;
+        assert!(ConfigValidator::validate(&config).is_err());
+        config.server.tls = Some(crate::config::TlsConfig {
+            cert_path: "cert.pem".into(),
+            key_path: "key.pem".into(),
+        });
+        config.server.tls_port = config.server.port;
+        assert!(ConfigValidator::validate(&config).is_err());
+
+        // Trusted proxies must be networks or addresses
+        config.server.tls = None;
+        config.server.api_https_only = false;
+        config.server.trusted_proxies = vec!["10.0.0.0/8".to_string(), "192.0.2.7".to_string()];
+        assert!(ConfigValidator::validate(&config).is_ok());
+        config.server.trusted_proxies = vec!["proxy.lan".to_string()];
+        assert!(ConfigValidator::validate(&config).is_err());
+
+        // The base URL must be an absolute http URL
+        config.server.trusted_proxies.clear();
+        config.server.base_url = Some("http://192.168.1.20:8088".to_string());
+        assert!(ConfigValidator::validate(&config).is_ok());
+        for invalid in [
+            "192.168.1.20:8088",
+            "/vuio",
+            "https://media.example",
+            "http://host/?a=1",
+        ] {
+            config.server.base_url = Some(invalid.to_string());
+            assert!(ConfigValidator::validate(&config).is_err(), "{invalid}");
+        }
     }
 
     #[test]
@@ -532,6 +772,12 @@ mod tests {
             extensions: None,
             exclude_patterns: None,
             validation_mode: ValidationMode::Strict,
+            watch_mode: WatchMode::Auto,
+            merge_group: None,
+            priority: 0,
+            media_types: None,
+            min_file_size: None,
+            min_file_age_seconds: None,
         }];
 
         // Test invalid TTL (SSDP port is now hardcoded to 1900)
@@ -555,6 +801,12 @@ mod tests {
             extensions: None,
             exclude_patterns: None,
             validation_mode: ValidationMode::Warn,
+            watch_mode: WatchMode::Auto,
+            merge_group: None,
+            priority: 0,
+            media_types: None,
+            min_file_size: None,
+            min_file_age_seconds: None,
         }];
         config.media.supported_extensions = vec![];
         assert!(ConfigValidator::validate(&config).is_ok());
@@ -572,9 +824,35 @@ mod tests {
             extensions: Some(vec!["mp4".to_string()]),
             exclude_patterns: Some(vec!["*.tmp".to_string()]),
             validation_mode: super::ValidationMode::Strict,
+            watch_mode: WatchMode::Auto,
+            merge_group: None,
+            priority: 0,
+            media_types: None,
+            min_file_size: None,
+            min_file_age_seconds: None,
         };
         assert!(ConfigValidator::validate_monitored_directory(&valid_dir, 0).is_ok());
 
+        // Invalid glob in exclude patterns - error names the pattern
+        let mut invalid_glob_dir = valid_dir.clone();
+        invalid_glob_dir.exclude_patterns = Some(vec!["**/extras/[ab".to_string()]);
+        let error = ConfigValidator::validate_monitored_directory(&invalid_glob_dir, 0)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("'**/extras/[ab'"), "{error}");
+
+        // media_types accepts the top-level types only, in any case
+        let mut typed_dir = valid_dir.clone();
+        typed_dir.media_types = Some(vec!["Audio".to_string(), "image".to_string()]);
+        assert!(ConfigValidator::validate_monitored_directory(&typed_dir, 0).is_ok());
+        typed_dir.media_types = Some(vec!["music".to_string()]);
+        let error = ConfigValidator::validate_monitored_directory(&typed_dir, 0)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("'music'"), "{error}");
+        typed_dir.media_types = Some(Vec::new());
+        assert!(ConfigValidator::validate_monitored_directory(&typed_dir, 0).is_err());
+
         // Invalid directory (doesn't exist) with Strict mode - should fail
         let invalid_dir_strict = super::MonitoredDirectoryConfig {
             path: "/nonexistent/directory".to_string(),
@@ -583,6 +861,12 @@ mod tests {
             extensions: None,
             exclude_patterns: None,
             validation_mode: ValidationMode::Strict,
+            watch_mode: WatchMode::Auto,
+            merge_group: None,
+            priority: 0,
+            media_types: None,
+            min_file_size: None,
+            min_file_age_seconds: None,
         };
         assert!(ConfigValidator::validate_monitored_directory(&invalid_dir_strict, 0).is_err());
 
@@ -594,6 +878,12 @@ mod tests {
             extensions: None,
             exclude_patterns: None,
             validation_mode: ValidationMode::Warn,
+            watch_mode: WatchMode::Auto,
+            merge_group: None,
+            priority: 0,
+            media_types: None,
+            min_file_size: None,
+            min_file_age_seconds: None,
         };
         assert!(ConfigValidator::validate_monitored_directory(&invalid_dir_warn, 0).is_ok());
 
@@ -605,6 +895,12 @@ mod tests {
             extensions: None,
             exclude_patterns: None,
             validation_mode: ValidationMode::Skip,
+            watch_mode: WatchMode::Auto,
+            merge_group: None,
+            priority: 0,
+            media_types: None,
+            min_file_size: None,
+            min_file_age_seconds: None,
         };
         assert!(ConfigValidator::validate_monitored_directory(&invalid_dir_skip, 0).is_ok());
 
@@ -616,6 +912,12 @@ mod tests {
             extensions: None,
             exclude_patterns: None,
             validation_mode: ValidationMode::Warn,
+            watch_mode: WatchMode::Auto,
+            merge_group: None,
+            priority: 0,
+            media_types: None,
+            min_file_size: None,
+            min_file_age_seconds: None,
         };
         assert!(ConfigValidator::validate_monitored_directory(&empty_path_dir, 0).is_err());
     }
@@ -630,6 +932,12 @@ mod tests {
             extensions: None,
             exclude_patterns: None,
             validation_mode: ValidationMode::Warn,
+            watch_mode: WatchMode::Auto,
+            merge_group: None,
+            priority: 0,
+            media_types: None,
+            min_file_size: None,
+            min_file_age_seconds: None,
         };
 
         // Should succeed with warning logged
@@ -643,6 +951,12 @@ mod tests {
             extensions: None,
             exclude_patterns: None,
             validation_mode: ValidationMode::Skip,
+            watch_mode: WatchMode::Auto,
+            merge_group: None,
+            priority: 0,
+            media_types: None,
+            min_file_size: None,
+            min_file_age_seconds: None,
         };
 
         // Should succeed without any validation
@@ -656,6 +970,12 @@ mod tests {
             extensions: None,
             exclude_patterns: None,
             validation_mode: ValidationMode::Strict,
+            watch_mode: WatchMode::Auto,
+            merge_group: None,
+            priority: 0,
+            media_types: None,
+            min_file_size: None,
+            min_file_age_seconds: None,
         };
 
         // Should fail
@@ -678,6 +998,12 @@ mod tests {
                 extensions: None,
                 exclude_patterns: None,
                 validation_mode: ValidationMode::Strict, // This should pass
+                watch_mode: WatchMode::Auto,
+                merge_group: None,
+                priority: 0,
+                media_types: None,
+                min_file_size: None,
+                min_file_age_seconds: None,
             },
             super::MonitoredDirectoryConfig {
                 path: "/definitely/does/not/exist".to_string(),
@@ -686,6 +1012,12 @@ mod tests {
                 extensions: None,
                 exclude_patterns: None,
                 validation_mode: ValidationMode::Warn, // This should warn but not fail
+                watch_mode: WatchMode::Auto,
+                merge_group: None,
+                priority: 0,
+                media_types: None,
+                min_file_size: None,
+                min_file_age_seconds: None,
             },
             super::MonitoredDirectoryConfig {
                 path: "/another/missing/directory".to_string(),
@@ -694,6 +1026,12 @@ mod tests {
                 extensions: None,
                 exclude_patterns: None,
                 validation_mode: ValidationMode::Skip, // This should be skipped
+                watch_mode: WatchMode::Auto,
+                merge_group: None,
+                priority: 0,
+                media_types: None,
+                min_file_size: None,
+                min_file_age_seconds: None,
             },
         ];
 
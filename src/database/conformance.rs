@@ -0,0 +1,1223 @@
+//! Backend conformance suite.
+//!
+//! Every check is written once against the `DatabaseManager` traits and run
+//! against each backend. A check that passes on one backend and fails on the
+//! other is a behaviour difference the application would notice.
+
+use super::*;
+use std::sync::Arc;
+
+fn media(path: &str, mime_type: &str) -> MediaFile {
+    MediaFile::new(PathBuf::from(path), 1, mime_type.to_owned())
+}
+
+fn names(files: &[MediaFile]) -> Vec<&str> {
+    files.iter().map(|file| file.filename.as_str()).collect()
+}
+
+fn directory_names(directories: &[MediaDirectory]) -> Vec<&str> {
+    directories
+        .iter()
+        .map(|directory| directory.name.as_str())
+        .collect()
+}
+
+fn category_counts(categories: &[MusicCategory]) -> Vec<(&str, usize)> {
+    categories
+        .iter()
+        .map(|category| (category.name.as_str(), category.count))
+        .collect()
+}
+
+async fn upsert_reuses_ids_and_removal_leaves_no_ghosts<D: DatabaseManager>(db: Arc<D>) {
+    let mut file = media("/media/deleted/movie.mkv", "video/x-matroska");
+    file.artist = Some("Ghost Artist".to_owned());
+    file.album = Some("Ghost Album".to_owned());
+    file.genre = Some("Ghost Genre".to_owned());
+    file.year = Some(2026);
+    file.album_artist = Some("Ghost Album Artist".to_owned());
+    let first = db.store_media_file(&file).await.unwrap();
+    let second = db
+        .bulk_store_media_files(std::slice::from_ref(&file))
+        .await
+        .unwrap();
+    assert_eq!(vec![first], second);
+
+    let mut stored = db.get_file_by_id(first).await.unwrap().unwrap();
+    assert_eq!(stored.path, file.path);
+    stored.size = 99;
+    db.update_media_file(&stored).await.unwrap();
+    let updated = db.get_file_by_path(&file.path).await.unwrap().unwrap();
+    assert_eq!((updated.id, updated.size), (Some(first), 99));
+    assert!(db.update_media_file(&file).await.is_err());
+
+    let removed = db
+        .remove_media_under_path(Path::new("/media/deleted"))
+        .await
+        .unwrap();
+    assert_eq!(removed.removed_files, 1);
+    assert_eq!(
+        removed.affected_parents,
+        vec![PathBuf::from("/media/deleted")]
+    );
+    assert!(removed.mime_families.contains("video/"));
+    let (dirs, files) = db
+        .get_directory_listing(Path::new("/media"), "video/")
+        .await
+        .unwrap();
+    assert!(dirs.is_empty(), "ghost directories: {dirs:?}");
+    assert!(files.is_empty());
+    assert!(db.get_artists().await.unwrap().is_empty());
+    assert!(db.get_albums(None).await.unwrap().is_empty());
+    assert!(db.get_genres().await.unwrap().is_empty());
+    assert!(db.get_years().await.unwrap().is_empty());
+    assert!(db.get_album_artists().await.unwrap().is_empty());
+    assert!(db.get_file_by_id(first).await.unwrap().is_none());
+}
+
+async fn other_spellings_of_a_path_reach_the_same_record<D: DatabaseManager>(db: Arc<D>) {
+    let files = [
+        media("/Library/Case/Song.mp3", "audio/mpeg"),
+        media("/Library/Case/Other.mp3", "audio/mpeg"),
+    ];
+    let ids = db.bulk_store_media_files(&files).await.unwrap();
+    // Watcher events and scans may spell a stored path differently; only the
+    // normalizer decides whether two spellings are one file.
+    let doubled = PathBuf::from("/Library//Case/Song.mp3");
+    let recased = PathBuf::from("/library/case/song.mp3");
+    let folds_case =
+        canonical_media_path(&recased).unwrap() == canonical_media_path(&files[0].path).unwrap();
+    if cfg!(target_os = "windows") {
+        assert!(folds_case);
+    }
+
+    let found = db.get_file_by_path(&doubled).await.unwrap().unwrap();
+    assert_eq!(found.id, Some(ids[0]));
+    let found = db.get_file_by_path(&recased).await.unwrap();
+    assert_eq!(found.and_then(|file| file.id), folds_case.then_some(ids[0]));
+    let under_root = if folds_case { 2 } else { 0 };
+    assert_eq!(
+        db.get_files_with_path_prefix("/library/case")
+            .await
+            .unwrap()
+            .len(),
+        under_root
+    );
+    assert_eq!(
+        db.load_file_fingerprints_under_root(Path::new("/library/case"))
+            .await
+            .unwrap()
+            .len(),
+        under_root
+    );
+    assert_eq!(
+        db.load_file_fingerprints_under_root(Path::new("/Library//Case/"))
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+
+    // Listing a file under another spelling keeps it.
+    let removed = db
+        .cleanup_missing_files(&[doubled, PathBuf::from("/library/case/other.mp3")])
+        .await
+        .unwrap();
+    assert_eq!(removed, usize::from(!folds_case));
+    assert_eq!(
+        db.bulk_remove_media_files(std::slice::from_ref(&recased))
+            .await
+            .unwrap(),
+        usize::from(folds_case)
+    );
+    assert_eq!(
+        db.get_file_by_id(ids[0]).await.unwrap().is_some(),
+        !folds_case
+    );
+}
+
+async fn bulk_operations_keep_stats_and_streams_consistent<D: DatabaseManager>(db: Arc<D>) {
+    let mut files = vec![
+        media("/library/a.mkv", "video/x-matroska"),
+        media("/library/b.mp3", "audio/mpeg"),
+        media("/library/c.jpg", "image/jpeg"),
+    ];
+    files[0].size = 10;
+    files[1].size = 20;
+    files[2].size = 30;
+    let ids = db.bulk_store_media_files(&files).await.unwrap();
+    assert_eq!(ids.len(), 3);
+    db.create_playlist("Mix", None).await.unwrap();
+
+    let stats = db.get_stats().await.unwrap();
+    assert_eq!(
+        (
+            stats.total_files,
+            stats.total_size,
+            stats.video_files,
+            stats.audio_files,
+            stats.image_files,
+            stats.playlists
+        ),
+        (3, 60, 1, 1, 1, 1)
+    );
+
+    let mut streamed = db
+        .collect_all_media_files()
+        .await
+        .unwrap()
+        .iter()
+        .map(|file| file.id.unwrap())
+        .collect::<Vec<_>>();
+    streamed.sort_unstable();
+    let mut stored = ids.clone();
+    stored.sort_unstable();
+    assert_eq!(streamed, stored);
+    assert_eq!(
+        db.get_file_location_by_id(ids[1])
+            .await
+            .unwrap()
+            .map(|location| location.filename),
+        Some("b.mp3".to_owned())
+    );
+
+    let mut renamed = files[1].clone();
+    renamed.id = Some(ids[1]);
+    renamed.path = PathBuf::from("/library/renamed/b.mp3");
+    db.bulk_update_media_files(std::slice::from_ref(&renamed))
+        .await
+        .unwrap();
+    assert!(db.get_file_by_path(&files[1].path).await.unwrap().is_none());
+    assert_eq!(
+        db.get_file_by_path(&renamed.path)
+            .await
+            .unwrap()
+            .unwrap()
+            .id,
+        Some(ids[1])
+    );
+
+    assert_eq!(
+        db.bulk_remove_media_files(&[files[0].path.clone(), PathBuf::from("/library/none.mkv")])
+            .await
+            .unwrap(),
+        1
+    );
+    assert!(db.remove_media_file(&renamed.path).await.unwrap());
+    assert!(!db.remove_media_file(&renamed.path).await.unwrap());
+    let stats = db.get_stats().await.unwrap();
+    assert_eq!((stats.total_files, stats.total_size), (1, 30));
+}
+
+async fn media_type_counts_follow_every_write<D: DatabaseManager>(db: Arc<D>) {
+    let counts = |videos, songs, pictures, other| MediaTypeCounts {
+        videos,
+        songs,
+        pictures,
+        other,
+    };
+    assert_eq!(db.count_by_media_type().await.unwrap(), counts(0, 0, 0, 0));
+    let files = [
+        media("/library/Films/a.mkv", "video/x-matroska"),
+        media("/library/Music/b.mp3", "audio/mpeg"),
+        media("/library/Music/c.flac", "audio/flac"),
+        media("/library/Photos/d.jpg", "image/jpeg"),
+        media("/library/Discs/e.iso", "application/x-iso9660-image"),
+    ];
+    let ids = db.bulk_store_media_files(&files).await.unwrap();
+    assert_eq!(db.count_by_media_type().await.unwrap(), counts(1, 2, 1, 1));
+
+    // Re-storing is an update, and a changed type moves between counts.
+    db.bulk_store_media_files(&files[..1]).await.unwrap();
+    let mut retyped = files[2].clone();
+    retyped.id = Some(ids[2]);
+    retyped.mime_type = "video/mp4".to_owned();
+    db.bulk_update_media_files(&[retyped]).await.unwrap();
+    assert_eq!(db.count_by_media_type().await.unwrap(), counts(2, 1, 1, 1));
+
+    db.remove_media_file(&files[0].path).await.unwrap();
+    db.remove_media_under_path(Path::new("/library/Photos"))
+        .await
+        .unwrap();
+    assert_eq!(db.count_by_media_type().await.unwrap(), counts(1, 1, 0, 1));
+    let stats = db.get_stats().await.unwrap();
+    assert_eq!(
+        (stats.video_files, stats.audio_files, stats.image_files),
+        (1, 1, 0)
+    );
+}
+
+async fn prefix_queries_respect_component_boundaries<D: DatabaseManager>(db: Arc<D>) {
+    let films = [
+        media("/media/Film/a.mkv", "video/x"),
+        media("/media/Film b/c.mkv", "video/x"),
+        media("/media/Films/b.mkv", "video/x"),
+    ];
+    db.bulk_store_media_files(&films).await.unwrap();
+
+    assert_eq!(
+        names(&db.get_files_with_path_prefix("/media/Film").await.unwrap()),
+        ["a.mkv"]
+    );
+    let fingerprints = db
+        .load_file_fingerprints_under_root(Path::new("/media/Film"))
+        .await
+        .unwrap();
+    assert_eq!(
+        fingerprints
+            .iter()
+            .map(|fingerprint| fingerprint.path.clone())
+            .collect::<Vec<_>>(),
+        [films[0].path.clone()]
+    );
+    assert_eq!(db.load_file_fingerprints().await.unwrap().len(), 3);
+
+    let removed = db
+        .remove_media_under_path(Path::new("/media/Film"))
+        .await
+        .unwrap();
+    assert_eq!(removed.removed_files, 1);
+    assert!(db.get_file_by_path(&films[1].path).await.unwrap().is_some());
+    assert!(db.get_file_by_path(&films[2].path).await.unwrap().is_some());
+
+    // A file path removes just that file.
+    let removed = db.remove_media_under_path(&films[2].path).await.unwrap();
+    assert_eq!(removed.removed_files, 1);
+    assert_eq!(
+        names(&db.get_files_with_path_prefix("/media").await.unwrap()),
+        ["c.mkv"]
+    );
+}
+
+async fn directory_listing_filters_by_family_and_sorts_naturally<D: DatabaseManager>(db: Arc<D>) {
+    let mut second = media("/lib/Music/b.mp3", "audio/mpeg");
+    second.track_number = Some(2);
+    let mut first = media("/lib/Music/z.mp3", "audio/mpeg");
+    first.track_number = Some(1);
+    db.bulk_store_media_files(&[
+        media("/lib/Movies/Episode 10.mkv", "video/x-matroska"),
+        media("/lib/Movies/episode 2.mkv", "video/x-matroska"),
+        second,
+        first,
+        media("/lib/Music/Deep/Nested/c.flac", "audio/flac"),
+        media("/lib/mixed/x.mp3", "audio/mpeg"),
+        media("/lib/mixed/y.mkv", "video/x-matroska"),
+        media("/lib/Photos/p.jpg", "image/jpeg"),
+    ])
+    .await
+    .unwrap();
+
+    let (video_dirs, _) = db
+        .get_directory_listing(Path::new("/lib"), "video/")
+        .await
+        .unwrap();
+    assert_eq!(directory_names(&video_dirs), ["mixed", "Movies"]);
+    let (audio_dirs, _) = db
+        .get_directory_listing(Path::new("/lib/"), "audio/")
+        .await
+        .unwrap();
+    assert_eq!(directory_names(&audio_dirs), ["mixed", "Music"]);
+    let (all_dirs, _) = db
+        .get_directory_listing(Path::new("/lib"), "")
+        .await
+        .unwrap();
+    assert_eq!(
+        directory_names(&all_dirs),
+        ["mixed", "Movies", "Music", "Photos"]
+    );
+
+    let (_, movies) = db
+        .get_directory_listing(Path::new("/lib/Movies"), "video/")
+        .await
+        .unwrap();
+    assert_eq!(names(&movies), ["episode 2.mkv", "Episode 10.mkv"]);
+    let (nested, tracks) = db
+        .get_directory_listing(Path::new("/lib/Music"), "audio/")
+        .await
+        .unwrap();
+    assert_eq!(directory_names(&nested), ["Deep"]);
+    assert_eq!(names(&tracks), ["z.mp3", "b.mp3"]);
+    let (_, mixed_audio) = db
+        .get_directory_listing(Path::new("/lib/mixed"), "audio/")
+        .await
+        .unwrap();
+    assert_eq!(names(&mixed_audio), ["x.mp3"]);
+
+    assert_eq!(
+        directory_names(
+            &db.get_filtered_direct_subdirectories("/lib", "image/")
+                .await
+                .unwrap()
+        ),
+        ["Photos"]
+    );
+    let mut direct = db.get_direct_subdirectories("/lib").await.unwrap();
+    direct.sort();
+    assert_eq!(
+        directory_names(&direct),
+        ["Movies", "Music", "Photos", "mixed"]
+    );
+    assert_eq!(
+        db.get_files_in_directory(Path::new("/lib/mixed"))
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+    assert!(db
+        .get_directory_listing(Path::new("/missing"), "")
+        .await
+        .unwrap()
+        .0
+        .is_empty());
+}
+
+async fn read_sessions_order_filter_and_page<D: DatabaseManager + 'static>(db: Arc<D>) {
+    let mut tagged = media("/session/Albums/Track 10.mp3", "audio/mpeg");
+    tagged.artist = Some("Needle Artist".to_owned());
+    let ids = db
+        .bulk_store_media_files(&[
+            tagged,
+            media("/session/Albums/track 9.mp3", "audio/mpeg"),
+            media("/session/Albums/cover.jpg", "image/jpeg"),
+            media("/session/b/one.mp3", "audio/mpeg"),
+            media("/session/B2/two.mkv", "video/x"),
+            media("/session/a10/three.mp3", "audio/mpeg"),
+            media("/session/a9/four.mp3", "audio/mpeg"),
+        ])
+        .await
+        .unwrap();
+    let playlist = db.create_playlist("Session", None).await.unwrap();
+    db.batch_add_to_playlist(playlist, &[(ids[3], 2), (ids[0], 1)])
+        .await
+        .unwrap();
+
+    // Keyset pages follow id order, which is derived from the paths.
+    let mut audio_after_first = [
+        (ids[1], "track 9.mp3"),
+        (ids[3], "one.mp3"),
+        (ids[5], "three.mp3"),
+        (ids[6], "four.mp3"),
+    ]
+    .into_iter()
+    .filter(|(id, _)| *id > ids[0])
+    .collect::<Vec<_>>();
+    audio_after_first.sort_unstable();
+    let filtered_after_first = audio_after_first
+        .iter()
+        .skip(1)
+        .take(2)
+        .map(|(_, name)| *name)
+        .collect::<Vec<_>>();
+
+    let (directory_files, filtered, needle, paged_dirs, audio_dirs, playlist_files, by_path) = db
+        .clone()
+        .read(move |session| {
+            let mut collect = |query: MediaFileQuery, offset: usize, limit: usize| {
+                let mut names = Vec::new();
+                let summary = session.visit_files(&query, offset, limit, |file| {
+                    names.push(file.filename().to_owned());
+                    Ok(())
+                })?;
+                anyhow::Ok((names, summary.matched))
+            };
+            let directory_files = collect(
+                MediaFileQuery::Directory {
+                    path: "/session/Albums".to_owned(),
+                    mime_family: Some("audio/".to_owned()),
+                },
+                0,
+                usize::MAX,
+            )?;
+            let filtered = collect(
+                MediaFileQuery::Filtered {
+                    after_id: Some(ids[0]),
+                    mime_family: Some("audio/".to_owned()),
+                    text: None,
+                },
+                1,
+                2,
+            )?;
+            let needle = collect(
+                MediaFileQuery::Filtered {
+                    after_id: None,
+                    mime_family: None,
+                    text: Some("needle".to_owned()),
+                },
+                0,
+                10,
+            )?;
+            let playlist_files = collect(MediaFileQuery::Playlist(playlist), 0, 10)?;
+            let by_path = collect(MediaFileQuery::Path("/session/b/one.mp3".to_owned()), 0, 10)?;
+
+            let mut visit_directories = |family: Option<&str>, offset: usize, limit: usize| {
+                let mut names = Vec::new();
+                let summary = session.visit_direct_subdirectories(
+                    "/session",
+                    family,
+                    offset,
+                    limit,
+                    |directory| {
+                        names.push(directory.name().to_owned());
+                        Ok(())
+                    },
+                )?;
+                anyhow::Ok((names, summary.matched))
+            };
+            let paged_dirs = visit_directories(None, 1, 3)?;
+            let audio_dirs = visit_directories(Some("audio/"), 0, usize::MAX)?;
+            Ok((
+                directory_files,
+                filtered,
+                needle,
+                paged_dirs,
+                audio_dirs,
+                playlist_files,
+                by_path,
+            ))
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(
+        directory_files,
+        (vec!["track 9.mp3".to_owned(), "Track 10.mp3".to_owned()], 2)
+    );
+    assert_eq!(filtered.0, filtered_after_first);
+    assert_eq!(needle, (vec!["Track 10.mp3".to_owned()], 1));
+    // Natural, case-insensitive order: a9, a10, Albums, b, B2.
+    assert_eq!(
+        paged_dirs,
+        (
+            vec!["a10".to_owned(), "Albums".to_owned(), "b".to_owned()],
+            5
+        )
+    );
+    assert_eq!(audio_dirs.0, ["a9", "a10", "Albums", "b"]);
+    assert_eq!(playlist_files.0, ["Track 10.mp3", "one.mp3"]);
+    assert_eq!(by_path, (vec!["one.mp3".to_owned()], 1));
+
+    let playlists = db
+        .clone()
+        .read(|session| {
+            let mut names = Vec::new();
+            session.visit_playlists(0, 10, |playlist| {
+                names.push(playlist.name().to_owned());
+                Ok(())
+            })?;
+            Ok(names)
+        })
+        .await
+        .unwrap();
+    assert_eq!(playlists, ["Session"]);
+}
+
+async fn music_categories_count_existing_files<D: DatabaseManager>(db: Arc<D>) {
+    let tagged = |path: &str, artist: &str, album: &str, year: u32| {
+        let mut file = media(path, "audio/mpeg");
+        file.artist = Some(artist.to_owned());
+        file.album = Some(album.to_owned());
+        file.genre = Some("Jazz".to_owned());
+        file.year = Some(year);
+        file.album_artist = Some(format!("{artist} Band"));
+        file
+    };
+    let ids = db
+        .bulk_store_media_files(&[
+            tagged("/music/1.mp3", "Miles", "Blue", 1959),
+            tagged("/music/2.mp3", "Miles", "Blue", 1959),
+            tagged("/music/3.mp3", "Bill", "Blue", 1961),
+            tagged("/music/4.mp3", "Bill", "Sunday", 1961),
+        ])
+        .await
+        .unwrap();
+    db.remove_media_file(Path::new("/music/4.mp3"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        category_counts(&db.get_artists().await.unwrap()),
+        [("Bill", 1), ("Miles", 2)]
+    );
+    assert_eq!(
+        category_counts(&db.get_albums(None).await.unwrap()),
+        [("Blue", 3)]
+    );
+    assert_eq!(
+        category_counts(&db.get_albums(Some("Bill")).await.unwrap()),
+        [("Blue", 1)]
+    );
+    assert_eq!(
+        category_counts(&db.get_genres().await.unwrap()),
+        [("Jazz", 3)]
+    );
+    assert_eq!(
+        category_counts(&db.get_years().await.unwrap()),
+        [("1959", 2), ("1961", 1)]
+    );
+    assert_eq!(
+        category_counts(&db.get_album_artists().await.unwrap()),
+        [("Bill Band", 1), ("Miles Band", 2)]
+    );
+
+    // Ids follow the paths, not the insertion order, so compare them sorted.
+    let ids_of = |files: Vec<MediaFile>| {
+        let mut ids = files
+            .into_iter()
+            .map(|file| file.id.unwrap())
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids
+    };
+    let sorted = |ids: &[i64]| {
+        let mut ids = ids.to_vec();
+        ids.sort_unstable();
+        ids
+    };
+    assert_eq!(
+        ids_of(db.get_music_by_artist("Miles").await.unwrap()),
+        sorted(&ids[..2])
+    );
+    assert_eq!(
+        ids_of(db.get_music_by_album("Blue", None).await.unwrap()),
+        sorted(&ids[..3])
+    );
+    assert_eq!(
+        ids_of(db.get_music_by_album("Blue", Some("Bill")).await.unwrap()),
+        [ids[2]]
+    );
+    assert_eq!(
+        ids_of(db.get_music_by_genre("Jazz").await.unwrap()),
+        sorted(&ids[..3])
+    );
+    assert_eq!(ids_of(db.get_music_by_year(1961).await.unwrap()), [ids[2]]);
+    assert_eq!(
+        ids_of(db.get_music_by_album_artist("Miles Band").await.unwrap()),
+        sorted(&ids[..2])
+    );
+}
+
+async fn playlists_keep_ordered_entries<D: DatabaseManager>(db: Arc<D>) {
+    let ids = db
+        .bulk_store_media_files(&[
+            media("/pl/a.mp3", "audio/mpeg"),
+            media("/pl/b.mp3", "audio/mpeg"),
+            media("/pl/c.mp3", "audio/mpeg"),
+        ])
+        .await
+        .unwrap();
+    let playlist_id = db.create_playlist("Road", Some("trip")).await.unwrap();
+    let mut playlist = db.get_playlist(playlist_id).await.unwrap().unwrap();
+    assert_eq!(playlist.description.as_deref(), Some("trip"));
+    playlist.name = "Road Trip".to_owned();
+    db.update_playlist(&playlist).await.unwrap();
+    assert_eq!(db.get_playlists().await.unwrap()[0].name, "Road Trip");
+    playlist.id = Some(playlist_id + 1000);
+    assert!(db.update_playlist(&playlist).await.is_err());
+
+    db.add_to_playlist(playlist_id, ids[2], Some(0))
+        .await
+        .unwrap();
+    db.batch_add_to_playlist(playlist_id, &[(ids[0], 1), (ids[1], 2)])
+        .await
+        .unwrap();
+    assert_eq!(
+        names(&db.get_playlist_tracks(playlist_id).await.unwrap()),
+        ["c.mp3", "a.mp3", "b.mp3"]
+    );
+    db.reorder_playlist(playlist_id, &[(ids[0], 0), (ids[1], 1), (ids[2], 2)])
+        .await
+        .unwrap();
+    assert_eq!(
+        names(&db.get_playlist_tracks(playlist_id).await.unwrap()),
+        ["a.mp3", "b.mp3", "c.mp3"]
+    );
+    assert!(db.remove_from_playlist(playlist_id, ids[1]).await.unwrap());
+    assert!(!db.remove_from_playlist(playlist_id, ids[1]).await.unwrap());
+    db.remove_media_file(Path::new("/pl/c.mp3")).await.unwrap();
+    assert_eq!(
+        names(&db.get_playlist_tracks(playlist_id).await.unwrap()),
+        ["a.mp3"]
+    );
+
+    let missing = playlist_id + 1000;
+    assert!(db.add_to_playlist(missing, ids[0], None).await.is_err());
+    assert!(db.add_to_playlist(playlist_id, 9_999, None).await.is_err());
+    assert!(db.reorder_playlist(missing, &[]).await.is_err());
+    assert!(db.get_playlist_tracks(missing).await.is_err());
+    assert!(db.delete_playlist(playlist_id).await.unwrap());
+    assert!(!db.delete_playlist(playlist_id).await.unwrap());
+    assert!(db.get_playlists().await.unwrap().is_empty());
+}
+
+async fn concurrent_playlist_edits_lose_nothing<D: DatabaseManager + 'static>(db: Arc<D>) {
+    let files = (0..40)
+        .map(|index| media(&format!("/stress/{index:02}.mp3"), "audio/mpeg"))
+        .collect::<Vec<_>>();
+    let ids = db.bulk_store_media_files(&files).await.unwrap();
+    let playlist_id = db.create_playlist("Stress", None).await.unwrap();
+    db.append_to_playlist(playlist_id, &ids[..10])
+        .await
+        .unwrap();
+
+    // Appends, removals and reorders built from stale listings all race.
+    let mut tasks = Vec::new();
+    for &id in &ids[10..] {
+        let db = db.clone();
+        tasks.push(tokio::spawn(async move {
+            db.add_to_playlist(playlist_id, id, None).await.unwrap();
+        }));
+    }
+    for &id in &ids[..5] {
+        let db = db.clone();
+        tasks.push(tokio::spawn(async move {
+            assert!(db.remove_from_playlist(playlist_id, id).await.unwrap());
+        }));
+    }
+    for _ in 0..10 {
+        let db = db.clone();
+        tasks.push(tokio::spawn(async move {
+            let listing = db.get_playlist_tracks(playlist_id).await.unwrap();
+            let reversed = listing
+                .iter()
+                .rev()
+                .enumerate()
+                .map(|(position, file)| (file.id.unwrap(), position as u32))
+                .collect::<Vec<_>>();
+            db.reorder_playlist(playlist_id, &reversed).await.unwrap();
+        }));
+    }
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    let mut tracks = db
+        .get_playlist_tracks(playlist_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|file| file.id.unwrap())
+        .collect::<Vec<_>>();
+    tracks.sort_unstable();
+    let mut expected = ids[5..].to_vec();
+    expected.sort_unstable();
+    assert_eq!(tracks, expected);
+
+    // A settled reorder is applied exactly; duplicate positions are refused.
+    let order = ids[5..].iter().rev().copied().collect::<Vec<_>>();
+    let positions = order
+        .iter()
+        .enumerate()
+        .map(|(position, id)| (*id, position as u32))
+        .collect::<Vec<_>>();
+    db.reorder_playlist(playlist_id, &positions).await.unwrap();
+    let listed = db.get_playlist_tracks(playlist_id).await.unwrap();
+    assert_eq!(
+        listed
+            .iter()
+            .map(|file| file.id.unwrap())
+            .collect::<Vec<_>>(),
+        order
+    );
+    assert!(db
+        .reorder_playlist(playlist_id, &[(ids[5], 0), (ids[6], 0)])
+        .await
+        .is_err());
+    db.add_to_playlist(playlist_id, ids[0], Some(1))
+        .await
+        .unwrap();
+    let listed = db.get_playlist_tracks(playlist_id).await.unwrap();
+    assert_eq!(listed[1].id, Some(ids[0]));
+    assert_eq!(listed.len(), 36);
+}
+
+async fn source_content_owns_playlists_and_streams<D: DatabaseManager>(db: Arc<D>) {
+    let local = db
+        .store_media_file(&media("/radio/local.mp3", "audio/mpeg"))
+        .await
+        .unwrap();
+    let stream = "http://radio.example/live";
+    let entry = |location: &str, position: u32, title: Option<&str>| SourceMediaEntry {
+        location: PathBuf::from(location),
+        position,
+        stream_title: title.map(str::to_owned),
+        info: Default::default(),
+    };
+    let first_source = Path::new("/radio/lists/first.m3u");
+    let second_source = Path::new("/radio/lists/second.m3u");
+    let entries = [
+        entry("/radio/local.mp3", 0, None),
+        entry("/radio/unindexed.mp3", 1, None),
+        entry(stream, 2, Some("Live Radio")),
+    ];
+    let playlist_id = db
+        .replace_source_content(first_source, Some("First"), &entries)
+        .await
+        .unwrap()
+        .unwrap();
+    let tracks = db.get_playlist_tracks(playlist_id).await.unwrap();
+    assert_eq!(names(&tracks), ["local.mp3", "Live Radio"]);
+    assert_eq!(tracks[0].id, Some(local));
+    assert_eq!(tracks[1].mime_type, "audio/radio");
+    assert_eq!(tracks[1].title.as_deref(), Some("Live Radio"));
+    assert_eq!(tracks[1].path, PathBuf::from(stream));
+
+    // Re-importing the same source keeps its playlist ID.
+    assert_eq!(
+        db.replace_source_content(first_source, Some("First again"), &entries)
+            .await
+            .unwrap(),
+        Some(playlist_id)
+    );
+    assert_eq!(db.get_playlists().await.unwrap().len(), 1);
+
+    db.replace_source_content(second_source, None, &[entry(stream, 0, None)])
+        .await
+        .unwrap();
+    db.replace_source_content(first_source, None, &[])
+        .await
+        .unwrap();
+    assert!(db.get_playlist(playlist_id).await.unwrap().is_none());
+    assert!(
+        db.get_file_by_path(Path::new(stream))
+            .await
+            .unwrap()
+            .is_some(),
+        "a stream still owned by another source must survive"
+    );
+
+    let derived = db
+        .replace_playlist_from_source(Path::new("/radio/lists/third.pls"), "Third", &[(local, 0)])
+        .await
+        .unwrap();
+    assert!(db
+        .replace_playlist_from_source(Path::new("/radio/lists/third.pls"), "Third", &[(9_999, 0)])
+        .await
+        .is_err());
+    let manual = db.create_playlist("Manual", None).await.unwrap();
+    let user = db.create_playlist("User", None).await.unwrap();
+    db.set_playlist_source(manual, Path::new("/radio/lists/manual.m3u"))
+        .await
+        .unwrap();
+    let sources = db.get_playlist_sources().await.unwrap();
+    assert_eq!(
+        sources.get(&manual),
+        Some(&canonical_media_path(Path::new("/radio/lists/manual.m3u")).unwrap())
+    );
+    assert!(sources.contains_key(&derived));
+    assert!(!sources.contains_key(&user));
+    assert!(db
+        .set_playlist_source(manual + 1000, Path::new("/radio/lists/manual.m3u"))
+        .await
+        .is_err());
+
+    let sibling = db
+        .replace_playlist_from_source(Path::new("/radio/lists b/other.m3u"), "Other", &[])
+        .await
+        .unwrap();
+
+    // Second source's stream, the derived playlist, and the manual playlist.
+    let removed = db
+        .remove_derived_content_by_source(Path::new("/radio/lists"))
+        .await
+        .unwrap();
+    assert_eq!(removed, 4);
+    assert!(db
+        .get_file_by_path(Path::new(stream))
+        .await
+        .unwrap()
+        .is_none());
+    assert!(db.get_playlist(derived).await.unwrap().is_none());
+    assert!(db.get_playlist(manual).await.unwrap().is_none());
+    assert!(db.get_playlist(sibling).await.unwrap().is_some());
+    assert!(db.get_file_by_id(local).await.unwrap().is_some());
+}
+
+async fn playlist_entry_info_stays_on_the_playlist<D: DatabaseManager>(db: Arc<D>) {
+    let song = db
+        .store_media_file(&media("/music/untagged.mp3", "audio/mpeg"))
+        .await
+        .unwrap();
+    let info = PlaylistEntryInfo {
+        title: Some("Untagged Song".to_owned()),
+        duration: Some(Duration::from_secs(215)),
+    };
+    let source = Path::new("/music/lists/mix.m3u8");
+    let playlist_id = db
+        .replace_source_content(
+            source,
+            Some("Mix"),
+            &[SourceMediaEntry {
+                location: PathBuf::from("/music/untagged.mp3"),
+                position: 0,
+                stream_title: None,
+                info: info.clone(),
+            }],
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+    let tracks = db.get_playlist_tracks(playlist_id).await.unwrap();
+    assert_eq!(tracks[0].title.as_deref(), Some("Untagged Song"));
+    assert_eq!(tracks[0].duration, Some(Duration::from_secs(215)));
+    assert_eq!(
+        db.get_playlist_entry_info(playlist_id).await.unwrap(),
+        HashMap::from([(song, info)])
+    );
+    let library = db.get_file_by_id(song).await.unwrap().unwrap();
+    assert_eq!((library.title, library.duration), (None, None));
+
+    let other = db.create_playlist("Other", None).await.unwrap();
+    db.add_to_playlist(other, song, None).await.unwrap();
+    assert_eq!(db.get_playlist_tracks(other).await.unwrap()[0].title, None);
+
+    db.delete_playlist(playlist_id).await.unwrap();
+    assert!(db
+        .get_playlist_entry_info(playlist_id)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+async fn root_availability_round_trips<D: DatabaseManager>(db: Arc<D>) {
+    let state = RootAvailability {
+        path: PathBuf::from("/mnt/nas"),
+        last_seen_secs: 10,
+        unavailable_since_secs: Some(20),
+        indexed_count: 3,
+        reason: "mount missing".to_owned(),
+    };
+    db.set_root_availability(&state).await.unwrap();
+    assert_eq!(
+        db.get_root_availability(Path::new("/mnt/nas"))
+            .await
+            .unwrap(),
+        Some(state.clone())
+    );
+    assert_eq!(db.list_root_availability().await.unwrap(), [state]);
+    db.remove_root_availability(Path::new("/mnt/nas"))
+        .await
+        .unwrap();
+    assert!(db.list_root_availability().await.unwrap().is_empty());
+}
+
+async fn settings_round_trip<D: DatabaseManager>(db: Arc<D>) {
+    assert_eq!(db.get_setting("scan_state").await.unwrap(), None);
+    db.set_setting("scan_state", "{\"status\":\"running\"}")
+        .await
+        .unwrap();
+    db.set_setting("scan_state", "{\"status\":\"completed\"}")
+        .await
+        .unwrap();
+    assert_eq!(
+        db.get_setting("scan_state").await.unwrap().as_deref(),
+        Some("{\"status\":\"completed\"}")
+    );
+    db.remove_setting("scan_state").await.unwrap();
+    assert_eq!(db.get_setting("scan_state").await.unwrap(), None);
+}
+
+async fn cleanup_removes_unlisted_paths<D: DatabaseManager>(db: Arc<D>) {
+    let files = [
+        media("/keep/a.mp3", "audio/mpeg"),
+        media("/keep/b.mp3", "audio/mpeg"),
+        media("/keep/c.mp3", "audio/mpeg"),
+        media("/keep/d.mp3", "audio/mpeg"),
+    ];
+    db.bulk_store_media_files(&files).await.unwrap();
+    assert_eq!(
+        names(
+            &db.get_files_by_paths(&[
+                files[2].path.clone(),
+                PathBuf::from("/keep/none.mp3"),
+                files[0].path.clone(),
+            ])
+            .await
+            .unwrap()
+        ),
+        ["c.mp3", "a.mp3"]
+    );
+
+    let all = files
+        .iter()
+        .map(|file| file.path.to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        db.cleanup_missing_files(
+            &files[..3]
+                .iter()
+                .map(|file| file.path.clone())
+                .collect::<Vec<_>>()
+        )
+        .await
+        .unwrap(),
+        1
+    );
+    assert_eq!(
+        db.batch_cleanup_missing_files(&all[..2].iter().cloned().collect())
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(db.database_native_cleanup(&all[..1]).await.unwrap(), 1);
+    assert_eq!(
+        names(&db.collect_all_media_files().await.unwrap()),
+        ["a.mp3"]
+    );
+
+    let health = db.check_and_repair().await.unwrap();
+    assert!(health.is_healthy);
+    assert_eq!(db.get_stats().await.unwrap().total_files, 1);
+}
+
+async fn object_keys_and_ids_survive_rescans_and_rebuilds<D: DatabaseManager + 'static>(
+    db: Arc<D>,
+) {
+    let files = [
+        media("/library/keys/a.mkv", "video/x-matroska"),
+        media("/library/keys/b.mkv", "video/x-matroska"),
+    ];
+    let first_ids = db.bulk_store_media_files(&files).await.unwrap();
+    let key = media_object_key("/library/keys/b.mkv");
+    let location = |db: Arc<D>| async move {
+        db.get_file_location_by_object_key(key)
+            .await
+            .unwrap()
+            .map(|location| (location.id, location.path))
+    };
+    assert_eq!(
+        location(db.clone()).await,
+        Some((first_ids[1], PathBuf::from("/library/keys/b.mkv")))
+    );
+
+    // A full rescan after the index was dropped, in another order, stores
+    // every path under the id it had before.
+    db.remove_media_under_path(Path::new("/library/keys"))
+        .await
+        .unwrap();
+    assert_eq!(location(db.clone()).await, None);
+    let second_ids = db
+        .bulk_store_media_files(&[files[1].clone(), files[0].clone()])
+        .await
+        .unwrap();
+    assert_eq!(second_ids, [first_ids[1], first_ids[0]]);
+    assert_eq!(
+        db.get_file_by_id(first_ids[1])
+            .await
+            .unwrap()
+            .map(|file| file.path),
+        Some(PathBuf::from("/library/keys/b.mkv"))
+    );
+    assert_eq!(
+        location(db.clone()).await,
+        Some((second_ids[0], PathBuf::from("/library/keys/b.mkv")))
+    );
+
+    db.rebuild_derived_indexes().await.unwrap();
+    let visited = db
+        .clone()
+        .read(move |session| {
+            let mut paths = Vec::new();
+            session.visit_files(&MediaFileQuery::ObjectKey(key), 0, 10, |file| {
+                paths.push(file.path().into_owned());
+                Ok(())
+            })?;
+            Ok(paths)
+        })
+        .await
+        .unwrap();
+    assert_eq!(visited, ["/library/keys/b.mkv"]);
+
+    let mut renamed = db
+        .get_file_by_path(Path::new("/library/keys/b.mkv"))
+        .await
+        .unwrap()
+        .unwrap();
+    renamed.path = PathBuf::from("/library/keys/c.mkv");
+    db.update_media_file(&renamed).await.unwrap();
+    assert_eq!(location(db.clone()).await, None);
+    assert!(db
+        .get_file_location_by_object_key(media_object_key("/library/keys/c.mkv"))
+        .await
+        .unwrap()
+        .is_some());
+}
+
+async fn directory_counts_ignore_paging_and_track_changes<D: DatabaseManager + 'static>(
+    db: Arc<D>,
+) {
+    let files = [
+        media("/library/count/a.mp3", "audio/mpeg"),
+        media("/library/count/b.mp3", "audio/mpeg"),
+        media("/library/count/film.mp4", "video/mp4"),
+        media("/library/count/nested/c.mp3", "audio/mpeg"),
+        media("/library/count/nested/deeper/d.mp3", "audio/mpeg"),
+    ];
+    let ids = db.bulk_store_media_files(&files).await.unwrap();
+    let count = |hidden: HashSet<i64>| {
+        let db = db.clone();
+        async move {
+            db.read(move |session| {
+                session.count_directory_entries("/library/count", Some("audio/"), &hidden)
+            })
+            .await
+            .unwrap()
+        }
+    };
+
+    let before = count(HashSet::new()).await;
+    assert_eq!((before.directories, before.files), (1, 2));
+    let hidden = count(HashSet::from([ids[0]])).await;
+    assert_eq!((hidden.directories, hidden.files), (1, 1));
+    assert_ne!(hidden.revision, before.revision);
+
+    // Deeper changes are not this directory's; its own files are.
+    db.store_media_file(&media("/library/count/nested/e.mp3", "audio/mpeg"))
+        .await
+        .unwrap();
+    assert_eq!(count(HashSet::new()).await, before);
+    db.store_media_file(&media("/library/count/f.mp3", "audio/mpeg"))
+        .await
+        .unwrap();
+    let after = count(HashSet::new()).await;
+    assert_eq!(after.files, 3);
+    assert_ne!(after.revision, before.revision);
+}
+
+async fn missing_files_keep_their_id_until_they_return<D: DatabaseManager + 'static>(db: Arc<D>) {
+    let file = media("/library/missing/song.mp3", "audio/mpeg");
+    let id = db.store_media_file(&file).await.unwrap();
+    let mut missing = db.get_file_by_id(id).await.unwrap().unwrap();
+    let since = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    missing.missing_since = Some(since);
+    db.bulk_update_media_files(&[missing]).await.unwrap();
+
+    let fingerprints = db
+        .load_file_fingerprints_under_root(Path::new("/library/missing"))
+        .await
+        .unwrap();
+    assert_eq!(fingerprints[0].missing_since, Some(since));
+    let visited = db
+        .clone()
+        .read(|session| {
+            let mut visited = Vec::new();
+            session.visit_files(&MediaFileQuery::All, 0, usize::MAX, |file| {
+                visited.push((file.id(), file.missing_since_secs()));
+                Ok(())
+            })?;
+            Ok(visited)
+        })
+        .await
+        .unwrap();
+    assert_eq!(visited, vec![(Some(id), Some(1_700_000_000))]);
+
+    // Indexing the path again, as a rescan does, brings the same record back.
+    assert_eq!(db.store_media_file(&file).await.unwrap(), id);
+    let restored = db.get_file_by_id(id).await.unwrap().unwrap();
+    assert_eq!(restored.missing_since, None);
+}
+
+async fn file_ids_are_derived_from_paths<D: DatabaseManager + 'static>(db: Arc<D>) {
+    // Ids must not depend on the backend or on what was stored before.
+    let expected = |path: &str| media_file_id(path, |_| Ok(false)).unwrap();
+    db.store_media_file(&media("/library/ids/first.mp3", "audio/mpeg"))
+        .await
+        .unwrap();
+    let id = db
+        .store_media_file(&media("/library/ids/second.mp3", "audio/mpeg"))
+        .await
+        .unwrap();
+    assert_eq!(id, expected("/library/ids/second.mp3"));
+    assert!(id > 0 && id < 1 << 53);
+
+    // A record already holding the derived id pushes the next path one up.
+    let mut squatter = media("/library/ids/squatter.mp3", "audio/mpeg");
+    squatter.id = Some(expected("/library/ids/third.mp3"));
+    db.store_media_file(&squatter).await.unwrap();
+    let third = db
+        .store_media_file(&media("/library/ids/third.mp3", "audio/mpeg"))
+        .await
+        .unwrap();
+    assert_eq!(
+        third,
+        expected("/library/ids/third.mp3") % ((1 << 53) - 1) + 1
+    );
+
+    // Radio streams created by playlist imports follow the same rule.
+    db.replace_source_content(
+        Path::new("/library/ids/radio.m3u"),
+        Some("Radio"),
+        &[SourceMediaEntry {
+            location: PathBuf::from("http://radio.example/stream"),
+            position: 0,
+            stream_title: Some("Example FM".to_owned()),
+            info: Default::default(),
+        }],
+    )
+    .await
+    .unwrap();
+    let stream = db
+        .get_file_by_path(Path::new("http://radio.example/stream"))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stream.id, Some(expected("http://radio.example/stream")));
+}
+
+macro_rules! conformance_suite {
+    ($($check:ident),* $(,)?) => {
+        mod redb_backend {
+            $(
+                #[tokio::test]
+                async fn $check() {
+                    let temp = tempfile::tempdir().unwrap();
+                    let database = crate::database::redb::RedbDatabase::new(
+                        temp.path().join("conformance.redb"),
+                    )
+                    .await
+                    .unwrap();
+                    crate::database::DatabaseManager::initialize(&database)
+                        .await
+                        .unwrap();
+                    super::$check(std::sync::Arc::new(database)).await;
+                }
+            )*
+        }
+
+        mod memory_backend {
+            $(
+                #[tokio::test]
+                async fn $check() {
+                    let database = crate::database::memory::InMemoryDatabase::new();
+                    crate::database::DatabaseManager::initialize(&database)
+                        .await
+                        .unwrap();
+                    super::$check(std::sync::Arc::new(database)).await;
+                }
+            )*
+        }
+    };
+}
+
+conformance_suite!(
+    upsert_reuses_ids_and_removal_leaves_no_ghosts,
+    bulk_operations_keep_stats_and_streams_consistent,
+    media_type_counts_follow_every_write,
+    prefix_queries_respect_component_boundaries,
+    directory_listing_filters_by_family_and_sorts_naturally,
+    read_sessions_order_filter_and_page,
+    music_categories_count_existing_files,
+    playlists_keep_ordered_entries,
+    concurrent_playlist_edits_lose_nothing,
+    source_content_owns_playlists_and_streams,
+    playlist_entry_info_stays_on_the_playlist,
+    root_availability_round_trips,
+    settings_round_trip,
+    cleanup_removes_unlisted_paths,
+    object_keys_and_ids_survive_rescans_and_rebuilds,
+    file_ids_are_derived_from_paths,
+    missing_files_keep_their_id_until_they_return,
+    directory_counts_ignore_paging_and_track_changes,
+    other_spellings_of_a_path_reach_the_same_record,
+);
@@ -0,0 +1,239 @@
+//! `media.hide_duplicates`: list one copy of files with the same content hash.
+//!
+//! Files whose [`MediaFile::content_hash`](super::MediaFile::content_hash)
+//! match are treated as copies. Only the copy in the best-ranked monitored
+//! directory is listed (highest `priority`, configuration order breaking
+//! ties); listings pass the rest as `hidden` to
+//! [`visit_sorted_files`](super::sort::visit_sorted_files). They stay indexed
+//! and can still be played by ID. [`duplicate_groups`] lists every set of
+//! copies for cleanup.
+
+use anyhow::Result;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::{DatabaseReadSession, MediaFileQuery, MediaFileView};
+use crate::config::MonitoredDirectoryConfig;
+
+/// Where a copy's directory ranks: priority first, then configuration order.
+type DirectoryRank = (Reverse<i32>, usize);
+
+/// Indexed files sharing one content hash.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct DuplicateGroup {
+    /// Hex-encoded, since JSON numbers cannot hold every `u64`.
+    pub content_hash: String,
+    /// The listed copy first, then the hidden ones in rank order.
+    pub files: Vec<DuplicateCopy>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct DuplicateCopy {
+    pub id: i64,
+    pub path: String,
+    pub size: u64,
+}
+
+/// IDs of every indexed file that has a better-ranked copy.
+pub fn hidden_duplicate_ids<S: DatabaseReadSession + ?Sized>(
+    session: &mut S,
+    directories: &[MonitoredDirectoryConfig],
+) -> Result<HashSet<i64>> {
+    Ok(duplicate_groups(session, directories)?
+        .into_iter()
+        .flat_map(|group| group.files.into_iter().skip(1).map(|copy| copy.id))
+        .collect())
+}
+
+/// Every group of two or more copies, ordered by the listed copy's path.
+pub fn duplicate_groups<S: DatabaseReadSession + ?Sized>(
+    session: &mut S,
+    directories: &[MonitoredDirectoryConfig],
+) -> Result<Vec<DuplicateGroup>> {
+    let roots = directories
+        .iter()
+        .map(|directory| {
+            let configured = PathBuf::from(&directory.path);
+            std::fs::canonicalize(&configured).unwrap_or(configured)
+        })
+        .collect::<Vec<_>>();
+    // Files outside every root rank after all of them.
+    let rank = |path: &Path| -> DirectoryRank {
+        roots
+            .iter()
+            .enumerate()
+            .filter(|(_, root)| path.starts_with(root))
+            .max_by_key(|(_, root)| root.components().count())
+            .map_or((Reverse(i32::MIN), usize::MAX), |(index, _)| {
+                (Reverse(directories[index].priority), index)
+            })
+    };
+
+    let mut copies: HashMap<u64, Vec<(DirectoryRank, DuplicateCopy)>> = HashMap::new();
+    session.visit_files(&MediaFileQuery::All, 0, usize::MAX, |file| {
+        // A missing copy must not hide one that is still on disk.
+        if file.missing_since_secs().is_some() {
+            return Ok(());
+        }
+        if let (Some(hash), Some(id)) = (file.content_hash(), file.id()) {
+            copies.entry(hash).or_default().push((
+                rank(Path::new(&*file.path())),
+                DuplicateCopy {
+                    id,
+                    path: file.path().into_owned(),
+                    size: file.size(),
+                },
+            ));
+        }
+        Ok(())
+    })?;
+
+    let mut groups = copies
+        .into_iter()
+        .filter(|(_, group)| group.len() > 1)
+        .map(|(hash, mut group)| {
+            group.sort_by(|(left_rank, left), (right_rank, right)| {
+                left_rank
+                    .cmp(right_rank)
+                    .then_with(|| left.path.cmp(&right.path))
+            });
+            DuplicateGroup {
+                content_hash: format!("{hash:016x}"),
+                files: group.into_iter().map(|(_, copy)| copy).collect(),
+            }
+        })
+        .collect::<Vec<_>>();
+    groups.sort_by(|left, right| left.files[0].path.cmp(&right.files[0].path));
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ValidationMode, WatchMode};
+    use crate::database::memory::InMemoryDatabase;
+    use crate::database::sort::{visit_sorted_files, SortSpec};
+    use crate::database::{DatabaseManager, MediaFile, MediaRepository};
+    use std::sync::Arc;
+
+    fn directory(path: &str, priority: i32) -> MonitoredDirectoryConfig {
+        MonitoredDirectoryConfig {
+            path: path.to_owned(),
+            recursive: true,
+            case_sensitive: None,
+            extensions: None,
+            exclude_patterns: None,
+            validation_mode: ValidationMode::Warn,
+            watch_mode: WatchMode::Auto,
+            merge_group: None,
+            priority,
+            media_types: None,
+            min_file_size: None,
+            min_file_age_seconds: None,
+        }
+    }
+
+    fn directory_query(path: &str) -> MediaFileQuery {
+        MediaFileQuery::Directory {
+            path: path.to_owned(),
+            mime_family: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn only_the_copy_in_the_best_ranked_directory_is_listed() {
+        let database = Arc::new(InMemoryDatabase::new());
+        database.initialize().await.unwrap();
+        let file = |path: &str, hash: Option<u64>| {
+            let mut file = MediaFile::new(PathBuf::from(path), 10, "video/mp4".to_owned());
+            file.content_hash = hash;
+            file
+        };
+        let ids = database
+            .bulk_store_media_files(&[
+                file("/archive/Films/Heat.mp4", Some(7)),
+                file("/films/Heat.mp4", Some(7)),
+                file("/films/Heat_copy.mp4", Some(7)),
+                file("/films/Ronin.mp4", Some(8)),
+                file("/films/Unhashed.mp4", None),
+            ])
+            .await
+            .unwrap();
+        // `/films` ranks first on priority although `/archive` is listed first.
+        let directories = vec![directory("/archive", 0), directory("/films", 5)];
+
+        let listing = database
+            .clone()
+            .read(move |session| {
+                let hidden = hidden_duplicate_ids(session, &directories)?;
+                let groups = duplicate_groups(session, &directories)?;
+                let mut page = Vec::new();
+                let films = visit_sorted_files(
+                    session,
+                    &directory_query("/films"),
+                    &SortSpec::default(),
+                    &hidden,
+                    1,
+                    10,
+                    |file| {
+                        page.push(file.filename().to_owned());
+                        Ok(())
+                    },
+                )?;
+                let archive = visit_sorted_files(
+                    session,
+                    &directory_query("/archive/Films"),
+                    &SortSpec::parse("dc:title"),
+                    &hidden,
+                    0,
+                    10,
+                    |_| Ok(()),
+                )?;
+                Ok((hidden, groups, films.matched, page.len(), archive.matched))
+            })
+            .await
+            .unwrap();
+        let (hidden, groups, matched, page, archive) = listing;
+        assert_eq!(hidden, HashSet::from([ids[0], ids[2]]));
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].content_hash, "0000000000000007");
+        assert_eq!(
+            groups[0]
+                .files
+                .iter()
+                .map(|copy| copy.id)
+                .collect::<Vec<_>>(),
+            vec![ids[1], ids[2], ids[0]]
+        );
+        assert_eq!(matched, 3);
+        assert_eq!(page, 2);
+        assert_eq!(archive, 0);
+    }
+
+    #[test]
+    fn the_partial_hash_matches_copies_and_reads_only_the_ends() {
+        let temp = tempfile::tempdir().unwrap();
+        let large = vec![1u8; 3 * 1024 * 1024];
+        let mut middle_changed = large.clone();
+        middle_changed[1536 * 1024] = 2;
+        let mut end_changed = large.clone();
+        *end_changed.last_mut().unwrap() = 2;
+        for (name, bytes) in [
+            ("a.mkv", &large),
+            ("b.mkv", &large),
+            ("middle.mkv", &middle_changed),
+            ("end.mkv", &end_changed),
+        ] {
+            std::fs::write(temp.path().join(name), bytes).unwrap();
+        }
+        std::fs::write(temp.path().join("small.mp3"), b"tiny").unwrap();
+        let hash =
+            |name: &str| crate::media::partial_content_hash(&temp.path().join(name)).unwrap();
+
+        assert_eq!(hash("a.mkv"), hash("b.mkv"));
+        assert_eq!(hash("a.mkv"), hash("middle.mkv"));
+        assert_ne!(hash("a.mkv"), hash("end.mkv"));
+        assert_ne!(hash("small.mp3"), hash("a.mkv"));
+    }
+}
@@ -0,0 +1,1523 @@
+//! InMemoryDatabase - a complete `DatabaseManager` kept in ordinary maps.
+//!
+//! Nothing is persisted: every start begins with an empty index that the
+//! initial scan fills. The backend exists for tests, demos, and as the
+//! reference implementation the conformance suite compares ReDB against, so
+//! it favours obviously-correct bookkeeping over clever indexing.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use futures_util::Stream;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{debug, info};
+
+use crate::platform::filesystem::{path_from_lossless_string, path_to_lossless_string};
+use crate::platform::DatabaseError;
+
+use super::{
+    canonical_media_path, contains_ignore_ascii_case, directory_key, is_stream_location,
+    media_file_id, media_object_key, mime_family, parent_directory_key, playlist_order,
+    DatabaseBackend, DatabaseHealth, DatabaseManager, DatabaseReadSession, DatabaseStats,
+    DirectoryView, FileFingerprint, FileLocation, HealthRepository, MediaDirectory, MediaFile,
+    MediaFileQuery, MediaFileView, MediaRepository, MediaTypeCounts, MusicCategory,
+    MusicCategoryType, Playlist, PlaylistEntryInfo, PlaylistRepository, RemovalSummary,
+    RootAvailability, SourceMediaEntry, StatsRepository, VisitSummary,
+};
+
+/// Complete database contents. Directory records are derived from
+/// `directory_files` after every write, which keeps pruning trivially correct.
+#[derive(Clone, Debug)]
+struct MemoryState {
+    files: BTreeMap<i64, MediaFile>,
+    paths: BTreeMap<String, i64>,
+    directory_files: BTreeMap<String, BTreeSet<i64>>,
+    directories: BTreeMap<String, u64>,
+    playlists: BTreeMap<i64, Playlist>,
+    playlist_entries: BTreeMap<(i64, u32), i64>,
+    /// `(playlist, file)` to what the playlist file said about that entry.
+    playlist_entry_info: BTreeMap<(i64, i64), PlaylistEntryInfo>,
+    playlist_sources: BTreeMap<i64, String>,
+    source_streams: BTreeMap<String, BTreeSet<i64>>,
+    roots: BTreeMap<String, RootAvailability>,
+    settings: BTreeMap<String, String>,
+    next_playlist_id: i64,
+    next_directory_id: u64,
+}
+
+impl Default for MemoryState {
+    fn default() -> Self {
+        Self {
+            files: BTreeMap::new(),
+            paths: BTreeMap::new(),
+            directory_files: BTreeMap::new(),
+            directories: BTreeMap::new(),
+            playlists: BTreeMap::new(),
+            playlist_entries: BTreeMap::new(),
+            playlist_entry_info: BTreeMap::new(),
+            playlist_sources: BTreeMap::new(),
+            source_streams: BTreeMap::new(),
+            roots: BTreeMap::new(),
+            settings: BTreeMap::new(),
+            next_playlist_id: 1,
+            next_directory_id: 1,
+        }
+    }
+}
+
+/// Timestamps are kept at the same whole-second precision ReDB archives.
+fn whole_seconds(time: SystemTime) -> SystemTime {
+    UNIX_EPOCH
+        + Duration::from_secs(
+            time.duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        )
+}
+
+fn child_prefix(directory: &str) -> String {
+    if directory.ends_with('/') {
+        directory.to_owned()
+    } else {
+        format!("{directory}/")
+    }
+}
+
+fn directory_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+fn is_within(path: &str, root: &str, child_prefix: &str) -> bool {
+    path == root || path.starts_with(child_prefix)
+}
+
+impl MemoryState {
+    /// Ids whose path hashes to `key`; a linear scan is fine for this backend.
+    fn object_key_ids(&self, key: u64) -> impl Iterator<Item = i64> + '_ {
+        self.paths
+            .iter()
+            .filter(move |(path, _)| media_object_key(path) == key)
+            .map(|(_, id)| *id)
+    }
+
+    fn allocate_playlist_id(&mut self) -> i64 {
+        let id = self.next_playlist_id;
+        self.next_playlist_id += 1;
+        id
+    }
+
+    /// Drop a record's path and directory membership without touching
+    /// playlist entries, which survive an in-place upsert.
+    fn detach_file(&mut self, id: i64) -> Option<MediaFile> {
+        let file = self.files.remove(&id)?;
+        let path = path_to_lossless_string(&file.path);
+        if self.paths.get(path.as_ref()) == Some(&id) {
+            self.paths.remove(path.as_ref());
+        }
+        let directory = directory_key(&file.path);
+        if let Some(members) = self.directory_files.get_mut(&directory) {
+            members.remove(&id);
+            if members.is_empty() {
+                self.directory_files.remove(&directory);
+            }
+        }
+        Some(file)
+    }
+
+    fn remove_file(&mut self, id: i64) -> Option<MediaFile> {
+        let file = self.detach_file(id)?;
+        self.playlist_entries.retain(|_, file_id| *file_id != id);
+        self.playlist_entry_info
+            .retain(|(_, file_id), _| *file_id != id);
+        self.source_streams.retain(|_, streams| {
+            streams.remove(&id);
+            !streams.is_empty()
+        });
+        Some(file)
+    }
+
+    /// Upsert one already-canonical record, reusing the ID of an existing
+    /// record at the same path.
+    fn upsert_file(&mut self, file: &MediaFile) -> Result<i64> {
+        let path = path_to_lossless_string(&file.path).into_owned();
+        let id = match self.paths.get(&path).copied().or(file.id) {
+            Some(id) => id,
+            None => media_file_id(&path, |id| Ok(self.files.contains_key(&id)))?,
+        };
+        self.detach_file(id);
+
+        let mut stored = file.to_owned_media_file();
+        stored.id = Some(id);
+        stored.created_at = whole_seconds(stored.created_at);
+        stored.updated_at = whole_seconds(stored.updated_at);
+        self.directory_files
+            .entry(directory_key(&stored.path))
+            .or_default()
+            .insert(id);
+        self.paths.insert(path, id);
+        self.files.insert(id, stored);
+        Ok(id)
+    }
+
+    /// Recompute the directory set as every ancestor of a directory that
+    /// holds files. Surviving directories keep their IDs.
+    fn sync_directories(&mut self) {
+        let mut live = BTreeSet::new();
+        for directory in self.directory_files.keys() {
+            let mut current = Some(directory.clone());
+            while let Some(path) = current {
+                if !live.insert(path.clone()) {
+                    break;
+                }
+                current = parent_directory_key(&path);
+            }
+        }
+        self.directories.retain(|path, _| live.contains(path));
+        for path in live {
+            if !self.directories.contains_key(&path) {
+                self.directories.insert(path, self.next_directory_id);
+                self.next_directory_id += 1;
+            }
+        }
+    }
+
+    /// Child directories of `parent`, paired with their IDs.
+    fn child_directories<'a>(&'a self, parent: &str) -> Vec<(&'a str, u64)> {
+        let prefix = child_prefix(parent);
+        self.directories
+            .range(prefix.clone()..)
+            .take_while(|(path, _)| path.starts_with(&prefix))
+            .filter(|(path, _)| parent_directory_key(path).as_deref() == Some(parent))
+            .map(|(path, id)| (path.as_str(), *id))
+            .collect()
+    }
+
+    /// Whether the subtree rooted at `directory` holds a file of the MIME
+    /// family (`"*"` matches any file), mirroring ReDB's recursive counters.
+    fn subtree_has_family(&self, directory: &str, family: &str) -> bool {
+        let prefix = child_prefix(directory);
+        self.directory_files
+            .range(directory.to_owned()..)
+            .take_while(|(path, _)| path.starts_with(directory))
+            .filter(|(path, _)| is_within(path, directory, &prefix))
+            .flat_map(|(_, ids)| ids)
+            .filter_map(|id| self.files.get(id))
+            .any(|file| family == "*" || mime_family(&file.mime_type) == family)
+    }
+
+    fn files_where(&self, predicate: impl Fn(&MediaFile) -> bool) -> Vec<MediaFile> {
+        self.files
+            .values()
+            .filter(|file| predicate(file))
+            .cloned()
+            .collect()
+    }
+
+    fn categories<K: Ord + ToString>(
+        &self,
+        key: impl Fn(&MediaFile) -> Option<K>,
+        category_type: MusicCategoryType,
+    ) -> Vec<MusicCategory> {
+        let mut counts = BTreeMap::new();
+        for file in self.files.values() {
+            if let Some(value) = key(file) {
+                *counts.entry(value).or_insert(0) += 1;
+            }
+        }
+        let named = category_type != MusicCategoryType::Year;
+        let mut categories = counts
+            .into_iter()
+            .map(|(value, count)| MusicCategory {
+                id: value.to_string(),
+                name: value.to_string(),
+                category_type: category_type.clone(),
+                count,
+            })
+            .collect::<Vec<_>>();
+        if named {
+            super::sort_categories(&mut categories);
+        }
+        categories
+    }
+
+    fn require_playlist(&self, playlist_id: i64) -> Result<()> {
+        if self.playlists.contains_key(&playlist_id) {
+            Ok(())
+        } else {
+            Err(anyhow!("playlist {playlist_id} not found"))
+        }
+    }
+
+    fn require_files(&self, entries: &[(i64, u32)]) -> Result<()> {
+        for (file_id, _) in entries {
+            if !self.files.contains_key(file_id) {
+                return Err(anyhow!("media file {file_id} not found"));
+            }
+        }
+        Ok(())
+    }
+
+    fn clear_playlist_entries(&mut self, playlist_id: i64) {
+        self.playlist_entries
+            .retain(|(owner, _), _| *owner != playlist_id);
+    }
+
+    fn clear_playlist_entry_info(&mut self, playlist_id: i64) {
+        self.playlist_entry_info
+            .retain(|(owner, _), _| *owner != playlist_id);
+    }
+
+    fn playlist_entry_list(&self, playlist_id: i64) -> Vec<(u32, i64)> {
+        self.playlist_entries
+            .range((playlist_id, 0)..=(playlist_id, u32::MAX))
+            .map(|((_, position), file_id)| (*position, *file_id))
+            .collect()
+    }
+
+    fn set_playlist_entries(&mut self, playlist_id: i64, entries: Vec<(u32, i64)>) {
+        self.clear_playlist_entries(playlist_id);
+        for (position, file_id) in entries {
+            self.playlist_entries
+                .insert((playlist_id, position), file_id);
+        }
+    }
+
+    fn source_playlist_ids(&self, source: &str) -> Vec<i64> {
+        self.playlist_sources
+            .iter()
+            .filter(|(_, owner)| owner.as_str() == source)
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Make `source` own exactly one playlist with the given entries,
+    /// reusing the smallest ID it already owned.
+    fn replace_source_playlist(
+        &mut self,
+        source: &str,
+        name: String,
+        entries: &[(i64, u32)],
+    ) -> i64 {
+        let existing = self.source_playlist_ids(source);
+        let playlist_id = match existing.iter().min() {
+            Some(id) => *id,
+            None => self.allocate_playlist_id(),
+        };
+        for duplicate_id in existing {
+            self.clear_playlist_entries(duplicate_id);
+            self.clear_playlist_entry_info(duplicate_id);
+            if duplicate_id != playlist_id {
+                self.playlists.remove(&duplicate_id);
+                self.playlist_sources.remove(&duplicate_id);
+            }
+        }
+        let now = whole_seconds(SystemTime::now());
+        self.playlists.insert(
+            playlist_id,
+            Playlist {
+                id: Some(playlist_id),
+                name,
+                description: None,
+                created_at: now,
+                updated_at: now,
+            },
+        );
+        self.playlist_sources.insert(playlist_id, source.to_owned());
+        for (file_id, position) in entries {
+            self.playlist_entries
+                .insert((playlist_id, *position), *file_id);
+        }
+        playlist_id
+    }
+
+    /// Returns the source's playlist ID and how many radio streams were
+    /// removed because nothing references them any more.
+    fn replace_source_content(
+        &mut self,
+        source: &str,
+        playlist_name: Option<String>,
+        entries: &[SourceMediaEntry],
+    ) -> Result<(Option<i64>, usize)> {
+        let mut resolved = Vec::with_capacity(entries.len());
+        let mut info = Vec::new();
+        let mut stream_ids = BTreeSet::new();
+        for entry in entries {
+            let path = path_to_lossless_string(&entry.location).into_owned();
+            let is_stream = is_stream_location(&path);
+            let file_id = if let Some(id) = self.paths.get(&path).copied() {
+                id
+            } else if is_stream {
+                let mut stream =
+                    MediaFile::new(entry.location.clone(), 0, "audio/radio".to_owned());
+                let title = entry.stream_title.clone().unwrap_or_else(|| path.clone());
+                stream.filename = title.clone();
+                stream.title = Some(title);
+                self.upsert_file(&stream)?
+            } else {
+                continue;
+            };
+            if is_stream {
+                stream_ids.insert(file_id);
+            }
+            resolved.push((file_id, entry.position));
+            if !entry.info.is_empty() {
+                info.push((file_id, entry.info.clone()));
+            }
+        }
+
+        let playlist_id = match playlist_name {
+            Some(name) => {
+                let playlist_id = self.replace_source_playlist(source, name, &resolved);
+                for (file_id, info) in info {
+                    self.playlist_entry_info
+                        .insert((playlist_id, file_id), info);
+                }
+                Some(playlist_id)
+            }
+            None => {
+                for playlist_id in self.source_playlist_ids(source) {
+                    self.clear_playlist_entries(playlist_id);
+                    self.clear_playlist_entry_info(playlist_id);
+                    self.playlists.remove(&playlist_id);
+                    self.playlist_sources.remove(&playlist_id);
+                }
+                None
+            }
+        };
+
+        let old_stream_ids = self.source_streams.remove(source).unwrap_or_default();
+        if !stream_ids.is_empty() {
+            self.source_streams.insert(source.to_owned(), stream_ids);
+        }
+        let mut removed = 0;
+        for file_id in old_stream_ids {
+            let has_owner = self
+                .source_streams
+                .values()
+                .any(|streams| streams.contains(&file_id));
+            let has_playlist = self.playlist_entries.values().any(|id| *id == file_id);
+            let is_radio = self
+                .files
+                .get(&file_id)
+                .is_some_and(|file| file.mime_type == "audio/radio");
+            if !has_owner && !has_playlist && is_radio && self.remove_file(file_id).is_some() {
+                removed += 1;
+            }
+        }
+        self.sync_directories();
+        Ok((playlist_id, removed))
+    }
+}
+
+/// In-memory database selected with `database.backend = "memory"`.
+#[derive(Debug, Default)]
+pub struct InMemoryDatabase {
+    state: RwLock<Arc<MemoryState>>,
+}
+
+impl InMemoryDatabase {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn snapshot(&self) -> Result<Arc<MemoryState>> {
+        self.state
+            .read()
+            .map(|state| Arc::clone(&state))
+            .map_err(|_| anyhow!("in-memory database lock is poisoned"))
+    }
+
+    /// Apply one write. Read sessions keep their snapshot because the state
+    /// is copied on write while they hold it. Operations validate before
+    /// mutating, so an error leaves the state untouched.
+    fn write<R>(&self, operation: impl FnOnce(&mut MemoryState) -> Result<R>) -> Result<R> {
+        let mut state = self
+            .state
+            .write()
+            .map_err(|_| anyhow!("in-memory database lock is poisoned"))?;
+        operation(Arc::make_mut(&mut state))
+    }
+
+    /// Remove every stored path that is not in the canonical `existing` set.
+    fn cleanup_missing_canonical(&self, existing: &HashSet<String>) -> Result<usize> {
+        let missing = self
+            .snapshot()?
+            .paths
+            .keys()
+            .filter(|path| !existing.contains(*path))
+            .cloned()
+            .collect::<Vec<_>>();
+        self.remove_canonical_paths(&missing)
+    }
+
+    fn remove_canonical_paths(&self, paths: &[String]) -> Result<usize> {
+        self.write(|state| {
+            let mut removed = 0;
+            for path in paths {
+                if let Some(id) = state.paths.get(path).copied() {
+                    if state.remove_file(id).is_some() {
+                        removed += 1;
+                    }
+                }
+            }
+            state.sync_directories();
+            debug!(target: crate::logging::targets::DB, "Bulk removed {} media files", removed);
+            Ok(removed)
+        })
+    }
+
+    fn store_files(&self, files: Vec<MediaFile>) -> Result<Vec<i64>> {
+        self.write(|state| {
+            let ids = files
+                .iter()
+                .map(|file| state.upsert_file(file))
+                .collect::<Result<Vec<_>>>()?;
+            state.sync_directories();
+            debug!(target: crate::logging::targets::DB, "Bulk stored {} media files", ids.len());
+            Ok(ids)
+        })
+    }
+}
+
+fn canonical_string(path: &Path) -> Result<String> {
+    Ok(path_to_lossless_string(&canonical_media_path(path)?).into_owned())
+}
+
+fn canonical_files(files: &[MediaFile]) -> Result<Vec<MediaFile>> {
+    files
+        .iter()
+        .map(|file| {
+            let mut file = file.clone();
+            file.path = canonical_media_path(&file.path)?;
+            Ok(file)
+        })
+        .collect()
+}
+
+#[async_trait]
+impl DatabaseBackend for InMemoryDatabase {
+    async fn open(_path: PathBuf, _cache_size_mb: usize) -> Result<Self> {
+        Ok(Self::new())
+    }
+}
+
+/// Borrowed directory record lent by an in-memory read session.
+pub struct MemoryDirectoryView<'a> {
+    id: u64,
+    path: &'a str,
+}
+
+impl DirectoryView for MemoryDirectoryView<'_> {
+    fn id(&self) -> u64 {
+        self.id
+    }
+
+    fn path(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self.path)
+    }
+
+    fn name(&self) -> &str {
+        directory_name(self.path)
+    }
+}
+
+/// Read session over an immutable snapshot of the database.
+pub struct InMemoryReadSession {
+    state: Arc<MemoryState>,
+}
+
+impl DatabaseReadSession for InMemoryReadSession {
+    type File<'a> = &'a MediaFile;
+    type Playlist<'a> = &'a Playlist;
+    type Directory<'a> = MemoryDirectoryView<'a>;
+
+    fn visit_files<F>(
+        &mut self,
+        query: &MediaFileQuery,
+        offset: usize,
+        limit: usize,
+        mut visitor: F,
+    ) -> Result<VisitSummary>
+    where
+        F: for<'a> FnMut(Self::File<'a>) -> Result<()>,
+    {
+        let state = &*self.state;
+        let mut summary = VisitSummary::default();
+        let ids_where = |predicate: &dyn Fn(&MediaFile) -> bool| {
+            state
+                .files
+                .values()
+                .filter(|file| predicate(file))
+                .filter_map(|file| file.id)
+                .collect::<Vec<_>>()
+        };
+
+        let ids: Vec<i64> = match query {
+            MediaFileQuery::All => state.files.keys().copied().collect(),
+            MediaFileQuery::Id(id) => vec![*id],
+            MediaFileQuery::Path(path) => state.paths.get(path).copied().into_iter().collect(),
+            MediaFileQuery::ObjectKey(key) => state.object_key_ids(*key).collect(),
+            MediaFileQuery::Directory { path, mime_family } => {
+                let mut files = state
+                    .directory_files
+                    .get(path)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|id| state.files.get(id))
+                    .filter(|file| {
+                        mime_family
+                            .as_deref()
+                            .is_none_or(|family| file.mime_type.starts_with(family))
+                    })
+                    .map(|file| {
+                        (
+                            crate::natural_sort::collation_key(&file.filename),
+                            file.id.unwrap_or_default(),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                files.sort();
+                files.into_iter().map(|(_, id)| id).collect()
+            }
+            MediaFileQuery::Artist(value) => {
+                ids_where(&|file| file.artist.as_deref() == Some(value))
+            }
+            MediaFileQuery::Album { album, artist } => ids_where(&|file| {
+                file.album.as_deref() == Some(album)
+                    && artist
+                        .as_deref()
+                        .is_none_or(|artist| file.artist.as_deref() == Some(artist))
+            }),
+            MediaFileQuery::Genre(value) => ids_where(&|file| file.genre.as_deref() == Some(value)),
+            MediaFileQuery::Year(value) => ids_where(&|file| file.year == Some(*value)),
+            MediaFileQuery::AlbumArtist(value) => {
+                ids_where(&|file| file.album_artist.as_deref() == Some(value))
+            }
+            MediaFileQuery::Playlist(playlist_id) => state
+                .playlist_entries
+                .range((*playlist_id, 0)..=(*playlist_id, u32::MAX))
+                .map(|(_, id)| *id)
+                .collect(),
+            MediaFileQuery::Filtered {
+                after_id,
+                mime_family,
+                text,
+            } => {
+                let first_id = after_id.unwrap_or(i64::MIN).saturating_add(1);
+                let needle = text.as_deref().map(str::to_lowercase);
+                for file in state.files.range(first_id..).map(|(_, file)| file) {
+                    if mime_family
+                        .as_deref()
+                        .is_some_and(|family| !file.mime_type.starts_with(family))
+                    {
+                        continue;
+                    }
+                    if let Some(needle) = needle.as_deref() {
+                        let matches_text = contains_ignore_ascii_case(&file.filename, needle)
+                            || [&file.title, &file.artist, &file.album]
+                                .into_iter()
+                                .flatten()
+                                .any(|value| contains_ignore_ascii_case(value, needle));
+                        if !matches_text {
+                            continue;
+                        }
+                    }
+                    summary.matched += 1;
+                    if summary.matched > offset {
+                        if summary.visited < limit {
+                            visitor(file)?;
+                            summary.visited += 1;
+                        }
+                        if summary.visited >= limit {
+                            break;
+                        }
+                    }
+                }
+                return Ok(summary);
+            }
+        };
+
+        for id in ids {
+            summary.matched += 1;
+            if summary.matched > offset && summary.visited < limit {
+                if let Some(file) = state.files.get(&id) {
+                    visitor(file)?;
+                    summary.visited += 1;
+                }
+            }
+        }
+        Ok(summary)
+    }
+
+    fn visit_direct_subdirectories<F>(
+        &mut self,
+        canonical_parent: &str,
+        mime_family: Option<&str>,
+        offset: usize,
+        limit: usize,
+        mut visitor: F,
+    ) -> Result<VisitSummary>
+    where
+        F: for<'a> FnMut(Self::Directory<'a>) -> Result<()>,
+    {
+        let state = &*self.state;
+        if !state.directories.contains_key(canonical_parent) {
+            return Ok(VisitSummary::default());
+        }
+        let family = mime_family.filter(|value| !value.is_empty()).unwrap_or("*");
+        let mut children = state.child_directories(canonical_parent);
+        children.sort_by_cached_key(|(path, id)| {
+            (
+                crate::natural_sort::collation_key(directory_name(path)),
+                *id,
+            )
+        });
+        let mut summary = VisitSummary::default();
+        for (path, id) in children {
+            if !state.subtree_has_family(path, family) {
+                continue;
+            }
+            summary.matched += 1;
+            if summary.matched <= offset || summary.visited >= limit {
+                continue;
+            }
+            visitor(MemoryDirectoryView { id, path })?;
+            summary.visited += 1;
+        }
+        Ok(summary)
+    }
+
+    fn visit_playlists<F>(
+        &mut self,
+        offset: usize,
+        limit: usize,
+        mut visitor: F,
+    ) -> Result<VisitSummary>
+    where
+        F: for<'a> FnMut(Self::Playlist<'a>) -> Result<()>,
+    {
+        let mut summary = VisitSummary::default();
+        for playlist in self.state.playlists.values() {
+            summary.matched += 1;
+            if summary.matched <= offset || summary.visited >= limit {
+                continue;
+            }
+            visitor(playlist)?;
+            summary.visited += 1;
+        }
+        Ok(summary)
+    }
+}
+
+#[async_trait]
+impl MediaRepository for InMemoryDatabase {
+    type ReadSession = InMemoryReadSession;
+
+    async fn read<R, F>(self: Arc<Self>, operation: F) -> Result<R>
+    where
+        Self: Sized + 'static,
+        R: Send + 'static,
+        F: FnOnce(&mut Self::ReadSession) -> Result<R> + Send + 'static,
+    {
+        let mut session = InMemoryReadSession {
+            state: self.snapshot()?,
+        };
+        operation(&mut session)
+    }
+
+    async fn store_media_file(&self, file: &MediaFile) -> Result<i64> {
+        self.store_files(canonical_files(std::slice::from_ref(file))?)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("media upsert returned no ID"))
+    }
+
+    fn stream_all_media_files(
+        &self,
+    ) -> Pin<Box<dyn Stream<Item = Result<MediaFile, DatabaseError>> + Send + '_>> {
+        let files = match self.snapshot() {
+            Ok(state) => state.files.values().cloned().map(Ok).collect::<Vec<_>>(),
+            Err(error) => vec![Err(DatabaseError::QueryFailed {
+                query: "snapshot".into(),
+                reason: error.to_string(),
+            })],
+        };
+        Box::pin(futures_util::stream::iter(files))
+    }
+
+    async fn remove_media_file(&self, path: &Path) -> Result<bool> {
+        Ok(self.bulk_remove_media_files(&[path.to_path_buf()]).await? > 0)
+    }
+
+    async fn update_media_file(&self, file: &MediaFile) -> Result<()> {
+        if file.id.is_none() {
+            return Err(anyhow!("Cannot update file without ID"));
+        }
+        self.store_media_file(file).await?;
+        Ok(())
+    }
+
+    async fn get_files_in_directory(&self, dir: &Path) -> Result<Vec<MediaFile>> {
+        let directory = canonical_string(dir)?;
+        let state = self.snapshot()?;
+        Ok(state
+            .directory_files
+            .get(&directory)
+            .into_iter()
+            .flatten()
+            .filter_map(|id| state.files.get(id).cloned())
+            .collect())
+    }
+
+    async fn get_directory_listing(
+        &self,
+        parent_path: &Path,
+        media_type_filter: &str,
+    ) -> Result<(Vec<MediaDirectory>, Vec<MediaFile>)> {
+        let raw_parent = canonical_string(parent_path)?;
+        let parent = if raw_parent.len() > 1 && raw_parent.ends_with(['/', '\\']) {
+            raw_parent[..raw_parent.len() - 1].to_owned()
+        } else {
+            raw_parent
+        };
+        let state = self.snapshot()?;
+        if !state.directories.contains_key(&parent) {
+            return Ok((Vec::new(), Vec::new()));
+        }
+
+        let mut files = state
+            .directory_files
+            .get(&parent)
+            .into_iter()
+            .flatten()
+            .filter_map(|id| state.files.get(id))
+            .filter(|file| file.mime_type.starts_with(media_type_filter))
+            .cloned()
+            .collect::<Vec<_>>();
+        let count_family = if media_type_filter.is_empty() {
+            "*"
+        } else {
+            media_type_filter
+        };
+        let mut directories = state
+            .child_directories(&parent)
+            .into_iter()
+            .filter(|(path, _)| state.subtree_has_family(path, count_family))
+            .map(|(path, _)| MediaDirectory {
+                path: path_from_lossless_string(path),
+                name: directory_name(path).to_owned(),
+            })
+            .collect::<Vec<_>>();
+
+        directories
+            .sort_by_cached_key(|directory| crate::natural_sort::collation_key(&directory.name));
+        files.sort_by(|a, b| match (a.track_number, b.track_number) {
+            (Some(ta), Some(tb)) if ta != tb => ta.cmp(&tb),
+            _ => {
+                let a_key = crate::natural_sort::collation_key(&a.filename);
+                let b_key = crate::natural_sort::collation_key(&b.filename);
+                a_key.cmp(&b_key)
+            }
+        });
+        Ok((directories, files))
+    }
+
+    async fn cleanup_missing_files(&self, existing_paths: &[PathBuf]) -> Result<usize> {
+        let existing = existing_paths
+            .iter()
+            .map(|path| canonical_string(path))
+            .collect::<Result<HashSet<_>>>()?;
+        self.cleanup_missing_canonical(&existing)
+    }
+
+    async fn get_file_by_path(&self, path: &Path) -> Result<Option<MediaFile>> {
+        let path = canonical_string(path)?;
+        let state = self.snapshot()?;
+        Ok(state
+            .paths
+            .get(&path)
+            .and_then(|id| state.files.get(id))
+            .cloned())
+    }
+
+    async fn get_file_by_id(&self, id: i64) -> Result<Option<MediaFile>> {
+        Ok(self.snapshot()?.files.get(&id).cloned())
+    }
+
+    async fn get_file_location_by_id(&self, id: i64) -> Result<Option<FileLocation>> {
+        Ok(self
+            .snapshot()?
+            .files
+            .get(&id)
+            .and_then(MediaFileView::to_file_location))
+    }
+
+    async fn get_file_location_by_object_key(&self, key: u64) -> Result<Option<FileLocation>> {
+        let state = self.snapshot()?;
+        let location = state.object_key_ids(key).find_map(|id| {
+            state
+                .files
+                .get(&id)
+                .and_then(MediaFileView::to_file_location)
+        });
+        Ok(location)
+    }
+
+    async fn load_file_fingerprints(&self) -> Result<Vec<FileFingerprint>> {
+        Ok(self
+            .snapshot()?
+            .files
+            .values()
+            .filter_map(MediaFileView::to_fingerprint)
+            .collect())
+    }
+
+    async fn load_file_fingerprints_under_root(&self, root: &Path) -> Result<Vec<FileFingerprint>> {
+        let root = canonical_string(root)?.trim_end_matches('/').to_owned();
+        let prefix = format!("{root}/");
+        let state = self.snapshot()?;
+        Ok(state
+            .paths
+            .range(root.clone()..)
+            .take_while(|(path, _)| path.starts_with(&root))
+            .filter(|(path, _)| is_within(path, &root, &prefix))
+            .filter_map(|(_, id)| state.files.get(id))
+            .filter_map(MediaFileView::to_fingerprint)
+            .collect())
+    }
+
+    async fn get_root_availability(&self, path: &Path) -> Result<Option<RootAvailability>> {
+        let key = canonical_string(path)?;
+        Ok(self.snapshot()?.roots.get(&key).cloned())
+    }
+
+    async fn list_root_availability(&self) -> Result<Vec<RootAvailability>> {
+        Ok(self.snapshot()?.roots.values().cloned().collect())
+    }
+
+    async fn set_root_availability(&self, state: &RootAvailability) -> Result<()> {
+        let mut root = state.clone();
+        root.path = canonical_media_path(&root.path)?;
+        let key = path_to_lossless_string(&root.path).into_owned();
+        self.write(|state| {
+            state.roots.insert(key, root);
+            Ok(())
+        })
+    }
+
+    async fn remove_root_availability(&self, path: &Path) -> Result<()> {
+        let key = canonical_string(path)?;
+        self.write(|state| {
+            state.roots.remove(&key);
+            Ok(())
+        })
+    }
+
+    async fn get_setting(&self, key: &str) -> Result<Option<String>> {
+        Ok(self.snapshot()?.settings.get(key).cloned())
+    }
+
+    async fn set_setting(&self, key: &str, value: &str) -> Result<()> {
+        self.write(|state| {
+            state.settings.insert(key.to_owned(), value.to_owned());
+            Ok(())
+        })
+    }
+
+    async fn remove_setting(&self, key: &str) -> Result<()> {
+        self.write(|state| {
+            state.settings.remove(key);
+            Ok(())
+        })
+    }
+
+    async fn get_artists(&self) -> Result<Vec<MusicCategory>> {
+        Ok(self
+            .snapshot()?
+            .categories(|file| file.artist.clone(), MusicCategoryType::Artist))
+    }
+
+    async fn get_albums(&self, artist: Option<&str>) -> Result<Vec<MusicCategory>> {
+        Ok(self.snapshot()?.categories(
+            |file| {
+                file.album
+                    .clone()
+                    .filter(|_| artist.is_none_or(|artist| file.artist.as_deref() == Some(artist)))
+            },
+            MusicCategoryType::Album,
+        ))
+    }
+
+    async fn get_genres(&self) -> Result<Vec<MusicCategory>> {
+        Ok(self
+            .snapshot()?
+            .categories(|file| file.genre.clone(), MusicCategoryType::Genre))
+    }
+
+    async fn get_years(&self) -> Result<Vec<MusicCategory>> {
+        Ok(self
+            .snapshot()?
+            .categories(|file| file.year, MusicCategoryType::Year))
+    }
+
+    async fn get_album_artists(&self) -> Result<Vec<MusicCategory>> {
+        Ok(self.snapshot()?.categories(
+            |file| file.album_artist.clone(),
+            MusicCategoryType::AlbumArtist,
+        ))
+    }
+
+    async fn get_music_by_artist(&self, artist: &str) -> Result<Vec<MediaFile>> {
+        Ok(self
+            .snapshot()?
+            .files_where(|file| file.artist.as_deref() == Some(artist)))
+    }
+
+    async fn get_music_by_album(
+        &self,
+        album: &str,
+        artist: Option<&str>,
+    ) -> Result<Vec<MediaFile>> {
+        Ok(self.snapshot()?.files_where(|file| {
+            file.album.as_deref() == Some(album)
+                && artist.is_none_or(|artist| file.artist.as_deref() == Some(artist))
+        }))
+    }
+
+    async fn get_music_by_genre(&self, genre: &str) -> Result<Vec<MediaFile>> {
+        Ok(self
+            .snapshot()?
+            .files_where(|file| file.genre.as_deref() == Some(genre)))
+    }
+
+    async fn get_music_by_year(&self, year: u32) -> Result<Vec<MediaFile>> {
+        Ok(self.snapshot()?.files_where(|file| file.year == Some(year)))
+    }
+
+    async fn get_music_by_album_artist(&self, album_artist: &str) -> Result<Vec<MediaFile>> {
+        Ok(self
+            .snapshot()?
+            .files_where(|file| file.album_artist.as_deref() == Some(album_artist)))
+    }
+
+    async fn get_files_by_paths(&self, paths: &[PathBuf]) -> Result<Vec<MediaFile>> {
+        let paths = paths
+            .iter()
+            .map(|path| canonical_string(path))
+            .collect::<Result<Vec<_>>>()?;
+        let state = self.snapshot()?;
+        Ok(paths
+            .iter()
+            .filter_map(|path| state.paths.get(path))
+            .filter_map(|id| state.files.get(id).cloned())
+            .collect())
+    }
+
+    async fn bulk_store_media_files(&self, files: &[MediaFile]) -> Result<Vec<i64>> {
+        self.store_files(canonical_files(files)?)
+    }
+
+    async fn bulk_store_canonical_media_files(&self, files: &[MediaFile]) -> Result<Vec<i64>> {
+        self.store_files(files.to_vec())
+    }
+
+    async fn bulk_update_media_files(&self, files: &[MediaFile]) -> Result<()> {
+        if files.iter().any(|file| file.id.is_none()) {
+            return Err(anyhow!("cannot update a media file without an ID"));
+        }
+        self.bulk_store_media_files(files).await?;
+        Ok(())
+    }
+
+    async fn bulk_update_canonical_media_files(&self, files: &[MediaFile]) -> Result<()> {
+        if files.iter().any(|file| file.id.is_none()) {
+            return Err(anyhow!("cannot update a media file without an ID"));
+        }
+        self.bulk_store_canonical_media_files(files).await?;
+        Ok(())
+    }
+
+    async fn bulk_remove_media_files(&self, paths: &[PathBuf]) -> Result<usize> {
+        let paths = paths
+            .iter()
+            .map(|path| canonical_string(path))
+            .collect::<Result<Vec<_>>>()?;
+        self.remove_canonical_paths(&paths)
+    }
+
+    async fn bulk_remove_canonical_media_files(&self, paths: &[PathBuf]) -> Result<usize> {
+        let paths = paths
+            .iter()
+            .map(|path| path_to_lossless_string(path).into_owned())
+            .collect::<Vec<_>>();
+        self.remove_canonical_paths(&paths)
+    }
+
+    async fn remove_media_under_path(&self, path: &Path) -> Result<RemovalSummary> {
+        let canonical = canonical_string(path)?;
+        let root = canonical.trim_end_matches('/').to_owned();
+        self.write(|state| {
+            let ids = if state.directories.contains_key(&root) {
+                let prefix = child_prefix(&root);
+                state
+                    .directory_files
+                    .range(root.clone()..)
+                    .take_while(|(directory, _)| directory.starts_with(&root))
+                    .filter(|(directory, _)| is_within(directory, &root, &prefix))
+                    .flat_map(|(_, ids)| ids.iter().copied())
+                    .collect::<Vec<_>>()
+            } else {
+                state.paths.get(&root).copied().into_iter().collect()
+            };
+
+            let mut summary = RemovalSummary::default();
+            for id in ids {
+                let Some(file) = state.remove_file(id) else {
+                    continue;
+                };
+                if let Some(parent) = file.path.parent() {
+                    summary.affected_parents.push(parent.to_path_buf());
+                }
+                summary.mime_families.insert(mime_family(&file.mime_type));
+                summary.removed_files += 1;
+            }
+            summary.affected_parents.sort();
+            summary.affected_parents.dedup();
+            state.sync_directories();
+            Ok(summary)
+        })
+    }
+
+    async fn get_files_with_path_prefix(&self, canonical_prefix: &str) -> Result<Vec<MediaFile>> {
+        let canonical = canonical_string(&path_from_lossless_string(canonical_prefix))?;
+        let root = canonical.trim_end_matches('/').to_owned();
+        let prefix = format!("{root}/");
+        let state = self.snapshot()?;
+        Ok(state
+            .paths
+            .range(root.clone()..)
+            .take_while(|(path, _)| path.starts_with(&root))
+            .filter(|(path, _)| is_within(path, &root, &prefix))
+            .filter_map(|(_, id)| state.files.get(id).cloned())
+            .collect())
+    }
+
+    async fn get_direct_subdirectories(
+        &self,
+        canonical_parent_path: &str,
+    ) -> Result<Vec<MediaDirectory>> {
+        let parent = canonical_string(&path_from_lossless_string(canonical_parent_path))?;
+        let state = self.snapshot()?;
+        let mut children = state.child_directories(&parent);
+        children.sort_by_key(|(_, id)| *id);
+        Ok(children
+            .into_iter()
+            .filter(|(path, _)| state.subtree_has_family(path, "*"))
+            .map(|(path, _)| MediaDirectory {
+                path: path_from_lossless_string(path),
+                name: directory_name(path).to_owned(),
+            })
+            .collect())
+    }
+
+    async fn batch_cleanup_missing_files(
+        &self,
+        existing_canonical_paths: &HashSet<String>,
+    ) -> Result<usize> {
+        self.cleanup_missing_canonical(existing_canonical_paths)
+    }
+
+    async fn database_native_cleanup(&self, existing_canonical_paths: &[String]) -> Result<usize> {
+        self.cleanup_missing_canonical(&existing_canonical_paths.iter().cloned().collect())
+    }
+
+    async fn get_filtered_direct_subdirectories(
+        &self,
+        canonical_parent_path: &str,
+        mime_filter: &str,
+    ) -> Result<Vec<MediaDirectory>> {
+        Ok(self
+            .get_directory_listing(
+                &path_from_lossless_string(canonical_parent_path),
+                mime_filter,
+            )
+            .await?
+            .0)
+    }
+}
+
+#[async_trait]
+impl PlaylistRepository for InMemoryDatabase {
+    async fn create_playlist(&self, name: &str, description: Option<&str>) -> Result<i64> {
+        let playlist_id = self.write(|state| {
+            let playlist_id = state.allocate_playlist_id();
+            let now = whole_seconds(SystemTime::now());
+            state.playlists.insert(
+                playlist_id,
+                Playlist {
+                    id: Some(playlist_id),
+                    name: name.to_owned(),
+                    description: description.map(str::to_owned),
+                    created_at: now,
+                    updated_at: now,
+                },
+            );
+            Ok(playlist_id)
+        })?;
+        info!(
+            target: crate::logging::targets::DB,
+            "Created playlist '{}' with ID {}",
+            name,
+            playlist_id
+        );
+        Ok(playlist_id)
+    }
+
+    async fn get_playlists(&self) -> Result<Vec<Playlist>> {
+        Ok(self.snapshot()?.playlists.values().cloned().collect())
+    }
+
+    async fn get_playlist(&self, playlist_id: i64) -> Result<Option<Playlist>> {
+        Ok(self.snapshot()?.playlists.get(&playlist_id).cloned())
+    }
+
+    async fn update_playlist(&self, playlist: &Playlist) -> Result<()> {
+        let Some(playlist_id) = playlist.id else {
+            return Err(anyhow!("Cannot update playlist without ID"));
+        };
+        self.write(|state| {
+            state.require_playlist(playlist_id)?;
+            let mut stored = playlist.clone();
+            stored.created_at = whole_seconds(stored.created_at);
+            stored.updated_at = whole_seconds(stored.updated_at);
+            state.playlists.insert(playlist_id, stored);
+            Ok(())
+        })
+    }
+
+    async fn delete_playlist(&self, playlist_id: i64) -> Result<bool> {
+        self.write(|state| {
+            let existed = state.playlists.remove(&playlist_id).is_some();
+            state.clear_playlist_entries(playlist_id);
+            state.clear_playlist_entry_info(playlist_id);
+            state.playlist_sources.remove(&playlist_id);
+            Ok(existed)
+        })
+    }
+
+    async fn set_playlist_source(&self, playlist_id: i64, source_path: &Path) -> Result<()> {
+        let source = canonical_string(source_path)?;
+        self.write(|state| {
+            state.require_playlist(playlist_id)?;
+            state.playlist_sources.insert(playlist_id, source);
+            Ok(())
+        })
+    }
+
+    async fn get_playlist_sources(&self) -> Result<HashMap<i64, PathBuf>> {
+        Ok(self
+            .snapshot()?
+            .playlist_sources
+            .iter()
+            .map(|(playlist_id, source)| (*playlist_id, path_from_lossless_string(source)))
+            .collect())
+    }
+
+    async fn replace_playlist_from_source(
+        &self,
+        source_path: &Path,
+        name: &str,
+        media_file_ids: &[(i64, u32)],
+    ) -> Result<i64> {
+        let source = canonical_string(source_path)?;
+        self.write(|state| {
+            state.require_files(media_file_ids)?;
+            Ok(state.replace_source_playlist(&source, name.to_owned(), media_file_ids))
+        })
+    }
+
+    async fn replace_source_content(
+        &self,
+        source_path: &Path,
+        playlist_name: Option<&str>,
+        entries: &[SourceMediaEntry],
+    ) -> Result<Option<i64>> {
+        let source = canonical_string(source_path)?;
+        let entries = entries
+            .iter()
+            .map(|entry| {
+                Ok(SourceMediaEntry {
+                    location: canonical_media_path(&entry.location)?,
+                    position: entry.position,
+                    stream_title: entry.stream_title.clone(),
+                    info: entry.info.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.write(|state| {
+            Ok(state
+                .replace_source_content(&source, playlist_name.map(str::to_owned), &entries)?
+                .0)
+        })
+    }
+
+    async fn remove_derived_content_by_source(&self, source_path: &Path) -> Result<usize> {
+        let source = canonical_string(source_path)?;
+        let prefix = format!("{}/", source.trim_end_matches('/'));
+        self.write(|state| {
+            let sources = state
+                .playlist_sources
+                .values()
+                .chain(state.source_streams.keys())
+                .filter(|owner| is_within(owner, &source, &prefix))
+                .cloned()
+                .collect::<BTreeSet<_>>();
+            let mut removed = 0;
+            for owner in sources {
+                removed += state.replace_source_content(&owner, None, &[])?.1 + 1;
+            }
+            Ok(removed)
+        })
+    }
+
+    async fn add_to_playlist(
+        &self,
+        playlist_id: i64,
+        media_file_id: i64,
+        position: Option<u32>,
+    ) -> Result<i64> {
+        self.write(|state| {
+            state.require_playlist(playlist_id)?;
+            state.require_files(&[(media_file_id, 0)])?;
+            let current = state.playlist_entry_list(playlist_id);
+            let entries = match position {
+                Some(position) => playlist_order::inserted(&current, position, media_file_id)?,
+                None => {
+                    let position = playlist_order::next_position(
+                        current.last().map(|(position, _)| *position),
+                    )?;
+                    current
+                        .into_iter()
+                        .chain([(position, media_file_id)])
+                        .collect()
+                }
+            };
+            state.set_playlist_entries(playlist_id, entries);
+            Ok(media_file_id)
+        })
+    }
+
+    async fn append_to_playlist(
+        &self,
+        playlist_id: i64,
+        media_file_ids: &[i64],
+    ) -> Result<Vec<i64>> {
+        self.write(|state| {
+            state.require_playlist(playlist_id)?;
+            for file_id in media_file_ids {
+                state.require_files(&[(*file_id, 0)])?;
+            }
+            let mut last = state
+                .playlist_entry_list(playlist_id)
+                .last()
+                .map(|(position, _)| *position);
+            for file_id in media_file_ids {
+                let position = playlist_order::next_position(last)?;
+                state
+                    .playlist_entries
+                    .insert((playlist_id, position), *file_id);
+                last = Some(position);
+            }
+            Ok(media_file_ids.to_vec())
+        })
+    }
+
+    async fn batch_add_to_playlist(
+        &self,
+        playlist_id: i64,
+        media_file_ids: &[(i64, u32)],
+    ) -> Result<Vec<i64>> {
+        self.write(|state| {
+            state.require_playlist(playlist_id)?;
+            state.require_files(media_file_ids)?;
+            for (file_id, position) in media_file_ids {
+                state
+                    .playlist_entries
+                    .insert((playlist_id, *position), *file_id);
+            }
+            Ok(media_file_ids.iter().map(|(id, _)| *id).collect())
+        })
+    }
+
+    async fn remove_from_playlist(&self, playlist_id: i64, media_file_id: i64) -> Result<bool> {
+        self.write(|state| {
+            state.require_playlist(playlist_id)?;
+            let key = state
+                .playlist_entries
+                .range((playlist_id, 0)..=(playlist_id, u32::MAX))
+                .find(|(_, file_id)| **file_id == media_file_id)
+                .map(|(key, _)| *key);
+            Ok(key
+                .and_then(|key| state.playlist_entries.remove(&key))
+                .is_some())
+        })
+    }
+
+    async fn get_playlist_tracks(&self, playlist_id: i64) -> Result<Vec<MediaFile>> {
+        let state = self.snapshot()?;
+        state.require_playlist(playlist_id)?;
+        state
+            .playlist_entries
+            .range((playlist_id, 0)..=(playlist_id, u32::MAX))
+            .map(|(_, file_id)| {
+                let mut file = state.files.get(file_id).cloned().ok_or_else(|| {
+                    anyhow!("playlist {playlist_id} references missing media file {file_id}")
+                })?;
+                if let Some(info) = state.playlist_entry_info.get(&(playlist_id, *file_id)) {
+                    info.apply_to(&mut file);
+                }
+                Ok(file)
+            })
+            .collect()
+    }
+
+    async fn get_playlist_entry_info(
+        &self,
+        playlist_id: i64,
+    ) -> Result<HashMap<i64, PlaylistEntryInfo>> {
+        let state = self.snapshot()?;
+        Ok(state
+            .playlist_entry_info
+            .range((playlist_id, i64::MIN)..=(playlist_id, i64::MAX))
+            .map(|((_, file_id), info)| (*file_id, info.clone()))
+            .collect())
+    }
+
+    async fn reorder_playlist(
+        &self,
+        playlist_id: i64,
+        track_positions: &[(i64, u32)],
+    ) -> Result<()> {
+        self.write(|state| {
+            state.require_playlist(playlist_id)?;
+            state.require_files(track_positions)?;
+            let entries = playlist_order::reordered(
+                &state.playlist_entry_list(playlist_id),
+                track_positions,
+            )?;
+            state.set_playlist_entries(playlist_id, entries);
+            Ok(())
+        })
+    }
+}
+
+#[async_trait]
+impl HealthRepository for InMemoryDatabase {
+    async fn check_and_repair(&self) -> Result<DatabaseHealth> {
+        self.rebuild_derived_indexes().await
+    }
+
+    async fn rebuild_derived_indexes(&self) -> Result<DatabaseHealth> {
+        self.write(|state| {
+            let MemoryState {
+                files,
+                playlists,
+                playlist_entries,
+                playlist_entry_info,
+                playlist_sources,
+                source_streams,
+                ..
+            } = state;
+            let before = playlist_entries.len() + playlist_sources.len();
+            playlist_entries.retain(|(playlist_id, _), file_id| {
+                playlists.contains_key(playlist_id) && files.contains_key(file_id)
+            });
+            playlist_sources.retain(|playlist_id, _| playlists.contains_key(playlist_id));
+            playlist_entry_info.retain(|(playlist_id, file_id), _| {
+                playlists.contains_key(playlist_id) && files.contains_key(file_id)
+            });
+            source_streams.retain(|_, streams| {
+                streams.retain(|id| files.contains_key(id));
+                !streams.is_empty()
+            });
+            let repaired = before != playlist_entries.len() + playlist_sources.len();
+            state.sync_directories();
+            Ok(DatabaseHealth {
+                is_healthy: true,
+                corruption_detected: repaired,
+                integrity_check_passed: true,
+                issues: Vec::new(),
+                repair_attempted: true,
+                repair_successful: true,
+                restored_from_backup: false,
+            })
+        })
+    }
+
+    async fn create_backup(&self, _backup_path: &Path) -> Result<()> {
+        Err(anyhow!(
+            "the in-memory database has no on-disk state to back up"
+        ))
+    }
+
+    async fn vacuum(&self) -> Result<bool> {
+        Ok(false)
+    }
+}
+
+#[async_trait]
+impl StatsRepository for InMemoryDatabase {
+    async fn get_stats(&self) -> Result<DatabaseStats> {
+        let state = self.snapshot()?;
+        let count_family = |family: &str| {
+            state
+                .files
+                .values()
+                .filter(|file| file.mime_type.starts_with(family))
+                .count()
+        };
+        Ok(DatabaseStats {
+            total_files: state.files.len(),
+            total_size: state.files.values().map(|file| file.size).sum(),
+            database_size: 0,
+            video_files: count_family("video/"),
+            audio_files: count_family("audio/"),
+            image_files: count_family("image/"),
+            playlists: state.playlists.len(),
+        })
+    }
+
+    async fn count_by_media_type(&self) -> Result<MediaTypeCounts> {
+        // Nothing here touches disk, so counting in place stays cheap.
+        let state = self.snapshot()?;
+        let mut counts = MediaTypeCounts::default();
+        for file in state.files.values() {
+            match mime_family(&file.mime_type).as_str() {
+                "video/" => counts.videos += 1,
+                "audio/" => counts.songs += 1,
+                "image/" => counts.pictures += 1,
+                _ => counts.other += 1,
+            }
+        }
+        Ok(counts)
+    }
+}
+
+#[async_trait]
+impl DatabaseManager for InMemoryDatabase {
+    fn backend_name(&self) -> &'static str {
+        "memory"
+    }
+
+    async fn initialize(&self) -> Result<()> {
+        info!(
+            target: crate::logging::targets::DB,
+            "InMemoryDatabase initialized; the index will not be persisted"
+        );
+        Ok(())
+    }
+}
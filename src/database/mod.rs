@@ -1,16 +1,144 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use futures_util::Stream;
 
-use std::collections::HashSet;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::time::{Duration, SystemTime};
 
+use crate::platform::filesystem::{path_from_lossless_string, path_to_lossless_string};
 use crate::platform::DatabaseError;
 
+pub mod duplicates;
+pub mod memory;
 pub mod playlist_formats;
+mod playlist_order;
 pub mod redb;
+pub mod smart_playlists;
+pub mod sort;
+
+pub use sort::{SortField, SortKey, SortSpec};
+
+#[cfg(test)]
+mod conformance;
+
+pub(crate) fn is_stream_location(raw: &str) -> bool {
+    raw.get(..7)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("http://"))
+        || raw
+            .get(..8)
+            .is_some_and(|scheme| scheme.eq_ignore_ascii_case("https://"))
+}
+
+/// Canonical key under which every backend stores a media path. Stream URLs
+/// are kept verbatim; filesystem paths go through the platform normalizer.
+/// Stored keys are the [`path_to_lossless_string`] spelling of this path, so
+/// filenames that are not valid UTF-8 keep their bytes.
+pub(crate) fn canonical_media_path(path: &Path) -> Result<PathBuf> {
+    if is_stream_location(&path.to_string_lossy()) {
+        return Ok(path.to_path_buf());
+    }
+    let normalizer = crate::platform::filesystem::create_platform_path_normalizer();
+    Ok(path_from_lossless_string(&normalizer.to_canonical(path)?))
+}
+
+/// Stable key of a stored (canonical) media path.
+///
+/// This key depends only on the path, so clients that remember it keep working
+/// across rescans, and new row ids are derived from it (see [`media_file_id`]).
+/// The hash is FNV-1a so it never changes between builds or platforms.
+pub fn media_object_key(path: &str) -> u64 {
+    fnv1a(path.as_bytes())
+}
+
+/// 64-bit FNV-1a, stable across builds and platforms.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    fnv1a_extend(0xcbf2_9ce4_8422_2325, bytes)
+}
+
+/// Continue an FNV-1a `hash` over more `bytes`.
+pub(crate) fn fnv1a_extend(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Largest row id handed out for a new path. Ids travel as JSON numbers to the
+/// web UI, so they must stay exact in an IEEE double.
+const MAX_MEDIA_FILE_ID: i64 = (1 << 53) - 1;
+
+/// Row id for a record stored at a path that is not indexed yet.
+///
+/// The id is the [`media_object_key`] of the canonical path folded into
+/// `1..=MAX_MEDIA_FILE_ID`, so dropping and rescanning a file, restarting, or
+/// switching backends yields the same id. When another record already holds
+/// that id (`taken`), the next free one is used. Records stored before ids were
+/// derived keep their sequential ids; only new paths get derived ones.
+pub(crate) fn media_file_id(path: &str, mut taken: impl FnMut(i64) -> Result<bool>) -> Result<i64> {
+    let mut id = (media_object_key(path) % MAX_MEDIA_FILE_ID as u64) as i64 + 1;
+    for _ in 0..1024 {
+        if !taken(id)? {
+            return Ok(id);
+        }
+        id = id % MAX_MEDIA_FILE_ID + 1;
+    }
+    Err(anyhow!(
+        "no free media file id near the one derived for {path}"
+    ))
+}
+
+/// Directory-index key for the folder containing `path`.
+fn directory_key(path: &Path) -> String {
+    path.parent()
+        .map(|p| {
+            let s = path_to_lossless_string(p).replace('\\', "/");
+            if cfg!(target_os = "windows") {
+                s.to_lowercase()
+            } else {
+                s
+            }
+        })
+        .unwrap_or_default()
+}
+
+/// Directory-index key of the parent of a directory key, if it has one.
+fn parent_directory_key(path: &str) -> Option<String> {
+    let parent = Path::new(path)
+        .parent()?
+        .to_string_lossy()
+        .replace('\\', "/");
+    if parent == path || parent.is_empty() {
+        None
+    } else {
+        Some(if cfg!(target_os = "windows") {
+            parent.to_lowercase()
+        } else {
+            parent
+        })
+    }
+}
+
+fn mime_family(mime: &str) -> String {
+    mime.split_once('/')
+        .map(|(v, _)| format!("{v}/"))
+        .unwrap_or_else(|| mime.to_string())
+}
+
+fn contains_ignore_ascii_case(value: &str, needle: &str) -> bool {
+    if needle.is_empty() {
+        return true;
+    }
+    if value.is_ascii() && needle.is_ascii() {
+        value
+            .as_bytes()
+            .windows(needle.len())
+            .any(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+    } else {
+        value.to_lowercase().contains(&needle.to_lowercase())
+    }
+}
 
 /// Represents a subdirectory in the media library.
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -32,6 +160,31 @@ pub struct SourceMediaEntry {
     pub location: PathBuf,
     pub position: u32,
     pub stream_title: Option<String>,
+    pub info: PlaylistEntryInfo,
+}
+
+/// What a playlist file says about one of its entries (`#EXTINF`). It belongs
+/// to that playlist only and stands in for tags the file itself lacks.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlaylistEntryInfo {
+    pub title: Option<String>,
+    pub duration: Option<Duration>,
+}
+
+impl PlaylistEntryInfo {
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.duration.is_none()
+    }
+
+    /// Fill in the title and duration `file` has none of.
+    pub fn apply_to(&self, file: &mut MediaFile) {
+        if file.title.is_none() {
+            file.title = self.title.clone();
+        }
+        if file.duration.is_none() {
+            file.duration = self.duration;
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
@@ -83,6 +236,17 @@ pub enum MusicCategoryType {
     Playlist,
 }
 
+/// Order named categories for display. Years are listed numerically and are
+/// left as they are.
+pub(crate) fn sort_categories(categories: &mut [MusicCategory]) {
+    categories.sort_by_cached_key(|category| {
+        (
+            crate::natural_sort::collation_key(&category.name),
+            category.name.clone(),
+        )
+    });
+}
+
 /// Enhanced MediaFile structure for database storage
 #[derive(Clone, Debug)]
 pub struct MediaFile {
@@ -100,6 +264,15 @@ pub struct MediaFile {
     pub track_number: Option<u32>,
     pub year: Option<u32>,
     pub album_artist: Option<String>,
+    /// Set from the `compilation`/`TCMP` tag of various-artists albums.
+    pub compilation: bool,
+    /// Hash of the size and the first and last MiB of the file, computed when
+    /// `media.hide_duplicates` is on. Equal hashes mark copies of one file.
+    pub content_hash: Option<u64>,
+    /// When the file was seen to disappear. Missing files keep their ID,
+    /// playlist entries and bookmarks but are left out of listings until they
+    /// come back, or removed once `media.deletion_grace_minutes` has passed.
+    pub missing_since: Option<SystemTime>,
     pub subtitle_available: bool,
     pub created_at: SystemTime,
     pub updated_at: SystemTime,
@@ -117,6 +290,8 @@ pub struct FileFingerprint {
     pub modified: SystemTime,
     pub created_at: SystemTime,
     pub subtitle_available: bool,
+    pub content_hash: Option<u64>,
+    pub missing_since: Option<SystemTime>,
 }
 
 /// Minimal owned state needed after a database session to serve one resource.
@@ -149,7 +324,7 @@ impl IndexSnapshot {
     pub fn from_view(view: &impl MediaFileView) -> Option<Self> {
         Some(Self {
             id: view.id()?,
-            path: view.path().to_owned(),
+            path: view.path().into_owned(),
             size: view.size(),
             mime_type: view.mime_type().to_owned(),
             artist: view.artist().map(str::to_owned),
@@ -165,8 +340,8 @@ impl MediaFileView for IndexSnapshot {
     fn id(&self) -> Option<i64> {
         Some(self.id)
     }
-    fn path(&self) -> &str {
-        &self.path
+    fn path(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.path)
     }
     fn filename(&self) -> &str {
         ""
@@ -207,6 +382,15 @@ impl MediaFileView for IndexSnapshot {
     fn album_artist(&self) -> Option<&str> {
         self.album_artist.as_deref()
     }
+    fn compilation(&self) -> bool {
+        false
+    }
+    fn content_hash(&self) -> Option<u64> {
+        None
+    }
+    fn missing_since_secs(&self) -> Option<u64> {
+        None
+    }
     fn subtitle_available(&self) -> bool {
         false
     }
@@ -243,6 +427,9 @@ impl MediaFile {
             track_number: None,
             year: None,
             album_artist: None,
+            compilation: false,
+            content_hash: None,
+            missing_since: None,
             subtitle_available: false,
             created_at: now,
             updated_at: now,
@@ -254,8 +441,8 @@ impl MediaFileView for MediaFile {
     fn id(&self) -> Option<i64> {
         self.id
     }
-    fn path(&self) -> &str {
-        self.path.to_str().unwrap_or_default()
+    fn path(&self) -> Cow<'_, str> {
+        path_to_lossless_string(&self.path)
     }
     fn filename(&self) -> &str {
         &self.filename
@@ -302,6 +489,20 @@ impl MediaFileView for MediaFile {
     fn album_artist(&self) -> Option<&str> {
         self.album_artist.as_deref()
     }
+    fn compilation(&self) -> bool {
+        self.compilation
+    }
+    fn content_hash(&self) -> Option<u64> {
+        self.content_hash
+    }
+    fn missing_since_secs(&self) -> Option<u64> {
+        self.missing_since.map(|since| {
+            since
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs()
+        })
+    }
     fn subtitle_available(&self) -> bool {
         self.subtitle_available
     }
@@ -325,7 +526,8 @@ impl MediaFileView for MediaFile {
 /// retain any returned string beyond the scoped read-session callback.
 pub trait MediaFileView {
     fn id(&self) -> Option<i64>;
-    fn path(&self) -> &str;
+    /// Stored key of the record, see [`canonical_media_path`].
+    fn path(&self) -> Cow<'_, str>;
     fn filename(&self) -> &str;
     fn size(&self) -> u64;
     fn modified_secs(&self) -> u64;
@@ -339,6 +541,9 @@ pub trait MediaFileView {
     fn track_number(&self) -> Option<u32>;
     fn year(&self) -> Option<u32>;
     fn album_artist(&self) -> Option<&str>;
+    fn compilation(&self) -> bool;
+    fn content_hash(&self) -> Option<u64>;
+    fn missing_since_secs(&self) -> Option<u64>;
     fn subtitle_available(&self) -> bool;
     fn created_at_secs(&self) -> u64;
     fn updated_at_secs(&self) -> u64;
@@ -346,19 +551,23 @@ pub trait MediaFileView {
     fn to_fingerprint(&self) -> Option<FileFingerprint> {
         Some(FileFingerprint {
             id: self.id()?,
-            path: PathBuf::from(self.path()),
+            path: path_from_lossless_string(&self.path()),
             size: self.size(),
             modified: SystemTime::UNIX_EPOCH
                 + Duration::new(self.modified_secs(), self.modified_nanos().min(999_999_999)),
             created_at: SystemTime::UNIX_EPOCH + Duration::from_secs(self.created_at_secs()),
             subtitle_available: self.subtitle_available(),
+            content_hash: self.content_hash(),
+            missing_since: self
+                .missing_since_secs()
+                .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs)),
         })
     }
 
     fn to_file_location(&self) -> Option<FileLocation> {
         Some(FileLocation {
             id: self.id()?,
-            path: PathBuf::from(self.path()),
+            path: path_from_lossless_string(&self.path()),
             filename: self.filename().to_owned(),
             title: self.title().map(str::to_owned),
             mime_type: self.mime_type().to_owned(),
@@ -370,7 +579,7 @@ pub trait MediaFileView {
     fn to_owned_media_file(&self) -> MediaFile {
         MediaFile {
             id: self.id(),
-            path: PathBuf::from(self.path()),
+            path: path_from_lossless_string(&self.path()),
             filename: self.filename().to_owned(),
             size: self.size(),
             modified: SystemTime::UNIX_EPOCH
@@ -384,6 +593,11 @@ pub trait MediaFileView {
             track_number: self.track_number(),
             year: self.year(),
             album_artist: self.album_artist().map(str::to_owned),
+            compilation: self.compilation(),
+            content_hash: self.content_hash(),
+            missing_since: self
+                .missing_since_secs()
+                .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs)),
             subtitle_available: self.subtitle_available(),
             created_at: SystemTime::UNIX_EPOCH + Duration::from_secs(self.created_at_secs()),
             updated_at: SystemTime::UNIX_EPOCH + Duration::from_secs(self.updated_at_secs()),
@@ -402,7 +616,7 @@ pub trait PlaylistView {
 /// Borrowed directory record. ReDB implements this directly over its table value.
 pub trait DirectoryView {
     fn id(&self) -> u64;
-    fn path(&self) -> &str;
+    fn path(&self) -> Cow<'_, str>;
     fn name(&self) -> &str;
 }
 
@@ -411,13 +625,126 @@ impl DirectoryView for MediaDirectory {
         0
     }
 
-    fn path(&self) -> &str {
-        self.path.to_str().unwrap_or_default()
+    fn path(&self) -> Cow<'_, str> {
+        path_to_lossless_string(&self.path)
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Lets backends that keep owned records lend them directly as session views.
+impl<T: MediaFileView + ?Sized> MediaFileView for &T {
+    fn id(&self) -> Option<i64> {
+        (**self).id()
+    }
+    fn path(&self) -> Cow<'_, str> {
+        (**self).path()
+    }
+    fn filename(&self) -> &str {
+        (**self).filename()
+    }
+    fn size(&self) -> u64 {
+        (**self).size()
+    }
+    fn modified_secs(&self) -> u64 {
+        (**self).modified_secs()
+    }
+    fn modified_nanos(&self) -> u32 {
+        (**self).modified_nanos()
+    }
+    fn mime_type(&self) -> &str {
+        (**self).mime_type()
+    }
+    fn duration_secs(&self) -> Option<f64> {
+        (**self).duration_secs()
+    }
+    fn title(&self) -> Option<&str> {
+        (**self).title()
+    }
+    fn artist(&self) -> Option<&str> {
+        (**self).artist()
+    }
+    fn album(&self) -> Option<&str> {
+        (**self).album()
+    }
+    fn genre(&self) -> Option<&str> {
+        (**self).genre()
+    }
+    fn track_number(&self) -> Option<u32> {
+        (**self).track_number()
+    }
+    fn year(&self) -> Option<u32> {
+        (**self).year()
+    }
+    fn album_artist(&self) -> Option<&str> {
+        (**self).album_artist()
+    }
+    fn compilation(&self) -> bool {
+        (**self).compilation()
+    }
+    fn content_hash(&self) -> Option<u64> {
+        (**self).content_hash()
+    }
+    fn missing_since_secs(&self) -> Option<u64> {
+        (**self).missing_since_secs()
+    }
+    fn subtitle_available(&self) -> bool {
+        (**self).subtitle_available()
+    }
+    fn created_at_secs(&self) -> u64 {
+        (**self).created_at_secs()
+    }
+    fn updated_at_secs(&self) -> u64 {
+        (**self).updated_at_secs()
+    }
+}
+
+impl PlaylistView for Playlist {
+    fn id(&self) -> Option<i64> {
+        self.id
     }
 
     fn name(&self) -> &str {
         &self.name
     }
+
+    fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    fn created_at_secs(&self) -> u64 {
+        self.created_at
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+    }
+
+    fn updated_at_secs(&self) -> u64 {
+        self.updated_at
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+    }
+}
+
+impl<T: PlaylistView + ?Sized> PlaylistView for &T {
+    fn id(&self) -> Option<i64> {
+        (**self).id()
+    }
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+    fn description(&self) -> Option<&str> {
+        (**self).description()
+    }
+    fn created_at_secs(&self) -> u64 {
+        (**self).created_at_secs()
+    }
+    fn updated_at_secs(&self) -> u64 {
+        (**self).updated_at_secs()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -425,6 +752,8 @@ pub enum MediaFileQuery {
     All,
     Id(i64),
     Path(String),
+    /// Records whose stored path hashes to this [`media_object_key`].
+    ObjectKey(u64),
     Directory {
         path: String,
         mime_family: Option<String>,
@@ -454,6 +783,32 @@ pub struct VisitSummary {
     pub visited: usize,
 }
 
+/// Fold what a listing shows of `file` into a container `revision`.
+pub(crate) fn file_revision<F: MediaFileView>(revision: u64, file: &F) -> u64 {
+    let revision = fnv1a_extend(revision, file.path().as_bytes());
+    [file.size(), file.modified_secs(), file.updated_at_secs()]
+        .iter()
+        .fold(revision, |revision, value| {
+            fnv1a_extend(revision, &value.to_le_bytes())
+        })
+}
+
+/// What one directory lists, counted apart from any page of it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DirectoryEntryCount {
+    pub directories: usize,
+    pub files: usize,
+    /// Changes when a listed subdirectory or file is added, removed or
+    /// updated, and only then.
+    pub revision: u64,
+}
+
+impl DirectoryEntryCount {
+    pub fn total(&self) -> usize {
+        self.directories + self.files
+    }
+}
+
 /// Backend-owned read transaction with lending record views.
 pub trait DatabaseReadSession {
     type File<'a>: MediaFileView
@@ -495,6 +850,44 @@ pub trait DatabaseReadSession {
     ) -> Result<VisitSummary>
     where
         F: for<'a> FnMut(Self::Playlist<'a>) -> Result<()>;
+
+    /// Count the direct subdirectories and files of a directory holding
+    /// `mime_family`, leaving out `hidden` files. Browse reports this as
+    /// `TotalMatches` whatever page it returns.
+    fn count_directory_entries(
+        &mut self,
+        canonical_parent: &str,
+        mime_family: Option<&str>,
+        hidden: &HashSet<i64>,
+    ) -> Result<DirectoryEntryCount> {
+        let mut count = DirectoryEntryCount::default();
+        let mut revision = fnv1a(canonical_parent.as_bytes());
+        self.visit_direct_subdirectories(
+            canonical_parent,
+            mime_family,
+            0,
+            usize::MAX,
+            |directory| {
+                count.directories += 1;
+                revision = fnv1a_extend(revision, directory.path().as_bytes());
+                Ok(())
+            },
+        )?;
+        let query = MediaFileQuery::Directory {
+            path: canonical_parent.to_owned(),
+            mime_family: mime_family.map(str::to_owned),
+        };
+        self.visit_files(&query, 0, usize::MAX, |file| {
+            if file.id().is_some_and(|id| hidden.contains(&id)) {
+                return Ok(());
+            }
+            count.files += 1;
+            revision = file_revision(revision, &file);
+            Ok(())
+        })?;
+        count.revision = revision;
+        Ok(count)
+    }
 }
 
 /// Media-library storage and query operations implemented by a database backend.
@@ -585,6 +978,9 @@ pub trait MediaRepository: Send + Sync {
     /// Load only the fields required by media streaming.
     async fn get_file_location_by_id(&self, id: i64) -> Result<Option<FileLocation>>;
 
+    /// Streaming fields of the file whose path hashes to `key`.
+    async fn get_file_location_by_object_key(&self, key: u64) -> Result<Option<FileLocation>>;
+
     /// Load compact scanner comparison records instead of complete media metadata.
     async fn load_file_fingerprints(&self) -> Result<Vec<FileFingerprint>>;
 
@@ -599,6 +995,13 @@ pub trait MediaRepository: Send + Sync {
 
     async fn remove_root_availability(&self, path: &Path) -> Result<()>;
 
+    /// Read a persisted server setting, such as the startup scan progress.
+    async fn get_setting(&self, key: &str) -> Result<Option<String>>;
+
+    async fn set_setting(&self, key: &str, value: &str) -> Result<()>;
+
+    async fn remove_setting(&self, key: &str) -> Result<()>;
+
     // Music categorization methods
     /// Get all unique artists
     async fn get_artists(&self) -> Result<Vec<MusicCategory>>;
@@ -717,6 +1120,10 @@ pub trait PlaylistRepository: Send + Sync {
     /// Mark a playlist as derived from an on-disk source file.
     async fn set_playlist_source(&self, playlist_id: i64, source_path: &Path) -> Result<()>;
 
+    /// Canonical source file of every playlist imported from one. Playlists
+    /// missing from the map were created by users.
+    async fn get_playlist_sources(&self) -> Result<HashMap<i64, PathBuf>>;
+
     /// Atomically create or replace the playlist derived from one source.
     async fn replace_playlist_from_source(
         &self,
@@ -737,7 +1144,8 @@ pub trait PlaylistRepository: Send + Sync {
     /// Delete playlists/radio records derived from an on-disk source file.
     async fn remove_derived_content_by_source(&self, source_path: &Path) -> Result<usize>;
 
-    /// Add a track to a playlist
+    /// Add a track to a playlist. `None` appends it; `Some(position)` inserts
+    /// it there and moves later entries up by one.
     async fn add_to_playlist(
         &self,
         playlist_id: i64,
@@ -745,6 +1153,13 @@ pub trait PlaylistRepository: Send + Sync {
         position: Option<u32>,
     ) -> Result<i64>;
 
+    /// Append tracks after the current last entry, in one transaction.
+    async fn append_to_playlist(
+        &self,
+        playlist_id: i64,
+        media_file_ids: &[i64],
+    ) -> Result<Vec<i64>>;
+
     /// Add multiple tracks to a playlist in a single transaction (batch operation)
     async fn batch_add_to_playlist(
         &self,
@@ -755,10 +1170,18 @@ pub trait PlaylistRepository: Send + Sync {
     /// Remove a track from a playlist
     async fn remove_from_playlist(&self, playlist_id: i64, media_file_id: i64) -> Result<bool>;
 
-    /// Get all tracks in a playlist
+    /// Get all tracks in a playlist, with their [`PlaylistEntryInfo`] applied.
     async fn get_playlist_tracks(&self, playlist_id: i64) -> Result<Vec<MediaFile>>;
 
-    /// Reorder tracks in a playlist
+    /// [`PlaylistEntryInfo`] of a playlist's entries, by media file ID.
+    async fn get_playlist_entry_info(
+        &self,
+        playlist_id: i64,
+    ) -> Result<HashMap<i64, PlaylistEntryInfo>>;
+
+    /// Reorder tracks in a playlist. Tracks no longer in the playlist are
+    /// ignored and entries not listed keep their order after the listed ones,
+    /// so a reorder based on a stale listing never loses a concurrent edit.
     async fn reorder_playlist(
         &self,
         playlist_id: i64,
@@ -779,6 +1202,10 @@ pub trait HealthRepository: Send + Sync {
 #[async_trait]
 pub trait StatsRepository: Send + Sync {
     async fn get_stats(&self) -> Result<DatabaseStats>;
+
+    /// Indexed files per media type, from counters kept current on every
+    /// write rather than a pass over the library.
+    async fn count_by_media_type(&self) -> Result<MediaTypeCounts>;
 }
 
 /// Aggregate database capability used by the application.
@@ -786,6 +1213,9 @@ pub trait StatsRepository: Send + Sync {
 pub trait DatabaseManager:
     MediaRepository + PlaylistRepository + HealthRepository + StatsRepository + Send + Sync
 {
+    /// Short name of the storage engine, used in logs and diagnostics.
+    fn backend_name(&self) -> &'static str;
+
     /// Initialize the database and create tables if needed.
     async fn initialize(&self) -> Result<()>;
 
@@ -796,7 +1226,7 @@ pub trait DatabaseManager:
         &self,
         file_path: &Path,
         playlist_name: Option<String>,
-    ) -> Result<i64> {
+    ) -> Result<playlist_formats::PlaylistImport> {
         playlist_formats::PlaylistFileManager::import_playlist(self, file_path, playlist_name).await
     }
 
@@ -806,12 +1236,14 @@ pub trait DatabaseManager:
         playlist_id: i64,
         output_path: &Path,
         format: playlist_formats::PlaylistFormat,
+        options: &playlist_formats::PlaylistExportOptions,
     ) -> Result<()> {
         playlist_formats::PlaylistFileManager::export_playlist(
             self,
             playlist_id,
             output_path,
             format,
+            options,
         )
         .await
     }
@@ -833,10 +1265,19 @@ pub trait DatabaseManager:
 #[async_trait]
 pub trait DatabaseBackend: DatabaseManager + Sized + 'static {
     async fn open(path: PathBuf, cache_size_mb: usize) -> Result<Self>;
-    fn backend_name() -> &'static str;
 }
 
-#[derive(Debug)]
+/// Indexed files per media type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct MediaTypeCounts {
+    pub videos: u64,
+    pub songs: u64,
+    pub pictures: u64,
+    /// Files outside the three families, e.g. ISO images.
+    pub other: u64,
+}
+
+#[derive(Debug, serde::Serialize)]
 pub struct DatabaseStats {
     pub total_files: usize,
     pub total_size: u64,
@@ -855,6 +1296,28 @@ pub struct DatabaseHealth {
     pub issues: Vec<DatabaseIssue>,
     pub repair_attempted: bool,
     pub repair_successful: bool,
+    /// Startup replaced the database with a backup, which misses whatever
+    /// changed since the backup was taken.
+    pub restored_from_backup: bool,
+}
+
+impl DatabaseHealth {
+    /// The check found problems and repair did not fix them, so the
+    /// database cannot be trusted to serve requests.
+    pub fn is_unrecoverable(&self) -> bool {
+        !self.is_healthy && !self.repair_successful
+    }
+
+    /// The first critical issue, when repair was attempted and failed.
+    /// Startup refuses to serve a database in this state.
+    pub fn unrepaired_critical_issue(&self) -> Option<&DatabaseIssue> {
+        if !self.repair_attempted || self.repair_successful {
+            return None;
+        }
+        self.issues
+            .iter()
+            .find(|issue| matches!(issue.severity, IssueSeverity::Critical))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -891,4 +1354,9 @@ mod repository_contract_tests {
     fn redb_implements_every_repository_capability() {
         assert_composed_manager::<redb::RedbDatabase>();
     }
+
+    #[test]
+    fn memory_implements_every_repository_capability() {
+        assert_composed_manager::<memory::InMemoryDatabase>();
+    }
 }
@@ -3,13 +3,42 @@ use std::path::{Path, PathBuf};
 use tokio::io::AsyncReadExt;
 use tracing::{debug, warn};
 
-use crate::database::{DatabaseManager, MediaFile, Playlist, SourceMediaEntry};
+use crate::database::{DatabaseManager, MediaFile, Playlist, PlaylistEntryInfo, SourceMediaEntry};
+use crate::platform::filesystem::path_from_lossless_string;
 
 const MAX_PLAYLIST_BYTES: u64 = 16 * 1024 * 1024;
 const MAX_PLAYLIST_LINE_BYTES: usize = 64 * 1024;
 const MAX_PLAYLIST_ENTRIES: usize = 100_000;
 
-async fn read_playlist_text(path: &Path) -> Result<String> {
+/// Setting prefix for the content hash a playlist file was last synced from,
+/// followed by the file's canonical path.
+const PLAYLIST_HASH_SETTING: &str = "playlist_hash:";
+
+/// File in a playlist mirror directory naming, one per line, the `.m3u8`
+/// files [`PlaylistFileManager::mirror_playlists`] wrote there. Only those
+/// are ever replaced or removed by the mirror, and playlist scans skip them
+/// so mirrored files are never imported back. Other playlists in the
+/// directory belong to the user.
+const MIRROR_MANIFEST: &str = ".vuio-mirror";
+
+/// Names of the files the mirror wrote to `directory`; empty when it is not
+/// a mirror directory.
+async fn mirrored_files(directory: &Path) -> std::collections::HashSet<std::ffi::OsString> {
+    tokio::fs::read_to_string(directory.join(MIRROR_MANIFEST))
+        .await
+        .map(|manifest| {
+            manifest
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(std::ffi::OsString::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The raw bytes of a playlist file, refused before reading when it is
+/// larger than [`MAX_PLAYLIST_BYTES`].
+async fn read_playlist_bytes(path: &Path) -> Result<Vec<u8>> {
     let metadata = tokio::fs::metadata(path).await?;
     if metadata.len() > MAX_PLAYLIST_BYTES {
         return Err(anyhow!(
@@ -28,6 +57,11 @@ async fn read_playlist_text(path: &Path) -> Result<String> {
             MAX_PLAYLIST_BYTES
         ));
     }
+    Ok(bytes)
+}
+
+async fn read_playlist_text(path: &Path) -> Result<String> {
+    let bytes = read_playlist_bytes(path).await?;
     if bytes
         .split_inclusive(|byte| *byte == b'\n')
         .any(|line| line.len() > MAX_PLAYLIST_LINE_BYTES)
@@ -37,14 +71,177 @@ async fn read_playlist_text(path: &Path) -> Result<String> {
             MAX_PLAYLIST_LINE_BYTES
         ));
     }
-    String::from_utf8(bytes).map_err(Into::into)
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(&bytes);
+    if PlaylistFormat::from_extension(path) == Some(PlaylistFormat::XSPF)
+        && xml_declares_latin1(bytes)
+    {
+        return Ok(bytes.iter().map(|&byte| char::from(byte)).collect());
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Ok(text.to_owned()),
+        // `.m3u8` is UTF-8 by definition; a decode failure means a damaged file.
+        Err(error) if PlaylistFormat::from_extension(path) == Some(PlaylistFormat::M3U8) => {
+            Err(anyhow!("playlist is not valid UTF-8: {error}"))
+        }
+        // Legacy `.m3u`/`.pls` files are commonly written in Latin-1.
+        Err(_) => Ok(bytes.iter().map(|&byte| char::from(byte)).collect()),
+    }
+}
+
+/// Whether an XML declaration names ISO-8859-1 as the document encoding.
+fn xml_declares_latin1(bytes: &[u8]) -> bool {
+    let Some(declaration) = bytes
+        .strip_prefix(b"<?xml")
+        .and_then(|rest| rest.split(|byte| *byte == b'>').next())
+    else {
+        return false;
+    };
+    let declaration = String::from_utf8_lossy(declaration).to_ascii_lowercase();
+    declaration.contains("iso-8859-1") || declaration.contains("latin1")
+}
+
+/// One playlist entry with the `#EXTINF` metadata that preceded it.
+#[derive(Debug, Clone, PartialEq)]
+struct PlaylistEntry {
+    location: String,
+    duration_seconds: Option<i64>,
+    title: Option<String>,
+}
+
+/// Parse simple and extended M3U. `#EXTINF:<secs>,<title>` applies to the
+/// next non-comment line; other directives are ignored.
+fn parse_m3u(content: &str) -> Vec<PlaylistEntry> {
+    let mut entries = Vec::new();
+    let mut pending_info: Option<(Option<i64>, Option<String>)> = None;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(info) = line.strip_prefix("#EXTINF:") {
+            let (duration, title) = info.split_once(',').unwrap_or((info, ""));
+            // Attributes such as `tvg-id="..."` may follow the duration.
+            let duration = duration
+                .split_whitespace()
+                .next()
+                .and_then(|value| value.parse::<f64>().ok())
+                .filter(|value| *value >= 0.0)
+                .map(|value| value.round() as i64);
+            let title = Some(title.trim())
+                .filter(|title| !title.is_empty())
+                .map(str::to_owned);
+            pending_info = Some((duration, title));
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+        let (duration_seconds, title) = pending_info.take().unwrap_or((None, None));
+        entries.push(PlaylistEntry {
+            location: line.to_owned(),
+            duration_seconds,
+            title,
+        });
+    }
+    entries
+}
+
+/// Parse the `<track>` elements of an XSPF playlist in order. `location`,
+/// `title` and `duration` (milliseconds) are read; tracks without a location
+/// are skipped.
+fn parse_xspf(content: &str) -> Result<Vec<PlaylistEntry>> {
+    use quick_xml::{events::Event, Reader};
+
+    let mut reader = Reader::from_str(content);
+    let mut entries = Vec::new();
+    let mut track: Option<PlaylistEntry> = None;
+    let mut field: Option<String> = None;
+    let mut text = String::new();
+    loop {
+        match reader
+            .read_event()
+            .map_err(|error| anyhow!("invalid XSPF playlist: {error}"))?
+        {
+            Event::Start(element) => {
+                let name = String::from_utf8_lossy(element.local_name().as_ref()).into_owned();
+                if name == "track" {
+                    track = Some(PlaylistEntry {
+                        location: String::new(),
+                        duration_seconds: None,
+                        title: None,
+                    });
+                } else if track.is_some()
+                    && matches!(name.as_str(), "location" | "title" | "duration")
+                {
+                    field = Some(name);
+                    text.clear();
+                }
+            }
+            Event::Text(value) if field.is_some() => text.push_str(&value.decode()?),
+            Event::CData(value) if field.is_some() => text.push_str(&value.decode()?),
+            Event::GeneralRef(reference) if field.is_some() => {
+                if let Some(character) = reference.resolve_char_ref()? {
+                    text.push(character);
+                } else if let Some(value) =
+                    quick_xml::escape::resolve_predefined_entity(&reference.decode()?)
+                {
+                    text.push_str(value);
+                }
+            }
+            Event::End(element) => {
+                let name = element.local_name();
+                let name = String::from_utf8_lossy(name.as_ref());
+                if name == "track" {
+                    if let Some(entry) = track.take().filter(|entry| !entry.location.is_empty()) {
+                        entries.push(entry);
+                    }
+                } else if field.as_deref() == Some(name.as_ref()) {
+                    field = None;
+                    let (Some(entry), value) = (track.as_mut(), text.trim()) else {
+                        continue;
+                    };
+                    match name.as_ref() {
+                        "location" if entry.location.is_empty() => {
+                            entry.location = value.to_owned()
+                        }
+                        "title" if !value.is_empty() => entry.title = Some(value.to_owned()),
+                        "duration" => {
+                            entry.duration_seconds = value
+                                .parse::<i64>()
+                                .ok()
+                                .filter(|milliseconds| *milliseconds >= 0)
+                                .map(|milliseconds| (milliseconds + 500) / 1000)
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(entries)
+}
+
+/// Outcome of importing one playlist file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaylistImport {
+    pub playlist_id: i64,
+    /// Entries added to the playlist.
+    pub imported: usize,
+    /// Local entries, as written in the file, that match no indexed file.
+    pub unresolved: Vec<String>,
 }
 
 /// Supported playlist file formats
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PlaylistFormat {
     M3U,
+    /// UTF-8 extended M3U.
+    M3U8,
     PLS,
+    /// XML Shareable Playlist Format, as read and written by VLC.
+    XSPF,
 }
 
 impl PlaylistFormat {
@@ -52,20 +249,69 @@ impl PlaylistFormat {
     pub fn extension(&self) -> &'static str {
         match self {
             PlaylistFormat::M3U => "m3u",
+            PlaylistFormat::M3U8 => "m3u8",
             PlaylistFormat::PLS => "pls",
+            PlaylistFormat::XSPF => "xspf",
         }
     }
 
     /// Detect format from file extension
     pub fn from_extension(path: &Path) -> Option<Self> {
         match path.extension()?.to_str()?.to_lowercase().as_str() {
-            "m3u" | "m3u8" => Some(PlaylistFormat::M3U),
+            "m3u" => Some(PlaylistFormat::M3U),
+            "m3u8" => Some(PlaylistFormat::M3U8),
             "pls" => Some(PlaylistFormat::PLS),
+            "xspf" => Some(PlaylistFormat::XSPF),
             _ => None,
         }
     }
 }
 
+/// How exported playlist entries refer to local media.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlaylistPathMode {
+    /// Absolute library paths; only valid on this machine.
+    #[default]
+    Absolute,
+    /// Paths relative to [`PlaylistExportOptions::base_dir`], so the playlist
+    /// keeps working when the library is mounted somewhere else.
+    Relative,
+}
+
+/// Character encoding of an exported playlist.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlaylistEncoding {
+    #[default]
+    Utf8,
+    /// ISO-8859-1 for legacy players. Titles outside it become `?` (XSPF
+    /// uses character references instead) and such paths are written as
+    /// `file://` URIs. `.m3u8` is UTF-8 by definition and cannot use it.
+    Latin1,
+}
+
+/// Options controlling how [`PlaylistFileManager::export_playlist`] writes entries.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlaylistExportOptions {
+    pub path_mode: PlaylistPathMode,
+    /// Directory relative entries are computed from. When exporting to a file it
+    /// defaults to the directory the playlist is written to.
+    pub base_dir: Option<PathBuf>,
+    pub encoding: PlaylistEncoding,
+}
+
+impl PlaylistExportOptions {
+    /// Export entries relative to `base_dir`.
+    pub fn relative_to(base_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            path_mode: PlaylistPathMode::Relative,
+            base_dir: Some(base_dir.into()),
+            ..Self::default()
+        }
+    }
+}
+
 /// Playlist file import/export functionality
 pub struct PlaylistFileManager;
 
@@ -75,7 +321,7 @@ impl PlaylistFileManager {
         database: &D,
         file_path: &Path,
         playlist_name: Option<String>,
-    ) -> Result<i64> {
+    ) -> Result<PlaylistImport> {
         let format = PlaylistFormat::from_extension(file_path).ok_or_else(|| {
             anyhow!(
                 "Unsupported playlist format for file: {}",
@@ -97,8 +343,8 @@ impl PlaylistFileManager {
             .to_canonical(file_path)
             .unwrap_or_else(|_| file_path.to_string_lossy().to_string());
 
-        let playlist_id = match format {
-            PlaylistFormat::M3U => {
+        match format {
+            PlaylistFormat::M3U | PlaylistFormat::M3U8 => {
                 Self::import_m3u(
                     database,
                     &file_content,
@@ -118,8 +364,75 @@ impl PlaylistFileManager {
                 )
                 .await
             }
-        }?;
-        Ok(playlist_id)
+            PlaylistFormat::XSPF => {
+                Self::import_xspf(
+                    database,
+                    &file_content,
+                    &playlist_name,
+                    base_dir,
+                    &source_path,
+                )
+                .await
+            }
+        }
+    }
+
+    /// Re-import a watched playlist file whose content changed since its last
+    /// sync, replacing the playlist it owns in place. Returns `None` when the
+    /// content is unchanged and the file still owns a playlist. Playlists
+    /// without a source file are never touched. A file with entries missing
+    /// from the library is imported again next time, in case they have been
+    /// indexed since.
+    pub async fn sync_playlist_file<D: DatabaseManager + ?Sized>(
+        database: &D,
+        file_path: &Path,
+    ) -> Result<Option<PlaylistImport>> {
+        let hash_key = Self::hash_setting(file_path);
+        let content = read_playlist_bytes(file_path).await?;
+        let hash = format!(
+            "{:016x}:{}",
+            crate::database::fnv1a(&content),
+            content.len()
+        );
+        if database.get_setting(&hash_key).await?.as_deref() == Some(hash.as_str()) {
+            let source = crate::database::canonical_media_path(file_path)?;
+            if database
+                .get_playlist_sources()
+                .await?
+                .values()
+                .any(|owner| *owner == source)
+            {
+                debug!(
+                    target: crate::logging::targets::DB,
+                    "Playlist file unchanged, skipping sync: {}",
+                    file_path.display()
+                );
+                return Ok(None);
+            }
+        }
+        let import = Self::import_playlist(database, file_path, None).await?;
+        if import.unresolved.is_empty() {
+            database.set_setting(&hash_key, &hash).await?;
+        } else {
+            database.remove_setting(&hash_key).await?;
+        }
+        Ok(Some(import))
+    }
+
+    /// Forget the sync state of a deleted playlist file.
+    pub async fn forget_playlist_file<D: DatabaseManager + ?Sized>(
+        database: &D,
+        file_path: &Path,
+    ) -> Result<()> {
+        database
+            .remove_setting(&Self::hash_setting(file_path))
+            .await
+    }
+
+    fn hash_setting(file_path: &Path) -> String {
+        let source = crate::database::canonical_media_path(file_path)
+            .unwrap_or_else(|_| file_path.to_path_buf());
+        format!("{PLAYLIST_HASH_SETTING}{}", source.display())
     }
 
     /// Export a playlist to a file
@@ -128,18 +441,93 @@ impl PlaylistFileManager {
         playlist_id: i64,
         output_path: &Path,
         format: PlaylistFormat,
+        options: &PlaylistExportOptions,
     ) -> Result<()> {
+        let mut options = options.clone();
+        if options.path_mode == PlaylistPathMode::Relative && options.base_dir.is_none() {
+            options.base_dir = Some(match output_path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+                _ => std::env::current_dir()?,
+            });
+        }
+        let content = Self::render_playlist(database, playlist_id, format, &options).await?;
+        tokio::fs::write(output_path, content).await?;
+        debug!(
+            target: crate::logging::targets::DB,
+            "Exported playlist {} to {}",
+            playlist_id,
+            output_path.display()
+        );
+        Ok(())
+    }
+
+    /// Render a playlist in `format` and `options.encoding` without writing
+    /// it anywhere.
+    pub async fn render_playlist<D: DatabaseManager + ?Sized>(
+        database: &D,
+        playlist_id: i64,
+        format: PlaylistFormat,
+        options: &PlaylistExportOptions,
+    ) -> Result<Vec<u8>> {
+        let latin1 = options.encoding == PlaylistEncoding::Latin1;
+        if latin1 && format == PlaylistFormat::M3U8 {
+            return Err(anyhow!("m3u8 playlists are always UTF-8"));
+        }
+        let base_dir = match options.path_mode {
+            PlaylistPathMode::Absolute => None,
+            PlaylistPathMode::Relative => {
+                let base_dir = options
+                    .base_dir
+                    .as_deref()
+                    .ok_or_else(|| anyhow!("relative playlist export needs a base directory"))?;
+                if !base_dir.is_absolute() {
+                    return Err(anyhow!(
+                        "playlist export base directory must be absolute: {}",
+                        base_dir.display()
+                    ));
+                }
+                Some(
+                    tokio::fs::canonicalize(base_dir)
+                        .await
+                        .unwrap_or_else(|_| base_dir.to_path_buf()),
+                )
+            }
+        };
+
         let playlist = database
             .get_playlist(playlist_id)
             .await?
             .ok_or_else(|| anyhow!("Playlist with ID {} not found", playlist_id))?;
 
         let tracks = database.get_playlist_tracks(playlist_id).await?;
+        let locations = tracks
+            .iter()
+            .map(|track| match format {
+                PlaylistFormat::XSPF => xspf_location(&track.path, base_dir.as_deref()),
+                _ => {
+                    let location = export_location(&track.path, base_dir.as_deref());
+                    if latin1 && location.chars().any(|c| u32::from(c) > 0xFF) {
+                        file_uri(&track.path)
+                    } else {
+                        location
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
 
-        match format {
-            PlaylistFormat::M3U => Self::export_m3u(&playlist, &tracks, output_path).await,
-            PlaylistFormat::PLS => Self::export_pls(&playlist, &tracks, output_path).await,
-        }
+        let content = match format {
+            PlaylistFormat::M3U | PlaylistFormat::M3U8 => {
+                Self::render_m3u(&playlist, &tracks, &locations)?
+            }
+            PlaylistFormat::PLS => Self::render_pls(&playlist, &tracks, &locations),
+            PlaylistFormat::XSPF => {
+                Self::render_xspf(&playlist, &tracks, &locations, options.encoding)
+            }
+        };
+        Ok(match options.encoding {
+            PlaylistEncoding::Utf8 => content.into_bytes(),
+            PlaylistEncoding::Latin1 => encode_latin1(&content, format == PlaylistFormat::XSPF),
+        })
     }
 
     /// Import M3U playlist format
@@ -149,65 +537,17 @@ impl PlaylistFileManager {
         playlist_name: &str,
         base_dir: &Path,
         source_path: &str,
-    ) -> Result<i64> {
-        debug!("Importing M3U playlist: {}", playlist_name);
-
-        // Collect all track paths first
-        let mut track_paths = Vec::new();
-        let lines: Vec<&str> = content.lines().collect();
-        let mut i = 0;
-
-        while i < lines.len() {
-            let line = lines[i].trim();
-
-            // Skip empty lines and comments (except #EXTINF)
-            if line.is_empty() || (line.starts_with('#') && !line.starts_with("#EXTINF")) {
-                i += 1;
-                continue;
-            }
-
-            // Handle extended M3U format
-            if line.starts_with("#EXTINF") {
-                // Next line should be the file path
-                i += 1;
-                if i < lines.len() {
-                    let file_path_str = lines[i].trim();
-                    track_paths.push(resolve_playlist_entry(base_dir, file_path_str).await);
-                }
-            } else if !line.starts_with('#') {
-                // Simple M3U format - just file paths
-                track_paths.push(resolve_playlist_entry(base_dir, line).await);
-            }
-
-            i += 1;
-        }
+    ) -> Result<PlaylistImport> {
+        debug!(target: crate::logging::targets::DB, "Importing M3U playlist: {}", playlist_name);
 
-        // Create list of (path, position) pairs
-        if track_paths.len() > MAX_PLAYLIST_ENTRIES {
+        let entries = parse_m3u(content);
+        if entries.len() > MAX_PLAYLIST_ENTRIES {
             return Err(anyhow!(
                 "playlist exceeds the {} entry limit",
                 MAX_PLAYLIST_ENTRIES
             ));
         }
-        let file_paths_with_positions: Vec<(String, u32)> = track_paths
-            .into_iter()
-            .enumerate()
-            .map(|(index, path)| Ok((path, u32::try_from(index)?)))
-            .collect::<Result<_>>()?;
-
-        // Add all tracks to playlist in batch operation
-        let media_entries = Self::source_entries(&file_paths_with_positions);
-        let playlist_id = database
-            .replace_source_content(Path::new(source_path), Some(playlist_name), &media_entries)
-            .await?
-            .ok_or_else(|| anyhow!("playlist import did not create a playlist"))?;
-
-        debug!(
-            "Imported {} tracks to playlist '{}'",
-            media_entries.len(),
-            playlist_name
-        );
-        Ok(playlist_id)
+        Self::import_entries(database, entries, playlist_name, base_dir, source_path).await
     }
 
     /// Import PLS playlist format
@@ -217,8 +557,8 @@ impl PlaylistFileManager {
         playlist_name: &str,
         base_dir: &Path,
         source_path: &str,
-    ) -> Result<i64> {
-        debug!("Importing PLS playlist: {}", playlist_name);
+    ) -> Result<PlaylistImport> {
+        debug!(target: crate::logging::targets::DB, "Importing PLS playlist: {}", playlist_name);
 
         let mut tracks: Vec<(u32, String)> = Vec::new();
 
@@ -231,10 +571,7 @@ impl PlaylistFileManager {
 
                     // Extract the number from "File1", "File2", etc.
                     if let Ok(track_num) = key[4..].parse::<u32>() {
-                        tracks.push((
-                            track_num,
-                            resolve_playlist_entry(base_dir, value.trim()).await,
-                        ));
+                        tracks.push((track_num, value.trim().to_owned()));
                     }
                 }
             }
@@ -250,38 +587,139 @@ impl PlaylistFileManager {
             ));
         }
 
-        // Create list of (path, position) pairs for batch operation
-        let file_paths_with_positions: Vec<(String, u32)> = tracks
+        let entries = tracks
             .into_iter()
-            .enumerate()
-            .map(|(index, (_, file_path))| Ok((file_path, u32::try_from(index)?)))
-            .collect::<Result<_>>()?;
+            .map(|(_, location)| PlaylistEntry {
+                location,
+                duration_seconds: None,
+                title: None,
+            })
+            .collect();
+        Self::import_entries(database, entries, playlist_name, base_dir, source_path).await
+    }
+
+    /// Import XSPF playlist format
+    async fn import_xspf<D: DatabaseManager + ?Sized>(
+        database: &D,
+        content: &str,
+        playlist_name: &str,
+        base_dir: &Path,
+        source_path: &str,
+    ) -> Result<PlaylistImport> {
+        debug!(target: crate::logging::targets::DB, "Importing XSPF playlist: {}", playlist_name);
+
+        let entries = parse_xspf(content)?;
+        if entries.len() > MAX_PLAYLIST_ENTRIES {
+            return Err(anyhow!(
+                "playlist exceeds the {} entry limit",
+                MAX_PLAYLIST_ENTRIES
+            ));
+        }
+        Self::import_entries(database, entries, playlist_name, base_dir, source_path).await
+    }
+
+    /// Resolve parsed entries against `base_dir`, match local ones to the
+    /// library and replace the playlist owned by `source_path`. Local entries
+    /// must already be indexed; the rest are reported as unresolved so one
+    /// stale line does not abort the whole import. `#EXTINF` titles and
+    /// durations are kept on the playlist's entries, where they stand in for
+    /// tags the matched files lack without changing the files themselves.
+    async fn import_entries<D: DatabaseManager + ?Sized>(
+        database: &D,
+        entries: Vec<PlaylistEntry>,
+        playlist_name: &str,
+        base_dir: &Path,
+        source_path: &str,
+    ) -> Result<PlaylistImport> {
+        let mut resolved = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let location = resolve_playlist_entry(base_dir, &entry.location).await;
+            resolved.push((location, entry));
+        }
+
+        let normalizer = crate::platform::filesystem::create_platform_path_normalizer();
+        let comparable = |path: &Path| {
+            normalizer
+                .to_canonical(path)
+                .unwrap_or_else(|_| path.to_string_lossy().into_owned())
+        };
+        let local_paths = resolved
+            .iter()
+            .filter(|(location, _)| !is_http_stream(location))
+            .map(|(location, _)| PathBuf::from(location))
+            .collect::<Vec<_>>();
+        let indexed = database
+            .get_files_by_paths(&local_paths)
+            .await?
+            .iter()
+            .map(|file| comparable(&file.path))
+            .collect::<std::collections::HashSet<_>>();
+
+        let mut media_entries = Vec::with_capacity(resolved.len());
+        let mut unresolved = Vec::new();
+        for (location, entry) in resolved {
+            let is_stream = is_http_stream(&location);
+            if !is_stream && !indexed.contains(&comparable(Path::new(&location))) {
+                warn!(
+                    target: crate::logging::targets::DB,
+                    "Skipping playlist entry not found in the media library: {} ({})",
+                    entry.location,
+                    playlist_name
+                );
+                unresolved.push(entry.location);
+                continue;
+            }
+            let info = if is_stream {
+                PlaylistEntryInfo::default()
+            } else {
+                PlaylistEntryInfo {
+                    title: entry.title.clone(),
+                    duration: entry
+                        .duration_seconds
+                        .filter(|seconds| *seconds > 0)
+                        .map(|seconds| std::time::Duration::from_secs(seconds as u64)),
+                }
+            };
+            media_entries.push(SourceMediaEntry {
+                position: u32::try_from(media_entries.len())?,
+                stream_title: is_stream.then(|| entry.title.unwrap_or_else(|| location.clone())),
+                location: PathBuf::from(location),
+                info,
+            });
+        }
 
-        // Add all tracks to playlist in batch operation
-        let media_entries = Self::source_entries(&file_paths_with_positions);
         let playlist_id = database
-            .replace_source_content(Path::new(source_path), Some(playlist_name), &media_entries)
+            .replace_source_content(
+                &path_from_lossless_string(source_path),
+                Some(playlist_name),
+                &media_entries,
+            )
             .await?
             .ok_or_else(|| anyhow!("playlist import did not create a playlist"))?;
 
         debug!(
+            target: crate::logging::targets::DB,
             "Imported {} tracks to playlist '{}'",
             media_entries.len(),
             playlist_name
         );
-        Ok(playlist_id)
+        Ok(PlaylistImport {
+            playlist_id,
+            imported: media_entries.len(),
+            unresolved,
+        })
     }
 
-    /// Export playlist to M3U format
-    async fn export_m3u(
+    /// Render playlist entries as extended M3U
+    fn render_m3u(
         playlist: &Playlist,
         tracks: &[MediaFile],
-        output_path: &Path,
-    ) -> Result<()> {
+        locations: &[String],
+    ) -> Result<String> {
         debug!(
-            "Exporting playlist '{}' to M3U format: {}",
-            playlist.name,
-            output_path.display()
+            target: crate::logging::targets::DB,
+            "Exporting playlist '{}' to M3U format",
+            playlist.name
         );
 
         use std::fmt::Write;
@@ -290,7 +728,7 @@ impl PlaylistFileManager {
         // Write M3U header
         writeln!(content, "#EXTM3U").unwrap();
 
-        for track in tracks {
+        for (track, location) in tracks.iter().zip(locations) {
             // Write extended info if available
             let duration = track
                 .duration
@@ -314,26 +752,31 @@ impl PlaylistFileManager {
 
             let artist = track.artist.as_deref().unwrap_or("Unknown Artist");
 
-            writeln!(content, "#EXTINF:{},{} - {}", duration, artist, title).unwrap();
-            writeln!(content, "{}", track.path.display()).unwrap();
+            writeln!(
+                content,
+                "#EXTINF:{},{} - {}",
+                duration,
+                single_line(artist),
+                single_line(title)
+            )
+            .unwrap();
+            writeln!(content, "{}", location).unwrap();
         }
 
-        tokio::fs::write(output_path, content).await?;
-
-        debug!("Successfully exported {} tracks to M3U", tracks.len());
-        Ok(())
+        debug!(
+            target: crate::logging::targets::DB,
+            "Successfully exported {} tracks to M3U",
+            tracks.len()
+        );
+        Ok(content)
     }
 
-    /// Export playlist to PLS format
-    async fn export_pls(
-        playlist: &Playlist,
-        tracks: &[MediaFile],
-        output_path: &Path,
-    ) -> Result<()> {
+    /// Render playlist entries as PLS
+    fn render_pls(playlist: &Playlist, tracks: &[MediaFile], locations: &[String]) -> String {
         debug!(
-            "Exporting playlist '{}' to PLS format: {}",
-            playlist.name,
-            output_path.display()
+            target: crate::logging::targets::DB,
+            "Exporting playlist '{}' to PLS format",
+            playlist.name
         );
 
         use std::fmt::Write;
@@ -344,16 +787,29 @@ impl PlaylistFileManager {
         writeln!(content, "NumberOfEntries={}", tracks.len()).unwrap();
         writeln!(content).unwrap();
 
-        for (i, track) in tracks.iter().enumerate() {
+        for (i, (track, location)) in tracks.iter().zip(locations).enumerate() {
             let track_num = i + 1;
 
-            writeln!(content, "File{}={}", track_num, track.path.display()).unwrap();
+            writeln!(content, "File{}={}", track_num, location).unwrap();
 
             if let Some(ref title) = track.title {
                 let artist = track.artist.as_deref().unwrap_or("Unknown Artist");
-                writeln!(content, "Title{}={} - {}", track_num, artist, title).unwrap();
+                writeln!(
+                    content,
+                    "Title{}={} - {}",
+                    track_num,
+                    single_line(artist),
+                    single_line(title)
+                )
+                .unwrap();
             } else {
-                writeln!(content, "Title{}={}", track_num, track.filename).unwrap();
+                writeln!(
+                    content,
+                    "Title{}={}",
+                    track_num,
+                    single_line(&track.filename)
+                )
+                .unwrap();
             }
 
             if let Some(duration) = track.duration {
@@ -367,30 +823,90 @@ impl PlaylistFileManager {
 
         writeln!(content, "Version=2").unwrap();
 
-        tokio::fs::write(output_path, content).await?;
-
-        debug!("Successfully exported {} tracks to PLS", tracks.len());
-        Ok(())
+        debug!(
+            target: crate::logging::targets::DB,
+            "Successfully exported {} tracks to PLS",
+            tracks.len()
+        );
+        content
     }
 
-    /// Add tracks to playlist by file paths using batch operations
-    fn source_entries(file_paths_with_positions: &[(String, u32)]) -> Vec<SourceMediaEntry> {
-        file_paths_with_positions
-            .iter()
-            .map(|(location, position)| SourceMediaEntry {
-                location: PathBuf::from(location),
-                position: *position,
-                stream_title: is_http_stream(location).then(|| location.clone()),
-            })
-            .collect()
+    /// Render playlist entries as XSPF. Titles are written only for tagged
+    /// tracks so an import does not mistake file names for titles.
+    fn render_xspf(
+        playlist: &Playlist,
+        tracks: &[MediaFile],
+        locations: &[String],
+        encoding: PlaylistEncoding,
+    ) -> String {
+        debug!(
+            target: crate::logging::targets::DB,
+            "Exporting playlist '{}' to XSPF format",
+            playlist.name
+        );
+
+        use quick_xml::escape::escape;
+        use std::fmt::Write;
+        let text = |value: &str| escape(single_line(value).into_owned()).into_owned();
+        let mut content = String::new();
+
+        let encoding = match encoding {
+            PlaylistEncoding::Utf8 => "UTF-8",
+            PlaylistEncoding::Latin1 => "ISO-8859-1",
+        };
+        writeln!(content, r#"<?xml version="1.0" encoding="{encoding}"?>"#).unwrap();
+        writeln!(
+            content,
+            r#"<playlist version="1" xmlns="http://xspf.org/ns/0/">"#
+        )
+        .unwrap();
+        writeln!(content, "  <title>{}</title>", text(&playlist.name)).unwrap();
+        writeln!(content, "  <trackList>").unwrap();
+        for (track, location) in tracks.iter().zip(locations) {
+            writeln!(content, "    <track>").unwrap();
+            writeln!(content, "      <location>{}</location>", text(location)).unwrap();
+            if let Some(title) = &track.title {
+                writeln!(content, "      <title>{}</title>", text(title)).unwrap();
+            }
+            if let Some(artist) = &track.artist {
+                writeln!(content, "      <creator>{}</creator>", text(artist)).unwrap();
+            }
+            if let Some(album) = &track.album {
+                writeln!(content, "      <album>{}</album>", text(album)).unwrap();
+            }
+            if let Some(track_number) = track.track_number {
+                writeln!(content, "      <trackNum>{track_number}</trackNum>").unwrap();
+            }
+            if let Some(duration) = track.duration {
+                writeln!(
+                    content,
+                    "      <duration>{}</duration>",
+                    duration.as_millis()
+                )
+                .unwrap();
+            }
+            writeln!(content, "    </track>").unwrap();
+        }
+        writeln!(content, "  </trackList>").unwrap();
+        writeln!(content, "</playlist>").unwrap();
+
+        debug!(
+            target: crate::logging::targets::DB,
+            "Successfully exported {} tracks to XSPF",
+            tracks.len()
+        );
+        content
     }
 
-    /// Scan a directory for playlist files and import them
+    /// Scan a directory for playlist files and import them. Files unchanged
+    /// since their last import are skipped, so only the IDs of new or
+    /// updated playlists are returned.
     pub async fn scan_and_import_playlists<D: DatabaseManager + ?Sized>(
         database: &D,
         directory: &Path,
     ) -> Result<Vec<i64>> {
         debug!(
+            target: crate::logging::targets::DB,
             "Scanning directory for playlist files: {}",
             directory.display()
         );
@@ -407,6 +923,7 @@ impl PlaylistFileManager {
         if !directory_metadata.is_dir() {
             return Err(anyhow!("Path is not a directory: {}", directory.display()));
         }
+        let mirrored = mirrored_files(directory).await;
 
         let mut entries = tokio::fs::read_dir(directory).await?;
 
@@ -415,21 +932,41 @@ impl PlaylistFileManager {
             let file_type = entry.file_type().await?;
 
             if file_type.is_symlink() {
-                warn!("Skipping symbolic link: {}", path.display());
-            } else if file_type.is_file() {
+                warn!(
+                    target: crate::logging::targets::DB,
+                    "Skipping symbolic link: {}",
+                    path.display()
+                );
+            } else if file_type.is_file() && !mirrored.contains(&entry.file_name()) {
                 if let Some(_format) = PlaylistFormat::from_extension(&path) {
                     if is_radio_playlist_path(&path) {
                         if let Err(e) = Self::import_radio_playlist(database, &path).await {
-                            warn!("Failed to import radio playlist {}: {}", path.display(), e);
+                            warn!(
+                                target: crate::logging::targets::DB,
+                                "Failed to import radio playlist {}: {}",
+                                path.display(),
+                                e
+                            );
                         }
                     } else {
-                        match Self::import_playlist(database, &path, None).await {
-                            Ok(playlist_id) => {
-                                debug!("Successfully imported playlist: {}", path.display());
-                                imported_playlists.push(playlist_id);
+                        match Self::sync_playlist_file(database, &path).await {
+                            Ok(None) => {}
+                            Ok(Some(import)) => {
+                                debug!(
+                                    target: crate::logging::targets::DB,
+                                    "Successfully imported playlist: {} ({} unresolved entries)",
+                                    path.display(),
+                                    import.unresolved.len()
+                                );
+                                imported_playlists.push(import.playlist_id);
                             }
                             Err(e) => {
-                                warn!("Failed to import playlist {}: {}", path.display(), e);
+                                warn!(
+                                    target: crate::logging::targets::DB,
+                                    "Failed to import playlist {}: {}",
+                                    path.display(),
+                                    e
+                                );
                             }
                         }
                     }
@@ -438,13 +975,15 @@ impl PlaylistFileManager {
         }
 
         debug!(
+            target: crate::logging::targets::DB,
             "Imported {} playlists from directory",
             imported_playlists.len()
         );
         Ok(imported_playlists)
     }
 
-    /// Recursively scan a directory tree for playlist files and import them
+    /// Recursively scan a directory tree for playlist files and import them,
+    /// skipping unchanged files like [`Self::scan_and_import_playlists`].
     pub async fn scan_and_import_playlists_recursive<D: DatabaseManager + ?Sized>(
         database: &D,
         directory: &Path,
@@ -452,6 +991,7 @@ impl PlaylistFileManager {
         use tracing::info;
 
         info!(
+            target: crate::logging::targets::DB,
             "Recursively scanning for playlist files: {}",
             directory.display()
         );
@@ -466,11 +1006,17 @@ impl PlaylistFileManager {
             if current_metadata.file_type().is_symlink() || !current_metadata.is_dir() {
                 continue;
             }
+            let mirrored = mirrored_files(&current_dir).await;
 
             let mut entries = match tokio::fs::read_dir(&current_dir).await {
                 Ok(entries) => entries,
                 Err(e) => {
-                    warn!("Failed to read directory {}: {}", current_dir.display(), e);
+                    warn!(
+                        target: crate::logging::targets::DB,
+                        "Failed to read directory {}: {}",
+                        current_dir.display(),
+                        e
+                    );
                     continue;
                 }
             };
@@ -480,7 +1026,11 @@ impl PlaylistFileManager {
                 let file_type = entry.file_type().await?;
 
                 if file_type.is_symlink() {
-                    warn!("Skipping symbolic link: {}", path.display());
+                    warn!(
+                        target: crate::logging::targets::DB,
+                        "Skipping symbolic link: {}",
+                        path.display()
+                    );
                 } else if file_type.is_dir() {
                     // Skip hidden directories
                     if path
@@ -489,19 +1039,38 @@ impl PlaylistFileManager {
                     {
                         dirs_to_scan.push(path);
                     }
-                } else if file_type.is_file() && PlaylistFormat::from_extension(&path).is_some() {
+                } else if file_type.is_file()
+                    && PlaylistFormat::from_extension(&path).is_some()
+                    && !mirrored.contains(&entry.file_name())
+                {
                     if is_radio_playlist_path(&path) {
                         if let Err(e) = Self::import_radio_playlist(database, &path).await {
-                            warn!("Failed to import radio playlist {}: {}", path.display(), e);
+                            warn!(
+                                target: crate::logging::targets::DB,
+                                "Failed to import radio playlist {}: {}",
+                                path.display(),
+                                e
+                            );
                         }
                     } else {
-                        match Self::import_playlist(database, &path, None).await {
-                            Ok(playlist_id) => {
-                                debug!("Imported playlist: {}", path.display());
-                                imported_playlists.push(playlist_id);
+                        match Self::sync_playlist_file(database, &path).await {
+                            Ok(None) => {}
+                            Ok(Some(import)) => {
+                                debug!(
+                                    target: crate::logging::targets::DB,
+                                    "Imported playlist: {} ({} unresolved entries)",
+                                    path.display(),
+                                    import.unresolved.len()
+                                );
+                                imported_playlists.push(import.playlist_id);
                             }
                             Err(e) => {
-                                warn!("Failed to import playlist {}: {}", path.display(), e);
+                                warn!(
+                                    target: crate::logging::targets::DB,
+                                    "Failed to import playlist {}: {}",
+                                    path.display(),
+                                    e
+                                );
                             }
                         }
                     }
@@ -510,6 +1079,7 @@ impl PlaylistFileManager {
         }
 
         info!(
+            target: crate::logging::targets::DB,
             "Imported {} playlists from directory tree",
             imported_playlists.len()
         );
@@ -535,7 +1105,7 @@ impl PlaylistFileManager {
 
         let mut stations = Vec::new();
         match format {
-            PlaylistFormat::M3U => {
+            PlaylistFormat::M3U | PlaylistFormat::M3U8 => {
                 let lines: Vec<&str> = file_content.lines().collect();
                 let mut i = 0;
                 while i < lines.len() {
@@ -590,6 +1160,14 @@ impl PlaylistFileManager {
                     stations.push((name, url));
                 }
             }
+            PlaylistFormat::XSPF => {
+                for entry in parse_xspf(&file_content)? {
+                    if is_http_stream(&entry.location) {
+                        let name = entry.title.unwrap_or_else(|| entry.location.clone());
+                        stations.push((name, entry.location));
+                    }
+                }
+            }
         }
 
         if stations.len() > MAX_PLAYLIST_ENTRIES {
@@ -606,16 +1184,122 @@ impl PlaylistFileManager {
                     location: PathBuf::from(url),
                     position: u32::try_from(position)?,
                     stream_title: Some(name),
+                    info: Default::default(),
                 })
             })
             .collect::<Result<Vec<_>>>()?;
         database
-            .replace_source_content(Path::new(&playlist_path_str), None, &entries)
+            .replace_source_content(
+                &path_from_lossless_string(&playlist_path_str),
+                None,
+                &entries,
+            )
             .await?;
 
         Ok(())
     }
 
+    /// Make `directory` hold exactly one `.m3u8` file per database playlist.
+    ///
+    /// Files are named after their playlist and only rewritten when their
+    /// content changes; files an earlier pass wrote for renamed or deleted
+    /// playlists are removed. Files the mirror did not write are left alone,
+    /// and a playlist whose name one of them takes is written under its id
+    /// instead. Tracks inside `share_root`, the monitored directory containing
+    /// `directory`, are written relative to `directory` so a copy of the whole
+    /// share stays playable. Other tracks keep absolute paths.
+    pub async fn mirror_playlists<D: DatabaseManager + ?Sized>(
+        database: &D,
+        directory: &Path,
+        share_root: Option<&Path>,
+    ) -> Result<()> {
+        tokio::fs::create_dir_all(directory).await?;
+        let directory = tokio::fs::canonicalize(directory).await?;
+        let share_root = match share_root {
+            Some(root) => Some(
+                tokio::fs::canonicalize(root)
+                    .await
+                    .unwrap_or_else(|_| root.to_path_buf()),
+            ),
+            None => None,
+        };
+
+        let previously_written = mirrored_files(&directory).await;
+        let mut written = std::collections::HashSet::new();
+        let mut taken = std::collections::HashSet::new();
+        let mut user_files = tokio::fs::read_dir(&directory).await?;
+        while let Some(entry) = user_files.next_entry().await? {
+            if !previously_written.contains(&entry.file_name()) {
+                taken.insert(entry.file_name().to_string_lossy().to_lowercase());
+            }
+        }
+        for playlist in database.get_playlists().await? {
+            let Some(playlist_id) = playlist.id else {
+                continue;
+            };
+            let mut file_name = Self::get_output_filename(&playlist.name, PlaylistFormat::M3U8);
+            if !taken.insert(file_name.to_lowercase()) {
+                file_name = Self::get_output_filename(
+                    &format!("{} {}", playlist.name, playlist_id),
+                    PlaylistFormat::M3U8,
+                );
+                taken.insert(file_name.to_lowercase());
+            }
+
+            let tracks = database.get_playlist_tracks(playlist_id).await?;
+            let locations = tracks
+                .iter()
+                .map(|track| {
+                    let shared = share_root
+                        .as_deref()
+                        .is_some_and(|root| track.path.starts_with(root));
+                    export_location(&track.path, shared.then_some(directory.as_path()))
+                })
+                .collect::<Vec<_>>();
+            let content = Self::render_m3u(&playlist, &tracks, &locations)?;
+
+            let path = directory.join(&file_name);
+            if tokio::fs::read_to_string(&path).await.ok().as_deref() != Some(content.as_str()) {
+                let temporary = path.with_extension("m3u8.tmp");
+                tokio::fs::write(&temporary, &content).await?;
+                tokio::fs::rename(&temporary, &path).await?;
+                debug!(
+                    target: crate::logging::targets::DB,
+                    "Mirrored playlist '{}' to {}",
+                    playlist.name,
+                    path.display()
+                );
+            }
+            written.insert(std::ffi::OsString::from(file_name));
+        }
+
+        for stale in previously_written.difference(&written) {
+            let path = directory.join(stale);
+            match tokio::fs::remove_file(&path).await {
+                Ok(()) => debug!(
+                    target: crate::logging::targets::DB,
+                    "Removed mirrored playlist {}",
+                    path.display()
+                ),
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+                Err(error) => return Err(error.into()),
+            }
+        }
+
+        if written != previously_written {
+            let mut manifest = written
+                .iter()
+                .map(|name| name.to_string_lossy().into_owned())
+                .collect::<Vec<_>>();
+            manifest.sort();
+            let manifest_path = directory.join(MIRROR_MANIFEST);
+            let temporary = manifest_path.with_extension("tmp");
+            tokio::fs::write(&temporary, manifest.join("\n") + "\n").await?;
+            tokio::fs::rename(&temporary, &manifest_path).await?;
+        }
+        Ok(())
+    }
+
     /// Get the appropriate file extension for a playlist export
     pub fn get_output_filename(playlist_name: &str, format: PlaylistFormat) -> String {
         // Sanitize the playlist name for use as filename
@@ -657,15 +1341,200 @@ fn is_radio_playlist_path(path: &Path) -> bool {
     })
 }
 
+/// Characters escaped in exported `file://` URIs; `/` stays readable.
+const FILE_URI_ESCAPES: &percent_encoding::AsciiSet = &percent_encoding::NON_ALPHANUMERIC
+    .remove(b'/')
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// Titles share a line with their `#EXTINF`/`TitleN=` key, so line breaks are flattened.
+fn single_line(value: &str) -> std::borrow::Cow<'_, str> {
+    if value.chars().any(char::is_control) {
+        value
+            .chars()
+            .map(|c| if c.is_control() { ' ' } else { c })
+            .collect::<String>()
+            .into()
+    } else {
+        value.into()
+    }
+}
+
+/// Format one playlist entry. Local files are written relative to `base_dir`
+/// when it is given and shares a root with the file, and as percent-encoded
+/// `file://` URIs when the raw path would not survive a line-oriented playlist.
+fn export_location(path: &Path, base_dir: Option<&Path>) -> String {
+    let Some(raw) = path.to_str() else {
+        return file_uri(path);
+    };
+    if is_http_stream(raw) {
+        return raw.to_owned();
+    }
+    let location = match base_dir.and_then(|base_dir| relative_location(path, base_dir)) {
+        // A leading `#` would read as an M3U directive.
+        Some(relative) if relative.starts_with('#') => format!("./{relative}"),
+        Some(relative) => relative,
+        None => raw.to_owned(),
+    };
+    let ambiguous = location.chars().any(char::is_control)
+        || location.trim() != location
+        || (!cfg!(windows) && location.contains('\\'))
+        || location
+            .get(..7)
+            .is_some_and(|scheme| scheme.eq_ignore_ascii_case("file://"));
+    if ambiguous {
+        file_uri(path)
+    } else {
+        location
+    }
+}
+
+/// `path` relative to `base_dir` with `/` separators, or `None` when the two
+/// do not share a root (for example different Windows drives).
+fn relative_location(path: &Path, base_dir: &Path) -> Option<String> {
+    use std::path::Component;
+
+    let path_components = path.components().collect::<Vec<_>>();
+    let base_components = base_dir
+        .components()
+        .filter(|component| *component != Component::CurDir)
+        .collect::<Vec<_>>();
+    fn roots<'a>(components: &[Component<'a>]) -> Vec<Component<'a>> {
+        components
+            .iter()
+            .take_while(|component| matches!(component, Component::Prefix(_) | Component::RootDir))
+            .copied()
+            .collect()
+    }
+    if roots(&path_components).is_empty() || roots(&path_components) != roots(&base_components) {
+        return None;
+    }
+    if base_components.contains(&Component::ParentDir) {
+        return None;
+    }
+
+    let common = path_components
+        .iter()
+        .zip(&base_components)
+        .take_while(|(left, right)| left == right)
+        .count();
+    let mut parts = vec![".."; base_components.len() - common];
+    for component in &path_components[common..] {
+        match component {
+            Component::Normal(name) => parts.push(name.to_str()?),
+            _ => return None,
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+/// Format one XSPF `<location>`, which is a URI: a percent-encoded reference
+/// relative to `base_dir` when possible, otherwise a `file://` URI.
+fn xspf_location(path: &Path, base_dir: Option<&Path>) -> String {
+    if let Some(stream) = path.to_str().filter(|raw| is_http_stream(raw)) {
+        return stream.to_owned();
+    }
+    match base_dir.and_then(|base_dir| relative_location(path, base_dir)) {
+        Some(relative) => {
+            percent_encoding::utf8_percent_encode(&relative, FILE_URI_ESCAPES).to_string()
+        }
+        None => file_uri(path),
+    }
+}
+
+/// Encode rendered text as ISO-8859-1. Characters outside it become XML
+/// character references when `xml` is set and `?` otherwise.
+fn encode_latin1(content: &str, xml: bool) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(content.len());
+    for character in content.chars() {
+        match u8::try_from(u32::from(character)) {
+            Ok(byte) => encoded.push(byte),
+            Err(_) if xml => {
+                encoded.extend_from_slice(format!("&#{};", u32::from(character)).as_bytes())
+            }
+            Err(_) => encoded.push(b'?'),
+        }
+    }
+    encoded
+}
+
+fn file_uri(path: &Path) -> String {
+    #[cfg(unix)]
+    let encoded = {
+        use std::os::unix::ffi::OsStrExt;
+        percent_encoding::percent_encode(path.as_os_str().as_bytes(), FILE_URI_ESCAPES).to_string()
+    };
+    #[cfg(not(unix))]
+    let encoded = {
+        let path = path.to_string_lossy().replace('\\', "/");
+        let path = if path.starts_with('/') {
+            path
+        } else {
+            format!("/{path}")
+        };
+        percent_encoding::utf8_percent_encode(&path, FILE_URI_ESCAPES)
+            .to_string()
+            .replacen("%3A", ":", 1)
+    };
+    format!("file://{encoded}")
+}
+
+/// Decode a local `file://` URI written by [`file_uri`] or another player.
+fn file_uri_path(entry: &str) -> Option<PathBuf> {
+    let rest = entry
+        .get(..7)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("file://"))
+        .map(|_| &entry[7..])?;
+    let rest = rest.strip_prefix("localhost").unwrap_or(rest);
+    if !rest.starts_with('/') {
+        return None;
+    }
+    let bytes = percent_encoding::percent_decode_str(rest).collect::<Vec<u8>>();
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+        Some(PathBuf::from(std::ffi::OsString::from_vec(bytes)))
+    }
+    #[cfg(not(unix))]
+    {
+        let decoded = String::from_utf8(bytes).ok()?;
+        // `/C:/Music` names the drive path `C:/Music`.
+        let decoded = match decoded.as_bytes() {
+            [b'/', drive, b':', ..] if drive.is_ascii_alphabetic() => decoded[1..].to_owned(),
+            _ => decoded,
+        };
+        Some(PathBuf::from(decoded))
+    }
+}
+
+/// Resolve one entry to a stream URL or an absolute path. Plain paths may be
+/// URL-encoded (`My%20Song.mp3`); the decoded form is used when the entry
+/// does not name an existing file as written, so literal `%` names still work.
 async fn resolve_playlist_entry(base_dir: &Path, entry: &str) -> String {
     let entry = entry.trim();
     if is_http_stream(entry) {
         entry.to_string()
-    } else {
-        resolve_playlist_path(base_dir, entry)
+    } else if let Some(path) = file_uri_path(entry) {
+        tokio::fs::canonicalize(&path)
             .await
+            .unwrap_or(path)
             .to_string_lossy()
             .into_owned()
+    } else {
+        let literal = resolve_playlist_path(base_dir, entry).await;
+        let decoded = percent_encoding::percent_decode_str(entry)
+            .decode_utf8()
+            .ok()
+            .filter(|decoded| decoded != entry);
+        let path = match decoded {
+            Some(decoded) if !tokio::fs::try_exists(&literal).await.unwrap_or(false) => {
+                resolve_playlist_path(base_dir, &decoded).await
+            }
+            _ => literal,
+        };
+        path.to_string_lossy().into_owned()
     }
 }
 
@@ -700,8 +1569,6 @@ mod tests {
     use super::*;
     use crate::database::{MediaRepository, PlaylistRepository};
     use std::fs;
-    use std::io::Write;
-    use tempfile::NamedTempFile;
 
     #[test]
     fn test_format_detection() {
@@ -715,7 +1582,7 @@ mod tests {
         );
         assert_eq!(
             PlaylistFormat::from_extension(Path::new("test.m3u8")),
-            Some(PlaylistFormat::M3U)
+            Some(PlaylistFormat::M3U8)
         );
         assert_eq!(
             PlaylistFormat::from_extension(Path::new("test.pls")),
@@ -725,6 +1592,10 @@ mod tests {
             PlaylistFormat::from_extension(Path::new("test.PLS")),
             Some(PlaylistFormat::PLS)
         );
+        assert_eq!(
+            PlaylistFormat::from_extension(Path::new("test.XSPF")),
+            Some(PlaylistFormat::XSPF)
+        );
         assert_eq!(PlaylistFormat::from_extension(Path::new("test.txt")), None);
     }
 
@@ -751,6 +1622,12 @@ mod tests {
         let file = std::fs::File::create(&oversized).unwrap();
         file.set_len(MAX_PLAYLIST_BYTES + 1).unwrap();
         assert!(read_playlist_text(&oversized).await.is_err());
+        let database = crate::database::memory::InMemoryDatabase::new();
+        assert!(
+            PlaylistFileManager::sync_playlist_file(&database, &oversized)
+                .await
+                .is_err()
+        );
 
         let long_line = temp.path().join("long-line.m3u");
         std::fs::write(&long_line, vec![b'a'; MAX_PLAYLIST_LINE_BYTES + 1]).unwrap();
@@ -798,7 +1675,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_generic_playlist_import_materializes_http_stream() {
-        use crate::database::redb::RedbDatabase;
+        use crate::database::memory::InMemoryDatabase;
         use crate::database::DatabaseManager;
         use tempfile::tempdir;
 
@@ -807,13 +1684,12 @@ mod tests {
         let url = "https://cast1.asurahosting.com/proxy/julien/stream";
         fs::write(&playlist_path, format!("#EXTM3U\n{url}\n")).unwrap();
 
-        let database = RedbDatabase::new(temp.path().join("playlist.redb"))
-            .await
-            .unwrap();
+        let database = InMemoryDatabase::new();
         database.initialize().await.unwrap();
         let playlist_id = PlaylistFileManager::import_playlist(&database, &playlist_path, None)
             .await
-            .unwrap();
+            .unwrap()
+            .playlist_id;
 
         let tracks = database.get_playlist_tracks(playlist_id).await.unwrap();
         assert_eq!(tracks.len(), 1);
@@ -830,7 +1706,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_non_recursive_radio_root_uses_radio_importer() {
-        use crate::database::redb::RedbDatabase;
+        use crate::database::memory::InMemoryDatabase;
         use crate::database::DatabaseManager;
         use tempfile::tempdir;
 
@@ -845,9 +1721,7 @@ mod tests {
         )
         .unwrap();
 
-        let database = RedbDatabase::new(temp.path().join("radio.redb"))
-            .await
-            .unwrap();
+        let database = InMemoryDatabase::new();
         database.initialize().await.unwrap();
         PlaylistFileManager::scan_and_import_playlists(&database, &radio_dir)
             .await
@@ -867,17 +1741,517 @@ mod tests {
         let m3u_content = r#"#EXTM3U
 #EXTINF:123,Artist Name - Song Title
 /path/to/song1.mp3
-#EXTINF:456,Another Artist - Another Song
-/path/to/song2.mp3
+#EXTINF:-1 tvg-id="radio",Live, Radio
+#EXTVLCOPT:network-caching=1000
+http://radio.example/stream
 /path/to/song3.mp3
 "#;
 
-        // We can't test the full import without a database, but we can test parsing logic
-        // This would be expanded in a real test with a mock database
-        let lines: Vec<&str> = m3u_content.lines().collect();
-        assert!(lines[0] == "#EXTM3U");
-        assert!(lines[1].starts_with("#EXTINF"));
-        assert!(lines[2] == "/path/to/song1.mp3");
+        assert_eq!(
+            parse_m3u(m3u_content),
+            vec![
+                PlaylistEntry {
+                    location: "/path/to/song1.mp3".to_owned(),
+                    duration_seconds: Some(123),
+                    title: Some("Artist Name - Song Title".to_owned()),
+                },
+                PlaylistEntry {
+                    location: "http://radio.example/stream".to_owned(),
+                    duration_seconds: None,
+                    title: Some("Live, Radio".to_owned()),
+                },
+                PlaylistEntry {
+                    location: "/path/to/song3.mp3".to_owned(),
+                    duration_seconds: None,
+                    title: None,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn m3u8_reader_strips_bom_and_rejects_invalid_utf8() {
+        let temp = tempfile::tempdir().unwrap();
+        let with_bom = temp.path().join("bom.m3u8");
+        std::fs::write(&with_bom, "\u{feff}#EXTM3U\nCafé.mp3\n").unwrap();
+        assert_eq!(
+            read_playlist_text(&with_bom).await.unwrap(),
+            "#EXTM3U\nCafé.mp3\n"
+        );
+
+        let latin1 = b"Caf\xe9.mp3\n";
+        let legacy = temp.path().join("legacy.m3u");
+        std::fs::write(&legacy, latin1).unwrap();
+        assert_eq!(read_playlist_text(&legacy).await.unwrap(), "Café.mp3\n");
+        let strict = temp.path().join("strict.m3u8");
+        std::fs::write(&strict, latin1).unwrap();
+        assert!(read_playlist_text(&strict).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_m3u8_round_trip_skips_entries_missing_from_library() {
+        use crate::database::memory::InMemoryDatabase;
+        use crate::database::DatabaseManager;
+
+        let temp = tempfile::tempdir().unwrap();
+        let music = temp.path().join("Música");
+        fs::create_dir(&music).unwrap();
+        let names = ["Café del Mar.mp3", "東京.flac", "Ñandú 1.mp3"];
+        let database = InMemoryDatabase::new();
+        database.initialize().await.unwrap();
+        let mut files = Vec::new();
+        for name in names {
+            let path = music.join(name);
+            fs::write(&path, b"audio").unwrap();
+            files.push(MediaFile::new(path, 5, "audio/mpeg".to_owned()));
+        }
+        database.bulk_store_media_files(&files).await.unwrap();
+
+        let playlists = temp.path().join("playlists");
+        fs::create_dir(&playlists).unwrap();
+        let source = playlists.join("Favoritos.m3u8");
+        fs::write(
+            &source,
+            "#EXTM3U\n\
+             #EXTINF:215,Café del Mar\n\
+             ../Música/Café del Mar.mp3\n\
+             #EXTINF:60,Deleted\n\
+             ../Música/gone.mp3\n\
+             #EXTINF:-1,Radio Ñ\n\
+             https://radio.example/ñ\n\
+             ../Música/東京.flac\n\
+             ../M%C3%BAsica/%C3%91and%C3%BA%201.mp3\n",
+        )
+        .unwrap();
+
+        let import = PlaylistFileManager::import_playlist(&database, &source, None)
+            .await
+            .unwrap();
+        assert_eq!(import.imported, 4);
+        assert_eq!(import.unresolved, ["../Música/gone.mp3"]);
+        let playlist_id = import.playlist_id;
+        let tracks = database.get_playlist_tracks(playlist_id).await.unwrap();
+        let expected = vec![
+            music.join(names[0]).canonicalize().unwrap(),
+            PathBuf::from("https://radio.example/ñ"),
+            music.join(names[1]).canonicalize().unwrap(),
+            music.join(names[2]).canonicalize().unwrap(),
+        ];
+        assert_eq!(
+            tracks
+                .iter()
+                .map(|track| track.path.clone())
+                .collect::<Vec<_>>(),
+            expected
+        );
+        assert_eq!(tracks[1].filename, "Radio Ñ");
+        // `#EXTINF` fills in files without tags of their own, in this
+        // playlist only.
+        assert_eq!(tracks[0].title.as_deref(), Some("Café del Mar"));
+        assert_eq!(
+            tracks[0].duration,
+            Some(std::time::Duration::from_secs(215))
+        );
+        assert_eq!(tracks[2].title, None);
+        let library = database
+            .get_file_by_path(&music.join(names[0]))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!((library.title, library.duration), (None, None));
+
+        let exported = temp.path().join("exported.m3u8");
+        PlaylistFileManager::export_playlist(
+            &database,
+            playlist_id,
+            &exported,
+            PlaylistFormat::M3U8,
+            &PlaylistExportOptions::default(),
+        )
+        .await
+        .unwrap();
+        let content = fs::read_to_string(&exported).unwrap();
+        assert!(content.starts_with("#EXTM3U\n"));
+        assert!(content.contains("東京.flac"));
+        assert!(content.contains("#EXTINF:215,Unknown Artist - Café del Mar\n"));
+
+        let reimported = PlaylistFileManager::import_playlist(&database, &exported, None)
+            .await
+            .unwrap()
+            .playlist_id;
+        let round_trip = database.get_playlist_tracks(reimported).await.unwrap();
+        assert_eq!(
+            round_trip
+                .iter()
+                .map(|track| track.path.clone())
+                .collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    #[test]
+    fn relative_locations_walk_up_from_the_base_directory() {
+        let root = std::env::temp_dir().join("library");
+        assert_eq!(
+            relative_location(&root.join("Music/a.mp3"), &root.join("Playlists")).as_deref(),
+            Some("../Music/a.mp3")
+        );
+        assert_eq!(
+            relative_location(&root.join("Music/b c/d.mp3"), &root.join("Music")).as_deref(),
+            Some("b c/d.mp3")
+        );
+        assert_eq!(relative_location(Path::new("relative/a.mp3"), &root), None);
+        assert_eq!(
+            export_location(&root.join("#1 hit.mp3"), Some(&root)),
+            "./#1 hit.mp3"
+        );
+        assert_eq!(
+            export_location(Path::new("https://radio.example/live"), Some(&root)),
+            "https://radio.example/live"
+        );
+        assert_eq!(single_line("two\nlines"), "two lines");
+    }
+
+    #[tokio::test]
+    async fn relative_export_round_trips_awkward_file_names() {
+        use crate::database::memory::InMemoryDatabase;
+        use crate::database::DatabaseManager;
+
+        let temp = tempfile::tempdir().unwrap();
+        let music = temp.path().join("Music");
+        fs::create_dir(&music).unwrap();
+        let mut names = vec!["#1 hit.mp3", "100% pure.mp3"];
+        if cfg!(unix) {
+            names.extend(["trailing.mp3 ", "line\nbreak.mp3"]);
+        }
+        let database = InMemoryDatabase::new();
+        database.initialize().await.unwrap();
+        let playlist_id = database.create_playlist("Mix", None).await.unwrap();
+        let mut expected = Vec::new();
+        for (position, name) in names.iter().enumerate() {
+            let path = music.join(name);
+            fs::write(&path, b"audio").unwrap();
+            let path = path.canonicalize().unwrap();
+            let id = database
+                .store_media_file(&MediaFile::new(path.clone(), 5, "audio/mpeg".to_owned()))
+                .await
+                .unwrap();
+            database
+                .add_to_playlist(playlist_id, id, Some(position as u32))
+                .await
+                .unwrap();
+            expected.push(path);
+        }
+
+        let playlists = temp.path().join("Playlists");
+        fs::create_dir(&playlists).unwrap();
+        for format in [PlaylistFormat::M3U8, PlaylistFormat::PLS] {
+            let exported = playlists.join(format!("mix.{}", format.extension()));
+            database
+                .export_playlist_file(
+                    playlist_id,
+                    &exported,
+                    format,
+                    &PlaylistExportOptions {
+                        path_mode: PlaylistPathMode::Relative,
+                        base_dir: None,
+                        ..PlaylistExportOptions::default()
+                    },
+                )
+                .await
+                .unwrap();
+            let content = fs::read_to_string(&exported).unwrap();
+            assert!(content.contains("../Music/#1 hit.mp3"));
+            assert!(content.contains("../Music/100% pure.mp3"));
+            if cfg!(unix) {
+                // Edge whitespace would be trimmed on import, so that entry is a URI.
+                assert!(content.contains("/Music/trailing.mp3%20\n"), "{content}");
+                assert!(content.contains("/Music/line%0Abreak.mp3\n"), "{content}");
+            }
+
+            let reimported = PlaylistFileManager::import_playlist(&database, &exported, None)
+                .await
+                .unwrap()
+                .playlist_id;
+            let tracks = database.get_playlist_tracks(reimported).await.unwrap();
+            assert_eq!(
+                tracks
+                    .iter()
+                    .map(|track| track.path.clone())
+                    .collect::<Vec<_>>(),
+                expected,
+                "{format:?} round trip"
+            );
+        }
+
+        let error = PlaylistFileManager::render_playlist(
+            &database,
+            playlist_id,
+            PlaylistFormat::M3U8,
+            &PlaylistExportOptions {
+                path_mode: PlaylistPathMode::Relative,
+                base_dir: None,
+                ..PlaylistExportOptions::default()
+            },
+        )
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("base directory"));
+    }
+
+    #[tokio::test]
+    async fn xspf_round_trip_keeps_track_order_and_titles() {
+        use crate::database::memory::InMemoryDatabase;
+        use crate::database::DatabaseManager;
+
+        let temp = tempfile::tempdir().unwrap();
+        let music = temp.path().join("Music");
+        fs::create_dir(&music).unwrap();
+        let tagged = InMemoryDatabase::new();
+        tagged.initialize().await.unwrap();
+        let untagged = InMemoryDatabase::new();
+        untagged.initialize().await.unwrap();
+        let playlist_id = tagged.create_playlist("Rock & Roll", None).await.unwrap();
+        let mut expected = Vec::new();
+        for (position, (name, title)) in [
+            ("b side.mp3", "B <Side>"),
+            ("100% a.mp3", "Café & Crème"),
+            ("c.mp3", "Third"),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let path = music.join(name);
+            fs::write(&path, b"audio").unwrap();
+            let path = path.canonicalize().unwrap();
+            let mut file = MediaFile::new(path.clone(), 5, "audio/mpeg".to_owned());
+            untagged.store_media_file(&file).await.unwrap();
+            file.title = Some(title.to_owned());
+            file.duration = Some(std::time::Duration::from_millis(61_400));
+            let id = tagged.store_media_file(&file).await.unwrap();
+            tagged
+                .add_to_playlist(playlist_id, id, Some(position as u32))
+                .await
+                .unwrap();
+            expected.push((path, title.to_owned()));
+        }
+
+        let playlists = temp.path().join("Playlists");
+        fs::create_dir(&playlists).unwrap();
+        for (name, options) in [
+            ("absolute.xspf", PlaylistExportOptions::default()),
+            (
+                "relative.xspf",
+                PlaylistExportOptions {
+                    path_mode: PlaylistPathMode::Relative,
+                    ..PlaylistExportOptions::default()
+                },
+            ),
+        ] {
+            let exported = playlists.join(name);
+            tagged
+                .export_playlist_file(playlist_id, &exported, PlaylistFormat::XSPF, &options)
+                .await
+                .unwrap();
+            let content = fs::read_to_string(&exported).unwrap();
+            assert!(
+                content.contains("<title>Rock &amp; Roll</title>"),
+                "{content}"
+            );
+            assert!(content.contains("<duration>61400</duration>"), "{content}");
+            if options.path_mode == PlaylistPathMode::Relative {
+                assert!(content.contains("<location>../Music/100%25%20a.mp3</location>"));
+            } else {
+                assert!(content.contains("<location>file://"));
+            }
+
+            // The untagged library takes its titles and durations from the file.
+            let reimported = PlaylistFileManager::import_playlist(&untagged, &exported, None)
+                .await
+                .unwrap();
+            assert!(
+                reimported.unresolved.is_empty(),
+                "{:?}",
+                reimported.unresolved
+            );
+            let tracks = untagged
+                .get_playlist_tracks(reimported.playlist_id)
+                .await
+                .unwrap();
+            assert_eq!(
+                tracks
+                    .iter()
+                    .map(|track| (track.path.clone(), track.title.clone().unwrap()))
+                    .collect::<Vec<_>>(),
+                expected,
+                "{name}"
+            );
+            assert_eq!(tracks[0].duration, Some(std::time::Duration::from_secs(61)));
+        }
+    }
+
+    #[tokio::test]
+    async fn edited_playlist_files_resync_in_place_and_unchanged_ones_are_skipped() {
+        use crate::database::memory::InMemoryDatabase;
+        use crate::database::DatabaseManager;
+
+        let temp = tempfile::tempdir().unwrap();
+        let database = InMemoryDatabase::new();
+        database.initialize().await.unwrap();
+        for name in ["a.mp3", "b.mp3", "c.mp3"] {
+            let path = temp.path().join(name);
+            fs::write(&path, b"audio").unwrap();
+            database
+                .store_media_file(&MediaFile::new(
+                    path.canonicalize().unwrap(),
+                    5,
+                    "audio/mpeg".to_owned(),
+                ))
+                .await
+                .unwrap();
+        }
+        let user = database.create_playlist("Mine", None).await.unwrap();
+        let playlist = temp.path().join("mix.m3u");
+        let filenames = |tracks: Vec<MediaFile>| {
+            tracks
+                .into_iter()
+                .map(|track| track.filename)
+                .collect::<Vec<_>>()
+        };
+
+        fs::write(&playlist, "a.mp3\nb.mp3\n").unwrap();
+        let first = PlaylistFileManager::sync_playlist_file(&database, &playlist)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(
+            PlaylistFileManager::sync_playlist_file(&database, &playlist)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        fs::write(&playlist, "c.mp3\na.mp3\n").unwrap();
+        let second = PlaylistFileManager::sync_playlist_file(&database, &playlist)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(second.playlist_id, first.playlist_id);
+        assert_eq!(
+            filenames(
+                database
+                    .get_playlist_tracks(first.playlist_id)
+                    .await
+                    .unwrap()
+            ),
+            vec!["c.mp3", "a.mp3"]
+        );
+        let sources = database.get_playlist_sources().await.unwrap();
+        assert!(sources.contains_key(&first.playlist_id));
+        assert!(!sources.contains_key(&user));
+
+        // Deleting the file's playlist while the file stays puts it back on
+        // the next event even though the content did not change.
+        database
+            .remove_derived_content_by_source(&playlist)
+            .await
+            .unwrap();
+        assert!(
+            PlaylistFileManager::sync_playlist_file(&database, &playlist)
+                .await
+                .unwrap()
+                .is_some()
+        );
+        assert_eq!(database.get_playlists().await.unwrap().len(), 2);
+        assert!(database.get_playlist(user).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn latin1_exports_escape_what_latin1_cannot_hold() {
+        use crate::database::memory::InMemoryDatabase;
+        use crate::database::DatabaseManager;
+
+        let temp = tempfile::tempdir().unwrap();
+        let database = InMemoryDatabase::new();
+        database.initialize().await.unwrap();
+        let playlist_id = database.create_playlist("Mix", None).await.unwrap();
+        let mut expected = Vec::new();
+        for (position, (name, title)) in [("café.mp3", "Café"), ("東京.mp3", "東京")]
+            .into_iter()
+            .enumerate()
+        {
+            let path = temp.path().join(name);
+            fs::write(&path, b"audio").unwrap();
+            let path = path.canonicalize().unwrap();
+            let mut file = MediaFile::new(path.clone(), 5, "audio/mpeg".to_owned());
+            file.title = Some(title.to_owned());
+            let id = database.store_media_file(&file).await.unwrap();
+            database
+                .add_to_playlist(playlist_id, id, Some(position as u32))
+                .await
+                .unwrap();
+            expected.push(path);
+        }
+        let latin1 = PlaylistExportOptions {
+            encoding: PlaylistEncoding::Latin1,
+            ..PlaylistExportOptions::default()
+        };
+
+        let m3u = PlaylistFileManager::render_playlist(
+            &database,
+            playlist_id,
+            PlaylistFormat::M3U,
+            &latin1,
+        )
+        .await
+        .unwrap();
+        assert!(std::str::from_utf8(&m3u).is_err());
+        assert!(m3u.windows(5).any(|window| window == b"Caf\xE9\n"));
+        assert!(m3u.windows(3).any(|window| window == b"??\n"));
+        let exported = temp.path().join("legacy.m3u");
+        fs::write(&exported, &m3u).unwrap();
+        let reimported = PlaylistFileManager::import_playlist(&database, &exported, None)
+            .await
+            .unwrap();
+        let tracks = database
+            .get_playlist_tracks(reimported.playlist_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            tracks
+                .into_iter()
+                .map(|track| track.path)
+                .collect::<Vec<_>>(),
+            expected
+        );
+
+        let xspf = PlaylistFileManager::render_playlist(
+            &database,
+            playlist_id,
+            PlaylistFormat::XSPF,
+            &latin1,
+        )
+        .await
+        .unwrap();
+        let xspf: String = xspf.iter().map(|&byte| char::from(byte)).collect();
+        assert!(xspf.starts_with(r#"<?xml version="1.0" encoding="ISO-8859-1"?>"#));
+        assert!(xspf.contains("<title>&#26481;&#20140;</title>"), "{xspf}");
+        let exported = temp.path().join("legacy.xspf");
+        fs::write(&exported, encode_latin1(&xspf, true)).unwrap();
+        let reimported = PlaylistFileManager::import_playlist(&database, &exported, None)
+            .await
+            .unwrap();
+        assert_eq!(reimported.imported, 2);
+
+        let error = PlaylistFileManager::render_playlist(
+            &database,
+            playlist_id,
+            PlaylistFormat::M3U8,
+            &latin1,
+        )
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("UTF-8"));
     }
 
     #[test]
@@ -933,18 +2307,16 @@ Version=2
             track_number: Some(1),
             year: Some(2023),
             album_artist: Some("Test Artist".to_string()),
+            compilation: false,
+            content_hash: None,
+            missing_since: None,
             subtitle_available: false,
             created_at: std::time::SystemTime::now(),
             updated_at: std::time::SystemTime::now(),
         }];
 
-        let mut temp_file = NamedTempFile::new().unwrap();
-        temp_file.write_all(b"").unwrap(); // Ensure file exists
-
-        let result = PlaylistFileManager::export_m3u(&playlist, &tracks, temp_file.path()).await;
-        assert!(result.is_ok());
-
-        let content = fs::read_to_string(temp_file.path()).unwrap();
+        let locations = vec![export_location(&tracks[0].path, None)];
+        let content = PlaylistFileManager::render_m3u(&playlist, &tracks, &locations).unwrap();
         assert!(content.contains("#EXTM3U"));
         assert!(content.contains("#EXTINF:180,Test Artist - Test Song 1"));
         assert!(content.contains("/test/song1.mp3"));
@@ -0,0 +1,81 @@
+//! Playlist entry ordering shared by the storage backends.
+//!
+//! Every playlist mutation reads the current entries and writes the new ones
+//! inside one backend write transaction, so these functions only decide the
+//! resulting order. A client that reorders from a stale listing must not drop
+//! tracks another client added meanwhile, nor bring back ones it removed.
+
+use anyhow::{anyhow, Result};
+use std::collections::HashSet;
+
+/// Position for a track appended after `last`, the highest position in use.
+pub(crate) fn next_position(last: Option<u32>) -> Result<u32> {
+    match last {
+        None => Ok(0),
+        Some(last) => last
+            .checked_add(1)
+            .ok_or_else(|| anyhow!("playlist has no free position after {last}")),
+    }
+}
+
+/// Entries after inserting `file_id` at `position`: entries at or after it
+/// move up by one.
+pub(crate) fn inserted(
+    current: &[(u32, i64)],
+    position: u32,
+    file_id: i64,
+) -> Result<Vec<(u32, i64)>> {
+    let mut entries = Vec::with_capacity(current.len() + 1);
+    for &(entry_position, entry_file) in current {
+        if entry_position < position {
+            entries.push((entry_position, entry_file));
+        } else {
+            let shifted = entry_position
+                .checked_add(1)
+                .ok_or_else(|| anyhow!("playlist has no free position after {entry_position}"))?;
+            entries.push((shifted, entry_file));
+        }
+    }
+    entries.push((position, file_id));
+    entries.sort_unstable();
+    Ok(entries)
+}
+
+/// Entries after applying a requested order to `current` (position, file)
+/// pairs.
+///
+/// Each requested track takes over one current entry of the same file; a
+/// track that is no longer in the playlist is ignored. Current entries the
+/// request did not mention keep their relative order after the requested ones.
+pub(crate) fn reordered(
+    current: &[(u32, i64)],
+    requested: &[(i64, u32)],
+) -> Result<Vec<(u32, i64)>> {
+    let mut positions = HashSet::with_capacity(requested.len());
+    if let Some((_, position)) = requested
+        .iter()
+        .find(|(_, position)| !positions.insert(*position))
+    {
+        return Err(anyhow!("position {position} is requested more than once"));
+    }
+
+    let mut unclaimed = current
+        .iter()
+        .map(|&(_, file)| Some(file))
+        .collect::<Vec<_>>();
+    let mut entries = Vec::with_capacity(current.len());
+    for &(file_id, position) in requested {
+        if let Some(slot) = unclaimed.iter_mut().find(|slot| **slot == Some(file_id)) {
+            *slot = None;
+            entries.push((position, file_id));
+        }
+    }
+    entries.sort_unstable();
+    let mut last = entries.last().map(|&(position, _)| position);
+    for file_id in unclaimed.into_iter().flatten() {
+        let position = next_position(last)?;
+        entries.push((position, file_id));
+        last = Some(position);
+    }
+    Ok(entries)
+}
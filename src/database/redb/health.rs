@@ -42,7 +42,11 @@ impl RedbDatabase {
         })
         .await
         .context("ReDB backup task failed")??;
-        info!("Created database backup at {}", backup_path.display());
+        info!(
+            target: crate::logging::targets::DB,
+            "Created database backup at {}",
+            backup_path.display()
+        );
         Ok(())
     }
 
@@ -167,7 +171,7 @@ impl RedbDatabase {
                     for entry in files.iter()? {
                         let (id, bytes) = entry?;
                         let view = RedbReadSession::view(bytes.value())?;
-                        let path = view.path().to_owned();
+                        let path = view.path().into_owned();
                         if let Some((old_id, old_updated_at)) = winners.get(&path) {
                             if (view.updated_at_secs(), id.value()) > (*old_updated_at, *old_id) {
                                 remap.insert(*old_id, id.value());
@@ -240,6 +244,7 @@ impl RedbDatabase {
                     let mut directory_files = txn.open_multimap_table(DIRECTORY_FILES)?;
                     let mut ordered_files = txn.open_table(DIRECTORY_FILES_BY_NAME)?;
                     let mut directory_mime_counts = txn.open_table(DIRECTORY_MIME_COUNTS)?;
+                    let mut object_keys = txn.open_multimap_table(OBJECT_KEY_INDEX)?;
                     let mut artists = txn.open_multimap_table(ARTIST_INDEX)?;
                     let mut albums = txn.open_multimap_table(ALBUM_INDEX)?;
                     let mut genres = txn.open_multimap_table(GENRE_INDEX)?;
@@ -249,7 +254,7 @@ impl RedbDatabase {
                     for entry in files.iter()? {
                         let (id, bytes) = entry?;
                         let view = RedbReadSession::view(bytes.value())?;
-                        paths.insert(view.path(), id.value())?;
+                        paths.insert(&*view.path(), id.value())?;
                         Self::add_directory_membership(
                             &mut directory_paths,
                             &mut directory_records,
@@ -262,6 +267,7 @@ impl RedbDatabase {
                             &view,
                         )?;
                         Self::add_file_indexes(
+                            &mut object_keys,
                             &mut artists,
                             &mut albums,
                             &mut genres,
@@ -302,6 +308,16 @@ impl RedbDatabase {
                             table.remove(key)?;
                         }
                     }
+                    {
+                        let mut table = txn.open_table(PLAYLIST_ENTRY_INFO)?;
+                        let keys = table
+                            .iter()?
+                            .map(|entry| entry.map(|(key, _)| key.value()))
+                            .collect::<std::result::Result<Vec<_>, _>>()?;
+                        for key in keys {
+                            table.remove(key)?;
+                        }
+                    }
                     {
                         let mut table = txn.open_table(PLAYLIST_SOURCES)?;
                         let keys = table
@@ -375,6 +391,17 @@ impl RedbDatabase {
                     for playlist_id in stale_sources {
                         sources.remove(playlist_id)?;
                     }
+                    let mut entry_info = txn.open_table(PLAYLIST_ENTRY_INFO)?;
+                    let stale_info = entry_info
+                        .iter()?
+                        .map(|entry| entry.map(|(key, _)| key.value()))
+                        .collect::<std::result::Result<Vec<_>, _>>()?
+                        .into_iter()
+                        .filter(|key| !live_playlists.contains(&(((key >> 64) as u64) as i64)))
+                        .collect::<Vec<_>>();
+                    for key in stale_info {
+                        entry_info.remove(key)?;
+                    }
                     let mut reverse = txn.open_multimap_table(SOURCE_PLAYLISTS)?;
                     let keys = reverse
                         .iter()?
@@ -433,6 +460,7 @@ impl RedbDatabase {
                     issues: Vec::new(),
                     repair_attempted: true,
                     repair_successful: true,
+                    restored_from_backup: false,
                 };
                 Ok((health, total_files, total_size))
             })
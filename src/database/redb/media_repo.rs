@@ -8,8 +8,10 @@ impl RedbDatabase {
         R: Send + 'static,
         F: FnOnce(&mut RedbReadSession) -> Result<R> + Send + 'static,
     {
+        let permit = self.read_permit().await?;
         let database = Arc::clone(&self.db);
         tokio::task::spawn_blocking(move || {
+            let _permit = permit;
             let database = database
                 .read()
                 .map_err(|_| anyhow!("ReDB handle lock is poisoned"))?;
@@ -48,6 +50,28 @@ impl RedbDatabase {
         .await
     }
 
+    pub(super) async fn get_file_location_by_object_key_impl(
+        &self,
+        key: u64,
+    ) -> Result<Option<FileLocation>> {
+        self.execute_read(move |database| {
+            let transaction = database.begin_read()?;
+            let index = transaction.open_multimap_table(OBJECT_KEY_INDEX)?;
+            let files = transaction.open_table(FILES_TABLE)?;
+            for id in index.get(key)? {
+                let id = id?.value();
+                if let Some(bytes) = files.get(id)? {
+                    return RedbReadSession::view(bytes.value())?
+                        .to_file_location()
+                        .ok_or_else(|| anyhow!("stored media record {id} has no ID"))
+                        .map(Some);
+                }
+            }
+            Ok(None)
+        })
+        .await
+    }
+
     pub(super) async fn load_file_fingerprints_impl(&self) -> Result<Vec<FileFingerprint>> {
         let capacity = self.total_files.load(Ordering::Relaxed) as usize;
         self.execute_read(move |database| {
@@ -59,7 +83,7 @@ impl RedbDatabase {
                 let view = RedbReadSession::view(bytes.value())?;
                 fingerprints.push(FileFingerprint {
                     id: id.value(),
-                    path: PathBuf::from(view.path()),
+                    path: path_from_lossless_string(&view.path()),
                     size: view.size(),
                     modified: UNIX_EPOCH
                         + Duration::new(
@@ -68,6 +92,10 @@ impl RedbDatabase {
                         ),
                     created_at: UNIX_EPOCH + Duration::from_secs(view.created_at_secs()),
                     subtitle_available: view.subtitle_available(),
+                    content_hash: view.content_hash(),
+                    missing_since: view
+                        .missing_since_secs()
+                        .map(|secs| UNIX_EPOCH + Duration::from_secs(secs)),
                 });
             }
             Ok(fingerprints)
@@ -79,11 +107,9 @@ impl RedbDatabase {
         &self,
         root: &Path,
     ) -> Result<Vec<FileFingerprint>> {
-        let root_str = root
-            .to_string_lossy()
-            .trim_end_matches(['/', '\\'])
+        let root_str = path_to_lossless_string(&Self::canonical_path(root)?)
+            .trim_end_matches('/')
             .to_string();
-        let child_prefix = format!("{}{sep}", root_str, sep = std::path::MAIN_SEPARATOR);
         self.execute_read(move |database| {
             let transaction = database.begin_read()?;
             let files = transaction.open_table(FILES_TABLE)?;
@@ -92,11 +118,10 @@ impl RedbDatabase {
             for entry in paths.range(root_str.as_str()..)? {
                 let (path, id) = entry?;
                 let path = path.value();
-                if path != root_str && !path.starts_with(&child_prefix) {
-                    if !path.starts_with(&root_str) {
-                        break;
-                    }
-                    continue;
+                match subtree_key(&root_str, path) {
+                    SubtreeKey::Inside => {}
+                    SubtreeKey::Sibling => continue,
+                    SubtreeKey::Past => break,
                 }
                 let Some(bytes) = files.get(id.value())? else {
                     continue;
@@ -104,7 +129,7 @@ impl RedbDatabase {
                 let view = RedbReadSession::view(bytes.value())?;
                 fingerprints.push(FileFingerprint {
                     id: id.value(),
-                    path: PathBuf::from(view.path()),
+                    path: path_from_lossless_string(&view.path()),
                     size: view.size(),
                     modified: UNIX_EPOCH
                         + Duration::new(
@@ -113,6 +138,10 @@ impl RedbDatabase {
                         ),
                     created_at: UNIX_EPOCH + Duration::from_secs(view.created_at_secs()),
                     subtitle_available: view.subtitle_available(),
+                    content_hash: view.content_hash(),
+                    missing_since: view
+                        .missing_since_secs()
+                        .map(|secs| UNIX_EPOCH + Duration::from_secs(secs)),
                 });
             }
             Ok(fingerprints)
@@ -188,7 +217,7 @@ impl RedbDatabase {
     }
 
     pub(super) async fn get_files_in_directory_impl(&self, dir: &Path) -> Result<Vec<MediaFile>> {
-        let dir_key = Self::canonical_path(dir)?.to_string_lossy().to_string();
+        let dir_key = path_to_lossless_string(&Self::canonical_path(dir)?).into_owned();
         self.execute_read(move |database| {
             let read_txn = database.begin_read()?;
             let files_table = read_txn.open_table(FILES_TABLE)?;
@@ -222,7 +251,7 @@ impl RedbDatabase {
         media_type_filter: &str,
     ) -> Result<(Vec<MediaDirectory>, Vec<MediaFile>)> {
         let canonical_parent = Self::canonical_path(parent_path)?;
-        let raw_parent_str = canonical_parent.to_string_lossy().to_string();
+        let raw_parent_str = path_to_lossless_string(&canonical_parent).into_owned();
 
         // Strip trailing slash if present, unless it's the root path "/"
         let parent_str = if raw_parent_str.len() > 1
@@ -234,6 +263,7 @@ impl RedbDatabase {
         };
 
         debug!(
+            target: crate::logging::targets::DB,
             "get_directory_listing: querying for parent_path='{}' (raw='{}'), filter='{}'",
             parent_str,
             parent_path.to_string_lossy(),
@@ -256,15 +286,25 @@ impl RedbDatabase {
                 return Ok((Vec::new(), Vec::new()));
             };
 
+            let count_family = if media_type_filter.is_empty() {
+                "*"
+            } else {
+                media_type_filter.as_str()
+            };
             let mut files = Vec::new();
 
-            // Get files in this directory
-            let file_ids = directory_files
-                .get(parent_id)?
-                .map(|value| value.map(|value| value.value()))
-                .collect::<std::result::Result<Vec<_>, _>>()?;
+            let file_ids =
+                if Self::may_hold_files(&directory_mime_counts, parent_id, &media_type_filter)? {
+                    directory_files
+                        .get(parent_id)?
+                        .map(|value| value.map(|value| value.value()))
+                        .collect::<std::result::Result<Vec<_>, _>>()?
+                } else {
+                    Vec::new()
+                };
 
             debug!(
+                target: crate::logging::targets::DB,
                 "get_directory_listing: found {} file IDs for dir '{}'",
                 file_ids.len(),
                 parent_str
@@ -272,54 +312,32 @@ impl RedbDatabase {
 
             for file_id in file_ids {
                 if let Some(data) = files_table.get(file_id)? {
-                    let file = Self::deserialize_media_file(data.value())?;
+                    // Check the archived record before paying for a full
+                    // deserialization of files the filter drops.
                     if media_type_filter.is_empty()
-                        || file.mime_type.starts_with(&media_type_filter)
+                        || RedbReadSession::view(data.value())?
+                            .mime_type()
+                            .starts_with(&media_type_filter)
                     {
-                        files.push(file);
+                        files.push(Self::deserialize_media_file(data.value())?);
                     }
                 }
             }
 
-            let count_family = if media_type_filter.is_empty() {
-                "*"
-            } else {
-                media_type_filter.as_str()
-            };
-            let mut directories = Vec::new();
-            for child in directory_children.get(parent_id)? {
-                let child_id = child?.value();
-                let count_key = Self::mime_count_key(child_id, count_family);
-                if directory_mime_counts
-                    .get(count_key.as_str())?
-                    .is_none_or(|count| count.value() == 0)
-                {
-                    continue;
-                }
-                if let Some(path) = directory_records.get(child_id)? {
-                    let path = path.value().to_owned();
-                    let name = PathBuf::from(&path)
-                        .file_name()
-                        .map(|n| n.to_string_lossy().to_string())
-                        .unwrap_or_default();
-                    directories.push(MediaDirectory {
-                        path: PathBuf::from(&path),
-                        name,
-                    });
-                }
-            }
-
-            // Sort subdirectories case-insensitively using natural sort
-            directories.sort_by_cached_key(|directory| {
-                crate::natural_sort::natural_sort_key(&directory.name.to_lowercase())
-            });
+            let directories = Self::child_directories_with_media(
+                &directory_children,
+                &directory_records,
+                &directory_mime_counts,
+                parent_id,
+                count_family,
+            )?;
 
             // Sort files by track number if available, then case-insensitively by filename using natural sort
             files.sort_by(|a, b| match (a.track_number, b.track_number) {
                 (Some(ta), Some(tb)) if ta != tb => ta.cmp(&tb),
                 _ => {
-                    let a_key = crate::natural_sort::natural_sort_key(&a.filename.to_lowercase());
-                    let b_key = crate::natural_sort::natural_sort_key(&b.filename.to_lowercase());
+                    let a_key = crate::natural_sort::collation_key(&a.filename);
+                    let b_key = crate::natural_sort::collation_key(&b.filename);
                     a_key.cmp(&b_key)
                 }
             });
@@ -333,13 +351,24 @@ impl RedbDatabase {
         &self,
         existing_paths: &[PathBuf],
     ) -> Result<usize> {
-        let existing_set: HashSet<String> = existing_paths
+        let existing_set = existing_paths
             .iter()
-            .map(|p| p.to_string_lossy().to_string())
-            .collect();
+            .map(|path| {
+                Self::canonical_path(path).map(|path| path_to_lossless_string(&path).into_owned())
+            })
+            .collect::<Result<HashSet<_>>>()?;
+        self.cleanup_missing_canonical_files_impl(existing_set)
+            .await
+    }
 
+    /// Remove every indexed path that is not in `existing_set`, whose entries
+    /// are already canonical.
+    async fn cleanup_missing_canonical_files_impl(
+        &self,
+        existing_set: HashSet<String>,
+    ) -> Result<usize> {
         // First, collect all paths to remove
-        let paths_to_remove: Vec<PathBuf> = self
+        let paths_to_remove = self
             .execute_read(move |database| {
                 let read_txn = database.begin_read()?;
                 let path_index = read_txn.open_table(PATH_INDEX)?;
@@ -348,7 +377,7 @@ impl RedbDatabase {
                 for entry in path_index.iter()? {
                     let (key, _) = entry?;
                     if !existing_set.contains(key.value()) {
-                        paths.push(PathBuf::from(key.value()));
+                        paths.push(key.value().to_owned());
                     }
                 }
                 Ok(paths)
@@ -356,11 +385,12 @@ impl RedbDatabase {
             .await?;
 
         // Use batch removal
-        self.bulk_remove_media_files_impl(&paths_to_remove).await
+        self.bulk_remove_canonical_path_strings_impl(paths_to_remove)
+            .await
     }
 
     pub(super) async fn get_file_by_path_impl(&self, path: &Path) -> Result<Option<MediaFile>> {
-        let path_str = Self::canonical_path(path)?.to_string_lossy().to_string();
+        let path_str = path_to_lossless_string(&Self::canonical_path(path)?).into_owned();
         self.execute_read(move |database| {
             let read_txn = database.begin_read()?;
             let path_index = read_txn.open_table(PATH_INDEX)?;
@@ -417,6 +447,7 @@ impl RedbDatabase {
                     count,
                 });
             }
+            crate::database::sort_categories(&mut categories);
             Ok(categories)
         })
         .await
@@ -470,6 +501,7 @@ impl RedbDatabase {
                     });
                 }
             }
+            crate::database::sort_categories(&mut categories);
             Ok(categories)
         })
         .await
@@ -501,6 +533,7 @@ impl RedbDatabase {
                     count,
                 });
             }
+            crate::database::sort_categories(&mut categories);
             Ok(categories)
         })
         .await
@@ -563,6 +596,7 @@ impl RedbDatabase {
                     count,
                 });
             }
+            crate::database::sort_categories(&mut categories);
             Ok(categories)
         })
         .await
@@ -683,7 +717,7 @@ impl RedbDatabase {
         let paths = paths
             .iter()
             .map(|path| {
-                Self::canonical_path(path).map(|value| value.to_string_lossy().into_owned())
+                Self::canonical_path(path).map(|value| path_to_lossless_string(&value).into_owned())
             })
             .collect::<Result<Vec<_>>>()?;
         self.execute_read(move |database| {
@@ -725,13 +759,6 @@ impl RedbDatabase {
         paths_are_canonical: bool,
     ) -> Result<Vec<i64>> {
         let inputs = files.to_vec();
-        let candidate_ids = inputs
-            .iter()
-            .map(|file| {
-                file.id
-                    .unwrap_or_else(|| self.next_file_id.fetch_add(1, Ordering::SeqCst))
-            })
-            .collect::<Vec<_>>();
         let next_directory_id = Arc::clone(&self.next_directory_id);
         let (ids, added_files, replaced_size, stored_size) = self
             .execute_write(move |database| {
@@ -753,6 +780,7 @@ impl RedbDatabase {
                     let mut ordered_files = write_txn.open_table(DIRECTORY_FILES_BY_NAME)?;
                     let mut directory_mime_counts = write_txn.open_table(DIRECTORY_MIME_COUNTS)?;
 
+                    let mut object_key_index = write_txn.open_multimap_table(OBJECT_KEY_INDEX)?;
                     let mut artist_index = write_txn.open_multimap_table(ARTIST_INDEX)?;
                     let mut album_index = write_txn.open_multimap_table(ALBUM_INDEX)?;
                     let mut genre_index = write_txn.open_multimap_table(GENRE_INDEX)?;
@@ -761,16 +789,21 @@ impl RedbDatabase {
                         write_txn.open_multimap_table(ALBUM_ARTIST_INDEX)?;
                     let mut archive_scratch: rkyv::util::AlignedVec = rkyv::util::AlignedVec::new();
 
-                    for (input, candidate_id) in inputs.iter().zip(candidate_ids) {
+                    for input in &inputs {
                         let file = if paths_are_canonical {
                             input.clone()
                         } else {
                             Self::canonical_file(input)?
                         };
-                        let path_str = file.path.to_string_lossy().to_string();
+                        let path_str = path_to_lossless_string(&file.path).into_owned();
                         let existing_path_id =
                             path_index.get(path_str.as_str())?.map(|v| v.value());
-                        let file_id = existing_path_id.or(file.id).unwrap_or(candidate_id);
+                        let file_id = match existing_path_id.or(file.id) {
+                            Some(id) => id,
+                            None => {
+                                media_file_id(&path_str, |id| Ok(files_table.get(id)?.is_some()))?
+                            }
+                        };
                         ids.push(file_id);
 
                         let mut file_with_id = file.clone();
@@ -789,6 +822,7 @@ impl RedbDatabase {
                                 &old,
                             )?;
                             Self::remove_file_indexes(
+                                &mut object_key_index,
                                 &mut artist_index,
                                 &mut album_index,
                                 &mut genre_index,
@@ -797,8 +831,8 @@ impl RedbDatabase {
                                 file_id,
                                 &old,
                             )?;
-                            if old.path() != path_str {
-                                path_index.remove(old.path())?;
+                            if *old.path() != *path_str {
+                                path_index.remove(&*old.path())?;
                             }
                             replaced_size = replaced_size.saturating_add(old.size());
                             true
@@ -831,6 +865,7 @@ impl RedbDatabase {
                             &file_with_id,
                         )?;
                         Self::add_file_indexes(
+                            &mut object_key_index,
                             &mut artist_index,
                             &mut album_index,
                             &mut genre_index,
@@ -859,7 +894,7 @@ impl RedbDatabase {
                 });
         }
 
-        debug!("Bulk stored {} media files", ids.len());
+        debug!(target: crate::logging::targets::DB, "Bulk stored {} media files", ids.len());
         Ok(ids)
     }
 
@@ -885,7 +920,9 @@ impl RedbDatabase {
     pub(super) async fn bulk_remove_media_files_impl(&self, paths: &[PathBuf]) -> Result<usize> {
         let paths = paths
             .iter()
-            .map(|path| Self::canonical_path(path).map(|path| path.to_string_lossy().to_string()))
+            .map(|path| {
+                Self::canonical_path(path).map(|path| path_to_lossless_string(&path).into_owned())
+            })
             .collect::<Result<Vec<_>>>()?;
         self.bulk_remove_canonical_path_strings_impl(paths).await
     }
@@ -896,7 +933,7 @@ impl RedbDatabase {
     ) -> Result<usize> {
         let paths = paths
             .iter()
-            .map(|path| path.to_string_lossy().to_string())
+            .map(|path| path_to_lossless_string(path).into_owned())
             .collect();
         self.bulk_remove_canonical_path_strings_impl(paths).await
     }
@@ -956,14 +993,13 @@ impl RedbDatabase {
                 Some(current.saturating_sub(removed_size))
             });
 
-        debug!("Bulk removed {} media files", removed);
+        debug!(target: crate::logging::targets::DB, "Bulk removed {} media files", removed);
         Ok(removed)
     }
 
     pub(super) async fn remove_media_under_path_impl(&self, path: &Path) -> Result<RemovalSummary> {
         let canonical = Self::canonical_path(path)?;
-        let prefix = canonical
-            .to_string_lossy()
+        let prefix = path_to_lossless_string(&canonical)
             .trim_end_matches('/')
             .to_string();
         let (mut summary, removed, removed_size) = self
@@ -1006,7 +1042,7 @@ impl RedbDatabase {
                         }
                         if let Some(data) = files_table.get(id)? {
                             let view = RedbReadSession::view(data.value())?;
-                            if let Some(parent) = Path::new(view.path()).parent() {
+                            if let Some(parent) = Path::new(&*view.path()).parent() {
                                 summary.affected_parents.push(parent.to_path_buf());
                             }
                             summary
@@ -1014,7 +1050,7 @@ impl RedbDatabase {
                                 .insert(Self::mime_family(view.mime_type()));
                             let snapshot = IndexSnapshot::from_view(&view)
                                 .ok_or_else(|| anyhow!("stored media record {id} has no ID"))?;
-                            files.push((view.path().to_owned(), id, snapshot));
+                            files.push((view.path().into_owned(), id, snapshot));
                         }
                     }
                 }
@@ -1032,8 +1068,10 @@ impl RedbDatabase {
                         summary.affected_parents.dedup();
                     }
                     debug!(
+                        target: crate::logging::targets::DB,
                         "Defensively pruned {} directory records under {}",
-                        pruned_directories, prefix
+                        pruned_directories,
+                        prefix
                     );
                 }
                 transaction.commit()?;
@@ -1060,12 +1098,10 @@ impl RedbDatabase {
         canonical_prefix: &str,
     ) -> Result<Vec<MediaFile>> {
         let mut files = Vec::new();
-        let canonical = Self::canonical_path(Path::new(canonical_prefix))?;
-        let prefix = canonical
-            .to_string_lossy()
+        let canonical = Self::canonical_path(&path_from_lossless_string(canonical_prefix))?;
+        let prefix = path_to_lossless_string(&canonical)
             .trim_end_matches('/')
             .to_string();
-        let child = format!("{prefix}/");
 
         self.execute_read(move |database| {
             let read_txn = database.begin_read()?;
@@ -1074,12 +1110,14 @@ impl RedbDatabase {
 
             for result in path_index.range(prefix.as_str()..)? {
                 let (key, value) = result?;
-                if key.value() == prefix || key.value().starts_with(&child) {
-                    if let Some(data) = files_table.get(value.value())? {
-                        files.push(Self::deserialize_media_file(data.value())?);
+                match subtree_key(&prefix, key.value()) {
+                    SubtreeKey::Inside => {
+                        if let Some(data) = files_table.get(value.value())? {
+                            files.push(Self::deserialize_media_file(data.value())?);
+                        }
                     }
-                } else {
-                    break;
+                    SubtreeKey::Sibling => {}
+                    SubtreeKey::Past => break,
                 }
             }
 
@@ -1092,8 +1130,8 @@ impl RedbDatabase {
         &self,
         canonical_parent_path: &str,
     ) -> Result<Vec<MediaDirectory>> {
-        let canonical = Self::canonical_path(Path::new(canonical_parent_path))?;
-        let canonical_parent_path = canonical.to_string_lossy().to_string();
+        let canonical = Self::canonical_path(&path_from_lossless_string(canonical_parent_path))?;
+        let canonical_parent_path = path_to_lossless_string(&canonical).into_owned();
 
         self.execute_read(move |database| {
             let read_txn = database.begin_read()?;
@@ -1108,48 +1146,85 @@ impl RedbDatabase {
                 return Ok(Vec::new());
             };
 
-            let mut result = Vec::new();
-            for child in children.get(parent_id)? {
-                let child_id = child?.value();
-                let count_key = Self::mime_count_key(child_id, "*");
-                if counts
-                    .get(count_key.as_str())?
-                    .is_none_or(|value| value.value() == 0)
-                {
-                    continue;
-                }
-                if let Some(path) = records.get(child_id)? {
-                    let path = path.value().to_owned();
-                    let name = PathBuf::from(&path)
-                        .file_name()
-                        .map(|n| n.to_string_lossy().to_string())
-                        .unwrap_or_default();
-                    result.push(MediaDirectory {
-                        path: PathBuf::from(&path),
-                        name,
-                    });
-                }
-            }
-            Ok(result)
+            Self::child_directories_with_media(&children, &records, &counts, parent_id, "*")
         })
         .await
     }
 
+    fn has_media(
+        counts: &redb::ReadOnlyTable<&str, u64>,
+        directory_id: u64,
+        mime_family: &str,
+    ) -> Result<bool> {
+        let key = Self::mime_count_key(directory_id, mime_family);
+        Ok(counts
+            .get(key.as_str())?
+            .is_some_and(|value| value.value() > 0))
+    }
+
+    /// Whether the directory's own files can match `mime_filter`. The
+    /// recursive count of a family covers the directory's own files, so
+    /// zero rules them out without reading any record. Filters narrower than
+    /// a family are not counted and always need the records.
+    pub(super) fn may_hold_files(
+        counts: &redb::ReadOnlyTable<&str, u64>,
+        directory_id: u64,
+        mime_filter: &str,
+    ) -> Result<bool> {
+        if mime_filter.is_empty() {
+            return Self::has_media(counts, directory_id, "*");
+        }
+        if Self::mime_family(mime_filter) != mime_filter || !mime_filter.ends_with('/') {
+            return Ok(true);
+        }
+        Self::has_media(counts, directory_id, mime_filter)
+    }
+
+    /// Direct children of `parent_id` with media of `mime_family` (`"*"` for
+    /// any) somewhere below them, in natural name order. Only the recursive
+    /// mime counts are consulted; no file record is read.
+    fn child_directories_with_media(
+        children: &redb::ReadOnlyMultimapTable<u64, u64>,
+        records: &redb::ReadOnlyTable<u64, &str>,
+        counts: &redb::ReadOnlyTable<&str, u64>,
+        parent_id: u64,
+        mime_family: &str,
+    ) -> Result<Vec<MediaDirectory>> {
+        let mut directories = Vec::new();
+        for child in children.get(parent_id)? {
+            let child_id = child?.value();
+            if !Self::has_media(counts, child_id, mime_family)? {
+                continue;
+            }
+            if let Some(path) = records.get(child_id)? {
+                let path = path.value();
+                directories.push(MediaDirectory {
+                    path: path_from_lossless_string(path),
+                    name: Self::directory_name(path).to_owned(),
+                });
+            }
+        }
+        directories
+            .sort_by_cached_key(|directory| crate::natural_sort::collation_key(&directory.name));
+        Ok(directories)
+    }
+
     pub(super) async fn batch_cleanup_missing_files_impl(
         &self,
         existing_canonical_paths: &HashSet<String>,
     ) -> Result<usize> {
-        let paths_vec: Vec<PathBuf> = existing_canonical_paths.iter().map(PathBuf::from).collect();
-        self.cleanup_missing_files_impl(&paths_vec).await
+        self.cleanup_missing_canonical_files_impl(existing_canonical_paths.clone())
+            .await
     }
 
     pub(super) async fn database_native_cleanup_impl(
         &self,
         existing_canonical_paths: &[String],
     ) -> Result<usize> {
-        let existing_set: HashSet<String> = existing_canonical_paths.iter().cloned().collect();
-        let paths_vec: Vec<PathBuf> = existing_set.iter().map(PathBuf::from).collect();
-        self.cleanup_missing_files_impl(&paths_vec).await
+        self.cleanup_missing_canonical_files_impl(
+            existing_canonical_paths.iter().cloned().collect(),
+        )
+        .await
     }
 
     pub(super) async fn get_filtered_direct_subdirectories_impl(
@@ -1157,9 +1232,34 @@ impl RedbDatabase {
         canonical_parent_path: &str,
         mime_filter: &str,
     ) -> Result<Vec<MediaDirectory>> {
-        Ok(self
-            .get_directory_listing(Path::new(canonical_parent_path), mime_filter)
-            .await?
-            .0)
+        let canonical = Self::canonical_path(&path_from_lossless_string(canonical_parent_path))?;
+        let canonical_parent_path = path_to_lossless_string(&canonical).into_owned();
+        let mime_family = if mime_filter.is_empty() {
+            "*".to_owned()
+        } else {
+            mime_filter.to_owned()
+        };
+
+        self.execute_read(move |database| {
+            let read_txn = database.begin_read()?;
+            let paths = read_txn.open_table(DIRECTORY_PATH_INDEX)?;
+            let records = read_txn.open_table(DIRECTORY_RECORDS)?;
+            let children = read_txn.open_multimap_table(DIRECTORY_CHILDREN)?;
+            let counts = read_txn.open_table(DIRECTORY_MIME_COUNTS)?;
+            let Some(parent_id) = paths
+                .get(canonical_parent_path.as_str())?
+                .map(|value| value.value())
+            else {
+                return Ok(Vec::new());
+            };
+            Self::child_directories_with_media(
+                &children,
+                &records,
+                &counts,
+                parent_id,
+                &mime_family,
+            )
+        })
+        .await
     }
 }
@@ -10,6 +10,7 @@ use redb::{
     Database, MultimapTableDefinition, ReadableDatabase, ReadableMultimapTable, ReadableTable,
     TableDefinition,
 };
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::pin::Pin;
@@ -18,14 +19,16 @@ use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tracing::{debug, info};
 
+use crate::platform::filesystem::{path_from_lossless_string, path_to_lossless_string};
 use crate::platform::DatabaseError;
 
 use super::{
-    DatabaseBackend, DatabaseHealth, DatabaseManager, DatabaseReadSession, DatabaseStats,
-    DirectoryView, FileFingerprint, FileLocation, HealthRepository, IndexSnapshot, MediaDirectory,
-    MediaFile, MediaFileQuery, MediaFileView, MediaRepository, MusicCategory, MusicCategoryType,
-    Playlist, PlaylistRepository, PlaylistView, RemovalSummary, RootAvailability, SourceMediaEntry,
-    StatsRepository, VisitSummary,
+    contains_ignore_ascii_case, media_file_id, media_object_key, playlist_order, DatabaseBackend,
+    DatabaseHealth, DatabaseManager, DatabaseReadSession, DatabaseStats, DirectoryView,
+    FileFingerprint, FileLocation, HealthRepository, IndexSnapshot, MediaDirectory, MediaFile,
+    MediaFileQuery, MediaFileView, MediaRepository, MediaTypeCounts, MusicCategory,
+    MusicCategoryType, Playlist, PlaylistEntryInfo, PlaylistRepository, PlaylistView,
+    RemovalSummary, RootAvailability, SourceMediaEntry, StatsRepository, VisitSummary,
 };
 
 include!("schema.rs");
@@ -34,6 +37,7 @@ mod health;
 mod media_repo;
 mod playlist_repo;
 mod root_repo;
+mod settings_repo;
 mod stats;
 
 #[derive(Debug)]
@@ -60,23 +64,48 @@ pub fn is_incompatible_database(error: &anyhow::Error) -> bool {
         .is_some()
 }
 
+/// Read transactions that may run at once unless configured otherwise.
+pub const DEFAULT_MAX_READERS: usize = 16;
+
+/// Where a key met while scanning a path-keyed table upwards from `root`
+/// stands relative to the subtree at `root`.
+#[derive(Debug, PartialEq, Eq)]
+enum SubtreeKey {
+    Inside,
+    /// A name such as `/music b` that extends `root` without being under
+    /// it. These sort between `/music` and `/music/`, so the scan goes on.
+    Sibling,
+    /// Every later key is outside the subtree too.
+    Past,
+}
+
+fn subtree_key(root: &str, key: &str) -> SubtreeKey {
+    match key.strip_prefix(root.trim_end_matches('/')) {
+        Some(rest) if key == root || rest.starts_with('/') => SubtreeKey::Inside,
+        Some(_) => SubtreeKey::Sibling,
+        None => SubtreeKey::Past,
+    }
+}
+
 /// RedbDatabase - ACID-compliant embedded database
 pub struct RedbDatabase {
     db: Arc<std::sync::RwLock<Database>>,
     db_path: PathBuf,
-    next_file_id: AtomicI64,
     next_playlist_id: AtomicI64,
     next_directory_id: Arc<AtomicU64>,
     total_files: AtomicU64,
     total_size: AtomicU64,
     mutation_lock: tokio::sync::Mutex<()>,
+    /// Bounds concurrent read transactions, each of which holds a blocking
+    /// thread. Writes are serialized by `mutation_lock` instead and never
+    /// wait for these: readers work on a snapshot and do not block the writer.
+    read_permits: Arc<tokio::sync::Semaphore>,
 }
 
 impl std::fmt::Debug for RedbDatabase {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RedbDatabase")
             .field("db_path", &self.db_path)
-            .field("next_file_id", &self.next_file_id.load(Ordering::Relaxed))
             .field(
                 "next_playlist_id",
                 &self.next_playlist_id.load(Ordering::Relaxed),
@@ -90,26 +119,11 @@ impl DatabaseBackend for RedbDatabase {
     async fn open(path: PathBuf, cache_size_mb: usize) -> Result<Self> {
         Self::new_with_cache(path, cache_size_mb).await
     }
-
-    fn backend_name() -> &'static str {
-        "redb"
-    }
 }
 
 impl RedbDatabase {
     fn canonical_path(path: &Path) -> Result<PathBuf> {
-        let raw = path.to_string_lossy();
-        if raw
-            .get(..7)
-            .is_some_and(|scheme| scheme.eq_ignore_ascii_case("http://"))
-            || raw
-                .get(..8)
-                .is_some_and(|scheme| scheme.eq_ignore_ascii_case("https://"))
-        {
-            return Ok(path.to_path_buf());
-        }
-        let normalizer = crate::platform::filesystem::create_platform_path_normalizer();
-        Ok(PathBuf::from(normalizer.to_canonical(path)?))
+        super::canonical_media_path(path)
     }
 
     fn canonical_file(file: &MediaFile) -> Result<MediaFile> {
@@ -119,9 +133,7 @@ impl RedbDatabase {
     }
 
     fn mime_family(mime: &str) -> String {
-        mime.split_once('/')
-            .map(|(v, _)| format!("{v}/"))
-            .unwrap_or_else(|| mime.to_string())
+        super::mime_family(mime)
     }
     /// Create a new RedbDatabase at the specified path
     pub async fn new(path: PathBuf) -> Result<Self> {
@@ -237,6 +249,7 @@ impl RedbDatabase {
         };
 
         info!(
+            target: crate::logging::targets::DB,
             "Opened RedbDatabase at {} (max_file_id={}, max_playlist_id={}, files={}, size={} bytes)",
             path.display(),
             max_file_id,
@@ -248,15 +261,29 @@ impl RedbDatabase {
         Ok(Self {
             db: Arc::new(std::sync::RwLock::new(db)),
             db_path: path,
-            next_file_id: AtomicI64::new(max_file_id + 1),
             next_playlist_id: AtomicI64::new(max_playlist_id + 1),
             next_directory_id: Arc::new(AtomicU64::new(max_directory_id + 1)),
             total_files: AtomicU64::new(total_files_count),
             total_size: AtomicU64::new(total_size_sum),
             mutation_lock: tokio::sync::Mutex::new(()),
+            read_permits: Arc::new(tokio::sync::Semaphore::new(DEFAULT_MAX_READERS)),
         })
     }
 
+    /// Allow at most `readers` read transactions at once; further reads wait
+    /// for one to finish.
+    pub fn with_max_readers(mut self, readers: usize) -> Self {
+        self.read_permits = Arc::new(tokio::sync::Semaphore::new(readers.max(1)));
+        self
+    }
+
+    async fn read_permit(&self) -> Result<tokio::sync::OwnedSemaphorePermit> {
+        Arc::clone(&self.read_permits)
+            .acquire_owned()
+            .await
+            .context("ReDB read permits closed")
+    }
+
     /// Materialize an owned media record for legacy/ownership-requiring callers.
     fn deserialize_media_file(data: &[u8]) -> Result<MediaFile> {
         let serializable = rkyv::from_bytes::<MediaFileSerializable, rkyv::rancor::Error>(data)
@@ -275,13 +302,27 @@ impl RedbDatabase {
         Ok(serializable.into())
     }
 
+    fn serialize_playlist_entry_info(info: &PlaylistEntryInfo) -> Result<rkyv::util::AlignedVec> {
+        rkyv::to_bytes::<rkyv::rancor::Error>(&PlaylistEntryInfoSerializable::from(info))
+            .map_err(|error| anyhow!("Failed to archive playlist entry info: {error}"))
+    }
+
+    fn deserialize_playlist_entry_info(data: &[u8]) -> Result<PlaylistEntryInfo> {
+        let serializable =
+            rkyv::from_bytes::<PlaylistEntryInfoSerializable, rkyv::rancor::Error>(data)
+                .map_err(|error| anyhow!("Failed to deserialize playlist entry info: {error}"))?;
+        Ok(serializable.into())
+    }
+
     async fn execute_read<R, F>(&self, operation: F) -> Result<R>
     where
         R: Send + 'static,
         F: FnOnce(&Database) -> Result<R> + Send + 'static,
     {
+        let permit = self.read_permit().await?;
         let database = Arc::clone(&self.db);
         tokio::task::spawn_blocking(move || {
+            let _permit = permit;
             let database = database
                 .read()
                 .map_err(|_| anyhow!("ReDB handle lock is poisoned"))?;
@@ -310,36 +351,15 @@ impl RedbDatabase {
 
     /// Get the directory key for a path
     fn get_dir_key(path: &Path) -> String {
-        path.parent()
-            .map(|p| {
-                let s = p.to_string_lossy().to_string().replace('\\', "/");
-                if cfg!(target_os = "windows") {
-                    s.to_lowercase()
-                } else {
-                    s
-                }
-            })
-            .unwrap_or_default()
+        super::directory_key(path)
     }
 
     fn get_dir_key_str(path: &str) -> String {
-        Self::get_dir_key(Path::new(path))
+        Self::get_dir_key(&path_from_lossless_string(path))
     }
 
     fn parent_directory(path: &str) -> Option<String> {
-        let parent = Path::new(path)
-            .parent()?
-            .to_string_lossy()
-            .replace('\\', "/");
-        if parent == path || parent.is_empty() {
-            None
-        } else {
-            Some(if cfg!(target_os = "windows") {
-                parent.to_lowercase()
-            } else {
-                parent
-            })
-        }
+        super::parent_directory_key(path)
     }
 
     fn directory_name(path: &str) -> &str {
@@ -349,7 +369,7 @@ impl RedbDatabase {
     fn directory_order_key(parent_id: u64, path: &str, child_id: u64) -> String {
         format!(
             "{parent_id:016x}\0{}\0{child_id:016x}",
-            crate::natural_sort::natural_sort_key(&Self::directory_name(path).to_lowercase())
+            crate::natural_sort::collation_key(Self::directory_name(path))
         )
     }
 
@@ -363,7 +383,7 @@ impl RedbDatabase {
     fn directory_file_order_key(directory_id: u64, filename: &str, file_id: i64) -> String {
         format!(
             "{directory_id:016x}\0{}\0{file_id:016x}",
-            crate::natural_sort::natural_sort_key(&filename.to_lowercase())
+            crate::natural_sort::collation_key(filename)
         )
     }
 
@@ -414,6 +434,38 @@ impl RedbDatabase {
         format!("{directory_id}:{mime_family}")
     }
 
+    /// Library-wide count of a mime family, kept next to the per-directory
+    /// counts. The prefix is not a directory ID, so directory cleanup never
+    /// matches it.
+    fn library_count_key(mime_family: &str) -> String {
+        format!("library:{mime_family}")
+    }
+
+    fn change_count(counts: &mut redb::Table<&str, u64>, key: &str, delta: i8) -> Result<()> {
+        let old = counts.get(key)?.map(|value| value.value()).unwrap_or(0);
+        if delta > 0 {
+            counts.insert(key, old.saturating_add(delta as u64))?;
+        } else {
+            let new = old.saturating_sub((-delta) as u64);
+            if new == 0 {
+                counts.remove(key)?;
+            } else {
+                counts.insert(key, new)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn library_count(
+        counts: &impl ReadableTable<&'static str, u64>,
+        mime_family: &str,
+    ) -> Result<u64> {
+        Ok(counts
+            .get(Self::library_count_key(mime_family).as_str())?
+            .map(|value| value.value())
+            .unwrap_or(0))
+    }
+
     fn playlist_entry_key(playlist_id: i64, position: u32) -> u128 {
         ((playlist_id as u64 as u128) << 32) | position as u128
     }
@@ -422,6 +474,65 @@ impl RedbDatabase {
         Self::playlist_entry_key(playlist_id, 0)..=Self::playlist_entry_key(playlist_id, u32::MAX)
     }
 
+    /// Key of the [`PlaylistEntryInfo`] of `file_id` in `playlist_id`. It
+    /// follows the file rather than its position, so reordering keeps it.
+    fn playlist_entry_info_key(playlist_id: i64, file_id: i64) -> u128 {
+        ((playlist_id as u64 as u128) << 64) | file_id as u64 as u128
+    }
+
+    fn playlist_entry_info_range(playlist_id: i64) -> std::ops::RangeInclusive<u128> {
+        let first = (playlist_id as u64 as u128) << 64;
+        first..=first | u64::MAX as u128
+    }
+
+    /// Drop the [`PlaylistEntryInfo`] of every entry of `playlist_id`.
+    fn clear_playlist_entry_info(
+        info: &mut redb::Table<u128, &[u8]>,
+        playlist_id: i64,
+    ) -> Result<()> {
+        let keys = info
+            .range(Self::playlist_entry_info_range(playlist_id))?
+            .map(|entry| entry.map(|(key, _)| key.value()))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        for key in keys {
+            info.remove(key)?;
+        }
+        Ok(())
+    }
+
+    /// (position, file) pairs of one playlist, in order.
+    fn playlist_entry_list(
+        entries: &redb::Table<u128, i64>,
+        playlist_id: i64,
+    ) -> Result<Vec<(u32, i64)>> {
+        entries
+            .range(Self::playlist_entry_range(playlist_id))?
+            .map(|entry| {
+                let (key, file_id) = entry?;
+                Ok((key.value() as u32, file_id.value()))
+            })
+            .collect()
+    }
+
+    fn set_playlist_entries(
+        entries: &mut redb::Table<u128, i64>,
+        reverse: &mut redb::MultimapTable<i64, u128>,
+        playlist_id: i64,
+        new_entries: &[(u32, i64)],
+    ) -> Result<()> {
+        for (position, file_id) in Self::playlist_entry_list(entries, playlist_id)? {
+            let key = Self::playlist_entry_key(playlist_id, position);
+            entries.remove(key)?;
+            reverse.remove(file_id, key)?;
+        }
+        for &(position, file_id) in new_entries {
+            let key = Self::playlist_entry_key(playlist_id, position);
+            entries.insert(key, file_id)?;
+            reverse.insert(file_id, key)?;
+        }
+        Ok(())
+    }
+
     fn change_recursive_mime_count(
         paths: &redb::Table<&str, u64>,
         counts: &mut redb::Table<&str, u64>,
@@ -432,25 +543,15 @@ impl RedbDatabase {
         let mut current = Some(directory_path.to_owned());
         while let Some(path) = current {
             if let Some(directory_id) = paths.get(path.as_str())?.map(|value| value.value()) {
-                let key = Self::mime_count_key(directory_id, mime_family);
-                let old = counts
-                    .get(key.as_str())?
-                    .map(|value| value.value())
-                    .unwrap_or(0);
-                if delta > 0 {
-                    counts.insert(key.as_str(), old.saturating_add(delta as u64))?;
-                } else {
-                    let new = old.saturating_sub((-delta) as u64);
-                    if new == 0 {
-                        counts.remove(key.as_str())?;
-                    } else {
-                        counts.insert(key.as_str(), new)?;
-                    }
-                }
+                Self::change_count(
+                    counts,
+                    &Self::mime_count_key(directory_id, mime_family),
+                    delta,
+                )?;
             }
             current = Self::parent_directory(&path);
         }
-        Ok(())
+        Self::change_count(counts, &Self::library_count_key(mime_family), delta)
     }
 
     #[allow(clippy::too_many_arguments)] // One atomic directory-index update spans these tables.
@@ -468,7 +569,7 @@ impl RedbDatabase {
         let file_id = file
             .id()
             .ok_or_else(|| anyhow!("cannot index directory membership without a file ID"))?;
-        let directory_path = Self::get_dir_key_str(file.path());
+        let directory_path = Self::get_dir_key_str(&file.path());
         let directory_id = Self::ensure_directory(
             paths,
             records,
@@ -502,7 +603,7 @@ impl RedbDatabase {
         file_id: i64,
         file: &V,
     ) -> Result<()> {
-        let directory_path = Self::get_dir_key_str(file.path());
+        let directory_path = Self::get_dir_key_str(&file.path());
         let Some(directory_id) = paths
             .get(directory_path.as_str())?
             .map(|value| value.value())
@@ -671,7 +772,9 @@ impl RedbDatabase {
         Ok(directories.len())
     }
 
+    #[allow(clippy::too_many_arguments)] // Every secondary file index changes in one transaction.
     fn remove_file_indexes<V: MediaFileView>(
+        object_keys: &mut redb::MultimapTable<u64, i64>,
         artist: &mut redb::MultimapTable<&str, i64>,
         album: &mut redb::MultimapTable<&str, i64>,
         genre: &mut redb::MultimapTable<&str, i64>,
@@ -680,6 +783,7 @@ impl RedbDatabase {
         id: i64,
         file: &V,
     ) -> Result<()> {
+        object_keys.remove(media_object_key(&file.path()), id)?;
         if let Some(v) = file.artist() {
             artist.remove(v, id)?;
         }
@@ -698,7 +802,9 @@ impl RedbDatabase {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)] // Every secondary file index changes in one transaction.
     fn add_file_indexes<V: MediaFileView>(
+        object_keys: &mut redb::MultimapTable<u64, i64>,
         artist: &mut redb::MultimapTable<&str, i64>,
         album: &mut redb::MultimapTable<&str, i64>,
         genre: &mut redb::MultimapTable<&str, i64>,
@@ -707,6 +813,7 @@ impl RedbDatabase {
         id: i64,
         file: &V,
     ) -> Result<()> {
+        object_keys.insert(media_object_key(&file.path()), id)?;
         if let Some(v) = file.artist() {
             artist.insert(v, id)?;
         }
@@ -738,6 +845,7 @@ impl RedbDatabase {
         let mut directory_files = transaction.open_multimap_table(DIRECTORY_FILES)?;
         let mut ordered_files = transaction.open_table(DIRECTORY_FILES_BY_NAME)?;
         let mut directory_mime_counts = transaction.open_table(DIRECTORY_MIME_COUNTS)?;
+        let mut object_key_index = transaction.open_multimap_table(OBJECT_KEY_INDEX)?;
         let mut artist_index = transaction.open_multimap_table(ARTIST_INDEX)?;
         let mut album_index = transaction.open_multimap_table(ALBUM_INDEX)?;
         let mut genre_index = transaction.open_multimap_table(GENRE_INDEX)?;
@@ -765,6 +873,7 @@ impl RedbDatabase {
                 file,
             )?;
             Self::remove_file_indexes(
+                &mut object_key_index,
                 &mut artist_index,
                 &mut album_index,
                 &mut genre_index,
@@ -856,6 +965,10 @@ impl MediaRepository for RedbDatabase {
         RedbDatabase::get_file_location_by_id_impl(self, id).await
     }
 
+    async fn get_file_location_by_object_key(&self, key: u64) -> Result<Option<FileLocation>> {
+        RedbDatabase::get_file_location_by_object_key_impl(self, key).await
+    }
+
     async fn load_file_fingerprints(&self) -> Result<Vec<FileFingerprint>> {
         RedbDatabase::load_file_fingerprints_impl(self).await
     }
@@ -880,6 +993,18 @@ impl MediaRepository for RedbDatabase {
         RedbDatabase::remove_root_availability_impl(self, path).await
     }
 
+    async fn get_setting(&self, key: &str) -> Result<Option<String>> {
+        RedbDatabase::get_setting_impl(self, key).await
+    }
+
+    async fn set_setting(&self, key: &str, value: &str) -> Result<()> {
+        RedbDatabase::set_setting_impl(self, key, value).await
+    }
+
+    async fn remove_setting(&self, key: &str) -> Result<()> {
+        RedbDatabase::remove_setting_impl(self, key).await
+    }
+
     async fn get_artists(&self) -> Result<Vec<MusicCategory>> {
         RedbDatabase::get_artists_impl(self).await
     }
@@ -1018,6 +1143,10 @@ impl PlaylistRepository for RedbDatabase {
         RedbDatabase::set_playlist_source_impl(self, playlist_id, source_path).await
     }
 
+    async fn get_playlist_sources(&self) -> Result<HashMap<i64, PathBuf>> {
+        RedbDatabase::get_playlist_sources_impl(self).await
+    }
+
     async fn replace_playlist_from_source(
         &self,
         source_path: &Path,
@@ -1058,6 +1187,14 @@ impl PlaylistRepository for RedbDatabase {
         RedbDatabase::batch_add_to_playlist_impl(self, playlist_id, media_file_ids).await
     }
 
+    async fn append_to_playlist(
+        &self,
+        playlist_id: i64,
+        media_file_ids: &[i64],
+    ) -> Result<Vec<i64>> {
+        RedbDatabase::append_to_playlist_impl(self, playlist_id, media_file_ids).await
+    }
+
     async fn remove_from_playlist(&self, playlist_id: i64, media_file_id: i64) -> Result<bool> {
         RedbDatabase::remove_from_playlist_impl(self, playlist_id, media_file_id).await
     }
@@ -1066,6 +1203,13 @@ impl PlaylistRepository for RedbDatabase {
         RedbDatabase::get_playlist_tracks_impl(self, playlist_id).await
     }
 
+    async fn get_playlist_entry_info(
+        &self,
+        playlist_id: i64,
+    ) -> Result<HashMap<i64, PlaylistEntryInfo>> {
+        RedbDatabase::get_playlist_entry_info_impl(self, playlist_id).await
+    }
+
     async fn reorder_playlist(
         &self,
         playlist_id: i64,
@@ -1099,12 +1243,20 @@ impl StatsRepository for RedbDatabase {
     async fn get_stats(&self) -> Result<DatabaseStats> {
         RedbDatabase::get_stats_impl(self).await
     }
+
+    async fn count_by_media_type(&self) -> Result<MediaTypeCounts> {
+        RedbDatabase::count_by_media_type_impl(self).await
+    }
 }
 
 #[async_trait]
 impl DatabaseManager for RedbDatabase {
+    fn backend_name(&self) -> &'static str {
+        "redb"
+    }
+
     async fn initialize(&self) -> Result<()> {
-        info!("RedbDatabase initialized");
+        info!(target: crate::logging::targets::DB, "RedbDatabase initialized");
         Ok(())
     }
 }
@@ -1114,6 +1266,103 @@ mod tests {
     use super::*;
     use tempfile::tempdir;
 
+    #[test]
+    fn subtree_scans_step_over_longer_sibling_names() {
+        assert_eq!(subtree_key("/music", "/music"), SubtreeKey::Inside);
+        assert_eq!(subtree_key("/music", "/music/a.mp3"), SubtreeKey::Inside);
+        assert_eq!(subtree_key("/music/", "/music/a.mp3"), SubtreeKey::Inside);
+        assert_eq!(subtree_key("/music", "/music b/a.mp3"), SubtreeKey::Sibling);
+        assert_eq!(subtree_key("/music", "/music-old"), SubtreeKey::Sibling);
+        assert_eq!(subtree_key("/music", "/musicals"), SubtreeKey::Sibling);
+        assert_eq!(subtree_key("/music", "/video/a.mkv"), SubtreeKey::Past);
+    }
+
+    #[tokio::test]
+    async fn path_prefix_queries_reach_past_sibling_names() {
+        let temp = tempdir().unwrap();
+        let db = RedbDatabase::new(temp.path().join("prefix.redb"))
+            .await
+            .unwrap();
+        db.initialize().await.unwrap();
+        // " " and "-" sort before "/", so these sit between "/music" and
+        // "/music/" in the path index.
+        let paths = [
+            "/music b/a.mp3",
+            "/music-old/b.mp3",
+            "/music/c.mp3",
+            "/music/sub/d.mp3",
+        ];
+        let files = paths
+            .iter()
+            .map(|path| MediaFile::new(PathBuf::from(path), 1, "audio/mpeg".to_owned()))
+            .collect::<Vec<_>>();
+        db.bulk_store_media_files(&files).await.unwrap();
+
+        let mut found = db
+            .get_files_with_path_prefix("/music")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|file| file.path)
+            .collect::<Vec<_>>();
+        found.sort();
+        assert_eq!(
+            found,
+            [
+                PathBuf::from("/music/c.mp3"),
+                PathBuf::from("/music/sub/d.mp3")
+            ]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn browsing_during_a_bulk_insert_sees_whole_snapshots_without_errors() {
+        let temp_dir = tempdir().unwrap();
+        let db = Arc::new(
+            RedbDatabase::new(temp_dir.path().join("stress.redb"))
+                .await
+                .unwrap()
+                .with_max_readers(2),
+        );
+        db.initialize().await.unwrap();
+        let directory = PathBuf::from("/stress/music");
+        let files = (0..5_000)
+            .map(|index| {
+                MediaFile::new(
+                    directory.join(format!("track-{index:05}.mp3")),
+                    1024,
+                    "audio/mpeg".to_string(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let writer = {
+            let db = Arc::clone(&db);
+            tokio::spawn(async move { db.bulk_store_media_files(&files).await })
+        };
+        let readers = (0..64)
+            .map(|_| {
+                let db = Arc::clone(&db);
+                let directory = directory.clone();
+                tokio::spawn(async move {
+                    let (_, files) = db.get_directory_listing(&directory, "audio").await?;
+                    db.get_stats().await?;
+                    anyhow::Ok(files.len())
+                })
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(writer.await.unwrap().unwrap().len(), 5_000);
+        for reader in readers {
+            let seen = reader.await.unwrap().unwrap();
+            assert!(
+                seen == 0 || seen == 5_000,
+                "partial snapshot of {seen} files"
+            );
+        }
+        assert_eq!(db.read_permits.available_permits(), 2);
+    }
+
     #[tokio::test]
     async fn test_redb_database_basic_operations() {
         let temp_dir = tempdir().unwrap();
@@ -1420,7 +1669,7 @@ mod tests {
         redb_schema!(collect_schema_name);
         let unique = names.iter().copied().collect::<HashSet<_>>();
         assert_eq!(names.len(), unique.len());
-        assert_eq!(names.len(), 23);
+        assert_eq!(names.len(), 26);
     }
 
     #[tokio::test]
@@ -1469,6 +1718,63 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn filtered_listings_are_answered_from_the_mime_counts() {
+        let temp = tempdir().unwrap();
+        let db = RedbDatabase::new(temp.path().join("filtered-listing.redb"))
+            .await
+            .unwrap();
+        db.initialize().await.unwrap();
+        let file = |path: &str, mime: &str| MediaFile::new(PathBuf::from(path), 1, mime.to_owned());
+        db.bulk_store_media_files(&[
+            file("/library/Films/movie.mkv", "video/x-matroska"),
+            file("/library/Albums/song.mp3", "audio/mpeg"),
+            file("/library/trailer.mkv", "video/x-matroska"),
+            file("/library/clip.mp4", "video/mp4"),
+        ])
+        .await
+        .unwrap();
+
+        let names = |directories: Vec<MediaDirectory>| {
+            directories
+                .into_iter()
+                .map(|directory| directory.name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(
+                db.get_filtered_direct_subdirectories("/library", "audio/")
+                    .await
+                    .unwrap()
+            ),
+            ["Albums"]
+        );
+        assert_eq!(
+            names(
+                db.get_filtered_direct_subdirectories("/library", "")
+                    .await
+                    .unwrap()
+            ),
+            ["Albums", "Films"]
+        );
+
+        let (directories, files) = db
+            .get_directory_listing(Path::new("/library"), "audio/")
+            .await
+            .unwrap();
+        assert_eq!(names(directories), ["Albums"]);
+        assert!(files.is_empty());
+
+        // Narrower than a family: no count to consult, the records decide.
+        let (directories, files) = db
+            .get_directory_listing(Path::new("/library"), "video/mp4")
+            .await
+            .unwrap();
+        assert!(directories.is_empty());
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].filename, "clip.mp4");
+    }
+
     #[tokio::test]
     async fn test_natural_sort_directory_files() {
         let temp = tempdir().unwrap();
@@ -1611,6 +1917,7 @@ mod tests {
                     location: PathBuf::from("https://radio.example/stream"),
                     position: 0,
                     stream_title: Some("Station".to_owned()),
+                    info: Default::default(),
                 }],
             )
             .await
@@ -1794,6 +2101,7 @@ mod tests {
             location: PathBuf::from("https://radio.example/shared"),
             position: 0,
             stream_title: Some("Shared".to_owned()),
+            info: Default::default(),
         };
         let source_a = Path::new("/playlists/a.m3u");
         let source_b = Path::new("/playlists/b.m3u");
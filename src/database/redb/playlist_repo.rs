@@ -9,9 +9,7 @@ impl RedbDatabase {
         playlist_name: Option<&str>,
         entries: &[SourceMediaEntry],
     ) -> Result<Option<i64>> {
-        let source = Self::canonical_path(source_path)?
-            .to_string_lossy()
-            .into_owned();
+        let source = path_to_lossless_string(&Self::canonical_path(source_path)?).into_owned();
         let entries = entries
             .iter()
             .map(|entry| {
@@ -19,15 +17,12 @@ impl RedbDatabase {
                     location: Self::canonical_path(&entry.location)?,
                     position: entry.position,
                     stream_title: entry.stream_title.clone(),
+                    info: entry.info.clone(),
                 })
             })
             .collect::<Result<Vec<_>>>()?;
         let playlist_name = playlist_name.map(str::to_owned);
         let candidate_playlist_id = self.next_playlist_id.fetch_add(1, Ordering::SeqCst);
-        let candidate_file_ids = entries
-            .iter()
-            .map(|_| self.next_file_id.fetch_add(1, Ordering::SeqCst))
-            .collect::<Vec<_>>();
         let next_directory_id = Arc::clone(&self.next_directory_id);
 
         let (playlist_id, added_files, removed_files, added_size, removed_size) = self
@@ -51,20 +46,16 @@ impl RedbDatabase {
                     let mut ordered_files = transaction.open_table(DIRECTORY_FILES_BY_NAME)?;
                     let mut directory_mime_counts =
                         transaction.open_table(DIRECTORY_MIME_COUNTS)?;
+                    let mut object_keys = transaction.open_multimap_table(OBJECT_KEY_INDEX)?;
                     let mut artist = transaction.open_multimap_table(ARTIST_INDEX)?;
                     let mut album = transaction.open_multimap_table(ALBUM_INDEX)?;
                     let mut genre = transaction.open_multimap_table(GENRE_INDEX)?;
                     let mut year = transaction.open_multimap_table(YEAR_INDEX)?;
                     let mut album_artist = transaction.open_multimap_table(ALBUM_ARTIST_INDEX)?;
 
-                    for (entry, candidate_id) in entries.iter().zip(candidate_file_ids) {
-                        let path = entry.location.to_string_lossy().into_owned();
-                        let is_stream = path
-                            .get(..7)
-                            .is_some_and(|scheme| scheme.eq_ignore_ascii_case("http://"))
-                            || path
-                                .get(..8)
-                                .is_some_and(|scheme| scheme.eq_ignore_ascii_case("https://"));
+                    for entry in &entries {
+                        let path = path_to_lossless_string(&entry.location).into_owned();
+                        let is_stream = super::super::is_stream_location(&path);
                         let existing_id = {
                             let value = paths.get(path.as_str())?.map(|id| id.value());
                             value
@@ -72,6 +63,8 @@ impl RedbDatabase {
                         let file_id = if let Some(id) = existing_id {
                             id
                         } else if is_stream {
+                            let candidate_id =
+                                media_file_id(&path, |id| Ok(files.get(id)?.is_some()))?;
                             let mut stream =
                                 MediaFile::new(entry.location.clone(), 0, "audio/radio".to_owned());
                             let title = entry.stream_title.clone().unwrap_or_else(|| path.clone());
@@ -96,6 +89,7 @@ impl RedbDatabase {
                                 &stream,
                             )?;
                             Self::add_file_indexes(
+                                &mut object_keys,
                                 &mut artist,
                                 &mut album,
                                 &mut genre,
@@ -113,7 +107,7 @@ impl RedbDatabase {
                         if is_stream {
                             stream_ids.insert(file_id);
                         }
-                        resolved.push((file_id, entry.position));
+                        resolved.push((file_id, entry.position, &entry.info));
                     }
                 }
 
@@ -148,6 +142,7 @@ impl RedbDatabase {
                         let mut sources = transaction.open_table(PLAYLIST_SOURCES)?;
                         let mut source_playlists =
                             transaction.open_multimap_table(SOURCE_PLAYLISTS)?;
+                        let mut entry_info = transaction.open_table(PLAYLIST_ENTRY_INFO)?;
                         for duplicate_id in existing {
                             let old_entries = playlist_entries
                                 .range(Self::playlist_entry_range(duplicate_id))?
@@ -157,6 +152,7 @@ impl RedbDatabase {
                                 playlist_entries.remove(key)?;
                                 reverse_entries.remove(file_id, key)?;
                             }
+                            Self::clear_playlist_entry_info(&mut entry_info, duplicate_id)?;
                             if duplicate_id != playlist_id {
                                 playlists.remove(duplicate_id)?;
                                 sources.remove(duplicate_id)?;
@@ -166,10 +162,16 @@ impl RedbDatabase {
                         playlists.insert(playlist_id, serialized.as_slice())?;
                         sources.insert(playlist_id, source.as_str())?;
                         source_playlists.insert(source.as_str(), playlist_id)?;
-                        for (file_id, position) in &resolved {
+                        for (file_id, position, info) in &resolved {
                             let key = Self::playlist_entry_key(playlist_id, *position);
                             playlist_entries.insert(key, *file_id)?;
                             reverse_entries.insert(*file_id, key)?;
+                            if !info.is_empty() {
+                                entry_info.insert(
+                                    Self::playlist_entry_info_key(playlist_id, *file_id),
+                                    Self::serialize_playlist_entry_info(info)?.as_slice(),
+                                )?;
+                            }
                         }
                     }
                     Some(playlist_id)
@@ -190,6 +192,7 @@ impl RedbDatabase {
                         let mut sources = transaction.open_table(PLAYLIST_SOURCES)?;
                         let mut source_playlists =
                             transaction.open_multimap_table(SOURCE_PLAYLISTS)?;
+                        let mut entry_info = transaction.open_table(PLAYLIST_ENTRY_INFO)?;
                         for playlist_id in existing {
                             let old_entries = playlist_entries
                                 .range(Self::playlist_entry_range(playlist_id))?
@@ -199,6 +202,7 @@ impl RedbDatabase {
                                 playlist_entries.remove(key)?;
                                 reverse_entries.remove(file_id, key)?;
                             }
+                            Self::clear_playlist_entry_info(&mut entry_info, playlist_id)?;
                             playlists.remove(playlist_id)?;
                             sources.remove(playlist_id)?;
                         }
@@ -242,7 +246,7 @@ impl RedbDatabase {
                                 let view = RedbReadSession::view(bytes.value())?;
                                 if view.mime_type() == "audio/radio" {
                                     orphaned.push((
-                                        view.path().to_owned(),
+                                        view.path().into_owned(),
                                         file_id,
                                         IndexSnapshot::from_view(&view).ok_or_else(|| {
                                             anyhow!("stream record {file_id} has no ID")
@@ -314,7 +318,12 @@ impl RedbDatabase {
         })
         .await?;
 
-        info!("Created playlist '{}' with ID {}", name, playlist_id);
+        info!(
+            target: crate::logging::targets::DB,
+            "Created playlist '{}' with ID {}",
+            name,
+            playlist_id
+        );
         Ok(playlist_id)
     }
 
@@ -382,8 +391,10 @@ impl RedbDatabase {
                 let mut reverse_entries = write_txn.open_multimap_table(FILE_PLAYLIST_ENTRIES)?;
                 let mut playlist_sources = write_txn.open_table(PLAYLIST_SOURCES)?;
                 let mut source_playlists = write_txn.open_multimap_table(SOURCE_PLAYLISTS)?;
+                let mut entry_info = write_txn.open_table(PLAYLIST_ENTRY_INFO)?;
 
                 let existed = playlists_table.remove(playlist_id)?.is_some();
+                Self::clear_playlist_entry_info(&mut entry_info, playlist_id)?;
 
                 // Remove all entries for this playlist
                 let entries = playlist_entries
@@ -416,9 +427,7 @@ impl RedbDatabase {
         playlist_id: i64,
         source_path: &Path,
     ) -> Result<()> {
-        let source = Self::canonical_path(source_path)?
-            .to_string_lossy()
-            .to_string();
+        let source = path_to_lossless_string(&Self::canonical_path(source_path)?).into_owned();
         self.execute_write(move |database| {
             let txn = database.begin_write()?;
             {
@@ -442,15 +451,30 @@ impl RedbDatabase {
         .await
     }
 
+    pub(super) async fn get_playlist_sources_impl(&self) -> Result<HashMap<i64, PathBuf>> {
+        self.execute_read(move |database| {
+            let txn = database.begin_read()?;
+            let sources = txn.open_table(PLAYLIST_SOURCES)?;
+            let mut playlist_sources = HashMap::new();
+            for result in sources.iter()? {
+                let (playlist_id, source) = result?;
+                playlist_sources.insert(
+                    playlist_id.value(),
+                    path_from_lossless_string(source.value()),
+                );
+            }
+            Ok(playlist_sources)
+        })
+        .await
+    }
+
     pub(super) async fn replace_playlist_from_source_impl(
         &self,
         source_path: &Path,
         name: &str,
         media_file_ids: &[(i64, u32)],
     ) -> Result<i64> {
-        let source = Self::canonical_path(source_path)?
-            .to_string_lossy()
-            .into_owned();
+        let source = path_to_lossless_string(&Self::canonical_path(source_path)?).into_owned();
         let name = name.to_owned();
         let entries = media_file_ids.to_vec();
         let candidate_id = self.next_playlist_id.fetch_add(1, Ordering::SeqCst);
@@ -489,6 +513,7 @@ impl RedbDatabase {
                 let mut reverse_entries = transaction.open_multimap_table(FILE_PLAYLIST_ENTRIES)?;
                 let mut sources = transaction.open_table(PLAYLIST_SOURCES)?;
                 let mut source_playlists = transaction.open_multimap_table(SOURCE_PLAYLISTS)?;
+                let mut entry_info = transaction.open_table(PLAYLIST_ENTRY_INFO)?;
 
                 let old_entries = playlist_entries
                     .range(Self::playlist_entry_range(playlist_id))?
@@ -498,6 +523,7 @@ impl RedbDatabase {
                     playlist_entries.remove(key)?;
                     reverse_entries.remove(file_id, key)?;
                 }
+                Self::clear_playlist_entry_info(&mut entry_info, playlist_id)?;
 
                 let duplicate_ids = source_playlists
                     .get(source.as_str())?
@@ -515,6 +541,7 @@ impl RedbDatabase {
                         playlist_entries.remove(key)?;
                         reverse_entries.remove(file_id, key)?;
                     }
+                    Self::clear_playlist_entry_info(&mut entry_info, duplicate_id)?;
                     playlists.remove(duplicate_id)?;
                     sources.remove(duplicate_id)?;
                 }
@@ -539,12 +566,8 @@ impl RedbDatabase {
         &self,
         source_path: &Path,
     ) -> Result<usize> {
-        let source = Self::canonical_path(source_path)?
-            .to_string_lossy()
-            .to_string();
-        let child_prefix = format!("{}/", source.trim_end_matches('/'));
+        let source = path_to_lossless_string(&Self::canonical_path(source_path)?).into_owned();
         let source_for_query = source.clone();
-        let child_for_query = child_prefix.clone();
         let sources = self
             .execute_read(move |database| {
                 let txn = database.begin_read()?;
@@ -554,23 +577,21 @@ impl RedbDatabase {
                         let table = txn.open_multimap_table(SOURCE_PLAYLISTS)?;
                         for entry in table.range(source_for_query.as_str()..)? {
                             let (key, _) = entry?;
-                            if key.value() != source_for_query
-                                && !key.value().starts_with(&child_for_query)
-                            {
-                                break;
+                            match subtree_key(&source_for_query, key.value()) {
+                                SubtreeKey::Inside => sources.push(key.value().to_owned()),
+                                SubtreeKey::Sibling => {}
+                                SubtreeKey::Past => break,
                             }
-                            sources.push(key.value().to_owned());
                         }
                     } else {
                         let table = txn.open_multimap_table(SOURCE_STREAMS)?;
                         for entry in table.range(source_for_query.as_str()..)? {
                             let (key, _) = entry?;
-                            if key.value() != source_for_query
-                                && !key.value().starts_with(&child_for_query)
-                            {
-                                break;
+                            match subtree_key(&source_for_query, key.value()) {
+                                SubtreeKey::Inside => sources.push(key.value().to_owned()),
+                                SubtreeKey::Sibling => {}
+                                SubtreeKey::Past => break,
                             }
-                            sources.push(key.value().to_owned());
                         }
                     }
                 }
@@ -582,8 +603,12 @@ impl RedbDatabase {
         let mut removed = 0;
         for derived_source in sources {
             let before = self.total_files.load(Ordering::SeqCst);
-            self.replace_source_content_impl(Path::new(&derived_source), None, &[])
-                .await?;
+            self.replace_source_content_impl(
+                &path_from_lossless_string(&derived_source),
+                None,
+                &[],
+            )
+            .await?;
             removed += before.saturating_sub(self.total_files.load(Ordering::SeqCst)) as usize;
             removed += 1;
         }
@@ -596,9 +621,6 @@ impl RedbDatabase {
         media_file_id: i64,
         position: Option<u32>,
     ) -> Result<i64> {
-        let pos = position.unwrap_or(0);
-        let key = Self::playlist_entry_key(playlist_id, pos);
-
         self.execute_write(move |database| {
             let write_txn = database.begin_write()?;
             {
@@ -617,14 +639,28 @@ impl RedbDatabase {
                     return Err(anyhow!("media file {media_file_id} not found"));
                 }
                 let mut entries = write_txn.open_table(PLAYLIST_ENTRIES)?;
-                let old = entries
-                    .insert(key, media_file_id)?
-                    .map(|value| value.value());
                 let mut reverse = write_txn.open_multimap_table(FILE_PLAYLIST_ENTRIES)?;
-                if let Some(old) = old {
-                    reverse.remove(old, key)?;
+                let current = Self::playlist_entry_list(&entries, playlist_id)?;
+                match position {
+                    Some(position) => {
+                        let new_entries =
+                            playlist_order::inserted(&current, position, media_file_id)?;
+                        Self::set_playlist_entries(
+                            &mut entries,
+                            &mut reverse,
+                            playlist_id,
+                            &new_entries,
+                        )?;
+                    }
+                    None => {
+                        let position = playlist_order::next_position(
+                            current.last().map(|(position, _)| *position),
+                        )?;
+                        let key = Self::playlist_entry_key(playlist_id, position);
+                        entries.insert(key, media_file_id)?;
+                        reverse.insert(media_file_id, key)?;
+                    }
                 }
-                reverse.insert(media_file_id, key)?;
             }
             write_txn.commit()?;
             Ok(media_file_id)
@@ -632,6 +668,47 @@ impl RedbDatabase {
         .await
     }
 
+    pub(super) async fn append_to_playlist_impl(
+        &self,
+        playlist_id: i64,
+        media_file_ids: &[i64],
+    ) -> Result<Vec<i64>> {
+        let media_file_ids = media_file_ids.to_vec();
+        self.execute_write(move |database| {
+            let write_txn = database.begin_write()?;
+            {
+                if write_txn
+                    .open_table(PLAYLISTS_TABLE)?
+                    .get(playlist_id)?
+                    .is_none()
+                {
+                    return Err(anyhow!("playlist {playlist_id} not found"));
+                }
+                let files = write_txn.open_table(FILES_TABLE)?;
+                for file_id in &media_file_ids {
+                    if files.get(*file_id)?.is_none() {
+                        return Err(anyhow!("media file {file_id} not found"));
+                    }
+                }
+                let mut entries = write_txn.open_table(PLAYLIST_ENTRIES)?;
+                let mut reverse = write_txn.open_multimap_table(FILE_PLAYLIST_ENTRIES)?;
+                let mut last = Self::playlist_entry_list(&entries, playlist_id)?
+                    .last()
+                    .map(|(position, _)| *position);
+                for file_id in &media_file_ids {
+                    let position = playlist_order::next_position(last)?;
+                    let key = Self::playlist_entry_key(playlist_id, position);
+                    entries.insert(key, *file_id)?;
+                    reverse.insert(*file_id, key)?;
+                    last = Some(position);
+                }
+            }
+            write_txn.commit()?;
+            Ok(media_file_ids)
+        })
+        .await
+    }
+
     pub(super) async fn batch_add_to_playlist_impl(
         &self,
         playlist_id: i64,
@@ -730,6 +807,7 @@ impl RedbDatabase {
             }
             let playlist_entries = read_txn.open_table(PLAYLIST_ENTRIES)?;
             let files_table = read_txn.open_table(FILES_TABLE)?;
+            let entry_info = read_txn.open_table(PLAYLIST_ENTRY_INFO)?;
 
             let mut files = Vec::new();
             for entry in playlist_entries.range(Self::playlist_entry_range(playlist_id))? {
@@ -738,7 +816,13 @@ impl RedbDatabase {
                 let data = files_table.get(file_id)?.ok_or_else(|| {
                     anyhow!("playlist {playlist_id} references missing media file {file_id}")
                 })?;
-                files.push(Self::deserialize_media_file(data.value())?);
+                let mut file = Self::deserialize_media_file(data.value())?;
+                if let Some(info) =
+                    entry_info.get(Self::playlist_entry_info_key(playlist_id, file_id))?
+                {
+                    Self::deserialize_playlist_entry_info(info.value())?.apply_to(&mut file);
+                }
+                files.push(file);
             }
 
             Ok(files)
@@ -746,6 +830,26 @@ impl RedbDatabase {
         .await
     }
 
+    pub(super) async fn get_playlist_entry_info_impl(
+        &self,
+        playlist_id: i64,
+    ) -> Result<HashMap<i64, PlaylistEntryInfo>> {
+        self.execute_read(move |database| {
+            let read_txn = database.begin_read()?;
+            let entry_info = read_txn.open_table(PLAYLIST_ENTRY_INFO)?;
+            let mut info = HashMap::new();
+            for entry in entry_info.range(Self::playlist_entry_info_range(playlist_id))? {
+                let (key, value) = entry?;
+                info.insert(
+                    key.value() as u64 as i64,
+                    Self::deserialize_playlist_entry_info(value.value())?,
+                );
+            }
+            Ok(info)
+        })
+        .await
+    }
+
     pub(super) async fn reorder_playlist_impl(
         &self,
         playlist_id: i64,
@@ -770,23 +874,16 @@ impl RedbDatabase {
                 }
                 let mut playlist_entries = write_txn.open_table(PLAYLIST_ENTRIES)?;
                 let mut reverse_entries = write_txn.open_multimap_table(FILE_PLAYLIST_ENTRIES)?;
-
-                // Remove existing entries for this playlist
-                let entries = playlist_entries
-                    .range(Self::playlist_entry_range(playlist_id))?
-                    .map(|entry| entry.map(|(key, file)| (key.value(), file.value())))
-                    .collect::<std::result::Result<Vec<_>, _>>()?;
-                for (key, file_id) in entries {
-                    playlist_entries.remove(key)?;
-                    reverse_entries.remove(file_id, key)?;
-                }
-
-                // Insert new order
-                for (file_id, position) in track_positions {
-                    let key = Self::playlist_entry_key(playlist_id, position);
-                    playlist_entries.insert(key, file_id)?;
-                    reverse_entries.insert(file_id, key)?;
-                }
+                let new_entries = playlist_order::reordered(
+                    &Self::playlist_entry_list(&playlist_entries, playlist_id)?,
+                    &track_positions,
+                )?;
+                Self::set_playlist_entries(
+                    &mut playlist_entries,
+                    &mut reverse_entries,
+                    playlist_id,
+                    &new_entries,
+                )?;
             }
             write_txn.commit()?;
 
@@ -5,7 +5,7 @@ impl RedbDatabase {
         &self,
         path: &Path,
     ) -> Result<Option<RootAvailability>> {
-        let key = Self::canonical_path(path)?.to_string_lossy().into_owned();
+        let key = path_to_lossless_string(&Self::canonical_path(path)?).into_owned();
         self.execute_read(move |database| {
             let transaction = database.begin_read()?;
             let table = transaction.open_table(ROOT_AVAILABILITY)?;
@@ -44,7 +44,7 @@ impl RedbDatabase {
     pub(super) async fn set_root_availability_impl(&self, state: &RootAvailability) -> Result<()> {
         let mut state = state.clone();
         state.path = Self::canonical_path(&state.path)?;
-        let key = state.path.to_string_lossy().into_owned();
+        let key = path_to_lossless_string(&state.path).into_owned();
         let bytes =
             rkyv::to_bytes::<rkyv::rancor::Error>(&RootAvailabilitySerializable::from(&state))
                 .map_err(|error| anyhow!("failed to archive root availability {key}: {error}"))?;
@@ -60,7 +60,7 @@ impl RedbDatabase {
     }
 
     pub(super) async fn remove_root_availability_impl(&self, path: &Path) -> Result<()> {
-        let key = Self::canonical_path(path)?.to_string_lossy().into_owned();
+        let key = path_to_lossless_string(&Self::canonical_path(path)?).into_owned();
         self.execute_write(move |database| {
             let transaction = database.begin_write()?;
             transaction
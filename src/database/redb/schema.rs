@@ -8,6 +8,7 @@ macro_rules! redb_schema {
     ($callback:ident) => {
         $callback!(table, FILES_TABLE, i64, &[u8], "files", primary);
         $callback!(table, PATH_INDEX, &str, i64, "path_index", derived);
+        $callback!(multimap, OBJECT_KEY_INDEX, u64, i64, "object_key_index", derived);
         $callback!(table, DIRECTORY_PATH_INDEX, &str, u64, "directory_path_index", derived);
         $callback!(table, DIRECTORY_RECORDS, u64, &str, "directory_records", derived);
         $callback!(multimap, DIRECTORY_CHILDREN, u64, u64, "directory_children", derived);
@@ -18,12 +19,14 @@ macro_rules! redb_schema {
         $callback!(table, PLAYLISTS_TABLE, i64, &[u8], "playlists", primary);
         $callback!(table, PLAYLIST_ENTRIES, u128, i64, "playlist_entries", primary);
         $callback!(multimap, FILE_PLAYLIST_ENTRIES, i64, u128, "file_playlist_entries", derived);
+        $callback!(table, PLAYLIST_ENTRY_INFO, u128, &[u8], "playlist_entry_info", primary);
         $callback!(table, PLAYLIST_SOURCES, i64, &str, "playlist_sources", primary);
         $callback!(multimap, SOURCE_PLAYLISTS, &str, i64, "source_playlists", derived);
         $callback!(multimap, SOURCE_STREAMS, &str, i64, "source_streams", primary);
         $callback!(multimap, STREAM_SOURCES, i64, &str, "stream_sources", derived);
         $callback!(table, METADATA_TABLE, &str, u64, "metadata", primary);
         $callback!(table, ROOT_AVAILABILITY, &str, &[u8], "root_availability", primary);
+        $callback!(table, SETTINGS_TABLE, &str, &str, "settings", primary);
         $callback!(multimap, ARTIST_INDEX, &str, i64, "artist_index", derived);
         $callback!(multimap, ALBUM_INDEX, &str, i64, "album_index", derived);
         $callback!(multimap, GENRE_INDEX, &str, i64, "genre_index", derived);
@@ -43,8 +46,8 @@ macro_rules! declare_schema_entry {
 }
 
 redb_schema!(declare_schema_entry);
-const SCHEMA_VERSION: u64 = 7;
-const CODEC_VERSION: u64 = 2;
+const SCHEMA_VERSION: u64 = 8;
+const CODEC_VERSION: u64 = 5;
 
 // Stable storage records. Keep these independent from application structs so
 // schema changes are explicit and versioned.
@@ -65,6 +68,9 @@ struct MediaFileSerializable {
     track_number: Option<u32>,
     year: Option<u32>,
     album_artist: Option<String>,
+    compilation: bool,
+    content_hash: Option<u64>,
+    missing_since_secs: Option<u64>,
     subtitle_available: bool,
     created_at_secs: u64,
     updated_at_secs: u64,
@@ -74,7 +80,7 @@ impl From<&MediaFile> for MediaFileSerializable {
     fn from(file: &MediaFile) -> Self {
         Self {
             id: file.id,
-            path: file.path.to_string_lossy().to_string(),
+            path: path_to_lossless_string(&file.path).into_owned(),
             filename: file.filename.clone(),
             size: file.size,
             modified_secs: file
@@ -96,6 +102,14 @@ impl From<&MediaFile> for MediaFileSerializable {
             track_number: file.track_number,
             year: file.year,
             album_artist: file.album_artist.clone(),
+            compilation: file.compilation,
+            content_hash: file.content_hash,
+            missing_since_secs: file.missing_since.map(|since| {
+                since
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs()
+            }),
             subtitle_available: file.subtitle_available,
             created_at_secs: file
                 .created_at
@@ -115,7 +129,7 @@ impl From<MediaFileSerializable> for MediaFile {
     fn from(s: MediaFileSerializable) -> Self {
         Self {
             id: s.id,
-            path: PathBuf::from(s.path),
+            path: path_from_lossless_string(&s.path),
             filename: s.filename,
             size: s.size,
             modified: UNIX_EPOCH
@@ -129,6 +143,11 @@ impl From<MediaFileSerializable> for MediaFile {
             track_number: s.track_number,
             year: s.year,
             album_artist: s.album_artist,
+            compilation: s.compilation,
+            content_hash: s.content_hash,
+            missing_since: s
+                .missing_since_secs
+                .map(|secs| UNIX_EPOCH + Duration::from_secs(secs)),
             subtitle_available: s.subtitle_available,
             created_at: UNIX_EPOCH + Duration::from_secs(s.created_at_secs),
             updated_at: UNIX_EPOCH + Duration::from_secs(s.updated_at_secs),
@@ -145,6 +164,30 @@ struct PlaylistSerializable {
     updated_at_secs: u64,
 }
 
+#[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+struct PlaylistEntryInfoSerializable {
+    title: Option<String>,
+    duration_secs: Option<f64>,
+}
+
+impl From<&PlaylistEntryInfo> for PlaylistEntryInfoSerializable {
+    fn from(info: &PlaylistEntryInfo) -> Self {
+        Self {
+            title: info.title.clone(),
+            duration_secs: info.duration.map(|duration| duration.as_secs_f64()),
+        }
+    }
+}
+
+impl From<PlaylistEntryInfoSerializable> for PlaylistEntryInfo {
+    fn from(s: PlaylistEntryInfoSerializable) -> Self {
+        Self {
+            title: s.title,
+            duration: s.duration_secs.map(Duration::from_secs_f64),
+        }
+    }
+}
+
 #[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
 struct RootAvailabilitySerializable {
     path: String,
@@ -157,7 +200,7 @@ struct RootAvailabilitySerializable {
 impl From<&RootAvailability> for RootAvailabilitySerializable {
     fn from(state: &RootAvailability) -> Self {
         Self {
-            path: state.path.to_string_lossy().into_owned(),
+            path: path_to_lossless_string(&state.path).into_owned(),
             last_seen_secs: state.last_seen_secs,
             unavailable_since_secs: state.unavailable_since_secs,
             indexed_count: state.indexed_count,
@@ -169,7 +212,7 @@ impl From<&RootAvailability> for RootAvailabilitySerializable {
 impl From<RootAvailabilitySerializable> for RootAvailability {
     fn from(state: RootAvailabilitySerializable) -> Self {
         Self {
-            path: PathBuf::from(state.path),
+            path: path_from_lossless_string(&state.path),
             last_seen_secs: state.last_seen_secs,
             unavailable_since_secs: state.unavailable_since_secs,
             indexed_count: state.indexed_count,
@@ -230,8 +273,8 @@ impl DirectoryView for RedbDirectoryView<'_> {
         self.id
     }
 
-    fn path(&self) -> &str {
-        self.path
+    fn path(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self.path)
     }
 
     fn name(&self) -> &str {
@@ -264,8 +307,8 @@ impl MediaFileView for RkyvMediaFileView<'_> {
     fn id(&self) -> Option<i64> {
         self.archived.id.as_ref().map(|value| value.to_native())
     }
-    fn path(&self) -> &str {
-        self.archived.path.as_str()
+    fn path(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self.archived.path.as_str())
     }
     fn filename(&self) -> &str {
         self.archived.filename.as_str()
@@ -315,6 +358,18 @@ impl MediaFileView for RkyvMediaFileView<'_> {
             .as_ref()
             .map(|value| value.as_str())
     }
+    fn compilation(&self) -> bool {
+        self.archived.compilation
+    }
+    fn content_hash(&self) -> Option<u64> {
+        self.archived.content_hash.as_ref().map(|hash| hash.to_native())
+    }
+    fn missing_since_secs(&self) -> Option<u64> {
+        self.archived
+            .missing_since_secs
+            .as_ref()
+            .map(|secs| secs.to_native())
+    }
     fn subtitle_available(&self) -> bool {
         self.archived.subtitle_available
     }
@@ -387,10 +442,22 @@ impl DatabaseReadSession for RedbReadSession {
                     emit_id!(id.value());
                 }
             }
+            MediaFileQuery::ObjectKey(key) => {
+                let index = self.transaction.open_multimap_table(OBJECT_KEY_INDEX)?;
+                for id in index.get(*key)? {
+                    emit_id!(id?.value());
+                }
+            }
             MediaFileQuery::Directory { path, mime_family } => {
                 let paths = self.transaction.open_table(DIRECTORY_PATH_INDEX)?;
                 if let Some(directory_id) = paths.get(path.as_str())? {
                     let directory_id = directory_id.value();
+                    if let Some(family) = mime_family {
+                        let counts = self.transaction.open_table(DIRECTORY_MIME_COUNTS)?;
+                        if !RedbDatabase::may_hold_files(&counts, directory_id, family)? {
+                            return Ok(summary);
+                        }
+                    }
                     let index = self.transaction.open_table(DIRECTORY_FILES_BY_NAME)?;
                     let (range_start, range_end) = RedbDatabase::directory_file_order_range(directory_id);
                     for entry in index.range(range_start.as_str()..range_end.as_str())? {
@@ -569,17 +636,3 @@ impl DatabaseReadSession for RedbReadSession {
         Ok(summary)
     }
 }
-
-fn contains_ignore_ascii_case(value: &str, needle: &str) -> bool {
-    if needle.is_empty() {
-        return true;
-    }
-    if value.is_ascii() && needle.is_ascii() {
-        value
-            .as_bytes()
-            .windows(needle.len())
-            .any(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
-    } else {
-        value.to_lowercase().contains(&needle.to_lowercase())
-    }
-}
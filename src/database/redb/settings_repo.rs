@@ -0,0 +1,43 @@
+use super::*;
+
+impl RedbDatabase {
+    pub(super) async fn get_setting_impl(&self, key: &str) -> Result<Option<String>> {
+        let key = key.to_owned();
+        self.execute_read(move |database| {
+            let transaction = database.begin_read()?;
+            let table = transaction.open_table(SETTINGS_TABLE)?;
+            let value = table
+                .get(key.as_str())?
+                .map(|value| value.value().to_owned());
+            Ok(value)
+        })
+        .await
+    }
+
+    pub(super) async fn set_setting_impl(&self, key: &str, value: &str) -> Result<()> {
+        let key = key.to_owned();
+        let value = value.to_owned();
+        self.execute_write(move |database| {
+            let transaction = database.begin_write()?;
+            transaction
+                .open_table(SETTINGS_TABLE)?
+                .insert(key.as_str(), value.as_str())?;
+            transaction.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub(super) async fn remove_setting_impl(&self, key: &str) -> Result<()> {
+        let key = key.to_owned();
+        self.execute_write(move |database| {
+            let transaction = database.begin_write()?;
+            transaction
+                .open_table(SETTINGS_TABLE)?
+                .remove(key.as_str())?;
+            transaction.commit()?;
+            Ok(())
+        })
+        .await
+    }
+}
@@ -11,26 +11,12 @@ impl RedbDatabase {
             .map(|m| m.len())
             .unwrap_or(0);
 
-        let (video_files, audio_files, image_files, playlists) = self
+        let counts = self.count_by_media_type_impl().await?;
+        let playlists = self
             .execute_read(|database| {
                 let transaction = database.begin_read()?;
-                let files = transaction.open_table(FILES_TABLE)?;
-                let mut video = 0;
-                let mut audio = 0;
-                let mut image = 0;
-                for entry in files.iter()? {
-                    let (_, bytes) = entry?;
-                    let view = RedbReadSession::view(bytes.value())?;
-                    if view.mime_type().starts_with("video/") {
-                        video += 1;
-                    } else if view.mime_type().starts_with("audio/") {
-                        audio += 1;
-                    } else if view.mime_type().starts_with("image/") {
-                        image += 1;
-                    }
-                }
-                let playlists = transaction.open_table(PLAYLISTS_TABLE)?.iter()?.count();
-                Ok((video, audio, image, playlists))
+                let playlists = transaction.open_table(PLAYLISTS_TABLE)?;
+                Ok(redb::ReadableTableMetadata::len(&playlists)? as usize)
             })
             .await?;
 
@@ -38,10 +24,29 @@ impl RedbDatabase {
             total_files,
             total_size,
             database_size,
-            video_files,
-            audio_files,
-            image_files,
+            video_files: counts.videos as usize,
+            audio_files: counts.songs as usize,
+            image_files: counts.pictures as usize,
             playlists,
         })
     }
+
+    /// Read from the library-wide entries of the mime count index.
+    pub(super) async fn count_by_media_type_impl(&self) -> Result<MediaTypeCounts> {
+        self.execute_read(|database| {
+            let transaction = database.begin_read()?;
+            let counts = transaction.open_table(DIRECTORY_MIME_COUNTS)?;
+            let videos = Self::library_count(&counts, "video/")?;
+            let songs = Self::library_count(&counts, "audio/")?;
+            let pictures = Self::library_count(&counts, "image/")?;
+            let total = Self::library_count(&counts, "*")?;
+            Ok(MediaTypeCounts {
+                videos,
+                songs,
+                pictures,
+                other: total.saturating_sub(videos + songs + pictures),
+            })
+        })
+        .await
+    }
 }
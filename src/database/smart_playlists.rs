@@ -0,0 +1,562 @@
+//! Smart playlists: saved metadata rules evaluated when they are browsed.
+//!
+//! A smart playlist stores rules such as `genre is Jazz` and `year > 1990`
+//! rather than tracks, so library changes show up on the next Browse without
+//! any bookkeeping. Evaluation starts from the artist, album, album artist,
+//! genre and year indexes: rules on those fields select category values, and
+//! only the files under matching values are loaded and checked against the
+//! remaining rules. Definitions live in one JSON server setting.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use super::{MediaFile, MediaRepository, MusicCategory, SortSpec};
+
+/// Server setting holding every smart playlist definition.
+pub const SMART_PLAYLISTS_SETTING: &str = "smart_playlists";
+
+/// Most tracks a smart playlist returns, whatever its own `limit` says.
+pub const MAX_SMART_PLAYLIST_ITEMS: usize = 1000;
+
+/// Serializes read-modify-write cycles on [`SMART_PLAYLISTS_SETTING`].
+static STORE_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleField {
+    Title,
+    Artist,
+    Album,
+    AlbumArtist,
+    Genre,
+    Year,
+    /// Track length in seconds.
+    Duration,
+    Filename,
+}
+
+impl RuleField {
+    /// Whether the field has a category index to start evaluation from.
+    pub fn is_indexed(self) -> bool {
+        matches!(
+            self,
+            RuleField::Artist
+                | RuleField::Album
+                | RuleField::AlbumArtist
+                | RuleField::Genre
+                | RuleField::Year
+        )
+    }
+
+    fn is_numeric(self) -> bool {
+        matches!(self, RuleField::Year | RuleField::Duration)
+    }
+
+    fn value(self, file: &MediaFile) -> Option<String> {
+        match self {
+            RuleField::Title => Some(file.title.clone().unwrap_or_else(|| file.filename.clone())),
+            RuleField::Artist => file.artist.clone(),
+            RuleField::Album => file.album.clone(),
+            RuleField::AlbumArtist => file.album_artist.clone(),
+            RuleField::Genre => file.genre.clone(),
+            RuleField::Year => file.year.map(|year| year.to_string()),
+            RuleField::Duration => file.duration.map(|duration| duration.as_secs().to_string()),
+            RuleField::Filename => Some(file.filename.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleOperator {
+    Is,
+    IsNot,
+    Contains,
+    StartsWith,
+    GreaterThan,
+    LessThan,
+}
+
+/// One condition. Text comparisons ignore case; `greater_than` and
+/// `less_than` compare numbers and only apply to year and duration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SmartRule {
+    pub field: RuleField,
+    pub operator: RuleOperator,
+    pub value: String,
+}
+
+impl SmartRule {
+    /// Whether a file's value for the rule's field satisfies it. Files
+    /// without the value never match, not even `is_not`.
+    fn matches_value(&self, actual: Option<&str>) -> bool {
+        let Some(actual) = actual else {
+            return false;
+        };
+        match self.operator {
+            RuleOperator::GreaterThan | RuleOperator::LessThan => {
+                let (Ok(actual), Ok(expected)) = (
+                    actual.trim().parse::<u64>(),
+                    self.value.trim().parse::<u64>(),
+                ) else {
+                    return false;
+                };
+                if self.operator == RuleOperator::GreaterThan {
+                    actual > expected
+                } else {
+                    actual < expected
+                }
+            }
+            RuleOperator::Is => actual.eq_ignore_ascii_case(self.value.trim()),
+            RuleOperator::IsNot => !actual.eq_ignore_ascii_case(self.value.trim()),
+            RuleOperator::Contains => actual
+                .to_lowercase()
+                .contains(&self.value.trim().to_lowercase()),
+            RuleOperator::StartsWith => actual
+                .to_lowercase()
+                .starts_with(&self.value.trim().to_lowercase()),
+        }
+    }
+
+    pub fn matches(&self, file: &MediaFile) -> bool {
+        self.matches_value(self.field.value(file).as_deref())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchMode {
+    /// Every rule must match.
+    #[default]
+    All,
+    /// At least one rule must match.
+    Any,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SmartPlaylist {
+    /// Assigned on creation; ignored in request bodies.
+    #[serde(default)]
+    pub id: i64,
+    pub name: String,
+    #[serde(default, rename = "match")]
+    pub match_mode: MatchMode,
+    pub rules: Vec<SmartRule>,
+    /// ContentDirectory sort criteria such as `-dc:date,+upnp:album`.
+    /// Empty keeps index order.
+    #[serde(default)]
+    pub sort: String,
+    /// Track cap, at most [`MAX_SMART_PLAYLIST_ITEMS`].
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl SmartPlaylist {
+    /// Check a definition before it is stored. Rules must be answerable from
+    /// an index: `all` needs at least one rule on an indexed field and `any`
+    /// needs every rule on one, so no rule set scans the whole library.
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Smart playlist name cannot be empty".to_string());
+        }
+        if self.rules.is_empty() {
+            return Err("Smart playlist needs at least one rule".to_string());
+        }
+        for rule in &self.rules {
+            let comparison = matches!(
+                rule.operator,
+                RuleOperator::GreaterThan | RuleOperator::LessThan
+            );
+            if comparison && !rule.field.is_numeric() {
+                return Err(format!(
+                    "{:?} rules only apply to year and duration",
+                    rule.operator
+                ));
+            }
+            if (comparison || rule.field.is_numeric() && rule.operator == RuleOperator::Is)
+                && rule.value.trim().parse::<u64>().is_err()
+            {
+                return Err(format!("'{}' is not a number", rule.value));
+            }
+        }
+        let indexed = self
+            .rules
+            .iter()
+            .filter(|rule| rule.field.is_indexed())
+            .count();
+        match self.match_mode {
+            MatchMode::All if indexed == 0 => {
+                return Err(
+                    "At least one rule must use artist, album, album_artist, genre or year"
+                        .to_string(),
+                )
+            }
+            MatchMode::Any if indexed != self.rules.len() => return Err(
+                "Rules of an 'any' playlist must use artist, album, album_artist, genre or year"
+                    .to_string(),
+            ),
+            _ => {}
+        }
+        match self.limit {
+            Some(0) => Err("Limit must be at least 1".to_string()),
+            Some(limit) if limit > MAX_SMART_PLAYLIST_ITEMS => {
+                Err(format!("Limit cannot exceed {MAX_SMART_PLAYLIST_ITEMS}"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn cap(&self) -> usize {
+        self.limit
+            .unwrap_or(MAX_SMART_PLAYLIST_ITEMS)
+            .min(MAX_SMART_PLAYLIST_ITEMS)
+    }
+}
+
+/// The tracks of `playlist` in its sort order, capped at its limit.
+pub async fn evaluate<D: MediaRepository + ?Sized>(
+    database: &D,
+    playlist: &SmartPlaylist,
+) -> Result<Vec<MediaFile>> {
+    let indexed: Vec<&SmartRule> = playlist
+        .rules
+        .iter()
+        .filter(|rule| rule.field.is_indexed())
+        .collect();
+    let starting_rules = match playlist.match_mode {
+        MatchMode::All => {
+            // The rule matching the fewest files is the cheapest to load.
+            let mut narrowest = None;
+            for rule in indexed {
+                let values = matching_categories(database, rule).await?;
+                let files: usize = values.iter().map(|category| category.count).sum();
+                if narrowest
+                    .as_ref()
+                    .is_none_or(|(count, _, _)| files < *count)
+                {
+                    narrowest = Some((files, rule, values));
+                }
+            }
+            narrowest
+                .map(|(_, rule, values)| vec![(rule, values)])
+                .unwrap_or_default()
+        }
+        MatchMode::Any => {
+            let mut starting = Vec::new();
+            for rule in indexed {
+                starting.push((rule, matching_categories(database, rule).await?));
+            }
+            starting
+        }
+    };
+
+    let mut seen = HashSet::new();
+    let mut files = Vec::new();
+    for (rule, values) in starting_rules {
+        for category in values {
+            for file in files_in_category(database, rule.field, &category).await? {
+                let matched = match playlist.match_mode {
+                    MatchMode::All => playlist.rules.iter().all(|rule| rule.matches(&file)),
+                    MatchMode::Any => playlist.rules.iter().any(|rule| rule.matches(&file)),
+                };
+                if matched && seen.insert(file.path.clone()) {
+                    files.push(file);
+                }
+            }
+        }
+    }
+
+    SortSpec::parse(&playlist.sort).sort_files(&mut files);
+    files.truncate(playlist.cap());
+    Ok(files)
+}
+
+async fn matching_categories<D: MediaRepository + ?Sized>(
+    database: &D,
+    rule: &SmartRule,
+) -> Result<Vec<MusicCategory>> {
+    let categories = match rule.field {
+        RuleField::Artist => database.get_artists().await?,
+        RuleField::Album => database.get_albums(None).await?,
+        RuleField::AlbumArtist => database.get_album_artists().await?,
+        RuleField::Genre => database.get_genres().await?,
+        RuleField::Year => database.get_years().await?,
+        _ => return Ok(Vec::new()),
+    };
+    let mut names = HashSet::new();
+    Ok(categories
+        .into_iter()
+        .filter(|category| rule.matches_value(Some(&category.name)))
+        .filter(|category| names.insert(category.name.clone()))
+        .collect())
+}
+
+async fn files_in_category<D: MediaRepository + ?Sized>(
+    database: &D,
+    field: RuleField,
+    category: &MusicCategory,
+) -> Result<Vec<MediaFile>> {
+    match field {
+        RuleField::Artist => database.get_music_by_artist(&category.name).await,
+        RuleField::Album => database.get_music_by_album(&category.name, None).await,
+        RuleField::AlbumArtist => database.get_music_by_album_artist(&category.name).await,
+        RuleField::Genre => database.get_music_by_genre(&category.name).await,
+        RuleField::Year => match category.name.parse() {
+            Ok(year) => database.get_music_by_year(year).await,
+            Err(_) => Ok(Vec::new()),
+        },
+        _ => Ok(Vec::new()),
+    }
+}
+
+async fn load<D: MediaRepository + ?Sized>(database: &D) -> Result<Vec<SmartPlaylist>> {
+    match database.get_setting(SMART_PLAYLISTS_SETTING).await? {
+        Some(stored) => {
+            serde_json::from_str(&stored).context("Stored smart playlists are not valid JSON")
+        }
+        None => Ok(Vec::new()),
+    }
+}
+
+async fn save<D: MediaRepository + ?Sized>(
+    database: &D,
+    playlists: &[SmartPlaylist],
+) -> Result<()> {
+    database
+        .set_setting(SMART_PLAYLISTS_SETTING, &serde_json::to_string(playlists)?)
+        .await
+}
+
+/// Every smart playlist, in creation order.
+pub async fn list<D: MediaRepository + ?Sized>(database: &D) -> Result<Vec<SmartPlaylist>> {
+    load(database).await
+}
+
+pub async fn get<D: MediaRepository + ?Sized>(
+    database: &D,
+    id: i64,
+) -> Result<Option<SmartPlaylist>> {
+    Ok(load(database)
+        .await?
+        .into_iter()
+        .find(|playlist| playlist.id == id))
+}
+
+/// Store a validated definition under a new id and return it.
+pub async fn create<D: MediaRepository + ?Sized>(
+    database: &D,
+    mut playlist: SmartPlaylist,
+) -> Result<SmartPlaylist> {
+    let _guard = STORE_LOCK.lock().await;
+    let mut playlists = load(database).await?;
+    playlist.id = playlists.iter().map(|stored| stored.id).max().unwrap_or(0) + 1;
+    playlists.push(playlist.clone());
+    save(database, &playlists).await?;
+    Ok(playlist)
+}
+
+/// Replace the definition of `id`. `None` when there is no such playlist.
+pub async fn update<D: MediaRepository + ?Sized>(
+    database: &D,
+    id: i64,
+    mut playlist: SmartPlaylist,
+) -> Result<Option<SmartPlaylist>> {
+    let _guard = STORE_LOCK.lock().await;
+    let mut playlists = load(database).await?;
+    let Some(stored) = playlists.iter_mut().find(|stored| stored.id == id) else {
+        return Ok(None);
+    };
+    playlist.id = id;
+    *stored = playlist.clone();
+    save(database, &playlists).await?;
+    Ok(Some(playlist))
+}
+
+/// Remove the definition of `id`, returning whether it existed.
+pub async fn delete<D: MediaRepository + ?Sized>(database: &D, id: i64) -> Result<bool> {
+    let _guard = STORE_LOCK.lock().await;
+    let mut playlists = load(database).await?;
+    let before = playlists.len();
+    playlists.retain(|playlist| playlist.id != id);
+    if playlists.len() == before {
+        return Ok(false);
+    }
+    save(database, &playlists).await?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::memory::InMemoryDatabase;
+    use crate::database::DatabaseManager;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn track(name: &str, artist: &str, genre: &str, year: u32, seconds: u64) -> MediaFile {
+        let mut file = MediaFile::new(
+            PathBuf::from(format!("/music/{name}.mp3")),
+            1024,
+            "audio/mpeg".to_string(),
+        );
+        file.title = Some(name.to_string());
+        file.artist = Some(artist.to_string());
+        file.genre = Some(genre.to_string());
+        file.year = Some(year);
+        file.duration = Some(Duration::from_secs(seconds));
+        file
+    }
+
+    fn rule(field: RuleField, operator: RuleOperator, value: &str) -> SmartRule {
+        SmartRule {
+            field,
+            operator,
+            value: value.to_string(),
+        }
+    }
+
+    fn playlist(match_mode: MatchMode, rules: Vec<SmartRule>) -> SmartPlaylist {
+        SmartPlaylist {
+            id: 0,
+            name: "Test".to_string(),
+            match_mode,
+            rules,
+            sort: String::new(),
+            limit: None,
+        }
+    }
+
+    async fn library() -> InMemoryDatabase {
+        let database = InMemoryDatabase::new();
+        database.initialize().await.unwrap();
+        database
+            .bulk_store_media_files(&[
+                track("So What", "Miles Davis", "Jazz", 1959, 562),
+                track("Tutu", "Miles Davis", "Jazz", 1986, 315),
+                track("Blue Train", "John Coltrane", "jazz", 1957, 643),
+                track("Paranoid", "Black Sabbath", "Metal", 1970, 170),
+            ])
+            .await
+            .unwrap();
+        database
+    }
+
+    fn titles(files: &[MediaFile]) -> Vec<&str> {
+        files
+            .iter()
+            .map(|file| file.title.as_deref().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn all_rules_filter_the_narrowest_index_and_follow_the_sort() {
+        let database = library().await;
+        let mut recent_jazz = playlist(
+            MatchMode::All,
+            vec![
+                rule(RuleField::Genre, RuleOperator::Is, "JAZZ"),
+                rule(RuleField::Year, RuleOperator::LessThan, "1980"),
+                rule(RuleField::Duration, RuleOperator::GreaterThan, "300"),
+            ],
+        );
+        recent_jazz.sort = "-res@duration".to_string();
+
+        let files = evaluate(&database, &recent_jazz).await.unwrap();
+        assert_eq!(titles(&files), vec!["Blue Train", "So What"]);
+
+        recent_jazz.limit = Some(1);
+        let files = evaluate(&database, &recent_jazz).await.unwrap();
+        assert_eq!(titles(&files), vec!["Blue Train"]);
+    }
+
+    #[tokio::test]
+    async fn any_rules_union_their_matches_once() {
+        let database = library().await;
+        let mut either = playlist(
+            MatchMode::Any,
+            vec![
+                rule(RuleField::Artist, RuleOperator::StartsWith, "miles"),
+                rule(RuleField::Year, RuleOperator::Is, "1986"),
+                rule(RuleField::Genre, RuleOperator::Is, "Metal"),
+            ],
+        );
+        either.sort = "+dc:title".to_string();
+
+        let files = evaluate(&database, &either).await.unwrap();
+        assert_eq!(titles(&files), vec!["Paranoid", "So What", "Tutu"]);
+    }
+
+    #[tokio::test]
+    async fn library_changes_show_up_on_the_next_evaluation() {
+        let database = library().await;
+        let metal = playlist(
+            MatchMode::All,
+            vec![rule(RuleField::Genre, RuleOperator::Is, "metal")],
+        );
+        assert_eq!(evaluate(&database, &metal).await.unwrap().len(), 1);
+
+        database
+            .bulk_store_media_files(&[track("Ace of Spades", "Motorhead", "Metal", 1980, 169)])
+            .await
+            .unwrap();
+        assert_eq!(evaluate(&database, &metal).await.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn definitions_that_would_scan_the_library_are_rejected() {
+        let title_only = playlist(
+            MatchMode::All,
+            vec![rule(RuleField::Title, RuleOperator::Contains, "blue")],
+        );
+        assert!(title_only.validate().is_err());
+
+        let mixed_any = playlist(
+            MatchMode::Any,
+            vec![
+                rule(RuleField::Genre, RuleOperator::Is, "Jazz"),
+                rule(RuleField::Title, RuleOperator::Contains, "blue"),
+            ],
+        );
+        assert!(mixed_any.validate().is_err());
+
+        let text_comparison = playlist(
+            MatchMode::All,
+            vec![rule(RuleField::Artist, RuleOperator::GreaterThan, "M")],
+        );
+        assert!(text_comparison.validate().is_err());
+
+        let mut too_long = playlist(
+            MatchMode::All,
+            vec![rule(RuleField::Genre, RuleOperator::Is, "Jazz")],
+        );
+        assert!(too_long.validate().is_ok());
+        too_long.limit = Some(MAX_SMART_PLAYLIST_ITEMS + 1);
+        assert!(too_long.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn definitions_round_trip_through_the_settings_store() {
+        let database = library().await;
+        let jazz = playlist(
+            MatchMode::All,
+            vec![rule(RuleField::Genre, RuleOperator::Is, "Jazz")],
+        );
+
+        let first = create(&database, jazz.clone()).await.unwrap();
+        let second = create(&database, jazz.clone()).await.unwrap();
+        assert_eq!((first.id, second.id), (1, 2));
+
+        let mut renamed = jazz.clone();
+        renamed.name = "Renamed".to_string();
+        assert!(update(&database, 2, renamed).await.unwrap().is_some());
+        assert_eq!(get(&database, 2).await.unwrap().unwrap().name, "Renamed");
+        assert!(update(&database, 9, jazz).await.unwrap().is_none());
+
+        assert!(delete(&database, 1).await.unwrap());
+        assert!(!delete(&database, 1).await.unwrap());
+        let remaining = list(&database).await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, 2);
+    }
+}
@@ -0,0 +1,420 @@
+//! ContentDirectory `SortCriteria` support.
+//!
+//! Renderers ask for an order such as `+upnp:album,+upnp:originalTrackNumber`.
+//! [`SortSpec::parse`] turns that into typed keys; fields VuIO cannot sort by
+//! are skipped, and a criteria string with no usable field falls back to title
+//! order instead of failing the Browse. Sorting happens in memory over the
+//! matches of one query, up to [`SORTED_QUERY_LIMIT`] records.
+
+use anyhow::Result;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use super::{file_revision, DatabaseReadSession, MediaFileQuery, MediaFileView, VisitSummary};
+
+/// Largest result set sorted in memory. Bigger queries keep storage order.
+pub const SORTED_QUERY_LIMIT: usize = 20_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SortField {
+    Title,
+    Date,
+    Class,
+    Album,
+    Artist,
+    Genre,
+    TrackNumber,
+    Size,
+    Duration,
+}
+
+impl SortField {
+    /// Every sortable field, in the order advertised by `GetSortCapabilities`.
+    pub const ALL: [SortField; 9] = [
+        SortField::Title,
+        SortField::Date,
+        SortField::Class,
+        SortField::Album,
+        SortField::Artist,
+        SortField::Genre,
+        SortField::TrackNumber,
+        SortField::Size,
+        SortField::Duration,
+    ];
+
+    pub fn property(self) -> &'static str {
+        match self {
+            SortField::Title => "dc:title",
+            SortField::Date => "dc:date",
+            SortField::Class => "upnp:class",
+            SortField::Album => "upnp:album",
+            SortField::Artist => "upnp:artist",
+            SortField::Genre => "upnp:genre",
+            SortField::TrackNumber => "upnp:originalTrackNumber",
+            SortField::Size => "res@size",
+            SortField::Duration => "res@duration",
+        }
+    }
+
+    fn from_property(property: &str) -> Option<Self> {
+        match property {
+            // dc:creator is what most renderers send for the artist column.
+            "dc:creator" => Some(SortField::Artist),
+            property => Self::ALL
+                .into_iter()
+                .find(|field| field.property() == property),
+        }
+    }
+
+    fn value<V: MediaFileView + ?Sized>(self, file: &V) -> Option<SortValue> {
+        let text = |value: &str| SortValue::Text(crate::natural_sort::collation_key(value));
+        match self {
+            SortField::Title => Some(text(file.title().unwrap_or(file.filename()))),
+            // Tagged media dates to its release year; everything else to
+            // its modification time.
+            SortField::Date => Some(SortValue::Number(
+                file.year()
+                    .and_then(year_start_secs)
+                    .unwrap_or_else(|| file.modified_secs()),
+            )),
+            SortField::Class => Some(text(file.mime_type().split('/').next().unwrap_or(""))),
+            SortField::Album => file.album().map(text),
+            SortField::Artist => file.artist().map(text),
+            SortField::Genre => file.genre().map(text),
+            SortField::TrackNumber => file.track_number().map(u64::from).map(SortValue::Number),
+            SortField::Size => Some(SortValue::Number(file.size())),
+            SortField::Duration => file
+                .duration_secs()
+                .map(|seconds| SortValue::Number((seconds * 1000.0) as u64)),
+        }
+    }
+}
+
+/// The `SortCaps` value of `GetSortCapabilities`.
+pub fn sort_capabilities() -> String {
+    SortField::ALL
+        .iter()
+        .map(|field| field.property())
+        .chain(["dc:creator"])
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SortKey {
+    pub field: SortField,
+    pub descending: bool,
+}
+
+/// Ordered sort keys. Empty means storage order, the natural filename order
+/// of a directory listing.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct SortSpec {
+    pub keys: Vec<SortKey>,
+}
+
+impl SortSpec {
+    /// Parse a ContentDirectory `SortCriteria` list such as
+    /// `+upnp:album,-dc:date`. A key without a sign sorts ascending.
+    pub fn parse(criteria: &str) -> Self {
+        let mut keys = Vec::new();
+        let mut requested = false;
+        for criterion in criteria.split(',').map(str::trim) {
+            if criterion.is_empty() {
+                continue;
+            }
+            requested = true;
+            let (descending, property) = match criterion.as_bytes()[0] {
+                b'-' => (true, &criterion[1..]),
+                b'+' => (false, &criterion[1..]),
+                _ => (false, criterion),
+            };
+            match SortField::from_property(property.trim()) {
+                Some(field) if !keys.iter().any(|key: &SortKey| key.field == field) => {
+                    keys.push(SortKey { field, descending })
+                }
+                Some(_) => {}
+                None => tracing::debug!(
+                    target: crate::logging::targets::DB,
+                    "Ignoring unsupported sort field '{}'",
+                    property
+                ),
+            }
+        }
+        if requested && keys.is_empty() {
+            keys.push(SortKey {
+                field: SortField::Title,
+                descending: false,
+            });
+        }
+        Self { keys }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Sort owned records in place. Ties keep their current order.
+    pub fn sort_files(&self, files: &mut Vec<super::MediaFile>) {
+        if self.is_empty() {
+            return;
+        }
+        let mut rows: Vec<(SortRow, super::MediaFile)> = files
+            .drain(..)
+            .map(|file| (self.row(&file), file))
+            .collect();
+        rows.sort_by(|left, right| self.compare(&left.0, &right.0));
+        files.extend(rows.into_iter().map(|(_, file)| file));
+    }
+
+    fn row<V: MediaFileView + ?Sized>(&self, file: &V) -> SortRow {
+        SortRow {
+            values: self.keys.iter().map(|key| key.field.value(file)).collect(),
+            path: file.path().into_owned(),
+        }
+    }
+
+    fn compare(&self, left: &SortRow, right: &SortRow) -> Ordering {
+        for (index, key) in self.keys.iter().enumerate() {
+            // Records without the value sort last in either direction.
+            let ordering = match (&left.values[index], &right.values[index]) {
+                (Some(left), Some(right)) if key.descending => right.cmp(left),
+                (Some(left), Some(right)) => left.cmp(right),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            };
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+        Ordering::Equal
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum SortValue {
+    Number(u64),
+    Text(String),
+}
+
+struct SortRow {
+    values: Vec<Option<SortValue>>,
+    path: String,
+}
+
+/// Visit one page of `query` in `sort` order, leaving out the files whose
+/// IDs are in `hidden`. Ties keep storage order.
+pub fn visit_sorted_files<S, F>(
+    session: &mut S,
+    query: &MediaFileQuery,
+    sort: &SortSpec,
+    hidden: &HashSet<i64>,
+    offset: usize,
+    limit: usize,
+    visitor: F,
+) -> Result<VisitSummary>
+where
+    S: DatabaseReadSession + ?Sized,
+    F: for<'a> FnMut(S::File<'a>) -> Result<()>,
+{
+    visit_sorted_files_with_revision(session, query, sort, hidden, offset, limit, None, visitor)
+}
+
+/// [`visit_sorted_files`], also folding the container revision of every
+/// file listed into `revision`, in the pass that counts them.
+#[allow(clippy::too_many_arguments)] // The page, plus the optional revision fold.
+pub fn visit_sorted_files_with_revision<S, F>(
+    session: &mut S,
+    query: &MediaFileQuery,
+    sort: &SortSpec,
+    hidden: &HashSet<i64>,
+    offset: usize,
+    limit: usize,
+    mut revision: Option<&mut u64>,
+    mut visitor: F,
+) -> Result<VisitSummary>
+where
+    S: DatabaseReadSession + ?Sized,
+    F: for<'a> FnMut(S::File<'a>) -> Result<()>,
+{
+    if sort.is_empty() {
+        return visit_unhidden_files(session, query, hidden, offset, limit, revision, visitor);
+    }
+    let mut rows = Vec::new();
+    let summary = session.visit_files(query, 0, SORTED_QUERY_LIMIT, |file| {
+        if !file.id().is_some_and(|id| hidden.contains(&id)) {
+            if let Some(revision) = revision.as_deref_mut() {
+                *revision = file_revision(*revision, &file);
+            }
+            rows.push(sort.row(&file));
+        }
+        Ok(())
+    })?;
+    if summary.matched > SORTED_QUERY_LIMIT {
+        tracing::debug!(
+            target: crate::logging::targets::DB,
+            "Not sorting {} matches (limit {}), keeping storage order",
+            summary.matched,
+            SORTED_QUERY_LIMIT
+        );
+        if let Some(revision) = revision.as_deref_mut() {
+            *revision = 0;
+        }
+        return visit_unhidden_files(session, query, hidden, offset, limit, revision, visitor);
+    }
+    rows.sort_by(|left, right| sort.compare(left, right));
+
+    let mut visited = 0;
+    for row in rows.iter().skip(offset).take(limit) {
+        visited += session
+            .visit_files(&MediaFileQuery::Path(row.path.clone()), 0, 1, &mut visitor)?
+            .visited;
+    }
+    Ok(VisitSummary {
+        matched: rows.len(),
+        visited,
+    })
+}
+
+/// One page of `query` in storage order without the `hidden` files. Folding
+/// a `revision` visits every match, as leaving files out does.
+fn visit_unhidden_files<S, F>(
+    session: &mut S,
+    query: &MediaFileQuery,
+    hidden: &HashSet<i64>,
+    offset: usize,
+    limit: usize,
+    mut revision: Option<&mut u64>,
+    mut visitor: F,
+) -> Result<VisitSummary>
+where
+    S: DatabaseReadSession + ?Sized,
+    F: for<'a> FnMut(S::File<'a>) -> Result<()>,
+{
+    if hidden.is_empty() && revision.is_none() {
+        return session.visit_files(query, offset, limit, visitor);
+    }
+    let mut summary = VisitSummary::default();
+    session.visit_files(query, 0, usize::MAX, |file| {
+        if file.id().is_some_and(|id| hidden.contains(&id)) {
+            return Ok(());
+        }
+        if let Some(revision) = revision.as_deref_mut() {
+            *revision = file_revision(*revision, &file);
+        }
+        summary.matched += 1;
+        if summary.matched > offset && summary.visited < limit {
+            summary.visited += 1;
+            visitor(file)?;
+        }
+        Ok(())
+    })?;
+    Ok(summary)
+}
+
+fn year_start_secs(year: u32) -> Option<u64> {
+    let start = chrono::NaiveDate::from_ymd_opt(i32::try_from(year).ok()?, 1, 1)?;
+    u64::try_from(start.and_hms_opt(0, 0, 0)?.and_utc().timestamp()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::memory::InMemoryDatabase;
+    use crate::database::{DatabaseManager, MediaFile, MediaRepository};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    #[test]
+    fn unsupported_fields_are_skipped_and_fall_back_to_title() {
+        assert!(SortSpec::parse("").is_empty());
+        assert_eq!(
+            SortSpec::parse("-dc:date, +upnp:bogus,dc:creator").keys,
+            vec![
+                SortKey {
+                    field: SortField::Date,
+                    descending: true,
+                },
+                SortKey {
+                    field: SortField::Artist,
+                    descending: false,
+                },
+            ]
+        );
+        assert_eq!(
+            SortSpec::parse("+upnp:bogus").keys,
+            vec![SortKey {
+                field: SortField::Title,
+                descending: false,
+            }]
+        );
+        assert!(sort_capabilities().starts_with("dc:title,dc:date,upnp:class"));
+    }
+
+    #[tokio::test]
+    async fn directory_pages_follow_the_sort_keys() {
+        let database = Arc::new(InMemoryDatabase::new());
+        database.initialize().await.unwrap();
+        let episode = |name: &str, track: Option<u32>| {
+            let mut file = MediaFile::new(
+                PathBuf::from(format!("/shows/{name}.mkv")),
+                1,
+                "video/x-matroska".to_owned(),
+            );
+            file.track_number = track;
+            file
+        };
+        database
+            .bulk_store_media_files(&[
+                episode("Episode 10", Some(1)),
+                episode("Episode 2", Some(3)),
+                episode("Episode 1", None),
+                episode("Episode 3", Some(2)),
+            ])
+            .await
+            .unwrap();
+
+        let page = |criteria: &'static str, offset: usize| {
+            database.clone().read(move |session| {
+                let query = MediaFileQuery::Directory {
+                    path: "/shows".to_owned(),
+                    mime_family: None,
+                };
+                let mut names = Vec::new();
+                let summary = visit_sorted_files(
+                    session,
+                    &query,
+                    &SortSpec::parse(criteria),
+                    &HashSet::new(),
+                    offset,
+                    2,
+                    |file| {
+                        names.push(file.filename().to_owned());
+                        Ok(())
+                    },
+                )?;
+                assert_eq!(summary.matched, 4);
+                Ok(names)
+            })
+        };
+
+        assert_eq!(
+            page("-dc:title", 0).await.unwrap(),
+            vec!["Episode 10.mkv", "Episode 3.mkv"]
+        );
+        assert_eq!(
+            page("-dc:title", 2).await.unwrap(),
+            vec!["Episode 2.mkv", "Episode 1.mkv"]
+        );
+        // Missing values sort last; unsupported criteria never fail the query.
+        assert_eq!(
+            page("+upnp:originalTrackNumber", 2).await.unwrap(),
+            vec!["Episode 2.mkv", "Episode 1.mkv"]
+        );
+        assert_eq!(
+            page("+upnp:bogus", 0).await.unwrap(),
+            vec!["Episode 1.mkv", "Episode 2.mkv"]
+        );
+    }
+}
@@ -0,0 +1,225 @@
+//! DVD backups: `VIDEO_TS` folders and `.iso` images.
+//!
+//! A `VIDEO_TS` folder is indexed as one video item whose path is the folder
+//! itself. Only the main title is played: the title set (`VTS_NN_*.VOB`) with
+//! the most data, its `VTS_NN_1.VOB`, `VTS_NN_2.VOB`, ... parts concatenated
+//! in order. `VTS_NN_0.VOB` holds the menus and is skipped. ISO images are
+//! indexed as video items and served with the configured
+//! `media.iso_mime_type`, for renderers that play disc images directly.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// MIME type of a `VIDEO_TS` item; VOBs are MPEG-2 program streams.
+pub const DVD_MIME_TYPE: &str = "video/mpeg";
+
+/// MIME type `.iso` images are indexed with, so they list as video. Clients
+/// see `media.iso_mime_type` instead.
+pub const ISO_MIME_TYPE: &str = "video/x-iso9660-image";
+
+/// The MIME type to send for an indexed `mime_type`.
+pub fn served_mime_type<'a>(mime_type: &'a str, iso_mime_type: &'a str) -> &'a str {
+    if mime_type == ISO_MIME_TYPE {
+        iso_mime_type
+    } else {
+        mime_type
+    }
+}
+
+/// Whether `path` is named `VIDEO_TS`, in any case.
+pub fn is_video_ts_dir(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.eq_ignore_ascii_case("VIDEO_TS"))
+}
+
+/// Whether `path` lies below a `VIDEO_TS` folder. Those files belong to the
+/// folder's item and are never indexed on their own.
+pub fn is_inside_video_ts(path: &Path) -> bool {
+    path.ancestors().skip(1).any(is_video_ts_dir)
+}
+
+/// The VOB parts of a DVD's main title, in playback order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DvdTitle {
+    /// Each part with its size in bytes.
+    pub segments: Vec<(PathBuf, u64)>,
+    /// Latest modification time of the parts.
+    pub modified: SystemTime,
+}
+
+impl DvdTitle {
+    pub fn size(&self) -> u64 {
+        self.segments.iter().map(|(_, size)| size).sum()
+    }
+
+    /// The part holding byte `offset` of the title and the offset within it.
+    pub fn locate(&self, offset: u64) -> Option<(usize, u64)> {
+        let mut start = 0;
+        for (index, (_, size)) in self.segments.iter().enumerate() {
+            if offset < start + size {
+                return Some((index, offset - start));
+            }
+            start += size;
+        }
+        None
+    }
+}
+
+/// `(title set, part)` of a `VTS_NN_M.VOB` name.
+fn title_part(name: &str) -> Option<(u32, u32)> {
+    let name = name.to_ascii_uppercase();
+    let stem = name.strip_prefix("VTS_")?.strip_suffix(".VOB")?;
+    let (title_set, part) = stem.split_once('_')?;
+    if title_set.len() != 2 || part.is_empty() {
+        return None;
+    }
+    Some((title_set.parse().ok()?, part.parse().ok()?))
+}
+
+/// The main title of the `VIDEO_TS` folder at `video_ts`, or `None` when it
+/// holds no title VOBs. Symbolic links are ignored.
+pub fn main_title(video_ts: &Path) -> io::Result<Option<DvdTitle>> {
+    let mut parts: Vec<(u32, u32, PathBuf, u64, SystemTime)> = Vec::new();
+    for entry in std::fs::read_dir(video_ts)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+        let Some((title_set, part)) = entry.file_name().to_str().and_then(title_part) else {
+            continue;
+        };
+        if part == 0 {
+            continue;
+        }
+        let metadata = entry.metadata()?;
+        let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+        parts.push((title_set, part, entry.path(), metadata.len(), modified));
+    }
+
+    let mut title_sets: Vec<u32> = parts.iter().map(|part| part.0).collect();
+    title_sets.sort_unstable();
+    title_sets.dedup();
+    let Some(main) = title_sets.into_iter().max_by_key(|title_set| {
+        let size: u64 = parts
+            .iter()
+            .filter(|part| part.0 == *title_set)
+            .map(|part| part.3)
+            .sum();
+        // The lowest title set wins a tie.
+        (size, std::cmp::Reverse(*title_set))
+    }) else {
+        return Ok(None);
+    };
+
+    parts.retain(|part| part.0 == main);
+    parts.sort_unstable_by_key(|part| part.1);
+    let modified = parts
+        .iter()
+        .map(|part| part.4)
+        .max()
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+    Ok(Some(DvdTitle {
+        segments: parts
+            .into_iter()
+            .map(|(_, _, path, size, _)| (path, size))
+            .collect(),
+        modified,
+    }))
+}
+
+/// [`main_title`] on the blocking pool, for async callers.
+pub async fn load_main_title(video_ts: &Path) -> io::Result<Option<DvdTitle>> {
+    let video_ts = video_ts.to_path_buf();
+    tokio::task::spawn_blocking(move || main_title(&video_ts))
+        .await
+        .map_err(io::Error::other)?
+}
+
+/// Whether `path` is a `VIDEO_TS` folder with a playable title.
+pub fn is_dvd_folder(path: &Path) -> bool {
+    is_video_ts_dir(path) && matches!(main_title(path), Ok(Some(_)))
+}
+
+/// Title of the item for the `VIDEO_TS` folder at `video_ts`: the name of
+/// the folder holding it.
+pub fn dvd_title_name(video_ts: &Path) -> Option<String> {
+    video_ts.parent()?.file_name()?.to_str().map(str::to_owned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, bytes: usize) {
+        std::fs::write(path, vec![0u8; bytes]).unwrap();
+    }
+
+    #[test]
+    fn the_largest_title_set_is_the_main_title_in_part_order() {
+        let temp = tempfile::tempdir().unwrap();
+        let video_ts = temp.path().join("Movie").join("VIDEO_TS");
+        std::fs::create_dir_all(&video_ts).unwrap();
+        write(&video_ts.join("VIDEO_TS.VOB"), 50);
+        write(&video_ts.join("VTS_01_0.VOB"), 40);
+        write(&video_ts.join("VTS_01_1.VOB"), 10);
+        write(&video_ts.join("VTS_02_0.VOB"), 5);
+        write(&video_ts.join("VTS_02_2.VOB"), 7);
+        write(&video_ts.join("vts_02_1.vob"), 8);
+        write(&video_ts.join("VTS_02_10.VOB"), 3);
+        write(&video_ts.join("VTS_02_1.IFO"), 100);
+
+        let title = main_title(&video_ts).unwrap().unwrap();
+        let names: Vec<_> = title
+            .segments
+            .iter()
+            .map(|(path, size)| (path.file_name().unwrap().to_str().unwrap(), *size))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                ("vts_02_1.vob", 8),
+                ("VTS_02_2.VOB", 7),
+                ("VTS_02_10.VOB", 3)
+            ]
+        );
+        assert_eq!(title.size(), 18);
+        assert!(is_dvd_folder(&video_ts));
+        assert_eq!(dvd_title_name(&video_ts).as_deref(), Some("Movie"));
+        assert!(is_inside_video_ts(&video_ts.join("VTS_02_1.VOB")));
+        assert!(!is_inside_video_ts(&video_ts));
+
+        let empty = temp.path().join("Empty").join("video_ts");
+        std::fs::create_dir_all(&empty).unwrap();
+        write(&empty.join("VTS_01_0.VOB"), 4);
+        assert_eq!(main_title(&empty).unwrap(), None);
+        assert!(!is_dvd_folder(&empty));
+    }
+
+    #[test]
+    fn offsets_map_to_the_part_that_holds_them() {
+        let title = DvdTitle {
+            segments: vec![
+                (PathBuf::from("1"), 4),
+                (PathBuf::from("2"), 0),
+                (PathBuf::from("3"), 3),
+            ],
+            modified: SystemTime::UNIX_EPOCH,
+        };
+        assert_eq!(title.locate(0), Some((0, 0)));
+        assert_eq!(title.locate(3), Some((0, 3)));
+        assert_eq!(title.locate(4), Some((2, 0)));
+        assert_eq!(title.locate(6), Some((2, 2)));
+        assert_eq!(title.locate(7), None);
+    }
+
+    #[test]
+    fn iso_images_are_served_with_the_configured_type() {
+        assert_eq!(
+            served_mime_type(ISO_MIME_TYPE, "application/x-iso9660-image"),
+            "application/x-iso9660-image"
+        );
+        assert_eq!(served_mime_type("video/mp4", "video/mpeg"), "video/mp4");
+    }
+}
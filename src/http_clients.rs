@@ -6,6 +6,7 @@ use std::time::Duration;
 static LOCAL: OnceLock<Client> = OnceLock::new();
 static EVENT: OnceLock<Client> = OnceLock::new();
 static UPDATER: OnceLock<Client> = OnceLock::new();
+static REMOTE_MEDIA: OnceLock<Client> = OnceLock::new();
 
 fn shared(slot: &'static OnceLock<Client>, builder: reqwest::ClientBuilder) -> Result<Client> {
     if let Some(client) = slot.get() {
@@ -50,3 +51,17 @@ pub fn updater() -> Result<Client> {
             .user_agent(format!("vuio-updater/{}", env!("CARGO_PKG_VERSION"))),
     )
 }
+
+/// Remote media roots. Streams last as long as playback, so only a stalled
+/// read times out. Redirects are disabled so a server cannot move a stream
+/// outside its configured root.
+pub fn remote_media() -> Result<Client> {
+    shared(
+        &REMOTE_MEDIA,
+        Client::builder()
+            .connect_timeout(Duration::from_secs(10))
+            .read_timeout(Duration::from_secs(30))
+            .redirect(reqwest::redirect::Policy::none())
+            .user_agent(format!("VuIO/{}", env!("CARGO_PKG_VERSION"))),
+    )
+}
@@ -3,14 +3,18 @@
 
 pub mod config;
 pub mod database;
+pub mod dvd;
 pub mod error;
 pub mod http_clients;
 pub mod lifecycle;
 pub mod logging;
 pub mod media;
 pub mod natural_sort;
+pub mod overlay;
 pub mod platform;
+pub mod remote;
 pub mod runtime_state;
+pub mod scan_progress;
 pub mod ssdp;
 pub mod tv_control;
 pub mod watcher;
@@ -50,6 +54,43 @@ pub mod state {
         }
     }
 
+    /// What `/health` reports: whether startup has reached the point of
+    /// serving HTTP, and whether the database came up usable.
+    #[derive(Default)]
+    pub struct ServiceHealth {
+        serving: std::sync::atomic::AtomicBool,
+        database_failure: std::sync::RwLock<Option<String>>,
+    }
+
+    impl ServiceHealth {
+        /// The database is initialized and the HTTP server is accepting
+        /// requests. The initial media scan may still be running.
+        pub fn mark_serving(&self) {
+            self.serving
+                .store(true, std::sync::atomic::Ordering::Release);
+        }
+
+        pub fn is_serving(&self) -> bool {
+            self.serving.load(std::sync::atomic::Ordering::Acquire)
+        }
+
+        /// Record that the startup health check found corruption it could
+        /// not repair.
+        pub fn record_database_failure(&self, reason: String) {
+            *self
+                .database_failure
+                .write()
+                .unwrap_or_else(|error| error.into_inner()) = Some(reason);
+        }
+
+        pub fn database_failure(&self) -> Option<String> {
+            self.database_failure
+                .read()
+                .unwrap_or_else(|error| error.into_inner())
+                .clone()
+        }
+    }
+
     #[derive(Hash, PartialEq, Eq, Clone, Debug)]
     pub struct SoapCacheKey {
         pub object_id: String,
@@ -59,6 +100,9 @@ pub mod state {
         pub client_profile: crate::web::client::DlnaClientProfile,
         pub content_update_id: u32,
         pub browse_epoch: u64,
+        pub sort_criteria: String,
+        /// Resource links embed the origin, which differs behind a proxy.
+        pub origin: String,
     }
 
     #[derive(Clone)]
@@ -93,17 +137,28 @@ pub mod state {
         pub auth: Arc<crate::web::auth::ReloadableAuthState>,
         pub auth_forced: bool,
         pub platform_info: Arc<PlatformInfo>,
+        /// Interfaces from the latest runtime re-detection. `None` until the
+        /// announced interface first changes; `platform_info` applies until then.
+        pub network_interfaces:
+            Arc<std::sync::RwLock<Option<Vec<crate::platform::NetworkInterface>>>>,
         pub filesystem_manager: Arc<dyn FileSystemManager>,
         pub content_update_id: Arc<std::sync::atomic::AtomicU32>,
         pub content_change_notify: Arc<tokio::sync::Notify>,
         pub http_rebind_notify: Arc<tokio::sync::Notify>,
         pub ssdp_reload_notify: Arc<tokio::sync::Notify>,
+        pub playlist_mirror_notify: Arc<tokio::sync::Notify>,
         pub web_metrics: Arc<crate::web::diagnostics::WebHandlerMetrics>,
+        pub stream_audit: Option<Arc<crate::web::audit::StreamAuditLog>>,
+        pub scan_control: Arc<crate::scan_progress::ScanControl>,
+        pub health: Arc<ServiceHealth>,
         pub runtime_diagnostics: Arc<crate::platform::diagnostics::SystemDiagnosticsSampler>,
         pub lifecycle_stats: Arc<crate::lifecycle::ApplicationStats>,
         pub bookmarks: Arc<tokio::sync::Mutex<crate::runtime_state::BookmarkRegistry>>,
         pub log_file_path: std::path::PathBuf,
         pub browse_cache: Arc<tokio::sync::Mutex<crate::runtime_state::BrowseResponseCache>>,
+        pub hidden_files: Arc<tokio::sync::Mutex<crate::runtime_state::HiddenFiles>>,
+        pub active_streams: Arc<crate::runtime_state::StreamSessions>,
+        pub container_update_ids: Arc<crate::runtime_state::ContainerUpdateIds>,
         pub mcp_clients: Arc<tokio::sync::Mutex<std::collections::HashMap<String, McpClient>>>,
         pub active_monitors: Arc<
             tokio::sync::Mutex<
@@ -137,17 +192,25 @@ pub mod state {
                 auth: self.auth.clone(),
                 auth_forced: self.auth_forced,
                 platform_info: self.platform_info.clone(),
+                network_interfaces: self.network_interfaces.clone(),
                 filesystem_manager: self.filesystem_manager.clone(),
                 content_update_id: self.content_update_id.clone(),
                 content_change_notify: self.content_change_notify.clone(),
                 http_rebind_notify: self.http_rebind_notify.clone(),
                 ssdp_reload_notify: self.ssdp_reload_notify.clone(),
+                playlist_mirror_notify: self.playlist_mirror_notify.clone(),
                 web_metrics: self.web_metrics.clone(),
+                stream_audit: self.stream_audit.clone(),
+                scan_control: self.scan_control.clone(),
+                health: self.health.clone(),
                 runtime_diagnostics: self.runtime_diagnostics.clone(),
                 lifecycle_stats: self.lifecycle_stats.clone(),
                 bookmarks: self.bookmarks.clone(),
                 log_file_path: self.log_file_path.clone(),
                 browse_cache: self.browse_cache.clone(),
+                hidden_files: self.hidden_files.clone(),
+                active_streams: self.active_streams.clone(),
+                container_update_ids: self.container_update_ids.clone(),
                 mcp_clients: self.mcp_clients.clone(),
                 active_monitors: self.active_monitors.clone(),
                 active_casts: self.active_casts.clone(),
@@ -165,6 +228,105 @@ pub mod state {
         pub fn current_config(&self) -> Arc<AppConfig> {
             self.live_config.load()
         }
+        /// The interface SSDP announces, from the latest interface detection.
+        pub fn primary_interface(&self) -> Option<crate::platform::NetworkInterface> {
+            let config = self.current_config();
+            let preferred_subnet = config.network.preferred_subnet.as_deref();
+            let detected = self
+                .network_interfaces
+                .read()
+                .unwrap_or_else(|error| error.into_inner());
+            match detected.as_deref() {
+                Some(interfaces) => {
+                    crate::platform::primary_interface_in(interfaces, preferred_subnet).cloned()
+                }
+                None => self
+                    .platform_info
+                    .get_primary_interface_in(preferred_subnet)
+                    .cloned(),
+            }
+        }
+
+        /// Interfaces from the latest detection.
+        pub fn detected_interfaces(&self) -> Vec<crate::platform::NetworkInterface> {
+            self.network_interfaces
+                .read()
+                .unwrap_or_else(|error| error.into_inner())
+                .clone()
+                .unwrap_or_else(|| self.platform_info.network_interfaces.clone())
+        }
+
+        /// The detected interfaces a `network.interface_selection` list names,
+        /// in list order, or `None` for any other selection. Listed interfaces
+        /// that have since disappeared are left out.
+        pub fn selected_interfaces(&self) -> Option<Vec<crate::platform::NetworkInterface>> {
+            let config = self.current_config();
+            let crate::config::NetworkInterfaceConfig::Multiple(selectors) =
+                &config.network.interface_selection
+            else {
+                return None;
+            };
+            let detected = self.detected_interfaces();
+            let mut selected = Vec::new();
+            for selector in selectors {
+                if let Ok(found) =
+                    crate::platform::select_interfaces(&detected, std::slice::from_ref(selector))
+                {
+                    for interface in found {
+                        if !selected
+                            .iter()
+                            .any(|existing: &crate::platform::NetworkInterface| {
+                                existing.ip_address == interface.ip_address
+                            })
+                        {
+                            selected.push(interface);
+                        }
+                    }
+                }
+            }
+            Some(selected)
+        }
+
+        /// Interfaces SSDP announces on one at a time, each with its own
+        /// LOCATION: those a `network.interface_selection` list names, or
+        /// every usable IPv4 interface for `All`. `None` for the other
+        /// selections, which announce the single [`Self::get_server_ip`].
+        pub fn announced_interfaces(&self) -> Option<Vec<crate::platform::NetworkInterface>> {
+            match &self.current_config().network.interface_selection {
+                crate::config::NetworkInterfaceConfig::All => Some(
+                    self.detected_interfaces()
+                        .into_iter()
+                        .filter(|interface| {
+                            interface.is_up
+                                && !interface.is_loopback
+                                && interface.supports_multicast
+                                && interface.ip_address.is_ipv4()
+                        })
+                        .collect(),
+                ),
+                crate::config::NetworkInterfaceConfig::Multiple(_) => self.selected_interfaces(),
+                _ => None,
+            }
+        }
+
+        /// Address clients on `interface`'s network reach the server at: the
+        /// interface's own, unless `server.ip` or a specific
+        /// `server.interface` pins a single address.
+        pub fn get_server_ip_for(&self, interface: &crate::platform::NetworkInterface) -> String {
+            let config = self.current_config();
+            let pinned_ip = config
+                .server
+                .ip
+                .as_deref()
+                .is_some_and(|ip| !ip.is_empty() && ip != "0.0.0.0");
+            let pinned_bind =
+                !config.server.interface.is_empty() && config.server.interface != "0.0.0.0";
+            if pinned_ip || pinned_bind {
+                return self.get_server_ip();
+            }
+            interface.ip_address.to_string()
+        }
+
         /// Get the server's IP address using unified logic from platform_info
         pub fn get_server_ip(&self) -> String {
             let config = self.current_config();
@@ -180,6 +342,11 @@ pub mod state {
                 crate::config::NetworkInterfaceConfig::Specific(ip) => {
                     return ip.clone();
                 }
+                crate::config::NetworkInterfaceConfig::Multiple(_) => {
+                    if let Some(first) = self.selected_interfaces().into_iter().flatten().next() {
+                        return first.ip_address.to_string();
+                    }
+                }
                 _ => {
                     // For Auto or All, fallback to server interface if it's not 0.0.0.0
                     if config.server.interface != "0.0.0.0" && !config.server.interface.is_empty() {
@@ -188,8 +355,8 @@ pub mod state {
                 }
             }
 
-            // Use the primary interface detected at startup instead of re-detecting
-            if let Some(primary_interface) = self.platform_info.get_primary_interface() {
+            // Use the last detected primary interface instead of re-detecting
+            if let Some(primary_interface) = self.primary_interface() {
                 return primary_interface.ip_address.to_string();
             }
 
@@ -208,8 +375,29 @@ pub mod state {
         /// Absolute HTTP origin advertised to DLNA clients. Request `Host`
         /// headers are deliberately excluded because they describe untrusted
         /// inbound routing, not this server's public identity.
+        /// `server.base_url` replaces it when set.
         pub fn advertised_http_origin(&self) -> String {
-            let address = self.get_server_ip();
+            if let Some(base_url) = self.configured_base_url() {
+                return base_url;
+            }
+            self.http_origin_at(self.get_server_ip())
+        }
+
+        /// [`Self::advertised_http_origin`] as seen from `peer`: with
+        /// per-interface announcements, the origin of the announced
+        /// interface nearest to it.
+        pub fn http_origin_for_peer(&self, peer: std::net::IpAddr) -> String {
+            if let Some(base_url) = self.configured_base_url() {
+                return base_url;
+            }
+            let interfaces = self.announced_interfaces().unwrap_or_default();
+            match crate::platform::nearest_interface(&interfaces, peer) {
+                Some(interface) => self.http_origin_at(self.get_server_ip_for(interface)),
+                None => self.advertised_http_origin(),
+            }
+        }
+
+        fn http_origin_at(&self, address: String) -> String {
             let host = address
                 .parse::<std::net::IpAddr>()
                 .map_or(address.clone(), |ip| match ip {
@@ -218,5 +406,14 @@ pub mod state {
                 });
             format!("http://{}:{}", host, self.current_config().server.port)
         }
+
+        /// `server.base_url` without its trailing slash.
+        pub fn configured_base_url(&self) -> Option<String> {
+            self.current_config()
+                .server
+                .base_url
+                .as_deref()
+                .map(|base_url| base_url.trim().trim_end_matches('/').to_owned())
+        }
     }
 }
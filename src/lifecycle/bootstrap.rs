@@ -1,3 +1,18 @@
+/// Whether a reload leaves the HTTP address pending, and whether the
+/// configured address changed. The live port may differ from the configured
+/// one after a startup fallback; that alone is neither pending nor a reason
+/// to rebind on every reload.
+fn http_address_reload(
+    old_desired: &crate::config::ServerConfig,
+    live: &crate::config::ServerConfig,
+    new: &crate::config::ServerConfig,
+    was_pending: bool,
+) -> (bool, bool) {
+    let changed = old_desired.port != new.port || old_desired.interface != new.interface;
+    let differs = live.port != new.port || live.interface != new.interface;
+    (differs && (changed || was_pending), changed)
+}
+
 /// Start platform adaptation services for runtime detection and adaptation
 async fn start_platform_adaptation<D: DatabaseManager + 'static>(
     _platform_info: Arc<PlatformInfo>,
@@ -23,22 +38,47 @@ async fn start_platform_adaptation<D: DatabaseManager + 'static>(
                                 ConfigChangeEvent::Reloaded(new_config) => {
                                     let new_config = *new_config;
                                     let old_config = app_state.current_config();
+                                    let old_desired = app_state.desired_config.load();
                                     app_state.desired_config.store(Arc::new(new_config.clone()));
                                     let mut effective = (*old_config).clone();
                                     let mut errors = Vec::new();
                                     let mut pending = Vec::new();
                                     let mut rebind_http = false;
 
-                                    // Listener rebinding is intentionally conservative: identity
-                                    // fields hot-reload, while a bind-address change remains pending
-                                    // until a replacement listener can be introduced safely.
-                                    if old_config.server.port == new_config.server.port
-                                        && old_config.server.interface == new_config.server.interface
-                                    {
-                                        effective.server = new_config.server.clone();
-                                    } else {
+                                    // Identity fields hot-reload. A bind-address change stays on the
+                                    // old address, and pending, until the HTTP supervisor has bound a
+                                    // replacement listener; it then switches the effective address and
+                                    // restarts SSDP so the new LOCATION is only announced once live.
+                                    effective.server = crate::config::ServerConfig {
+                                        port: old_config.server.port,
+                                        interface: old_config.server.interface.clone(),
+                                        tls_port: old_config.server.tls_port,
+                                        ..new_config.server.clone()
+                                    };
+                                    let was_pending = app_state
+                                        .pending_restart_fields
+                                        .read()
+                                        .unwrap_or_else(|e| e.into_inner())
+                                        .iter()
+                                        .any(|field| field == "server.port");
+                                    let (address_pending, address_changed) = http_address_reload(
+                                        &old_desired.server,
+                                        &old_config.server,
+                                        &new_config.server,
+                                        was_pending,
+                                    );
+                                    if address_pending {
                                         pending.extend(["server.port".to_owned(), "server.interface".to_owned()]);
-                                        rebind_http = true;
+                                        rebind_http = address_changed && new_config.server.rebind_on_change;
+                                    }
+                                    // New certificate paths are picked up by the HTTPS listener;
+                                    // adding, removing or moving it needs a restart.
+                                    if old_config.server.tls_port != new_config.server.tls_port {
+                                        pending.push("server.tls_port".to_owned());
+                                    }
+                                    if old_config.server.tls.is_some() != new_config.server.tls.is_some() {
+                                        effective.server.tls = old_config.server.tls.clone();
+                                        pending.push("server.tls".to_owned());
                                     }
                                     effective.network = new_config.network.clone();
 
@@ -66,6 +106,7 @@ async fn start_platform_adaptation<D: DatabaseManager + 'static>(
                                     };
                                     app_state.discovery_service.reconfigure(discovery_config).await;
                                     effective.cast = new_config.cast.clone();
+                                    effective.renderers = new_config.renderers.clone();
 
                                     let active_directories = new_config
                                         .media
@@ -89,10 +130,25 @@ async fn start_platform_adaptation<D: DatabaseManager + 'static>(
                                         *app_state.media_directories.write().await =
                                             new_config.media.directories.clone();
                                         effective.media = new_config.media.clone();
+                                        // Stored order keys were built with the old collation.
+                                        if old_config.media.natural_sort != new_config.media.natural_sort {
+                                            effective.media.natural_sort = old_config.media.natural_sort;
+                                            pending.push("media.natural_sort".to_owned());
+                                        }
+                                        if old_config.media.playlist_mirror_dir != new_config.media.playlist_mirror_dir {
+                                            app_state.playlist_mirror_notify.notify_one();
+                                        }
+                                        if old_config.media.samsung_bookmarks != new_config.media.samsung_bookmarks
+                                            || old_config.media.dlna_playlist_editing != new_config.media.dlna_playlist_editing
+                                            || old_config.media.hide_duplicates != new_config.media.hide_duplicates
+                                        {
+                                            crate::web::eventing::invalidate_browse_responses(&app_state).await;
+                                        }
                                     }
 
                                     if !old_config.database.backup_enabled
                                         && new_config.database.backup_enabled
+                                        && old_config.database.backend == DatabaseBackendKind::Redb
                                     {
                                         if let Err(error) = create_lifecycle_backup(&app_state.database, &new_config).await {
                                             errors.push(format!("database backup: {error}"));
@@ -104,15 +160,37 @@ async fn start_platform_adaptation<D: DatabaseManager + 'static>(
                                     }
                                     effective.database.compact_on_shutdown =
                                         new_config.database.compact_on_shutdown;
+                                    effective.database.backup_interval_hours =
+                                        new_config.database.backup_interval_hours;
+                                    effective.database.backup_dir = new_config.database.backup_dir.clone();
+                                    effective.database.backup_keep = new_config.database.backup_keep;
+                                    if old_config.database.backend != new_config.database.backend {
+                                        pending.push("database.backend".to_owned());
+                                    }
                                     if old_config.database.path != new_config.database.path {
                                         pending.push("database.path".to_owned());
                                     }
                                     if old_config.database.redb_cache_mb != new_config.database.redb_cache_mb {
                                         pending.push("database.redb_cache_mb".to_owned());
                                     }
+                                    if old_config.database.max_connections != new_config.database.max_connections {
+                                        pending.push("database.max_connections".to_owned());
+                                    }
                                     if old_config.database.vacuum_on_startup != new_config.database.vacuum_on_startup {
                                         pending.push("database.vacuum_on_startup".to_owned());
                                     }
+                                    if old_config.logging.stream_audit_file != new_config.logging.stream_audit_file {
+                                        pending.push("logging.stream_audit_file".to_owned());
+                                    }
+                                    if old_config.logging.filter != new_config.logging.filter {
+                                        let applied = crate::logging::log_filter().map_or(Ok(()), |log_filter| {
+                                            log_filter.apply_configured(new_config.logging.filter.as_deref())
+                                        });
+                                        match applied {
+                                            Ok(()) => effective.logging.filter = new_config.logging.filter.clone(),
+                                            Err(error) => errors.push(format!("logging.filter: {error}")),
+                                        }
+                                    }
 
                                     app_state.live_config.store(Arc::new(effective));
                                     *app_state.config_reload_errors.write().unwrap_or_else(|e| e.into_inner()) = errors;
@@ -286,6 +364,7 @@ async fn initialize_config_manager(
         info!("Docker environment detected - using environment variables for configuration");
         let config = AppConfig::from_env()
             .context("Failed to load configuration from environment variables")?;
+        config.keep_derived_device_uuid();
 
         info!("Configuration initialized from environment variables");
         info!(
@@ -482,8 +561,181 @@ fn discard_incompatible_database(db_path: &std::path::Path) -> anyhow::Result<()
     Ok(())
 }
 
-/// Initialize database manager with health checks and recovery
-async fn initialize_database(config: &AppConfig) -> anyhow::Result<database::redb::RedbDatabase> {
+/// Exclusive lock on the file beside the on-disk database, held for as long
+/// as this process uses the database, so a server and `vuio scan` or
+/// `vuio rebuild-db` never open it at the same time.
+struct DatabaseLock {
+    _file: std::fs::File,
+}
+
+/// Lock the configured database, or fail when another VuIO process holds it.
+/// Filesystems without lock support are used unlocked, with a warning.
+fn lock_database(config: &AppConfig) -> anyhow::Result<Option<DatabaseLock>> {
+    if config.database.backend != DatabaseBackendKind::Redb {
+        return Ok(None);
+    }
+    let db_path = config.get_database_path().with_extension("redb");
+    let lock_path = db_path.with_extension("lock");
+    if let Some(parent) = lock_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .with_context(|| format!("Failed to open database lock {}", lock_path.display()))?;
+    match file.try_lock() {
+        Ok(()) => Ok(Some(DatabaseLock { _file: file })),
+        Err(std::fs::TryLockError::WouldBlock) => anyhow::bail!(
+            "Database {} is in use by another VuIO process; stop it first",
+            db_path.display()
+        ),
+        Err(std::fs::TryLockError::Error(error)) => {
+            warn!(
+                "Cannot lock {} ({}); not guarding against concurrent use",
+                lock_path.display(),
+                error
+            );
+            Ok(None)
+        }
+    }
+}
+
+/// Clear the Redb index for `vuio rebuild-db` by removing its file. The old
+/// file is always kept in the backup directory as a `pre-rebuild-` copy,
+/// which is never pruned, since it holds the only copy of user playlists,
+/// bookmarks and settings.
+fn discard_database_for_rebuild(config: &AppConfig) -> anyhow::Result<()> {
+    if config.database.backend != DatabaseBackendKind::Redb {
+        return Ok(());
+    }
+    let db_path = config.get_database_path().with_extension("redb");
+    if !db_path.exists() {
+        return Ok(());
+    }
+    let backup_dir = config.get_backup_dir();
+    std::fs::create_dir_all(&backup_dir)?;
+    let pre_rebuild = backup_dir.join(format!(
+        "pre-rebuild-{}-{}.redb",
+        chrono::Utc::now().format("%Y%m%dT%H%M%SZ"),
+        uuid::Uuid::new_v4().simple()
+    ));
+    std::fs::copy(&db_path, &pre_rebuild).with_context(|| {
+        format!("Failed to keep a copy of {} before rebuilding", db_path.display())
+    })?;
+    info!("Kept the previous database as {}", pre_rebuild.display());
+    std::fs::remove_file(&db_path)
+        .with_context(|| format!("Failed to remove database {}", db_path.display()))?;
+    info!("Removed database {} for rebuild", db_path.display());
+    Ok(())
+}
+
+/// Lifecycle backups in `backup_dir`, newest first. Pre-repair copies are
+/// left out: they were taken from the database that is now failing.
+fn recovery_candidates(backup_dir: &std::path::Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(backup_dir) else {
+        return Vec::new();
+    };
+    let mut backups = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("vuio-") && name.ends_with(".redb"))
+        })
+        .collect::<Vec<_>>();
+    // Names start with a UTC timestamp.
+    backups.sort_unstable_by(|a, b| b.cmp(a));
+    backups
+}
+
+/// Move the unusable database at `db_path` aside and put a working one in
+/// its place. With backups or `auto_restore` enabled, the newest backup that
+/// opens and passes the health check is restored and returned with its
+/// health report. When there is none, or every one fails, an empty database
+/// is created as before; the backups themselves are never modified.
+async fn replace_failed_database(
+    config: &AppConfig,
+    db_path: &std::path::Path,
+) -> anyhow::Result<(database::redb::RedbDatabase, Option<database::DatabaseHealth>)> {
+    let cache_size_mb = config.database.redb_cache_mb;
+    preserve_failed_database(db_path)?;
+
+    if config.database.backup_enabled || config.database.auto_restore {
+        let backup_dir = config.get_backup_dir();
+        let candidates = recovery_candidates(&backup_dir);
+        info!(
+            "Database recovery: {} backup(s) found in {}",
+            candidates.len(),
+            backup_dir.display()
+        );
+        for backup in candidates {
+            info!("Database recovery: restoring {}", backup.display());
+            if let Err(error) = database::redb::RedbDatabase::restore_backup_file(
+                backup.clone(),
+                db_path.to_path_buf(),
+            )
+            .await
+            {
+                warn!(
+                    "Database recovery: backup {} is unusable: {}",
+                    backup.display(),
+                    error
+                );
+                continue;
+            }
+
+            info!("Database recovery: checking the restored database");
+            let restored = async {
+                let database =
+                    database::redb::RedbDatabase::new_with_cache(db_path.to_path_buf(), cache_size_mb)
+                        .await?;
+                database.initialize().await?;
+                let health = database.check_and_repair().await?;
+                anyhow::Ok((database, health))
+            }
+            .await;
+            match restored {
+                Ok((database, mut health)) => {
+                    info!(
+                        "Database recovery: restored {}; the startup scan will pick up changes made since",
+                        backup.display()
+                    );
+                    health.restored_from_backup = true;
+                    return Ok((database, Some(health)));
+                }
+                Err(error) => {
+                    warn!(
+                        "Database recovery: restored copy of {} failed its check: {}",
+                        backup.display(),
+                        error
+                    );
+                    // Only the copy is removed; the backup stays as it was.
+                    std::fs::remove_file(db_path).with_context(|| {
+                        format!("Failed to remove failed restore {}", db_path.display())
+                    })?;
+                }
+            }
+        }
+        warn!("Database recovery: no usable backup; starting with an empty database");
+    }
+
+    let database = database::redb::RedbDatabase::new_with_cache(db_path.to_path_buf(), cache_size_mb)
+        .await
+        .context("Failed to create replacement ReDB database")?;
+    database
+        .initialize()
+        .await
+        .context("Failed to initialize replacement database schema")?;
+    Ok((database, None))
+}
+
+/// Initialize database manager with health checks and recovery. The health
+/// report is returned for `/health`.
+async fn initialize_database(
+    config: &AppConfig,
+) -> anyhow::Result<(database::redb::RedbDatabase, database::DatabaseHealth)> {
     info!("Initializing Redb database...");
 
     let db_path = config.get_database_path();
@@ -492,20 +744,45 @@ async fn initialize_database(config: &AppConfig) -> anyhow::Result<database::red
     let cache_size_mb = config.database.redb_cache_mb;
     info!("Database path: {}", db_path.display());
 
-    // Create Redb database manager
-    let mut database =
+    // With auto_restore, a missing database is restored from the newest
+    // backup rather than created empty.
+    if config.database.auto_restore
+        && !db_path.try_exists()?
+        && !recovery_candidates(&config.get_backup_dir()).is_empty()
+    {
+        warn!(
+            "Database {} is missing; restoring from the newest backup",
+            db_path.display()
+        );
+        let (database, recovered_health) = replace_failed_database(config, &db_path).await?;
+        let health = match recovered_health {
+            Some(health) => health,
+            None => database
+                .check_and_repair()
+                .await
+                .context("Replacement ReDB database failed initial index construction")?,
+        };
+        return finish_database_initialization(config, database, health).await;
+    }
+
+    // Create Redb database manager. A restored backup arrives already
+    // checked, with its health report.
+    let (mut database, mut recovered_health) =
         match database::redb::RedbDatabase::new_with_cache(db_path.clone(), cache_size_mb).await {
-            Ok(database) => database,
+            Ok(database) => (database, None),
             Err(error) => {
                 error!("Failed to open ReDB database: {}", error);
                 if database::redb::is_incompatible_database(&error) {
+                    // Backups share the incompatible format, so none is restored.
                     discard_incompatible_database(&db_path)?;
+                    let database =
+                        database::redb::RedbDatabase::new_with_cache(db_path.clone(), cache_size_mb)
+                            .await
+                            .context("Failed to create replacement ReDB database")?;
+                    (database, None)
                 } else {
-                    preserve_failed_database(&db_path)?;
+                    replace_failed_database(config, &db_path).await?
                 }
-                database::redb::RedbDatabase::new_with_cache(db_path.clone(), cache_size_mb)
-                    .await
-                    .context("Failed to create replacement ReDB database")?
             }
         };
 
@@ -513,23 +790,17 @@ async fn initialize_database(config: &AppConfig) -> anyhow::Result<database::red
     if let Err(error) = database.initialize().await {
         error!("Failed to initialize ReDB schema: {}", error);
         drop(database);
-        preserve_failed_database(&db_path)?;
-        database = database::redb::RedbDatabase::new_with_cache(db_path.clone(), cache_size_mb)
-            .await
-            .context("Failed to create replacement ReDB database")?;
-        database
-            .initialize()
-            .await
-            .context("Failed to initialize replacement database schema")?;
+        (database, recovered_health) = replace_failed_database(config, &db_path).await?;
+    }
+
+    if let Some(health) = recovered_health {
+        return finish_database_initialization(config, database, health).await;
     }
 
     // Perform health check
     info!("Performing database health check...");
     if config.database.backup_enabled {
-        let backup_dir = db_path
-            .parent()
-            .unwrap_or_else(|| Path::new("."))
-            .join("backups");
+        let backup_dir = config.get_backup_dir();
         let pre_repair = backup_dir.join(format!(
             "pre-repair-{}-{}.redb",
             chrono::Utc::now().format("%Y%m%dT%H%M%SZ"),
@@ -546,22 +817,45 @@ async fn initialize_database(config: &AppConfig) -> anyhow::Result<database::red
             })?;
     }
     let health = match database.check_and_repair().await {
-        Ok(health) => health,
-        Err(repair_error) => {
-            error!("Database index rebuild failed: {}", repair_error);
+        Ok(health)
+            if !(config.database.auto_restore && health.unrepaired_critical_issue().is_some()) =>
+        {
+            health
+        }
+        result => {
+            match result {
+                Ok(health) => error!(
+                    "Database repair left a critical issue ({}); restoring from backup",
+                    health
+                        .unrepaired_critical_issue()
+                        .map(|issue| issue.description.as_str())
+                        .unwrap_or_default()
+                ),
+                Err(repair_error) => error!("Database index rebuild failed: {}", repair_error),
+            }
             drop(database);
-            preserve_failed_database(&db_path)?;
-            database = database::redb::RedbDatabase::new_with_cache(db_path.clone(), cache_size_mb)
-                .await
-                .context("Failed to create replacement ReDB database")?;
-            database.initialize().await?;
-            database
-                .check_and_repair()
-                .await
-                .context("Replacement ReDB database failed initial index construction")?
+            let (replacement, recovered_health) =
+                replace_failed_database(config, &db_path).await?;
+            database = replacement;
+            match recovered_health {
+                Some(health) => health,
+                None => database
+                    .check_and_repair()
+                    .await
+                    .context("Replacement ReDB database failed initial index construction")?,
+            }
         }
     };
+    finish_database_initialization(config, database, health).await
+}
 
+/// Report the health check, log statistics and compact when configured.
+async fn finish_database_initialization(
+    config: &AppConfig,
+    database: database::redb::RedbDatabase,
+    health: database::DatabaseHealth,
+) -> anyhow::Result<(database::redb::RedbDatabase, database::DatabaseHealth)> {
+    let database = database.with_max_readers(config.database.max_connections);
     if !health.is_healthy || !health.issues.is_empty() {
         warn!("Database health issues detected:");
         for issue in &health.issues {
@@ -604,7 +898,7 @@ async fn initialize_database(config: &AppConfig) -> anyhow::Result<database::red
     }
 
     info!("Database initialized successfully");
-    Ok(database)
+    Ok((database, health))
 }
 
 /// Initialize file system watcher for real-time media monitoring
@@ -628,7 +922,7 @@ async fn initialize_file_watcher(
         let dir_path = std::path::PathBuf::from(&dir_config.path);
         if dir_path.exists() && dir_path.is_dir() {
             valid_directories.push(dir_path);
-        } else {
+        } else if !crate::remote::is_remote_root(&dir_config.path) {
             warn!(
                 "Monitored directory does not exist or is not a directory: {}",
                 dir_config.path
@@ -670,7 +964,9 @@ impl BootstrapService {
     pub async fn initialize_database(
         config: &AppConfig,
     ) -> anyhow::Result<database::redb::RedbDatabase> {
-        initialize_database(config).await
+        initialize_database(config)
+            .await
+            .map(|(database, _)| database)
     }
 
     pub async fn initialize_watcher(
@@ -0,0 +1,137 @@
+/// Outcome of `vuio check-config`: what would stop the server from starting,
+/// what may not behave as intended, and platform recommendations.
+#[derive(Debug, Default)]
+pub struct ConfigCheckReport {
+    /// Where the checked configuration came from.
+    pub source: String,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    pub recommendations: Vec<String>,
+}
+
+impl ConfigCheckReport {
+    fn new(source: String) -> Self {
+        Self {
+            source,
+            ..Self::default()
+        }
+    }
+
+    /// Run every read-only check on `config`. Unlike startup, nothing is
+    /// created: missing directories are reported instead.
+    fn check(&mut self, mut config: AppConfig) {
+        config.fill_platform_defaults();
+        if let Err(error) = crate::config::validation::ConfigValidator::validate_flexible(&config) {
+            self.errors.push(format!("{error:#}"));
+        }
+        if let Err(error) = config.validate_settings_for_platform() {
+            self.errors.push(format!("{error:#}"));
+        }
+
+        for directory in &config.media.directories {
+            if crate::remote::is_remote_root(&directory.path) {
+                continue;
+            }
+            let path = Path::new(&directory.path);
+            if !path.is_dir() && directory.validation_mode != ValidationMode::Skip {
+                self.warnings.push(format!(
+                    "Media directory does not exist or is not a directory: {}",
+                    directory.path
+                ));
+            }
+        }
+
+        if let Some(tls) = &config.server.tls {
+            if let Err(error) = web::tls::ReloadableCertificate::load(tls) {
+                self.errors.push(format!("{error:#}"));
+            }
+        }
+
+        if config.database.backend == DatabaseBackendKind::Redb {
+            let database_path = config.get_database_path();
+            match database_path.parent().map(std::fs::metadata) {
+                Some(Ok(metadata)) if metadata.permissions().readonly() => {
+                    self.errors.push(format!(
+                        "Database directory is read-only: {}",
+                        database_path.parent().unwrap_or(Path::new("")).display()
+                    ));
+                }
+                Some(Err(_)) => self.warnings.push(format!(
+                    "Database directory does not exist and will be created at startup: {}",
+                    database_path.parent().unwrap_or(Path::new("")).display()
+                )),
+                _ => {}
+            }
+        }
+
+        self.recommendations = config.check_platform_best_practices();
+    }
+
+    /// Check the file at `config_path`.
+    pub fn for_file(config_path: &Path) -> Self {
+        let mut report = Self::new(config_path.display().to_string());
+        if !config_path.exists() {
+            report.errors.push(format!(
+                "Configuration file does not exist: {}",
+                config_path.display()
+            ));
+            return report;
+        }
+        match AppConfig::read_from_file(config_path) {
+            Ok(config) => report.check(config),
+            Err(error) => report.errors.push(format!("{error:#}")),
+        }
+        report
+    }
+
+    /// Check the `VUIO_*` environment used in Docker mode.
+    pub fn for_environment() -> Self {
+        let mut report = Self::new("environment variables (Docker mode)".to_string());
+        match AppConfig::from_env() {
+            Ok(config) => report.check(config),
+            Err(error) => report.errors.push(format!("{error:#}")),
+        }
+        report
+    }
+}
+
+impl std::fmt::Display for ConfigCheckReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Configuration: {}", self.source)?;
+        for (heading, entries) in [
+            ("Errors", &self.errors),
+            ("Warnings", &self.warnings),
+            ("Recommendations", &self.recommendations),
+        ] {
+            writeln!(f, "{heading}: {}", entries.len())?;
+            for entry in entries {
+                writeln!(f, "  - {entry}")?;
+            }
+        }
+        if self.errors.is_empty() {
+            write!(f, "Result: OK")
+        } else {
+            write!(f, "Result: FAILED")
+        }
+    }
+}
+
+/// `vuio check-config`: validate the configuration the server would load
+/// and print a report. Fails when the configuration has errors.
+fn check_config(config_path: Option<&str>) -> anyhow::Result<()> {
+    let report = if AppConfig::is_running_in_docker() && config_path.is_none() {
+        ConfigCheckReport::for_environment()
+    } else {
+        let path = config_path
+            .map(PathBuf::from)
+            .unwrap_or_else(AppConfig::get_platform_config_file_path);
+        ConfigCheckReport::for_file(&path)
+    };
+    println!("{report}");
+    anyhow::ensure!(
+        report.errors.is_empty(),
+        "configuration check found {} error(s)",
+        report.errors.len()
+    );
+    Ok(())
+}
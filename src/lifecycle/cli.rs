@@ -4,10 +4,33 @@ pub struct LaunchOptions {
     pub config_path: Option<String>,
     pub log_file: Option<String>,
     pub log_level: Option<String>,
+    /// `--log-format`; `VUIO_LOG_FORMAT` applies when it is absent.
+    pub log_format: Option<logging::LogFormat>,
     pub config_override: Option<AppConfig>,
     pub restore_backup: Option<String>,
     pub update: bool,
     pub auth: bool,
+    /// `vuio rebuild-db`: clear the database, rescan, and exit.
+    pub rebuild_db: bool,
+    /// `vuio check-config`: validate the configuration, print a report, and exit.
+    pub check_config: bool,
+    /// `vuio scan`: refresh the database once, print a summary, and exit.
+    pub scan: Option<ScanOptions>,
+}
+
+/// Flags of `vuio scan`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanOptions {
+    pub format: ScanReportFormat,
+    /// `--dry-run`: report what the scan would change without writing it.
+    pub dry_run: bool,
+}
+
+/// Output format of the `vuio scan` summary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanReportFormat {
+    Text,
+    Json,
 }
 
 /// Parse command line arguments once and return configuration overrides
@@ -15,9 +38,29 @@ pub struct LaunchOptions {
 fn parse_args_once() -> anyhow::Result<LaunchOptions> {
     use clap::Parser;
 
+    #[derive(clap::Subcommand, Debug)]
+    enum Command {
+        /// Clear the database, scan every configured directory, and exit
+        RebuildDb,
+        /// Validate the configuration without starting the server or writing anything
+        CheckConfig,
+        /// Scan every configured directory into the database, print a summary, and exit
+        Scan {
+            /// Print the summary as JSON
+            #[arg(long)]
+            json: bool,
+            /// Report what would be added, updated and removed without writing anything
+            #[arg(long)]
+            dry_run: bool,
+        },
+    }
+
     #[derive(Parser, Debug)]
     #[command(author, version, about, long_about = None)]
     struct Args {
+        #[command(subcommand)]
+        command: Option<Command>,
+
         /// The directory containing media files to serve
         media_dir: Option<String>,
 
@@ -38,7 +81,7 @@ fn parse_args_once() -> anyhow::Result<LaunchOptions> {
         debug: bool,
 
         /// Path to configuration file
-        #[arg(short, long)]
+        #[arg(short, long, global = true)]
         config: Option<String>,
 
         /// Path to log file
@@ -49,6 +92,10 @@ fn parse_args_once() -> anyhow::Result<LaunchOptions> {
         #[arg(long = "log-level")]
         log_level: Option<String>,
 
+        /// Log line format (text, json); overrides VUIO_LOG_FORMAT
+        #[arg(long = "log-format")]
+        log_format: Option<logging::LogFormat>,
+
         /// Restore a validated Redb backup before opening the database
         #[arg(long = "restore-backup")]
         restore_backup: Option<String>,
@@ -63,6 +110,19 @@ fn parse_args_once() -> anyhow::Result<LaunchOptions> {
     }
 
     let args = Args::parse();
+    let rebuild_db = matches!(args.command, Some(Command::RebuildDb));
+    let check_config = matches!(args.command, Some(Command::CheckConfig));
+    let scan = match args.command {
+        Some(Command::Scan { json, dry_run }) => Some(ScanOptions {
+            format: if json {
+                ScanReportFormat::Json
+            } else {
+                ScanReportFormat::Text
+            },
+            dry_run,
+        }),
+        _ => None,
+    };
 
     // If no media directories provided, return early args only
     if args.media_dir.is_none() && args.additional_media_dirs.is_empty() {
@@ -71,10 +131,14 @@ fn parse_args_once() -> anyhow::Result<LaunchOptions> {
             config_path: args.config,
             log_file: args.log_file,
             log_level: args.log_level,
+            log_format: args.log_format,
             config_override: None,
             restore_backup: args.restore_backup,
             update: args.update,
             auth: args.auth,
+            rebuild_db,
+            check_config,
+            scan,
         });
     }
 
@@ -109,6 +173,12 @@ fn parse_args_once() -> anyhow::Result<LaunchOptions> {
             extensions: None,
             exclude_patterns: None,
             validation_mode: ValidationMode::Warn,
+            watch_mode: WatchMode::Auto,
+            merge_group: None,
+            priority: 0,
+            media_types: None,
+            min_file_size: None,
+            min_file_age_seconds: None,
         });
     }
 
@@ -128,6 +198,12 @@ fn parse_args_once() -> anyhow::Result<LaunchOptions> {
             extensions: None,
             exclude_patterns: None,
             validation_mode: ValidationMode::Warn,
+            watch_mode: WatchMode::Auto,
+            merge_group: None,
+            priority: 0,
+            media_types: None,
+            min_file_size: None,
+            min_file_age_seconds: None,
         });
     }
 
@@ -138,10 +214,14 @@ fn parse_args_once() -> anyhow::Result<LaunchOptions> {
         config_path: args.config,
         log_file: args.log_file,
         log_level: args.log_level,
+        log_format: args.log_format,
         config_override: Some(config_override),
         restore_backup: args.restore_backup,
         update: args.update,
         auth: args.auth,
+        rebuild_db,
+        check_config,
+        scan,
     })
 }
 
@@ -1,11 +1,20 @@
+/// Missing files removed per write transaction by the deleted-file cleanup.
+const CLEANUP_BATCH_SIZE: usize = 500;
+
 /// Validate cached files and remove any that no longer exist on disk
 ///
 /// Uses two-phase approach to avoid RwLock deadlock:
 /// 1. Stream all files and collect paths to delete (read lock)
-/// 2. Drop stream, then bulk delete (write lock)
+/// 2. Drop stream, then bulk delete in batches of [`CLEANUP_BATCH_SIZE`]
+///    (write lock per batch)
+///
+/// With `dry_run` the second phase only logs the candidates. Returns how
+/// many files were, or would have been, removed.
 async fn validate_and_cleanup_deleted_files<D: DatabaseManager>(
     database: Arc<D>,
     monitored_roots: &[PathBuf],
+    dry_run: bool,
+    protect_unmounted: bool,
 ) -> anyhow::Result<usize> {
     use futures_util::{stream, StreamExt};
 
@@ -22,14 +31,30 @@ async fn validate_and_cleanup_deleted_files<D: DatabaseManager>(
 
     let unavailable_configured_roots = stream::iter(monitored_roots.iter().cloned())
         .map(|root| async move {
-            let unavailable = match tokio::fs::metadata(&root).await {
-                Ok(metadata) => !metadata.is_dir(),
-                Err(_) => true,
-            };
-            (root, unavailable)
+            match tokio::fs::metadata(&root).await {
+                Ok(metadata) if metadata.is_dir() && protect_unmounted => {
+                    media::unmounted_mount_points(&root).await
+                }
+                Ok(metadata) if metadata.is_dir() => Vec::new(),
+                Ok(_) => {
+                    warn!(
+                        "Media root {} is not a directory; skipping the deleted-file cleanup of its files",
+                        root.display()
+                    );
+                    vec![root]
+                }
+                Err(error) => {
+                    warn!(
+                        "Media root {} is unavailable ({}); skipping the deleted-file cleanup of its files",
+                        root.display(),
+                        error
+                    );
+                    vec![root]
+                }
+            }
         })
         .buffer_unordered(32)
-        .filter_map(|(root, unavailable)| async move { unavailable.then_some(root) })
+        .flat_map(stream::iter)
         .collect::<Vec<_>>()
         .await;
     let fingerprints = database.load_file_fingerprints().await?;
@@ -43,7 +68,8 @@ async fn validate_and_cleanup_deleted_files<D: DatabaseManager>(
                     .iter()
                     .chain(unavailable_roots.iter())
                     .any(|root| media_file.path.starts_with(root));
-                if unavailable {
+                // Remote files are reconciled against their index when scanned.
+                if unavailable || crate::remote::is_remote_root(&media_file.path.to_string_lossy()) {
                     return None;
                 }
                 tokio::fs::symlink_metadata(&media_file.path)
@@ -58,12 +84,32 @@ async fn validate_and_cleanup_deleted_files<D: DatabaseManager>(
         .await;
 
     // Phase 2: Bulk delete (acquires write lock)
-    let removed_count = paths_to_delete.len();
+    if dry_run {
+        for path in &paths_to_delete {
+            info!("Cleanup dry run: would remove {}", path.display());
+        }
+        warn!(
+            "Cleanup dry run: {} of {} indexed files are missing on disk and would be removed",
+            paths_to_delete.len(),
+            total_checked
+        );
+        return Ok(paths_to_delete.len());
+    }
+    let mut removed_count = 0;
     if !paths_to_delete.is_empty() {
-        info!("Removing {} deleted files from database", removed_count);
-        database
-            .bulk_remove_canonical_media_files(&paths_to_delete)
-            .await?;
+        warn!(
+            "Removing {} of {} indexed files that are missing on disk",
+            paths_to_delete.len(),
+            total_checked
+        );
+        for batch in paths_to_delete.chunks(CLEANUP_BATCH_SIZE) {
+            removed_count += database.bulk_remove_canonical_media_files(batch).await?;
+            info!(
+                "Removed {}/{} deleted files from database",
+                removed_count,
+                paths_to_delete.len()
+            );
+        }
     }
 
     if removed_count > 0 {
@@ -97,7 +143,11 @@ async fn reconcile_unavailable_media_roots<D: DatabaseManager>(
     let now = unix_now_secs();
     let grace_secs = grace_hours.saturating_mul(3600);
     let mut removed = 0;
-    for root in roots {
+    // Remote roots record their availability when their index is scanned.
+    for root in roots
+        .iter()
+        .filter(|root| !crate::remote::is_remote_root(&root.to_string_lossy()))
+    {
         let indexed_count = fingerprints
             .iter()
             .filter(|file| file.path.starts_with(root))
@@ -125,22 +175,8 @@ async fn reconcile_unavailable_media_roots<D: DatabaseManager>(
         let Some(reason) = reason else {
             continue;
         };
-        let previous = database.get_root_availability(root).await?;
-        let unavailable_since = previous
-            .as_ref()
-            .and_then(|state| state.unavailable_since_secs)
-            .unwrap_or(now);
-        database
-            .set_root_availability(&database::RootAvailability {
-                path: root.clone(),
-                last_seen_secs: previous.as_ref().map_or(0, |state| state.last_seen_secs),
-                unavailable_since_secs: Some(unavailable_since),
-                indexed_count: previous
-                    .as_ref()
-                    .map_or(indexed_count, |state| state.indexed_count.max(indexed_count)),
-                reason: reason.clone(),
-            })
-            .await?;
+        let unavailable_since =
+            record_root_unavailable(database, root, indexed_count, &reason).await?;
 
         let permission_denied = reason.starts_with("permission denied");
         if !permission_denied && now.saturating_sub(unavailable_since) >= grace_secs {
@@ -151,6 +187,86 @@ async fn reconcile_unavailable_media_roots<D: DatabaseManager>(
     Ok(removed)
 }
 
+/// Mark `root` unavailable, keeping the time it first went missing. Returns
+/// that time in Unix seconds.
+async fn record_root_unavailable<D: DatabaseManager>(
+    database: &Arc<D>,
+    root: &Path,
+    indexed_count: u64,
+    reason: &str,
+) -> anyhow::Result<u64> {
+    let previous = database.get_root_availability(root).await?;
+    let unavailable_since = previous
+        .as_ref()
+        .and_then(|state| state.unavailable_since_secs)
+        .unwrap_or_else(unix_now_secs);
+    database
+        .set_root_availability(&database::RootAvailability {
+            path: root.to_path_buf(),
+            last_seen_secs: previous.as_ref().map_or(0, |state| state.last_seen_secs),
+            unavailable_since_secs: Some(unavailable_since),
+            indexed_count: previous
+                .as_ref()
+                .map_or(indexed_count, |state| state.indexed_count.max(indexed_count)),
+            reason: reason.to_owned(),
+        })
+        .await?;
+    Ok(unavailable_since)
+}
+
+/// Whether a deletion event really comes from its media root going away, as
+/// when a drive is unmounted or a share disconnects. The root is then
+/// recorded as unavailable instead of its files being removed: it is hidden
+/// from browsing, startup cleanup leaves its files alone, and they are only
+/// dropped after `media.unavailable_root_grace_hours`. Its watch is removed
+/// until the periodic reconciliation finds the root again and re-adds it.
+async fn pause_missing_root<D: DatabaseManager + 'static>(
+    event: &FileSystemEvent,
+    app_state: &AppState<D>,
+    watcher: &CrossPlatformWatcher,
+) -> anyhow::Result<bool> {
+    let FileSystemEvent::Deleted { path, .. } = event else {
+        return Ok(false);
+    };
+    let Some(root) = app_state
+        .media_directories
+        .read()
+        .await
+        .iter()
+        .map(|root| PathBuf::from(&root.path))
+        .filter(|root| path.starts_with(root))
+        .max_by_key(|root| root.components().count())
+    else {
+        return Ok(false);
+    };
+    let reason = match tokio::fs::read_dir(&root).await {
+        Ok(mut entries) => {
+            // An unmounted mount point is left behind as an empty directory.
+            if entries.next_entry().await?.is_some() {
+                return Ok(false);
+            }
+            "media root is unexpectedly empty".to_owned()
+        }
+        Err(error) => format!("{}: {error}", error.kind()),
+    };
+    if app_state.unavailable_roots.read().await.contains(&root) {
+        return Ok(true);
+    }
+
+    warn!(
+        "Media root {} became unavailable ({}); keeping its files until it returns",
+        root.display(),
+        reason
+    );
+    record_root_unavailable(&app_state.database, &root, 0, &reason).await?;
+    if let Err(error) = watcher.remove_watch_path(&root).await {
+        warn!("Failed to pause watching {}: {}", root.display(), error);
+    }
+    refresh_unavailable_roots(app_state).await?;
+    increment_content_update_id(app_state).await;
+    Ok(true)
+}
+
 async fn record_root_scan<D: DatabaseManager>(
     database: &Arc<D>,
     root: &Path,
@@ -218,9 +334,10 @@ async fn reconcile_media_roots<D: DatabaseManager + 'static>(
     }
 }
 
+/// Reload the set of hidden media roots. Returns whether it changed.
 pub(crate) async fn refresh_unavailable_roots<D: DatabaseManager>(
     app_state: &AppState<D>,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<bool> {
     let unavailable = app_state
         .database
         .list_root_availability()
@@ -229,15 +346,21 @@ pub(crate) async fn refresh_unavailable_roots<D: DatabaseManager>(
         .filter(|state| state.unavailable_since_secs.is_some())
         .map(|state| state.path)
         .collect();
-    *app_state.unavailable_roots.write().await = unavailable;
-    Ok(())
+    let mut current = app_state.unavailable_roots.write().await;
+    let changed = *current != unavailable;
+    *current = unavailable;
+    Ok(changed)
 }
 
-/// Perform initial media scan, using database cache when possible
+/// Perform initial media scan, using database cache when possible, and
+/// return what it changed. A database `restored_from_backup` is always
+/// scanned, whatever `scan_on_startup` says.
 async fn perform_initial_media_scan<D: DatabaseManager + 'static>(
     config: &AppConfig,
     database: &Arc<D>,
-) -> anyhow::Result<()> {
+    control: &Arc<ScanControl>,
+    restored_from_backup: bool,
+) -> anyhow::Result<ScanSummary> {
     info!("Performing initial media scan...");
 
     let configured_roots = config
@@ -259,90 +382,544 @@ async fn perform_initial_media_scan<D: DatabaseManager + 'static>(
         );
     }
 
+    let directories = config
+        .media
+        .directories
+        .iter()
+        .map(|directory| directory.path.clone())
+        .collect::<Vec<_>>();
+    let previous = ScanState::load(database.as_ref())
+        .await
+        .unwrap_or_else(|error| {
+            warn!("Ignoring persisted scan state: {}", error);
+            None
+        });
+    let resume = previous
+        .filter(|_| config.media.resume_interrupted_scans)
+        .and_then(|state| Some((state.resume_position(&directories)?, state)));
+
     let database_is_empty = database.get_stats().await?.total_files == 0;
-    if config.media.scan_on_startup || database_is_empty {
+    // A restored backup misses whatever changed since it was taken, which
+    // matching per-directory counts would not reveal.
+    if config.media.verify_on_start
+        && !database_is_empty
+        && resume.is_none()
+        && !restored_from_backup
+    {
+        return verify_media_roots_on_start(config, database).await;
+    }
+    if config.media.scan_on_startup
+        || database_is_empty
+        || resume.is_some()
+        || restored_from_backup
+    {
         if database_is_empty && !config.media.scan_on_startup {
             warn!("Database is empty; forcing a full media scan despite scan_on_startup=false");
+        } else if restored_from_backup && !config.media.scan_on_startup {
+            warn!(
+                "Database was restored from a backup; \
+                 scanning for changes despite scan_on_startup=false"
+            );
         }
-        info!("Full media scan enabled - scanning all directories");
+        let mut state = match resume {
+            Some((position, mut state)) => {
+                info!(
+                    "Resuming interrupted media scan after {}/{} directories",
+                    position,
+                    directories.len()
+                );
+                state.status = ScanStatus::Running;
+                state.finished_at = None;
+                state
+            }
+            None => {
+                info!("Full media scan enabled - scanning all directories");
+                ScanState::new(directories)
+            }
+        };
 
-        let scanner = media::MediaScanner::with_database(database.clone());
-        let mut total_changes = 0;
-        let mut total_files_scanned = 0;
+        let mut summary = ScanSummary::default();
+        control.begin();
+        let outcome =
+            scan_configured_directories(config, database, control, &mut state, &mut summary).await;
+        control.finish();
+        outcome?;
 
-        for dir_config in &config.media.directories {
-            let dir_path = std::path::PathBuf::from(&dir_config.path);
-            let policy = media::ScanPolicy::from_config(config, dir_config);
+        info!(
+            "Initial media scan {} - total files scanned: {}, total changes: {}",
+            if state.status == ScanStatus::Cancelled {
+                "cancelled"
+            } else {
+                "completed"
+            },
+            state.files_scanned,
+            state.changes
+        );
 
-            if !dir_path.exists() {
-                warn!("Media directory does not exist: {}", dir_config.path);
-                continue;
+        Ok(summary)
+    } else {
+        info!("Skipping full scan (scan on startup disabled)");
+
+        let mut summary = ScanSummary::default();
+        cleanup_deleted_files_if_enabled(config, database, &mut summary).await?;
+        Ok(summary)
+    }
+}
+
+/// Validate that cached files still exist on disk and remove any that don't,
+/// when `cleanup_deleted_files` (or its dry run) is enabled.
+async fn cleanup_deleted_files_if_enabled<D: DatabaseManager + 'static>(
+    config: &AppConfig,
+    database: &Arc<D>,
+    summary: &mut ScanSummary,
+) -> anyhow::Result<()> {
+    if !(config.media.cleanup_deleted_files || config.media.cleanup_dry_run) {
+        return Ok(());
+    }
+    let roots: Vec<_> = config
+        .media
+        .directories
+        .iter()
+        .map(|d| PathBuf::from(&d.path))
+        .collect();
+    let removed = validate_and_cleanup_deleted_files(
+        database.clone(),
+        &roots,
+        config.media.cleanup_dry_run,
+        config.media.protect_unmounted_roots,
+    )
+    .await?;
+    if !config.media.cleanup_dry_run {
+        summary.files_removed += removed as u64;
+    }
+    Ok(())
+}
+
+/// Scan the configured directories from `state`'s queue position onwards,
+/// persisting the position after each one. Stops between directories, or
+/// inside a recursive scan at a batch boundary, once `control` is cancelled.
+async fn scan_configured_directories<D: DatabaseManager + 'static>(
+    config: &AppConfig,
+    database: &Arc<D>,
+    control: &Arc<ScanControl>,
+    state: &mut ScanState,
+    summary: &mut ScanSummary,
+) -> anyhow::Result<()> {
+    let scanner = media::MediaScanner::with_database(database.clone())
+        .with_scan_control(control.clone());
+    state.save(database.as_ref()).await?;
+
+    for (index, dir_config) in config
+        .media
+        .directories
+        .iter()
+        .enumerate()
+        .skip(state.completed_directories)
+    {
+        if control.is_cancel_requested() {
+            break;
+        }
+        state.current_directory = Some(dir_config.path.clone());
+        state.save(database.as_ref()).await?;
+
+        if let Some(scan_result) =
+            scan_configured_directory(config, database, &scanner, dir_config).await?
+        {
+            summary.record(&scan_result);
+            state.files_scanned += scan_result.total_scanned as u64;
+            state.changes += scan_result.total_changes() as u64;
+            if scan_result.cancelled {
+                break;
             }
+        } else {
+            summary.directories_missing += 1;
+        }
+        state.completed_directories = index + 1;
+        state.save(database.as_ref()).await?;
+    }
+
+    if control.is_cancel_requested() && state.completed_directories < state.directories.len() {
+        info!(
+            "Media scan cancelled after {}/{} directories",
+            state.completed_directories,
+            state.directories.len()
+        );
+        state.finish(ScanStatus::Cancelled);
+    } else {
+        state.finish(ScanStatus::Completed);
+    }
+    state.save(database.as_ref()).await
+}
 
-            info!("Scanning directory: {}", dir_config.path);
+/// Compare indexed and on-disk file counts for every configured root and
+/// rescan only the roots whose counts diverge beyond the configured threshold.
+async fn verify_media_roots_on_start<D: DatabaseManager + 'static>(
+    config: &AppConfig,
+    database: &Arc<D>,
+) -> anyhow::Result<ScanSummary> {
+    let threshold = config.media.verify_on_start_threshold;
+    info!(
+        "Verifying media directories against the database (threshold: {} files)",
+        threshold
+    );
 
-            let scan_result = if dir_config.recursive {
-                scanner
-                    .scan_directory_recursive_with_policy(&policy)
-                    .await
-                    .with_context(|| {
-                        format!("Failed to recursively scan directory: {}", dir_config.path)
-                    })?
-            } else {
-                scanner
-                    .scan_directory_with_policy(&policy)
-                    .await
-                    .with_context(|| format!("Failed to scan directory: {}", dir_config.path))?
-            };
+    let scanner = media::MediaScanner::with_database(database.clone());
+    let mut summary = ScanSummary::default();
+    let mut rescanned = 0;
+    let mut skipped = 0;
 
-            info!(
-                "Scan of {} completed: {}",
-                dir_path.display(),
-                scan_result.summary()
-            );
-            if !scan_result.errors.is_empty() {
-                // FIX: Iterate over a reference to avoid moving scan_result.errors
-                for err in &scan_result.errors {
-                    warn!("Scan error in {}: {}", err.path.display(), err.error);
-                }
+    for dir_config in &config.media.directories {
+        // A remote index has no cheap count; it is read again instead.
+        if crate::remote::is_remote_root(&dir_config.path) {
+            if let Some(scan_result) =
+                scan_configured_directory(config, database, &scanner, dir_config).await?
+            {
+                summary.record(&scan_result);
+                rescanned += 1;
             }
-            record_root_scan(database, &dir_path, &scan_result).await?;
-            total_changes += scan_result.total_changes();
-            total_files_scanned += scan_result.total_scanned;
+            continue;
+        }
+        if !Path::new(&dir_config.path).exists() {
+            warn!("Media directory does not exist: {}", dir_config.path);
+            continue;
+        }
+        let policy = media::ScanPolicy::from_config(config, dir_config);
+        let counts = scanner
+            .count_root_files_with_policy(&policy)
+            .await
+            .with_context(|| format!("Failed to count files in directory: {}", dir_config.path))?;
+
+        if !counts.diverges(threshold) {
+            debug!(
+                "Directory {} unchanged ({} indexed, {} on disk); skipping rescan",
+                dir_config.path, counts.indexed, counts.on_disk
+            );
+            skipped += 1;
+            continue;
         }
 
         info!(
-            "Initial media scan completed - total files scanned: {}, total changes: {}",
-            total_files_scanned, total_changes
+            "Directory {} changed while offline ({} indexed, {} on disk); rescanning",
+            dir_config.path, counts.indexed, counts.on_disk
         );
+        if let Some(scan_result) =
+            scan_configured_directory(config, database, &scanner, dir_config).await?
+        {
+            summary.record(&scan_result);
+            rescanned += 1;
+        }
+    }
 
-        Ok(())
+    info!(
+        "Startup verification completed - {} directories rescanned, {} unchanged",
+        rescanned, skipped
+    );
+    Ok(summary)
+}
+
+/// Scan one configured media directory and record the root scan outcome.
+/// Returns `None` when the directory does not exist.
+async fn scan_configured_directory<D: DatabaseManager + 'static>(
+    config: &AppConfig,
+    database: &Arc<D>,
+    scanner: &media::MediaScanner<D>,
+    dir_config: &MonitoredDirectoryConfig,
+) -> anyhow::Result<Option<media::ScanResult>> {
+    if crate::remote::is_remote_root(&dir_config.path) {
+        return scan_remote_directory(config, database, scanner, dir_config)
+            .await
+            .map(Some);
+    }
+    let dir_path = std::path::PathBuf::from(&dir_config.path);
+    let policy = media::ScanPolicy::from_config(config, dir_config);
+
+    if !dir_path.exists() {
+        warn!("Media directory does not exist: {}", dir_config.path);
+        return Ok(None);
+    }
+
+    info!("Scanning directory: {}", dir_config.path);
+
+    let scan_result = if dir_config.recursive {
+        scanner
+            .scan_directory_recursive_with_policy(&policy)
+            .await
+            .with_context(|| format!("Failed to recursively scan directory: {}", dir_config.path))?
     } else {
-        info!("Skipping full scan (scan on startup disabled)");
+        scanner
+            .scan_directory_with_policy(&policy)
+            .await
+            .with_context(|| format!("Failed to scan directory: {}", dir_config.path))?
+    };
+
+    if scan_result.cancelled {
+        // Partial results say nothing about the root's availability.
+        return Ok(Some(scan_result));
+    }
+    info!(
+        "Scan of {} completed: {}",
+        dir_path.display(),
+        scan_result.summary()
+    );
+    for err in &scan_result.errors {
+        warn!("Scan error in {}: {}", err.path.display(), err.error);
+    }
+    record_root_scan(database, &dir_path, &scan_result).await?;
+    Ok(Some(scan_result))
+}
+
+/// Read a remote root's index into the database and record the outcome.
+async fn scan_remote_directory<D: DatabaseManager + 'static>(
+    config: &AppConfig,
+    database: &Arc<D>,
+    scanner: &media::MediaScanner<D>,
+    dir_config: &MonitoredDirectoryConfig,
+) -> anyhow::Result<media::ScanResult> {
+    let root = crate::remote::RemoteRoot::from_config(config, dir_config)?;
+    info!("Reading remote index: {}", root.index);
+    let scan_result = scanner.scan_remote_root(&root).await?;
+    info!("Scan of {} completed: {}", root.index, scan_result.summary());
+    for err in &scan_result.errors {
+        warn!("Scan error in {}: {}", err.path.display(), err.error);
+    }
+    record_root_scan(database, &root.location(), &scan_result).await?;
+    Ok(scan_result)
+}
+
+/// What a media scan changed, as printed by `vuio rebuild-db` and `vuio scan`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize)]
+struct ScanSummary {
+    directories_scanned: usize,
+    directories_missing: usize,
+    files_scanned: u64,
+    files_added: u64,
+    files_updated: u64,
+    files_removed: u64,
+    playlists_imported: usize,
+    errors: u64,
+}
+
+impl ScanSummary {
+    fn record(&mut self, scan_result: &media::ScanResult) {
+        self.directories_scanned += 1;
+        self.files_scanned += scan_result.total_scanned as u64;
+        self.files_added += scan_result.new_files.len() as u64;
+        self.files_updated += scan_result.updated_files.len() as u64;
+        self.files_removed += scan_result.removed_files.len() as u64;
+        self.errors += scan_result.errors.len() as u64;
+    }
+}
+
+/// Scan every configured directory into a cleared database, as a startup
+/// scan with `scan_on_startup` does, then import their playlist files.
+async fn rebuild_media_index<D: DatabaseManager + 'static>(
+    config: &AppConfig,
+    database: &Arc<D>,
+) -> anyhow::Result<ScanSummary> {
+    info!("Rebuilding the media index from scratch...");
+    let scanner = media::MediaScanner::with_database(database.clone());
+    let mut summary = ScanSummary::default();
 
-        // Validate that cached files still exist on disk and remove any that don't (if enabled)
-        if config.media.cleanup_deleted_files {
-            let roots: Vec<_> = config
-                .media
-                .directories
-                .iter()
-                .map(|d| PathBuf::from(&d.path))
-                .collect();
-            validate_and_cleanup_deleted_files(database.clone(), &roots).await?;
+    for dir_config in &config.media.directories {
+        match scan_configured_directory(config, database, &scanner, dir_config).await? {
+            Some(scan_result) => summary.record(&scan_result),
+            None => summary.directories_missing += 1,
         }
+    }
 
-        Ok(())
+    summary.playlists_imported = perform_initial_playlist_scan(config, database).await?;
+    Ok(summary)
+}
+
+/// `vuio scan`: scan every configured directory as a startup scan with
+/// `scan_on_startup` does, apply `cleanup_deleted_files`, then import
+/// playlist files.
+async fn scan_media_index<D: DatabaseManager + 'static>(
+    config: &AppConfig,
+    database: &Arc<D>,
+) -> anyhow::Result<ScanSummary> {
+    let mut config = config.clone();
+    config.media.scan_on_startup = true;
+    config.media.verify_on_start = false;
+    let mut summary =
+        perform_initial_media_scan(&config, database, &Arc::new(ScanControl::new()), false).await?;
+    cleanup_deleted_files_if_enabled(&config, database, &mut summary).await?;
+    summary.playlists_imported = perform_initial_playlist_scan(&config, database).await?;
+    Ok(summary)
+}
+
+/// How a dry run found one configured directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum PreviewStatus {
+    Scanned,
+    Missing,
+    /// Remote indexes are fetched, not walked, so they are not previewed.
+    Remote,
+}
+
+/// What a scan would do to one configured directory.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+struct DirectoryPreview {
+    path: String,
+    status: PreviewStatus,
+    files_scanned: usize,
+    would_add: usize,
+    would_update: usize,
+    would_remove: usize,
+    unchanged: usize,
+    excluded: usize,
+    unsupported: usize,
+    too_small: usize,
+    too_recent: usize,
+    errors: usize,
+}
+
+/// Files with one extension that `supported_extensions` leaves out.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+struct UnsupportedExtension {
+    files: usize,
+    example: PathBuf,
+}
+
+/// What `vuio scan --dry-run` and `media.dry_run` report: the changes a
+/// scan would make, per directory, without writing any of them.
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize)]
+struct ScanPreview {
+    directories: Vec<DirectoryPreview>,
+    /// Keyed by lowercase extension; `(none)` for files without one.
+    unsupported_extensions: std::collections::BTreeMap<String, UnsupportedExtension>,
+    errors: usize,
+}
+
+impl ScanPreview {
+    fn record(&mut self, path: &str, status: PreviewStatus, scan_result: &media::ScanResult) {
+        let mut directory = DirectoryPreview {
+            path: path.to_owned(),
+            status,
+            files_scanned: scan_result.total_scanned,
+            would_add: scan_result.new_files.len(),
+            would_update: scan_result.updated_files.len(),
+            would_remove: scan_result.removed_files.len(),
+            unchanged: scan_result.unchanged_files.len(),
+            excluded: 0,
+            unsupported: 0,
+            too_small: 0,
+            too_recent: 0,
+            errors: scan_result.errors.len(),
+        };
+        for skipped in &scan_result.skipped {
+            match skipped.reason {
+                media::SkipReason::Excluded => directory.excluded += 1,
+                media::SkipReason::TooSmall => directory.too_small += 1,
+                media::SkipReason::TooRecent => directory.too_recent += 1,
+                media::SkipReason::UnsupportedExtension => {
+                    directory.unsupported += 1;
+                    let extension = skipped
+                        .path
+                        .extension()
+                        .map(|extension| extension.to_string_lossy().to_lowercase())
+                        .unwrap_or_else(|| "(none)".to_owned());
+                    self.unsupported_extensions
+                        .entry(extension)
+                        .or_insert_with(|| UnsupportedExtension {
+                            files: 0,
+                            example: skipped.path.clone(),
+                        })
+                        .files += 1;
+                }
+            }
+        }
+        self.errors += directory.errors;
+        self.directories.push(directory);
     }
 }
 
-/// Perform initial playlist file scan
+impl std::fmt::Display for ScanPreview {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Dry run: nothing was written to the database")?;
+        for directory in &self.directories {
+            match directory.status {
+                PreviewStatus::Missing => writeln!(f, "{}: missing", directory.path)?,
+                PreviewStatus::Remote => {
+                    writeln!(f, "{}: remote index, not previewed", directory.path)?
+                }
+                PreviewStatus::Scanned => {
+                    writeln!(f, "{}:", directory.path)?;
+                    writeln!(f, "  Files scanned: {}", directory.files_scanned)?;
+                    writeln!(f, "  Would add: {}", directory.would_add)?;
+                    writeln!(f, "  Would update: {}", directory.would_update)?;
+                    writeln!(f, "  Would remove: {}", directory.would_remove)?;
+                    writeln!(f, "  Unchanged: {}", directory.unchanged)?;
+                    writeln!(f, "  Excluded: {}", directory.excluded)?;
+                    writeln!(f, "  Unsupported: {}", directory.unsupported)?;
+                    writeln!(f, "  Too small: {}", directory.too_small)?;
+                    writeln!(f, "  Too recent: {}", directory.too_recent)?;
+                    writeln!(f, "  Errors: {}", directory.errors)?;
+                }
+            }
+        }
+        writeln!(
+            f,
+            "Unsupported extensions: {}",
+            self.unsupported_extensions.len()
+        )?;
+        for (extension, unsupported) in &self.unsupported_extensions {
+            writeln!(
+                f,
+                "  - {extension}: {} file(s), e.g. {}",
+                unsupported.files,
+                unsupported.example.display()
+            )?;
+        }
+        write!(f, "Errors: {}", self.errors)
+    }
+}
+
+/// Walk every configured directory as a scan would and report what it
+/// would add, update and remove. Nothing is written, root status included.
+async fn preview_media_scan<D: DatabaseManager + 'static>(
+    config: &AppConfig,
+    database: &Arc<D>,
+) -> anyhow::Result<ScanPreview> {
+    let mut config = config.clone();
+    config.media.dry_run = true;
+    let scanner = media::MediaScanner::with_database(database.clone());
+    let mut preview = ScanPreview::default();
+
+    for dir_config in &config.media.directories {
+        let empty = media::ScanResult::new();
+        if crate::remote::is_remote_root(&dir_config.path) {
+            preview.record(&dir_config.path, PreviewStatus::Remote, &empty);
+            continue;
+        }
+        if !Path::new(&dir_config.path).exists() {
+            preview.record(&dir_config.path, PreviewStatus::Missing, &empty);
+            continue;
+        }
+        let policy = media::ScanPolicy::from_config(&config, dir_config);
+        let scan_result = if dir_config.recursive {
+            scanner.scan_directory_recursive_with_policy(&policy).await
+        } else {
+            scanner.scan_directory_with_policy(&policy).await
+        }
+        .with_context(|| format!("Failed to preview a scan of {}", dir_config.path))?;
+        preview.record(&dir_config.path, PreviewStatus::Scanned, &scan_result);
+    }
+    Ok(preview)
+}
+
+/// Import the playlist files of every configured directory when
+/// `media.scan_playlists` is on. Files unchanged since their last import are
+/// skipped, so restarts neither duplicate nor rewrite playlists. Returns how
+/// many playlists were imported or updated.
 async fn perform_initial_playlist_scan<D: DatabaseManager + 'static>(
     config: &AppConfig,
     database: &Arc<D>,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<usize> {
     if !config.media.scan_playlists {
         info!("Playlist scanning disabled in configuration");
-        return Ok(());
+        return Ok(0);
     }
 
     info!("Scanning for playlist files...");
@@ -352,6 +929,9 @@ async fn perform_initial_playlist_scan<D: DatabaseManager + 'static>(
     for dir_config in &config.media.directories {
         let dir_path = std::path::PathBuf::from(&dir_config.path);
 
+        if crate::remote::is_remote_root(&dir_config.path) {
+            continue;
+        }
         if !dir_path.exists() {
             warn!(
                 "Media directory does not exist, skipping playlist scan: {}",
@@ -387,23 +967,249 @@ async fn perform_initial_playlist_scan<D: DatabaseManager + 'static>(
 
     if total_playlists > 0 {
         info!(
-            "Playlist scan completed: {} playlist(s) imported",
+            "Playlist scan completed: {} playlist(s) imported or updated",
             total_playlists
         );
     } else {
-        info!("Playlist scan completed: no playlist files found");
+        info!("Playlist scan completed: no new or changed playlist files");
     }
 
-    Ok(())
+    Ok(total_playlists)
+}
+
+/// Quiet period after a content change before the playlist mirror is
+/// rewritten, so a batch of edits costs one write per file.
+const PLAYLIST_MIRROR_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Keep `media.playlist_mirror_dir` in step with the database playlists: once
+/// at startup, then after every burst of content changes.
+async fn run_playlist_mirror<D: DatabaseManager + 'static>(
+    app_state: AppState<D>,
+    cancellation: CancellationToken,
+) {
+    loop {
+        mirror_playlists(&app_state).await;
+        tokio::select! {
+            _ = cancellation.cancelled() => return,
+            _ = app_state.playlist_mirror_notify.notified() => {}
+        }
+        loop {
+            tokio::select! {
+                _ = cancellation.cancelled() => return,
+                _ = app_state.playlist_mirror_notify.notified() => continue,
+                _ = tokio::time::sleep(PLAYLIST_MIRROR_DELAY) => break,
+            }
+        }
+    }
+}
+
+async fn mirror_playlists<D: DatabaseManager + 'static>(app_state: &AppState<D>) {
+    let config = app_state.current_config();
+    let Some(directory) = config.media.playlist_mirror_dir.as_deref() else {
+        return;
+    };
+    let share_root = app_state
+        .media_directories
+        .read()
+        .await
+        .iter()
+        .map(|monitored| PathBuf::from(&monitored.path))
+        .filter(|root| directory.starts_with(root))
+        .max_by_key(|root| root.components().count());
+    if let Err(error) = database::playlist_formats::PlaylistFileManager::mirror_playlists(
+        app_state.database.as_ref(),
+        directory,
+        share_root.as_deref(),
+    )
+    .await
+    {
+        warn!(
+            "Failed to mirror playlists to {}: {}",
+            directory.display(),
+            error
+        );
+    }
+}
+
+/// Created/Modified file events held back until the file stops growing, so a
+/// copy in progress is not indexed (and served) with a truncated size, and
+/// until it is older than its directory's `min_file_age_seconds`.
+#[derive(Default)]
+struct SettlingFiles {
+    pending: HashMap<PathBuf, SettlingFile>,
+}
+
+struct SettlingFile {
+    event: FileSystemEvent,
+    size: Option<u64>,
+    modified: Option<SystemTime>,
+    observed_at: std::time::Instant,
+    min_age: std::time::Duration,
+}
+
+fn settle_fingerprint(path: &Path) -> (Option<u64>, Option<SystemTime>) {
+    match std::fs::metadata(path) {
+        Ok(metadata) => (Some(metadata.len()), metadata.modified().ok()),
+        Err(_) => (None, None),
+    }
+}
+
+/// A writer still holding the file open blocks a share-nothing open on Windows.
+#[cfg(target_os = "windows")]
+fn exclusive_open_succeeds(path: &Path) -> bool {
+    use std::os::windows::fs::OpenOptionsExt;
+    std::fs::OpenOptions::new()
+        .read(true)
+        .share_mode(0)
+        .open(path)
+        .is_ok()
+}
+
+impl SettlingFiles {
+    /// Return the event when it can be handled now, or hold it until the file
+    /// has kept the same size and mtime for `settle_time` and was last
+    /// modified at least `min_age` ago.
+    fn defer(
+        &mut self,
+        event: FileSystemEvent,
+        settle_time: std::time::Duration,
+        min_age: std::time::Duration,
+    ) -> Option<FileSystemEvent> {
+        match &event {
+            FileSystemEvent::Created(path) | FileSystemEvent::Modified(path)
+                if (!settle_time.is_zero() || !min_age.is_zero()) && path.is_file() =>
+            {
+                let (size, modified) = settle_fingerprint(path);
+                let path = path.clone();
+                let event = match self.pending.remove(&path) {
+                    // Keep the original creation when more writes follow it.
+                    Some(previous) if matches!(previous.event, FileSystemEvent::Created(_)) => {
+                        previous.event
+                    }
+                    _ => event,
+                };
+                self.pending.insert(
+                    path,
+                    SettlingFile {
+                        event,
+                        size,
+                        modified,
+                        observed_at: std::time::Instant::now(),
+                        min_age,
+                    },
+                );
+                None
+            }
+            FileSystemEvent::Deleted { path, .. } => {
+                self.forget(path);
+                Some(event)
+            }
+            FileSystemEvent::Renamed { from, .. } => {
+                self.forget(from);
+                Some(event)
+            }
+            _ => Some(event),
+        }
+    }
+
+    fn forget(&mut self, path: &Path) {
+        self.pending.retain(|pending, _| !pending.starts_with(path));
+    }
+
+    /// Release the events whose files have stopped changing.
+    fn take_settled(&mut self, settle_time: std::time::Duration) -> Vec<FileSystemEvent> {
+        let now = std::time::Instant::now();
+        let mut settled = Vec::new();
+        let wall_clock = SystemTime::now();
+        self.pending.retain(|path, pending| {
+            let too_recent = |modified: Option<SystemTime>| {
+                modified.is_some_and(|modified| {
+                    media::modified_within(modified, pending.min_age, wall_clock)
+                })
+            };
+            #[cfg(target_os = "windows")]
+            if !too_recent(pending.modified) && exclusive_open_succeeds(path) {
+                settled.push(pending.event.clone());
+                return false;
+            }
+            if now.duration_since(pending.observed_at) < settle_time {
+                return true;
+            }
+            let (size, modified) = settle_fingerprint(path);
+            if size.is_none() {
+                // Gone before it settled; the deletion event covers the database.
+                return false;
+            }
+            if size == pending.size && modified == pending.modified {
+                if too_recent(modified) {
+                    return true;
+                }
+                settled.push(pending.event.clone());
+                return false;
+            }
+            debug!("File is still being written: {}", path.display());
+            pending.size = size;
+            pending.modified = modified;
+            pending.observed_at = now;
+            true
+        });
+        settled
+    }
+}
+
+/// `min_file_age_seconds` of the directory a created or modified file is in.
+fn min_file_age(config: &AppConfig, event: &FileSystemEvent) -> std::time::Duration {
+    let (FileSystemEvent::Created(path) | FileSystemEvent::Modified(path)) = event else {
+        return std::time::Duration::ZERO;
+    };
+    config
+        .media
+        .directories
+        .iter()
+        .filter(|directory| path.starts_with(&directory.path))
+        .max_by_key(|directory| Path::new(&directory.path).components().count())
+        .and_then(|directory| directory.min_file_age_seconds)
+        .map_or(std::time::Duration::ZERO, std::time::Duration::from_secs)
+}
+
+/// Handle one watcher event, falling back to a full reconciliation when the
+/// incremental update fails. Deletions caused by a whole media root going
+/// away pause that root instead.
+async fn handle_event_or_reconcile<D: DatabaseManager + 'static>(
+    event: FileSystemEvent,
+    app_state: &AppState<D>,
+    watcher: &CrossPlatformWatcher,
+) {
+    match pause_missing_root(&event, app_state, watcher).await {
+        Ok(true) => return,
+        Ok(false) => {}
+        Err(error) => error!("Failed to check media root availability: {}", error),
+    }
+    if let Err(e) = handle_file_system_event(event, app_state).await {
+        error!("Failed to handle file system event; reconciling all roots: {}", e);
+        let configured_roots = app_state.media_directories.read().await.clone();
+        reconcile_media_roots(app_state, &configured_roots).await;
+    }
 }
 
 /// Start file system monitoring with database integration
+///
+/// Watches are registered (and polled roots baselined) before this returns,
+/// so it must run before the initial scan. Events are held back until
+/// `initial_scan` turns true (or its sender is dropped) and then replayed in
+/// order, so a scan batch can never overwrite a newer change or resurrect a
+/// file deleted while the scan was running.
 async fn start_file_monitoring<D: DatabaseManager + 'static>(
     watcher: Arc<CrossPlatformWatcher>,
     app_state: AppState<D>,
     cancellation: CancellationToken,
+    mut initial_scan: tokio::sync::watch::Receiver<bool>,
 ) -> anyhow::Result<Option<tokio::task::JoinHandle<()>>> {
-    let watching_enabled = app_state.current_config().media.watch_for_changes;
+    // A dry run must not index what the watcher sees either.
+    let watching_enabled = {
+        let media = &app_state.current_config().media;
+        media.watch_for_changes && !media.dry_run
+    };
     info!("Starting file system monitoring controller...");
 
     // Get directories to monitor
@@ -435,6 +1241,7 @@ async fn start_file_monitoring<D: DatabaseManager + 'static>(
             .start_watching(&directories)
             .await
             .context("Failed to start watching directories")?;
+        watcher.wait_for_baselines().await;
         info!("File system watcher successfully started for all directories");
     } else {
         info!("File system watching is disabled; controller remains ready for reload");
@@ -453,6 +1260,10 @@ async fn start_file_monitoring<D: DatabaseManager + 'static>(
     let handle = tokio::spawn(async move {
         info!("File system event handler started");
 
+        let mut settling = SettlingFiles::default();
+        let mut scan_finished = *initial_scan.borrow_and_update();
+        let mut held_back = Vec::new();
+        let mut settle_check = tokio::time::interval(std::time::Duration::from_secs(1));
         let mut dirty_reconciliation =
             tokio::time::interval(std::time::Duration::from_secs(30));
         let mut full_reconciliation =
@@ -465,16 +1276,45 @@ async fn start_file_monitoring<D: DatabaseManager + 'static>(
                     info!("File system event handler received cancellation");
                     break;
                 }
+                finished = initial_scan.changed(), if !scan_finished => {
+                    if finished.is_ok() && !*initial_scan.borrow_and_update() {
+                        continue;
+                    }
+                    scan_finished = true;
+                    if !held_back.is_empty() {
+                        info!("Replaying {} file system event(s) received during the initial scan", held_back.len());
+                    }
+                    let config = app_state_clone.current_config();
+                    let settle_time =
+                        std::time::Duration::from_secs(config.media.settle_time_seconds);
+                    for event in std::mem::take(&mut held_back) {
+                        let min_age = min_file_age(&config, &event);
+                        if let Some(event) = settling.defer(event, settle_time, min_age) {
+                            handle_event_or_reconcile(event, &app_state_clone, &watcher_clone).await;
+                        }
+                    }
+                }
                 event = event_receiver.recv() => {
                     let Some(event) = event else { break; };
-                    if let Err(e) = handle_file_system_event(event, &app_state_clone).await {
-                        error!("Failed to handle file system event; reconciling all roots: {}", e);
-                        let configured_roots = app_state_clone
-                            .media_directories
-                            .read()
-                            .await
-                            .clone();
-                        reconcile_media_roots(&app_state_clone, &configured_roots).await;
+                    if !scan_finished {
+                        held_back.push(event);
+                        continue;
+                    }
+                    let config = app_state_clone.current_config();
+                    let settle_time =
+                        std::time::Duration::from_secs(config.media.settle_time_seconds);
+                    let min_age = min_file_age(&config, &event);
+                    let Some(event) = settling.defer(event, settle_time, min_age) else {
+                        continue;
+                    };
+                    handle_event_or_reconcile(event, &app_state_clone, &watcher_clone).await;
+                }
+                _ = settle_check.tick(), if !settling.pending.is_empty() => {
+                    let settle_time = std::time::Duration::from_secs(
+                        app_state_clone.current_config().media.settle_time_seconds,
+                    );
+                    for event in settling.take_settled(settle_time) {
+                        handle_event_or_reconcile(event, &app_state_clone, &watcher_clone).await;
                     }
                 }
                 _ = dirty_reconciliation.tick() => {
@@ -539,8 +1379,13 @@ async fn start_file_monitoring<D: DatabaseManager + 'static>(
                     // This mandatory sweep is independent of the dirty-root queue, so a noisy
                     // root cannot starve reconciliation of the rest of the library.
                     reconcile_media_roots(&app_state_clone, &configured_roots).await;
-                    if let Err(error) = refresh_unavailable_roots(&app_state_clone).await {
-                        error!("Failed to refresh unavailable-root visibility: {}", error);
+                    match refresh_unavailable_roots(&app_state_clone).await {
+                        // A root that came back is listed again.
+                        Ok(true) => increment_content_update_id(&app_state_clone).await,
+                        Ok(false) => {}
+                        Err(error) => {
+                            error!("Failed to refresh unavailable-root visibility: {}", error)
+                        }
                     }
                 }
             }
@@ -689,7 +1534,14 @@ async fn index_media_file_path<D: DatabaseManager + ?Sized>(
     else {
         return Ok(None);
     };
-    let mut media_file = media::build_media_file_from_path(&path, filesystem_manager).await?;
+    let mut media_file =
+        media::build_media_file_from_path(&path, filesystem_manager, policy.hash_content).await?;
+    if let Some(reason) =
+        policy.metadata_skip_reason(media_file.size, media_file.modified, SystemTime::now())
+    {
+        debug!("Not indexing {} ({:?})", path.display(), reason);
+        return Ok(None);
+    }
     if let Some(existing) = database.get_file_by_path(&media_file.path).await? {
         media_file.id = existing.id;
         media_file.created_at = existing.created_at;
@@ -704,6 +1556,33 @@ async fn index_media_file_path<D: DatabaseManager + ?Sized>(
         .ok_or_else(|| anyhow::anyhow!("media upsert returned no ID for {}", path.display()))
 }
 
+/// Drop the media files at or below a path that left the library. While
+/// `media.deletion_grace_minutes` is on they are only marked missing; a full
+/// reconciliation removes them once they have stayed gone that long. Returns
+/// how many files were removed or marked.
+async fn retire_media_under_path<D: DatabaseManager + 'static>(
+    app_state: &AppState<D>,
+    path: &Path,
+) -> anyhow::Result<usize> {
+    let database = app_state.database.as_ref();
+    if app_state.current_config().media.deletion_grace().is_none() {
+        return Ok(database.remove_media_under_path(path).await?.removed_files);
+    }
+    let now = SystemTime::now();
+    let mut files = database
+        .get_files_with_path_prefix(&crate::platform::filesystem::path_to_lossless_string(path))
+        .await?;
+    files.retain(|file| file.missing_since.is_none());
+    for file in &mut files {
+        file.missing_since = Some(now);
+    }
+    if !files.is_empty() {
+        database.bulk_update_media_files(&files).await?;
+        media::note_missing_files_marked(database).await?;
+    }
+    Ok(files.len())
+}
+
 async fn reconcile_rejected_watched_path<D: DatabaseManager + 'static>(
     database: &Arc<D>,
     policy: &media::ScanPolicy,
@@ -715,10 +1594,12 @@ async fn reconcile_rejected_watched_path<D: DatabaseManager + 'static>(
         .await
 }
 
+/// Re-import a created or modified playlist file. Returns whether the
+/// library changed; rewriting a playlist with the same content does not.
 async fn import_changed_playlist<D: DatabaseManager + ?Sized>(
     database: &D,
     path: &Path,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<bool> {
     let is_radio = path.parent().is_some_and(|parent| {
         parent.components().any(|component| {
             component
@@ -730,8 +1611,11 @@ async fn import_changed_playlist<D: DatabaseManager + ?Sized>(
     if is_radio {
         database::playlist_formats::PlaylistFileManager::import_radio_playlist(database, path)
             .await
+            .map(|_| true)
     } else {
-        database.import_playlist_file(path, None).await.map(|_| ())
+        database::playlist_formats::PlaylistFileManager::sync_playlist_file(database, path)
+            .await
+            .map(|import| import.is_some())
     }
 }
 
@@ -763,12 +1647,15 @@ async fn handle_file_system_event<D: DatabaseManager + 'static>(
                 return Ok(());
             };
             if is_srt_path(&path) {
-                update_subtitle_index(&secure_path, true, app_state).await?;
+                if policy.allows_subtitle(&path) {
+                    update_subtitle_index(&secure_path, true, app_state).await?;
+                }
                 return Ok(());
             }
             // Check if this is a directory or a file
             if path.is_dir() {
-                if !policy.recursive || path == policy.root {
+                if path == policy.root || !policy.allows_directory(&path) {
+                    debug!("Directory is outside the watched tree or excluded: {}", path.display());
                     return Ok(());
                 }
                 info!("Directory created: {}", path.display());
@@ -795,8 +1682,28 @@ async fn handle_file_system_event<D: DatabaseManager + 'static>(
                         info!("Added {} media files from new directory using ReDB bulk operations: {}",
                               scan_result.new_files.len(), path.display());
 
+                        // Import playlists once the files they list are indexed.
+                        let mut playlists_imported = 0;
+                        if policy.scan_playlists {
+                            match database.scan_and_import_playlists_recursive(&path).await {
+                                Ok(playlist_ids) => playlists_imported = playlist_ids.len(),
+                                Err(e) => warn!(
+                                    "Failed to import playlists from new directory {}: {}",
+                                    path.display(),
+                                    e
+                                ),
+                            }
+                            if playlists_imported > 0 {
+                                info!(
+                                    "Imported {} playlist(s) from new directory: {}",
+                                    playlists_imported,
+                                    path.display()
+                                );
+                            }
+                        }
+
                         // Increment update ID to notify DLNA clients
-                        if !scan_result.new_files.is_empty() {
+                        if !scan_result.new_files.is_empty() || playlists_imported > 0 {
                             increment_content_update_id(app_state).await;
                         }
                     }
@@ -809,8 +1716,9 @@ async fn handle_file_system_event<D: DatabaseManager + 'static>(
                 info!("Media file created: {}", path.display());
 
                 if policy.allows_playlist(&path) {
-                    import_changed_playlist(database.as_ref(), &secure_path).await?;
-                    increment_content_update_id(app_state).await;
+                    if import_changed_playlist(database.as_ref(), &secure_path).await? {
+                        increment_content_update_id(app_state).await;
+                    }
                     return Ok(());
                 }
                 if !policy.allows_media(&path) {
@@ -856,14 +1764,17 @@ async fn handle_file_system_event<D: DatabaseManager + 'static>(
                 return Ok(());
             };
             if is_srt_path(&path) {
-                update_subtitle_index(&secure_path, true, app_state).await?;
+                if policy.allows_subtitle(&path) {
+                    update_subtitle_index(&secure_path, true, app_state).await?;
+                }
                 return Ok(());
             }
             info!("Media file modified: {}", path.display());
 
             if policy.allows_playlist(&path) {
-                import_changed_playlist(database.as_ref(), &secure_path).await?;
-                increment_content_update_id(app_state).await;
+                if import_changed_playlist(database.as_ref(), &secure_path).await? {
+                    increment_content_update_id(app_state).await;
+                }
                 return Ok(());
             }
             if !policy.allows_media(&path) {
@@ -878,10 +1789,29 @@ async fn handle_file_system_event<D: DatabaseManager + 'static>(
                 let mut refreshed = media::build_media_file_from_path(
                     &secure_path,
                     app_state.filesystem_manager.as_ref(),
+                    policy.hash_content,
                 )
                 .await?;
                 refreshed.id = existing_file.id;
                 refreshed.created_at = existing_file.created_at;
+                match policy.metadata_skip_reason(
+                    refreshed.size,
+                    refreshed.modified,
+                    SystemTime::now(),
+                ) {
+                    // Still being written; a later event or the next
+                    // reconciliation updates it.
+                    Some(media::SkipReason::TooRecent) => return Ok(()),
+                    Some(_) => {
+                        database
+                            .bulk_remove_media_files(std::slice::from_ref(&secure_path))
+                            .await?;
+                        info!("Removed media file below min_file_size: {}", path.display());
+                        increment_content_update_id(app_state).await;
+                        return Ok(());
+                    }
+                    None => {}
+                }
 
                 // Use ReDB bulk update operation (single-item batch for atomic consistency)
                 database.bulk_update_media_files(&[refreshed]).await?;
@@ -926,23 +1856,31 @@ async fn handle_file_system_event<D: DatabaseManager + 'static>(
             }
             info!("Path deleted: {}", path.display());
             let derived_removed = database.remove_derived_content_by_source(&path).await?;
-            let summary = database
-                .remove_media_under_path(&path)
+            if is_directory != Some(true)
+                && database::playlist_formats::PlaylistFormat::from_extension(&path).is_some()
+            {
+                database::playlist_formats::PlaylistFileManager::forget_playlist_file(
+                    database.as_ref(),
+                    &path,
+                )
+                .await?;
+            }
+            let removed = retire_media_under_path(app_state, &path)
                 .await
                 .inspect_err(|_error| {
                     stats.record_error();
                 })?;
-            stats.record_files_processed(summary.removed_files as u64);
+            stats.record_files_processed(removed as u64);
             info!(
-                "Removed {} indexed files and {} derived items below deleted path {}",
-                summary.removed_files,
+                "Retired {} indexed files and removed {} derived items below deleted path {}",
+                removed,
                 derived_removed,
                 path.display()
             );
             // Publish empty/duplicate directory events because they can retire an
             // older browse generation. Known unrelated file deletions do not churn
             // the library revision unless they removed indexed/derived content.
-            if is_directory != Some(false) || summary.removed_files > 0 || derived_removed > 0 {
+            if is_directory != Some(false) || removed > 0 || derived_removed > 0 {
                 increment_content_update_id(app_state).await;
             }
         }
@@ -954,6 +1892,7 @@ async fn handle_file_system_event<D: DatabaseManager + 'static>(
                 }
                 if is_srt_path(&to) {
                     let secure_to = media::ScanPolicy::for_path(&policies, &to)
+                        .filter(|policy| policy.allows_subtitle(&to))
                         .map(|policy| async {
                             policy
                                 .secure_canonical_path(
@@ -984,17 +1923,19 @@ async fn handle_file_system_event<D: DatabaseManager + 'static>(
             if looks_like_directory {
                 info!("Directory renamed: {} -> {}", from.display(), to.display());
                 let from_policy = media::ScanPolicy::for_path(&policies, &from)
-                    .filter(|policy| from == policy.root || policy.recursive)
+                    .filter(|policy| policy.allows_directory(&from))
                     .cloned();
                 let to_policy = media::ScanPolicy::for_path(&policies, &to)
-                    .filter(|policy| to == policy.root || policy.recursive)
+                    .filter(|policy| policy.allows_directory(&to))
                     .cloned();
                 match (from_policy, to_policy) {
                     (None, None) => {}
                     (Some(_), None) => {
-                        let removed = database.remove_media_under_path(&from).await?;
+                        // Moving a folder out of the library, to the trash
+                        // for instance, counts as deleting it.
+                        let removed = retire_media_under_path(app_state, &from).await?;
                         let derived = database.remove_derived_content_by_source(&from).await?;
-                        if removed.removed_files > 0 || derived > 0 {
+                        if removed > 0 || derived > 0 {
                             increment_content_update_id(app_state).await;
                         }
                     }
@@ -1054,6 +1995,11 @@ async fn handle_file_system_event<D: DatabaseManager + 'static>(
                 if from_playlist || to_playlist {
                     if from_playlist {
                         database.remove_derived_content_by_source(&from).await?;
+                        database::playlist_formats::PlaylistFileManager::forget_playlist_file(
+                            database.as_ref(),
+                            &from,
+                        )
+                        .await?;
                     }
                     if to_playlist && to.is_file() {
                         let policy = media::ScanPolicy::for_path(&policies, &to)
@@ -1111,9 +2057,7 @@ async fn handle_file_system_event<D: DatabaseManager + 'static>(
                         increment_content_update_id(app_state).await;
                     }
                     MediaRenameKind::Remove => {
-                        let removed = database
-                            .bulk_remove_media_files(std::slice::from_ref(&from))
-                            .await?;
+                        let removed = retire_media_under_path(app_state, &from).await?;
                         if removed > 0 {
                             stats.record_files_processed(removed as u64);
                             info!(
@@ -1164,8 +2108,9 @@ impl MediaLifecycleService {
         config: &AppConfig,
         database: &Arc<D>,
     ) -> anyhow::Result<()> {
-        perform_initial_media_scan(config, database).await?;
-        perform_initial_playlist_scan(config, database).await
+        perform_initial_media_scan(config, database, &Arc::new(ScanControl::new()), false).await?;
+        perform_initial_playlist_scan(config, database).await?;
+        Ok(())
     }
 
     pub async fn handle_event<D: DatabaseManager + 'static>(event: FileSystemEvent, state: &AppState<D>) -> anyhow::Result<()> {
@@ -1177,6 +2122,8 @@ impl MediaLifecycleService {
         state: AppState<D>,
         cancellation: CancellationToken,
     ) -> anyhow::Result<Option<tokio::task::JoinHandle<()>>> {
-        start_file_monitoring(watcher, state, cancellation).await
+        // No initial scan is coordinated here, so events apply immediately.
+        let (_, scan_finished) = tokio::sync::watch::channel(true);
+        start_file_monitoring(watcher, state, cancellation, scan_finished).await
     }
 }
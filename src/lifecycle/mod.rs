@@ -1,6 +1,7 @@
 use crate::{
     config::{
-        AppConfig, ConfigChangeEvent, ConfigManager, MonitoredDirectoryConfig, ValidationMode,
+        AppConfig, ConfigChangeEvent, ConfigManager, DatabaseBackendKind, MonitoredDirectoryConfig,
+        ValidationMode, WatchMode,
     },
     database::{self, DatabaseManager, HealthRepository, StatsRepository},
     logging, media,
@@ -9,6 +10,7 @@ use crate::{
         filesystem::{create_platform_filesystem_manager, create_platform_path_normalizer},
         PlatformInfo,
     },
+    scan_progress::{ScanControl, ScanState, ScanStatus},
     ssdp,
     state::AppState,
     watcher::{CrossPlatformWatcher, FileSystemEvent, FileSystemWatcher, MediaRenameKind},
@@ -27,6 +29,7 @@ use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
 include!("cli.rs");
+include!("check.rs");
 include!("bootstrap.rs");
 include!("media.rs");
 include!("network.rs");
@@ -38,7 +41,7 @@ include!("update.rs");
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::database::MediaRepository;
+    use crate::database::{MediaRepository, PlaylistRepository};
     use futures_util::StreamExt;
     use tempfile::tempdir;
 
@@ -57,6 +60,121 @@ mod tests {
             .expect("cancellation waiter panicked");
     }
 
+    #[tokio::test]
+    async fn lifecycle_backups_keep_the_newest_and_spare_pre_repair_copies() {
+        let temp = tempdir().unwrap();
+        let database_path = temp.path().join("media.redb");
+        let database = Arc::new(
+            database::redb::RedbDatabase::new(database_path.clone())
+                .await
+                .unwrap(),
+        );
+        database.initialize().await.unwrap();
+        let mut config = AppConfig::default_for_platform();
+        config.database.path = Some(database_path.to_string_lossy().into_owned());
+        config.database.backup_dir = Some(temp.path().join("kept").to_string_lossy().into_owned());
+        config.database.backup_keep = 2;
+        let backup_dir = config.get_backup_dir();
+        std::fs::create_dir_all(&backup_dir).unwrap();
+        let pre_repair = backup_dir.join("pre-repair-20200101T000000Z-0.redb");
+        std::fs::write(&pre_repair, b"").unwrap();
+
+        let mut created = Vec::new();
+        for _ in 0..3 {
+            created.push(create_lifecycle_backup(&database, &config).await.unwrap());
+            // Names are ordered by their second-resolution timestamp.
+            tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+        }
+
+        let mut remaining = std::fs::read_dir(&backup_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect::<Vec<_>>();
+        remaining.sort();
+        assert_eq!(
+            remaining,
+            [pre_repair, created[1].clone(), created[2].clone()]
+        );
+        assert!(created.iter().all(|path| path.starts_with(&backup_dir)));
+    }
+
+    #[tokio::test]
+    async fn corrupt_databases_are_replaced_by_the_newest_usable_backup() {
+        let temp = tempdir().unwrap();
+        let database_path = temp.path().join("media.redb");
+        let song = temp.path().join("song.mp3");
+        tokio::fs::write(&song, b"media").await.unwrap();
+        let mut config = AppConfig::default_for_platform();
+        config.database.path = Some(database_path.to_string_lossy().into_owned());
+        config.database.backup_enabled = true;
+        {
+            let database = database::redb::RedbDatabase::new(database_path.clone())
+                .await
+                .unwrap();
+            database.initialize().await.unwrap();
+            let policy = media::ScanPolicy::platform_default(temp.path(), false);
+            let filesystem_manager = create_platform_filesystem_manager();
+            index_media_file_path(&database, &song, &policy, filesystem_manager.as_ref())
+                .await
+                .unwrap()
+                .unwrap();
+            create_lifecycle_backup(&Arc::new(database), &config)
+                .await
+                .unwrap();
+        }
+        // A newer backup that is not a database must be skipped, not trusted.
+        let backup_dir = config.get_backup_dir();
+        std::fs::write(backup_dir.join("vuio-99991231T235959Z.redb"), b"garbage").unwrap();
+        std::fs::write(&database_path, b"not a redb file").unwrap();
+
+        let (database, health) = initialize_database(&config).await.unwrap();
+
+        assert!(health.restored_from_backup);
+        assert!(database.get_file_by_path(&song).await.unwrap().is_some());
+        let quarantined = std::fs::read_dir(temp.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .filter(|name| name.starts_with("media.failed-"))
+            .count();
+        assert_eq!(quarantined, 1);
+        assert_eq!(
+            std::fs::read(backup_dir.join("vuio-99991231T235959Z.redb")).unwrap(),
+            b"garbage"
+        );
+    }
+
+    #[tokio::test]
+    async fn auto_restore_brings_back_a_missing_database_from_backup() {
+        let temp = tempdir().unwrap();
+        let database_path = temp.path().join("media.redb");
+        let song = temp.path().join("song.mp3");
+        tokio::fs::write(&song, b"media").await.unwrap();
+        let mut config = AppConfig::default_for_platform();
+        config.database.path = Some(database_path.to_string_lossy().into_owned());
+        config.database.auto_restore = true;
+        {
+            let database = database::redb::RedbDatabase::new(database_path.clone())
+                .await
+                .unwrap();
+            database.initialize().await.unwrap();
+            let policy = media::ScanPolicy::platform_default(temp.path(), false);
+            let filesystem_manager = create_platform_filesystem_manager();
+            index_media_file_path(&database, &song, &policy, filesystem_manager.as_ref())
+                .await
+                .unwrap()
+                .unwrap();
+            create_lifecycle_backup(&Arc::new(database), &config)
+                .await
+                .unwrap();
+        }
+        std::fs::remove_file(&database_path).unwrap();
+
+        let (database, health) = initialize_database(&config).await.unwrap();
+
+        assert!(health.restored_from_backup);
+        assert!(database.get_file_by_path(&song).await.unwrap().is_some());
+    }
+
     #[tokio::test]
     async fn downloaded_media_paths_are_indexed_and_persisted() {
         let temp = tempdir().unwrap();
@@ -100,6 +218,487 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn verify_on_start_rescans_only_directories_with_count_mismatch() {
+        let temp = tempdir().unwrap();
+        let changed = temp.path().join("changed");
+        let unchanged = temp.path().join("unchanged");
+        tokio::fs::create_dir(&changed).await.unwrap();
+        tokio::fs::create_dir(&unchanged).await.unwrap();
+        tokio::fs::write(changed.join("a.mp3"), b"a").await.unwrap();
+        tokio::fs::write(unchanged.join("b.mp3"), b"b")
+            .await
+            .unwrap();
+
+        let mut config = AppConfig::default_for_platform();
+        config.media.directories = [&changed, &unchanged]
+            .into_iter()
+            .map(|path| MonitoredDirectoryConfig {
+                path: path.to_string_lossy().into_owned(),
+                recursive: true,
+                case_sensitive: Some(true),
+                extensions: None,
+                exclude_patterns: None,
+                validation_mode: ValidationMode::Warn,
+                watch_mode: WatchMode::Auto,
+                merge_group: None,
+                priority: 0,
+                media_types: None,
+                min_file_size: None,
+                min_file_age_seconds: None,
+            })
+            .collect();
+        let database = Arc::new(database::memory::InMemoryDatabase::new());
+        database.initialize().await.unwrap();
+        perform_initial_media_scan(&config, &database, &Arc::new(ScanControl::new()), false)
+            .await
+            .unwrap();
+
+        // Added while offline: the count changes. Rewritten in place: it does not.
+        tokio::fs::write(changed.join("new.mp3"), b"new")
+            .await
+            .unwrap();
+        tokio::fs::write(unchanged.join("b.mp3"), b"rewritten")
+            .await
+            .unwrap();
+
+        config.media.verify_on_start = true;
+        perform_initial_media_scan(&config, &database, &Arc::new(ScanControl::new()), false)
+            .await
+            .unwrap();
+
+        assert!(database
+            .get_file_by_path(&changed.canonicalize().unwrap().join("new.mp3"))
+            .await
+            .unwrap()
+            .is_some());
+        let skipped = database
+            .get_file_by_path(&unchanged.canonicalize().unwrap().join("b.mp3"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(skipped.size, 1);
+    }
+
+    #[tokio::test]
+    async fn a_cleanup_dry_run_reports_missing_files_without_removing_them() {
+        let temp = tempdir().unwrap();
+        tokio::fs::write(temp.path().join("kept.mp3"), b"a")
+            .await
+            .unwrap();
+        tokio::fs::write(temp.path().join("gone.mp3"), b"b")
+            .await
+            .unwrap();
+        let mut config = AppConfig::default_for_platform();
+        config.media.directories = vec![monitored(temp.path())];
+        let database = Arc::new(database::memory::InMemoryDatabase::new());
+        database.initialize().await.unwrap();
+        perform_initial_media_scan(&config, &database, &Arc::new(ScanControl::new()), false)
+            .await
+            .unwrap();
+        let gone = temp.path().canonicalize().unwrap().join("gone.mp3");
+        tokio::fs::remove_file(&gone).await.unwrap();
+        let roots = [temp.path().to_path_buf()];
+
+        let candidates = validate_and_cleanup_deleted_files(database.clone(), &roots, true, true)
+            .await
+            .unwrap();
+        assert_eq!(candidates, 1);
+        assert!(database.get_file_by_path(&gone).await.unwrap().is_some());
+
+        let removed = validate_and_cleanup_deleted_files(database.clone(), &roots, false, true)
+            .await
+            .unwrap();
+        assert_eq!(removed, 1);
+        assert!(database.get_file_by_path(&gone).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn rebuilding_indexes_every_configured_directory_and_counts_missing_ones() {
+        let temp = tempdir().unwrap();
+        tokio::fs::write(temp.path().join("a.mp3"), b"a")
+            .await
+            .unwrap();
+        tokio::fs::write(temp.path().join("b.mp3"), b"b")
+            .await
+            .unwrap();
+        let mut config = AppConfig::default_for_platform();
+        config.media.scan_on_startup = false;
+        config.media.directories = vec![
+            monitored(temp.path()),
+            monitored(&temp.path().join("unplugged")),
+        ];
+        let database = Arc::new(database::memory::InMemoryDatabase::new());
+        database.initialize().await.unwrap();
+
+        let summary = rebuild_media_index(&config, &database).await.unwrap();
+
+        assert_eq!(
+            summary,
+            ScanSummary {
+                directories_scanned: 1,
+                directories_missing: 1,
+                files_scanned: 2,
+                files_added: 2,
+                files_updated: 0,
+                files_removed: 0,
+                playlists_imported: 0,
+                errors: 0,
+            }
+        );
+        assert_eq!(database.get_stats().await.unwrap().total_files, 2);
+    }
+
+    #[tokio::test]
+    async fn a_one_shot_scan_reports_what_changed_since_the_last_one() {
+        let temp = tempdir().unwrap();
+        let root = std::fs::canonicalize(temp.path()).unwrap();
+        for name in ["a.mp3", "b.mp3"] {
+            tokio::fs::write(root.join(name), b"audio").await.unwrap();
+        }
+        let mut config = AppConfig::default_for_platform();
+        config.media.scan_on_startup = false;
+        config.media.directories = vec![monitored(&root)];
+        let database = Arc::new(database::memory::InMemoryDatabase::new());
+        database.initialize().await.unwrap();
+
+        let first = scan_media_index(&config, &database).await.unwrap();
+        assert_eq!((first.files_added, first.files_removed), (2, 0));
+
+        tokio::fs::remove_file(root.join("b.mp3")).await.unwrap();
+        tokio::fs::write(root.join("c.mp3"), b"audio")
+            .await
+            .unwrap();
+        let second = scan_media_index(&config, &database).await.unwrap();
+        assert_eq!(
+            (
+                second.directories_scanned,
+                second.files_added,
+                second.files_removed,
+                second.errors
+            ),
+            (1, 1, 1, 0)
+        );
+        assert_eq!(database.get_stats().await.unwrap().total_files, 2);
+    }
+
+    #[tokio::test]
+    async fn a_dry_run_reports_changes_and_skipped_files_without_writing_them() {
+        let temp = tempdir().unwrap();
+        let root = std::fs::canonicalize(temp.path()).unwrap();
+        for name in ["a.mp3", "b.mp3"] {
+            tokio::fs::write(root.join(name), b"audio").await.unwrap();
+        }
+        let mut config = AppConfig::default_for_platform();
+        config.media.scan_on_startup = false;
+        let mut directory = monitored(&root);
+        directory.exclude_patterns = Some(vec!["sample*".to_owned()]);
+        config.media.directories = vec![directory, monitored(&root.join("unplugged"))];
+        let database = Arc::new(database::memory::InMemoryDatabase::new());
+        database.initialize().await.unwrap();
+        scan_media_index(&config, &database).await.unwrap();
+
+        tokio::fs::remove_file(root.join("b.mp3")).await.unwrap();
+        for name in ["c.mp3", "sample.mp3", "notes.XYZ", "cover.xyz"] {
+            tokio::fs::write(root.join(name), b"data").await.unwrap();
+        }
+        let preview = preview_media_scan(&config, &database).await.unwrap();
+
+        let scanned = &preview.directories[0];
+        assert_eq!(
+            (
+                scanned.would_add,
+                scanned.would_update,
+                scanned.would_remove,
+                scanned.unchanged,
+                scanned.excluded,
+                scanned.unsupported
+            ),
+            (1, 0, 1, 1, 1, 2)
+        );
+        assert_eq!(preview.directories[1].status, PreviewStatus::Missing);
+        assert_eq!(preview.unsupported_extensions["xyz"].files, 2);
+        assert!(database
+            .get_file_by_path(&root.join("b.mp3"))
+            .await
+            .unwrap()
+            .is_some());
+        assert!(database
+            .get_file_by_path(&root.join("c.mp3"))
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn only_one_process_at_a_time_holds_the_database() {
+        let temp = tempdir().unwrap();
+        let mut config = AppConfig::default_for_platform();
+        config.database.path = Some(
+            temp.path()
+                .join("db")
+                .join("media.redb")
+                .to_string_lossy()
+                .into_owned(),
+        );
+
+        let held = lock_database(&config).unwrap();
+        assert!(held.is_some());
+        let error = lock_database(&config).err().unwrap();
+        assert!(error.to_string().contains("in use by another VuIO process"));
+        drop(held);
+        assert!(lock_database(&config).unwrap().is_some());
+
+        config.database.backend = DatabaseBackendKind::Memory;
+        assert!(lock_database(&config).unwrap().is_none());
+    }
+
+    #[test]
+    fn only_critical_issues_that_repair_could_not_fix_stop_startup() {
+        let issue = |severity| database::DatabaseIssue {
+            severity,
+            description: "files table unreadable".to_owned(),
+            table_affected: None,
+            suggested_action: String::new(),
+        };
+        let mut health = database::DatabaseHealth {
+            is_healthy: false,
+            corruption_detected: true,
+            integrity_check_passed: false,
+            issues: vec![
+                issue(database::IssueSeverity::Warning),
+                issue(database::IssueSeverity::Critical),
+            ],
+            repair_attempted: true,
+            repair_successful: false,
+            restored_from_backup: false,
+        };
+        assert!(health.unrepaired_critical_issue().is_some());
+
+        health.repair_successful = true;
+        assert!(health.unrepaired_critical_issue().is_none());
+
+        health.repair_successful = false;
+        health.issues.pop();
+        assert!(health.unrepaired_critical_issue().is_none());
+
+        let message = DatabaseCorruptError {
+            reason: "files table unreadable".to_owned(),
+        }
+        .to_string();
+        assert!(message.contains("vuio rebuild-db"));
+        assert!(message.contains("--restore-backup"));
+    }
+
+    fn monitored(path: &Path) -> MonitoredDirectoryConfig {
+        MonitoredDirectoryConfig {
+            path: path.to_string_lossy().into_owned(),
+            recursive: true,
+            case_sensitive: Some(true),
+            extensions: None,
+            exclude_patterns: None,
+            validation_mode: ValidationMode::Warn,
+            watch_mode: WatchMode::Auto,
+            merge_group: None,
+            priority: 0,
+            media_types: None,
+            min_file_size: None,
+            min_file_age_seconds: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn interrupted_scans_resume_after_the_last_completed_directory() {
+        let temp = tempdir().unwrap();
+        let first = temp.path().join("first");
+        let second = temp.path().join("second");
+        tokio::fs::create_dir(&first).await.unwrap();
+        tokio::fs::create_dir(&second).await.unwrap();
+        tokio::fs::write(first.join("a.mp3"), b"a").await.unwrap();
+        tokio::fs::write(second.join("b.mp3"), b"b").await.unwrap();
+
+        let mut config = AppConfig::default_for_platform();
+        config.media.directories = vec![monitored(&first), monitored(&second)];
+        config.media.resume_interrupted_scans = true;
+        let database = Arc::new(database::memory::InMemoryDatabase::new());
+        database.initialize().await.unwrap();
+
+        // A previous run stopped after finishing the first directory.
+        let mut interrupted = ScanState::new(
+            config
+                .media
+                .directories
+                .iter()
+                .map(|directory| directory.path.clone())
+                .collect(),
+        );
+        interrupted.completed_directories = 1;
+        interrupted.files_scanned = 1;
+        interrupted.save(database.as_ref()).await.unwrap();
+
+        perform_initial_media_scan(&config, &database, &Arc::new(ScanControl::new()), false)
+            .await
+            .unwrap();
+
+        assert!(database
+            .get_file_by_path(&first.canonicalize().unwrap().join("a.mp3"))
+            .await
+            .unwrap()
+            .is_none());
+        assert!(database
+            .get_file_by_path(&second.canonicalize().unwrap().join("b.mp3"))
+            .await
+            .unwrap()
+            .is_some());
+        let state = ScanState::load(database.as_ref()).await.unwrap().unwrap();
+        assert_eq!(state.status, ScanStatus::Completed);
+        assert_eq!(state.started_at, interrupted.started_at);
+        assert_eq!(state.completed_directories, 2);
+        assert_eq!(state.files_scanned, 2);
+        assert!(state.finished_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn cancelled_scans_keep_their_position_and_remove_nothing() {
+        let temp = tempdir().unwrap();
+        let root = temp.path().join("media");
+        tokio::fs::create_dir(&root).await.unwrap();
+        tokio::fs::write(root.join("kept.mp3"), b"a").await.unwrap();
+
+        let mut config = AppConfig::default_for_platform();
+        config.media.directories = vec![monitored(&root)];
+        let database = Arc::new(database::memory::InMemoryDatabase::new());
+        database.initialize().await.unwrap();
+        perform_initial_media_scan(&config, &database, &Arc::new(ScanControl::new()), false)
+            .await
+            .unwrap();
+
+        // Deleted and added files are both left alone by a cancelled scan.
+        tokio::fs::remove_file(root.join("kept.mp3")).await.unwrap();
+        tokio::fs::write(root.join("new.mp3"), b"b").await.unwrap();
+        let control = Arc::new(ScanControl::new());
+        control.begin();
+        assert!(control.request_cancel());
+        let scanner =
+            media::MediaScanner::with_database(database.clone()).with_scan_control(control.clone());
+        let result = scanner
+            .scan_directory_recursive_with_policy(&media::ScanPolicy::from_config(
+                &config,
+                &config.media.directories[0],
+            ))
+            .await
+            .unwrap();
+        assert!(result.cancelled);
+        assert!(!result.complete);
+        let canonical = root.canonicalize().unwrap();
+        assert!(database
+            .get_file_by_path(&canonical.join("kept.mp3"))
+            .await
+            .unwrap()
+            .is_some());
+        assert!(database
+            .get_file_by_path(&canonical.join("new.mp3"))
+            .await
+            .unwrap()
+            .is_none());
+
+        let mut state = ScanState::new(vec![config.media.directories[0].path.clone()]);
+        scan_configured_directories(
+            &config,
+            &database,
+            &control,
+            &mut state,
+            &mut ScanSummary::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(state.status, ScanStatus::Cancelled);
+        assert_eq!(state.completed_directories, 0);
+        assert_eq!(
+            ScanState::load(database.as_ref()).await.unwrap(),
+            Some(state)
+        );
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn growing_files_are_held_until_their_size_settles() {
+        let temp = tempdir().unwrap();
+        let copy = temp.path().join("movie.mkv");
+        std::fs::write(&copy, b"partial").unwrap();
+        let settle_time = std::time::Duration::from_millis(50);
+        let mut settling = SettlingFiles::default();
+
+        assert!(settling
+            .defer(
+                FileSystemEvent::Created(copy.clone()),
+                settle_time,
+                std::time::Duration::ZERO
+            )
+            .is_none());
+        assert!(settling
+            .defer(
+                FileSystemEvent::Modified(copy.clone()),
+                settle_time,
+                std::time::Duration::ZERO
+            )
+            .is_none());
+        assert!(settling.take_settled(settle_time).is_empty());
+
+        std::thread::sleep(settle_time);
+        std::fs::write(&copy, b"partial and still copying").unwrap();
+        assert!(settling.take_settled(settle_time).is_empty());
+
+        std::thread::sleep(settle_time);
+        let settled = settling.take_settled(settle_time);
+        assert!(matches!(settled.as_slice(), [FileSystemEvent::Created(path)] if path == &copy));
+        assert!(settling.pending.is_empty());
+
+        // A disabled settle time passes events straight through.
+        assert!(settling
+            .defer(
+                FileSystemEvent::Modified(copy.clone()),
+                std::time::Duration::ZERO,
+                std::time::Duration::ZERO
+            )
+            .is_some());
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn recent_files_are_held_until_their_minimum_age() {
+        let temp = tempdir().unwrap();
+        let download = temp.path().join("movie.mkv");
+        std::fs::write(&download, b"complete").unwrap();
+        let settle_time = std::time::Duration::ZERO;
+        let min_age = std::time::Duration::from_secs(60);
+        let mut settling = SettlingFiles::default();
+
+        assert!(settling
+            .defer(
+                FileSystemEvent::Created(download.clone()),
+                settle_time,
+                min_age
+            )
+            .is_none());
+        // Unchanged, but not old enough yet.
+        assert!(settling.take_settled(settle_time).is_empty());
+        assert_eq!(settling.pending.len(), 1);
+
+        std::fs::File::options()
+            .write(true)
+            .open(&download)
+            .unwrap()
+            .set_modified(SystemTime::now() - std::time::Duration::from_secs(120))
+            .unwrap();
+        // The new mtime is a change; it settles on the next check.
+        assert!(settling.take_settled(settle_time).is_empty());
+        let settled = settling.take_settled(settle_time);
+        assert!(
+            matches!(settled.as_slice(), [FileSystemEvent::Created(path)] if path == &download)
+        );
+    }
+
     #[cfg(unix)]
     #[tokio::test]
     async fn watcher_index_helper_rejects_symlinked_media() {
@@ -113,9 +712,7 @@ mod tests {
         let link = media_root.join("created.mp4");
         symlink(&outside, &link).unwrap();
 
-        let database = database::redb::RedbDatabase::new(temp.path().join("watcher.redb"))
-            .await
-            .unwrap();
+        let database = database::memory::InMemoryDatabase::new();
         database.initialize().await.unwrap();
         let policy = media::ScanPolicy::platform_default(&media_root, true);
         let filesystem_manager = create_platform_filesystem_manager();
@@ -133,6 +730,840 @@ mod tests {
             .is_empty());
     }
 
+    #[test]
+    fn check_config_reports_errors_without_touching_the_filesystem() {
+        let dir = tempdir().unwrap();
+        let media_dir = dir.path().join("media");
+        std::fs::create_dir(&media_dir).unwrap();
+        let database_dir = dir.path().join("db");
+
+        let mut config = AppConfig::default_for_platform();
+        config.server.port = 18080;
+        config.media.directories = vec![monitored(&media_dir)];
+        config.database.path = Some(
+            database_dir
+                .join("media.redb")
+                .to_string_lossy()
+                .into_owned(),
+        );
+        let config_path = dir.path().join("config.toml");
+        config.save_to_file(&config_path).unwrap();
+
+        let report = ConfigCheckReport::for_file(&config_path);
+        assert!(report.errors.is_empty(), "{report}");
+        assert!(
+            report
+                .warnings
+                .iter()
+                .any(|warning| warning.contains("will be created at startup")),
+            "{report}"
+        );
+        assert!(!database_dir.exists());
+
+        config.media.directories[0].media_types = Some(vec!["music".to_string()]);
+        config.save_to_file(&config_path).unwrap();
+        let report = ConfigCheckReport::for_file(&config_path);
+        assert_eq!(report.errors.len(), 1, "{report}");
+        assert!(report.to_string().ends_with("Result: FAILED"));
+
+        let missing = ConfigCheckReport::for_file(&dir.path().join("missing.toml"));
+        assert!(missing.errors[0].contains("does not exist"));
+    }
+
+    async fn watched_state(
+        config: AppConfig,
+        log_dir: &Path,
+    ) -> AppState<database::memory::InMemoryDatabase> {
+        let config = Arc::new(config);
+        let discovery_service = Arc::new(crate::discovery::DiscoveryService::default());
+        let database = Arc::new(database::memory::InMemoryDatabase::new());
+        database.initialize().await.unwrap();
+        AppState {
+            config: config.clone(),
+            live_config: Arc::new(crate::state::LiveConfig::new(config.clone())),
+            desired_config: Arc::new(crate::state::LiveConfig::new(config.clone())),
+            config_reload_errors: Arc::new(std::sync::RwLock::new(Vec::new())),
+            pending_restart_fields: Arc::new(std::sync::RwLock::new(Vec::new())),
+            media_directories: Arc::new(tokio::sync::RwLock::new(config.media.directories.clone())),
+            unavailable_roots: Arc::new(tokio::sync::RwLock::new(HashSet::new())),
+            database,
+            auth: Arc::new(crate::web::auth::AuthState::testing()),
+            auth_forced: false,
+            platform_info: Arc::new(PlatformInfo::detect().await.unwrap()),
+            network_interfaces: Arc::default(),
+            filesystem_manager: Arc::from(create_platform_filesystem_manager()),
+            content_update_id: Arc::new(std::sync::atomic::AtomicU32::new(1)),
+            content_change_notify: Arc::new(tokio::sync::Notify::new()),
+            http_rebind_notify: Arc::new(tokio::sync::Notify::new()),
+            ssdp_reload_notify: Arc::new(tokio::sync::Notify::new()),
+            playlist_mirror_notify: Arc::new(tokio::sync::Notify::new()),
+            web_metrics: Arc::new(crate::web::diagnostics::WebHandlerMetrics::new()),
+            stream_audit: None,
+            scan_control: Arc::new(crate::scan_progress::ScanControl::new()),
+            health: Arc::new(crate::state::ServiceHealth::default()),
+            runtime_diagnostics: Arc::new(
+                crate::platform::diagnostics::SystemDiagnosticsSampler::new(),
+            ),
+            lifecycle_stats: Arc::new(ApplicationStats::new()),
+            bookmarks: Arc::new(tokio::sync::Mutex::new(
+                crate::runtime_state::BookmarkRegistry::new(
+                    crate::runtime_state::BOOKMARK_MAX_ENTRIES,
+                ),
+            )),
+            log_file_path: log_dir.join("vuio.log"),
+            browse_cache: Arc::new(tokio::sync::Mutex::new(
+                crate::runtime_state::BrowseResponseCache::new(),
+            )),
+            hidden_files: Arc::default(),
+            active_streams: Arc::default(),
+            container_update_ids: Arc::default(),
+            mcp_clients: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+            active_monitors: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+            active_casts: Arc::new(tokio::sync::Mutex::new(
+                crate::runtime_state::ActiveCastRegistry::new(),
+            )),
+            cast_sessions: Arc::new(tokio::sync::Mutex::new(
+                crate::runtime_state::CastSessionRegistry::new(),
+            )),
+            discovered_tvs: Arc::new(crate::runtime_state::RendererCache::from_discovery(
+                discovery_service.clone(),
+            )),
+            discovery_service,
+            upnp_subscriptions: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+            cancellation: CancellationToken::new(),
+            background_tasks: tokio_util::task::TaskTracker::new(),
+        }
+    }
+
+    fn free_local_port() -> u16 {
+        std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port()
+    }
+
+    async fn serves_health(port: u16) -> bool {
+        reqwest::get(format!("http://127.0.0.1:{port}/healthz"))
+            .await
+            .is_ok_and(|response| response.status().is_success())
+    }
+
+    #[tokio::test]
+    async fn port_changes_rebind_the_listener_and_reload_ssdp() {
+        let temp = tempdir().unwrap();
+        let old_port = free_local_port();
+        let mut config = AppConfig::default_for_platform();
+        config.server.interface = "127.0.0.1".to_owned();
+        config.server.port = old_port;
+        let state = watched_state(config.clone(), temp.path()).await;
+        let handles = start_http_server_task(state.clone(), state.cancellation.clone())
+            .await
+            .unwrap();
+        assert!(serves_health(old_port).await);
+
+        let new_port = free_local_port();
+        config.server.port = new_port;
+        state.desired_config.store(Arc::new(config));
+        state
+            .pending_restart_fields
+            .write()
+            .unwrap()
+            .push("server.port".to_owned());
+        state.http_rebind_notify.notify_one();
+
+        tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            state.ssdp_reload_notify.notified(),
+        )
+        .await
+        .expect("SSDP was not asked to re-announce");
+        assert_eq!(state.current_config().server.port, new_port);
+        assert!(state.pending_restart_fields.read().unwrap().is_empty());
+        assert!(serves_health(new_port).await);
+        assert!(!serves_health(old_port).await);
+
+        state.cancellation.cancel();
+        handles.http.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn a_taken_port_falls_back_to_a_preferred_port_only_when_enabled() {
+        let temp = tempdir().unwrap();
+        let occupied = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let taken_port = occupied.local_addr().unwrap().port();
+        let mut config = AppConfig::default_for_platform();
+        config.server.interface = "127.0.0.1".to_owned();
+        config.server.port = taken_port;
+
+        let state = watched_state(config.clone(), temp.path()).await;
+        assert!(
+            start_http_server_task(state.clone(), state.cancellation.clone())
+                .await
+                .is_err()
+        );
+
+        config.server.port_fallback = true;
+        let state = watched_state(config, temp.path()).await;
+        let handles = start_http_server_task(state.clone(), state.cancellation.clone())
+            .await
+            .unwrap();
+        let bound_port = state.current_config().server.port;
+        assert_ne!(bound_port, taken_port);
+        assert!(platform::config::PlatformConfig::for_current_platform()
+            .preferred_ports
+            .contains(&bound_port));
+        assert!(serves_health(bound_port).await);
+
+        // Reloading the unchanged configuration neither marks the fallback
+        // port pending nor asks for a rebind; changing the port does.
+        let desired = state.desired_config.load();
+        let live = state.current_config();
+        assert_eq!(desired.server.port, taken_port);
+        assert_eq!(
+            http_address_reload(&desired.server, &live.server, &desired.server, false),
+            (false, false)
+        );
+        let mut moved = (*desired).clone();
+        moved.server.port = free_local_port();
+        assert_eq!(
+            http_address_reload(&desired.server, &live.server, &moved.server, false),
+            (true, true)
+        );
+
+        state.cancellation.cancel();
+        handles.http.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn a_changed_primary_interface_restarts_ssdp_once_confirmed() {
+        let temp = tempdir().unwrap();
+        let state = watched_state(AppConfig::default_for_platform(), temp.path()).await;
+        let interface = |name: &str, address: [u8; 4]| platform::NetworkInterface {
+            name: name.to_owned(),
+            ip_address: std::net::IpAddr::from(address),
+            is_loopback: false,
+            is_up: true,
+            supports_multicast: true,
+            interface_type: platform::InterfaceType::Ethernet,
+        };
+        let moved = vec![interface("vuio-test0", [10, 254, 3, 7])];
+        let mut debounce = InterfaceChangeDebounce::default();
+
+        // The first sighting only arms the change.
+        assert!(!check_network_interfaces(
+            &state,
+            &mut debounce,
+            moved.clone()
+        ));
+        assert!(state.network_interfaces.read().unwrap().is_none());
+        assert!(check_network_interfaces(
+            &state,
+            &mut debounce,
+            moved.clone()
+        ));
+        tokio::time::timeout(
+            std::time::Duration::from_secs(1),
+            state.ssdp_reload_notify.notified(),
+        )
+        .await
+        .expect("SSDP was not restarted");
+        assert_eq!(state.get_server_ip(), "10.254.3.7");
+        assert!(!check_network_interfaces(
+            &state,
+            &mut debounce,
+            moved.clone()
+        ));
+
+        // A flap that recovers before the next check changes nothing.
+        assert!(!check_network_interfaces(&state, &mut debounce, Vec::new()));
+        assert!(!check_network_interfaces(
+            &state,
+            &mut debounce,
+            moved.clone()
+        ));
+        assert!(!check_network_interfaces(&state, &mut debounce, Vec::new()));
+        assert!(check_network_interfaces(&state, &mut debounce, Vec::new()));
+        assert!(state.primary_interface().is_none());
+    }
+
+    #[tokio::test]
+    async fn all_interfaces_announce_and_link_through_the_nearest_one() {
+        let temp = tempdir().unwrap();
+        let mut config = AppConfig::default_for_platform();
+        config.network.interface_selection = crate::config::NetworkInterfaceConfig::All;
+        config.server.ip = None;
+        config.server.interface = "0.0.0.0".to_owned();
+        let state = watched_state(config, temp.path()).await;
+        let interface =
+            |name: &str, address: [u8; 4], is_loopback: bool| platform::NetworkInterface {
+                name: name.to_owned(),
+                ip_address: std::net::IpAddr::from(address),
+                is_loopback,
+                is_up: true,
+                supports_multicast: true,
+                interface_type: platform::InterfaceType::Ethernet,
+            };
+        *state.network_interfaces.write().unwrap() = Some(vec![
+            interface("vuio-test0", [10, 1, 0, 2], false),
+            interface("vuio-test1", [192, 168, 5, 2], false),
+            interface("lo", [127, 0, 0, 1], true),
+        ]);
+
+        let announced = state.announced_interfaces().unwrap();
+        assert_eq!(
+            announced
+                .iter()
+                .map(|interface| state.get_server_ip_for(interface))
+                .collect::<Vec<_>>(),
+            ["10.1.0.2", "192.168.5.2"]
+        );
+        let port = state.current_config().server.port;
+        assert_eq!(
+            state.http_origin_for_peer("192.168.5.40".parse().unwrap()),
+            format!("http://192.168.5.2:{port}")
+        );
+        assert_eq!(
+            state.http_origin_for_peer("10.1.7.9".parse().unwrap()),
+            format!("http://10.1.0.2:{port}")
+        );
+
+        // An explicit server.ip still wins everywhere.
+        let mut config = (*state.current_config()).clone();
+        config.server.ip = Some("203.0.113.4".to_owned());
+        state.live_config.store(Arc::new(config));
+        assert_eq!(state.get_server_ip_for(&announced[1]), "203.0.113.4");
+        assert_eq!(
+            state.http_origin_for_peer("192.168.5.40".parse().unwrap()),
+            format!("http://203.0.113.4:{port}")
+        );
+    }
+
+    #[tokio::test]
+    async fn watcher_events_honor_directory_extensions_and_excludes() {
+        let temp = tempdir().unwrap();
+        let root = temp.path().join("media");
+        let junk = root.join("@eaDir");
+        tokio::fs::create_dir_all(&junk).await.unwrap();
+        let mut config = AppConfig::default_for_platform();
+        config.media.directories = vec![MonitoredDirectoryConfig {
+            path: root.to_string_lossy().into_owned(),
+            recursive: true,
+            case_sensitive: Some(true),
+            extensions: Some(vec!["mkv".to_owned(), "srt".to_owned()]),
+            exclude_patterns: Some(vec!["@eaDir".to_owned(), "*.part".to_owned()]),
+            validation_mode: ValidationMode::Warn,
+            watch_mode: WatchMode::Auto,
+            merge_group: None,
+            priority: 0,
+            media_types: None,
+            min_file_size: None,
+            min_file_age_seconds: None,
+        }];
+        let state = watched_state(config, temp.path()).await;
+
+        // Created inside an excluded folder, excluded by name, or outside the
+        // directory's own extension list: none of these reach the database.
+        let rejected = [
+            junk.join("thumb.mkv"),
+            root.join("movie.mkv.part"),
+            root.join("song.mp3"),
+        ];
+        for path in &rejected {
+            tokio::fs::write(path, b"media").await.unwrap();
+            handle_file_system_event(FileSystemEvent::Created(path.clone()), &state)
+                .await
+                .unwrap();
+            handle_file_system_event(FileSystemEvent::Modified(path.clone()), &state)
+                .await
+                .unwrap();
+        }
+        tokio::fs::write(junk.join("thumb.srt"), b"1")
+            .await
+            .unwrap();
+        handle_file_system_event(FileSystemEvent::Created(junk.join("thumb.srt")), &state)
+            .await
+            .unwrap();
+
+        // A junk folder appearing at runtime is not scanned as a new subtree.
+        let created_junk = root.join("season").join("@eaDir");
+        tokio::fs::create_dir_all(&created_junk).await.unwrap();
+        tokio::fs::write(created_junk.join("preview.mkv"), b"media")
+            .await
+            .unwrap();
+        handle_file_system_event(FileSystemEvent::Created(created_junk.clone()), &state)
+            .await
+            .unwrap();
+
+        assert!(state
+            .database
+            .stream_all_media_files()
+            .collect::<Vec<_>>()
+            .await
+            .is_empty());
+        assert_eq!(state.content_update_id.load(Ordering::SeqCst), 1);
+
+        let accepted = root.join("season").join("episode.mkv");
+        tokio::fs::write(&accepted, b"media").await.unwrap();
+        handle_file_system_event(FileSystemEvent::Created(root.join("season")), &state)
+            .await
+            .unwrap();
+        let indexed = state
+            .database
+            .stream_all_media_files()
+            .map(|file| file.unwrap().path)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(indexed, vec![accepted.canonicalize().unwrap()]);
+    }
+
+    #[tokio::test]
+    async fn playlists_in_new_directories_are_imported_once() {
+        let temp = tempdir().unwrap();
+        let root = std::fs::canonicalize(temp.path()).unwrap().join("music");
+        tokio::fs::create_dir_all(&root).await.unwrap();
+        let mut config = AppConfig::default_for_platform();
+        config.media.scan_playlists = true;
+        config.media.directories = vec![monitored(&root)];
+        let state = watched_state(config.clone(), temp.path()).await;
+
+        let album = root.join("album");
+        tokio::fs::create_dir_all(&album).await.unwrap();
+        tokio::fs::write(album.join("track.mp3"), b"audio")
+            .await
+            .unwrap();
+        tokio::fs::write(album.join("album.m3u"), "track.mp3\n")
+            .await
+            .unwrap();
+        handle_file_system_event(FileSystemEvent::Created(album.clone()), &state)
+            .await
+            .unwrap();
+        let playlists = state.database.get_playlists().await.unwrap();
+        assert_eq!(playlists.len(), 1);
+        assert_eq!(
+            state
+                .database
+                .get_playlist_tracks(playlists[0].id.unwrap())
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // A restart finds the same file again and leaves its playlist alone.
+        assert_eq!(
+            perform_initial_playlist_scan(&config, &state.database)
+                .await
+                .unwrap(),
+            0
+        );
+        let rescanned = state.database.get_playlists().await.unwrap();
+        assert_eq!(rescanned.len(), 1);
+        assert_eq!(rescanned[0].id, playlists[0].id);
+        assert_eq!(rescanned[0].updated_at, playlists[0].updated_at);
+
+        tokio::fs::write(album.join("album.m3u"), "track.mp3\ntrack.mp3\n")
+            .await
+            .unwrap();
+        assert_eq!(
+            perform_initial_playlist_scan(&config, &state.database)
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(state.database.get_playlists().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn a_deleted_file_is_hidden_and_keeps_its_id_when_restored() {
+        let temp = tempdir().unwrap();
+        let root = std::fs::canonicalize(temp.path()).unwrap().join("media");
+        tokio::fs::create_dir_all(&root).await.unwrap();
+        let film = root.join("film.mkv");
+        tokio::fs::write(&film, b"media").await.unwrap();
+        let mut config = AppConfig::default_for_platform();
+        config.media.directories = vec![monitored(&root)];
+        let state = watched_state(config, temp.path()).await;
+        handle_file_system_event(FileSystemEvent::Created(film.clone()), &state)
+            .await
+            .unwrap();
+        let id = state
+            .database
+            .get_file_by_path(&film)
+            .await
+            .unwrap()
+            .unwrap()
+            .id
+            .unwrap();
+
+        tokio::fs::remove_file(&film).await.unwrap();
+        handle_file_system_event(
+            FileSystemEvent::Deleted {
+                path: film.clone(),
+                is_directory: Some(false),
+            },
+            &state,
+        )
+        .await
+        .unwrap();
+        let missing = state.database.get_file_by_id(id).await.unwrap().unwrap();
+        assert!(missing.missing_since.is_some());
+        assert!(crate::web::soap::hidden_files(&state).await.contains(&id));
+
+        tokio::fs::write(&film, b"media").await.unwrap();
+        handle_file_system_event(FileSystemEvent::Created(film.clone()), &state)
+            .await
+            .unwrap();
+        let restored = state
+            .database
+            .get_file_by_path(&film)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!((restored.id, restored.missing_since), (Some(id), None));
+        assert!(!crate::web::soap::hidden_files(&state).await.contains(&id));
+    }
+
+    #[tokio::test]
+    async fn a_vanished_media_root_is_paused_instead_of_emptied() {
+        let temp = tempdir().unwrap();
+        let root = temp.path().join("drive");
+        tokio::fs::create_dir_all(&root).await.unwrap();
+        let film = root.join("film.mkv");
+        let extra = root.join("extra.mkv");
+        for path in [&film, &extra] {
+            tokio::fs::write(path, b"media").await.unwrap();
+        }
+        let mut config = AppConfig::default_for_platform();
+        config.media.directories = vec![monitored(&root)];
+        config.media.deletion_grace_minutes = 0;
+        let state = watched_state(config, temp.path()).await;
+        for path in [&film, &extra] {
+            handle_file_system_event(FileSystemEvent::Created(path.clone()), &state)
+                .await
+                .unwrap();
+        }
+        let watcher = CrossPlatformWatcher::new();
+        let deleted = |path: &Path, is_directory| FileSystemEvent::Deleted {
+            path: path.to_path_buf(),
+            is_directory: Some(is_directory),
+        };
+        let indexed = || async {
+            state
+                .database
+                .stream_all_media_files()
+                .map(|file| file.unwrap().path)
+                .collect::<Vec<_>>()
+                .await
+        };
+
+        // A file deleted from a present root is removed as before.
+        tokio::fs::remove_file(&extra).await.unwrap();
+        handle_event_or_reconcile(deleted(&extra, false), &state, &watcher).await;
+        assert_eq!(indexed().await, vec![film.clone()]);
+
+        // The drive is unmounted: the watcher reports everything deleted.
+        tokio::fs::rename(&root, temp.path().join("unmounted"))
+            .await
+            .unwrap();
+        handle_event_or_reconcile(deleted(&film, false), &state, &watcher).await;
+        handle_event_or_reconcile(deleted(&root, true), &state, &watcher).await;
+        assert_eq!(indexed().await, vec![film.clone()]);
+        assert!(state.unavailable_roots.read().await.contains(&root));
+        assert!(state
+            .database
+            .get_root_availability(&root)
+            .await
+            .unwrap()
+            .is_some_and(|availability| availability.unavailable_since_secs.is_some()));
+
+        // Remounted, the periodic reconciliation lists it again.
+        tokio::fs::rename(temp.path().join("unmounted"), &root)
+            .await
+            .unwrap();
+        reconcile_unavailable_media_roots(&state.database, std::slice::from_ref(&root), 24)
+            .await
+            .unwrap();
+        assert!(refresh_unavailable_roots(&state).await.unwrap());
+        assert!(state.unavailable_roots.read().await.is_empty());
+        assert_eq!(indexed().await, vec![film]);
+    }
+
+    #[tokio::test]
+    async fn files_written_during_startup_are_indexed_exactly_once() {
+        let temp = tempdir().unwrap();
+        let native = temp.path().join("native");
+        let polled = temp.path().join("polled");
+        tokio::fs::create_dir_all(&native).await.unwrap();
+        tokio::fs::create_dir_all(&polled).await.unwrap();
+        let mut config = AppConfig::default_for_platform();
+        config.media.settle_time_seconds = 0;
+        config.media.watch_for_changes = true;
+        config.media.directories = vec![monitored(&native), monitored(&polled)];
+        config.media.directories[1].watch_mode = WatchMode::Poll;
+        let state = watched_state(config.clone(), temp.path()).await;
+
+        // Keep creating files in both roots for the whole startup sequence.
+        let writing = CancellationToken::new();
+        let writer = tokio::spawn({
+            let writing = writing.clone();
+            let roots = [native.clone(), polled.clone()];
+            async move {
+                let mut written = Vec::new();
+                while !writing.is_cancelled() {
+                    let path = roots[written.len() % 2].join(format!("clip-{}.mp4", written.len()));
+                    tokio::fs::write(&path, b"media").await.unwrap();
+                    written.push(path.canonicalize().unwrap());
+                    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+                }
+                written
+            }
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        let watcher = Arc::new(
+            CrossPlatformWatcher::with_policies(media::ScanPolicy::policies(&config))
+                .with_poll_interval(std::time::Duration::from_millis(100)),
+        );
+        let (scan_finished, initial_scan) = tokio::sync::watch::channel(false);
+        let monitor = start_file_monitoring(
+            watcher.clone(),
+            state.clone(),
+            state.cancellation.clone(),
+            initial_scan,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        perform_initial_media_scan(&config, &state.database, &state.scan_control, false)
+            .await
+            .unwrap();
+        scan_finished.send_replace(true);
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        writing.cancel();
+        let mut written = writer.await.unwrap();
+        written.sort();
+
+        let mut indexed = Vec::new();
+        for _ in 0..100 {
+            indexed = state
+                .database
+                .stream_all_media_files()
+                .map(|file| file.unwrap().path)
+                .collect::<Vec<_>>()
+                .await;
+            indexed.sort();
+            if indexed == written {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        assert_eq!(indexed, written);
+
+        state.cancellation.cancel();
+        monitor.await.unwrap();
+        watcher.stop_watching().await.unwrap();
+    }
+
+    async fn call_mcp_tool<D: DatabaseManager + 'static>(
+        state: &AppState<D>,
+        name: &str,
+        arguments: serde_json::Value,
+    ) -> serde_json::Value {
+        use axum::extract::{ConnectInfo, Json, Query, State};
+
+        let peer: SocketAddr = "127.0.0.1:43123".parse().unwrap();
+        let (sender, mut responses) = tokio::sync::mpsc::channel(1);
+        state.mcp_clients.lock().await.insert(
+            "mirror-test".to_owned(),
+            crate::state::McpClient {
+                sender,
+                peer: peer.ip(),
+                expires_at: std::time::Instant::now() + std::time::Duration::from_secs(60),
+            },
+        );
+        let request = serde_json::from_value(serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "tools/call",
+            "id": 1,
+            "params": { "name": name, "arguments": arguments }
+        }))
+        .unwrap();
+        let _ = web::mcp::message_handler(
+            State(state.clone()),
+            ConnectInfo(peer),
+            Query(web::mcp::MessageQuery {
+                client_id: "mirror-test".to_owned(),
+            }),
+            Json(request),
+        )
+        .await;
+        let response: serde_json::Value =
+            serde_json::from_str(&responses.recv().await.unwrap()).unwrap();
+        serde_json::from_str(response["result"]["content"][0]["text"].as_str().unwrap()).unwrap()
+    }
+
+    /// Wait until the mirrored playlist at `path` lists `entries`, or is gone
+    /// when `entries` is `None`.
+    async fn wait_for_mirror(path: &Path, entries: Option<&[&str]>) {
+        let mut current = None;
+        for _ in 0..100 {
+            current = tokio::fs::read_to_string(path).await.ok().map(|content| {
+                content
+                    .lines()
+                    .filter(|line| !line.starts_with('#'))
+                    .map(str::to_owned)
+                    .collect::<Vec<_>>()
+            });
+            if current
+                .as_deref()
+                .map(|lines| lines.iter().map(String::as_str).collect::<Vec<_>>())
+                == entries.map(<[&str]>::to_vec)
+            {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        panic!("{} holds {current:?}, expected {entries:?}", path.display());
+    }
+
+    #[tokio::test]
+    async fn playlist_edits_are_mirrored_and_never_imported_back() {
+        let temp = tempdir().unwrap();
+        let root = std::fs::canonicalize(temp.path()).unwrap();
+        let music = root.join("Music");
+        let mirror = root.join("Playlists");
+        tokio::fs::create_dir_all(&music).await.unwrap();
+        for name in ["one.mp3", "two.mp3"] {
+            tokio::fs::write(music.join(name), b"audio").await.unwrap();
+        }
+        let mut config = AppConfig::default_for_platform();
+        config.media.scan_playlists = true;
+        config.media.directories = vec![monitored(&root)];
+        config.media.playlist_mirror_dir = Some(mirror.clone());
+        let state = watched_state(config.clone(), temp.path()).await;
+        perform_initial_media_scan(&config, &state.database, &state.scan_control, false)
+            .await
+            .unwrap();
+        let mut file_ids = Vec::new();
+        for name in ["one.mp3", "two.mp3"] {
+            let file = state
+                .database
+                .get_file_by_path(&music.join(name))
+                .await
+                .unwrap()
+                .unwrap();
+            file_ids.push(file.id.unwrap());
+        }
+        let mirroring = tokio::spawn(run_playlist_mirror(
+            state.clone(),
+            state.cancellation.clone(),
+        ));
+
+        let created = call_mcp_tool(
+            &state,
+            "create_playlist",
+            serde_json::json!({ "name": "Road Trip" }),
+        )
+        .await;
+        let playlist_id = created["playlist_id"].as_i64().unwrap();
+        call_mcp_tool(
+            &state,
+            "add_to_playlist",
+            serde_json::json!({ "playlist_id": playlist_id, "media_file_ids": [file_ids[1], file_ids[0]] }),
+        )
+        .await;
+        let road_trip = mirror.join("Road Trip.m3u8");
+        wait_for_mirror(&road_trip, Some(&["../Music/two.mp3", "../Music/one.mp3"])).await;
+
+        call_mcp_tool(
+            &state,
+            "remove_from_playlist",
+            serde_json::json!({ "playlist_id": playlist_id, "media_file_id": file_ids[1] }),
+        )
+        .await;
+        wait_for_mirror(&road_trip, Some(&["../Music/one.mp3"])).await;
+
+        // Rescanning the library must not import the mirror as a second playlist.
+        perform_initial_playlist_scan(&config, &state.database)
+            .await
+            .unwrap();
+        assert_eq!(state.database.get_playlists().await.unwrap().len(), 1);
+        assert!(state
+            .database
+            .scan_and_import_playlists(&mirror)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let mut playlist = state
+            .database
+            .get_playlist(playlist_id)
+            .await
+            .unwrap()
+            .unwrap();
+        playlist.name = "Commute".to_owned();
+        state.database.update_playlist(&playlist).await.unwrap();
+        web::eventing::publish_content_change(&state).await;
+        wait_for_mirror(&mirror.join("Commute.m3u8"), Some(&["../Music/one.mp3"])).await;
+        wait_for_mirror(&road_trip, None).await;
+
+        // A playlist the user keeps in the mirror directory is theirs.
+        let own = mirror.join("Mine.m3u8");
+        tokio::fs::write(&own, "../Music/two.mp3\n").await.unwrap();
+        call_mcp_tool(
+            &state,
+            "delete_playlist",
+            serde_json::json!({ "playlist_id": playlist_id }),
+        )
+        .await;
+        wait_for_mirror(&mirror.join("Commute.m3u8"), None).await;
+        assert_eq!(
+            tokio::fs::read_to_string(&own).await.unwrap(),
+            "../Music/two.mp3\n"
+        );
+        assert_eq!(state.database.get_playlists().await.unwrap().len(), 0);
+        // Only the user's file is imported from the mirror directory.
+        assert_eq!(
+            state
+                .database
+                .scan_and_import_playlists(&mirror)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        state.cancellation.cancel();
+        mirroring.await.unwrap();
+    }
+
+    #[test]
+    fn rebuilding_keeps_the_old_database_even_without_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = AppConfig::default_for_platform();
+        config.database.backend = DatabaseBackendKind::Redb;
+        config.database.path = Some(dir.path().join("media.db").to_string_lossy().into_owned());
+        config.database.backup_enabled = false;
+        let db_path = config.get_database_path().with_extension("redb");
+        std::fs::write(&db_path, b"playlists and bookmarks").unwrap();
+
+        discard_database_for_rebuild(&config).unwrap();
+
+        assert!(!db_path.exists());
+        let kept = std::fs::read_dir(config.get_backup_dir())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect::<Vec<_>>();
+        assert_eq!(kept.len(), 1);
+        assert!(kept[0]
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("pre-rebuild-"));
+        assert_eq!(std::fs::read(&kept[0]).unwrap(), b"playlists and bookmarks");
+    }
+
     #[test]
     fn failed_database_is_quarantined_without_changing_its_contents() {
         let temp = tempdir().unwrap();
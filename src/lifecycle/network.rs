@@ -41,6 +41,160 @@ async fn start_ssdp_service<D: DatabaseManager + 'static>(
     Ok(handle)
 }
 
+/// How often the interface monitor re-reads a config that has the check
+/// disabled, so enabling it by reload takes effect.
+const INTERFACE_CHECK_DISABLED_POLL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Announced-interface changes waiting for confirmation. A change is applied
+/// only once two consecutive checks agree on it, so a link that flaps or is
+/// still coming up after resume does not restart SSDP twice.
+#[derive(Debug, Default)]
+struct InterfaceChangeDebounce {
+    candidate: Option<Option<(String, std::net::IpAddr)>>,
+}
+
+impl InterfaceChangeDebounce {
+    /// Record one check. Returns true when `detected` differs from `applied`
+    /// and the previous check saw the same change.
+    fn confirm(
+        &mut self,
+        applied: &Option<(String, std::net::IpAddr)>,
+        detected: Option<(String, std::net::IpAddr)>,
+    ) -> bool {
+        if &detected == applied {
+            self.candidate = None;
+            return false;
+        }
+        if self.candidate.as_ref() == Some(&detected) {
+            self.candidate = None;
+            return true;
+        }
+        self.candidate = Some(detected);
+        false
+    }
+}
+
+fn interface_identity(
+    interface: Option<&platform::NetworkInterface>,
+) -> Option<(String, std::net::IpAddr)> {
+    interface.map(|interface| (interface.name.clone(), interface.ip_address))
+}
+
+/// Re-detect network interfaces every `network.interface_check_interval_seconds`
+/// and restart SSDP when the announced interface changes or disappears, so a
+/// laptop that switches networks or resumes from sleep advertises a LOCATION
+/// that is still reachable.
+async fn run_interface_monitor<D: DatabaseManager + 'static>(
+    app_state: AppState<D>,
+    cancellation: CancellationToken,
+) {
+    let mut debounce = InterfaceChangeDebounce::default();
+    loop {
+        let seconds = app_state
+            .current_config()
+            .network
+            .interface_check_interval_seconds;
+        let wait = if seconds == 0 {
+            INTERFACE_CHECK_DISABLED_POLL
+        } else {
+            std::time::Duration::from_secs(seconds)
+        };
+        tokio::select! {
+            _ = cancellation.cancelled() => return,
+            _ = tokio::time::sleep(wait) => {}
+        }
+        if seconds == 0 {
+            continue;
+        }
+        match PlatformInfo::detect_network_interfaces().await {
+            Ok(interfaces) => {
+                check_network_interfaces(&app_state, &mut debounce, interfaces);
+            }
+            Err(error) => debug!("Network interface re-detection failed: {}", error),
+        }
+    }
+}
+
+/// Compare one detection with the announced interface and, once the change is
+/// confirmed, publish the new interfaces and restart SSDP. Returns true when
+/// SSDP was asked to restart.
+fn check_network_interfaces<D: DatabaseManager>(
+    app_state: &AppState<D>,
+    debounce: &mut InterfaceChangeDebounce,
+    interfaces: Vec<platform::NetworkInterface>,
+) -> bool {
+    let config = app_state.current_config();
+    let applied = interface_identity(app_state.primary_interface().as_ref());
+    let detected = interface_identity(platform::primary_interface_in(
+        &interfaces,
+        config.network.preferred_subnet.as_deref(),
+    ));
+    if !debounce.confirm(&applied, detected.clone()) {
+        return false;
+    }
+    let describe = |identity: &Option<(String, std::net::IpAddr)>| match identity {
+        Some((name, address)) => format!("{name} ({address})"),
+        None => "none".to_owned(),
+    };
+    info!(
+        "Primary network interface changed from {} to {}; restarting SSDP",
+        describe(&applied),
+        describe(&detected)
+    );
+    *app_state
+        .network_interfaces
+        .write()
+        .unwrap_or_else(|error| error.into_inner()) = Some(interfaces);
+    app_state.ssdp_reload_notify.notify_one();
+    true
+}
+
+/// Bind the startup HTTP listener. With `server.port_fallback`, a port that is
+/// already in use is replaced by the first free platform preferred port, which
+/// becomes the effective `server.port` so SSDP advertises the live address.
+async fn bind_http_listener<D: DatabaseManager + 'static>(
+    app_state: &AppState<D>,
+    addr: SocketAddr,
+) -> anyhow::Result<tokio::net::TcpListener> {
+    let error = match tokio::net::TcpListener::bind(addr).await {
+        Ok(listener) => return Ok(listener),
+        Err(error) => error,
+    };
+    let config = app_state.current_config();
+    if error.kind() != std::io::ErrorKind::AddrInUse || !config.server.port_fallback {
+        return Err(anyhow::Error::from(error))
+            .with_context(|| format!("Failed to bind to address: {}", addr));
+    }
+
+    let preferred_ports = platform::config::PlatformConfig::for_current_platform().preferred_ports;
+    for port in preferred_ports {
+        if port == addr.port() {
+            continue;
+        }
+        let candidate = SocketAddr::new(addr.ip(), port);
+        match tokio::net::TcpListener::bind(candidate).await {
+            Ok(listener) => {
+                warn!(
+                    "HTTP port {} is already in use; listening on fallback port {} instead",
+                    addr.port(),
+                    port
+                );
+                let mut effective = (*config).clone();
+                effective.server.port = port;
+                app_state.live_config.store(Arc::new(effective));
+                return Ok(listener);
+            }
+            Err(error) => debug!("Fallback HTTP port {} is unavailable: {}", port, error),
+        }
+    }
+    Err(anyhow::Error::from(error)).with_context(|| {
+        format!(
+            "Failed to bind to address {} and no preferred fallback port is free",
+            addr
+        )
+    })
+}
+
 /// Start HTTP server as a background task with proper error handling
 async fn start_http_server_task<D: DatabaseManager + 'static>(
     app_state: AppState<D>,
@@ -50,25 +204,39 @@ async fn start_http_server_task<D: DatabaseManager + 'static>(
 
     let config = app_state.current_config();
 
-    let addr = server_address(&config.server)?;
+    let addrs = http_addresses(&app_state, &config.server)?;
 
     info!("Server UUID: {}", config.server.uuid);
     info!("Server name: {}", config.server.name);
-    info!("Listening on http://{}", addr);
 
-    // Attempt to bind to the address
-    let listener = tokio::net::TcpListener::bind(addr)
-        .await
-        .with_context(|| format!("Failed to bind to address: {}", addr))?;
+    // The first address may fall back to another port; the rest follow it.
+    let first = bind_http_listener(&app_state, addrs[0]).await?;
+    let port = first.local_addr()?.port();
+    let mut listeners = vec![first];
+    for addr in &addrs[1..] {
+        let addr = SocketAddr::new(addr.ip(), port);
+        listeners.push(
+            tokio::net::TcpListener::bind(addr)
+                .await
+                .with_context(|| format!("Failed to bind to address: {}", addr))?,
+        );
+    }
 
+    for listener in &listeners {
+        info!("Listening on http://{}", listener.local_addr()?);
+    }
     info!("HTTP server started successfully");
 
+    // Before the HTTP supervisor starts, so a certificate that fails to load
+    // stops startup with nothing left serving.
+    let https = start_https_server_task(app_state.clone(), cancellation.clone()).await?;
+
     // A supervisor owns the active listener so bind/port changes can be
     // pre-bound and swapped without publishing an endpoint that is not live.
     let http = tokio::spawn(async move {
         let mut instance_cancel = CancellationToken::new();
         let mut instance = spawn_http_instance(
-            listener,
+            listeners,
             app_state.clone(),
             instance_cancel.clone(),
         );
@@ -83,23 +251,29 @@ async fn start_http_server_task<D: DatabaseManager + 'static>(
                 }
                 _ = app_state.http_rebind_notify.notified() => {
                     let desired = app_state.desired_config.load();
-                    let desired_addr = match server_address(&desired.server) {
-                        Ok(address) => address,
+                    let desired_addrs = match http_addresses(&app_state, &desired.server) {
+                        Ok(addresses) => addresses,
                         Err(error) => {
                             record_http_reload_error(&app_state, error.to_string());
                             continue;
                         }
                     };
-                    let replacement = match tokio::net::TcpListener::bind(desired_addr).await {
-                        Ok(listener) => listener,
-                        Err(error) => {
-                            record_http_reload_error(
-                                &app_state,
-                                format!("failed to bind replacement listener {desired_addr}: {error}"),
-                            );
-                            continue;
+                    let mut replacement = Vec::with_capacity(desired_addrs.len());
+                    for desired_addr in &desired_addrs {
+                        match tokio::net::TcpListener::bind(desired_addr).await {
+                            Ok(listener) => replacement.push(listener),
+                            Err(error) => {
+                                record_http_reload_error(
+                                    &app_state,
+                                    format!("failed to bind replacement listener {desired_addr}: {error}"),
+                                );
+                                break;
+                            }
                         }
-                    };
+                    }
+                    if replacement.len() != desired_addrs.len() {
+                        continue;
+                    }
                     let replacement_cancel = CancellationToken::new();
                     let replacement_task = spawn_http_instance(
                         replacement,
@@ -108,7 +282,12 @@ async fn start_http_server_task<D: DatabaseManager + 'static>(
                     );
 
                     let mut effective = (*app_state.current_config()).clone();
-                    effective.server = desired.server.clone();
+                    // The HTTPS listener only moves on restart.
+                    effective.server = crate::config::ServerConfig {
+                        tls: effective.server.tls.clone(),
+                        tls_port: effective.server.tls_port,
+                        ..desired.server.clone()
+                    };
                     app_state.live_config.store(Arc::new(effective));
                     app_state.ssdp_reload_notify.notify_one();
                     app_state
@@ -131,13 +310,101 @@ async fn start_http_server_task<D: DatabaseManager + 'static>(
                     }
                     instance_cancel = replacement_cancel;
                     instance = replacement_task;
-                    info!(%desired_addr, "HTTP listener reloaded");
+                    info!(?desired_addrs, "HTTP listener reloaded");
                 }
             }
         }
     });
 
-    Ok(NetworkTaskHandles { http })
+    Ok(NetworkTaskHandles { http, https })
+}
+
+/// How often the HTTPS service checks the certificate and key for changes.
+const TLS_RELOAD_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Start the `server.tls` listener on `server.tls_port` of every address the
+/// HTTP server uses, or nothing when TLS is not configured. The certificate
+/// is reloaded on SIGHUP and when its files or configured paths change.
+async fn start_https_server_task<D: DatabaseManager + 'static>(
+    app_state: AppState<D>,
+    cancellation: CancellationToken,
+) -> anyhow::Result<Option<tokio::task::JoinHandle<anyhow::Result<()>>>> {
+    let config = app_state.current_config();
+    let Some(tls) = config.server.tls.clone() else {
+        return Ok(None);
+    };
+    let certificate = Arc::new(web::tls::ReloadableCertificate::load(&tls)?);
+    let server_config = certificate.server_config()?;
+
+    let mut listeners = Vec::new();
+    for addr in http_addresses(&app_state, &config.server)? {
+        let addr = SocketAddr::new(addr.ip(), config.server.tls_port);
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to bind HTTPS address: {}", addr))?;
+        info!("Listening on https://{}", listener.local_addr()?);
+        listeners.push(web::tls::TlsListener::new(listener, server_config.clone())?);
+    }
+
+    Ok(Some(tokio::spawn(async move {
+        let router = web::create_router(app_state.clone())
+            .layer(axum::Extension(web::tls::ServedOverTls));
+        let mut servers = tokio::task::JoinSet::new();
+        for listener in listeners {
+            let router = router.clone();
+            let cancellation = cancellation.clone();
+            servers.spawn(async move {
+                // `tap_io` gives the listener the `ConnectInfo<SocketAddr>`
+                // the handlers extract; the connection itself is left alone.
+                let listener = axum::serve::ListenerExt::tap_io(listener, |_| {});
+                axum::serve(
+                    listener,
+                    router.into_make_service_with_connect_info::<SocketAddr>(),
+                )
+                .with_graceful_shutdown(cancellation.cancelled_owned())
+                .await
+            });
+        }
+
+        #[cfg(unix)]
+        let mut hangup =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())?;
+        let mut poll = tokio::time::interval(TLS_RELOAD_POLL_INTERVAL);
+        poll.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            #[cfg(unix)]
+            let reload_signal = hangup.recv();
+            #[cfg(not(unix))]
+            let reload_signal = std::future::pending::<Option<()>>();
+            let force = tokio::select! {
+                Some(result) = servers.join_next() => {
+                    cancellation.cancel();
+                    return result?.context("HTTPS server failed");
+                }
+                _ = cancellation.cancelled() => {
+                    while let Some(result) = servers.join_next().await {
+                        result?.context("HTTPS server failed")?;
+                    }
+                    return Ok(());
+                }
+                _ = reload_signal => true,
+                _ = poll.tick() => false,
+            };
+            // Paths changed by a config reload apply here; switching TLS on
+            // or off needs a restart.
+            let Some(tls) = app_state.current_config().server.tls.clone() else {
+                continue;
+            };
+            match certificate.reload_if_changed(&tls, force) {
+                Ok(true) => info!("Reloaded TLS certificate {}", tls.cert_path.display()),
+                Ok(false) => {}
+                Err(error) => warn!(
+                    "Failed to reload TLS certificate; keeping the current one: {:#}",
+                    error
+                ),
+            }
+        }
+    })))
 }
 
 fn server_address(config: &crate::config::ServerConfig) -> anyhow::Result<SocketAddr> {
@@ -152,19 +419,57 @@ fn server_address(config: &crate::config::ServerConfig) -> anyhow::Result<Socket
     Ok(SocketAddr::new(interface, config.port))
 }
 
+/// Addresses the web server listens on: `server.interface`, or each
+/// interface of a `network.interface_selection` list when no
+/// `server.interface` is set.
+fn http_addresses<D: DatabaseManager>(
+    app_state: &AppState<D>,
+    server: &crate::config::ServerConfig,
+) -> anyhow::Result<Vec<SocketAddr>> {
+    let addr = server_address(server)?;
+    if addr.ip().is_unspecified() {
+        if let Some(selected) = app_state
+            .selected_interfaces()
+            .filter(|selected| !selected.is_empty())
+        {
+            return Ok(selected
+                .iter()
+                .map(|interface| SocketAddr::new(interface.ip_address, server.port))
+                .collect());
+        }
+    }
+    Ok(vec![addr])
+}
+
+/// Serve every listener until `cancellation`; one failing stops the rest.
 fn spawn_http_instance<D: DatabaseManager + 'static>(
-    listener: tokio::net::TcpListener,
+    listeners: Vec<tokio::net::TcpListener>,
     state: AppState<D>,
     cancellation: CancellationToken,
 ) -> tokio::task::JoinHandle<std::io::Result<()>> {
     tokio::spawn(async move {
-        axum::serve(
-            listener,
-            web::create_router(state)
-                .into_make_service_with_connect_info::<std::net::SocketAddr>(),
-        )
-        .with_graceful_shutdown(cancellation.cancelled_owned())
-        .await
+        let router = web::create_router(state);
+        let mut servers = tokio::task::JoinSet::new();
+        for listener in listeners {
+            let router = router.clone();
+            let cancellation = cancellation.clone();
+            servers.spawn(async move {
+                axum::serve(
+                    listener,
+                    router.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+                )
+                .with_graceful_shutdown(cancellation.cancelled_owned())
+                .await
+            });
+        }
+        let mut outcome = Ok(());
+        while let Some(result) = servers.join_next().await {
+            if let Err(error) = result.map_err(std::io::Error::other).and_then(|served| served) {
+                cancellation.cancel();
+                outcome = outcome.and(Err(error));
+            }
+        }
+        outcome
     })
 }
 
@@ -182,6 +487,8 @@ pub struct NetworkLifecycleService;
 
 pub struct NetworkTaskHandles {
     pub http: tokio::task::JoinHandle<anyhow::Result<()>>,
+    /// The `server.tls` listener, when configured.
+    pub https: Option<tokio::task::JoinHandle<anyhow::Result<()>>>,
 }
 
 impl NetworkLifecycleService {
@@ -1,12 +1,13 @@
+/// How often the backup task checks whether a scheduled backup is due.
+const BACKUP_SCHEDULE_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Write a backup to `database.backup_dir` and delete all but the newest
+/// `database.backup_keep`. Pre-repair backups are never pruned.
 async fn create_lifecycle_backup<D: DatabaseManager>(
     database: &Arc<D>,
     config: &AppConfig,
 ) -> anyhow::Result<PathBuf> {
-    let database_path = config.get_database_path().with_extension("redb");
-    let backup_dir = database_path
-        .parent()
-        .unwrap_or_else(|| Path::new("."))
-        .join("backups");
+    let backup_dir = config.get_backup_dir();
     tokio::fs::create_dir_all(&backup_dir).await?;
     let filename = format!(
         "vuio-{}-{}.redb",
@@ -20,41 +21,76 @@ async fn create_lifecycle_backup<D: DatabaseManager>(
     let mut backups = Vec::new();
     while let Some(entry) = entries.next_entry().await? {
         let path = entry.path();
-        if path.extension().and_then(|value| value.to_str()) == Some("redb") {
+        let is_lifecycle_backup = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("vuio-") && name.ends_with(".redb"));
+        if is_lifecycle_backup {
             backups.push(path);
         }
     }
+    // Names start with a UTC timestamp, so they sort oldest first.
     backups.sort();
-    let remove_count = backups.len().saturating_sub(3);
+    let remove_count = backups
+        .len()
+        .saturating_sub(config.database.backup_keep.max(1));
     for old in backups.into_iter().take(remove_count) {
         tokio::fs::remove_file(old).await?;
     }
     Ok(destination)
 }
 
-async fn run_with_database<D, Initialize, InitializeFuture, Restore, RestoreFuture>(
+/// Exit status when startup finds the database corrupt beyond repair.
+pub const EXIT_DATABASE_CORRUPT: i32 = 3;
+
+/// Startup stopped because the database health check found critical
+/// corruption that repair could not fix. `main` exits with
+/// [`EXIT_DATABASE_CORRUPT`] on this error.
+#[derive(Debug)]
+pub struct DatabaseCorruptError {
+    pub reason: String,
+}
+
+impl std::fmt::Display for DatabaseCorruptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "the database is corrupt and could not be repaired ({}). Run `vuio rebuild-db` \
+             to rebuild it from the media directories, or restore a backup with \
+             `vuio --restore-backup <file>`",
+            self.reason
+        )
+    }
+}
+
+impl std::error::Error for DatabaseCorruptError {}
+
+/// Process-wide resources created before a database backend is selected.
+struct LaunchContext {
     cli_args: LaunchOptions,
-    initialize_backend: Initialize,
-    restore_backend: Restore,
-) -> anyhow::Result<()>
-where
-    D: DatabaseManager + 'static,
-    Initialize: FnOnce(Arc<AppConfig>) -> InitializeFuture,
-    InitializeFuture: std::future::Future<Output = anyhow::Result<D>>,
-    Restore: FnOnce(Arc<AppConfig>, PathBuf) -> RestoreFuture,
-    RestoreFuture: std::future::Future<Output = anyhow::Result<()>>,
-{
+    log_file_path: Option<PathBuf>,
+    _logging_guard: logging::LoggingGuard,
+    shutdown: ShutdownCoordinator,
+    cancellation: CancellationToken,
+    background_tasks: tokio_util::task::TaskTracker,
+    platform_info: Arc<PlatformInfo>,
+    config_manager: Arc<ConfigManager>,
+    config: Arc<AppConfig>,
+}
+
+async fn prepare_launch(mut cli_args: LaunchOptions) -> anyhow::Result<LaunchContext> {
     // Initialize logging with options
     let log_file_path = cli_args.log_file.as_ref().map(PathBuf::from);
     let logging_guard = logging::init_logging_with_options(
         cli_args.log_level.as_deref(),
         log_file_path.clone(),
         cli_args.debug,
+        cli_args.log_format,
+        cli_args.scan.is_some(),
     )
     .context("Failed to initialize logging")?;
 
     info!("Starting VuIO Server...");
-    let _logging_guard = logging_guard;
 
     let shutdown = ShutdownCoordinator::new();
     let cancellation = shutdown.token();
@@ -74,8 +110,8 @@ where
     // Initialize configuration manager with file watching
     let config_manager = match initialize_config_manager(
         &platform_info,
-        cli_args.config_path,
-        cli_args.config_override,
+        cli_args.config_path.take(),
+        cli_args.config_override.take(),
         cancellation.clone(),
         background_tasks.clone(),
     )
@@ -90,24 +126,99 @@ where
 
     // Get the current configuration
     let config = Arc::new(config_manager.get_config().await);
+    if let crate::config::NetworkInterfaceConfig::Multiple(selectors) =
+        &config.network.interface_selection
+    {
+        platform::select_interfaces(&platform_info.network_interfaces, selectors)
+            .map_err(|error| anyhow::anyhow!("Invalid network.interface_selection: {error}"))?;
+    }
+    crate::natural_sort::set_natural_sort(config.media.natural_sort);
+    if let Some(log_filter) = logging::log_filter() {
+        if let Err(error) = log_filter.apply_configured(config.logging.filter.as_deref()) {
+            warn!("Ignoring logging.filter: {}", error);
+        }
+    }
+
+    Ok(LaunchContext {
+        cli_args,
+        log_file_path,
+        _logging_guard: logging_guard,
+        shutdown,
+        cancellation,
+        background_tasks,
+        platform_info,
+        config_manager,
+        config,
+    })
+}
+
+/// Lifecycle backups copy the on-disk database; the in-memory backend has none.
+fn lifecycle_backups_enabled(config: &AppConfig) -> bool {
+    config.database.backup_enabled && config.database.backend == DatabaseBackendKind::Redb
+}
+
+async fn run_with_database<D, Initialize, InitializeFuture, Restore, RestoreFuture>(
+    launch: LaunchContext,
+    initialize_backend: Initialize,
+    restore_backend: Restore,
+) -> anyhow::Result<()>
+where
+    D: DatabaseManager + 'static,
+    Initialize: FnOnce(Arc<AppConfig>) -> InitializeFuture,
+    InitializeFuture: std::future::Future<Output = anyhow::Result<(D, database::DatabaseHealth)>>,
+    Restore: FnOnce(Arc<AppConfig>, PathBuf) -> RestoreFuture,
+    RestoreFuture: std::future::Future<Output = anyhow::Result<()>>,
+{
+    let LaunchContext {
+        cli_args,
+        log_file_path,
+        _logging_guard,
+        shutdown,
+        cancellation,
+        background_tasks,
+        platform_info,
+        config_manager,
+        config,
+    } = launch;
 
+    // Held from before a restore, so a backup never replaces the database
+    // under another running VuIO process.
+    let _database_lock = lock_database(&config)?;
     if let Some(backup) = cli_args.restore_backup.as_deref() {
         restore_backend(config.clone(), PathBuf::from(backup))
             .await
             .with_context(|| format!("Failed to restore database backup {backup}"))?;
         info!("Restored database backup from {}", backup);
     }
+    if cli_args.rebuild_db {
+        discard_database_for_rebuild(&config)?;
+    }
 
     // Initialize database manager
-    let database = match initialize_backend(config.clone()).await {
-        Ok(db) => Arc::new(db),
+    let (database, database_health) = match initialize_backend(config.clone()).await {
+        Ok((db, health)) => (Arc::new(db), health),
         Err(e) => {
             error!("Failed to initialize database: {}", e);
             return Err(e);
         }
     };
 
-    if config.database.backup_enabled {
+    if let Some(issue) = database_health.unrepaired_critical_issue() {
+        let error = DatabaseCorruptError {
+            reason: issue.description.clone(),
+        };
+        error!("{}", error);
+        return Err(error.into());
+    }
+
+    if cli_args.rebuild_db {
+        return rebuild_database(&config, &database).await;
+    }
+    if let Some(options) = cli_args.scan {
+        return scan_database(&config, &database, options).await;
+    }
+
+    if lifecycle_backups_enabled(&config) {
         match create_lifecycle_backup(&database, &config).await {
             Ok(path) => info!("Created startup database backup at {}", path.display()),
             Err(error) => warn!("Startup database backup failed: {}", error),
@@ -142,6 +253,33 @@ where
         discovery_interval: std::time::Duration::from_secs(config.cast.discovery_interval_seconds),
     };
     let discovery_service = Arc::new(crate::discovery::DiscoveryService::new(discovery_config));
+    let stream_audit = config.logging.stream_audit_file.clone().and_then(|path| {
+        match crate::web::audit::StreamAuditLog::open(path.clone()) {
+            Ok(log) => {
+                info!("Stream audit log: {}", path.display());
+                Some(Arc::new(log))
+            }
+            Err(error) => {
+                warn!(
+                    "Failed to open stream audit log {}: {}",
+                    path.display(),
+                    error
+                );
+                None
+            }
+        }
+    });
+    let service_health = Arc::new(crate::state::ServiceHealth::default());
+    if database_health.is_unrecoverable() {
+        service_health.record_database_failure(
+            database_health
+                .issues
+                .first()
+                .map_or_else(|| "database health check failed".to_owned(), |issue| {
+                    issue.description.clone()
+                }),
+        );
+    }
     let app_state = AppState {
         config: config.clone(),
         live_config: Arc::new(crate::state::LiveConfig::new(config.clone())),
@@ -154,12 +292,17 @@ where
         auth,
         auth_forced: cli_args.auth,
         platform_info: platform_info.clone(),
+        network_interfaces: Arc::default(),
         filesystem_manager,
         content_update_id: Arc::new(std::sync::atomic::AtomicU32::new(1)),
         content_change_notify: Arc::new(tokio::sync::Notify::new()),
         http_rebind_notify: Arc::new(tokio::sync::Notify::new()),
         ssdp_reload_notify: Arc::new(tokio::sync::Notify::new()),
+        playlist_mirror_notify: Arc::new(tokio::sync::Notify::new()),
         web_metrics: Arc::new(crate::web::diagnostics::WebHandlerMetrics::new()),
+        stream_audit,
+        scan_control: Arc::new(crate::scan_progress::ScanControl::new()),
+        health: service_health.clone(),
         runtime_diagnostics: Arc::new(
             crate::platform::diagnostics::SystemDiagnosticsSampler::new(),
         ),
@@ -173,6 +316,9 @@ where
         browse_cache: Arc::new(tokio::sync::Mutex::new(
             crate::runtime_state::BrowseResponseCache::new(),
         )),
+        hidden_files: Arc::default(),
+        active_streams: Arc::default(),
+        container_update_ids: Arc::default(),
         mcp_clients: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
         active_monitors: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
         active_casts: Arc::new(tokio::sync::Mutex::new(
@@ -212,18 +358,36 @@ where
     let backup_state = app_state.clone();
     let backup_cancellation = cancellation.clone();
     services.spawn(async move {
-        let mut interval = tokio::time::interval(std::time::Duration::from_secs(24 * 60 * 60));
-        interval.tick().await;
+        // The schedule is read from the live config on every check, so a
+        // reloaded interval applies without restarting the task. The startup
+        // backup above counts as the first run.
+        let mut last_backup = tokio::time::Instant::now();
+        let mut interval = tokio::time::interval(BACKUP_SCHEDULE_CHECK_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
         loop {
             tokio::select! {
                 _ = backup_cancellation.cancelled() => break,
                 _ = interval.tick() => {
                     let current = backup_state.current_config();
-                    if current.database.backup_enabled {
-                        if let Err(error) = create_lifecycle_backup(&backup_database, &current).await {
-                            warn!("Periodic database backup failed: {}", error);
-                        }
+                    let hours = current.database.backup_interval_hours;
+                    if !lifecycle_backups_enabled(&current)
+                        || hours == 0
+                        || last_backup.elapsed()
+                            < std::time::Duration::from_secs(hours.saturating_mul(60 * 60))
+                    {
+                        continue;
                     }
+                    // A backup taken mid-scan holds a half-updated index; try
+                    // again on a later check.
+                    if backup_state.scan_control.is_running() {
+                        debug!("Scheduled database backup deferred: a scan is in progress");
+                        continue;
+                    }
+                    match create_lifecycle_backup(&backup_database, &current).await {
+                        Ok(path) => info!("Created scheduled database backup at {}", path.display()),
+                        Err(error) => warn!("Scheduled database backup failed: {}", error),
+                    }
+                    last_backup = tokio::time::Instant::now();
                 }
             }
         }
@@ -274,11 +438,14 @@ where
         ("discovery service", Ok(()))
     });
 
-    // Start file system monitoring
+    // Start file system monitoring. Its events are held back until the
+    // initial scan below has finished.
+    let (initial_scan_finished, initial_scan) = tokio::sync::watch::channel(false);
     match start_file_monitoring(
         file_watcher.clone(),
         app_state.clone(),
         cancellation.clone(),
+        initial_scan,
     )
     .await
     {
@@ -297,21 +464,83 @@ where
         }
     }
 
+    // Start the HTTP server before the initial scan so its progress can be
+    // followed, and the scan cancelled, through the admin API.
+    let network_handles =
+        match start_http_server_task(app_state.clone(), cancellation.clone()).await {
+            Ok(handles) => handles,
+            Err(e) => {
+                error!("Failed to start HTTP server: {}", e);
+                return Err(e);
+            }
+        };
+    services.spawn(async move {
+        let result = network_handles
+            .http
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|result| result);
+        ("HTTP", result)
+    });
+    if let Some(https) = network_handles.https {
+        services.spawn(async move {
+            let result = https
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|result| result);
+            ("HTTPS", result)
+        });
+    }
+    service_health.mark_serving();
+
     // Scan only after the watcher is active. This closes the startup blind
     // window: a download that lands while the scan is running is either found
-    // by the scan or delivered by the watcher (and duplicate upserts are safe).
-    if let Err(e) = perform_initial_media_scan(&config, &database).await {
-        error!("Failed to perform initial media scan: {}", e);
-        return Err(e);
-    }
-    refresh_unavailable_roots(&app_state).await?;
+    // by the scan or delivered by the watcher. Watcher events are replayed
+    // only once the scan is done, so they always win over the scan's batches.
+    if config.media.dry_run {
+        // Report what the startup scan would change and leave the index as it is.
+        warn!("media.dry_run is on: scans report changes without writing them");
+        let preview = preview_media_scan(&config, &database).await;
+        initial_scan_finished.send_replace(true);
+        match preview {
+            Ok(preview) => {
+                for line in preview.to_string().lines() {
+                    info!("{}", line);
+                }
+            }
+            Err(e) => warn!("Failed to preview the initial media scan: {:#}", e),
+        }
+    } else {
+        let scan_result =
+            perform_initial_media_scan(
+                &config,
+                &database,
+                &app_state.scan_control,
+                database_health.restored_from_backup,
+            )
+            .await;
+        initial_scan_finished.send_replace(true);
+        if let Err(e) = scan_result {
+            error!("Failed to perform initial media scan: {}", e);
+            return Err(e);
+        }
+        refresh_unavailable_roots(&app_state).await?;
 
-    // Perform initial playlist file scan after media scan so referenced files exist.
-    if let Err(e) = perform_initial_playlist_scan(&config, &database).await {
-        // Log warning but don't fail startup - playlists are not critical
-        warn!("Failed to scan playlist files: {}", e);
+        // Perform initial playlist file scan after media scan so referenced files exist.
+        if let Err(e) = perform_initial_playlist_scan(&config, &database).await {
+            // Log warning but don't fail startup - playlists are not critical
+            warn!("Failed to scan playlist files: {}", e);
+        }
     }
 
+    // Mirror after the playlist scan so imported playlists are included.
+    let mirror_state = app_state.clone();
+    let mirror_cancellation = cancellation.clone();
+    services.spawn(async move {
+        run_playlist_mirror(mirror_state, mirror_cancellation).await;
+        ("playlist mirror", Ok(()))
+    });
+
     // Start runtime platform adaptation services
     let adaptation_handle = start_platform_adaptation(
         platform_info.clone(),
@@ -352,33 +581,26 @@ where
         ("SSDP", result)
     });
 
-    // Start the HTTP server as a background task
-    let network_handles =
-        match start_http_server_task(app_state.clone(), cancellation.clone()).await {
-            Ok(handles) => handles,
-            Err(e) => {
-                error!("Failed to start HTTP server: {}", e);
-                return Err(e);
-            }
-        };
+    // Follow network interface changes so SSDP keeps announcing a live address.
+    let monitor_state = app_state.clone();
+    let monitor_cancellation = cancellation.clone();
     services.spawn(async move {
-        let result = network_handles
-            .http
-            .await
-            .map_err(anyhow::Error::from)
-            .and_then(|result| result);
-        ("HTTP", result)
+        run_interface_monitor(monitor_state, monitor_cancellation).await;
+        ("interface monitor", Ok(()))
     });
+
     // Determine if console logging is verbose
-    let is_rust_log_set = std::env::var("RUST_LOG").is_ok();
+    let is_filter_set = crate::logging::explicit_filter_from_env().is_some();
     let in_docker = AppConfig::is_running_in_docker();
     let console_is_verbose =
-        cli_args.debug || is_rust_log_set || cli_args.log_level.is_some() || in_docker;
+        cli_args.debug || is_filter_set || cli_args.log_level.is_some() || in_docker;
 
     if !console_is_verbose {
         let display_ip =
             if config.server.interface == "0.0.0.0" || config.server.interface.is_empty() {
-                if let Some(primary) = platform_info.get_primary_interface() {
+                if let Some(primary) = platform_info
+                    .get_primary_interface_in(config.network.preferred_subnet.as_deref())
+                {
                     primary.ip_address.to_string()
                 } else {
                     "127.0.0.1".to_string()
@@ -386,9 +608,11 @@ where
             } else {
                 config.server.interface.clone()
             };
-        let web_url = format!("http://{}:{}", display_ip, config.server.port);
-        let db_path = config.get_database_path().with_extension("redb");
-
+        let web_url = format!(
+            "http://{}:{}",
+            display_ip,
+            app_state.current_config().server.port
+        );
         fn tail_with_ellipsis(value: &str, max_chars: usize) -> String {
             let count = value.chars().count();
             if count <= max_chars {
@@ -404,7 +628,14 @@ where
         let display_name = tail_with_ellipsis(&config.server.name, 41);
         let display_url = tail_with_ellipsis(&web_url, 41);
 
-        let db_path_str = db_path.to_string_lossy().to_string();
+        let db_path_str = match config.database.backend {
+            DatabaseBackendKind::Redb => config
+                .get_database_path()
+                .with_extension("redb")
+                .to_string_lossy()
+                .to_string(),
+            DatabaseBackendKind::Memory => "(in memory)".to_string(),
+        };
         let display_db_path = tail_with_ellipsis(&db_path_str, 41);
 
         println!("┌────────────────────────────────────────────────────────┐");
@@ -489,8 +720,11 @@ where
         );
         services.abort_all();
     }
+    if let Some(stream_audit) = &app_state.stream_audit {
+        stream_audit.flush();
+    }
 
-    if app_state.current_config().database.backup_enabled {
+    if lifecycle_backups_enabled(&app_state.current_config()) {
         match create_lifecycle_backup(&database, &app_state.current_config()).await {
             Ok(path) => info!("Created shutdown database backup at {}", path.display()),
             Err(error) => warn!("Shutdown database backup failed: {}", error),
@@ -508,16 +742,108 @@ where
     }
 }
 
+/// `vuio rebuild-db`: index every configured directory into the cleared
+/// database and print what was found. No services are started.
+async fn rebuild_database<D: DatabaseManager + 'static>(
+    config: &AppConfig,
+    database: &Arc<D>,
+) -> anyhow::Result<()> {
+    let started = std::time::Instant::now();
+    let summary = rebuild_media_index(config, database).await?;
+    println!("Database rebuilt in {:.1?}", started.elapsed());
+    println!(
+        "  Directories scanned: {} ({} missing)",
+        summary.directories_scanned, summary.directories_missing
+    );
+    println!("  Files scanned: {}", summary.files_scanned);
+    println!("  Files added: {}", summary.files_added);
+    println!("  Playlists imported: {}", summary.playlists_imported);
+    println!("  Errors: {}", summary.errors);
+    Ok(())
+}
+
+/// `vuio scan`: refresh the database once, print what changed, and exit.
+/// With `--dry-run` or `media.dry_run`, print what would change instead.
+/// Fails when the scan reported errors.
+async fn scan_database<D: DatabaseManager + 'static>(
+    config: &AppConfig,
+    database: &Arc<D>,
+    options: ScanOptions,
+) -> anyhow::Result<()> {
+    if config.database.backend == DatabaseBackendKind::Memory {
+        anyhow::bail!("scan needs the redb database backend; the memory backend keeps nothing");
+    }
+    if options.dry_run || config.media.dry_run {
+        let preview = preview_media_scan(config, database).await?;
+        match options.format {
+            ScanReportFormat::Json => println!("{}", serde_json::to_string_pretty(&preview)?),
+            ScanReportFormat::Text => println!("{preview}"),
+        }
+        anyhow::ensure!(
+            preview.errors == 0,
+            "media scan dry run finished with {} error(s)",
+            preview.errors
+        );
+        return Ok(());
+    }
+    let started = std::time::Instant::now();
+    let summary = scan_media_index(config, database).await?;
+    match options.format {
+        ScanReportFormat::Json => println!("{}", serde_json::to_string_pretty(&summary)?),
+        ScanReportFormat::Text => {
+            println!("Media scan finished in {:.1?}", started.elapsed());
+            println!(
+                "  Directories scanned: {} ({} missing)",
+                summary.directories_scanned, summary.directories_missing
+            );
+            println!("  Files scanned: {}", summary.files_scanned);
+            println!("  Files added: {}", summary.files_added);
+            println!("  Files updated: {}", summary.files_updated);
+            println!("  Files removed: {}", summary.files_removed);
+            println!("  Playlists imported: {}", summary.playlists_imported);
+            println!("  Errors: {}", summary.errors);
+        }
+    }
+    anyhow::ensure!(
+        summary.errors == 0,
+        "media scan finished with {} error(s)",
+        summary.errors
+    );
+    Ok(())
+}
+
 async fn run_application(cli_args: LaunchOptions) -> anyhow::Result<()> {
-    run_with_database::<database::redb::RedbDatabase, _, _, _, _>(
-        cli_args,
-        |config| async move { initialize_database(&config).await },
-        |config, backup| async move {
-            let database_path = config.get_database_path().with_extension("redb");
-            database::redb::RedbDatabase::restore_backup_file(backup, database_path).await
-        },
-    )
-    .await
+    let launch = prepare_launch(cli_args).await?;
+    info!("Database backend: {:?}", launch.config.database.backend);
+    match launch.config.database.backend {
+        DatabaseBackendKind::Redb => {
+            run_with_database::<database::redb::RedbDatabase, _, _, _, _>(
+                launch,
+                |config| async move { initialize_database(&config).await },
+                |config, backup| async move {
+                    let database_path = config.get_database_path().with_extension("redb");
+                    database::redb::RedbDatabase::restore_backup_file(backup, database_path).await
+                },
+            )
+            .await
+        }
+        DatabaseBackendKind::Memory => {
+            run_with_database::<database::memory::InMemoryDatabase, _, _, _, _>(
+                launch,
+                |_| async move {
+                    warn!("Using the in-memory database backend; the index is rebuilt on every start");
+                    let database = database::memory::InMemoryDatabase::new();
+                    database.initialize().await?;
+                    let health = database.check_and_repair().await?;
+                    Ok((database, health))
+                },
+                |_, _| async move {
+                    anyhow::bail!("the in-memory database backend cannot restore a backup")
+                },
+            )
+            .await
+        }
+    }
 }
 
 /// Top-level owner of VuIO startup, services, and shutdown.
@@ -525,9 +851,18 @@ pub struct ApplicationRunner;
 
 impl ApplicationRunner {
     pub async fn run(options: LaunchOptions) -> anyhow::Result<()> {
+        if options.check_config {
+            return check_config(options.config_path.as_deref());
+        }
         if options.update {
             return update_binary().await;
         }
+        if options.rebuild_db && options.restore_backup.is_some() {
+            anyhow::bail!("rebuild-db cannot be combined with --restore-backup");
+        }
+        if options.scan.is_some() && options.restore_backup.is_some() {
+            anyhow::bail!("scan cannot be combined with --restore-backup");
+        }
         run_application(options).await
     }
 }
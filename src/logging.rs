@@ -2,13 +2,176 @@ use crate::platform::PlatformError;
 use std::io::Write;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
 use tracing::info;
-use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+use tracing_subscriber::{fmt, prelude::*, reload, EnvFilter, Registry};
 
 const MAX_LOG_BYTES: u64 = 10 * 1024 * 1024;
 const LOG_RETENTION: usize = 3;
 const LOG_QUEUE_CAPACITY: usize = 8_192;
 static DROPPED_FILE_LOGS: AtomicU64 = AtomicU64::new(0);
+static LOG_FILTER: OnceLock<LogFilter> = OnceLock::new();
+
+/// Stable tracing targets, one per component, for filters such as
+/// `info,vuio::ssdp=debug`. SSDP and the watcher log under their module
+/// paths; browse handling and storage name their target explicitly.
+pub mod targets {
+    pub const SSDP: &str = "vuio::ssdp";
+    pub const WATCHER: &str = "vuio::watcher";
+    pub const BROWSE: &str = "vuio::web::browse";
+    pub const DB: &str = "vuio::db";
+}
+
+/// Line format of the console and file logs, chosen with `--log-format` or
+/// `VUIO_LOG_FORMAT`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable lines.
+    #[default]
+    Text,
+    /// One JSON object per line with `timestamp`, `level`, `target`,
+    /// `threadId`, `threadName` and `message` fields, for log collectors.
+    Json,
+}
+
+impl std::str::FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            other => Err(format!(
+                "unknown log format '{other}' (expected text or json)"
+            )),
+        }
+    }
+}
+
+impl LogFormat {
+    /// The format named by `VUIO_LOG_FORMAT`, if set.
+    pub fn from_env() -> Result<Option<Self>, PlatformError> {
+        parse_env_choice("VUIO_LOG_FORMAT")
+    }
+}
+
+/// When the log file is rotated, chosen with `VUIO_LOG_ROTATION`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogRotation {
+    /// When the file reaches 10 MiB.
+    #[default]
+    Size,
+    /// At the first record of each local day, and at 10 MiB.
+    Daily,
+}
+
+impl std::str::FromStr for LogRotation {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "size" => Ok(Self::Size),
+            "daily" => Ok(Self::Daily),
+            other => Err(format!(
+                "unknown log rotation '{other}' (expected size or daily)"
+            )),
+        }
+    }
+}
+
+impl LogRotation {
+    /// The rotation named by `VUIO_LOG_ROTATION`, if set.
+    pub fn from_env() -> Result<Option<Self>, PlatformError> {
+        parse_env_choice("VUIO_LOG_ROTATION")
+    }
+}
+
+fn parse_env_choice<T: std::str::FromStr<Err = String>>(
+    name: &str,
+) -> Result<Option<T>, PlatformError> {
+    std::env::var(name)
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .map(|value| value.parse())
+        .transpose()
+        .map_err(|reason: String| {
+            PlatformError::Configuration(crate::platform::ConfigurationError::ValidationFailed {
+                reason: format!("Invalid {name}: {reason}"),
+            })
+        })
+}
+
+/// The env-filter shared by the log file and the verbose console, which can be
+/// replaced at runtime. The quiet console keeps its `warn` level.
+pub struct LogFilter {
+    current: Mutex<String>,
+    /// The filter chosen at startup, restored when `logging.filter` is unset.
+    initial: String,
+    handles: Vec<reload::Handle<EnvFilter, Registry>>,
+    /// `VUIO_LOG`, `RUST_LOG`, `--log-level` or `--debug` chose the filter, so
+    /// `logging.filter` does not replace it.
+    overridden: bool,
+}
+
+impl LogFilter {
+    fn new(filter: &str, overridden: bool) -> Self {
+        Self {
+            current: Mutex::new(filter.to_owned()),
+            initial: filter.to_owned(),
+            handles: Vec::new(),
+            overridden,
+        }
+    }
+
+    /// A per-layer filter that follows every later [`LogFilter::set`].
+    fn layer_filter(&mut self) -> reload::Layer<EnvFilter, Registry> {
+        let filter = EnvFilter::try_new(self.current()).unwrap_or_else(|_| EnvFilter::new("info"));
+        let (layer, handle) = reload::Layer::new(filter);
+        self.handles.push(handle);
+        layer
+    }
+
+    pub fn current(&self) -> String {
+        self.current
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .clone()
+    }
+
+    /// Replace the filter, e.g. with `info,vuio::ssdp=debug`. An invalid
+    /// filter is rejected and the previous one stays in effect.
+    pub fn set(&self, filter: &str) -> anyhow::Result<()> {
+        let filter = filter.trim();
+        if filter.is_empty() {
+            anyhow::bail!("log filter cannot be empty");
+        }
+        EnvFilter::try_new(filter)?;
+        let mut current = self
+            .current
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        for handle in &self.handles {
+            handle.reload(EnvFilter::new(filter))?;
+        }
+        *current = filter.to_owned();
+        Ok(())
+    }
+
+    /// Apply `logging.filter`, or restore the startup filter when it is
+    /// unset, unless the command line, `VUIO_LOG` or `RUST_LOG` chose the
+    /// filter.
+    pub fn apply_configured(&self, filter: Option<&str>) -> anyhow::Result<()> {
+        if self.overridden {
+            return Ok(());
+        }
+        self.set(filter.unwrap_or(&self.initial))
+    }
+}
+
+/// The process log filter, once logging has been initialized.
+pub fn log_filter() -> Option<&'static LogFilter> {
+    LOG_FILTER.get()
+}
 
 enum LogCommand {
     Write(Vec<u8>),
@@ -105,22 +268,39 @@ struct RotatingFile {
     path: PathBuf,
     file: Option<std::fs::File>,
     bytes: u64,
+    rotation: LogRotation,
+    /// The local day the current file was last written on.
+    day: chrono::NaiveDate,
 }
 
 impl RotatingFile {
-    fn open(path: PathBuf) -> std::io::Result<Self> {
+    fn open(path: PathBuf, rotation: LogRotation) -> std::io::Result<Self> {
         let file = std::fs::OpenOptions::new()
             .create(true)
             .append(true)
             .open(&path)?;
-        let bytes = file.metadata()?.len();
+        let metadata = file.metadata()?;
+        // A file left by an earlier run is rotated on the first write of a
+        // later day.
+        let day = metadata
+            .modified()
+            .map(|modified| chrono::DateTime::<chrono::Local>::from(modified).date_naive())
+            .unwrap_or_else(|_| chrono::Local::now().date_naive());
         Ok(Self {
             path,
             file: Some(file),
-            bytes,
+            bytes: metadata.len(),
+            rotation,
+            day,
         })
     }
 
+    fn rotation_due(&self, incoming: usize, today: chrono::NaiveDate) -> bool {
+        self.bytes > 0
+            && (self.bytes.saturating_add(incoming as u64) > MAX_LOG_BYTES
+                || (self.rotation == LogRotation::Daily && today != self.day))
+    }
+
     fn rotate(&mut self) -> std::io::Result<()> {
         if let Some(file) = self.file.take() {
             file.sync_all()?;
@@ -152,9 +332,11 @@ impl RotatingFile {
 
 impl std::io::Write for RotatingFile {
     fn write(&mut self, buffer: &[u8]) -> std::io::Result<usize> {
-        if self.bytes > 0 && self.bytes.saturating_add(buffer.len() as u64) > MAX_LOG_BYTES {
+        let today = chrono::Local::now().date_naive();
+        if self.rotation_due(buffer.len(), today) {
             self.rotate()?;
         }
+        self.day = today;
         let written = self
             .file
             .as_mut()
@@ -172,78 +354,167 @@ impl std::io::Write for RotatingFile {
     }
 }
 
+/// A per-target filter such as `info,vuio::watcher=debug` from `VUIO_LOG`,
+/// or from `RUST_LOG` when `VUIO_LOG` is unset or empty.
+pub fn explicit_filter_from_env() -> Option<String> {
+    ["VUIO_LOG", "RUST_LOG"]
+        .into_iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|filter| !filter.trim().is_empty())
+}
+
+/// The filter logging starts with. An explicit filter wins over
+/// `--log-level`, which wins over `--debug`, a shorthand for `debug`.
+fn startup_filter(explicit: Option<String>, log_level: Option<&str>, debug: bool) -> String {
+    explicit.unwrap_or_else(|| {
+        log_level
+            .unwrap_or(if debug { "debug" } else { "info" })
+            .to_owned()
+    })
+}
+
 /// Initialize logging with platform-specific configuration.
 pub fn init_logging() -> Result<LoggingGuard, PlatformError> {
-    init_logging_with_options(None, None, false)
+    init_logging_with_options(None, None, false, None, false)
 }
 
 /// Initialize logging with debug output enabled.
 pub fn init_logging_with_debug(debug: bool) -> Result<LoggingGuard, PlatformError> {
     let log_level = if debug { "debug" } else { "info" };
-    init_logging_with_options(Some(log_level), None, debug)
+    init_logging_with_options(Some(log_level), None, debug, None, false)
 }
 
-/// Initialize console and rolling application-file logging.
+/// A JSON formatter with the fields log collectors expect, flattened so
+/// `message` sits next to `level` and `target`.
+fn json_layer<W>(make_writer: W) -> impl tracing_subscriber::Layer<Registry> + Send + Sync
+where
+    W: for<'writer> fmt::MakeWriter<'writer> + Send + Sync + 'static,
+{
+    fmt::layer()
+        .json()
+        .flatten_event(true)
+        .with_current_span(false)
+        .with_span_list(false)
+        .with_target(true)
+        .with_thread_ids(true)
+        .with_thread_names(true)
+        .with_timer(tracing_subscriber::fmt::time::LocalTime::rfc_3339())
+        .with_ansi(false)
+        .with_writer(make_writer)
+}
+
+/// Initialize console and rolling application-file logging. Without a
+/// `format`, `VUIO_LOG_FORMAT` chooses it, and text is the default.
+///
+/// The log file is written unless `VUIO_LOG_FILE_ENABLED=false` and no
+/// `log_file` is given; `VUIO_LOG_ROTATION` picks size or daily rotation.
+/// Without a log file, because it is disabled or cannot be opened, the
+/// console carries the full filter instead of only warnings. With
+/// `console_to_stderr` the console log goes to stderr, leaving stdout to a
+/// command's report.
 pub fn init_logging_with_options(
     log_level: Option<&str>,
     log_file: Option<PathBuf>,
     debug: bool,
+    format: Option<LogFormat>,
+    console_to_stderr: bool,
 ) -> Result<LoggingGuard, PlatformError> {
-    let is_rust_log_set = std::env::var("RUST_LOG").is_ok();
+    let format = match format {
+        Some(format) => format,
+        None => LogFormat::from_env()?.unwrap_or_default(),
+    };
+    let explicit_filter = explicit_filter_from_env();
     let in_docker = crate::config::AppConfig::is_running_in_docker();
-    let console_should_be_verbose = debug || is_rust_log_set || log_level.is_some() || in_docker;
-    let console_level = if console_should_be_verbose {
-        log_level.unwrap_or(if debug { "debug" } else { "info" })
+    let rotation = LogRotation::from_env()?.unwrap_or_default();
+    let file_enabled = log_file.is_some()
+        || std::env::var("VUIO_LOG_FILE_ENABLED")
+            .map(|v| v.to_lowercase() == "true")
+            .unwrap_or(true);
+    let resolved_log_file =
+        log_file.unwrap_or_else(crate::config::AppConfig::get_platform_log_file_path);
+    let log_file = if file_enabled {
+        if let Some(parent) = resolved_log_file.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        match RotatingFile::open(resolved_log_file.clone(), rotation) {
+            Ok(file) => Some(file),
+            Err(error) => {
+                eprintln!(
+                    "Warning: Failed to open log file {}: {}; logging to the console only",
+                    resolved_log_file.display(),
+                    error
+                );
+                None
+            }
+        }
     } else {
-        "warn"
+        None
     };
+    let console_should_be_verbose = debug
+        || explicit_filter.is_some()
+        || log_level.is_some()
+        || in_docker
+        || log_file.is_none();
 
-    let console_filter = EnvFilter::try_from_default_env()
-        .or_else(|_| EnvFilter::try_new(console_level))
-        .map_err(|error| {
-            PlatformError::Configuration(crate::platform::ConfigurationError::ValidationFailed {
-                reason: format!("Invalid console log level: {error}"),
-            })
-        })?;
+    let overridden = explicit_filter.is_some() || log_level.is_some() || debug;
+    let filter = startup_filter(explicit_filter, log_level, debug);
+    EnvFilter::try_new(&filter).map_err(|error| {
+        PlatformError::Configuration(crate::platform::ConfigurationError::ValidationFailed {
+            reason: format!("Invalid log filter: {error}"),
+        })
+    })?;
+    let mut log_filter = LogFilter::new(&filter, overridden);
 
     use tracing_subscriber::Layer;
-    let console_layer: Box<dyn Layer<tracing_subscriber::Registry> + Send + Sync> =
+    let console_writer = || {
+        if console_to_stderr {
+            fmt::writer::BoxMakeWriter::new(std::io::stderr)
+        } else {
+            fmt::writer::BoxMakeWriter::new(std::io::stdout)
+        }
+    };
+    let console_layer: Box<dyn Layer<Registry> + Send + Sync> = if format == LogFormat::Json {
         if console_should_be_verbose {
-            Box::new(
-                fmt::layer()
-                    .with_target(true)
-                    .with_thread_ids(true)
-                    .with_file(true)
-                    .with_line_number(true)
-                    .with_timer(tracing_subscriber::fmt::time::LocalTime::rfc_3339())
-                    .with_filter(console_filter),
-            )
+            Box::new(json_layer(console_writer()).with_filter(log_filter.layer_filter()))
         } else {
-            Box::new(
-                fmt::layer()
-                    .with_target(false)
-                    .with_thread_ids(false)
-                    .with_file(false)
-                    .with_line_number(false)
-                    .without_time()
-                    .with_filter(console_filter),
-            )
-        };
-
-    let resolved_log_file =
-        log_file.unwrap_or_else(crate::config::AppConfig::get_platform_log_file_path);
-    if let Some(parent) = resolved_log_file.parent() {
-        let _ = std::fs::create_dir_all(parent);
-    }
+            Box::new(json_layer(console_writer()).with_filter(EnvFilter::new("warn")))
+        }
+    } else if console_should_be_verbose {
+        Box::new(
+            fmt::layer()
+                .with_target(true)
+                .with_thread_ids(true)
+                .with_file(true)
+                .with_line_number(true)
+                .with_timer(tracing_subscriber::fmt::time::LocalTime::rfc_3339())
+                .with_writer(console_writer())
+                .with_filter(log_filter.layer_filter()),
+        )
+    } else {
+        Box::new(
+            fmt::layer()
+                .with_target(false)
+                .with_thread_ids(false)
+                .with_file(false)
+                .with_line_number(false)
+                .without_time()
+                .with_writer(console_writer())
+                .with_filter(EnvFilter::new("warn")),
+        )
+    };
 
-    let (file_layer, guard) = match RotatingFile::open(resolved_log_file.clone()) {
-        Ok(file) => {
+    let file_description = match (&log_file, rotation) {
+        (Some(_), LogRotation::Size) => resolved_log_file.display().to_string(),
+        (Some(_), LogRotation::Daily) => format!("{} (daily)", resolved_log_file.display()),
+        (None, _) => "none".to_owned(),
+    };
+    let (file_layer, guard) = match log_file {
+        Some(file) => {
             let (writer, guard) = non_blocking_file(file);
-            let file_level = if debug { "debug" } else { "info" };
-            let file_filter =
-                EnvFilter::try_new(file_level).unwrap_or_else(|_| EnvFilter::new("info"));
-            (
-                Some(
+            let file_layer: Box<dyn Layer<Registry> + Send + Sync> = if format == LogFormat::Json {
+                Box::new(json_layer(move || writer.clone()).with_filter(log_filter.layer_filter()))
+            } else {
+                Box::new(
                     fmt::layer()
                         .with_target(true)
                         .with_thread_ids(true)
@@ -252,36 +523,41 @@ pub fn init_logging_with_options(
                         .with_timer(tracing_subscriber::fmt::time::LocalTime::rfc_3339())
                         .with_ansi(false)
                         .with_writer(move || writer.clone())
-                        .with_filter(file_filter),
-                ),
-                guard,
-            )
-        }
-        Err(error) => {
-            eprintln!(
-                "Warning: Failed to open log file {}: {}",
-                resolved_log_file.display(),
-                error
-            );
-            (
-                None,
-                LoggingGuard {
-                    sender: None,
-                    worker: None,
-                },
-            )
+                        .with_filter(log_filter.layer_filter()),
+                )
+            };
+            (Some(file_layer), guard)
         }
+        None => (
+            None,
+            LoggingGuard {
+                sender: None,
+                worker: None,
+            },
+        ),
     };
 
-    let subscriber = tracing_subscriber::registry()
-        .with(console_layer)
-        .with(file_layer);
-    let _ = subscriber.try_init();
+    let layers = std::iter::once(console_layer)
+        .chain(file_layer)
+        .collect::<Vec<_>>();
+    if tracing_subscriber::registry()
+        .with(layers)
+        .try_init()
+        .is_ok()
+    {
+        let _ = LOG_FILTER.set(log_filter);
+    }
 
     info!(
-        "Logging initialized. Console level: {}. File log: {}",
-        console_level,
-        resolved_log_file.display()
+        "Logging initialized. Filter: {}{}. Format: {:?}. File log: {}",
+        filter,
+        if console_should_be_verbose {
+            ""
+        } else {
+            " (console: warn)"
+        },
+        format,
+        file_description
     );
     Ok(guard)
 }
@@ -292,6 +568,152 @@ mod tests {
 
     #[test]
     fn logging_initialization_accepts_a_valid_level() {
-        assert!(init_logging_with_options(Some("debug"), None, true).is_ok());
+        assert!(init_logging_with_options(Some("debug"), None, true, None, false).is_ok());
+    }
+
+    #[test]
+    fn changing_the_filter_at_runtime_enables_one_component() {
+        #[derive(Clone, Default)]
+        struct Captured(std::sync::Arc<Mutex<Vec<u8>>>);
+        impl Write for Captured {
+            fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(bytes);
+                Ok(bytes.len())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let mut log_filter = LogFilter::new("info", false);
+        let layer = fmt::layer()
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .with_filter(log_filter.layer_filter());
+        let subscriber = tracing_subscriber::registry().with(layer);
+        let logged = || String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let emit = |round: u32| {
+                tracing::debug!(target: targets::SSDP, "ssdp debug {round}");
+                tracing::debug!(target: targets::DB, "db debug {round}");
+                tracing::info!(target: targets::WATCHER, "watcher info {round}");
+            };
+            emit(1);
+            assert!(log_filter.set("info,vuio::ssdp=debug").is_ok());
+            emit(2);
+            assert!(log_filter.set("vuio::ssdp=bogus").is_err());
+            assert!(log_filter.set(" ").is_err());
+            assert_eq!(log_filter.current(), "info,vuio::ssdp=debug");
+            emit(3);
+            assert!(log_filter.apply_configured(None).is_ok());
+            emit(4);
+        });
+
+        let logged = logged();
+        assert!(!logged.contains("ssdp debug 1"));
+        assert!(logged.contains("ssdp debug 2"));
+        assert!(logged.contains("ssdp debug 3"));
+        assert!(!logged.contains("ssdp debug 4"));
+        assert!(!logged.contains("db debug"));
+        for round in 1..=4 {
+            assert!(logged.contains(&format!("watcher info {round}")));
+        }
+        assert_eq!(log_filter.current(), "info");
+    }
+
+    #[test]
+    fn json_lines_carry_the_fields_log_collectors_expect() {
+        #[derive(Clone, Default)]
+        struct Captured(std::sync::Arc<Mutex<Vec<u8>>>);
+        impl Write for Captured {
+            fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(bytes);
+                Ok(bytes.len())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::registry().with(json_layer(move || writer.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(target: targets::SSDP, "announce \"failed\"");
+        });
+
+        let logged = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        let line: serde_json::Value = serde_json::from_str(logged.trim()).unwrap();
+        assert_eq!(line["level"], "WARN");
+        assert_eq!(line["target"], targets::SSDP);
+        assert_eq!(line["message"], "announce \"failed\"");
+        assert!(line["threadId"].is_string());
+        assert!(line["timestamp"]
+            .as_str()
+            .is_some_and(|time| time.contains('T')));
+        assert_eq!(logged.lines().count(), 1);
+    }
+
+    #[test]
+    fn daily_rotation_starts_a_new_file_on_a_new_day() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("vuio.log");
+        let mut file = RotatingFile::open(path.clone(), LogRotation::Daily).unwrap();
+        file.write_all(b"today\n").unwrap();
+        let today = chrono::Local::now().date_naive();
+        assert!(!file.rotation_due(6, today));
+
+        file.day = today.pred_opt().unwrap();
+        file.write_all(b"tomorrow\n").unwrap();
+
+        assert_eq!(
+            std::fs::read(path.with_extension("log.1")).unwrap(),
+            b"today\n"
+        );
+        assert_eq!(std::fs::read(&path).unwrap(), b"tomorrow\n");
+    }
+
+    #[test]
+    fn size_rotation_ignores_the_date() {
+        let temp = tempfile::tempdir().unwrap();
+        let mut file = RotatingFile::open(temp.path().join("vuio.log"), LogRotation::Size).unwrap();
+        file.write_all(b"line\n").unwrap();
+        let tomorrow = chrono::Local::now().date_naive().succ_opt().unwrap();
+
+        assert!(!file.rotation_due(5, tomorrow));
+        assert!(file.rotation_due(MAX_LOG_BYTES as usize, tomorrow));
+    }
+
+    #[test]
+    fn log_formats_parse_case_insensitively() {
+        assert_eq!("JSON".parse::<LogFormat>(), Ok(LogFormat::Json));
+        assert_eq!(" text ".parse::<LogFormat>(), Ok(LogFormat::Text));
+        assert!("logfmt".parse::<LogFormat>().is_err());
+        assert_eq!("Daily".parse::<LogRotation>(), Ok(LogRotation::Daily));
+        assert!("hourly".parse::<LogRotation>().is_err());
+    }
+
+    #[test]
+    fn an_explicit_filter_wins_over_the_command_line() {
+        let filter = "info,vuio::watcher=debug";
+        assert_eq!(
+            startup_filter(Some(filter.to_owned()), Some("warn"), true),
+            filter
+        );
+        assert_eq!(startup_filter(None, Some("warn"), true), "warn");
+        assert_eq!(startup_filter(None, None, true), "debug");
+        assert_eq!(startup_filter(None, None, false), "info");
+    }
+
+    #[test]
+    fn the_command_line_filter_wins_over_the_configured_one() {
+        let log_filter = LogFilter::new("debug", true);
+        assert!(log_filter
+            .apply_configured(Some("info,vuio::db=trace"))
+            .is_ok());
+        assert_eq!(log_filter.current(), "debug");
     }
 }
@@ -1,5 +1,12 @@
 #[tokio::main(flavor = "multi_thread")]
 async fn main() -> anyhow::Result<()> {
     let options = vuio::lifecycle::CliService::parse_env()?;
-    vuio::lifecycle::ApplicationRunner::run(options).await
+    let result = vuio::lifecycle::ApplicationRunner::run(options).await;
+    if let Err(error) = &result {
+        if let Some(corrupt) = error.downcast_ref::<vuio::lifecycle::DatabaseCorruptError>() {
+            eprintln!("Error: {corrupt}");
+            std::process::exit(vuio::lifecycle::EXIT_DATABASE_CORRUPT);
+        }
+    }
+    result
 }
@@ -2,16 +2,55 @@ use anyhow::Result;
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::SystemTime;
+use std::time::{Duration, Instant, SystemTime};
 use tracing::{debug, info, warn};
 
-use crate::config::{AppConfig, MonitoredDirectoryConfig};
+use crate::config::{AppConfig, ExcludeMatcher, MonitoredDirectoryConfig, WatchMode};
 use crate::database::{redb::RedbDatabase, DatabaseManager, FileFingerprint, MediaFile};
-use crate::platform::filesystem::{create_platform_filesystem_manager, FileSystemManager};
+use crate::platform::filesystem::{
+    create_platform_filesystem_manager, display_path, extended_length_path,
+    path_from_lossless_string, path_to_lossless_string, FileSystemManager,
+};
+use crate::scan_progress::ScanControl;
 
 /// Batch size for database operations during parallel scanning
 const BATCH_SIZE: usize = 1000;
 
+/// Setting that changes whenever files are marked missing, so listings only
+/// search the whole library for missing files after it changed.
+pub const MISSING_FILES_MARKED_SETTING: &str = "missing_files_marked";
+
+/// Record that files were just marked missing; see
+/// [`MISSING_FILES_MARKED_SETTING`].
+pub async fn note_missing_files_marked<D: DatabaseManager + ?Sized>(database: &D) -> Result<()> {
+    let marked = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    database
+        .set_setting(MISSING_FILES_MARKED_SETTING, &marked.to_string())
+        .await
+}
+
+/// The unmounted mount points at, above or below `root`, each logged. Files
+/// under them are left alone until the drive is mounted again.
+pub(crate) async fn unmounted_mount_points(root: &Path) -> Vec<PathBuf> {
+    let probe = root.to_path_buf();
+    let mount_points = tokio::task::spawn_blocking(move || {
+        crate::platform::filesystem::missing_mount_points(&probe)
+    })
+    .await
+    .unwrap_or_default();
+    for mount_point in &mount_points {
+        warn!(
+            "{} is not mounted; leaving the indexed files under it in {} alone",
+            mount_point.display(),
+            root.display()
+        );
+    }
+    mount_points
+}
+
 /// Immutable rules for one configured media root.  The same value is shared by
 /// startup scans, reconciliation and watcher filtering so those paths cannot
 /// disagree about what belongs in the catalog.
@@ -24,8 +63,140 @@ pub struct ScanPolicy {
     pub recursive: bool,
     pub case_sensitive: bool,
     extensions: HashSet<String>,
-    exclude_patterns: Vec<String>,
+    /// `VIDEO_TS` folders are indexed unless `media_types` leaves out video.
+    dvd_folders: bool,
+    excludes: ExcludeMatcher,
+    own_data: OwnDataPaths,
     pub scan_playlists: bool,
+    /// Store a [`partial_content_hash`] with every file (`media.hide_duplicates`).
+    pub hash_content: bool,
+    /// Report what a scan would change without writing it (`media.dry_run`).
+    /// Files the rules leave out are collected in [`ScanResult::skipped`].
+    pub dry_run: bool,
+    /// How long a file that disappeared stays indexed, hidden, before it is
+    /// removed (`media.deletion_grace_minutes`). `None` removes it at once.
+    pub deletion_grace: Option<Duration>,
+    /// Keep the indexed files under unmounted mount points instead of
+    /// removing them (`media.protect_unmounted_roots`).
+    pub protect_unmounted: bool,
+    /// Smaller files are left out (`min_file_size`).
+    min_file_size: u64,
+    /// Files modified more recently than this are left out until they are
+    /// older (`min_file_age_seconds`).
+    min_file_age: Duration,
+    pub watch_mode: WatchMode,
+    pub progress: ScanProgressInterval,
+}
+
+/// How often a scan logs that it is still going. A zero field disables that
+/// trigger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanProgressInterval {
+    pub files: u64,
+    pub seconds: u64,
+}
+
+impl Default for ScanProgressInterval {
+    fn default() -> Self {
+        Self {
+            files: 25_000,
+            seconds: 30,
+        }
+    }
+}
+
+/// Decides when a running scan logs its progress.
+struct ScanProgress {
+    interval: ScanProgressInterval,
+    reported_files: u64,
+    reported_at: Instant,
+}
+
+impl ScanProgress {
+    fn new(interval: ScanProgressInterval, now: Instant) -> Self {
+        Self {
+            interval,
+            reported_files: 0,
+            reported_at: now,
+        }
+    }
+
+    /// Whether `scanned` files at `now` warrant a progress line; the line is
+    /// then counted as written.
+    fn due(&mut self, scanned: u64, now: Instant) -> bool {
+        let by_files = self.interval.files > 0
+            && scanned >= self.reported_files.saturating_add(self.interval.files);
+        let by_time = self.interval.seconds > 0
+            && now.duration_since(self.reported_at) >= Duration::from_secs(self.interval.seconds);
+        if by_files || by_time {
+            self.reported_files = scanned;
+            self.reported_at = now;
+        }
+        by_files || by_time
+    }
+}
+
+/// `1234567` as `1,234,567`.
+fn group_thousands(value: u64) -> String {
+    let digits = value.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// Files and directories VuIO writes itself. They are never indexed, even
+/// when they live inside a monitored directory and have a media extension.
+#[derive(Debug, Clone, Default)]
+struct OwnDataPaths {
+    /// Excluded together with everything below them.
+    directories: Vec<PathBuf>,
+    /// Excluded together with the siblings derived from them: database
+    /// backups, restores and quarantined copies (`<stem>.*.<ext>`, `<stem>.*.tmp`)
+    /// and rotated logs (`<name>.*`).
+    files: Vec<PathBuf>,
+}
+
+impl OwnDataPaths {
+    fn from_config(config: &AppConfig) -> Self {
+        let database = config.get_database_path();
+        let mut directories = vec![AppConfig::get_platform_cache_dir(), config.get_backup_dir()];
+        directories.extend(config.media.playlist_mirror_dir.clone());
+        let mut files = vec![
+            database.with_extension("redb"),
+            database,
+            AppConfig::get_platform_log_file_path(),
+        ];
+        files.extend(config.logging.stream_audit_file.clone());
+        Self {
+            directories: directories
+                .iter()
+                .map(|directory| {
+                    std::fs::canonicalize(directory)
+                        .map(|directory| display_path(&directory))
+                        .unwrap_or_else(|_| resolve_parent(directory))
+                })
+                .collect(),
+            files: files.iter().map(|file| resolve_parent(file)).collect(),
+        }
+    }
+}
+
+/// Canonicalize the directory of a file that may not exist yet, in its plain
+/// spelling so it compares equal to scanned paths on Windows.
+pub(crate) fn resolve_parent(path: &Path) -> PathBuf {
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) if !parent.as_os_str().is_empty() => {
+            std::fs::canonicalize(parent)
+                .map(|parent| display_path(&parent).join(name))
+                .unwrap_or_else(|_| path.to_path_buf())
+        }
+        _ => path.to_path_buf(),
+    }
 }
 
 impl ScanPolicy {
@@ -37,29 +208,52 @@ impl ScanPolicy {
             .iter()
             .map(|extension| extension.trim_start_matches('.').to_ascii_lowercase())
             .filter(|extension| !extension.is_empty())
+            .filter(|extension| {
+                directory.admits_mime_type(
+                    &crate::platform::filesystem::get_mime_type_for_extension(extension),
+                )
+            })
             .collect();
+        let case_sensitive = directory.case_sensitive.unwrap_or_else(|| {
+            detect_case_sensitivity(Path::new(&directory.path)).unwrap_or_else(|| {
+                let fallback = !cfg!(target_os = "windows");
+                warn!(
+                    "Could not detect case behavior for {}; using {} fallback",
+                    directory.path,
+                    if fallback {
+                        "case-sensitive"
+                    } else {
+                        "case-insensitive"
+                    }
+                );
+                fallback
+            })
+        });
+        let excludes = ExcludeMatcher::new(
+            directory.exclude_patterns.as_deref().unwrap_or_default(),
+            case_sensitive,
+        );
         Self {
             root: PathBuf::from(&directory.path),
             security_root: PathBuf::from(&directory.path),
             recursive: directory.recursive,
-            case_sensitive: directory.case_sensitive.unwrap_or_else(|| {
-                detect_case_sensitivity(Path::new(&directory.path)).unwrap_or_else(|| {
-                    let fallback = !cfg!(target_os = "windows");
-                    warn!(
-                        "Could not detect case behavior for {}; using {} fallback",
-                        directory.path,
-                        if fallback {
-                            "case-sensitive"
-                        } else {
-                            "case-insensitive"
-                        }
-                    );
-                    fallback
-                })
-            }),
+            case_sensitive,
             extensions,
-            exclude_patterns: directory.exclude_patterns.clone().unwrap_or_default(),
+            dvd_folders: directory.admits_mime_type(crate::dvd::DVD_MIME_TYPE),
+            excludes,
+            own_data: OwnDataPaths::from_config(config),
             scan_playlists: config.media.scan_playlists,
+            hash_content: config.media.hide_duplicates,
+            dry_run: config.media.dry_run,
+            deletion_grace: config.media.deletion_grace(),
+            protect_unmounted: config.media.protect_unmounted_roots,
+            min_file_size: directory.min_file_size.unwrap_or_default(),
+            min_file_age: Duration::from_secs(directory.min_file_age_seconds.unwrap_or_default()),
+            watch_mode: directory.watch_mode,
+            progress: ScanProgressInterval {
+                files: config.media.scan_progress_interval_files,
+                seconds: config.media.scan_progress_interval_seconds,
+            },
         }
     }
 
@@ -73,16 +267,28 @@ impl ScanPolicy {
                 .iter()
                 .map(|extension| extension.to_ascii_lowercase())
                 .collect(),
-            exclude_patterns: Vec::new(),
+            dvd_folders: true,
+            excludes: ExcludeMatcher::new(&[], true),
+            own_data: OwnDataPaths::default(),
             scan_playlists: false,
+            hash_content: false,
+            dry_run: false,
+            deletion_grace: None,
+            protect_unmounted: false,
+            min_file_size: 0,
+            min_file_age: Duration::ZERO,
+            watch_mode: WatchMode::Auto,
+            progress: ScanProgressInterval::default(),
         }
     }
 
+    /// Policies of the local roots; remote roots have no filesystem rules.
     pub fn policies(config: &AppConfig) -> Vec<Self> {
         config
             .media
             .directories
             .iter()
+            .filter(|directory| !crate::remote::is_remote_root(&directory.path))
             .map(|directory| Self::from_config(config, directory))
             .collect()
     }
@@ -125,13 +331,13 @@ impl ScanPolicy {
             }
         }
 
-        let canonical_root = PathBuf::from(
-            filesystem_manager
+        let canonical_root = path_from_lossless_string(
+            &filesystem_manager
                 .get_canonical_path(&self.security_root)
                 .map_err(|error| anyhow::anyhow!("failed to canonicalize media root: {error}"))?,
         );
-        let canonical_path = PathBuf::from(
-            filesystem_manager
+        let canonical_path = path_from_lossless_string(
+            &filesystem_manager
                 .get_canonical_path(path)
                 .map_err(|error| anyhow::anyhow!("failed to canonicalize media path: {error}"))?,
         );
@@ -177,9 +383,15 @@ impl ScanPolicy {
                 .is_some_and(|parent| self.paths_equal(parent, &self.root))
     }
 
+    /// A `VIDEO_TS` folder is media in its own right; the files inside it
+    /// never are.
     pub fn allows_media(&self, path: &Path) -> bool {
+        if crate::dvd::is_video_ts_dir(path) {
+            return self.dvd_folders && self.contains(path) && !self.is_excluded(path);
+        }
         self.contains(path)
             && !self.is_excluded(path)
+            && !crate::dvd::is_inside_video_ts(path)
             && path
                 .extension()
                 .and_then(|extension| extension.to_str())
@@ -196,37 +408,133 @@ impl ScanPolicy {
                 .is_some_and(|extension| {
                     matches!(
                         extension.to_ascii_lowercase().as_str(),
-                        "m3u" | "m3u8" | "pls"
+                        "m3u" | "m3u8" | "pls" | "xspf"
                     )
                 })
     }
 
+    pub fn allows_subtitle(&self, path: &Path) -> bool {
+        self.contains(path)
+            && !self.is_excluded(path)
+            && path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("srt"))
+    }
+
+    /// Why a file in this policy's tree is not indexed, or `None` when it is
+    /// media or a playlist or subtitle handled on its own.
+    pub fn skip_reason(&self, path: &Path) -> Option<SkipReason> {
+        if !self.contains(path)
+            || crate::dvd::is_inside_video_ts(path)
+            || self.allows_media(path)
+            || self.allows_playlist(path)
+            || self.allows_subtitle(path)
+        {
+            return None;
+        }
+        if self.is_excluded(path) {
+            Some(SkipReason::Excluded)
+        } else {
+            Some(SkipReason::UnsupportedExtension)
+        }
+    }
+
+    /// Whether `min_file_size` or `min_file_age_seconds` is set.
+    pub fn has_metadata_limits(&self) -> bool {
+        self.min_file_size > 0 || !self.min_file_age.is_zero()
+    }
+
+    /// Why a media file of `size` bytes, last modified at `modified`, stays
+    /// out of the index for now: it is below `min_file_size`, or was modified
+    /// within `min_file_age_seconds` of `now`.
+    pub fn metadata_skip_reason(
+        &self,
+        size: u64,
+        modified: SystemTime,
+        now: SystemTime,
+    ) -> Option<SkipReason> {
+        if size < self.min_file_size {
+            Some(SkipReason::TooSmall)
+        } else if modified_within(modified, self.min_file_age, now) {
+            Some(SkipReason::TooRecent)
+        } else {
+            None
+        }
+    }
+
+    /// Whether a directory at `path` is part of this policy's tree: the root
+    /// itself, or any non-excluded descendant of a recursive root.
+    pub fn allows_directory(&self, path: &Path) -> bool {
+        self.paths_equal(path, &self.root)
+            || (self.recursive
+                && self.path_starts_with(path, &self.root)
+                && !self.is_excluded(path))
+    }
+
     pub fn allows_watched_path(&self, path: &Path) -> bool {
         if path.is_dir() {
-            return self.paths_equal(path, &self.root)
-                || (self.recursive && self.path_starts_with(path, &self.root));
+            return self.allows_directory(path);
         }
-        self.allows_media(path)
-            || self.allows_playlist(path)
-            || (self.contains(path)
-                && !self.is_excluded(path)
-                && path
-                    .extension()
-                    .and_then(|extension| extension.to_str())
-                    .is_some_and(|extension| extension.eq_ignore_ascii_case("srt")))
+        self.allows_media(path) || self.allows_playlist(path) || self.allows_subtitle(path)
     }
 
+    /// Patterns match the path relative to the configured root, including
+    /// the root of a narrowed subtree scan.
     fn is_excluded(&self, path: &Path) -> bool {
-        let skip = if self.path_starts_with(path, &self.root) {
-            self.root.components().count()
+        if self.is_own_data(path) {
+            return true;
+        }
+        let skip = [&self.security_root, &self.root]
+            .into_iter()
+            .find(|root| self.path_starts_with(path, root))
+            .map_or(0, |root| root.components().count());
+        let relative = path.components().skip(skip).collect::<PathBuf>();
+        self.excludes.is_excluded(&relative)
+    }
+
+    fn is_own_data(&self, path: &Path) -> bool {
+        if self
+            .own_data
+            .directories
+            .iter()
+            .any(|directory| self.path_starts_with(path, directory))
+        {
+            return true;
+        }
+        let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+            return false;
+        };
+        let name = name.to_string_lossy();
+        let name = if self.case_sensitive {
+            name.into_owned()
         } else {
-            0
+            name.to_lowercase()
         };
-        path.components().skip(skip).any(|component| {
-            let value = component.as_os_str().to_string_lossy();
-            self.exclude_patterns
-                .iter()
-                .any(|pattern| wildcard_match(pattern, &value, self.case_sensitive))
+        self.own_data.files.iter().any(|file| {
+            let (Some(file_parent), Some(file_name)) = (file.parent(), file.file_name()) else {
+                return false;
+            };
+            if !self.paths_equal(parent, file_parent) {
+                return false;
+            }
+            let file_name = file_name.to_string_lossy();
+            let file_name = if self.case_sensitive {
+                file_name.into_owned()
+            } else {
+                file_name.to_lowercase()
+            };
+            let file = Path::new(&file_name);
+            let stem = file.file_stem().map(|stem| stem.to_string_lossy());
+            let extension = file
+                .extension()
+                .map(|extension| extension.to_string_lossy());
+            name == file_name
+                || name.starts_with(&format!("{file_name}."))
+                || stem.zip(extension).is_some_and(|(stem, extension)| {
+                    name.starts_with(&format!("{stem}."))
+                        && (name.ends_with(&format!(".{extension}")) || name.ends_with(".tmp"))
+                })
         })
     }
 
@@ -244,37 +552,6 @@ impl ScanPolicy {
     }
 }
 
-fn wildcard_match(pattern: &str, value: &str, case_sensitive: bool) -> bool {
-    let (pattern, value) = if !case_sensitive {
-        (pattern.to_ascii_lowercase(), value.to_ascii_lowercase())
-    } else {
-        (pattern.to_owned(), value.to_owned())
-    };
-    let pattern = pattern.as_bytes();
-    let value = value.as_bytes();
-    let (mut p, mut v, mut star, mut checkpoint) = (0, 0, None, 0);
-    while v < value.len() {
-        if p < pattern.len() && (pattern[p] == b'?' || pattern[p] == value[v]) {
-            p += 1;
-            v += 1;
-        } else if p < pattern.len() && pattern[p] == b'*' {
-            star = Some(p);
-            p += 1;
-            checkpoint = v;
-        } else if let Some(star_position) = star {
-            p = star_position + 1;
-            checkpoint += 1;
-            v = checkpoint;
-        } else {
-            return false;
-        }
-    }
-    while p < pattern.len() && pattern[p] == b'*' {
-        p += 1;
-    }
-    p == pattern.len()
-}
-
 fn component_equal(
     left: std::path::Component<'_>,
     right: std::path::Component<'_>,
@@ -301,6 +578,16 @@ fn path_components_equal(left: &Path, right: &Path, case_sensitive: bool) -> boo
     }
 }
 
+/// Whether `modified` lies less than `window` from `now`. A timestamp ahead
+/// of the clock counts the same way, so a share with a skewed clock does not
+/// keep its files out for good.
+pub(crate) fn modified_within(modified: SystemTime, window: Duration, now: SystemTime) -> bool {
+    let distance = now
+        .duration_since(modified)
+        .unwrap_or_else(|ahead| ahead.duration());
+    distance < window
+}
+
 /// Detect case behavior without writing to a monitored root. We change the
 /// ASCII case of one existing path component and ask the filesystem whether it
 /// resolves to the same canonical object.
@@ -337,17 +624,102 @@ fn swap_one_ascii_case(value: &str) -> Option<String> {
 
 #[derive(Debug)]
 struct TraversalReport {
-    file_paths: Vec<PathBuf>,
     uncertain_prefixes: Vec<PathBuf>,
     errors: Vec<ScanError>,
     root_complete: bool,
     rejected_symlinks: usize,
+    /// Only collected by dry runs.
+    skipped: Vec<SkippedFile>,
+}
+
+impl TraversalReport {
+    fn record_error(&mut self, root: &Path, failed_path: &Path, error: std::io::Error) {
+        if failed_path == root {
+            self.root_complete = false;
+        }
+        self.uncertain_prefixes.push(failed_path.to_path_buf());
+        self.errors.push(ScanError {
+            path: failed_path.to_path_buf(),
+            error: error.to_string(),
+        });
+    }
+}
+
+/// Walk `root` depth-first and send every media file `policy` allows.
+///
+/// Directories are read entry by entry rather than listed up front, so one
+/// folder of 100k files costs no more memory than a small one; only the
+/// directories still to visit are held. The walk stops once `files` closes.
+fn walk_media_files(
+    root: &Path,
+    policy: &ScanPolicy,
+    files: &tokio::sync::mpsc::Sender<PathBuf>,
+) -> TraversalReport {
+    let mut report = TraversalReport {
+        uncertain_prefixes: Vec::new(),
+        errors: Vec::new(),
+        root_complete: true,
+        rejected_symlinks: 0,
+        skipped: Vec::new(),
+    };
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(directory) = pending.pop() {
+        let entries = match std::fs::read_dir(extended_length_path(&directory)) {
+            Ok(entries) => entries,
+            Err(error) => {
+                report.record_error(root, &directory, error);
+                continue;
+            }
+        };
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(error) => {
+                    report.record_error(root, &directory, error);
+                    break;
+                }
+            };
+            // Keep the plain spelling; only the listing uses the extended form.
+            let path = directory.join(entry.file_name());
+            match entry.file_type() {
+                Ok(file_type) if file_type.is_symlink() => {
+                    warn!("Skipping symbolic link: {}", path.display());
+                    report.rejected_symlinks += 1;
+                }
+                Ok(file_type) if file_type.is_dir() && crate::dvd::is_video_ts_dir(&path) => {
+                    if policy.allows_media(&path)
+                        && crate::dvd::is_dvd_folder(&path)
+                        && files.blocking_send(path).is_err()
+                    {
+                        return report;
+                    }
+                }
+                Ok(file_type) if file_type.is_dir() => pending.push(path),
+                Ok(file_type) if file_type.is_file() => {
+                    if policy.allows_media(&path) {
+                        if files.blocking_send(path).is_err() {
+                            // The scan stopped consuming paths.
+                            return report;
+                        }
+                    } else if policy.dry_run {
+                        if let Some(reason) = policy.skip_reason(&path) {
+                            report.skipped.push(SkippedFile { path, reason });
+                        }
+                    }
+                }
+                Ok(_) => {}
+                Err(error) => report.record_error(root, &path, error),
+            }
+        }
+    }
+    report
 }
 
 /// Media scanner that uses the file system manager and database for efficient scanning
 pub struct MediaScanner<D: DatabaseManager = RedbDatabase> {
     filesystem_manager: Box<dyn FileSystemManager>,
     database_manager: Arc<D>,
+    scan_control: Option<Arc<ScanControl>>,
 }
 
 impl<D: DatabaseManager> MediaScanner<D> {
@@ -359,6 +731,8 @@ impl<D: DatabaseManager> MediaScanner<D> {
             modified: file.modified,
             created_at: file.created_at,
             subtitle_available: file.subtitle_available,
+            content_hash: file.content_hash,
+            missing_since: file.missing_since,
         }
     }
 
@@ -367,9 +741,23 @@ impl<D: DatabaseManager> MediaScanner<D> {
         Self {
             filesystem_manager: create_platform_filesystem_manager(),
             database_manager,
+            scan_control: None,
         }
     }
 
+    /// Stop recursive scans at the next batch boundary once `control` is
+    /// asked to cancel.
+    pub fn with_scan_control(mut self, control: Arc<ScanControl>) -> Self {
+        self.scan_control = Some(control);
+        self
+    }
+
+    fn cancel_requested(&self) -> bool {
+        self.scan_control
+            .as_ref()
+            .is_some_and(|control| control.is_cancel_requested())
+    }
+
     /// Simple directory scan that returns files without database operations
     pub async fn scan_directory_simple(&self, directory: &Path) -> Result<Vec<MediaFile>> {
         if tokio::fs::symlink_metadata(directory)
@@ -384,7 +772,7 @@ impl<D: DatabaseManager> MediaScanner<D> {
         }
         // Use canonical path normalization for consistency
         let canonical_dir = match self.filesystem_manager.get_canonical_path(directory) {
-            Ok(canonical) => PathBuf::from(canonical),
+            Ok(canonical) => path_from_lossless_string(&canonical),
             Err(e) => {
                 tracing::warn!(
                     "Failed to get canonical path for {}: {}, using basic normalization",
@@ -423,6 +811,7 @@ impl<D: DatabaseManager> MediaScanner<D> {
         Self {
             filesystem_manager,
             database_manager,
+            scan_control: None,
         }
     }
 
@@ -444,169 +833,131 @@ impl<D: DatabaseManager> MediaScanner<D> {
                 )
             })?;
         self.filesystem_manager.validate_path(&canonical_dir)?;
+        if policy.protect_unmounted {
+            let unmounted = unmounted_mount_points(&canonical_dir).await;
+            if let Some(result) = ScanResult::unmounted_root(&canonical_dir, &unmounted) {
+                return Ok(result);
+            }
+        }
         let mut effective_policy = policy.clone();
         effective_policy.root = canonical_dir.clone();
-        let mut entries = tokio::fs::read_dir(&canonical_dir).await?;
-        let existing_files = self
+        let mut entries = tokio::fs::read_dir(extended_length_path(&canonical_dir)).await?;
+        // Files are taken out of this map as the listing sees them, so
+        // whatever is left at the end disappeared from disk.
+        let mut existing_by_path: HashMap<PathBuf, MediaFile> = self
             .database_manager
             .get_files_in_directory(&canonical_dir)
-            .await?;
-        let mut current_files = Vec::new();
+            .await?
+            .into_iter()
+            .map(|file| (file.path.clone(), file))
+            .collect();
+        let had_existing_files = !existing_by_path.is_empty();
+        let mut result = ScanResult::new();
+        let mut files_to_insert = Vec::with_capacity(BATCH_SIZE);
+        let mut files_to_update = Vec::with_capacity(BATCH_SIZE);
         let mut rejected_symlinks = 0usize;
         while let Some(entry) = entries.next_entry().await? {
-            let path = entry.path();
+            let path = canonical_dir.join(entry.file_name());
             let file_type = entry.file_type().await?;
             if file_type.is_symlink() {
                 warn!("Skipping symbolic link: {}", path.display());
                 rejected_symlinks += 1;
                 continue;
             }
-            if effective_policy.allows_media(&path) && file_type.is_file() {
-                if let Some(canonical_path) = policy
-                    .secure_canonical_path(&path, self.filesystem_manager.as_ref())
-                    .await?
-                {
-                    current_files.push(self.create_media_file_from_path(&canonical_path).await?);
-                } else {
-                    rejected_symlinks += 1;
+            let is_media = file_type.is_file()
+                || (file_type.is_dir()
+                    && crate::dvd::is_video_ts_dir(&path)
+                    && matches!(crate::dvd::load_main_title(&path).await, Ok(Some(_))));
+            if !is_media || !effective_policy.allows_media(&path) {
+                if policy.dry_run && file_type.is_file() {
+                    if let Some(reason) = effective_policy.skip_reason(&path) {
+                        result.skipped.push(SkippedFile { path, reason });
+                    }
                 }
+                continue;
             }
-        }
-        if current_files.is_empty() && !existing_files.is_empty() && rejected_symlinks == 0 {
-            let mut result = ScanResult::new();
-            result.complete = false;
-            result.total_scanned = 0;
-            result.errors.push(ScanError {
-                path: canonical_dir,
-                error: "previously populated root is unexpectedly empty; destructive reconciliation deferred"
-                    .to_owned(),
-            });
-            result
-                .unchanged_files
-                .extend(existing_files.iter().map(Self::fingerprint));
-            return Ok(result);
-        }
-        self.perform_incremental_update(&canonical_dir, existing_files, current_files)
-            .await
-    }
-
-    /// Perform an incremental update by comparing database state with file system state
-    /// **OPTIMIZED FOR REDB DATABASE WITH BULK OPERATIONS**
-    async fn perform_incremental_update(
-        &self,
-        _directory: &Path,
-        existing_files: Vec<MediaFile>,
-        current_files: Vec<MediaFile>,
-    ) -> Result<ScanResult> {
-        let mut result = ScanResult::new();
-
-        let existing_by_normalized: std::collections::HashMap<PathBuf, MediaFile> = existing_files
-            .into_iter()
-            .map(|file| (file.path.clone(), file))
-            .collect();
-        let current_normalized: std::collections::HashMap<PathBuf, MediaFile> = current_files
-            .into_iter()
-            .map(|file| (file.path.clone(), file))
-            .collect();
-
-        let current_paths: HashSet<PathBuf> = current_normalized.keys().cloned().collect();
-
-        // **REDB BULK OPERATIONS - Collect files for batch processing**
-        let mut files_to_insert = Vec::new();
-        let mut files_to_update = Vec::new();
-        let mut files_to_remove = Vec::new();
-
-        // Process current files - collect new ones and changed ones for bulk operations
-        for (normalized_current_path, current_file) in &current_normalized {
-            let existing_file = existing_by_normalized.get(normalized_current_path);
-
-            match existing_file {
-                Some(existing_file) => {
-                    // File exists in database, check if it needs updating
-                    if self.file_needs_update(existing_file, current_file) {
-                        tracing::debug!(
-                            "File needs update: {} (modified: {:?} vs {:?}, size: {} vs {})",
-                            existing_file.path.display(),
-                            existing_file.modified,
-                            current_file.modified,
-                            existing_file.size,
-                            current_file.size
-                        );
-
-                        // Use the canonical path from current_file (already normalized above)
-                        let mut updated_file = current_file.clone();
-                        updated_file.id = existing_file.id; // Preserve database ID
-                        updated_file.created_at = existing_file.created_at; // Preserve creation time
-                        updated_file.updated_at = SystemTime::now();
-
-                        files_to_update.push(updated_file);
-                    } else {
+            let Some(canonical_path) = policy
+                .secure_canonical_path(&path, self.filesystem_manager.as_ref())
+                .await?
+            else {
+                rejected_symlinks += 1;
+                continue;
+            };
+            let current_file = self
+                .create_media_file_from_path(&canonical_path, policy)
+                .await?;
+            result.total_scanned += 1;
+            if let Some(reason) = policy.metadata_skip_reason(
+                current_file.size,
+                current_file.modified,
+                SystemTime::now(),
+            ) {
+                if reason == SkipReason::TooRecent {
+                    if let Some(existing_file) = existing_by_path.remove(&canonical_path) {
                         result
                             .unchanged_files
-                            .push(Self::fingerprint(existing_file));
+                            .push(Self::fingerprint(&existing_file));
                     }
                 }
-                None => {
-                    // New file, add to bulk insert list with canonical path format
-                    // The current_file already has the canonical path from normalization above
-                    files_to_insert.push(current_file.clone());
+                if policy.dry_run {
+                    result.skipped.push(SkippedFile { path, reason });
                 }
+                continue;
             }
-        }
-
-        // Find files that were removed from the file system
-        for (normalized_existing_path, existing_file) in existing_by_normalized {
-            if !current_paths.contains(&normalized_existing_path) {
-                // File was removed from file system, add to bulk removal list
-                files_to_remove.push(existing_file.path.clone());
-                result.removed_files.push(Self::fingerprint(&existing_file));
+            match existing_by_path.remove(&canonical_path) {
+                Some(existing_file) if self.file_needs_update(&existing_file, &current_file) => {
+                    tracing::debug!(
+                        "File needs update: {} (modified: {:?} vs {:?}, size: {} vs {})",
+                        existing_file.path.display(),
+                        existing_file.modified,
+                        current_file.modified,
+                        existing_file.size,
+                        current_file.size
+                    );
+                    let mut updated_file = current_file;
+                    updated_file.id = existing_file.id; // Preserve database ID
+                    updated_file.created_at = existing_file.created_at; // Preserve creation time
+                    updated_file.updated_at = SystemTime::now();
+                    files_to_update.push(updated_file);
+                }
+                Some(existing_file) => result
+                    .unchanged_files
+                    .push(Self::fingerprint(&existing_file)),
+                None => files_to_insert.push(current_file),
             }
-        }
-
-        // **EXECUTE BULK OPERATIONS WITH REDB DATABASE**
-
-        // Bulk insert new files
-        if !files_to_insert.is_empty() {
-            tracing::info!(
-                "Bulk inserting {} new files using ReDB database",
-                files_to_insert.len()
-            );
-            for file in &files_to_insert {
-                tracing::debug!(
-                    "Inserting file: path='{}', mime_type='{}', size={}",
-                    file.path.display(),
-                    file.mime_type,
-                    file.size
-                );
+            if files_to_insert.len() >= BATCH_SIZE {
+                self.store_new_files(&mut files_to_insert, &mut result, policy.dry_run)
+                    .await?;
             }
-            let insert_ids = self
-                .database_manager
-                .bulk_store_canonical_media_files(&files_to_insert)
-                .await?;
-
-            // Update result with inserted files and their IDs
-            for (i, mut file) in files_to_insert.into_iter().enumerate() {
-                if let Some(id) = insert_ids.get(i) {
-                    file.id = Some(*id);
-                }
-                result.new_files.push(file);
+            if files_to_update.len() >= BATCH_SIZE {
+                self.store_updated_files(&mut files_to_update, &mut result, policy.dry_run)
+                    .await?;
             }
         }
-
-        // Bulk update changed files
-        if !files_to_update.is_empty() {
-            tracing::info!(
-                "Bulk updating {} changed files using ReDB database",
-                files_to_update.len()
-            );
-            self.database_manager
-                .bulk_update_canonical_media_files(&files_to_update)
-                .await?;
-            result.updated_files.extend(files_to_update);
+        if result.total_scanned == 0 && had_existing_files && rejected_symlinks == 0 {
+            result.complete = false;
+            result.errors.push(ScanError {
+                path: canonical_dir,
+                error: "previously populated root is unexpectedly empty; destructive reconciliation deferred"
+                    .to_owned(),
+            });
+            result
+                .unchanged_files
+                .extend(existing_by_path.values().map(Self::fingerprint));
+            return Ok(result);
         }
+        self.store_new_files(&mut files_to_insert, &mut result, policy.dry_run)
+            .await?;
+        self.store_updated_files(&mut files_to_update, &mut result, policy.dry_run)
+            .await?;
 
         // Bulk remove deleted files
-        if !files_to_remove.is_empty() {
+        if !existing_by_path.is_empty() && policy.dry_run {
+            result
+                .removed_files
+                .extend(existing_by_path.values().map(Self::fingerprint));
+        } else if !existing_by_path.is_empty() {
+            let files_to_remove = existing_by_path.keys().cloned().collect::<Vec<_>>();
             tracing::info!(
                 "Bulk removing {} deleted files using ReDB database",
                 files_to_remove.len()
@@ -620,10 +971,11 @@ impl<D: DatabaseManager> MediaScanner<D> {
                 removed_count,
                 files_to_remove.len()
             );
+            result
+                .removed_files
+                .extend(existing_by_path.values().map(Self::fingerprint));
         }
 
-        result.total_scanned = current_paths.len();
-
         // Log bulk operation summary
         tracing::info!(
             "ReDB bulk operations completed: {} inserted, {} updated, {} removed, {} unchanged",
@@ -636,6 +988,190 @@ impl<D: DatabaseManager> MediaScanner<D> {
         Ok(result)
     }
 
+    /// Reconcile a remote root with its index. A root whose index cannot be
+    /// read keeps its files and is reported incomplete, as a local root that
+    /// went away is.
+    pub async fn scan_remote_root(&self, root: &crate::remote::RemoteRoot) -> Result<ScanResult> {
+        let location = root.location();
+        let mut result = ScanResult::new();
+        let mut existing_by_path: HashMap<PathBuf, MediaFile> = self
+            .database_manager
+            .get_files_with_path_prefix(&path_to_lossless_string(&location))
+            .await?
+            .into_iter()
+            .map(|file| (file.path.clone(), file))
+            .collect();
+        let listed = match root.fetch().await {
+            Ok(listed) => listed,
+            Err(error) => {
+                result.complete = false;
+                result.errors.push(ScanError {
+                    path: PathBuf::from(root.index.as_str()),
+                    error: format!("{error:#}"),
+                });
+                result
+                    .unchanged_files
+                    .extend(existing_by_path.values().map(Self::fingerprint));
+                return Ok(result);
+            }
+        };
+
+        let mut files_to_insert = Vec::with_capacity(BATCH_SIZE.min(listed.len()));
+        let mut files_to_update = Vec::new();
+        for current_file in listed {
+            result.total_scanned += 1;
+            match existing_by_path.remove(&current_file.path) {
+                Some(existing_file) if self.file_needs_update(&existing_file, &current_file) => {
+                    let mut updated_file = current_file;
+                    updated_file.id = existing_file.id;
+                    updated_file.created_at = existing_file.created_at;
+                    updated_file.updated_at = SystemTime::now();
+                    files_to_update.push(updated_file);
+                }
+                Some(existing_file) => result
+                    .unchanged_files
+                    .push(Self::fingerprint(&existing_file)),
+                None => files_to_insert.push(current_file),
+            }
+            if files_to_insert.len() >= BATCH_SIZE {
+                self.store_new_files(&mut files_to_insert, &mut result, false)
+                    .await?;
+            }
+            if files_to_update.len() >= BATCH_SIZE {
+                self.store_updated_files(&mut files_to_update, &mut result, false)
+                    .await?;
+            }
+        }
+        if result.total_scanned == 0 && !existing_by_path.is_empty() {
+            result.complete = false;
+            result.errors.push(ScanError {
+                path: PathBuf::from(root.index.as_str()),
+                error: "previously populated index is unexpectedly empty; destructive reconciliation deferred"
+                    .to_owned(),
+            });
+            result
+                .unchanged_files
+                .extend(existing_by_path.values().map(Self::fingerprint));
+            return Ok(result);
+        }
+        self.store_new_files(&mut files_to_insert, &mut result, false)
+            .await?;
+        self.store_updated_files(&mut files_to_update, &mut result, false)
+            .await?;
+
+        if !existing_by_path.is_empty() {
+            let files_to_remove = existing_by_path.keys().cloned().collect::<Vec<_>>();
+            info!(
+                "Removing {} files no longer listed in {}",
+                files_to_remove.len(),
+                root.index
+            );
+            self.database_manager
+                .bulk_remove_canonical_media_files(&files_to_remove)
+                .await?;
+            result
+                .removed_files
+                .extend(existing_by_path.values().map(Self::fingerprint));
+        }
+        Ok(result)
+    }
+
+    /// Insert one batch of new files and record them, with their IDs, in `result`.
+    /// A dry run only records them.
+    async fn store_new_files(
+        &self,
+        files: &mut Vec<MediaFile>,
+        result: &mut ScanResult,
+        dry_run: bool,
+    ) -> Result<()> {
+        if files.is_empty() {
+            return Ok(());
+        }
+        if dry_run {
+            result.new_files.append(files);
+            return Ok(());
+        }
+        info!("Inserting batch of {} files", files.len());
+        let ids = self
+            .database_manager
+            .bulk_store_canonical_media_files(files)
+            .await?;
+        for (i, mut file) in files.drain(..).enumerate() {
+            if let Some(id) = ids.get(i) {
+                file.id = Some(*id);
+            }
+            result.new_files.push(file);
+        }
+        Ok(())
+    }
+
+    /// Update one batch of changed files and record them in `result`.
+    /// A dry run only records them.
+    async fn store_updated_files(
+        &self,
+        files: &mut Vec<MediaFile>,
+        result: &mut ScanResult,
+        dry_run: bool,
+    ) -> Result<()> {
+        if files.is_empty() {
+            return Ok(());
+        }
+        if dry_run {
+            result.updated_files.append(files);
+            return Ok(());
+        }
+        info!("Updating batch of {} files", files.len());
+        self.database_manager
+            .bulk_update_canonical_media_files(files)
+            .await?;
+        result.updated_files.append(files);
+        Ok(())
+    }
+
+    /// Apply `policy.deletion_grace` to files a complete scan did not find.
+    /// Files seen for the first time are marked missing and recorded as
+    /// updated; the ones returned have been missing for the whole grace
+    /// period, or there is none, and are removed by the caller.
+    async fn mark_missing_files(
+        &self,
+        files: Vec<FileFingerprint>,
+        policy: &ScanPolicy,
+        result: &mut ScanResult,
+    ) -> Result<Vec<FileFingerprint>> {
+        // Dry runs report what will eventually be removed.
+        let Some(grace) = policy.deletion_grace.filter(|_| !policy.dry_run) else {
+            return Ok(files);
+        };
+        let now = SystemTime::now();
+        let (newly_missing, files): (Vec<_>, Vec<_>) = files
+            .into_iter()
+            .partition(|file| file.missing_since.is_none());
+        let expired = files
+            .into_iter()
+            .filter(|file| {
+                file.missing_since
+                    .is_some_and(|since| now.duration_since(since).unwrap_or_default() >= grace)
+            })
+            .collect();
+
+        let paths = newly_missing
+            .into_iter()
+            .map(|file| file.path)
+            .collect::<Vec<_>>();
+        for batch in paths.chunks(BATCH_SIZE) {
+            let mut missing = self.database_manager.bulk_get_files_by_paths(batch).await?;
+            for file in &mut missing {
+                file.missing_since = Some(now);
+            }
+            self.store_updated_files(&mut missing, result, policy.dry_run)
+                .await?;
+        }
+        if !paths.is_empty() {
+            note_missing_files_marked(self.database_manager.as_ref()).await?;
+        }
+        Ok(expired)
+    }
+
     /// Check if a file needs to be updated in the database
     fn file_needs_update(&self, existing: &MediaFile, current: &MediaFile) -> bool {
         if existing.size != current.size {
@@ -646,7 +1182,9 @@ impl<D: DatabaseManager> MediaScanner<D> {
             return true;
         }
 
-        if existing.subtitle_available != current.subtitle_available {
+        if existing.subtitle_available != current.subtitle_available
+            || existing.content_hash != current.content_hash
+        {
             return true;
         }
 
@@ -675,10 +1213,10 @@ impl<D: DatabaseManager> MediaScanner<D> {
         Ok(combined_result)
     }
 
-    /// Perform a recursive scan of a directory using parallel multi-threaded traversal (jwalk)
+    /// Perform a recursive scan of a directory
     ///
-    /// This method uses jwalk for fast parallel directory traversal, collecting all media files
-    /// in a single pass. Files are then batched for efficient database operations.
+    /// Media files are streamed from the directory walk into batched database operations,
+    /// so the traversal does not hold a list of every file it has seen.
     pub async fn scan_directory_recursive(&self, directory: &Path) -> Result<ScanResult> {
         let policy = ScanPolicy::platform_default(directory, true);
         self.scan_directory_recursive_with_policy(&policy).await
@@ -688,8 +1226,6 @@ impl<D: DatabaseManager> MediaScanner<D> {
         &self,
         policy: &ScanPolicy,
     ) -> Result<ScanResult> {
-        use jwalk::WalkDir;
-
         let directory = &policy.root;
 
         let canonical_root = policy
@@ -702,14 +1238,25 @@ impl<D: DatabaseManager> MediaScanner<D> {
                 )
             })?;
 
+        let unmounted = if policy.protect_unmounted {
+            unmounted_mount_points(&canonical_root).await
+        } else {
+            Vec::new()
+        };
+        if let Some(result) = ScanResult::unmounted_root(&canonical_root, &unmounted) {
+            return Ok(result);
+        }
+
         info!(
             "Starting parallel directory scan of: {}",
             canonical_root.display()
         );
 
-        // Load existing files for this root from database (for incremental updates)
+        // Load existing files for this root from database (for incremental updates).
+        // Entries are taken out of the map as the walk sees them, so whatever
+        // is left at the end is what disappeared from disk.
         debug!("Loading existing files under root from database...");
-        let existing_files_map: HashMap<PathBuf, FileFingerprint> = self
+        let mut existing_files_map: HashMap<PathBuf, FileFingerprint> = self
             .database_manager
             .load_file_fingerprints_under_root(&canonical_root)
             .await?
@@ -720,93 +1267,52 @@ impl<D: DatabaseManager> MediaScanner<D> {
             "Loaded {} existing files from database",
             existing_files_map.len()
         );
-
-        // Use jwalk for parallel directory traversal - runs in a blocking thread pool
-        let root_clone = canonical_root.clone();
-        let mut traversal_policy = policy.clone();
-        traversal_policy.root = canonical_root.clone();
-
-        let traversal = tokio::task::spawn_blocking(move || {
-            let mut report = TraversalReport {
-                file_paths: Vec::new(),
-                uncertain_prefixes: Vec::new(),
-                errors: Vec::new(),
-                root_complete: true,
-                rejected_symlinks: 0,
-            };
-            for entry in WalkDir::new(&root_clone).skip_hidden(false) {
-                match entry {
-                    Ok(entry) if entry.file_type().is_file() => {
-                        let path = entry.path();
-                        if traversal_policy.allows_media(&path) {
-                            report.file_paths.push(path);
-                        }
-                    }
-                    Ok(entry) if entry.file_type().is_symlink() => {
-                        warn!("Skipping symbolic link: {}", entry.path().display());
-                        report.rejected_symlinks += 1;
-                    }
-                    Ok(_) => {}
-                    Err(error) => {
-                        let failed_path = error
-                            .path()
-                            .map(Path::to_path_buf)
-                            .unwrap_or_else(|| root_clone.clone());
-                        if failed_path == root_clone {
-                            report.root_complete = false;
-                        }
-                        report.uncertain_prefixes.push(failed_path.clone());
-                        report.errors.push(ScanError {
-                            path: failed_path,
-                            error: error.to_string(),
-                        });
-                    }
-                }
-            }
-            report
-        })
-        .await?;
-
-        let file_paths = traversal.file_paths;
-
-        let total_files = file_paths.len();
         let existing_in_root = existing_files_map
             .keys()
             .filter(|path| path.starts_with(&canonical_root))
             .count();
-        let suspect_empty_root =
-            total_files == 0 && existing_in_root > 0 && traversal.rejected_symlinks == 0;
-        info!(
-            "Found {} media files, processing in batches of {}",
-            total_files, BATCH_SIZE
-        );
-
-        // Process files in batches
-        let mut result = ScanResult::new();
-        result.errors.extend(traversal.errors);
-        result.complete = traversal.root_complete
-            && traversal.uncertain_prefixes.is_empty()
-            && !suspect_empty_root;
-        if suspect_empty_root {
-            result.errors.push(ScanError {
-                path: canonical_root.clone(),
-                error: "previously populated root is unexpectedly empty; destructive reconciliation deferred"
-                    .to_owned(),
-            });
-        }
+
+        // The walk runs on the blocking pool and streams paths through a
+        // bounded channel, so a folder of 100k files never sits in memory as
+        // one list.
+        let root_clone = canonical_root.clone();
+        let mut traversal_policy = policy.clone();
+        traversal_policy.root = canonical_root.clone();
+        let (path_sender, mut path_receiver) = tokio::sync::mpsc::channel::<PathBuf>(BATCH_SIZE);
+        let traversal = tokio::task::spawn_blocking(move || {
+            walk_media_files(&root_clone, &traversal_policy, &path_sender)
+        });
+
+        info!("Processing media files in batches of {}", BATCH_SIZE);
+
+        // Process files in batches
+        let mut result = ScanResult::new();
         let mut files_to_insert: Vec<MediaFile> = Vec::with_capacity(BATCH_SIZE);
         let mut files_to_update: Vec<MediaFile> = Vec::with_capacity(BATCH_SIZE);
-        let mut current_paths: HashSet<PathBuf> = HashSet::with_capacity(total_files);
+        let mut total_files = 0;
         let mut processed = 0;
+        let mut cancelled = false;
+        let mut progress = ScanProgress::new(policy.progress, Instant::now());
 
-        for path in file_paths {
+        while let Some(path) = path_receiver.recv().await {
+            if processed % BATCH_SIZE == 0 && self.cancel_requested() {
+                cancelled = true;
+                break;
+            }
+            total_files += 1;
+            if progress.due(total_files as u64, Instant::now()) {
+                info!(
+                    "Scanned {} files in {}...",
+                    group_thousands(total_files as u64),
+                    canonical_root.display()
+                );
+            }
             let Some(path) = policy
                 .secure_canonical_path(&path, self.filesystem_manager.as_ref())
                 .await?
             else {
                 continue;
             };
-            current_paths.insert(path.clone());
 
             // Compare the cheap filesystem fingerprint before parsing audio
             // tags. Periodic scans should not perform blocking metadata work
@@ -821,23 +1327,61 @@ impl<D: DatabaseManager> MediaScanner<D> {
                     continue;
                 }
             };
-            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            let (size, modified) = if metadata.is_dir() {
+                match crate::dvd::load_main_title(&path).await {
+                    Ok(Some(title)) => (title.size(), title.modified),
+                    Ok(None) => continue,
+                    Err(error) => {
+                        result.errors.push(ScanError {
+                            path: path.clone(),
+                            error: error.to_string(),
+                        });
+                        continue;
+                    }
+                }
+            } else {
+                (
+                    metadata.len(),
+                    metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                )
+            };
+            if let Some(reason) = policy.metadata_skip_reason(size, modified, SystemTime::now()) {
+                // A file still being written keeps what is indexed for it; one
+                // below the size limit is left for removal like a deleted one.
+                if reason == SkipReason::TooRecent {
+                    if let Some(existing) = existing_files_map.remove(&path) {
+                        result.unchanged_files.push(existing);
+                    }
+                }
+                if policy.dry_run {
+                    result.skipped.push(SkippedFile { path, reason });
+                }
+                processed += 1;
+                continue;
+            }
+            let existing = existing_files_map.remove(&path);
             let subtitle_available = tokio::fs::symlink_metadata(path.with_extension("srt"))
                 .await
                 .is_ok_and(|metadata| metadata.is_file() && !metadata.file_type().is_symlink());
-            if let Some(existing) = existing_files_map.get(&path) {
-                if existing.size == metadata.len()
-                    && existing.modified == modified
-                    && existing.subtitle_available == subtitle_available
+            let existing = match existing {
+                Some(existing)
+                    if existing.size == size
+                        && existing.modified == modified
+                        && existing.subtitle_available == subtitle_available
+                        && existing.missing_since.is_none()
+                        && (!policy.hash_content
+                            || existing.content_hash.is_some()
+                            || metadata.is_dir()) =>
                 {
-                    result.unchanged_files.push(existing.clone());
+                    result.unchanged_files.push(existing);
                     processed += 1;
                     continue;
                 }
-            }
+                existing => existing,
+            };
 
             // Create MediaFile from path
-            let current_file = match self.create_media_file_from_path(&path).await {
+            let current_file = match self.create_media_file_from_path(&path, policy).await {
                 Ok(f) => f,
                 Err(e) => {
                     debug!("Failed to create MediaFile for {}: {}", path.display(), e);
@@ -850,7 +1394,7 @@ impl<D: DatabaseManager> MediaScanner<D> {
             };
 
             // Check if file exists in database
-            if let Some(existing) = existing_files_map.get(&path) {
+            if let Some(existing) = existing {
                 let mut updated = current_file;
                 updated.id = Some(existing.id);
                 updated.created_at = existing.created_at;
@@ -864,96 +1408,87 @@ impl<D: DatabaseManager> MediaScanner<D> {
 
             // Process batch when full
             if files_to_insert.len() >= BATCH_SIZE {
-                info!(
-                    "Inserting batch of {} files ({}/{})",
-                    files_to_insert.len(),
-                    processed,
-                    total_files
-                );
-                let ids = self
-                    .database_manager
-                    .bulk_store_canonical_media_files(&files_to_insert)
+                self.store_new_files(&mut files_to_insert, &mut result, policy.dry_run)
                     .await?;
-                for (i, mut file) in files_to_insert.drain(..).enumerate() {
-                    if let Some(id) = ids.get(i) {
-                        file.id = Some(*id);
-                    }
-                    result.new_files.push(file);
-                }
             }
-
             if files_to_update.len() >= BATCH_SIZE {
-                info!(
-                    "Updating batch of {} files ({}/{})",
-                    files_to_update.len(),
-                    processed,
-                    total_files
-                );
-                self.database_manager
-                    .bulk_update_canonical_media_files(&files_to_update)
+                self.store_updated_files(&mut files_to_update, &mut result, policy.dry_run)
                     .await?;
-                result.updated_files.append(&mut files_to_update);
             }
+        }
 
-            // Progress logging every 1000 files
-            if processed % 1000 == 0 {
-                info!("Progress: {}/{} files processed", processed, total_files);
-            }
+        // Closing the channel stops a walk the loop above left early.
+        drop(path_receiver);
+        let traversal = traversal.await?;
+        let suspect_empty_root =
+            total_files == 0 && existing_in_root > 0 && traversal.rejected_symlinks == 0;
+        result.errors.extend(traversal.errors);
+        result.skipped.extend(traversal.skipped);
+        result.complete = traversal.root_complete
+            && traversal.uncertain_prefixes.is_empty()
+            && !suspect_empty_root;
+        if suspect_empty_root {
+            result.errors.push(ScanError {
+                path: canonical_root.clone(),
+                error: "previously populated root is unexpectedly empty; destructive reconciliation deferred"
+                    .to_owned(),
+            });
         }
 
         // Process remaining files in last batch
-        if !files_to_insert.is_empty() {
-            info!("Inserting final batch of {} files", files_to_insert.len());
-            let ids = self
-                .database_manager
-                .bulk_store_canonical_media_files(&files_to_insert)
-                .await?;
-            for (i, mut file) in files_to_insert.into_iter().enumerate() {
-                if let Some(id) = ids.get(i) {
-                    file.id = Some(*id);
-                }
-                result.new_files.push(file);
-            }
-        }
+        self.store_new_files(&mut files_to_insert, &mut result, policy.dry_run)
+            .await?;
+        self.store_updated_files(&mut files_to_update, &mut result, policy.dry_run)
+            .await?;
 
-        if !files_to_update.is_empty() {
-            info!("Updating final batch of {} files", files_to_update.len());
-            self.database_manager
-                .bulk_update_canonical_media_files(&files_to_update)
-                .await?;
-            result.updated_files.extend(files_to_update);
+        // A cancelled scan has not seen every file, so nothing is removed.
+        if cancelled {
+            info!(
+                "Scan of {} cancelled after {} files",
+                canonical_root.display(),
+                processed
+            );
+            result.complete = false;
+            result.cancelled = true;
+            result.total_scanned = processed;
+            return Ok(result);
         }
 
-        // Find and remove deleted files
-        let files_to_remove: Vec<PathBuf> = existing_files_map
-            .iter()
-            .filter(|(path, _)| !current_paths.contains(*path))
-            .filter(|(path, _)| path.starts_with(&canonical_root)) // Only remove files under scanned directory
-            .filter(|(path, _)| {
-                traversal.root_complete
-                    && !suspect_empty_root
-                    && !traversal
+        // Find and remove deleted files: everything the walk did not see.
+        let files_gone: Vec<FileFingerprint> = if traversal.root_complete && !suspect_empty_root {
+            existing_files_map
+                .into_values()
+                .filter(|file| file.path.starts_with(&canonical_root)) // Only remove files under scanned directory
+                .filter(|file| {
+                    !traversal
                         .uncertain_prefixes
                         .iter()
-                        .any(|prefix| path.starts_with(prefix))
-            })
-            .map(|(_, file)| file.path.clone())
-            .collect();
+                        .chain(&unmounted)
+                        .any(|prefix| file.path.starts_with(prefix))
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let files_to_remove = self
+            .mark_missing_files(files_gone, policy, &mut result)
+            .await?;
 
-        if !files_to_remove.is_empty() {
+        if !files_to_remove.is_empty() && policy.dry_run {
+            result.removed_files = files_to_remove;
+        } else if !files_to_remove.is_empty() {
             info!(
                 "Removing {} deleted files from database",
                 files_to_remove.len()
             );
+            let removed_paths = files_to_remove
+                .iter()
+                .map(|file| file.path.clone())
+                .collect::<Vec<_>>();
             self.database_manager
-                .bulk_remove_canonical_media_files(&files_to_remove)
+                .bulk_remove_canonical_media_files(&removed_paths)
                 .await?;
-            let removed_paths = files_to_remove.iter().collect::<HashSet<_>>();
-            for (path, file) in existing_files_map.iter() {
-                if removed_paths.contains(path) {
-                    result.removed_files.push(file.clone());
-                }
-            }
+            result.removed_files = files_to_remove;
         }
 
         result.total_scanned = total_files;
@@ -969,9 +1504,79 @@ impl<D: DatabaseManager> MediaScanner<D> {
         Ok(result)
     }
 
+    /// Compare how many files the database holds for a root with a quick
+    /// filesystem count under the same policy. No file metadata is read, so
+    /// this is cheap enough to run for every root at startup.
+    pub async fn count_root_files_with_policy(
+        &self,
+        policy: &ScanPolicy,
+    ) -> Result<RootFileCounts> {
+        let canonical_root = policy
+            .secure_canonical_path(&policy.root, self.filesystem_manager.as_ref())
+            .await?
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "configured media directory is a symbolic link or escapes its root: {}",
+                    policy.root.display()
+                )
+            })?;
+        let mut traversal_policy = policy.clone();
+        traversal_policy.root = canonical_root.clone();
+
+        let indexed = self
+            .database_manager
+            .load_file_fingerprints_under_root(&canonical_root)
+            .await?
+            .iter()
+            .filter(|fingerprint| traversal_policy.allows_media(&fingerprint.path))
+            .count();
+
+        let on_disk = tokio::task::spawn_blocking(move || -> Result<usize> {
+            let mut walker =
+                jwalk::WalkDir::new(extended_length_path(&canonical_root)).skip_hidden(false);
+            if !traversal_policy.recursive {
+                walker = walker.max_depth(1);
+            }
+            let mut count = 0;
+            let now = SystemTime::now();
+            for entry in walker {
+                let entry = entry.map_err(|error| anyhow::anyhow!(error.to_string()))?;
+                let path = display_path(&entry.path());
+                let is_media = entry.file_type().is_file()
+                    || (entry.file_type().is_dir() && crate::dvd::is_dvd_folder(&path));
+                if !is_media || !traversal_policy.allows_media(&path) {
+                    continue;
+                }
+                // Only the size and age limits need metadata.
+                if entry.file_type().is_file() && traversal_policy.has_metadata_limits() {
+                    let Ok(metadata) = entry.metadata() else {
+                        continue;
+                    };
+                    let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                    if traversal_policy
+                        .metadata_skip_reason(metadata.len(), modified, now)
+                        .is_some()
+                    {
+                        continue;
+                    }
+                }
+                count += 1;
+            }
+            Ok(count)
+        })
+        .await??;
+
+        Ok(RootFileCounts { indexed, on_disk })
+    }
+
     /// Create a MediaFile from a path by reading file metadata
-    async fn create_media_file_from_path(&self, path: &Path) -> Result<MediaFile> {
-        build_media_file_from_path(path, self.filesystem_manager.as_ref()).await
+    async fn create_media_file_from_path(
+        &self,
+        path: &Path,
+        policy: &ScanPolicy,
+    ) -> Result<MediaFile> {
+        build_media_file_from_path(path, self.filesystem_manager.as_ref(), policy.hash_content)
+            .await
     }
 
     /// Traverse and materialize a subtree without changing the database. Any
@@ -988,14 +1593,21 @@ impl<D: DatabaseManager> MediaScanner<D> {
         let recursive = traversal_policy.recursive;
         let paths = tokio::task::spawn_blocking(move || -> Result<Vec<PathBuf>> {
             let mut paths = Vec::new();
-            for entry in jwalk::WalkDir::new(&canonical_root).skip_hidden(false) {
+            for entry in
+                jwalk::WalkDir::new(extended_length_path(&canonical_root)).skip_hidden(false)
+            {
                 let entry = entry.map_err(|error| anyhow::anyhow!(error.to_string()))?;
                 if entry.file_type().is_symlink() {
-                    warn!("Skipping symbolic link: {}", entry.path().display());
+                    warn!(
+                        "Skipping symbolic link: {}",
+                        display_path(&entry.path()).display()
+                    );
                     continue;
                 }
-                if entry.file_type().is_file() {
-                    let path = entry.path();
+                let path = display_path(&entry.path());
+                if entry.file_type().is_file()
+                    || (entry.file_type().is_dir() && crate::dvd::is_dvd_folder(&path))
+                {
                     if (!recursive && path.parent() != Some(canonical_root.as_path()))
                         || !traversal_policy.allows_media(&path)
                     {
@@ -1013,7 +1625,13 @@ impl<D: DatabaseManager> MediaScanner<D> {
                 .secure_canonical_path(&path, self.filesystem_manager.as_ref())
                 .await?
             {
-                files.push(self.create_media_file_from_path(&path).await?);
+                let file = self.create_media_file_from_path(&path, policy).await?;
+                if policy
+                    .metadata_skip_reason(file.size, file.modified, SystemTime::now())
+                    .is_none()
+                {
+                    files.push(file);
+                }
             }
         }
         Ok(files)
@@ -1025,23 +1643,58 @@ impl<D: DatabaseManager> MediaScanner<D> {
     }
 }
 
+/// Bytes read from each end of a file by [`partial_content_hash`].
+const CONTENT_HASH_SPAN: u64 = 1024 * 1024;
+
+/// FNV-1a of the file size and its first and last [`CONTENT_HASH_SPAN`]
+/// bytes. Copies of a file always match; reading only the ends keeps the
+/// cost per file constant.
+pub(crate) fn partial_content_hash(path: &Path) -> std::io::Result<u64> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut file = std::fs::File::open(path)?;
+    let size = file.metadata()?.len();
+    let head = size.min(CONTENT_HASH_SPAN);
+    let tail_start = size.saturating_sub(CONTENT_HASH_SPAN).max(head);
+    let mut bytes = Vec::with_capacity(8 + (head + size - tail_start) as usize);
+    bytes.extend_from_slice(&size.to_le_bytes());
+    (&mut file).take(head).read_to_end(&mut bytes)?;
+    if tail_start < size {
+        file.seek(SeekFrom::Start(tail_start))?;
+        file.take(size - tail_start).read_to_end(&mut bytes)?;
+    }
+    Ok(crate::database::fnv1a(&bytes))
+}
+
 pub(crate) async fn build_media_file_from_path(
     path: &Path,
     filesystem_manager: &dyn FileSystemManager,
+    hash_content: bool,
 ) -> Result<MediaFile> {
     let metadata = tokio::fs::metadata(path).await?;
-    let filename = path
+    let mut filename = path
         .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("unknown")
-        .to_string();
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "unknown".to_string());
     let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-    let mime_type = crate::platform::filesystem::get_mime_type_for_extension(ext);
-    let size = metadata.len();
-    let modified = metadata.modified().unwrap_or_else(|_| SystemTime::now());
+    let mut mime_type = crate::platform::filesystem::get_mime_type_for_extension(ext);
+    let mut size = metadata.len();
+    let mut modified = metadata.modified().unwrap_or_else(|_| SystemTime::now());
+    let mut title = None;
+    if metadata.is_dir() {
+        let dvd = crate::dvd::load_main_title(path)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("no DVD title in {}", path.display()))?;
+        title = crate::dvd::dvd_title_name(path);
+        // The concatenated title is one long VOB.
+        filename = format!("{}.vob", title.as_deref().unwrap_or("VIDEO_TS"));
+        mime_type = crate::dvd::DVD_MIME_TYPE.to_string();
+        size = dvd.size();
+        modified = dvd.modified;
+    }
     let storage_path = filesystem_manager
         .get_canonical_path(path)
-        .map(PathBuf::from)
+        .map(|canonical| path_from_lossless_string(&canonical))
         .unwrap_or_else(|_| path.to_path_buf());
 
     let mut media_file = MediaFile {
@@ -1052,13 +1705,16 @@ pub(crate) async fn build_media_file_from_path(
         modified,
         mime_type,
         duration: None,
-        title: None,
+        title,
         artist: None,
         album: None,
         genre: None,
         track_number: None,
         year: None,
         album_artist: None,
+        compilation: false,
+        content_hash: None,
+        missing_since: None,
         subtitle_available: tokio::fs::symlink_metadata(path.with_extension("srt"))
             .await
             .is_ok_and(|metadata| metadata.is_file() && !metadata.file_type().is_symlink()),
@@ -1069,6 +1725,14 @@ pub(crate) async fn build_media_file_from_path(
     if media_file.mime_type.starts_with("audio/") {
         let _ = crate::platform::filesystem::extract_audio_metadata(&mut media_file).await;
     }
+    // DVD folders have no single byte stream to hash.
+    if hash_content && metadata.is_file() {
+        let hashed = path.to_path_buf();
+        match tokio::task::spawn_blocking(move || partial_content_hash(&hashed)).await? {
+            Ok(hash) => media_file.content_hash = Some(hash),
+            Err(error) => debug!("Failed to hash {}: {}", path.display(), error),
+        }
+    }
 
     Ok(media_file)
 }
@@ -1096,6 +1760,12 @@ pub struct ScanResult {
 
     /// True only when the whole requested root was enumerated without uncertainty.
     pub complete: bool,
+
+    /// True when the scan stopped early because it was cancelled.
+    pub cancelled: bool,
+
+    /// Files left out of the index; only collected by dry runs.
+    pub skipped: Vec<SkippedFile>,
 }
 
 impl ScanResult {
@@ -1109,10 +1779,27 @@ impl ScanResult {
             total_scanned: 0,
             errors: Vec::with_capacity(10),
             complete: true,
+            cancelled: false,
+            skipped: Vec::new(),
         }
     }
 
     /// Merge another scan result into this one
+    /// An incomplete scan that changed nothing, when one of `unmounted` is
+    /// at or above `root`.
+    fn unmounted_root(root: &Path, unmounted: &[PathBuf]) -> Option<Self> {
+        let mount_point = unmounted
+            .iter()
+            .find(|mount_point| root.starts_with(mount_point))?;
+        let mut result = Self::new();
+        result.complete = false;
+        result.errors.push(ScanError {
+            path: root.to_path_buf(),
+            error: format!("{} is not mounted", mount_point.display()),
+        });
+        Some(result)
+    }
+
     pub fn merge(&mut self, other: ScanResult) {
         self.new_files.extend(other.new_files);
         self.updated_files.extend(other.updated_files);
@@ -1121,6 +1808,8 @@ impl ScanResult {
         self.total_scanned += other.total_scanned;
         self.errors.extend(other.errors);
         self.complete &= other.complete;
+        self.cancelled |= other.cancelled;
+        self.skipped.extend(other.skipped);
     }
 
     /// Get the total number of changes (new + updated + removed)
@@ -1153,6 +1842,20 @@ impl Default for ScanResult {
     }
 }
 
+/// Indexed and on-disk media counts for one configured root.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RootFileCounts {
+    pub indexed: usize,
+    pub on_disk: usize,
+}
+
+impl RootFileCounts {
+    /// True when the counts differ by more than `threshold` files.
+    pub fn diverges(&self, threshold: u64) -> bool {
+        self.indexed.abs_diff(self.on_disk) as u64 > threshold
+    }
+}
+
 /// Error that occurred during scanning
 #[derive(Debug, Clone)]
 pub struct ScanError {
@@ -1163,6 +1866,27 @@ pub struct ScanError {
     pub error: String,
 }
 
+/// Why a dry run left a file out of the index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// Matched one of the directory's `exclude_patterns`.
+    Excluded,
+    /// The extension is not in `supported_extensions`.
+    UnsupportedExtension,
+    /// Smaller than the directory's `min_file_size`.
+    TooSmall,
+    /// Modified within the directory's `min_file_age_seconds`.
+    TooRecent,
+}
+
+/// A file a dry run found but would not index.
+#[derive(Debug, Clone)]
+pub struct SkippedFile {
+    pub path: PathBuf,
+    pub reason: SkipReason,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1175,6 +1899,79 @@ mod tests {
     use std::sync::Arc;
     use tempfile::tempdir;
 
+    #[test]
+    fn scan_progress_is_logged_per_file_count_or_elapsed_time() {
+        let start = Instant::now();
+        let mut progress = ScanProgress::new(
+            ScanProgressInterval {
+                files: 25_000,
+                seconds: 30,
+            },
+            start,
+        );
+        assert!(!progress.due(24_999, start + Duration::from_secs(29)));
+        assert!(progress.due(25_000, start + Duration::from_secs(29)));
+        assert!(!progress.due(30_000, start + Duration::from_secs(58)));
+        assert!(progress.due(30_001, start + Duration::from_secs(59)));
+        assert!(progress.due(55_001, start + Duration::from_secs(60)));
+
+        let mut silent = ScanProgress::new(
+            ScanProgressInterval {
+                files: 0,
+                seconds: 0,
+            },
+            start,
+        );
+        assert!(!silent.due(1_000_000, start + Duration::from_secs(3600)));
+
+        assert_eq!(group_thousands(0), "0");
+        assert_eq!(group_thousands(999), "999");
+        assert_eq!(group_thousands(25_000), "25,000");
+        assert_eq!(group_thousands(1_234_567), "1,234,567");
+    }
+
+    #[tokio::test]
+    async fn dvd_folders_and_iso_images_are_indexed_as_single_video_items() {
+        let temp_dir = tempdir().unwrap();
+        let root = std::fs::canonicalize(temp_dir.path())
+            .unwrap()
+            .join("media");
+        let video_ts = root.join("Some Movie").join("VIDEO_TS");
+        std::fs::create_dir_all(&video_ts).unwrap();
+        std::fs::write(video_ts.join("VTS_01_0.VOB"), b"menu").unwrap();
+        std::fs::write(video_ts.join("VTS_01_1.VOB"), b"abcd").unwrap();
+        std::fs::write(video_ts.join("VTS_01_2.VOB"), b"efg").unwrap();
+        std::fs::write(root.join("Backup.iso"), b"disc").unwrap();
+
+        let db = Arc::new(
+            RedbDatabase::new(temp_dir.path().join("test.redb"))
+                .await
+                .unwrap(),
+        );
+        db.initialize().await.unwrap();
+        let scanner = MediaScanner::with_database(db.clone());
+        let result = scanner.scan_directory_recursive(&root).await.unwrap();
+        assert_eq!(result.new_files.len(), 2, "{:?}", result.new_files);
+
+        let dvd = db.get_file_by_path(&video_ts).await.unwrap().unwrap();
+        assert_eq!(dvd.mime_type, crate::dvd::DVD_MIME_TYPE);
+        assert_eq!(dvd.title.as_deref(), Some("Some Movie"));
+        assert_eq!(dvd.size, 7);
+        let iso = db
+            .get_file_by_path(&root.join("Backup.iso"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(iso.mime_type, crate::dvd::ISO_MIME_TYPE);
+
+        let rescan = scanner.scan_directory_recursive(&root).await.unwrap();
+        assert_eq!(rescan.unchanged_files.len(), 2);
+        std::fs::write(video_ts.join("VTS_01_3.VOB"), b"hi").unwrap();
+        let rescan = scanner.scan_directory_recursive(&root).await.unwrap();
+        assert_eq!(rescan.updated_files.len(), 1);
+        assert_eq!(rescan.updated_files[0].size, 9);
+    }
+
     #[tokio::test]
     async fn test_media_scanner_basic_functionality() {
         let temp_dir = tempdir().unwrap();
@@ -1277,6 +2074,9 @@ mod tests {
             track_number: None,
             year: None,
             album_artist: None,
+            compilation: false,
+            content_hash: None,
+            missing_since: None,
             subtitle_available: false,
             created_at: SystemTime::now(),
             updated_at: SystemTime::now(),
@@ -1299,6 +2099,9 @@ mod tests {
             track_number: None,
             year: None,
             album_artist: None,
+            compilation: false,
+            content_hash: None,
+            missing_since: None,
             subtitle_available: false,
             created_at: SystemTime::now(),
             updated_at: SystemTime::now(),
@@ -1397,11 +2200,7 @@ mod tests {
         symlink(&internal_target, media_root.join("internal-link.mp4")).unwrap();
         symlink(&external_target, media_root.join("external-link.mp4")).unwrap();
 
-        let database = Arc::new(
-            RedbDatabase::new(temp.path().join("symlink.redb"))
-                .await
-                .unwrap(),
-        );
+        let database = Arc::new(crate::database::memory::InMemoryDatabase::new());
         database.initialize().await.unwrap();
         let scanner = MediaScanner::with_filesystem_manager(
             Box::new(BaseFileSystemManager::new(true)),
@@ -1432,11 +2231,7 @@ mod tests {
             .unwrap();
         symlink(&external, media_root.join("linked-directory")).unwrap();
 
-        let database = Arc::new(
-            RedbDatabase::new(temp.path().join("directory-symlink.redb"))
-                .await
-                .unwrap(),
-        );
+        let database = Arc::new(crate::database::memory::InMemoryDatabase::new());
         database.initialize().await.unwrap();
         let scanner = MediaScanner::with_filesystem_manager(
             Box::new(BaseFileSystemManager::new(true)),
@@ -1459,11 +2254,7 @@ mod tests {
         let indexed_path = media_root.join("movie.mp4");
         tokio::fs::write(&indexed_path, b"video").await.unwrap();
 
-        let database = Arc::new(
-            RedbDatabase::new(temp.path().join("reconcile-symlink.redb"))
-                .await
-                .unwrap(),
-        );
+        let database = Arc::new(crate::database::memory::InMemoryDatabase::new());
         database.initialize().await.unwrap();
         let scanner = MediaScanner::with_filesystem_manager(
             Box::new(BaseFileSystemManager::new(true)),
@@ -1506,11 +2297,7 @@ mod tests {
         let linked = media_root.join("renamed");
         symlink(&external, &linked).unwrap();
 
-        let database = Arc::new(
-            RedbDatabase::new(temp.path().join("stage-symlink.redb"))
-                .await
-                .unwrap(),
-        );
+        let database = Arc::new(crate::database::memory::InMemoryDatabase::new());
         database.initialize().await.unwrap();
         let scanner = MediaScanner::with_filesystem_manager(
             Box::new(BaseFileSystemManager::new(true)),
@@ -1524,6 +2311,293 @@ mod tests {
             .unwrap()
             .is_empty());
     }
+
+    #[tokio::test]
+    async fn files_indexed_before_hashing_was_enabled_are_hashed_on_the_next_scan() {
+        let temp_dir = tempdir().unwrap();
+        let db = Arc::new(
+            RedbDatabase::new(temp_dir.path().join("test.redb"))
+                .await
+                .unwrap(),
+        );
+        db.initialize().await.unwrap();
+        let scanner =
+            MediaScanner::with_filesystem_manager(Box::new(BaseFileSystemManager::new(true)), db);
+        let root = temp_dir.path().join("media");
+        tokio::fs::create_dir_all(&root).await.unwrap();
+        tokio::fs::write(root.join("a.mp4"), b"same film")
+            .await
+            .unwrap();
+        tokio::fs::write(root.join("b.mp4"), b"same film")
+            .await
+            .unwrap();
+
+        let mut policy = ScanPolicy::platform_default(&root, true);
+        let first = scanner
+            .scan_directory_recursive_with_policy(&policy)
+            .await
+            .unwrap();
+        assert!(first
+            .new_files
+            .iter()
+            .all(|file| file.content_hash.is_none()));
+
+        policy.hash_content = true;
+        let second = scanner
+            .scan_directory_recursive_with_policy(&policy)
+            .await
+            .unwrap();
+        assert_eq!(second.updated_files.len(), 2);
+        assert!(second.updated_files[0].content_hash.is_some());
+        assert_eq!(
+            second.updated_files[0].content_hash,
+            second.updated_files[1].content_hash
+        );
+
+        let third = scanner
+            .scan_directory_recursive_with_policy(&policy)
+            .await
+            .unwrap();
+        assert_eq!(third.unchanged_files.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn deleted_files_are_kept_missing_until_the_grace_period_ends() {
+        let temp_dir = tempdir().unwrap();
+        let db = Arc::new(
+            RedbDatabase::new(temp_dir.path().join("test.redb"))
+                .await
+                .unwrap(),
+        );
+        db.initialize().await.unwrap();
+        let scanner = MediaScanner::with_filesystem_manager(
+            Box::new(BaseFileSystemManager::new(true)),
+            db.clone(),
+        );
+        let root = temp_dir.path().join("media");
+        tokio::fs::create_dir_all(&root).await.unwrap();
+        let film = root.join("film.mp4");
+        tokio::fs::write(&film, b"film").await.unwrap();
+        // An emptied root is never reconciled, so keep one file in it.
+        tokio::fs::write(root.join("trailer.mp4"), b"trailer")
+            .await
+            .unwrap();
+
+        let mut policy = ScanPolicy::platform_default(&root, true);
+        policy.deletion_grace = Some(Duration::from_secs(3600));
+        let first = scanner
+            .scan_directory_recursive_with_policy(&policy)
+            .await
+            .unwrap();
+        let film = std::fs::canonicalize(&film).unwrap();
+        let id = first
+            .new_files
+            .iter()
+            .find(|file| file.path == film)
+            .unwrap()
+            .id;
+
+        tokio::fs::remove_file(&film).await.unwrap();
+        let deleted = scanner
+            .scan_directory_recursive_with_policy(&policy)
+            .await
+            .unwrap();
+        assert!(deleted.removed_files.is_empty());
+        assert!(deleted.updated_files[0].missing_since.is_some());
+        // Listings search the library for missing files again.
+        assert!(db
+            .get_setting(MISSING_FILES_MARKED_SETTING)
+            .await
+            .unwrap()
+            .is_some());
+        let missing = db.get_file_by_path(&film).await.unwrap().unwrap();
+        assert_eq!(missing.id, id);
+        assert!(missing.missing_since.is_some());
+
+        tokio::fs::write(&film, b"film").await.unwrap();
+        let restored = scanner
+            .scan_directory_recursive_with_policy(&policy)
+            .await
+            .unwrap();
+        assert_eq!(restored.updated_files[0].id, id);
+        assert!(restored.updated_files[0].missing_since.is_none());
+
+        tokio::fs::remove_file(&film).await.unwrap();
+        scanner
+            .scan_directory_recursive_with_policy(&policy)
+            .await
+            .unwrap();
+        policy.deletion_grace = Some(Duration::ZERO);
+        let expired = scanner
+            .scan_directory_recursive_with_policy(&policy)
+            .await
+            .unwrap();
+        assert_eq!(expired.removed_files.len(), 1);
+        assert!(db.get_file_by_path(&film).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn small_and_recent_files_wait_until_they_qualify() {
+        let temp_dir = tempdir().unwrap();
+        let db = Arc::new(
+            RedbDatabase::new(temp_dir.path().join("test.redb"))
+                .await
+                .unwrap(),
+        );
+        db.initialize().await.unwrap();
+        let scanner = MediaScanner::with_filesystem_manager(
+            Box::new(BaseFileSystemManager::new(true)),
+            db.clone(),
+        );
+        let root = temp_dir.path().join("incoming");
+        tokio::fs::create_dir_all(&root).await.unwrap();
+        let age = |path: &Path, seconds: u64| {
+            std::fs::File::options()
+                .write(true)
+                .open(path)
+                .unwrap()
+                .set_modified(SystemTime::now() - Duration::from_secs(seconds))
+                .unwrap();
+        };
+        let film = root.join("film.mp4");
+        let sample = root.join("sample.mp4");
+        let download = root.join("download.mp4");
+        tokio::fs::write(&film, [0; 64]).await.unwrap();
+        tokio::fs::write(&sample, [0; 4]).await.unwrap();
+        tokio::fs::write(&download, [0; 64]).await.unwrap();
+        age(&film, 3600);
+        age(&sample, 3600);
+
+        let mut policy = ScanPolicy::platform_default(&root, true);
+        policy.min_file_size = 16;
+        policy.min_file_age = Duration::from_secs(60);
+        let names = |files: &[MediaFile]| {
+            let mut names = files
+                .iter()
+                .map(|file| file.filename.clone())
+                .collect::<Vec<_>>();
+            names.sort();
+            names
+        };
+        let first = scanner
+            .scan_directory_recursive_with_policy(&policy)
+            .await
+            .unwrap();
+        assert_eq!(names(&first.new_files), ["film.mp4"]);
+
+        // Once the download has been quiet long enough it is indexed, and a
+        // rewrite of an indexed file leaves its entry alone meanwhile.
+        age(&download, 120);
+        tokio::fs::write(&film, [1; 64]).await.unwrap();
+        let second = scanner
+            .scan_directory_recursive_with_policy(&policy)
+            .await
+            .unwrap();
+        assert_eq!(names(&second.new_files), ["download.mp4"]);
+        assert!(second.removed_files.is_empty());
+        assert_eq!(second.unchanged_files.len(), 1);
+        assert_eq!(second.unchanged_files[0].id, first.new_files[0].id.unwrap());
+        assert!(db.get_file_by_path(&sample).await.unwrap().is_none());
+
+        policy.dry_run = true;
+        policy.recursive = false;
+        let preview = scanner.scan_directory_with_policy(&policy).await.unwrap();
+        let mut skipped = preview
+            .skipped
+            .iter()
+            .map(|skipped| (skipped.path.file_name().unwrap(), skipped.reason))
+            .collect::<Vec<_>>();
+        skipped.sort_by_key(|(name, _)| *name);
+        assert_eq!(
+            skipped,
+            [
+                ("film.mp4".as_ref(), SkipReason::TooRecent),
+                ("sample.mp4".as_ref(), SkipReason::TooSmall),
+            ]
+        );
+    }
+
+    #[test]
+    fn own_database_and_backups_inside_a_root_are_never_media() {
+        let temp = tempdir().unwrap();
+        let root = std::fs::canonicalize(temp.path()).unwrap();
+        let data = root.join("vuio");
+        std::fs::create_dir_all(data.join("backups")).unwrap();
+        let mut config = AppConfig::default_for_platform();
+        config.database.path = Some(data.join("media.db").to_string_lossy().into_owned());
+        config.logging.stream_audit_file = Some(data.join("streams.jsonl"));
+        config.media.playlist_mirror_dir = Some(root.join("Playlists"));
+        config.media.scan_playlists = true;
+        config.media.supported_extensions = ["db", "redb", "tmp", "jsonl", "mp4"]
+            .map(str::to_owned)
+            .to_vec();
+        let directory = MonitoredDirectoryConfig {
+            path: root.to_string_lossy().into_owned(),
+            recursive: true,
+            case_sensitive: Some(true),
+            extensions: None,
+            exclude_patterns: None,
+            validation_mode: crate::config::ValidationMode::Warn,
+            watch_mode: WatchMode::Auto,
+            merge_group: None,
+            priority: 0,
+            media_types: None,
+            min_file_size: None,
+            min_file_age_seconds: None,
+        };
+        let policy = ScanPolicy::from_config(&config, &directory);
+
+        for own in [
+            "media.db",
+            "media.redb",
+            "media.previous-backup-1f2e.redb",
+            "media.backup-1f2e.tmp",
+            "backups/vuio-20240101T000000Z-1f2e.redb",
+            "streams.jsonl",
+            "streams.2024-03-09.jsonl",
+        ] {
+            assert!(!policy.allows_media(&data.join(own)), "{own}");
+        }
+        assert!(!policy.allows_directory(&data.join("backups")));
+        assert!(policy.allows_media(&data.join("media.mp4")));
+        assert!(policy.allows_media(&data.join("other.db")));
+        assert!(policy.allows_media(&root.join("Movies/media.db")));
+        assert!(!policy.allows_playlist(&root.join("Playlists/Road Trip.m3u8")));
+        assert!(policy.allows_playlist(&root.join("Music/Road Trip.m3u8")));
+    }
+
+    #[test]
+    fn media_types_restrict_a_root_to_matching_files() {
+        let root = PathBuf::from("/library/music");
+        let mut directory = MonitoredDirectoryConfig {
+            path: root.to_string_lossy().into_owned(),
+            recursive: true,
+            case_sensitive: Some(true),
+            extensions: None,
+            exclude_patterns: None,
+            validation_mode: crate::config::ValidationMode::Warn,
+            watch_mode: WatchMode::Auto,
+            merge_group: None,
+            priority: 0,
+            media_types: Some(vec!["audio".to_string()]),
+            min_file_size: None,
+            min_file_age_seconds: None,
+        };
+        let config = AppConfig::default_for_platform();
+        let policy = ScanPolicy::from_config(&config, &directory);
+        assert!(policy.allows_media(&root.join("Album/01.flac")));
+        assert!(policy.allows_media(&root.join("Album/02.MP3")));
+        assert!(!policy.allows_media(&root.join("Album/making-of.mp4")));
+        assert!(!policy.allows_media(&root.join("Album/cover.jpg")));
+        assert!(!policy.allows_media(&root.join("Concert/VIDEO_TS")));
+
+        directory.media_types = Some(vec!["video".to_string(), "Image".to_string()]);
+        let policy = ScanPolicy::from_config(&config, &directory);
+        assert!(!policy.allows_media(&root.join("Album/01.flac")));
+        assert!(policy.allows_media(&root.join("Album/making-of.mp4")));
+        assert!(policy.allows_media(&root.join("Album/cover.jpg")));
+        assert!(policy.allows_media(&root.join("Concert/VIDEO_TS")));
+    }
 }
 #[test]
 fn case_policy_compares_path_components_without_changing_boundaries() {
@@ -1,3 +1,69 @@
+//! Collation for titles, filenames and category names.
+//!
+//! Every listing VuIO serves orders names with [`collation_key`]: case and
+//! diacritics are folded so "Émile" sorts beside "emile" instead of after
+//! "zebra", and, with `media.natural_sort` on, digit runs compare by value so
+//! "Episode 2" comes before "Episode 10".
+
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+
+static NATURAL_SORT: AtomicBool = AtomicBool::new(true);
+
+/// Select numeric (`true`) or plain character order for digit runs. Called
+/// once from startup configuration; stored order keys are rebuilt from it
+/// when the database is opened.
+pub fn set_natural_sort(enabled: bool) {
+    NATURAL_SORT.store(enabled, AtomicOrdering::Relaxed);
+}
+
+pub fn natural_sort_enabled() -> bool {
+    NATURAL_SORT.load(AtomicOrdering::Relaxed)
+}
+
+/// Fold case and strip diacritics: "Ångström" becomes "angstrom".
+pub fn fold(input: &str) -> Cow<'_, str> {
+    if input
+        .bytes()
+        .all(|byte| byte.is_ascii() && !byte.is_ascii_uppercase())
+    {
+        return Cow::Borrowed(input);
+    }
+    let decomposed = icu_normalizer::DecomposingNormalizerBorrowed::new_nfd().normalize(input);
+    Cow::Owned(
+        decomposed
+            .chars()
+            .filter(|c| !is_combining_mark(*c))
+            .flat_map(char::to_lowercase)
+            .collect(),
+    )
+}
+
+fn is_combining_mark(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0300}'..='\u{036F}'
+            | '\u{1AB0}'..='\u{1AFF}'
+            | '\u{1DC0}'..='\u{1DFF}'
+            | '\u{20D0}'..='\u{20FF}'
+            | '\u{FE20}'..='\u{FE2F}'
+    )
+}
+
+/// The key every listing sorts names by, honouring `media.natural_sort`.
+pub fn collation_key(input: &str) -> String {
+    collation_key_with(input, natural_sort_enabled())
+}
+
+fn collation_key_with(input: &str, natural: bool) -> String {
+    let folded = fold(input);
+    if natural {
+        natural_sort_key(&folded)
+    } else {
+        folded.into_owned()
+    }
+}
+
 /// Generates a natural sort key for a string.
 /// Numeric substrings are padded with leading zeros to a fixed width of 20 characters
 /// so that lexicographical sorting sorts them numerically.
@@ -55,4 +121,24 @@ mod tests {
         assert_eq!(natural_sort_key("abc"), "abc");
         assert_eq!(natural_sort_key("123"), "00000000000000000123");
     }
+
+    #[test]
+    fn collation_folds_case_and_diacritics() {
+        assert!(collation_key_with("Episode 2", true) < collation_key_with("episode 10", true));
+        assert!(collation_key_with("Episode 2", false) > collation_key_with("Episode 10", false));
+        assert_eq!(fold("ÉMILE Zola"), "emile zola");
+        assert_eq!(fold("Ångström"), "angstrom");
+        assert!(matches!(fold("already folded 1"), Cow::Borrowed(_)));
+
+        let mut titles = vec!["zebra", "Émile", "apple", "Ölfass", "edgar", "Oak"];
+        titles.sort_by(|left, right| {
+            collation_key_with(left, true).cmp(&collation_key_with(right, true))
+        });
+        assert_eq!(
+            titles,
+            vec!["apple", "edgar", "Émile", "Oak", "Ölfass", "zebra"]
+        );
+        // Non-Latin scripts keep their own order after folding.
+        assert!(collation_key_with("Ωμέγα", true) > collation_key_with("Άλφα", true));
+    }
 }
@@ -0,0 +1,331 @@
+//! Merged browse trees for monitored directories that share a `merge_group`.
+//!
+//! Every member directory is still scanned and indexed on its own; the overlay
+//! only decides which copy a client sees. Members are ranked by `priority`
+//! (highest first, configuration order breaking ties). For each path relative
+//! to the member roots, the best-ranked copy is listed and streamed, and the
+//! others are hidden until it disappears.
+
+use anyhow::Result;
+use std::collections::HashSet;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+use crate::config::MonitoredDirectoryConfig;
+use crate::database::{
+    DatabaseReadSession, DirectoryView, MediaDirectory, MediaFileQuery, MediaFileView,
+};
+
+/// The monitored directories of one merge group, best-ranked first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeGroup {
+    pub name: String,
+    /// Indexes into the monitored directory list.
+    pub members: Vec<usize>,
+}
+
+impl MergeGroup {
+    /// The group that the directory at `index` belongs to, if any.
+    pub fn for_directory(directories: &[MonitoredDirectoryConfig], index: usize) -> Option<Self> {
+        let name = group_name(directories.get(index)?)?;
+        let mut members = directories
+            .iter()
+            .enumerate()
+            .filter(|(_, directory)| group_name(directory) == Some(name))
+            .map(|(member, _)| member)
+            .collect::<Vec<_>>();
+        // Stable sort, so equal priorities keep their configuration order.
+        members.sort_by_key(|&member| std::cmp::Reverse(directories[member].priority));
+        Some(Self {
+            name: name.to_owned(),
+            members,
+        })
+    }
+}
+
+fn group_name(directory: &MonitoredDirectoryConfig) -> Option<&str> {
+    directory
+        .merge_group
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+}
+
+/// Every copy of `path` across its merge group, in preference order. The
+/// result always contains `path` itself; a path outside any group yields only
+/// that.
+pub async fn copies(directories: &[MonitoredDirectoryConfig], path: &Path) -> Vec<PathBuf> {
+    if !directories
+        .iter()
+        .any(|directory| group_name(directory).is_some())
+    {
+        return vec![path.to_path_buf()];
+    }
+    let mut roots = Vec::with_capacity(directories.len());
+    for directory in directories {
+        let configured = PathBuf::from(&directory.path);
+        roots.push(
+            tokio::fs::canonicalize(&configured)
+                .await
+                .unwrap_or(configured),
+        );
+    }
+    let owner = roots
+        .iter()
+        .enumerate()
+        .filter(|(_, root)| path.starts_with(root))
+        .max_by_key(|(_, root)| root.components().count());
+    let Some((index, relative)) =
+        owner.and_then(|(index, root)| Some((index, path.strip_prefix(root).ok()?)))
+    else {
+        return vec![path.to_path_buf()];
+    };
+    let Some(group) = MergeGroup::for_directory(directories, index) else {
+        return vec![path.to_path_buf()];
+    };
+    group
+        .members
+        .iter()
+        .map(|&member| {
+            if member == index {
+                path.to_path_buf()
+            } else {
+                roots[member].join(relative)
+            }
+        })
+        .collect()
+}
+
+/// Open the first copy that `open` accepts, returning it with the path that
+/// was used. When every copy fails, the last error is returned.
+pub async fn open_first<T, E, F, Fut>(
+    candidates: Vec<PathBuf>,
+    mut open: F,
+) -> Result<(PathBuf, T), E>
+where
+    E: From<std::io::Error>,
+    F: FnMut(PathBuf) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut last_error = None;
+    for candidate in candidates {
+        match open(candidate.clone()).await {
+            Ok(opened) => return Ok((candidate, opened)),
+            Err(error) => {
+                tracing::debug!(
+                    "Copy unavailable, trying the next one: {}",
+                    candidate.display()
+                );
+                last_error = Some(error);
+            }
+        }
+    }
+    Err(last_error.unwrap_or_else(|| std::io::Error::from(std::io::ErrorKind::NotFound).into()))
+}
+
+/// One merged folder: sub-folders and the winning file of every name, each
+/// in natural sort order.
+#[derive(Debug, Default)]
+pub struct OverlayListing {
+    pub directories: Vec<MediaDirectory>,
+    /// Indexed paths of the files to show.
+    pub files: Vec<String>,
+}
+
+/// List the same folder in every member root (`parents`, best-ranked first)
+/// and overlay them by name. Files whose IDs are in `hidden` are left out.
+pub fn overlay_directory<S: DatabaseReadSession>(
+    session: &mut S,
+    parents: &[String],
+    mime_family: Option<&str>,
+    hidden: &HashSet<i64>,
+) -> Result<OverlayListing> {
+    let mut directory_names = HashSet::new();
+    let mut file_names = HashSet::new();
+    let mut directories = Vec::new();
+    let mut files = Vec::new();
+    for parent in parents {
+        session.visit_direct_subdirectories(parent, mime_family, 0, usize::MAX, |directory| {
+            if directory_names.insert(directory.name().to_owned()) {
+                directories.push(MediaDirectory {
+                    path: crate::platform::filesystem::path_from_lossless_string(&directory.path()),
+                    name: directory.name().to_owned(),
+                });
+            }
+            Ok(())
+        })?;
+        let query = MediaFileQuery::Directory {
+            path: parent.clone(),
+            mime_family: mime_family.map(str::to_owned),
+        };
+        session.visit_files(&query, 0, usize::MAX, |file| {
+            if file.id().is_some_and(|id| hidden.contains(&id)) {
+                return Ok(());
+            }
+            if file_names.insert(file.filename().to_owned()) {
+                files.push((
+                    crate::natural_sort::collation_key(file.filename()),
+                    file.path().into_owned(),
+                ));
+            }
+            Ok(())
+        })?;
+    }
+    directories.sort_by_cached_key(|directory| crate::natural_sort::collation_key(&directory.name));
+    files.sort_by(|(left, _), (right, _)| left.cmp(right));
+    Ok(OverlayListing {
+        directories,
+        files: files.into_iter().map(|(_, path)| path).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ValidationMode, WatchMode};
+    use crate::database::memory::InMemoryDatabase;
+    use crate::database::{DatabaseManager, MediaFile, MediaRepository};
+    use std::sync::Arc;
+
+    fn directory(path: &Path, group: Option<&str>, priority: i32) -> MonitoredDirectoryConfig {
+        MonitoredDirectoryConfig {
+            path: path.to_string_lossy().into_owned(),
+            recursive: true,
+            case_sensitive: None,
+            extensions: None,
+            exclude_patterns: None,
+            validation_mode: ValidationMode::Warn,
+            watch_mode: WatchMode::Auto,
+            merge_group: group.map(str::to_owned),
+            priority,
+            media_types: None,
+            min_file_size: None,
+            min_file_age_seconds: None,
+        }
+    }
+
+    async fn listing(database: &Arc<InMemoryDatabase>, parents: &[PathBuf]) -> Vec<String> {
+        let parents = parents
+            .iter()
+            .map(|parent| parent.to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        database
+            .clone()
+            .read(move |session| overlay_directory(session, &parents, None, &HashSet::new()))
+            .await
+            .unwrap()
+            .files
+    }
+
+    #[tokio::test]
+    async fn members_rank_by_priority_then_configuration_order() {
+        let directories = [
+            directory(Path::new("/remote"), Some("library"), 0),
+            directory(Path::new("/other"), None, 0),
+            directory(Path::new("/local"), Some("library"), 10),
+            directory(Path::new("/backup"), Some("library"), 0),
+        ];
+
+        let group = MergeGroup::for_directory(&directories, 0).unwrap();
+        assert_eq!(group.name, "library");
+        assert_eq!(group.members, vec![2, 0, 3]);
+        assert!(MergeGroup::for_directory(&directories, 1).is_none());
+
+        assert_eq!(
+            copies(&directories, Path::new("/remote/Movies/film.mkv")).await,
+            vec![
+                PathBuf::from("/local/Movies/film.mkv"),
+                PathBuf::from("/remote/Movies/film.mkv"),
+                PathBuf::from("/backup/Movies/film.mkv"),
+            ]
+        );
+        assert_eq!(
+            copies(&directories, Path::new("/other/song.mp3")).await,
+            vec![PathBuf::from("/other/song.mp3")]
+        );
+    }
+
+    #[tokio::test]
+    async fn the_preferred_copy_hides_duplicates_until_it_is_removed() {
+        let database = Arc::new(InMemoryDatabase::new());
+        database.initialize().await.unwrap();
+        let local = PathBuf::from("/local/Movies");
+        let remote = PathBuf::from("/remote/Movies");
+        let parents = [local.clone(), remote.clone()];
+        let file = |path: PathBuf| MediaFile::new(path, 1, "video/mp4".to_owned());
+
+        database
+            .store_media_file(&file(remote.join("b.mp4")))
+            .await
+            .unwrap();
+        database
+            .store_media_file(&file(remote.join("a.mp4")))
+            .await
+            .unwrap();
+        assert_eq!(
+            listing(&database, &parents).await,
+            vec!["/remote/Movies/a.mp4", "/remote/Movies/b.mp4"]
+        );
+
+        // Adding the preferred copy replaces the remote one in place.
+        database
+            .store_media_file(&file(local.join("a.mp4")))
+            .await
+            .unwrap();
+        assert_eq!(
+            listing(&database, &parents).await,
+            vec!["/local/Movies/a.mp4", "/remote/Movies/b.mp4"]
+        );
+
+        // Removing it brings the hidden, still indexed, copy back.
+        database
+            .remove_media_file(&local.join("a.mp4"))
+            .await
+            .unwrap();
+        assert_eq!(
+            listing(&database, &parents).await,
+            vec!["/remote/Movies/a.mp4", "/remote/Movies/b.mp4"]
+        );
+    }
+
+    #[tokio::test]
+    async fn streaming_falls_back_when_the_preferred_copy_fails_to_open() {
+        let temp = tempfile::tempdir().unwrap();
+        let local = temp.path().join("local");
+        let remote = temp.path().join("remote");
+        std::fs::create_dir_all(local.join("Movies")).unwrap();
+        std::fs::create_dir_all(remote.join("Movies")).unwrap();
+        std::fs::write(local.join("Movies/film.mkv"), b"local").unwrap();
+        std::fs::write(remote.join("Movies/film.mkv"), b"remote").unwrap();
+        let directories = [
+            directory(&remote, Some("library"), 0),
+            directory(&local, Some("library"), 1),
+        ];
+        let requested = std::fs::canonicalize(remote.join("Movies/film.mkv")).unwrap();
+        let read = |path: PathBuf| async move { tokio::fs::read(path).await };
+
+        let (path, bytes) =
+            open_first::<_, std::io::Error, _, _>(copies(&directories, &requested).await, read)
+                .await
+                .unwrap();
+        assert_eq!(bytes, b"local");
+        assert!(path.starts_with(std::fs::canonicalize(&local).unwrap()));
+
+        std::fs::remove_file(local.join("Movies/film.mkv")).unwrap();
+        let (path, bytes) =
+            open_first::<_, std::io::Error, _, _>(copies(&directories, &requested).await, read)
+                .await
+                .unwrap();
+        assert_eq!(bytes, b"remote");
+        assert_eq!(path, requested);
+
+        std::fs::remove_file(remote.join("Movies/film.mkv")).unwrap();
+        let error = open_first::<Vec<u8>, std::io::Error, _, _>(
+            copies(&directories, &requested).await,
+            read,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+    }
+}
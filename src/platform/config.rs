@@ -466,6 +466,7 @@ impl PlatformConfig {
             "mpeg".to_string(),
             "3gp".to_string(),
             "ogv".to_string(),
+            "iso".to_string(),
             // Audio formats
             "mp3".to_string(),
             "flac".to_string(),
@@ -511,7 +512,6 @@ impl PlatformConfig {
                 // Linux typically supports all open formats
                 extensions.extend(vec![
                     "mka".to_string(), // Matroska audio
-                    "mks".to_string(), // Matroska subtitles
                 ]);
             }
 
@@ -519,7 +519,6 @@ impl PlatformConfig {
                 // BSD typically supports all open formats
                 extensions.extend(vec![
                     "mka".to_string(), // Matroska audio
-                    "mks".to_string(), // Matroska subtitles
                 ]);
             }
         }
@@ -19,6 +19,9 @@ pub enum PlatformError {
     #[error("Network configuration error: {0}")]
     NetworkConfig(String),
 
+    #[error("UDP port {port} is already in use by a process that does not allow sharing it. Stop the conflicting service or set VUIO_SSDP_PORT to a free port")]
+    PortInUse { port: u16 },
+
     #[error("File system access error: {0}")]
     FileSystemAccess(String),
 
@@ -267,6 +270,7 @@ impl PlatformError {
             PlatformError::Database(err) => err.is_recoverable(),
             PlatformError::Configuration(err) => err.is_recoverable(),
             PlatformError::NetworkConfig(_) => true,
+            PlatformError::PortInUse { .. } => false,
             PlatformError::FileSystemAccess(_) => false,
             PlatformError::DetectionFailed(_) => false,
             PlatformError::UnsupportedFeature(_) => false,
@@ -287,6 +291,10 @@ impl PlatformError {
                 "Verify network connectivity".to_string(),
                 format!("Review error details: {}", msg),
             ],
+            PlatformError::PortInUse { port } => vec![
+                format!("Find the process holding UDP port {port} (for example with `ss -ulpn` or `netstat -anu`)"),
+                "Stop it, or set VUIO_SSDP_PORT to a free port".to_string(),
+            ],
             _ => vec!["Contact support with error details".to_string()],
         }
     }
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
@@ -50,6 +51,10 @@ impl WindowsPathNormalizer {
     }
 
     /// Convert Windows path to canonical format (lowercase, forward slashes)
+    ///
+    /// Extended-length (`\\?\`) and device (`\\.\`) prefixes are dropped, so
+    /// `\\?\C:\Media` and `\\?\UNC\server\share` share a form with `C:\Media`
+    /// and `\\server\share`, which is what prefix queries and dedup compare.
     fn normalize_to_canonical(&self, path: &Path) -> Result<String, PathNormalizationError> {
         let path_str = path.to_string_lossy();
 
@@ -60,20 +65,30 @@ impl WindowsPathNormalizer {
             });
         }
 
+        // Convert to lowercase and use forward slashes
+        let mut canonical = path_str.to_lowercase().replace('\\', "/");
+
+        // Strip the extended-length or device prefix; `?` is only valid there
+        for prefix in ["//?/", "//./"] {
+            if let Some(rest) = canonical.strip_prefix(prefix) {
+                canonical = match rest.strip_prefix("unc/") {
+                    Some(share) => format!("//{share}"),
+                    None => rest.to_string(),
+                };
+                break;
+            }
+        }
+
         // Check for invalid characters
         let invalid_chars = ['\0', '<', '>', '"', '|', '?', '*'];
         for &invalid_char in &invalid_chars {
-            if path_str.contains(invalid_char) {
+            if canonical.contains(invalid_char) {
                 return Err(PathNormalizationError::InvalidCharacters {
                     path: path_str.to_string(),
                 });
             }
         }
 
-        // Convert to lowercase and use forward slashes
-        let mut canonical = path_str.to_lowercase();
-        canonical = canonical.replace('\\', "/");
-
         // Deduplicate slashes
         if canonical.starts_with("//") {
             // UNC path: preserve leading double slash, clean the rest
@@ -215,8 +230,7 @@ impl UnixPathNormalizer {
             p
         };
 
-        let canonical = final_path.to_string_lossy().to_string();
-        Ok(canonical)
+        Ok(path_to_lossless_string(&final_path).into_owned())
     }
 
     /// Convert canonical format back to Unix path format
@@ -228,7 +242,7 @@ impl UnixPathNormalizer {
         }
 
         // Unix canonical format is already the correct format
-        Ok(PathBuf::from(canonical))
+        Ok(path_from_lossless_string(canonical))
     }
 }
 
@@ -272,6 +286,151 @@ pub fn create_platform_path_normalizer() -> Box<dyn PathNormalizer> {
     }
 }
 
+/// Spell an absolute Windows path in its `\\?\` extended-length form so the
+/// Win32 file APIs accept it beyond the 260 character `MAX_PATH` limit.
+/// `C:\Media` becomes `\\?\C:\Media` and `\\server\share` becomes
+/// `\\?\UNC\server\share`. The kernel does not parse verbatim paths, so
+/// forward slashes, repeated separators and `.` segments are folded here.
+/// Relative and already prefixed paths are returned unchanged.
+pub fn extended_length_path(path: &Path) -> PathBuf {
+    let Some(path_str) = path.to_str() else {
+        return path.to_path_buf();
+    };
+    if has_verbatim_prefix(path_str) {
+        return path.to_path_buf();
+    }
+    let verbatim_tail = |rest: &str| {
+        rest.split(['\\', '/'])
+            .filter(|segment| !segment.is_empty() && *segment != ".")
+            .collect::<Vec<_>>()
+            .join(r"\")
+    };
+    let bytes = path_str.as_bytes();
+    if bytes.len() > 2 && matches!(&bytes[..2], b"\\\\" | b"//" | b"\\/" | b"/\\") {
+        return PathBuf::from(format!(r"\\?\UNC\{}", verbatim_tail(&path_str[2..])));
+    }
+    if bytes.len() >= 3
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && matches!(bytes[2], b'\\' | b'/')
+    {
+        return PathBuf::from(format!(
+            r"\\?\{}\{}",
+            &path_str[..2],
+            verbatim_tail(&path_str[3..])
+        ));
+    }
+    path.to_path_buf()
+}
+
+/// Spell a path the way a user would type it, dropping the `\\?\` and
+/// `\\.\` prefixes that [`extended_length_path`] and `fs::canonicalize` add
+/// on Windows. `\\?\UNC\server\share` becomes `\\server\share`. Device and
+/// volume GUID paths have no plain spelling and are returned unchanged.
+pub fn display_path(path: &Path) -> PathBuf {
+    let Some(path_str) = path.to_str() else {
+        return path.to_path_buf();
+    };
+    if !has_verbatim_prefix(path_str) {
+        return path.to_path_buf();
+    }
+    let rest = &path_str[4..];
+    if rest.len() > 4 && rest[..4].eq_ignore_ascii_case(r"UNC\") {
+        return PathBuf::from(format!(r"\\{}", &rest[4..]));
+    }
+    let bytes = rest.as_bytes();
+    if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+        return PathBuf::from(rest);
+    }
+    path.to_path_buf()
+}
+
+fn has_verbatim_prefix(path_str: &str) -> bool {
+    path_str.starts_with(r"\\?\") || path_str.starts_with(r"\\.\")
+}
+
+/// Code point that stands in for byte `0x00` in [`path_to_lossless_string`].
+/// Only bytes `0x80..=0xFF` can be invalid UTF-8, so escapes occupy the
+/// private-use range U+10FF80..=U+10FFFF.
+const RAW_BYTE_ESCAPE_BASE: u32 = 0x10_FF00;
+
+#[cfg(unix)]
+fn is_raw_byte_escape(character: char) -> bool {
+    (0x10_FF80..=0x10_FFFF).contains(&u32::from(character))
+}
+
+#[cfg(unix)]
+fn push_raw_bytes(encoded: &mut String, bytes: &[u8]) {
+    encoded.extend(bytes.iter().map(|byte| {
+        char::from_u32(RAW_BYTE_ESCAPE_BASE + u32::from(*byte))
+            .unwrap_or(char::REPLACEMENT_CHARACTER)
+    }));
+}
+
+/// Spell `path` as a string without losing anything, for database keys and
+/// object IDs. On Unix, each byte of a filename that is not valid UTF-8 (an
+/// old Latin-1 name, say) becomes one private-use code point, so the string
+/// still sorts and prefix-matches under its parent directory. A name that
+/// already contains one of those code points has its UTF-8 bytes escaped the
+/// same way, so it cannot be mistaken for raw bytes.
+/// [`path_from_lossless_string`] restores the original bytes. Other valid
+/// UTF-8 paths are returned unchanged.
+pub fn path_to_lossless_string(path: &Path) -> Cow<'_, str> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        let bytes = path.as_os_str().as_bytes();
+        if let Ok(valid) = std::str::from_utf8(bytes) {
+            if !valid.chars().any(is_raw_byte_escape) {
+                return Cow::Borrowed(valid);
+            }
+        }
+        let mut encoded = String::with_capacity(bytes.len() + 8);
+        for chunk in bytes.utf8_chunks() {
+            for character in chunk.valid().chars() {
+                if is_raw_byte_escape(character) {
+                    push_raw_bytes(&mut encoded, character.encode_utf8(&mut [0; 4]).as_bytes());
+                } else {
+                    encoded.push(character);
+                }
+            }
+            push_raw_bytes(&mut encoded, chunk.invalid());
+        }
+        Cow::Owned(encoded)
+    }
+    #[cfg(not(unix))]
+    {
+        path.to_string_lossy()
+    }
+}
+
+/// Inverse of [`path_to_lossless_string`].
+pub fn path_from_lossless_string(value: &str) -> PathBuf {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+        let raw_byte = |character: char| {
+            is_raw_byte_escape(character)
+                .then(|| (u32::from(character) - RAW_BYTE_ESCAPE_BASE) as u8)
+        };
+        if !value.chars().any(is_raw_byte_escape) {
+            return PathBuf::from(value);
+        }
+        let mut bytes = Vec::with_capacity(value.len());
+        for character in value.chars() {
+            match raw_byte(character) {
+                Some(byte) => bytes.push(byte),
+                None => bytes.extend_from_slice(character.encode_utf8(&mut [0; 4]).as_bytes()),
+            }
+        }
+        PathBuf::from(std::ffi::OsString::from_vec(bytes))
+    }
+    #[cfg(not(unix))]
+    {
+        PathBuf::from(value)
+    }
+}
+
 /// File system manager trait for cross-platform file operations
 #[async_trait::async_trait]
 pub trait FileSystemManager: Send + Sync {
@@ -589,10 +748,22 @@ pub const SUPPORTED_MEDIA_TYPES: &[(&str, &str)] = &[
     ("webm", "video/webm"),
     ("m4v", "video/x-m4v"),
     ("3gp", "video/3gpp"),
+    ("3g2", "video/3gpp2"),
     ("mpg", "video/mpeg"),
     ("mpeg", "video/mpeg"),
+    ("mpe", "video/mpeg"),
+    ("vob", "video/mpeg"),
     ("ts", "video/mp2t"),
     ("m2ts", "video/mp2t"),
+    ("mts", "video/mp2t"),
+    ("m2t", "video/mp2t"),
+    ("ogv", "video/ogg"),
+    ("asf", "video/x-ms-asf"),
+    ("wm", "video/x-ms-asf"),
+    ("dvr-ms", "video/x-ms-dvr"),
+    ("divx", "video/divx"),
+    ("f4v", "video/mp4"),
+    ("iso", crate::dvd::ISO_MIME_TYPE),
     // Audio formats
     ("mp3", "audio/mpeg"),
     ("flac", "audio/flac"),
@@ -601,8 +772,22 @@ pub const SUPPORTED_MEDIA_TYPES: &[(&str, &str)] = &[
     ("ogg", "audio/ogg"),
     ("wma", "audio/x-ms-wma"),
     ("m4a", "audio/mp4"),
+    ("m4b", "audio/mp4"),
+    ("m4p", "audio/mp4"),
     ("opus", "audio/opus"),
+    ("oga", "audio/ogg"),
+    ("weba", "audio/webm"),
+    ("mka", "audio/x-matroska"),
     ("aiff", "audio/aiff"),
+    ("aif", "audio/aiff"),
+    ("mp2", "audio/mpeg"),
+    ("ac3", "audio/ac3"),
+    ("dts", "audio/vnd.dts"),
+    ("amr", "audio/amr"),
+    ("ape", "audio/x-ape"),
+    ("wv", "audio/x-wavpack"),
+    ("dsf", "audio/x-dsf"),
+    ("dff", "audio/x-dff"),
     // Image formats
     ("jpg", "image/jpeg"),
     ("jpeg", "image/jpeg"),
@@ -610,11 +795,13 @@ pub const SUPPORTED_MEDIA_TYPES: &[(&str, &str)] = &[
     ("gif", "image/gif"),
     ("bmp", "image/bmp"),
     ("tiff", "image/tiff"),
+    ("tif", "image/tiff"),
     ("webp", "image/webp"),
     ("svg", "image/svg+xml"),
     ("heif", "image/heif"),
     ("heic", "image/heic"),
     ("avif", "image/avif"),
+    ("jxl", "image/jxl"),
 ];
 
 /// Get MIME type for a file based on its extension
@@ -637,6 +824,19 @@ pub fn get_mime_type_for_extension(extension: &str) -> String {
         .to_string()
 }
 
+/// Whether `path` has one of `extensions`. Extensions compare without regard
+/// to case on every platform, so `Movie.MP4` matches `mp4` even where the
+/// filesystem itself is case-sensitive; a leading `.` in the list is ignored.
+pub fn extension_matches(path: &Path, extensions: &[String]) -> bool {
+    let Some(extension) = path.extension().and_then(|extension| extension.to_str()) else {
+        return false;
+    };
+    let extension = extension.to_lowercase();
+    extensions
+        .iter()
+        .any(|allowed| allowed.trim_start_matches('.').to_lowercase() == extension)
+}
+
 /// Check if a file extension is supported for media serving
 pub fn is_supported_media_extension(extension: &str) -> bool {
     use std::sync::LazyLock;
@@ -666,6 +866,217 @@ pub fn get_supported_extensions() -> &'static HashSet<String> {
     &SUPPORTED_EXTENSIONS
 }
 
+/// Report whether `path` lives on a network filesystem (NFS, SMB/CIFS, AFP and
+/// similar) where native change notifications are not delivered for changes
+/// made by other clients. Returns `None` when the type cannot be determined.
+#[cfg(target_os = "linux")]
+pub fn is_network_filesystem(path: &Path) -> Option<bool> {
+    use std::os::unix::ffi::OsStrExt;
+
+    const NETWORK_MAGICS: &[u32] = &[
+        0x6969,      // NFS
+        0x517B,      // SMB
+        0xFF53_4D42, // CIFS
+        0xFE53_4D42, // SMB2
+        0x7375_7245, // CODA
+        0x5346_414F, // AFS
+        0x0102_1997, // 9P
+        0x00C3_6400, // CEPH
+    ];
+
+    let path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat = std::mem::MaybeUninit::<libc::statfs>::uninit();
+    // SAFETY: `path` is NUL-terminated and `stat` is only read after statfs succeeds.
+    if unsafe { libc::statfs(path.as_ptr(), stat.as_mut_ptr()) } != 0 {
+        return None;
+    }
+    // SAFETY: statfs returned success, so it fully initialized `stat`.
+    let stat = unsafe { stat.assume_init() };
+    Some(NETWORK_MAGICS.contains(&(stat.f_type as u32)))
+}
+
+/// Report whether `path` lives on a network filesystem (NFS, SMB/CIFS, AFP and
+/// similar) where native change notifications are not delivered for changes
+/// made by other clients. Returns `None` when the type cannot be determined.
+#[cfg(any(target_os = "macos", target_os = "freebsd"))]
+pub fn is_network_filesystem(path: &Path) -> Option<bool> {
+    use std::os::unix::ffi::OsStrExt;
+
+    const NETWORK_TYPES: &[&str] = &["nfs", "smbfs", "afpfs", "webdav", "cifs"];
+
+    let path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat = std::mem::MaybeUninit::<libc::statfs>::uninit();
+    // SAFETY: `path` is NUL-terminated and `stat` is only read after statfs succeeds.
+    if unsafe { libc::statfs(path.as_ptr(), stat.as_mut_ptr()) } != 0 {
+        return None;
+    }
+    // SAFETY: statfs returned success, so it fully initialized `stat`.
+    let stat = unsafe { stat.assume_init() };
+    // SAFETY: the kernel NUL-terminates f_fstypename.
+    let name = unsafe { std::ffi::CStr::from_ptr(stat.f_fstypename.as_ptr()) };
+    Some(NETWORK_TYPES.contains(&name.to_str().ok()?))
+}
+
+/// Report whether `path` lives on a network share. Only UNC paths are
+/// recognized; mapped drive letters report as local.
+#[cfg(target_os = "windows")]
+pub fn is_network_filesystem(path: &Path) -> Option<bool> {
+    let path = path.to_string_lossy();
+    Some(
+        path.starts_with(r"\\?\UNC\")
+            || (path.starts_with(r"\\")
+                && !path.starts_with(r"\\?\")
+                && !path.starts_with(r"\\.\")),
+    )
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "freebsd",
+    target_os = "windows"
+)))]
+pub fn is_network_filesystem(_path: &Path) -> Option<bool> {
+    None
+}
+
+/// The mount points at, above or below `path` that should be mounted but are
+/// not: `/etc/fstab` entries, or on macOS a `/Volumes` disk, missing from the
+/// current mount table. An unmounted drive leaves its mount point behind as a
+/// plain directory, so the path existing says nothing. Empty when every
+/// expected mount is present or the mount table cannot be read.
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "freebsd"))]
+pub fn missing_mount_points(path: &Path) -> Vec<PathBuf> {
+    let mut expected = std::fs::read_to_string("/etc/fstab")
+        .map(|fstab| fstab_mount_points(&fstab))
+        .unwrap_or_default();
+    if cfg!(target_os = "macos") {
+        let mut components = path.components();
+        if let (Some(root), Some(volumes), Some(volume)) =
+            (components.next(), components.next(), components.next())
+        {
+            if volumes.as_os_str() == "Volumes" {
+                expected.push(
+                    [root.as_os_str(), volumes.as_os_str(), volume.as_os_str()]
+                        .iter()
+                        .collect(),
+                );
+            }
+        }
+    }
+    current_mount_points()
+        .map(|mounted| unmounted_mount_points(path, &expected, &mounted))
+        .unwrap_or_default()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "freebsd")))]
+pub fn missing_mount_points(_path: &Path) -> Vec<PathBuf> {
+    Vec::new()
+}
+
+/// Mount points listed in an fstab, without `/` and swap entries.
+#[cfg_attr(
+    not(any(target_os = "linux", target_os = "macos", target_os = "freebsd")),
+    allow(dead_code)
+)]
+fn fstab_mount_points(fstab: &str) -> Vec<PathBuf> {
+    fstab
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(unescape_mount_path)
+        .filter(|target| target.starts_with('/') && target != "/")
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Decode the octal escapes (`\040` for a space) fstab and `/proc/mounts` use.
+#[cfg_attr(
+    not(any(target_os = "linux", target_os = "macos", target_os = "freebsd")),
+    allow(dead_code)
+)]
+fn unescape_mount_path(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escape = bytes.get(index + 1..index + 4).filter(|digits| {
+            bytes[index] == b'\\' && digits.iter().all(|digit| (b'0'..=b'7').contains(digit))
+        });
+        match escape
+            .and_then(|digits| u8::from_str_radix(std::str::from_utf8(digits).ok()?, 8).ok())
+        {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 4;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// The deepest of `expected` at or above `path` when it is not in `mounted`,
+/// followed by those of `expected` below `path` that are not.
+#[cfg_attr(
+    not(any(target_os = "linux", target_os = "macos", target_os = "freebsd")),
+    allow(dead_code)
+)]
+fn unmounted_mount_points(path: &Path, expected: &[PathBuf], mounted: &[PathBuf]) -> Vec<PathBuf> {
+    let ancestor = expected
+        .iter()
+        .filter(|mount_point| path.starts_with(mount_point))
+        .max_by_key(|mount_point| mount_point.components().count());
+    let below = expected
+        .iter()
+        .filter(|mount_point| mount_point.starts_with(path) && mount_point.as_path() != path);
+    ancestor
+        .into_iter()
+        .chain(below)
+        .filter(|mount_point| !mounted.contains(mount_point))
+        .cloned()
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn current_mount_points() -> Option<Vec<PathBuf>> {
+    let mounts = std::fs::read_to_string("/proc/self/mounts").ok()?;
+    Some(
+        mounts
+            .lines()
+            .filter_map(|line| line.split_whitespace().nth(1))
+            .map(|target| PathBuf::from(unescape_mount_path(target)))
+            .collect(),
+    )
+}
+
+#[cfg(any(target_os = "macos", target_os = "freebsd"))]
+fn current_mount_points() -> Option<Vec<PathBuf>> {
+    let mut mounts: *mut libc::statfs = std::ptr::null_mut();
+    // SAFETY: getmntinfo points `mounts` at `count` statfs records in a
+    // buffer it owns; they are copied out before anything can call it again.
+    let count = unsafe { libc::getmntinfo(&mut mounts, libc::MNT_NOWAIT) };
+    if count <= 0 || mounts.is_null() {
+        return None;
+    }
+    // SAFETY: see above; `count` is positive.
+    let mounts = unsafe { std::slice::from_raw_parts(mounts, count as usize) };
+    Some(
+        mounts
+            .iter()
+            .map(|mount| {
+                // SAFETY: the kernel NUL-terminates f_mntonname.
+                let target = unsafe { std::ffi::CStr::from_ptr(mount.f_mntonname.as_ptr()) };
+                PathBuf::from(target.to_string_lossy().into_owned())
+            })
+            .collect(),
+    )
+}
+
 /// Base implementation of FileSystemManager with common functionality
 pub struct BaseFileSystemManager {
     /// Whether the file system is case-sensitive
@@ -824,6 +1235,9 @@ impl BaseFileSystemManager {
                     track_number: None,
                     year: None,
                     album_artist: None,
+                    compilation: false,
+                    content_hash: None,
+                    missing_since: None,
                     subtitle_available,
                     created_at: now,
                     updated_at: now,
@@ -916,24 +1330,7 @@ impl FileSystemManager for BaseFileSystemManager {
     }
 
     fn matches_extension(&self, path: &Path, extensions: &[String]) -> bool {
-        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-            let ext_to_check = if self.case_sensitive {
-                ext.to_string()
-            } else {
-                ext.to_lowercase()
-            };
-
-            extensions.iter().any(|allowed| {
-                let allowed_ext = if self.case_sensitive {
-                    allowed.clone()
-                } else {
-                    allowed.to_lowercase()
-                };
-                ext_to_check == allowed_ext
-            })
-        } else {
-            false
-        }
+        extension_matches(path, extensions)
     }
 }
 
@@ -960,6 +1357,59 @@ pub fn create_platform_filesystem_manager() -> Box<dyn FileSystemManager> {
     }
 }
 
+/// Album artist given to compilation tracks that carry no album artist tag.
+pub(crate) const COMPILATION_ALBUM_ARTIST: &str = "Various Artists";
+
+/// Read the iTunes-style compilation flag (`TCMP`, `cpil` or `COMPILATION`).
+///
+/// audiotags does not expose the flag, so the concrete tag is briefly taken
+/// apart to reach the underlying format crate and then put back unchanged.
+fn read_compilation_flag(tag: &mut (dyn audiotags::AudioTag + Send + Sync)) -> bool {
+    use audiotags::AudioTagConfig;
+
+    fn flag_value(value: &str) -> bool {
+        matches!(value.trim(), "1" | "true" | "TRUE" | "True")
+    }
+
+    let config = *tag.config();
+    let any = tag.to_any_mut();
+    if let Some(wrapper) = any.downcast_mut::<audiotags::Id3v2Tag>() {
+        let inner = id3::Tag::from(std::mem::take(wrapper));
+        let flag = inner
+            .frames()
+            .filter(|frame| frame.id() == "TCMP")
+            .filter_map(|frame| frame.content().text())
+            .any(flag_value);
+        *wrapper = inner.into();
+        wrapper.set_config(config);
+        flag
+    } else if let Some(wrapper) = any.downcast_mut::<audiotags::Mp4Tag>() {
+        let inner = mp4ameta::Tag::from(std::mem::take(wrapper));
+        let flag = inner.compilation();
+        *wrapper = inner.into();
+        wrapper.set_config(config);
+        flag
+    } else if let Some(wrapper) = any.downcast_mut::<audiotags::FlacTag>() {
+        let inner = metaflac::Tag::from(std::mem::take(wrapper));
+        let flag = inner
+            .get_vorbis("COMPILATION")
+            .is_some_and(|mut values| values.any(flag_value));
+        *wrapper = inner.into();
+        wrapper.set_config(config);
+        flag
+    } else {
+        false
+    }
+}
+
+/// Group compilation tracks under one album artist when the file names none,
+/// so per-track artists do not scatter the album across the album artist view.
+fn apply_compilation_album_artist(media_file: &mut MediaFile) {
+    if media_file.compilation && media_file.album_artist.is_none() {
+        media_file.album_artist = Some(COMPILATION_ALBUM_ARTIST.to_string());
+    }
+}
+
 /// Extract audio metadata using audiotags library
 pub(crate) async fn extract_audio_metadata(
     media_file: &mut MediaFile,
@@ -977,13 +1427,18 @@ pub(crate) async fn extract_audio_metadata(
     let path = media_file.path.clone();
 
     // Wrap the synchronous I/O operation in spawn_blocking to prevent blocking the async runtime
-    let metadata_result =
-        tokio::task::spawn_blocking(move || audiotags::Tag::new().read_from_path(&path)).await;
+    let metadata_result = tokio::task::spawn_blocking(move || {
+        audiotags::Tag::new().read_from_path(&path).map(|mut tag| {
+            let compilation = read_compilation_flag(tag.as_mut());
+            (tag, compilation)
+        })
+    })
+    .await;
     drop(permit);
 
     // Handle the result from spawn_blocking
     match metadata_result {
-        Ok(Ok(tag)) => {
+        Ok(Ok((tag, compilation))) => {
             // Extract basic metadata
             if let Some(title) = tag.title() {
                 media_file.title = Some(title.to_string());
@@ -1016,6 +1471,9 @@ pub(crate) async fn extract_audio_metadata(
                 media_file.album_artist = Some(album_artist.to_string());
             }
 
+            media_file.compilation = compilation;
+            apply_compilation_album_artist(media_file);
+
             // Extract duration if available
             if let Some(duration) = tag.duration() {
                 media_file.duration = Some(Duration::from_secs(duration as u64));
@@ -1142,6 +1600,53 @@ fn fallback_parse_filename(media_file: &mut MediaFile) {
 mod tests {
     use super::*;
 
+    #[test]
+    fn a_root_below_an_unmounted_fstab_entry_is_reported() {
+        let fstab = "# <file system> <mount point> <type>\n\
+                     UUID=1234 / ext4 defaults 0 1\n\
+                     /dev/sdb1 /mnt/media ext4 nofail 0 2\n\
+                     //nas/films /mnt/media/nas\\040films cifs noauto 0 0\n\
+                     /swapfile none swap sw 0 0\n";
+        let expected = fstab_mount_points(fstab);
+        assert_eq!(
+            expected,
+            [
+                PathBuf::from("/mnt/media"),
+                PathBuf::from("/mnt/media/nas films")
+            ]
+        );
+
+        let mounted = [PathBuf::from("/"), PathBuf::from("/mnt/media")];
+        let films = Path::new("/mnt/media/nas films/2024");
+        assert_eq!(
+            unmounted_mount_points(films, &expected, &mounted),
+            [PathBuf::from("/mnt/media/nas films")]
+        );
+        assert!(
+            unmounted_mount_points(Path::new("/mnt/media/music"), &expected, &mounted).is_empty()
+        );
+        assert_eq!(
+            unmounted_mount_points(Path::new("/mnt/media/music"), &expected, &mounted[..1]),
+            [PathBuf::from("/mnt/media")]
+        );
+        assert!(
+            unmounted_mount_points(Path::new("/home/music"), &expected, &mounted[..1]).is_empty()
+        );
+
+        // A root above an unmounted share keeps the files below it.
+        assert_eq!(
+            unmounted_mount_points(Path::new("/mnt/media"), &expected, &mounted),
+            [PathBuf::from("/mnt/media/nas films")]
+        );
+        assert_eq!(
+            unmounted_mount_points(Path::new("/mnt"), &expected, &mounted[..1]),
+            [
+                PathBuf::from("/mnt/media"),
+                PathBuf::from("/mnt/media/nas films")
+            ]
+        );
+    }
+
     #[test]
     fn test_mime_type_detection() {
         assert_eq!(get_mime_type_for_extension("mp4"), "video/mp4");
@@ -1149,6 +1654,11 @@ mod tests {
         assert_eq!(get_mime_type_for_extension("mp3"), "audio/mpeg");
         assert_eq!(get_mime_type_for_extension("ts"), "video/mp2t");
         assert_eq!(get_mime_type_for_extension("m2ts"), "video/mp2t");
+        assert_eq!(get_mime_type_for_extension("MTS"), "video/mp2t");
+        assert_eq!(get_mime_type_for_extension("opus"), "audio/opus");
+        assert_eq!(get_mime_type_for_extension("m4b"), "audio/mp4");
+        assert_eq!(get_mime_type_for_extension("heic"), "image/heic");
+        assert_eq!(get_mime_type_for_extension("ogv"), "video/ogg");
         assert_eq!(
             get_mime_type_for_extension("unknown"),
             "application/octet-stream"
@@ -1166,6 +1676,27 @@ mod tests {
         assert!(!is_supported_media_extension("unknown"));
     }
 
+    #[test]
+    fn default_extensions_all_have_a_mime_type() {
+        let config = crate::platform::config::PlatformConfig::for_current_platform();
+        let defaults = config.get_default_media_extensions().into_iter().chain(
+            crate::config::AppConfig::default()
+                .media
+                .supported_extensions,
+        );
+        for extension in defaults {
+            assert!(
+                is_supported_media_extension(&extension),
+                "{extension} is scanned by default but not in SUPPORTED_MEDIA_TYPES"
+            );
+            assert_ne!(
+                get_mime_type_for_extension(&extension),
+                "application/octet-stream",
+                "{extension}"
+            );
+        }
+    }
+
     #[test]
     fn test_path_validation() {
         let manager = BaseFileSystemManager::new(true);
@@ -1202,16 +1733,62 @@ mod tests {
         assert!(case_insensitive.paths_equal(path1, path2));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_lossless_path_strings_keep_raw_bytes() {
+        use std::os::unix::ffi::OsStringExt;
+
+        let utf8 = Path::new("/media/Café/déjà vu.mp3");
+        assert!(matches!(path_to_lossless_string(utf8), Cow::Borrowed(_)));
+        assert_eq!(
+            path_from_lossless_string(&path_to_lossless_string(utf8)),
+            utf8
+        );
+
+        let latin1 = PathBuf::from(std::ffi::OsString::from_vec(
+            b"/media/Caf\xe9/d\xe9j\xe0 vu.mp3".to_vec(),
+        ));
+        let encoded = path_to_lossless_string(&latin1);
+        assert!(!encoded.contains(char::REPLACEMENT_CHARACTER));
+        assert!(encoded.starts_with("/media/Caf"));
+        assert_eq!(path_from_lossless_string(&encoded), latin1);
+        // Two different byte spellings never share a key.
+        let other = PathBuf::from(std::ffi::OsString::from_vec(
+            b"/media/Caf\xe8/d\xe9j\xe0 vu.mp3".to_vec(),
+        ));
+        assert_ne!(path_to_lossless_string(&other), encoded);
+
+        // A valid name that already uses the escape code points is escaped
+        // too, rather than decoded as if it held raw bytes.
+        for literal in [
+            "/media/\u{10FF80}.mp3",
+            "/media/Caf\u{10FFE9}/\u{10FFFF}.mp3",
+        ] {
+            let literal = Path::new(literal);
+            let encoded = path_to_lossless_string(literal);
+            assert_ne!(encoded, literal.to_str().unwrap());
+            assert_eq!(path_from_lossless_string(&encoded), literal);
+        }
+        let escaped = Path::new("/media/Caf\u{10FFE9}/d\u{10FFE9}j\u{10FFE0} vu.mp3");
+        assert_ne!(path_to_lossless_string(escaped), encoded);
+    }
+
     #[test]
     fn test_extension_matching() {
         let case_sensitive = BaseFileSystemManager::new(true);
         let case_insensitive = BaseFileSystemManager::new(false);
 
         let path = Path::new("test.MP4");
-        let extensions = vec!["mp4".to_string(), "avi".to_string()];
+        let extensions = vec!["mp4".to_string(), ".JPG".to_string()];
 
-        assert!(!case_sensitive.matches_extension(path, &extensions));
+        // Extensions ignore case even on case-sensitive filesystems.
+        assert!(case_sensitive.matches_extension(path, &extensions));
         assert!(case_insensitive.matches_extension(path, &extensions));
+        assert!(case_sensitive.matches_extension(Path::new("photo.jpg"), &extensions));
+        assert!(!case_sensitive.matches_extension(Path::new("test.mp4.part"), &extensions));
+        assert!(!case_sensitive.matches_extension(Path::new("MP4"), &extensions));
+        assert_eq!(get_mime_type_for_extension("MKV"), "video/x-matroska");
+        assert!(is_supported_media_extension("FLAC"));
     }
 
     #[test]
@@ -1234,6 +1811,9 @@ mod tests {
             track_number: None,
             year: None,
             album_artist: None,
+            compilation: false,
+            content_hash: None,
+            missing_since: None,
             subtitle_available: false,
             created_at: SystemTime::UNIX_EPOCH,
             updated_at: SystemTime::UNIX_EPOCH,
@@ -1258,6 +1838,9 @@ mod tests {
             track_number: None,
             year: None,
             album_artist: None,
+            compilation: false,
+            content_hash: None,
+            missing_since: None,
             subtitle_available: false,
             created_at: SystemTime::UNIX_EPOCH,
             updated_at: SystemTime::UNIX_EPOCH,
@@ -1282,6 +1865,9 @@ mod tests {
             track_number: None,
             year: None,
             album_artist: None,
+            compilation: false,
+            content_hash: None,
+            missing_since: None,
             subtitle_available: false,
             created_at: SystemTime::UNIX_EPOCH,
             updated_at: SystemTime::UNIX_EPOCH,
@@ -1292,6 +1878,46 @@ mod tests {
         assert_eq!(f3.title.as_deref(), Some("Song Title"));
     }
 
+    #[tokio::test]
+    async fn compilation_tracks_group_under_various_artists() {
+        use id3::TagLike;
+
+        let dir = tempfile::tempdir().unwrap();
+        let write_track = |name: &str, compilation: bool, album_artist: Option<&str>| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, b"").unwrap();
+            let mut tag = id3::Tag::new();
+            tag.set_artist("Track Artist");
+            tag.set_album("Hits");
+            if compilation {
+                tag.set_text("TCMP", "1");
+            }
+            if let Some(album_artist) = album_artist {
+                tag.set_album_artist(album_artist);
+            }
+            tag.write_to_path(&path, id3::Version::Id3v24).unwrap();
+            MediaFile::new(path, 0, "audio/mpeg".to_string())
+        };
+
+        let mut untagged = write_track("untagged.mp3", true, None);
+        let mut tagged = write_track("tagged.mp3", true, Some("The Label"));
+        let mut regular = write_track("regular.mp3", false, None);
+        for file in [&mut untagged, &mut tagged, &mut regular] {
+            extract_audio_metadata(file).await.unwrap();
+        }
+
+        assert!(untagged.compilation);
+        assert_eq!(
+            untagged.album_artist.as_deref(),
+            Some(COMPILATION_ALBUM_ARTIST)
+        );
+        assert!(tagged.compilation);
+        assert_eq!(tagged.album_artist.as_deref(), Some("The Label"));
+        assert!(!regular.compilation);
+        assert_eq!(regular.album_artist, None);
+        assert_eq!(untagged.artist.as_deref(), Some("Track Artist"));
+    }
+
     // PathNormalizer tests
     mod path_normalizer_tests {
         use super::*;
@@ -86,6 +86,10 @@ impl WindowsFileSystemManager {
 
     /// Validate Windows-specific path constraints
     fn validate_windows_path(&self, path: &Path) -> Result<(), FileSystemError> {
+        // The `\\?\` prefix is ours to add, so rules apply to the plain spelling
+        let display_path = super::display_path(path);
+        let path = display_path.as_path();
+
         // First run common validation
         self.base.validate_path_common(path)?;
 
@@ -130,14 +134,7 @@ impl WindowsFileSystemManager {
             }
         }
 
-        // Check path length limits
-        if path_str.len() > 260 && !path_str.starts_with(r"\\?\") {
-            return Err(FileSystemError::PathTooLong {
-                path: path.display().to_string(),
-                details: format!("Path length is {} characters, which exceeds the Windows MAX_PATH limit of 260 characters. Consider using shorter names or enabling long path support.", path_str.len()),
-            });
-        }
-
+        // No MAX_PATH check: file operations go through `extended_length_path`
         Ok(())
     }
 
@@ -150,7 +147,7 @@ impl WindowsFileSystemManager {
         let metadata = tokio::fs::OpenOptions::new()
             .read(true)
             .write(false)
-            .open(path)
+            .open(super::extended_length_path(path))
             .await?
             .metadata()
             .await?;
@@ -352,8 +349,16 @@ impl FileSystemManager for WindowsFileSystemManager {
             });
         }
 
-        // Use the base implementation for scanning
-        self.base.scan_directory_common(path).await
+        // Scan through the extended-length spelling, but hand back the plain
+        // paths that logs, the database and DIDL output show to users
+        let mut media_files = self
+            .base
+            .scan_directory_common(&super::extended_length_path(path))
+            .await?;
+        for media_file in &mut media_files {
+            media_file.path = super::display_path(&media_file.path);
+        }
+        Ok(media_files)
     }
 
     fn normalize_path(&self, path: &Path) -> PathBuf {
@@ -365,15 +370,16 @@ impl FileSystemManager for WindowsFileSystemManager {
     }
 
     async fn is_accessible(&self, path: &Path) -> bool {
+        let path = super::extended_length_path(path);
         // For directories, check if we can read the directory
         if path.is_dir() {
-            tokio::fs::read_dir(path).await.is_ok()
+            tokio::fs::read_dir(&path).await.is_ok()
         } else {
             // For files, try to access the path with read-only access
             tokio::fs::OpenOptions::new()
                 .read(true)
                 .write(false)
-                .open(path)
+                .open(&path)
                 .await
                 .is_ok()
         }
@@ -384,7 +390,7 @@ impl FileSystemManager for WindowsFileSystemManager {
         let metadata = tokio::fs::OpenOptions::new()
             .read(true)
             .write(false)
-            .open(path)
+            .open(super::extended_length_path(path))
             .await?
             .metadata()
             .await?;
@@ -399,14 +405,15 @@ impl FileSystemManager for WindowsFileSystemManager {
 
         let is_hidden = self.is_hidden_windows(path);
 
+        let display_path = super::display_path(path);
         let mut platform_metadata = HashMap::new();
         platform_metadata.insert(
             "is_unc_path".to_string(),
-            self.is_unc_path(path).to_string(),
+            self.is_unc_path(&display_path).to_string(),
         );
         platform_metadata.insert(
             "has_drive_letter".to_string(),
-            self.has_drive_letter(path).to_string(),
+            self.has_drive_letter(&display_path).to_string(),
         );
 
         Ok(FileInfo {
@@ -439,7 +446,7 @@ impl FileSystemManager for WindowsFileSystemManager {
 
     async fn canonicalize_path(&self, path: &Path) -> Result<String, FileSystemError> {
         // First resolve symbolic links and relative components
-        match fs::canonicalize(path).await {
+        match fs::canonicalize(super::extended_length_path(path)).await {
             Ok(canonical_path) => {
                 // Then apply Windows-specific path normalization to the resolved path
                 self.path_normalizer
@@ -473,15 +480,7 @@ impl FileSystemManager for WindowsFileSystemManager {
     }
 
     fn matches_extension(&self, path: &Path, extensions: &[String]) -> bool {
-        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-            // Windows is case-insensitive
-            let ext_lower = ext.to_lowercase();
-            extensions
-                .iter()
-                .any(|allowed| allowed.to_lowercase() == ext_lower)
-        } else {
-            false
-        }
+        super::extension_matches(path, extensions)
     }
 }
 
@@ -653,6 +652,29 @@ mod tests {
             .is_ok());
     }
 
+    #[test]
+    fn test_extended_length_paths() {
+        let manager = WindowsFileSystemManager::new();
+
+        assert!(manager
+            .validate_windows_path(Path::new(r"\\?\C:\Media\Anime"))
+            .is_ok());
+        assert!(manager
+            .validate_windows_path(Path::new(r"\\?\UNC\server\share\Anime"))
+            .is_ok());
+        assert!(manager
+            .validate_windows_path(Path::new(r"\\?\C:\Media\file?name"))
+            .is_err());
+
+        // Past MAX_PATH with or without the prefix
+        let long_path = format!(r"C:\Media{}", r"\Season 01".repeat(30));
+        assert!(long_path.len() > 300);
+        assert!(manager.validate_windows_path(Path::new(&long_path)).is_ok());
+        assert!(manager
+            .validate_windows_path(&super::super::extended_length_path(Path::new(&long_path)))
+            .is_ok());
+    }
+
     #[test]
     fn test_invalid_colon_usage() {
         let manager = WindowsFileSystemManager::new();
@@ -197,7 +197,7 @@ impl PlatformInfo {
     }
 
     /// Detect available network interfaces
-    async fn detect_network_interfaces() -> Result<Vec<NetworkInterface>, PlatformError> {
+    pub async fn detect_network_interfaces() -> Result<Vec<NetworkInterface>, PlatformError> {
         #[cfg(target_os = "windows")]
         {
             use crate::platform::network::windows::WindowsNetworkManager;
@@ -292,39 +292,17 @@ impl PlatformInfo {
 
     /// Get the best network interface for DLNA operations using a deterministic priority.
     pub fn get_primary_interface(&self) -> Option<&NetworkInterface> {
-        // A simple, deterministic approach to finding the best interface.
-
-        // Priority 1: Find the first active, non-loopback Ethernet interface with a private IPv4 address.
-        if let Some(iface) = self.network_interfaces.iter().find(|i| {
-            i.is_up
-                && !i.is_loopback
-                && i.interface_type == InterfaceType::Ethernet
-                && matches!(i.ip_address, IpAddr::V4(ip) if ip.is_private())
-        }) {
-            return Some(iface);
-        }
-
-        // Priority 2: Find the first active, non-loopback Wi-Fi interface with a private IPv4 address.
-        if let Some(iface) = self.network_interfaces.iter().find(|i| {
-            i.is_up
-                && !i.is_loopback
-                && i.interface_type == InterfaceType::WiFi
-                && matches!(i.ip_address, IpAddr::V4(ip) if ip.is_private())
-        }) {
-            return Some(iface);
-        }
-
-        // Priority 3: Find any other active, non-loopback interface with a private IPv4 address.
-        if let Some(iface) = self.network_interfaces.iter().find(|i| {
-            i.is_up && !i.is_loopback && matches!(i.ip_address, IpAddr::V4(ip) if ip.is_private())
-        }) {
-            return Some(iface);
-        }
+        primary_interface(&self.network_interfaces)
+    }
 
-        // Priority 4: As a last resort, take the first active, non-loopback interface of any kind.
-        self.network_interfaces
-            .iter()
-            .find(|i| i.is_up && !i.is_loopback)
+    /// The primary interface, restricted to `preferred_subnet` (a CIDR) when
+    /// an active interface lies inside it. Without one the usual priority
+    /// order applies.
+    pub fn get_primary_interface_in(
+        &self,
+        preferred_subnet: Option<&str>,
+    ) -> Option<&NetworkInterface> {
+        primary_interface_in(&self.network_interfaces, preferred_subnet)
     }
 
     /// Check if the platform supports a specific feature
@@ -336,6 +314,131 @@ impl PlatformInfo {
     }
 }
 
+/// The best of `interfaces` for DLNA operations, using a deterministic priority.
+pub fn primary_interface(interfaces: &[NetworkInterface]) -> Option<&NetworkInterface> {
+    // A simple, deterministic approach to finding the best interface.
+
+    // Priority 1: Find the first active, non-loopback Ethernet interface with a private IPv4 address.
+    if let Some(iface) = interfaces.iter().find(|i| {
+        i.is_up
+            && !i.is_loopback
+            && i.interface_type == InterfaceType::Ethernet
+            && matches!(i.ip_address, IpAddr::V4(ip) if ip.is_private())
+    }) {
+        return Some(iface);
+    }
+
+    // Priority 2: Find the first active, non-loopback Wi-Fi interface with a private IPv4 address.
+    if let Some(iface) = interfaces.iter().find(|i| {
+        i.is_up
+            && !i.is_loopback
+            && i.interface_type == InterfaceType::WiFi
+            && matches!(i.ip_address, IpAddr::V4(ip) if ip.is_private())
+    }) {
+        return Some(iface);
+    }
+
+    // Priority 3: Find any other active, non-loopback interface with a private IPv4 address.
+    if let Some(iface) = interfaces.iter().find(|i| {
+        i.is_up && !i.is_loopback && matches!(i.ip_address, IpAddr::V4(ip) if ip.is_private())
+    }) {
+        return Some(iface);
+    }
+
+    // Priority 4: As a last resort, take the first active, non-loopback interface of any kind.
+    interfaces.iter().find(|i| i.is_up && !i.is_loopback)
+}
+
+/// [`primary_interface`], restricted to `preferred_subnet` (a CIDR) when an
+/// active interface lies inside it.
+pub fn primary_interface_in<'a>(
+    interfaces: &'a [NetworkInterface],
+    preferred_subnet: Option<&str>,
+) -> Option<&'a NetworkInterface> {
+    let preferred = preferred_subnet.and_then(|subnet| subnet.parse::<ipnet::IpNet>().ok());
+    if let Some(subnet) = preferred {
+        let mut candidates = interfaces
+            .iter()
+            .filter(|i| i.is_up && !i.is_loopback && subnet.contains(&i.ip_address));
+        if let Some(iface) = candidates
+            .clone()
+            .find(|i| i.interface_type == InterfaceType::Ethernet)
+            .or_else(|| candidates.next())
+        {
+            return Some(iface);
+        }
+    }
+    primary_interface(interfaces)
+}
+
+/// The interfaces named by `selectors`, each an interface name or IP address,
+/// in the order given. A selector that matches nothing is an error listing
+/// the detected interfaces.
+pub fn select_interfaces(
+    interfaces: &[NetworkInterface],
+    selectors: &[String],
+) -> Result<Vec<NetworkInterface>, String> {
+    let mut selected: Vec<NetworkInterface> = Vec::new();
+    for selector in selectors {
+        let selector = selector.trim();
+        let matched = interfaces.iter().filter(|interface| {
+            interface.name == selector || interface.ip_address.to_string() == selector
+        });
+        let mut found = false;
+        for interface in matched {
+            found = true;
+            if !selected
+                .iter()
+                .any(|existing| existing.ip_address == interface.ip_address)
+            {
+                selected.push(interface.clone());
+            }
+        }
+        if !found {
+            let available = interfaces
+                .iter()
+                .map(|interface| format!("{} ({})", interface.name, interface.ip_address))
+                .collect::<Vec<_>>();
+            return Err(format!(
+                "network interface {selector:?} was not found; available interfaces: {}",
+                if available.is_empty() {
+                    "none".to_owned()
+                } else {
+                    available.join(", ")
+                }
+            ));
+        }
+    }
+    Ok(selected)
+}
+
+/// The interface of `interfaces` that shares the longest address prefix with
+/// `peer`, i.e. the one on the peer's subnet. Ties go to the first listed.
+pub fn nearest_interface(
+    interfaces: &[NetworkInterface],
+    peer: IpAddr,
+) -> Option<&NetworkInterface> {
+    let common_prefix = |address: IpAddr| match (address, peer) {
+        (IpAddr::V4(address), IpAddr::V4(peer)) => {
+            (u32::from(address) ^ u32::from(peer)).leading_zeros()
+        }
+        (IpAddr::V6(address), IpAddr::V6(peer)) => {
+            (u128::from(address) ^ u128::from(peer)).leading_zeros()
+        }
+        _ => 0,
+    };
+    interfaces
+        .iter()
+        .fold(None, |best, interface| {
+            let prefix = common_prefix(interface.ip_address);
+            match best {
+                Some((_, best_prefix)) if best_prefix >= prefix => best,
+                _ => Some((interface, prefix)),
+            }
+        })
+        .map(|(interface, _)| interface)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -372,4 +475,70 @@ mod tests {
         assert!(!info.version.is_empty());
         assert!(!info.metadata.is_empty());
     }
+
+    #[test]
+    fn preferred_subnet_picks_the_announced_interface() {
+        let interface = |name: &str, ip: &str, interface_type| NetworkInterface {
+            name: name.to_owned(),
+            ip_address: ip.parse().unwrap(),
+            is_loopback: false,
+            is_up: true,
+            supports_multicast: true,
+            interface_type,
+        };
+        let info = PlatformInfo {
+            os_type: OsType::current(),
+            version: "test".to_owned(),
+            capabilities: PlatformCapabilities::for_current_platform(),
+            network_interfaces: vec![
+                interface("eth0", "192.168.1.20", InterfaceType::Ethernet),
+                interface("eth1", "10.0.0.5", InterfaceType::Ethernet),
+            ],
+            metadata: HashMap::new(),
+        };
+
+        let ip = |subnet| {
+            info.get_primary_interface_in(subnet)
+                .map(|iface| iface.ip_address.to_string())
+        };
+        assert_eq!(ip(None).as_deref(), Some("192.168.1.20"));
+        assert_eq!(ip(Some("10.0.0.0/8")).as_deref(), Some("10.0.0.5"));
+        // A subnet no interface is in falls back to the usual priority.
+        assert_eq!(ip(Some("172.16.0.0/12")).as_deref(), Some("192.168.1.20"));
+    }
+
+    #[test]
+    fn selected_interfaces_match_names_or_addresses_and_peers_get_the_nearest() {
+        let interface = |name: &str, ip: &str| NetworkInterface {
+            name: name.to_owned(),
+            ip_address: ip.parse().unwrap(),
+            is_loopback: false,
+            is_up: true,
+            supports_multicast: true,
+            interface_type: InterfaceType::Ethernet,
+        };
+        let interfaces = vec![
+            interface("eth0", "192.168.1.20"),
+            interface("wlan0", "10.0.0.5"),
+            interface("tailscale0", "100.64.0.7"),
+        ];
+
+        let selected =
+            select_interfaces(&interfaces, &["eth0".to_owned(), "10.0.0.5".to_owned()]).unwrap();
+        let names = selected
+            .iter()
+            .map(|interface| interface.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["eth0", "wlan0"]);
+
+        let error = select_interfaces(&interfaces, &["eth9".to_owned()]).unwrap_err();
+        assert!(error.contains("\"eth9\""));
+        assert!(error.contains("tailscale0 (100.64.0.7)"));
+
+        let nearest = |peer: &str| {
+            nearest_interface(&selected, peer.parse().unwrap()).map(|i| i.name.as_str())
+        };
+        assert_eq!(nearest("192.168.1.77"), Some("eth0"));
+        assert_eq!(nearest("10.0.0.200"), Some("wlan0"));
+    }
 }
@@ -55,15 +55,15 @@ impl BsdNetworkManager {
 
     /// Try to bind to a port
     async fn try_bind_port(&self, port: u16) -> PlatformResult<UdpSocket> {
-        let socket_addr = SocketAddr::from(([0, 0, 0, 0], port));
-
-        match UdpSocket::bind(socket_addr).await {
+        match super::bind_shared_udp(port) {
             Ok(socket) => {
                 debug!("Successfully bound to port {} on FreeBSD", port);
                 Ok(socket)
             }
             Err(e) => {
-                if self.requires_elevation(port) && !self.is_elevated() {
+                if let Some(in_use) = super::port_in_use(port, &e) {
+                    Err(in_use)
+                } else if self.requires_elevation(port) && !self.is_elevated() {
                     warn!("Port {} requires root/sudo privileges on FreeBSD", port);
                     Err(PlatformError::NetworkConfig(format!(
                         "Port {} requires root/sudo privileges on FreeBSD. Error: {}",
@@ -285,6 +285,7 @@ impl NetworkManager for BsdNetworkManager {
                     multicast_enabled: false,
                 })
             }
+            Err(primary_error @ PlatformError::PortInUse { .. }) => Err(primary_error),
             Err(primary_error) => {
                 warn!(
                     "Primary port {} failed on FreeBSD: {}. Trying fallback ports.",
@@ -7,8 +7,6 @@ use crate::platform::{
 };
 use async_trait::async_trait;
 use std::net::{IpAddr, SocketAddr};
-#[cfg(test)]
-use std::process::Command;
 use tokio::net::UdpSocket;
 use tracing::{debug, error, info, warn};
 
@@ -59,9 +57,7 @@ impl LinuxNetworkManager {
 
     /// Try to bind to a port with Linux-specific handling
     async fn try_bind_port_linux(&self, port: u16) -> PlatformResult<UdpSocket> {
-        let socket_addr = SocketAddr::from(([0, 0, 0, 0], port));
-
-        match UdpSocket::bind(socket_addr).await {
+        match super::bind_shared_udp(port) {
             Ok(socket) => {
                 debug!("Successfully bound to port {} on Linux", port);
 
@@ -73,7 +69,9 @@ impl LinuxNetworkManager {
                 Ok(socket)
             }
             Err(e) => {
-                if self.requires_elevation(port) && !self.is_elevated() {
+                if let Some(in_use) = super::port_in_use(port, &e) {
+                    Err(in_use)
+                } else if self.requires_elevation(port) && !self.is_elevated() {
                     warn!("Port {} requires root privileges on Linux", port);
                     Err(PlatformError::NetworkConfig(format!(
                         "Port {} requires root privileges on Linux. Please run with sudo or use a port >= 1024. Error: {}",
@@ -95,7 +93,6 @@ impl LinuxNetworkManager {
         socket: &UdpSocket,
     ) -> Result<(), Box<dyn std::error::Error>> {
         let socket = socket2::SockRef::from(socket);
-        socket.set_reuse_address(true)?;
         socket.set_multicast_ttl_v4(4)?;
         socket.set_multicast_loop_v4(true)?;
 
@@ -428,235 +425,6 @@ impl LinuxNetworkManager {
             || std::env::var_os("KUBERNETES_SERVICE_HOST").is_some()
     }
 
-    /// Alternative method to get network interfaces when standard detection fails
-    #[cfg(test)]
-    fn get_interfaces_alternative_method(&self) -> PlatformResult<Vec<NetworkInterface>> {
-        let mut interfaces = Vec::new();
-
-        // Priority 1: If we have VUIO_IP configured and we're in Docker, use it directly
-        if self.is_running_in_docker() {
-            if let Ok(server_ip_str) = std::env::var("VUIO_IP") {
-                if let Ok(server_ip) = server_ip_str.parse::<IpAddr>() {
-                    // Find the interface that should be used for this IP
-                    let interface_name =
-                        std::env::var("VUIO_SSDP_INTERFACE").unwrap_or_else(|_| {
-                            // Try to determine from routing table
-                            if let Ok(output) = Command::new("ip")
-                                .args(["route", "get", &server_ip_str])
-                                .output()
-                            {
-                                let output_str = String::from_utf8_lossy(&output.stdout);
-                                output_str
-                                    .lines()
-                                    .find_map(|line| {
-                                        if line.contains("dev") {
-                                            let parts: Vec<&str> =
-                                                line.split_whitespace().collect();
-                                            if let Some(dev_idx) =
-                                                parts.iter().position(|&x| x == "dev")
-                                            {
-                                                parts.get(dev_idx + 1).map(|s| s.to_string())
-                                            } else {
-                                                None
-                                            }
-                                        } else {
-                                            None
-                                        }
-                                    })
-                                    .unwrap_or_else(|| "enp12s0".to_string())
-                            } else {
-                                "enp12s0".to_string()
-                            }
-                        });
-
-                    let interface_type = self.determine_linux_interface_type(&interface_name);
-                    interfaces.push(NetworkInterface {
-                        name: interface_name.clone(),
-                        ip_address: server_ip,
-                        is_loopback: false,
-                        is_up: true,
-                        supports_multicast: true,
-                        interface_type,
-                    });
-
-                    info!(
-                        "Docker detected: Using configured server IP {} for interface {}",
-                        server_ip, interface_name
-                    );
-                    return Ok(interfaces);
-                }
-            }
-        }
-
-        // Priority 2: Try to get the default route interface and its IP
-        if let Ok(output) = Command::new("ip")
-            .args(["route", "show", "default"])
-            .output()
-        {
-            if output.status.success() {
-                let output_str = String::from_utf8_lossy(&output.stdout);
-                for line in output_str.lines() {
-                    if line.contains("default") && line.contains("dev") {
-                        // Parse: "default via 192.168.1.1 dev enp12s0 proto dhcp metric 100"
-                        let parts: Vec<&str> = line.split_whitespace().collect();
-                        if let Some(dev_idx) = parts.iter().position(|&x| x == "dev") {
-                            if let Some(interface_name) = parts.get(dev_idx + 1) {
-                                if let Some(ip) = self.get_interface_ip_robust(interface_name) {
-                                    let interface_type =
-                                        self.determine_linux_interface_type(interface_name);
-                                    interfaces.push(NetworkInterface {
-                                        name: interface_name.to_string(),
-                                        ip_address: ip,
-                                        is_loopback: false,
-                                        is_up: true,
-                                        supports_multicast: true,
-                                        interface_type,
-                                    });
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        // Priority 3: If still no interfaces, try to use the configured server IP with best guess interface
-        if interfaces.is_empty() {
-            if let Ok(server_ip_str) = std::env::var("VUIO_IP") {
-                if let Ok(server_ip) = server_ip_str.parse::<IpAddr>() {
-                    // Find the most likely interface name
-                    let interface_name = if let Ok(output) = Command::new("ip")
-                        .args(["route", "get", &server_ip_str])
-                        .output()
-                    {
-                        let output_str = String::from_utf8_lossy(&output.stdout);
-                        output_str
-                            .lines()
-                            .find_map(|line| {
-                                if line.contains("dev") {
-                                    let parts: Vec<&str> = line.split_whitespace().collect();
-                                    if let Some(dev_idx) = parts.iter().position(|&x| x == "dev") {
-                                        parts.get(dev_idx + 1).map(|s| s.to_string())
-                                    } else {
-                                        None
-                                    }
-                                } else {
-                                    None
-                                }
-                            })
-                            .unwrap_or_else(|| "enp12s0".to_string()) // Fallback based on your logs
-                    } else {
-                        "enp12s0".to_string()
-                    };
-
-                    let interface_type = self.determine_linux_interface_type(&interface_name);
-                    interfaces.push(NetworkInterface {
-                        name: interface_name,
-                        ip_address: server_ip,
-                        is_loopback: false,
-                        is_up: true,
-                        supports_multicast: true,
-                        interface_type,
-                    });
-                }
-            }
-        }
-
-        Ok(interfaces)
-    }
-
-    /// Get IP address for a specific interface with more robust methods
-    #[cfg(test)]
-    fn get_interface_ip_robust(&self, interface_name: &str) -> Option<IpAddr> {
-        // First check if we're in Docker and have a configured server IP
-        if self.is_running_in_docker() {
-            if let Ok(server_ip_str) = std::env::var("VUIO_IP") {
-                if let Ok(server_ip) = server_ip_str.parse::<IpAddr>() {
-                    // Check if this interface should use the configured server IP
-                    if let Ok(ssdp_interface) = std::env::var("VUIO_SSDP_INTERFACE") {
-                        if interface_name == ssdp_interface {
-                            debug!(
-                                "Using configured server IP {} for Docker interface {}",
-                                server_ip, interface_name
-                            );
-                            return Some(server_ip);
-                        }
-                    } else {
-                        // If no specific SSDP interface is configured, use server IP for primary interfaces
-                        if interface_name.starts_with("enp") || interface_name.starts_with("eth") {
-                            debug!(
-                                "Using configured server IP {} for Docker interface {}",
-                                server_ip, interface_name
-                            );
-                            return Some(server_ip);
-                        }
-                    }
-                }
-            }
-        }
-
-        // First try the standard method
-        if let Some(ip) = self.get_interface_ip(interface_name) {
-            if !ip.is_loopback() {
-                return Some(ip);
-            }
-        }
-
-        // Try using ip route to find the source IP for this interface
-        if let Ok(output) = Command::new("ip")
-            .args(["route", "show", "dev", interface_name])
-            .output()
-        {
-            if output.status.success() {
-                let output_str = String::from_utf8_lossy(&output.stdout);
-                for line in output_str.lines() {
-                    if line.contains("src") {
-                        let parts: Vec<&str> = line.split_whitespace().collect();
-                        if let Some(src_idx) = parts.iter().position(|&x| x == "src") {
-                            if let Some(ip_str) = parts.get(src_idx + 1) {
-                                if let Ok(ip) = ip_str.parse::<IpAddr>() {
-                                    if !ip.is_loopback() {
-                                        return Some(ip);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        None
-    }
-
-    /// Get IP address for a specific interface
-    #[cfg(test)]
-    fn get_interface_ip(&self, interface_name: &str) -> Option<IpAddr> {
-        match Command::new("ip")
-            .args(["addr", "show", interface_name])
-            .output()
-        {
-            Ok(output) if output.status.success() => {
-                let output_str = String::from_utf8_lossy(&output.stdout);
-                for line in output_str.lines() {
-                    if line.contains("inet ") && !line.contains("inet6") {
-                        if let Some(inet_pos) = line.find("inet ") {
-                            let after_inet = &line[inet_pos + 5..];
-                            if let Some(ip_part) = after_inet.split_whitespace().next() {
-                                let ip_str = ip_part.split('/').next().unwrap_or(ip_part);
-                                if let Ok(ip) = ip_str.parse::<IpAddr>() {
-                                    return Some(ip);
-                                }
-                            }
-                        }
-                    }
-                }
-                None
-            }
-            _ => None,
-        }
-    }
-
     /// Determine interface type based on Linux interface name
     fn determine_linux_interface_type(&self, name: &str) -> InterfaceType {
         if name.starts_with("eth") || name.starts_with("enp") || name.starts_with("eno") {
@@ -715,37 +483,21 @@ impl LinuxNetworkManager {
     async fn create_receive_socket(&self, port: u16) -> PlatformResult<UdpSocket> {
         let socket_addr = SocketAddr::from(([0, 0, 0, 0], port)); // INADDR_ANY
 
-        let socket = UdpSocket::bind(socket_addr).await.map_err(|e| {
-            PlatformError::NetworkConfig(format!(
-                "Failed to bind receive socket to {}: {}",
-                socket_addr, e
-            ))
+        // SO_REUSEADDR/SO_REUSEPORT must be set before the bind to let other
+        // DLNA servers and mDNS responders share the port.
+        let socket = super::bind_shared_udp(port).map_err(|e| {
+            super::port_in_use(port, &e).unwrap_or_else(|| {
+                PlatformError::NetworkConfig(format!(
+                    "Failed to bind receive socket to {}: {}",
+                    socket_addr, e
+                ))
+            })
         })?;
 
-        self.apply_receive_socket_options(&socket)?;
-
         info!("Created receive socket bound to {}", socket_addr);
         Ok(socket)
     }
 
-    /// Apply receive socket options
-    fn apply_receive_socket_options(&self, socket: &UdpSocket) -> PlatformResult<()> {
-        let socket = socket2::SockRef::from(socket);
-        if let Err(error) = socket.set_reuse_address(true) {
-            warn!("Failed to set SO_REUSEADDR: {error}");
-        }
-
-        // This is necessary to prevent the "Address in use" error when creating the announcement socket.
-        if let Err(error) = socket.set_reuse_port(true) {
-            warn!(
-                "Failed to set SO_REUSEPORT: {error}. This might cause issues in some environments."
-            );
-        }
-
-        debug!("Applied receive socket options");
-        Ok(())
-    }
-
     /// Join multicast membership on specific interface
     async fn join_multicast_on_interface(
         &self,
@@ -792,8 +544,12 @@ impl NetworkManager for LinuxNetworkManager {
         // Create receive socket bound to INADDR_ANY:1900
         let receive_socket = self.create_receive_socket(config.primary_port).await?;
 
-        // Get all available network interfaces
-        let interfaces = self.get_local_interfaces().await?;
+        // Use the configured interfaces, or all available ones
+        let interfaces = if config.interfaces.is_empty() {
+            self.get_local_interfaces().await?
+        } else {
+            config.interfaces.clone()
+        };
         let suitable_interfaces: Vec<_> = interfaces
             .into_iter()
             .filter(|iface| !iface.is_loopback && iface.is_up && iface.supports_multicast)
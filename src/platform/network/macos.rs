@@ -56,15 +56,15 @@ impl MacOSNetworkManager {
 
     /// Try to bind to a port with macOS-specific handling
     async fn try_bind_port_macos(&self, port: u16) -> PlatformResult<UdpSocket> {
-        let socket_addr = SocketAddr::from(([0, 0, 0, 0], port));
-
-        match UdpSocket::bind(socket_addr).await {
+        match super::bind_shared_udp(port) {
             Ok(socket) => {
                 debug!("Successfully bound to port {} on macOS", port);
                 Ok(socket)
             }
             Err(e) => {
-                if self.requires_elevation(port) && !self.is_elevated() {
+                if let Some(in_use) = super::port_in_use(port, &e) {
+                    Err(in_use)
+                } else if self.requires_elevation(port) && !self.is_elevated() {
                     warn!("Port {} requires sudo privileges on macOS", port);
                     Err(PlatformError::NetworkConfig(format!(
                         "Port {} requires sudo privileges on macOS. Please run with sudo or use a port >= 1024. Error: {}",
@@ -466,6 +466,7 @@ impl NetworkManager for MacOSNetworkManager {
                     multicast_enabled: false,
                 });
             }
+            Err(primary_error @ PlatformError::PortInUse { .. }) => Err(primary_error),
             Err(primary_error) => {
                 warn!(
                     "Primary port {} failed on macOS: {}. Trying fallback ports.",
@@ -34,7 +34,7 @@ pub mod macos;
 mod socket;
 #[cfg(target_os = "windows")]
 pub mod windows;
-pub use socket::SsdpSocket;
+pub use socket::{bind_shared_udp, port_in_use, SsdpSocket};
 
 // Re-export platform-specific managers
 #[cfg(target_os = "windows")]
@@ -206,20 +206,21 @@ impl BaseNetworkManager {
 
     /// Try to bind to a specific port
     async fn try_bind_port(&self, port: u16) -> PlatformResult<UdpSocket> {
-        let socket_addr = SocketAddr::from(([0, 0, 0, 0], port));
-        UdpSocket::bind(socket_addr).await.map_err(|e| {
-            PlatformError::NetworkConfig(format!("Failed to bind to port {}: {}", port, e))
+        bind_shared_udp(port).map_err(|e| {
+            port_in_use(port, &e).unwrap_or_else(|| {
+                PlatformError::NetworkConfig(format!("Failed to bind to port {}: {}", port, e))
+            })
         })
     }
 
     /// Find an available port from the configuration
     async fn find_available_port(&self) -> PlatformResult<u16> {
-        // Try primary port first
-        if self
-            .is_port_available_internal(self.config.primary_port)
-            .await
-        {
-            return Ok(self.config.primary_port);
+        // Try primary port first. A port held exclusively by another process
+        // stays held, so report it instead of walking the fallbacks.
+        match self.try_bind_port(self.config.primary_port).await {
+            Ok(_) => return Ok(self.config.primary_port),
+            Err(error @ PlatformError::PortInUse { .. }) => return Err(error),
+            Err(_) => {}
         }
 
         warn!(
@@ -543,6 +544,39 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn ssdp_port_is_shared_and_exclusive_holders_are_reported() {
+        let first = bind_shared_udp(0).unwrap();
+        let port = first.local_addr().unwrap().port();
+        let second = SsdpSocket::new(port, Vec::new()).await.unwrap();
+        assert_eq!(second.socket.local_addr().unwrap().port(), port);
+
+        // Windows lets SO_REUSEADDR take over a port unless the holder
+        // opted into SO_EXCLUSIVEADDRUSE, so only Unix refuses here.
+        #[cfg(unix)]
+        {
+            let exclusive = std::net::UdpSocket::bind("0.0.0.0:0").unwrap();
+            let held = exclusive.local_addr().unwrap().port();
+            let error = BaseNetworkManager::new()
+                .try_bind_port(held)
+                .await
+                .unwrap_err();
+            assert!(matches!(error, PlatformError::PortInUse { port } if port == held));
+            assert!(error.to_string().contains("VUIO_SSDP_PORT"));
+        }
+    }
+
+    #[tokio::test]
+    async fn configured_multicast_ttl_survives_joining_the_group() {
+        let mut socket = SsdpSocket::new(0, Vec::new()).await.unwrap();
+        socket.set_multicast_ttl(7).unwrap();
+        // Joining may fail without a multicast route; the TTL must hold either way.
+        let _ = socket
+            .enable_multicast(SSDP_MULTICAST_IP, IpAddr::V4(std::net::Ipv4Addr::LOCALHOST))
+            .await;
+        assert_eq!(socket.multicast_ttl().unwrap(), 7);
+    }
+
     #[test]
     fn test_interface_filtering() {
         let manager = BaseNetworkManager::new();
@@ -16,11 +16,37 @@ pub struct SsdpSocket {
     pub multicast_enabled: bool,
 }
 
+/// Bind a UDP socket on all IPv4 addresses with `SO_REUSEADDR`, and
+/// `SO_REUSEPORT` where the platform has it, set before the bind. Other DLNA
+/// servers and mDNS responders legitimately share the SSDP multicast port.
+pub fn bind_shared_udp(port: u16) -> std::io::Result<UdpSocket> {
+    let socket = socket2::Socket::new(
+        socket2::Domain::IPV4,
+        socket2::Type::DGRAM,
+        Some(socket2::Protocol::UDP),
+    )?;
+    socket.set_reuse_address(true)?;
+    #[cfg(all(unix, not(any(target_os = "solaris", target_os = "illumos"))))]
+    if let Err(error) = socket.set_reuse_port(true) {
+        warn!("Failed to set SO_REUSEPORT on port {port}: {error}");
+    }
+    socket.set_nonblocking(true)?;
+    socket.bind(&SocketAddr::from(([0, 0, 0, 0], port)).into())?;
+    UdpSocket::from_std(socket.into())
+}
+
+/// Map a failed SSDP bind to [`PlatformError::PortInUse`] when another process
+/// holds the port exclusively. Retrying with the same options cannot help.
+pub fn port_in_use(port: u16, error: &std::io::Error) -> Option<PlatformError> {
+    (error.kind() == std::io::ErrorKind::AddrInUse).then_some(PlatformError::PortInUse { port })
+}
+
 impl SsdpSocket {
     pub async fn new(port: u16, interfaces: Vec<NetworkInterface>) -> PlatformResult<Self> {
-        let socket_addr = SocketAddr::from(([0, 0, 0, 0], port));
-        let socket = UdpSocket::bind(socket_addr).await.map_err(|error| {
-            PlatformError::NetworkConfig(format!("Failed to bind to port {port}: {error}"))
+        let socket = bind_shared_udp(port).map_err(|error| {
+            port_in_use(port, &error).unwrap_or_else(|| {
+                PlatformError::NetworkConfig(format!("Failed to bind to port {port}: {error}"))
+            })
         })?;
         if let Err(error) = Self::configure_socket(&socket) {
             warn!("Failed to configure socket options: {error}");
@@ -36,7 +62,6 @@ impl SsdpSocket {
 
     fn configure_socket(socket: &UdpSocket) -> std::io::Result<()> {
         let socket = socket2::SockRef::from(socket);
-        socket.set_reuse_address(true)?;
         socket.set_broadcast(true)
     }
 
@@ -92,7 +117,6 @@ impl SsdpSocket {
         bind_addr: std::net::Ipv4Addr,
     ) -> std::io::Result<()> {
         let socket = socket2::SockRef::from(socket);
-        socket.set_multicast_ttl_v4(4)?;
         socket.set_multicast_loop_v4(false)?;
         if !bind_addr.is_unspecified() {
             socket.set_multicast_if_v4(&bind_addr)?;
@@ -100,6 +124,44 @@ impl SsdpSocket {
         Ok(())
     }
 
+    /// Set `IP_MULTICAST_IF`, so announcements leave through `interface`
+    /// instead of the interface of the default route.
+    pub fn set_multicast_interface(&self, interface: &NetworkInterface) -> PlatformResult<()> {
+        let IpAddr::V4(address) = interface.ip_address else {
+            return Err(PlatformError::NetworkConfig(format!(
+                "Cannot send IPv4 multicast through {} ({})",
+                interface.name, interface.ip_address
+            )));
+        };
+        socket2::SockRef::from(&self.socket)
+            .set_multicast_if_v4(&address)
+            .map_err(|error| {
+                PlatformError::NetworkConfig(format!(
+                    "Failed to send multicast through {} ({address}): {error}",
+                    interface.name
+                ))
+            })
+    }
+
+    /// Set `IP_MULTICAST_TTL`, the number of router hops announcements may
+    /// cross.
+    pub fn set_multicast_ttl(&self, ttl: u32) -> PlatformResult<()> {
+        socket2::SockRef::from(&self.socket)
+            .set_multicast_ttl_v4(ttl)
+            .map_err(|error| {
+                PlatformError::NetworkConfig(format!("Failed to set multicast TTL {ttl}: {error}"))
+            })
+    }
+
+    /// The `IP_MULTICAST_TTL` currently set on the socket.
+    pub fn multicast_ttl(&self) -> PlatformResult<u32> {
+        socket2::SockRef::from(&self.socket)
+            .multicast_ttl_v4()
+            .map_err(|error| {
+                PlatformError::NetworkConfig(format!("Failed to read multicast TTL: {error}"))
+            })
+    }
+
     pub async fn send_to(&self, data: &[u8], addr: SocketAddr) -> PlatformResult<usize> {
         self.socket
             .send_to(data, addr)
@@ -77,15 +77,15 @@ impl WindowsNetworkManager {
 
     /// Try to bind to a port with Windows-specific socket options
     async fn try_bind_port_windows(&self, port: u16) -> PlatformResult<UdpSocket> {
-        let socket_addr = SocketAddr::from(([0, 0, 0, 0], port));
-
-        match UdpSocket::bind(socket_addr).await {
+        match super::bind_shared_udp(port) {
             Ok(socket) => {
                 debug!("Successfully bound to port {} on Windows", port);
                 Ok(socket)
             }
             Err(e) => {
-                if self.requires_elevation(port) && !self.is_elevated() {
+                if let Some(in_use) = super::port_in_use(port, &e) {
+                    Err(in_use)
+                } else if self.requires_elevation(port) && !self.is_elevated() {
                     warn!("Port {} requires administrator privileges on Windows", port);
                     Err(PlatformError::NetworkConfig(format!(
                         "Port {} requires administrator privileges. Please run as administrator or use a port >= 1024. Error: {}",
@@ -502,6 +502,9 @@ impl NetworkManager for WindowsNetworkManager {
         }
 
         let primary_error = primary_result.unwrap_err();
+        if matches!(primary_error, PlatformError::PortInUse { .. }) {
+            return Err(primary_error);
+        }
         warn!(
             "Primary port {} failed on Windows: {}",
             config.primary_port, primary_error
@@ -3,7 +3,7 @@
 use crate::{state::SoapCacheKey, tv_control::DiscoveredTv};
 use axum::body::Bytes;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     hash::Hash,
     sync::Arc,
     time::{Duration, Instant},
@@ -98,6 +98,147 @@ pub const ACTIVE_CAST_TTL: Duration = Duration::from_secs(180);
 pub const RENDERER_CACHE_MAX_ENTRIES: usize = 128;
 pub const RENDERER_CACHE_FRESH_TTL: Duration = Duration::from_secs(90);
 pub const RENDERER_CACHE_STALE_TTL: Duration = Duration::from_secs(600);
+pub const CONTAINER_UPDATE_ID_MAX_ENTRIES: usize = 4096;
+
+/// File IDs left out of listings (missing files and hidden duplicates), as
+/// of one `content_update_id` and `media.hide_duplicates` setting.
+#[derive(Default)]
+pub struct HiddenFiles {
+    pub update_id: Option<u32>,
+    pub duplicates: bool,
+    pub hidden: Arc<HashSet<i64>>,
+    /// The missing files among `hidden`, as of the
+    /// [`crate::media::MISSING_FILES_MARKED_SETTING`] value `missing_marked`.
+    pub missing: HashSet<i64>,
+    pub missing_marked: Option<String>,
+}
+
+type StreamKey = (std::net::IpAddr, String);
+
+/// Media streams being sent, counted against `media.max_concurrent_streams`.
+/// A stream is one client playing one object; the overlapping range requests
+/// renderers open while probing or seeking join it instead of counting again.
+#[derive(Default)]
+pub struct StreamSessions {
+    sessions: Arc<std::sync::Mutex<HashMap<StreamKey, usize>>>,
+}
+
+impl StreamSessions {
+    /// Start or join the stream of `object_id` to `client` unless `limit`
+    /// other streams are already running; zero means no limit. The request
+    /// counts until the returned guard is dropped.
+    pub fn try_start(
+        &self,
+        client: std::net::IpAddr,
+        object_id: &str,
+        limit: usize,
+    ) -> Option<StreamSession> {
+        let key = (client, object_id.to_owned());
+        let mut sessions = self
+            .sessions
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if !sessions.contains_key(&key) && limit != 0 && sessions.len() >= limit {
+            return None;
+        }
+        *sessions.entry(key.clone()).or_default() += 1;
+        Some(StreamSession {
+            sessions: self.sessions.clone(),
+            key,
+        })
+    }
+
+    /// Streams running, however many requests each has open.
+    pub fn active(&self) -> usize {
+        self.sessions
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .len()
+    }
+}
+
+/// One request of a running stream; see [`StreamSessions::try_start`].
+pub struct StreamSession {
+    sessions: Arc<std::sync::Mutex<HashMap<StreamKey, usize>>>,
+    key: StreamKey,
+}
+
+impl Drop for StreamSession {
+    fn drop(&mut self) {
+        let mut sessions = self
+            .sessions
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(requests) = sessions.get_mut(&self.key) {
+            *requests -= 1;
+            if *requests == 0 {
+                sessions.remove(&self.key);
+            }
+        }
+    }
+}
+
+/// Browse `UpdateID` of each container: the `SystemUpdateID` at which its
+/// listing last changed, so it stays put while other containers change.
+/// The least recently browsed containers are forgotten first.
+pub struct ContainerUpdateIds {
+    containers: std::sync::Mutex<BoundedRegistry<String, ContainerUpdate>>,
+}
+
+#[derive(Clone, Copy)]
+struct ContainerUpdate {
+    revision: u64,
+    update_id: u32,
+    /// `SystemUpdateID` at which `revision` was last computed.
+    checked_at: u32,
+}
+
+impl ContainerUpdateIds {
+    /// `UpdateID` of `object_id` if its revision was already computed at
+    /// `system_update_id`, so later pages can skip computing it again.
+    pub fn known(&self, object_id: &str, system_update_id: u32) -> Option<u32> {
+        let mut containers = self
+            .containers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        containers
+            .get(&object_id.to_owned())
+            .filter(|container| container.checked_at == system_update_id)
+            .map(|container| container.update_id)
+    }
+
+    /// `UpdateID` of `object_id` whose listing now has `revision`.
+    /// Containers first seen, or changed since, take `system_update_id`.
+    pub fn update_id(&self, object_id: &str, revision: u64, system_update_id: u32) -> u32 {
+        let mut containers = self
+            .containers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let update_id = containers
+            .get(&object_id.to_owned())
+            .filter(|container| container.revision == revision)
+            .map_or(system_update_id, |container| container.update_id);
+        containers.insert(
+            object_id.to_owned(),
+            ContainerUpdate {
+                revision,
+                update_id,
+                checked_at: system_update_id,
+            },
+        );
+        update_id
+    }
+}
+
+impl Default for ContainerUpdateIds {
+    fn default() -> Self {
+        Self {
+            containers: std::sync::Mutex::new(BoundedRegistry::new(
+                CONTAINER_UPDATE_ID_MAX_ENTRIES,
+            )),
+        }
+    }
+}
 
 struct BrowseEntry {
     value: Bytes,
@@ -501,9 +642,33 @@ mod tests {
             client_profile: crate::web::client::DlnaClientProfile::Standard,
             content_update_id: 1,
             browse_epoch: epoch,
+            sort_criteria: String::new(),
+            origin: String::new(),
         }
     }
 
+    #[test]
+    fn stream_sessions_are_bounded_until_a_guard_drops() {
+        let tv: std::net::IpAddr = "192.168.1.44".parse().unwrap();
+        let phone: std::net::IpAddr = "192.168.1.45".parse().unwrap();
+        let sessions = StreamSessions::default();
+        let first = sessions.try_start(tv, "1", 2).unwrap();
+        let _second = sessions.try_start(phone, "1", 2).unwrap();
+        assert!(sessions.try_start(tv, "2", 2).is_none());
+        assert_eq!(sessions.active(), 2);
+
+        // A seek opens another request for the same stream.
+        let seek = sessions.try_start(tv, "1", 2).unwrap();
+        drop(first);
+        assert_eq!(sessions.active(), 2);
+        assert!(sessions.try_start(tv, "2", 2).is_none());
+        drop(seek);
+        assert_eq!(sessions.active(), 1);
+        assert!(sessions.try_start(tv, "2", 2).is_some());
+        assert!(sessions.try_start(tv, "3", 0).is_some());
+        assert_eq!(sessions.active(), 1);
+    }
+
     #[test]
     fn cleared_epoch_cannot_reuse_a_late_stale_response() {
         let mut cache = BrowseResponseCache::new();
@@ -516,4 +681,22 @@ mod tests {
         cache.insert(stale_key, Bytes::from_static(b"stale"));
         assert!(cache.get(&current_key).is_none());
     }
+
+    #[test]
+    fn container_update_ids_forget_one_container_at_a_time() {
+        let ids = ContainerUpdateIds::default();
+        assert_eq!(ids.known("video", 1), None);
+        assert_eq!(ids.update_id("video", 7, 1), 1);
+        assert_eq!(ids.known("video", 1), Some(1));
+        assert_eq!(ids.known("video", 2), None);
+        assert_eq!(ids.update_id("video", 7, 2), 1);
+        assert_eq!(ids.update_id("video", 8, 3), 3);
+
+        for container in 0..CONTAINER_UPDATE_ID_MAX_ENTRIES {
+            ids.update_id(&format!("folder-{container}"), 1, 4);
+        }
+        assert_eq!(ids.known("video", 3), None);
+        assert_eq!(ids.known("folder-1", 4), Some(4));
+        assert_eq!(ids.update_id("folder-1", 1, 5), 4);
+    }
 }
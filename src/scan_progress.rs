@@ -0,0 +1,177 @@
+//! Progress of the startup media scan.
+//!
+//! The scan walks the configured directories in order and records its
+//! position in the database settings after every directory, so a restart can
+//! tell an interrupted scan from a finished one and, with
+//! `media.resume_interrupted_scans`, skip the directories that were already
+//! completed. [`ScanControl`] is the in-process side: whether a scan is
+//! running now and whether it has been asked to stop.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::database::DatabaseManager;
+
+/// Settings key holding the JSON-encoded [`ScanState`].
+pub const SCAN_STATE_SETTING: &str = "scan_state";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanStatus {
+    Running,
+    Completed,
+    Cancelled,
+    /// Reported for a persisted `Running` scan that no process is running,
+    /// i.e. the server stopped mid-scan. Never stored.
+    Interrupted,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanState {
+    pub status: ScanStatus,
+    /// Unix seconds.
+    pub started_at: u64,
+    pub updated_at: u64,
+    pub finished_at: Option<u64>,
+    /// Configured directories, in scan order.
+    pub directories: Vec<String>,
+    /// Queue position: how many of `directories` have been fully scanned.
+    pub completed_directories: usize,
+    pub current_directory: Option<String>,
+    pub files_scanned: u64,
+    pub changes: u64,
+}
+
+impl ScanState {
+    pub fn new(directories: Vec<String>) -> Self {
+        let now = unix_now_secs();
+        Self {
+            status: ScanStatus::Running,
+            started_at: now,
+            updated_at: now,
+            finished_at: None,
+            directories,
+            completed_directories: 0,
+            current_directory: None,
+            files_scanned: 0,
+            changes: 0,
+        }
+    }
+
+    pub async fn load<D: DatabaseManager + ?Sized>(database: &D) -> Result<Option<Self>> {
+        let Some(value) = database.get_setting(SCAN_STATE_SETTING).await? else {
+            return Ok(None);
+        };
+        serde_json::from_str(&value)
+            .map(Some)
+            .context("invalid persisted scan state")
+    }
+
+    pub async fn save<D: DatabaseManager + ?Sized>(&mut self, database: &D) -> Result<()> {
+        self.updated_at = unix_now_secs();
+        database
+            .set_setting(SCAN_STATE_SETTING, &serde_json::to_string(self)?)
+            .await
+    }
+
+    /// Where a new scan over `directories` should start, if this state is an
+    /// unfinished scan of the same directory list.
+    pub fn resume_position(&self, directories: &[String]) -> Option<usize> {
+        let unfinished = matches!(
+            self.status,
+            ScanStatus::Running | ScanStatus::Cancelled | ScanStatus::Interrupted
+        );
+        (unfinished && self.directories == directories && self.completed_directories > 0)
+            .then_some(self.completed_directories)
+    }
+
+    pub fn finish(&mut self, status: ScanStatus) {
+        self.status = status;
+        self.current_directory = None;
+        self.finished_at = Some(unix_now_secs());
+    }
+}
+
+/// Live state of the scan running in this process.
+#[derive(Debug, Default)]
+pub struct ScanControl {
+    running: AtomicBool,
+    cancel_requested: AtomicBool,
+}
+
+impl ScanControl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn begin(&self) {
+        self.cancel_requested.store(false, Ordering::SeqCst);
+        self.running.store(true, Ordering::SeqCst);
+    }
+
+    pub fn finish(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    /// Ask the running scan to stop at its next batch boundary. Returns
+    /// `false` when no scan is running.
+    pub fn request_cancel(&self) -> bool {
+        if !self.is_running() {
+            return false;
+        }
+        self.cancel_requested.store(true, Ordering::SeqCst);
+        true
+    }
+
+    pub fn is_cancel_requested(&self) -> bool {
+        self.cancel_requested.load(Ordering::SeqCst)
+    }
+}
+
+fn unix_now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_unfinished_scans_of_the_same_directories_resume() {
+        let directories = vec!["/media/a".to_owned(), "/media/b".to_owned()];
+        let mut state = ScanState::new(directories.clone());
+        assert_eq!(state.resume_position(&directories), None);
+
+        state.completed_directories = 1;
+        assert_eq!(state.resume_position(&directories), Some(1));
+        assert_eq!(state.resume_position(&directories[..1]), None);
+
+        state.finish(ScanStatus::Cancelled);
+        assert_eq!(state.resume_position(&directories), Some(1));
+        state.finish(ScanStatus::Completed);
+        assert_eq!(state.resume_position(&directories), None);
+    }
+
+    #[test]
+    fn cancelling_needs_a_running_scan() {
+        let control = ScanControl::new();
+        assert!(!control.request_cancel());
+
+        control.begin();
+        assert!(control.request_cancel());
+        assert!(control.is_cancel_requested());
+
+        control.finish();
+        control.begin();
+        assert!(!control.is_cancel_requested());
+    }
+}
@@ -8,13 +8,112 @@ use crate::state::AppState;
 use anyhow::Result;
 use async_trait::async_trait;
 use std::{net::SocketAddr, sync::Arc, time::Duration};
-use tokio::time::interval;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
+mod message;
+
+use message::{DeviceUuid, SearchRequest, SsdpAdvertiser, SsdpEntity};
+
 const SSDP_PORT: u16 = 1900;
+
+/// Shortest period between NOTIFY alive rounds, whatever
+/// `network.announce_interval_seconds` says, so a misconfiguration cannot
+/// flood the network.
+const MIN_ANNOUNCE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Upper bound of the random delay added to every announcement period.
+const MAX_ANNOUNCE_JITTER: Duration = Duration::from_secs(2);
+
+/// Time until the next NOTIFY alive round: the configured interval, at least
+/// [`MIN_ANNOUNCE_INTERVAL`], plus a jitter of up to a tenth of it taken from
+/// `random`, so servers started together do not announce in lockstep.
+fn announce_delay(interval_seconds: u64, random: u64) -> Duration {
+    let period = Duration::from_secs(interval_seconds).max(MIN_ANNOUNCE_INTERVAL);
+    let jitter_ms = (period / 10).min(MAX_ANNOUNCE_JITTER).as_millis() as u64;
+    period + Duration::from_millis(random % (jitter_ms + 1))
+}
+
+/// Most M-SEARCH answers waiting out their delay at once. Searches that
+/// arrive while this many are pending are dropped; the client searches again.
+const MAX_PENDING_SEARCH_ANSWERS: usize = 64;
+
+/// Random delay of a search response within the request's MX window, so
+/// every device on the network does not answer a multicast search at once.
+fn search_response_delay(mx_seconds: u64, random: u64) -> Duration {
+    Duration::from_millis(random % (mx_seconds.max(1) * 1000))
+}
+
+/// How one M-SEARCH gets answered: one response per matching entity,
+/// unicast to the address and port the request came from after `delay`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SearchAnswer {
+    destination: SocketAddr,
+    entities: Vec<SsdpEntity>,
+    delay: Duration,
+}
+
+impl SearchAnswer {
+    /// `None` when `search` asks for nothing this server provides.
+    fn plan(
+        search: &SearchRequest,
+        source: SocketAddr,
+        uuid: &DeviceUuid,
+        random: u64,
+    ) -> Option<Self> {
+        let entities = SsdpEntity::matching(&search.target, uuid);
+        if entities.is_empty() {
+            return None;
+        }
+        Some(Self {
+            destination: source,
+            entities,
+            delay: search_response_delay(search.mx_seconds, random),
+        })
+    }
+}
+
+/// Port the SSDP socket listens on. `VUIO_SSDP_PORT` moves it off 1900 when
+/// another responder holds that port exclusively; announcements still go to
+/// the standard multicast group and port.
+fn listen_port() -> u16 {
+    std::env::var("VUIO_SSDP_PORT")
+        .ok()
+        .and_then(|port| port.parse().ok())
+        .unwrap_or(SSDP_PORT)
+}
 type SharedSsdpSocket = Arc<std::sync::RwLock<Arc<SsdpSocket>>>;
 
+/// Announcements use `network.multicast_ttl` on every platform, after the
+/// platform manager has joined the group.
+fn apply_multicast_ttl(socket: &SsdpSocket, ttl: u32) {
+    if let Err(error) = socket.set_multicast_ttl(ttl) {
+        warn!("{error}");
+    }
+}
+
+/// Send this binding's multicast through its own interface.
+fn pin_multicast_interface(socket: &SsdpSocket, interface: &NetworkInterface) -> Result<()> {
+    socket
+        .set_multicast_interface(interface)
+        .map_err(|error| anyhow::anyhow!("{error}"))
+}
+
+/// Whether the binding on `interface` answers a search from `peer`. With
+/// pinned interfaces only the one nearest the peer does, so the peer gets
+/// one LOCATION it can reach.
+fn answers_peer(
+    pinned: &[NetworkInterface],
+    interface: Option<&NetworkInterface>,
+    peer: SocketAddr,
+) -> bool {
+    if pinned.is_empty() {
+        return true;
+    }
+    let nearest = crate::platform::nearest_interface(pinned, peer.ip());
+    nearest.map(|nearest| nearest.ip_address) == interface.map(|interface| interface.ip_address)
+}
+
 fn load_ssdp_socket(socket: &SharedSsdpSocket) -> Arc<SsdpSocket> {
     socket
         .read()
@@ -42,13 +141,24 @@ pub trait SsdpPlatformAdapter: Send + Sync {
     fn get_ssdp_config(&self, config: &AppConfig) -> SsdpConfig;
 }
 
+/// One socket set of the SSDP service: the address it announces as
+/// LOCATION and the interface it joins the multicast group on.
+#[derive(Debug, Clone)]
+struct SsdpBinding {
+    server_ip: String,
+    interface: Option<NetworkInterface>,
+}
+
 /// Unified SSDP service that works across all platforms
 pub struct UnifiedSsdpService {
     network_manager: Arc<dyn NetworkManager>,
     platform_adapter: Box<dyn SsdpPlatformAdapter>,
     config: Arc<AppConfig>,
-    server_ip: String,
-    primary_interface: Option<NetworkInterface>,
+    bindings: Vec<SsdpBinding>,
+    /// Interfaces of a `network.interface_selection` list, or all of them
+    /// for `All`. Each binding sends through its own one and answers only
+    /// searches from peers nearest to it. Empty for the other selections.
+    pinned: Arc<[NetworkInterface]>,
 }
 
 impl UnifiedSsdpService {
@@ -68,17 +178,44 @@ impl UnifiedSsdpService {
             }
         };
 
+        let config = state.current_config();
+        let pinned = match state.announced_interfaces() {
+            Some(selected) if selected.is_empty() => {
+                warn!(
+                    "None of the interfaces in network.interface_selection is present; \
+                     announcing on the primary interface"
+                );
+                Vec::new()
+            }
+            Some(selected) => selected,
+            None => Vec::new(),
+        };
+        let bindings = if pinned.is_empty() {
+            vec![SsdpBinding {
+                server_ip: state.get_server_ip(),
+                interface: state.primary_interface(),
+            }]
+        } else {
+            pinned
+                .iter()
+                .map(|interface| SsdpBinding {
+                    server_ip: state.get_server_ip_for(interface),
+                    interface: Some(interface.clone()),
+                })
+                .collect()
+        };
         Self {
             network_manager,
             platform_adapter,
-            config: state.current_config(),
-            server_ip: state.get_server_ip(),
-            primary_interface: state.platform_info.get_primary_interface().cloned(),
+            config,
+            bindings,
+            pinned: pinned.into(),
         }
     }
 
     async fn spawn_tasks(
         &self,
+        binding: &SsdpBinding,
         cancellation: CancellationToken,
     ) -> Result<(
         tokio::task::JoinHandle<Result<()>>,
@@ -86,11 +223,18 @@ impl UnifiedSsdpService {
     )> {
         info!("Starting unified SSDP service");
 
-        let server_ip = self.server_ip.clone();
+        let server_ip = binding.server_ip.clone();
         info!("SSDP service using server IP: {}", server_ip);
 
         // Create SSDP socket with platform-specific configuration
-        let ssdp_config = self.platform_adapter.get_ssdp_config(&self.config);
+        let mut ssdp_config = self.platform_adapter.get_ssdp_config(&self.config);
+        let pinned_interface = binding
+            .interface
+            .clone()
+            .filter(|_| !self.pinned.is_empty());
+        if let Some(interface) = &pinned_interface {
+            ssdp_config.interfaces = vec![interface.clone()];
+        }
         let mut socket = self
             .network_manager
             .create_ssdp_socket_with_config(&ssdp_config)
@@ -102,7 +246,7 @@ impl UnifiedSsdpService {
 
         // Join multicast group
         let multicast_addr = SSDP_MULTICAST_IP;
-        let primary_interface = self.primary_interface.clone();
+        let primary_interface = binding.interface.clone();
         if let Err(e) = self
             .network_manager
             .join_multicast_group(&mut socket, multicast_addr, primary_interface.as_ref())
@@ -110,6 +254,23 @@ impl UnifiedSsdpService {
         {
             warn!("Failed to join multicast group: {}", e);
         }
+        apply_multicast_ttl(&socket, ssdp_config.multicast_ttl);
+        if let Some(interface) = &pinned_interface {
+            pin_multicast_interface(&socket, interface)?;
+        }
+        match socket.multicast_ttl() {
+            Ok(ttl) => info!("SSDP multicast TTL: {}", ttl),
+            Err(e) => warn!("{}", e),
+        }
+
+        let advertiser = Arc::new(match &self.config.server.base_url {
+            Some(base_url) => SsdpAdvertiser::with_base_url(&self.config.server.uuid, base_url)?,
+            None => SsdpAdvertiser::new(
+                &self.config.server.uuid,
+                &server_ip,
+                self.config.server.port,
+            )?,
+        });
 
         // Tokio's UDP socket supports concurrent send and receive through
         // shared references. Keeping the configured wrapper in an Arc avoids
@@ -117,19 +278,19 @@ impl UnifiedSsdpService {
         let socket = Arc::new(std::sync::RwLock::new(Arc::new(socket)));
 
         // Start M-SEARCH responder task
-        let responder_config = self.config.clone();
-        let responder_ip = self.server_ip.clone();
+        let responder_advertiser = advertiser.clone();
         let responder_manager = self.network_manager.clone();
         let responder_ssdp_config = ssdp_config.clone();
-        let responder_interface = self.primary_interface.clone();
+        let responder_interface = binding.interface.clone();
+        let responder_pinned = self.pinned.clone();
         let responder_socket = socket.clone();
         let responder = tokio::spawn(async move {
             Self::search_responder_task(
-                responder_config,
-                responder_ip,
+                responder_advertiser,
                 responder_manager,
                 responder_ssdp_config,
                 responder_interface,
+                responder_pinned,
                 responder_socket,
             )
             .await
@@ -137,13 +298,13 @@ impl UnifiedSsdpService {
 
         // Start announcement task
         let announcer_config = self.config.clone();
-        let announcer_ip = self.server_ip.clone();
+        let announcer_advertiser = advertiser;
         let announcer_manager = self.network_manager.clone();
         let announcer_socket = socket.clone();
         let announcer = tokio::spawn(async move {
             Self::announcer_task(
                 announcer_config,
-                announcer_ip,
+                announcer_advertiser,
                 announcer_manager,
                 announcer_socket,
                 cancellation,
@@ -155,9 +316,31 @@ impl UnifiedSsdpService {
         Ok((responder, announcer))
     }
 
-    /// Run SSDP until cancellation while retaining ownership of both worker tasks.
+    /// Run SSDP until cancellation while retaining ownership of every
+    /// binding's worker tasks. A failing binding stops all of them.
     pub async fn run_until_cancelled(self, cancellation: CancellationToken) -> Result<()> {
-        let (mut responder, mut announcer) = self.spawn_tasks(cancellation.clone()).await?;
+        let mut bindings = tokio::task::JoinSet::new();
+        for binding in &self.bindings {
+            let (responder, announcer) = self.spawn_tasks(binding, cancellation.clone()).await?;
+            bindings.spawn(Self::supervise(responder, announcer, cancellation.clone()));
+        }
+        while let Some(result) = bindings.join_next().await {
+            if let Err(error) =
+                result.map_err(|error| anyhow::anyhow!("SSDP task failed: {error}"))?
+            {
+                bindings.shutdown().await;
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    /// Wait for one binding's tasks until cancellation or until either fails.
+    async fn supervise(
+        mut responder: tokio::task::JoinHandle<Result<()>>,
+        mut announcer: tokio::task::JoinHandle<Result<()>>,
+        cancellation: CancellationToken,
+    ) -> Result<()> {
         tokio::select! {
             _ = cancellation.cancelled() => {
                 responder.abort();
@@ -177,16 +360,17 @@ impl UnifiedSsdpService {
 
     /// Task for handling M-SEARCH requests
     async fn search_responder_task(
-        config: Arc<AppConfig>,
-        server_ip: String,
+        advertiser: Arc<SsdpAdvertiser>,
         network_manager: Arc<dyn NetworkManager>,
         ssdp_config: SsdpConfig,
         primary_interface: Option<NetworkInterface>,
+        pinned: Arc<[NetworkInterface]>,
         socket: SharedSsdpSocket,
     ) -> Result<()> {
         let mut buf = vec![0u8; 2048];
         let mut consecutive_errors = 0;
         const MAX_CONSECUTIVE_ERRORS: u32 = 10;
+        let pending_answers = Arc::new(tokio::sync::Semaphore::new(MAX_PENDING_SEARCH_ANSWERS));
 
         loop {
             let (len, addr) = {
@@ -218,6 +402,12 @@ impl UnifiedSsdpService {
                                 .map_err(|error| {
                                     anyhow::anyhow!("SSDP multicast rejoin failed: {error}")
                                 })?;
+                            apply_multicast_ttl(&replacement, ssdp_config.multicast_ttl);
+                            if let Some(interface) =
+                                primary_interface.as_ref().filter(|_| !pinned.is_empty())
+                            {
+                                pin_multicast_interface(&replacement, interface)?;
+                            }
                             *socket.write().unwrap_or_else(|error| error.into_inner()) =
                                 Arc::new(replacement);
                             consecutive_errors = 0;
@@ -232,80 +422,68 @@ impl UnifiedSsdpService {
             consecutive_errors = 0;
             let request = String::from_utf8_lossy(&buf[..len]);
 
-            if request.contains("M-SEARCH") {
-                debug!("Received M-SEARCH from {}", addr);
-                Self::handle_msearch_request(&config, &server_ip, &socket, &request, addr).await;
+            let Some(search) = SearchRequest::parse(&request) else {
+                continue;
+            };
+            if !answers_peer(&pinned, primary_interface.as_ref(), addr) {
+                continue;
             }
+            let Some(answer) = SearchAnswer::plan(
+                &search,
+                addr,
+                advertiser.uuid(),
+                uuid::Uuid::new_v4().as_u128() as u64,
+            ) else {
+                debug!(
+                    "Ignoring M-SEARCH from {} for {:?}: not a target of this server",
+                    addr, search.target
+                );
+                continue;
+            };
+            debug!(
+                "Received M-SEARCH from {} for {:?} (MX {})",
+                addr, search.target, search.mx_seconds
+            );
+            let Ok(permit) = pending_answers.clone().try_acquire_owned() else {
+                debug!(
+                    "Dropping M-SEARCH from {}: {} answers already pending",
+                    addr, MAX_PENDING_SEARCH_ANSWERS
+                );
+                continue;
+            };
+            // Waiting out the delay here would hold up every other search.
+            let advertiser = advertiser.clone();
+            let socket = socket.clone();
+            tokio::spawn(async move {
+                Self::send_search_responses(advertiser, socket, answer).await;
+                drop(permit);
+            });
         }
     }
 
-    /// Handle M-SEARCH request and send appropriate responses
-    async fn handle_msearch_request(
-        config: &AppConfig,
-        server_ip: &str,
-        socket: &SharedSsdpSocket,
-        request: &str,
-        addr: SocketAddr,
+    /// Unicast the answers to one M-SEARCH after its delay.
+    async fn send_search_responses(
+        advertiser: Arc<SsdpAdvertiser>,
+        socket: SharedSsdpSocket,
+        answer: SearchAnswer,
     ) {
-        let mut st_header = None;
-        let mut mx_seconds = 1u64;
-
-        for line in request.lines() {
-            let line = line.trim();
-            if let Some((key, val)) = line.split_once(':') {
-                let key = key.trim();
-                let val = val.trim();
-                if key.eq_ignore_ascii_case("ST") {
-                    st_header = Some(val);
-                } else if key.eq_ignore_ascii_case("MX") {
-                    if let Ok(mx) = val.parse::<u64>() {
-                        mx_seconds = mx.clamp(1, 5);
-                    }
-                }
-            }
-        }
-
-        let st = st_header.unwrap_or("");
-        let mut response_types = Vec::new();
-
-        if st == "ssdp:all" || request.contains("ssdp:all") {
-            response_types.extend_from_slice(&[
-                "upnp:rootdevice",
-                "urn:schemas-upnp-org:device:MediaServer:1",
-                "urn:schemas-upnp-org:service:ContentDirectory:1",
-            ]);
-        } else if st == "upnp:rootdevice" || request.contains("upnp:rootdevice") {
-            response_types.push("upnp:rootdevice");
-        } else if st.starts_with("urn:schemas-upnp-org:device:MediaServer")
-            || request.contains("urn:schemas-upnp-org:device:MediaServer")
-        {
-            response_types.push("urn:schemas-upnp-org:device:MediaServer:1");
-        } else if st.starts_with("urn:schemas-upnp-org:service:ContentDirectory")
-            || request.contains("urn:schemas-upnp-org:service:ContentDirectory")
-        {
-            response_types.push("urn:schemas-upnp-org:service:ContentDirectory:1");
-        } else if request.contains("ssdp:discover") {
-            response_types.push("urn:schemas-upnp-org:device:MediaServer:1");
-        }
-
-        if !response_types.is_empty() {
-            // Apply a small bounded random delay (within MX window) to prevent response bursts
-            let delay_ms = (addr.port() as u64 % (mx_seconds * 200 + 1)).min(1000);
-            if delay_ms > 0 {
-                tokio::time::sleep(Duration::from_millis(delay_ms)).await;
-            }
-        }
-
-        let response_count = response_types.len();
-        for response_type in response_types {
-            let response = Self::create_ssdp_response(config, server_ip, response_type);
-            let active_socket = load_ssdp_socket(socket);
+        let SearchAnswer {
+            destination: addr,
+            entities,
+            delay,
+        } = answer;
+        tokio::time::sleep(delay).await;
+
+        let response_count = entities.len();
+        for response_type in entities {
+            let response = advertiser.search_response(response_type);
+            let active_socket = load_ssdp_socket(&socket);
 
             for retry in 0..3 {
                 match active_socket.send_to(response.as_bytes(), addr).await {
                     Ok(_) => {
                         debug!(
-                            "Successfully sent M-SEARCH response to {} for {}",
+                            "Successfully sent M-SEARCH response to {} for {:?}",
                             addr, response_type
                         );
                         break;
@@ -332,29 +510,37 @@ impl UnifiedSsdpService {
     /// Task for periodic SSDP announcements
     async fn announcer_task(
         config: Arc<AppConfig>,
-        server_ip: String,
+        advertiser: Arc<SsdpAdvertiser>,
         network_manager: Arc<dyn NetworkManager>,
         socket: SharedSsdpSocket,
         cancellation: CancellationToken,
     ) -> Result<()> {
-        let mut interval = interval(Duration::from_secs(
-            config.network.announce_interval_seconds.max(1),
-        ));
+        if Duration::from_secs(config.network.announce_interval_seconds) < MIN_ANNOUNCE_INTERVAL {
+            warn!(
+                "announce_interval_seconds = {} is below the minimum; announcing every {}s",
+                config.network.announce_interval_seconds,
+                MIN_ANNOUNCE_INTERVAL.as_secs()
+            );
+        }
+        // The first round goes out immediately.
+        let mut next_round = Duration::ZERO;
         let mut consecutive_failures = 0;
         const MAX_CONSECUTIVE_FAILURES: u32 = 5;
 
         loop {
             tokio::select! {
                 _ = cancellation.cancelled() => {
-                    Self::send_ssdp_byebye(&config, &server_ip, &network_manager, &socket).await?;
+                    Self::send_ssdp_byebye(&advertiser, &network_manager, &socket).await?;
                     return Ok(());
                 }
-                _ = interval.tick() => {}
+                _ = tokio::time::sleep(next_round) => {}
             }
+            next_round = announce_delay(
+                config.network.announce_interval_seconds,
+                uuid::Uuid::new_v4().as_u128() as u64,
+            );
 
-            match Self::send_ssdp_announcements(&config, &server_ip, &network_manager, &socket)
-                .await
-            {
+            match Self::send_ssdp_announcements(&advertiser, &network_manager, &socket).await {
                 Ok(()) => {
                     consecutive_failures = 0;
                 }
@@ -377,23 +563,16 @@ impl UnifiedSsdpService {
 
     /// Send SSDP NOTIFY announcements
     async fn send_ssdp_announcements(
-        config: &AppConfig,
-        server_ip: &str,
+        advertiser: &SsdpAdvertiser,
         network_manager: &Arc<dyn NetworkManager>,
         socket: &SharedSsdpSocket,
     ) -> Result<()> {
         info!("Sending SSDP NOTIFY announcements");
 
-        let service_types = [
-            "upnp:rootdevice",
-            "urn:schemas-upnp-org:device:MediaServer:1",
-            "urn:schemas-upnp-org:service:ContentDirectory:1",
-        ];
-
         let multicast_addr = SocketAddr::new(SSDP_MULTICAST_IP, SSDP_PORT);
 
-        for service_type in &service_types {
-            let message = Self::create_notify_message(config, server_ip, service_type);
+        for entity in SsdpEntity::ADVERTISED {
+            let message = advertiser.alive(entity);
             let active_socket = load_ssdp_socket(socket);
 
             match network_manager
@@ -401,12 +580,12 @@ impl UnifiedSsdpService {
                 .await
             {
                 Ok(()) => {
-                    info!("Successfully sent SSDP NOTIFY for {}", service_type);
+                    info!("Successfully sent SSDP NOTIFY for {:?}", entity);
                 }
                 Err(e) => {
                     warn!(
-                        "Multicast NOTIFY for {} failed: {}, trying unicast fallback",
-                        service_type, e
+                        "Multicast NOTIFY for {:?} failed: {}, trying unicast fallback",
+                        entity, e
                     );
 
                     if let Err(e) = network_manager
@@ -418,8 +597,8 @@ impl UnifiedSsdpService {
                         .await
                     {
                         error!(
-                            "Both multicast and unicast fallback failed for {}: {}",
-                            service_type, e
+                            "Both multicast and unicast fallback failed for {:?}: {}",
+                            entity, e
                         );
                     }
                 }
@@ -432,19 +611,13 @@ impl UnifiedSsdpService {
     }
 
     async fn send_ssdp_byebye(
-        config: &AppConfig,
-        server_ip: &str,
+        advertiser: &SsdpAdvertiser,
         network_manager: &Arc<dyn NetworkManager>,
         socket: &SharedSsdpSocket,
     ) -> Result<()> {
         let target = SocketAddr::new(SSDP_MULTICAST_IP, SSDP_PORT);
-        for service_type in [
-            "upnp:rootdevice",
-            "urn:schemas-upnp-org:device:MediaServer:1",
-            "urn:schemas-upnp-org:service:ContentDirectory:1",
-        ] {
-            let message = Self::create_notify_message(config, server_ip, service_type)
-                .replace("NTS: ssdp:alive", "NTS: ssdp:byebye");
+        for entity in SsdpEntity::ADVERTISED {
+            let message = advertiser.byebye(entity);
             let active_socket = load_ssdp_socket(socket);
             network_manager
                 .send_multicast(&active_socket, message.as_bytes(), target)
@@ -453,87 +626,6 @@ impl UnifiedSsdpService {
         info!("Sent SSDP byebye announcements");
         Ok(())
     }
-
-    /// Create SSDP response message
-    fn create_ssdp_response(config: &AppConfig, server_ip: &str, service_type: &str) -> String {
-        let (st, usn) = match service_type {
-            "upnp:rootdevice" => (
-                "upnp:rootdevice".to_string(),
-                format!("uuid:{}::upnp:rootdevice", config.server.uuid),
-            ),
-            "urn:schemas-upnp-org:device:MediaServer:1" => (
-                "urn:schemas-upnp-org:device:MediaServer:1".to_string(),
-                format!(
-                    "uuid:{}::urn:schemas-upnp-org:device:MediaServer:1",
-                    config.server.uuid
-                ),
-            ),
-            "urn:schemas-upnp-org:service:ContentDirectory:1" => (
-                "urn:schemas-upnp-org:service:ContentDirectory:1".to_string(),
-                format!(
-                    "uuid:{}::urn:schemas-upnp-org:service:ContentDirectory:1",
-                    config.server.uuid
-                ),
-            ),
-            _ => (
-                "urn:schemas-upnp-org:device:MediaServer:1".to_string(),
-                format!(
-                    "uuid:{}::urn:schemas-upnp-org:device:MediaServer:1",
-                    config.server.uuid
-                ),
-            ),
-        };
-
-        format!(
-            "HTTP/1.1 200 OK\r\n\
-            CACHE-CONTROL: max-age=1800\r\n\
-            EXT:\r\n\
-            LOCATION: http://{}:{}/description.xml\r\n\
-            SERVER: VuIO/1.0 UPnP/1.0\r\n\
-            ST: {}\r\n\
-            USN: {}\r\n\
-            \r\n",
-            server_ip, config.server.port, st, usn
-        )
-    }
-
-    /// Create SSDP NOTIFY message
-    fn create_notify_message(config: &AppConfig, server_ip: &str, service_type: &str) -> String {
-        let (nt, usn) = match service_type {
-            "upnp:rootdevice" => (
-                "upnp:rootdevice".to_string(),
-                format!("uuid:{}::upnp:rootdevice", config.server.uuid),
-            ),
-            "urn:schemas-upnp-org:device:MediaServer:1" => (
-                "urn:schemas-upnp-org:device:MediaServer:1".to_string(),
-                format!(
-                    "uuid:{}::urn:schemas-upnp-org:device:MediaServer:1",
-                    config.server.uuid
-                ),
-            ),
-            "urn:schemas-upnp-org:service:ContentDirectory:1" => (
-                "urn:schemas-upnp-org:service:ContentDirectory:1".to_string(),
-                format!(
-                    "uuid:{}::urn:schemas-upnp-org:service:ContentDirectory:1",
-                    config.server.uuid
-                ),
-            ),
-            _ => return String::new(),
-        };
-
-        format!(
-            "NOTIFY * HTTP/1.1\r\n\
-            HOST: {}:{}\r\n\
-            CACHE-CONTROL: max-age=1800\r\n\
-            LOCATION: http://{}:{}/description.xml\r\n\
-            NT: {}\r\n\
-            NTS: ssdp:alive\r\n\
-            SERVER: VuIO/1.0 UPnP/1.0\r\n\
-            USN: {}\r\n\
-            \r\n",
-            SSDP_MULTICAST_IP, SSDP_PORT, server_ip, config.server.port, nt, usn
-        )
-    }
 }
 
 /// Run SSDP as an owned lifecycle service until cancellation.
@@ -597,7 +689,7 @@ impl SsdpPlatformAdapter for DefaultSsdpAdapter {
 
     fn get_ssdp_config(&self, config: &AppConfig) -> SsdpConfig {
         SsdpConfig {
-            primary_port: SSDP_PORT,
+            primary_port: listen_port(),
             fallback_ports: self.fallback_ports.clone(),
             multicast_address: SSDP_MULTICAST_IP,
             announce_interval: Duration::from_secs(config.network.announce_interval_seconds),
@@ -611,3 +703,202 @@ impl SsdpPlatformAdapter for DefaultSsdpAdapter {
 // ============================================================================
 // Legacy implementations removed - now using UnifiedSsdpService with platform adapters
 // ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pinned_bindings_answer_only_peers_on_their_own_subnet() {
+        let interface = |name: &str, ip: &str| NetworkInterface {
+            name: name.to_owned(),
+            ip_address: ip.parse().unwrap(),
+            is_loopback: false,
+            is_up: true,
+            supports_multicast: true,
+            interface_type: crate::platform::InterfaceType::Ethernet,
+        };
+        let pinned = [
+            interface("eth0", "192.168.1.20"),
+            interface("eth1", "10.0.0.5"),
+        ];
+        let peer = "10.0.0.80:1900".parse().unwrap();
+
+        assert!(!answers_peer(&pinned, Some(&pinned[0]), peer));
+        assert!(answers_peer(&pinned, Some(&pinned[1]), peer));
+        assert!(answers_peer(&[], Some(&pinned[0]), peer));
+    }
+
+    #[test]
+    fn announcements_keep_a_floor_and_a_bounded_jitter() {
+        assert_eq!(announce_delay(30, 0), Duration::from_secs(30));
+        assert_eq!(announce_delay(10, 1000), Duration::from_secs(11));
+        assert_eq!(announce_delay(10, 1001), Duration::from_secs(10));
+        assert_eq!(announce_delay(1800, 2000), Duration::from_secs(1802));
+        assert_eq!(announce_delay(1800, 2001), Duration::from_secs(1800));
+        assert_eq!(announce_delay(0, 0), MIN_ANNOUNCE_INTERVAL);
+        assert_eq!(announce_delay(1, 500), Duration::from_millis(5500));
+        for random in [0, 1, 499, u64::MAX] {
+            let delay = announce_delay(1, random);
+            assert!(delay >= MIN_ANNOUNCE_INTERVAL && delay <= Duration::from_millis(5500));
+        }
+    }
+
+    #[test]
+    fn search_responses_wait_a_random_time_within_mx() {
+        assert_eq!(search_response_delay(1, 0), Duration::ZERO);
+        assert_eq!(search_response_delay(1, 999), Duration::from_millis(999));
+        assert_eq!(search_response_delay(1, 1000), Duration::ZERO);
+        assert_eq!(search_response_delay(5, 4999), Duration::from_millis(4999));
+        for random in [0, 1, 12_345, u64::MAX] {
+            assert!(search_response_delay(3, random) < Duration::from_secs(3));
+        }
+    }
+
+    #[test]
+    fn only_searches_for_served_targets_get_answers() {
+        let advertiser =
+            SsdpAdvertiser::new("6f0c9a3e-2b1d-4e5f-8a7b-1c2d3e4f5a6b", "10.0.0.2", 8080).unwrap();
+        let answers = |st: &str| {
+            let packet = format!(
+                "M-SEARCH * HTTP/1.1\r\n\
+                 HOST: 239.255.255.250:1900\r\n\
+                 MAN: \"ssdp:discover\"\r\n\
+                 MX: 2\r\n\
+                 ST: {st}\r\n\r\n"
+            );
+            let search = SearchRequest::parse(&packet).unwrap();
+            SsdpEntity::matching(&search.target, advertiser.uuid())
+        };
+        assert_eq!(answers("ssdp:all"), SsdpEntity::ADVERTISED);
+        assert_eq!(answers("upnp:rootdevice"), [SsdpEntity::RootDevice]);
+        assert_eq!(
+            answers("uuid:6f0c9a3e-2b1d-4e5f-8a7b-1c2d3e4f5a6b"),
+            [SsdpEntity::Device]
+        );
+        assert_eq!(
+            answers("urn:schemas-upnp-org:service:ContentDirectory:1"),
+            [SsdpEntity::ContentDirectory]
+        );
+        for unserved in [
+            "urn:schemas-upnp-org:device:MediaRenderer:1",
+            "urn:dial-multiscreen-org:service:dial:1",
+            "uuid:00000000-0000-0000-0000-000000000000",
+        ] {
+            assert!(answers(unserved).is_empty(), "{unserved}");
+        }
+    }
+
+    #[test]
+    fn captured_searches_are_answered_per_target_to_their_source() {
+        let advertiser =
+            SsdpAdvertiser::new("6f0c9a3e-2b1d-4e5f-8a7b-1c2d3e4f5a6b", "10.0.0.2", 8080).unwrap();
+        let captures: [(&str, &str, &[SsdpEntity], u64); 5] = [
+            // Windows Media Player
+            (
+                "M-SEARCH * HTTP/1.1\r\n\
+                 Host:239.255.255.250:1900\r\n\
+                 ST:urn:schemas-upnp-org:device:MediaServer:1\r\n\
+                 Man:\"ssdp:discover\"\r\n\
+                 MX:3\r\n\r\n",
+                "10.0.0.31:50123",
+                &[SsdpEntity::MediaServer],
+                3,
+            ),
+            // Samsung TV, with an MX above the UPnP cap
+            (
+                "M-SEARCH * HTTP/1.1\r\n\
+                 HOST: 239.255.255.250:1900\r\n\
+                 MAN: \"ssdp:discover\"\r\n\
+                 MX: 10\r\n\
+                 ST: ssdp:all\r\n\
+                 USER-AGENT: Linux/9.0 UPnP/1.0 SEC_HHP_[TV] Samsung/1.0\r\n\r\n",
+                "10.0.0.40:1900",
+                &SsdpEntity::ADVERTISED,
+                5,
+            ),
+            // VLC
+            (
+                "M-SEARCH * HTTP/1.1\r\n\
+                 HOST: 239.255.255.250:1900\r\n\
+                 MAN: \"ssdp:discover\"\r\n\
+                 MX: 1\r\n\
+                 ST: upnp:rootdevice\r\n\r\n",
+                "10.0.0.52:43210",
+                &[SsdpEntity::RootDevice],
+                1,
+            ),
+            // A control point checking the services
+            (
+                "M-SEARCH * HTTP/1.1\r\n\
+                 HOST: 239.255.255.250:1900\r\n\
+                 MAN: \"ssdp:discover\"\r\n\
+                 MX: 2\r\n\
+                 ST: urn:schemas-upnp-org:service:ConnectionManager:1\r\n\r\n",
+                "10.0.0.60:51000",
+                &[SsdpEntity::ConnectionManager],
+                2,
+            ),
+            // Unicast search, which may leave out MX
+            (
+                "M-SEARCH * HTTP/1.1\r\n\
+                 HOST: 10.0.0.2:1900\r\n\
+                 MAN: \"ssdp:discover\"\r\n\
+                 ST: uuid:6f0c9a3e-2b1d-4e5f-8a7b-1c2d3e4f5a6b\r\n\r\n",
+                "10.0.0.70:60000",
+                &[SsdpEntity::Device],
+                1,
+            ),
+        ];
+
+        for (packet, source, entities, mx_seconds) in captures {
+            let source: SocketAddr = source.parse().unwrap();
+            let search = SearchRequest::parse(packet).unwrap();
+            for random in [0, 4_321, u64::MAX] {
+                let answer = SearchAnswer::plan(&search, source, advertiser.uuid(), random)
+                    .unwrap_or_else(|| panic!("no answer for {packet}"));
+                assert_eq!(answer.destination, source);
+                assert_eq!(answer.entities, entities);
+                assert!(answer.delay < Duration::from_secs(mx_seconds), "{packet}");
+            }
+        }
+
+        let renderer_search = SearchRequest::parse(
+            "M-SEARCH * HTTP/1.1\r\n\
+             HOST: 239.255.255.250:1900\r\n\
+             MAN: \"ssdp:discover\"\r\n\
+             MX: 2\r\n\
+             ST: urn:schemas-upnp-org:device:MediaRenderer:1\r\n\r\n",
+        )
+        .unwrap();
+        assert_eq!(
+            SearchAnswer::plan(
+                &renderer_search,
+                "10.0.0.80:1900".parse().unwrap(),
+                advertiser.uuid(),
+                0
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn usn_shares_the_description_udn() {
+        let mut config = AppConfig::default_for_platform();
+        config.server.uuid =
+            crate::config::normalize_device_uuid("UUID:{6F0C9A3E-2B1D-4E5F-8A7B-1C2D3E4F5A6B}")
+                .unwrap();
+        let udn = config.server.udn();
+        assert_eq!(udn, "uuid:6f0c9a3e-2b1d-4e5f-8a7b-1c2d3e4f5a6b");
+
+        let advertiser =
+            SsdpAdvertiser::new(&config.server.uuid, "10.0.0.2", config.server.port).unwrap();
+        assert_eq!(advertiser.uuid().udn(), udn);
+        for entity in SsdpEntity::ADVERTISED {
+            let response = advertiser.search_response(entity);
+            let notify = advertiser.alive(entity);
+            assert!(response.contains(&format!("USN: {udn}")), "{response}");
+            assert!(notify.contains(&format!("USN: {udn}")), "{notify}");
+        }
+    }
+}
@@ -0,0 +1,508 @@
+//! Typed SSDP packet construction.
+//!
+//! Every NT, ST and USN value is derived from one validated device UUID and an
+//! [`SsdpEntity`], so search responses, alive and byebye notifications always
+//! agree with each other and with the UDN published in description.xml.
+
+use crate::config::normalize_device_uuid;
+use anyhow::{anyhow, Result};
+use std::net::{IpAddr, SocketAddr};
+
+use super::SSDP_PORT;
+use crate::platform::network::SSDP_MULTICAST_IP;
+
+/// Product token sent in the SERVER header.
+pub const SERVER_TOKEN: &str = "VuIO/1.0 UPnP/1.0";
+/// Advertisement lifetime in CACHE-CONTROL.
+pub const MAX_AGE_SECONDS: u32 = 1800;
+
+/// Canonical device UUID: lowercase, hyphenated, without the `uuid:` prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceUuid(String);
+
+impl DeviceUuid {
+    /// Accepts the same spellings as `server.uuid` and rejects anything that is
+    /// not a UUID.
+    pub fn parse(value: &str) -> Result<Self> {
+        normalize_device_uuid(value)
+            .map(Self)
+            .ok_or_else(|| anyhow!("invalid device UUID {value:?}"))
+    }
+
+    /// The `uuid:<uuid>` unique device name.
+    pub fn udn(&self) -> String {
+        format!("uuid:{}", self.0)
+    }
+}
+
+/// One advertised SSDP entity of the media server's root device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SsdpEntity {
+    RootDevice,
+    /// The device itself, addressed by its UDN.
+    Device,
+    MediaServer,
+    ContentDirectory,
+    ConnectionManager,
+    MediaReceiverRegistrar,
+}
+
+impl SsdpEntity {
+    /// Everything description.xml declares, in the order UPnP DA lists it:
+    /// root device, device UUID, device type, then each service type.
+    pub const ADVERTISED: [SsdpEntity; 6] = [
+        SsdpEntity::RootDevice,
+        SsdpEntity::Device,
+        SsdpEntity::MediaServer,
+        SsdpEntity::ContentDirectory,
+        SsdpEntity::ConnectionManager,
+        SsdpEntity::MediaReceiverRegistrar,
+    ];
+
+    fn type_urn(self) -> Option<&'static str> {
+        match self {
+            SsdpEntity::RootDevice | SsdpEntity::Device => None,
+            SsdpEntity::MediaServer => Some("urn:schemas-upnp-org:device:MediaServer:1"),
+            SsdpEntity::ContentDirectory => Some("urn:schemas-upnp-org:service:ContentDirectory:1"),
+            SsdpEntity::ConnectionManager => {
+                Some("urn:schemas-upnp-org:service:ConnectionManager:1")
+            }
+            SsdpEntity::MediaReceiverRegistrar => {
+                Some("urn:microsoft.com:service:X_MS_MediaReceiverRegistrar:1")
+            }
+        }
+    }
+
+    /// The NT of a notification, which is also the ST of a search response.
+    pub fn target(self, uuid: &DeviceUuid) -> String {
+        match self {
+            SsdpEntity::RootDevice => "upnp:rootdevice".to_owned(),
+            SsdpEntity::Device => uuid.udn(),
+            _ => self.type_urn().unwrap_or_default().to_owned(),
+        }
+    }
+
+    /// The USN: the bare UDN for the device itself, `<udn>::<target>` otherwise.
+    pub fn usn(self, uuid: &DeviceUuid) -> String {
+        match self {
+            SsdpEntity::Device => uuid.udn(),
+            _ => format!("{}::{}", uuid.udn(), self.target(uuid)),
+        }
+    }
+
+    /// Entities that answer an M-SEARCH for `search_target`. Targets this
+    /// device does not provide get no answer rather than a mismatched one.
+    pub fn matching(search_target: &str, uuid: &DeviceUuid) -> Vec<SsdpEntity> {
+        let search_target = search_target.trim();
+        match search_target {
+            "ssdp:all" => Self::ADVERTISED.to_vec(),
+            "upnp:rootdevice" => vec![SsdpEntity::RootDevice],
+            _ if search_target
+                .get(..5)
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case("uuid:")) =>
+            {
+                DeviceUuid::parse(search_target)
+                    .ok()
+                    .filter(|requested| requested == uuid)
+                    .map(|_| vec![SsdpEntity::Device])
+                    .unwrap_or_default()
+            }
+            _ => Self::ADVERTISED
+                .into_iter()
+                .filter(|entity| entity.type_urn() == Some(search_target))
+                .collect(),
+        }
+    }
+}
+
+/// Longest `MX` honoured; UPnP DA 1.1 treats larger values as 5.
+pub const MAX_SEARCH_MX_SECONDS: u64 = 5;
+
+/// The parts of an `M-SEARCH` request that decide the answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchRequest {
+    /// The ST header.
+    pub target: String,
+    /// Seconds the answer may be delayed by, clamped to
+    /// `1..=MAX_SEARCH_MX_SECONDS`. Unicast searches may omit MX.
+    pub mx_seconds: u64,
+}
+
+impl SearchRequest {
+    /// `None` unless `packet` is an `M-SEARCH` request with an ST header.
+    pub fn parse(packet: &str) -> Option<Self> {
+        let mut lines = packet.lines();
+        let mut start_line = lines.next()?.split_whitespace();
+        if start_line.next() != Some("M-SEARCH") {
+            return None;
+        }
+
+        let mut target = None;
+        let mut mx_seconds = 1;
+        for line in lines {
+            let Some((name, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            if name.trim().eq_ignore_ascii_case("ST") {
+                target = Some(value.to_owned());
+            } else if name.trim().eq_ignore_ascii_case("MX") {
+                if let Ok(mx) = value.parse::<u64>() {
+                    mx_seconds = mx.clamp(1, MAX_SEARCH_MX_SECONDS);
+                }
+            }
+        }
+        Some(Self {
+            target: target?,
+            mx_seconds,
+        })
+    }
+}
+
+/// Builds the SSDP packets for one device description URL.
+#[derive(Debug, Clone)]
+pub struct SsdpAdvertiser {
+    uuid: DeviceUuid,
+    location: String,
+}
+
+impl SsdpAdvertiser {
+    /// `host` is the address clients should fetch description.xml from; IPv6
+    /// addresses are bracketed automatically.
+    pub fn new(uuid: &str, host: &str, port: u16) -> Result<Self> {
+        let host = host.trim();
+        let authority = match host.parse::<IpAddr>() {
+            Ok(ip) => SocketAddr::new(ip, port).to_string(),
+            Err(_)
+                if !host.is_empty()
+                    && !host
+                        .chars()
+                        .any(|c| c.is_whitespace() || c.is_control() || "/?#@[]".contains(c)) =>
+            {
+                format!("{host}:{port}")
+            }
+            Err(_) => return Err(anyhow!("invalid SSDP server address {host:?}")),
+        };
+        Ok(Self {
+            uuid: DeviceUuid::parse(uuid)?,
+            location: format!("http://{authority}/description.xml"),
+        })
+    }
+
+    /// Advertise description.xml under `server.base_url` as given.
+    pub fn with_base_url(uuid: &str, base_url: &str) -> Result<Self> {
+        let base_url = base_url.trim().trim_end_matches('/');
+        if base_url
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control())
+        {
+            return Err(anyhow!("invalid SSDP base URL {base_url:?}"));
+        }
+        Ok(Self {
+            uuid: DeviceUuid::parse(uuid)?,
+            location: format!("{base_url}/description.xml"),
+        })
+    }
+
+    pub fn uuid(&self) -> &DeviceUuid {
+        &self.uuid
+    }
+
+    /// Unicast answer to an M-SEARCH.
+    pub fn search_response(&self, entity: SsdpEntity) -> String {
+        Packet::new("HTTP/1.1 200 OK")
+            .header("CACHE-CONTROL", &format!("max-age={MAX_AGE_SECONDS}"))
+            .header("EXT", "")
+            .header("LOCATION", &self.location)
+            .header("SERVER", SERVER_TOKEN)
+            .header("ST", &entity.target(&self.uuid))
+            .header("USN", &entity.usn(&self.uuid))
+            .finish()
+    }
+
+    /// Multicast `ssdp:alive` notification.
+    pub fn alive(&self, entity: SsdpEntity) -> String {
+        Packet::new("NOTIFY * HTTP/1.1")
+            .header("HOST", &multicast_host())
+            .header("CACHE-CONTROL", &format!("max-age={MAX_AGE_SECONDS}"))
+            .header("LOCATION", &self.location)
+            .header("NT", &entity.target(&self.uuid))
+            .header("NTS", "ssdp:alive")
+            .header("SERVER", SERVER_TOKEN)
+            .header("USN", &entity.usn(&self.uuid))
+            .finish()
+    }
+
+    /// Multicast `ssdp:byebye` notification, sent on shutdown.
+    pub fn byebye(&self, entity: SsdpEntity) -> String {
+        Packet::new("NOTIFY * HTTP/1.1")
+            .header("HOST", &multicast_host())
+            .header("NT", &entity.target(&self.uuid))
+            .header("NTS", "ssdp:byebye")
+            .header("USN", &entity.usn(&self.uuid))
+            .finish()
+    }
+}
+
+fn multicast_host() -> String {
+    SocketAddr::new(SSDP_MULTICAST_IP, SSDP_PORT).to_string()
+}
+
+/// CRLF-delimited start line and headers followed by the blank line.
+struct Packet(String);
+
+impl Packet {
+    fn new(start_line: &str) -> Self {
+        Self(format!("{start_line}\r\n"))
+    }
+
+    fn header(mut self, name: &str, value: &str) -> Self {
+        debug_assert!(
+            value.trim() == value && !value.chars().any(char::is_control),
+            "SSDP header {name} has an unsafe value {value:?}"
+        );
+        self.0.push_str(name);
+        self.0.push(':');
+        if !value.is_empty() {
+            self.0.push(' ');
+            self.0.push_str(value);
+        }
+        self.0.push_str("\r\n");
+        self
+    }
+
+    fn finish(mut self) -> String {
+        self.0.push_str("\r\n");
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UUID: &str = "6f0c9a3e-2b1d-4e5f-8a7b-1c2d3e4f5a6b";
+
+    fn advertiser() -> SsdpAdvertiser {
+        SsdpAdvertiser::new(
+            "UUID:{6F0C9A3E-2B1D-4E5F-8A7B-1C2D3E4F5A6B}",
+            "10.0.0.2",
+            8080,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn search_responses_match_golden_captures() {
+        let advertiser = advertiser();
+        let golden = [
+            (
+                SsdpEntity::RootDevice,
+                "HTTP/1.1 200 OK\r\n\
+                 CACHE-CONTROL: max-age=1800\r\n\
+                 EXT:\r\n\
+                 LOCATION: http://10.0.0.2:8080/description.xml\r\n\
+                 SERVER: VuIO/1.0 UPnP/1.0\r\n\
+                 ST: upnp:rootdevice\r\n\
+                 USN: uuid:6f0c9a3e-2b1d-4e5f-8a7b-1c2d3e4f5a6b::upnp:rootdevice\r\n\
+                 \r\n",
+            ),
+            (
+                SsdpEntity::Device,
+                "HTTP/1.1 200 OK\r\n\
+                 CACHE-CONTROL: max-age=1800\r\n\
+                 EXT:\r\n\
+                 LOCATION: http://10.0.0.2:8080/description.xml\r\n\
+                 SERVER: VuIO/1.0 UPnP/1.0\r\n\
+                 ST: uuid:6f0c9a3e-2b1d-4e5f-8a7b-1c2d3e4f5a6b\r\n\
+                 USN: uuid:6f0c9a3e-2b1d-4e5f-8a7b-1c2d3e4f5a6b\r\n\
+                 \r\n",
+            ),
+            (
+                SsdpEntity::MediaServer,
+                "HTTP/1.1 200 OK\r\n\
+                 CACHE-CONTROL: max-age=1800\r\n\
+                 EXT:\r\n\
+                 LOCATION: http://10.0.0.2:8080/description.xml\r\n\
+                 SERVER: VuIO/1.0 UPnP/1.0\r\n\
+                 ST: urn:schemas-upnp-org:device:MediaServer:1\r\n\
+                 USN: uuid:6f0c9a3e-2b1d-4e5f-8a7b-1c2d3e4f5a6b::urn:schemas-upnp-org:device:MediaServer:1\r\n\
+                 \r\n",
+            ),
+            (
+                SsdpEntity::MediaReceiverRegistrar,
+                "HTTP/1.1 200 OK\r\n\
+                 CACHE-CONTROL: max-age=1800\r\n\
+                 EXT:\r\n\
+                 LOCATION: http://10.0.0.2:8080/description.xml\r\n\
+                 SERVER: VuIO/1.0 UPnP/1.0\r\n\
+                 ST: urn:microsoft.com:service:X_MS_MediaReceiverRegistrar:1\r\n\
+                 USN: uuid:6f0c9a3e-2b1d-4e5f-8a7b-1c2d3e4f5a6b::urn:microsoft.com:service:X_MS_MediaReceiverRegistrar:1\r\n\
+                 \r\n",
+            ),
+        ];
+        for (entity, expected) in golden {
+            assert_eq!(advertiser.search_response(entity), expected, "{entity:?}");
+        }
+    }
+
+    #[test]
+    fn notifications_match_golden_captures() {
+        let advertiser = advertiser();
+        assert_eq!(
+            advertiser.alive(SsdpEntity::ContentDirectory),
+            "NOTIFY * HTTP/1.1\r\n\
+             HOST: 239.255.255.250:1900\r\n\
+             CACHE-CONTROL: max-age=1800\r\n\
+             LOCATION: http://10.0.0.2:8080/description.xml\r\n\
+             NT: urn:schemas-upnp-org:service:ContentDirectory:1\r\n\
+             NTS: ssdp:alive\r\n\
+             SERVER: VuIO/1.0 UPnP/1.0\r\n\
+             USN: uuid:6f0c9a3e-2b1d-4e5f-8a7b-1c2d3e4f5a6b::urn:schemas-upnp-org:service:ContentDirectory:1\r\n\
+             \r\n"
+        );
+        assert_eq!(
+            advertiser.alive(SsdpEntity::Device),
+            "NOTIFY * HTTP/1.1\r\n\
+             HOST: 239.255.255.250:1900\r\n\
+             CACHE-CONTROL: max-age=1800\r\n\
+             LOCATION: http://10.0.0.2:8080/description.xml\r\n\
+             NT: uuid:6f0c9a3e-2b1d-4e5f-8a7b-1c2d3e4f5a6b\r\n\
+             NTS: ssdp:alive\r\n\
+             SERVER: VuIO/1.0 UPnP/1.0\r\n\
+             USN: uuid:6f0c9a3e-2b1d-4e5f-8a7b-1c2d3e4f5a6b\r\n\
+             \r\n"
+        );
+        assert_eq!(
+            advertiser.byebye(SsdpEntity::RootDevice),
+            "NOTIFY * HTTP/1.1\r\n\
+             HOST: 239.255.255.250:1900\r\n\
+             NT: upnp:rootdevice\r\n\
+             NTS: ssdp:byebye\r\n\
+             USN: uuid:6f0c9a3e-2b1d-4e5f-8a7b-1c2d3e4f5a6b::upnp:rootdevice\r\n\
+             \r\n"
+        );
+    }
+
+    #[test]
+    fn every_entity_pairs_nt_and_usn_identically_across_messages() {
+        let advertiser = advertiser();
+        let udn = format!("uuid:{UUID}");
+        for entity in SsdpEntity::ADVERTISED {
+            let target = entity.target(advertiser.uuid());
+            let usn = entity.usn(advertiser.uuid());
+            if entity == SsdpEntity::Device {
+                assert_eq!(usn, udn);
+            } else {
+                assert_eq!(usn, format!("{udn}::{target}"));
+            }
+            let response = advertiser.search_response(entity);
+            assert!(response.contains(&format!("\r\nST: {target}\r\nUSN: {usn}\r\n")));
+            for notify in [advertiser.alive(entity), advertiser.byebye(entity)] {
+                assert!(notify.contains(&format!("\r\nNT: {target}\r\n")));
+                assert!(notify.contains(&format!("\r\nUSN: {usn}\r\n")));
+            }
+            for packet in [
+                response,
+                advertiser.alive(entity),
+                advertiser.byebye(entity),
+            ] {
+                assert!(packet.ends_with("\r\n\r\n"));
+                assert!(!packet.replace("\r\n", "").contains(['\r', '\n']));
+                assert!(packet.split("\r\n").all(|line| line.trim_end() == line));
+            }
+        }
+    }
+
+    #[test]
+    fn search_targets_answer_only_what_was_asked() {
+        let uuid = DeviceUuid::parse(UUID).unwrap();
+        assert_eq!(
+            SsdpEntity::matching("ssdp:all", &uuid),
+            SsdpEntity::ADVERTISED
+        );
+        assert_eq!(
+            SsdpEntity::matching(" upnp:rootdevice ", &uuid),
+            [SsdpEntity::RootDevice]
+        );
+        assert_eq!(
+            SsdpEntity::matching(&format!("uuid:{}", UUID.to_uppercase()), &uuid),
+            [SsdpEntity::Device]
+        );
+        assert_eq!(
+            SsdpEntity::matching("urn:schemas-upnp-org:service:ConnectionManager:1", &uuid),
+            [SsdpEntity::ConnectionManager]
+        );
+        for unrelated in [
+            "uuid:00000000-0000-0000-0000-000000000000",
+            "urn:schemas-upnp-org:device:MediaRenderer:1",
+            "urn:schemas-upnp-org:device:MediaServer:2",
+            "urn:dial-multiscreen-org:service:dial:1",
+            "",
+        ] {
+            assert!(
+                SsdpEntity::matching(unrelated, &uuid).is_empty(),
+                "{unrelated}"
+            );
+        }
+    }
+
+    #[test]
+    fn search_requests_are_parsed_and_mx_is_clamped() {
+        let search = |st: &str, mx: &str| {
+            format!(
+                "M-SEARCH * HTTP/1.1\r\n\
+                 HOST: 239.255.255.250:1900\r\n\
+                 MAN: \"ssdp:discover\"\r\n\
+                 MX: {mx}\r\n\
+                 ST: {st}\r\n\r\n"
+            )
+        };
+        assert_eq!(
+            SearchRequest::parse(&search("upnp:rootdevice", "3")),
+            Some(SearchRequest {
+                target: "upnp:rootdevice".to_owned(),
+                mx_seconds: 3,
+            })
+        );
+        for (mx, expected) in [("120", 5), ("0", 1), ("-2", 1), ("soon", 1)] {
+            let request = SearchRequest::parse(&search("ssdp:all", mx)).unwrap();
+            assert_eq!(request.mx_seconds, expected, "MX {mx}");
+        }
+        assert_eq!(
+            SearchRequest::parse("M-SEARCH * HTTP/1.1\r\nst: ssdp:all\r\n\r\n")
+                .map(|request| request.mx_seconds),
+            Some(1)
+        );
+        assert_eq!(
+            SearchRequest::parse("M-SEARCH * HTTP/1.1\r\nMX: 2\r\n\r\n"),
+            None
+        );
+        assert_eq!(
+            SearchRequest::parse(&advertiser().alive(SsdpEntity::RootDevice)),
+            None
+        );
+        assert_eq!(
+            SearchRequest::parse("HTTP/1.1 200 OK\r\nST: M-SEARCH\r\n\r\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn advertiser_validates_uuid_and_brackets_ipv6_locations() {
+        assert!(SsdpAdvertiser::new("not-a-uuid", "10.0.0.2", 8080).is_err());
+        assert!(SsdpAdvertiser::new(UUID, "bad host", 8080).is_err());
+        let ipv6 = SsdpAdvertiser::new(UUID, "fe80::1", 8080).unwrap();
+        assert!(ipv6
+            .search_response(SsdpEntity::RootDevice)
+            .contains("LOCATION: http://[fe80::1]:8080/description.xml\r\n"));
+        let named = SsdpAdvertiser::new(UUID, "vuio.local", 8080).unwrap();
+        assert!(named
+            .alive(SsdpEntity::RootDevice)
+            .contains("LOCATION: http://vuio.local:8080/description.xml\r\n"));
+        let configured = SsdpAdvertiser::with_base_url(UUID, "http://192.168.1.20:8088/").unwrap();
+        assert!(configured
+            .alive(SsdpEntity::RootDevice)
+            .contains("LOCATION: http://192.168.1.20:8088/description.xml\r\n"));
+    }
+}
@@ -10,9 +10,12 @@ use std::time::Duration;
 use tokio::sync::{mpsc, RwLock};
 use tracing::{debug, error, info, warn};
 
+use crate::config::WatchMode;
 use crate::error::Result;
 use crate::media::ScanPolicy;
 
+mod poll;
+
 /// Events that can occur in the file system for media files
 #[derive(Debug, Clone)]
 pub enum FileSystemEvent {
@@ -79,13 +82,18 @@ pub struct CrossPlatformWatcher {
     watched_paths: Arc<std::sync::Mutex<HashMap<PathBuf, WatchRegistration>>>,
     policies: Arc<std::sync::RwLock<Vec<ScanPolicy>>>,
     dirty_roots: Arc<std::sync::Mutex<HashSet<PathBuf>>>,
+    pollers: Arc<std::sync::Mutex<HashMap<PathBuf, tokio::task::JoinHandle<()>>>>,
+    /// Signalled by each poller once its baseline snapshot is taken.
+    baselines: Arc<std::sync::Mutex<Vec<tokio::sync::oneshot::Receiver<()>>>>,
     debounce_duration: Duration,
+    poll_interval: Duration,
 }
 
 #[derive(Debug, Clone)]
 struct WatchRegistration {
     path: PathBuf,
     recursive: bool,
+    polled: bool,
 }
 
 fn normalized_watch_key(path: &Path) -> PathBuf {
@@ -116,10 +124,19 @@ impl CrossPlatformWatcher {
             watched_paths: Arc::new(std::sync::Mutex::new(HashMap::with_capacity(16))),
             policies: Arc::new(std::sync::RwLock::new(Vec::new())),
             dirty_roots: Arc::new(std::sync::Mutex::new(HashSet::with_capacity(16))),
+            pollers: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            baselines: Arc::new(std::sync::Mutex::new(Vec::new())),
             debounce_duration: Duration::from_millis(250), // 250ms debounce for reduced event frequency
+            poll_interval: Duration::from_secs(10),
         }
     }
 
+    /// Override how often polled directories are checked for changes.
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
     pub fn with_policies(policies: Vec<ScanPolicy>) -> Self {
         let watcher = Self::new();
         *watcher
@@ -158,6 +175,13 @@ impl CrossPlatformWatcher {
             );
             return Ok(());
         }
+        let polled = {
+            let policies = self
+                .policies
+                .read()
+                .unwrap_or_else(|error| error.into_inner());
+            should_poll(path, &policies)
+        };
         let mut debouncer_guard = self.debouncer.write().await;
         if let Some(ref mut debouncer) = *debouncer_guard {
             let mut watched = self
@@ -166,38 +190,136 @@ impl CrossPlatformWatcher {
                 .unwrap_or_else(|error| error.into_inner());
             let key = normalized_watch_key(path);
             if let Some(existing) = watched.get(&key) {
-                if existing.recursive == recursive {
+                if existing.recursive == recursive && existing.polled == polled {
                     return Ok(());
                 }
-                debouncer.unwatch(&existing.path)?;
+                if existing.polled {
+                    self.stop_poller(&key);
+                } else {
+                    debouncer.unwatch(&existing.path)?;
+                }
                 watched.remove(&key);
             }
-            let mode = if recursive {
-                RecursiveMode::Recursive
+            if polled {
+                self.start_poller(key.clone(), path, recursive);
             } else {
-                RecursiveMode::NonRecursive
-            };
-            debouncer.watch(path, mode)?;
+                let mode = if recursive {
+                    RecursiveMode::Recursive
+                } else {
+                    RecursiveMode::NonRecursive
+                };
+                debouncer.watch(path, mode)?;
+            }
             watched.insert(
                 key,
                 WatchRegistration {
                     path: path.to_path_buf(),
                     recursive,
+                    polled,
                 },
             );
             info!(
-                "Added {} watch path: {}",
+                "Added {} {} path: {}",
                 if recursive {
                     "recursive"
                 } else {
                     "non-recursive"
                 },
+                if polled { "poll" } else { "watch" },
                 path.display()
             );
         }
         Ok(())
     }
 
+    /// Spawn a task that polls `path` and feeds synthesized events into the
+    /// same channel as the native watcher.
+    fn start_poller(&self, key: PathBuf, path: &Path, recursive: bool) {
+        let root = path.to_path_buf();
+        let event_sender = self.event_sender.clone();
+        let policies = self.policies.clone();
+        let interval = self.poll_interval;
+        let (baseline_taken, baseline) = tokio::sync::oneshot::channel();
+        self.baselines
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .push(baseline);
+        let handle = tokio::spawn(async move {
+            let capture_root = root.clone();
+            let Ok(mut snapshot) = tokio::task::spawn_blocking(move || {
+                poll::PollSnapshot::capture(&capture_root, recursive)
+            })
+            .await
+            else {
+                return;
+            };
+            let _ = baseline_taken.send(());
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let policy_snapshot = policies
+                    .read()
+                    .unwrap_or_else(|error| error.into_inner())
+                    .clone();
+                let refreshed = tokio::task::spawn_blocking(move || {
+                    let events = snapshot.refresh(&policy_snapshot);
+                    (snapshot, events)
+                })
+                .await;
+                let events;
+                (snapshot, events) = match refreshed {
+                    Ok(refreshed) => refreshed,
+                    Err(error) => {
+                        error!("Polling {} failed: {}", root.display(), error);
+                        return;
+                    }
+                };
+                for event in events {
+                    if event_sender.send(event).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+        if let Some(previous) = self
+            .pollers
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .insert(key, handle)
+        {
+            previous.abort();
+        }
+    }
+
+    /// Wait until every polled root has captured its baseline snapshot.
+    /// Files created after this returns are reported as events; anything
+    /// earlier must be picked up by a scan.
+    pub async fn wait_for_baselines(&self) {
+        let pending = std::mem::take(
+            &mut *self
+                .baselines
+                .lock()
+                .unwrap_or_else(|error| error.into_inner()),
+        );
+        for baseline in pending {
+            // A poller that failed to start drops its sender; nothing to wait for.
+            let _ = baseline.await;
+        }
+    }
+
+    fn stop_poller(&self, key: &Path) {
+        if let Some(handle) = self
+            .pollers
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .remove(key)
+        {
+            handle.abort();
+        }
+    }
+
     /// Check if a file is a supported media file based on its extension
     pub fn is_media_file(&self, path: &Path) -> bool {
         is_media_file(path)
@@ -223,6 +345,34 @@ fn path_is_relevant(path: &Path, policies: &[ScanPolicy]) -> bool {
     ScanPolicy::for_path(policies, path).is_some_and(|policy| policy.allows_watched_path(path))
 }
 
+/// Resolve the configured watch mode for a directory. `Auto` polls network
+/// filesystems, where inotify and friends only see changes made locally.
+fn should_poll(path: &Path, policies: &[ScanPolicy]) -> bool {
+    let mode =
+        ScanPolicy::for_path(policies, path).map_or(WatchMode::Auto, |policy| policy.watch_mode);
+    match mode {
+        WatchMode::Native => false,
+        WatchMode::Poll => true,
+        WatchMode::Auto => match crate::platform::filesystem::is_network_filesystem(path) {
+            Some(true) => {
+                info!(
+                    "{} is on a network filesystem; polling for changes instead of native notifications",
+                    path.display()
+                );
+                true
+            }
+            Some(false) => false,
+            None => {
+                debug!(
+                    "Could not determine filesystem type of {}; using native notifications",
+                    path.display()
+                );
+                false
+            }
+        },
+    }
+}
+
 /// Convert notify events to our FileSystemEvent enum (helper)
 fn convert_watcher_events(
     events: Vec<DebouncedEvent>,
@@ -551,6 +701,24 @@ impl FileSystemWatcher for CrossPlatformWatcher {
 
                 let recursive = ScanPolicy::for_path(&policies, directory)
                     .is_none_or(|policy| policy.recursive);
+                if should_poll(directory, &policies) {
+                    let key = normalized_watch_key(directory);
+                    self.start_poller(key.clone(), directory, recursive);
+                    watched_paths.insert(
+                        key,
+                        WatchRegistration {
+                            path: directory.clone(),
+                            recursive,
+                            polled: true,
+                        },
+                    );
+                    info!(
+                        "Started polling directory every {}s: {}",
+                        self.poll_interval.as_secs(),
+                        directory.display()
+                    );
+                    continue;
+                }
                 let mode = if recursive {
                     RecursiveMode::Recursive
                 } else {
@@ -563,6 +731,7 @@ impl FileSystemWatcher for CrossPlatformWatcher {
                             WatchRegistration {
                                 path: directory.clone(),
                                 recursive,
+                                polled: false,
                             },
                         );
                         info!("Started watching directory: {}", directory.display());
@@ -596,6 +765,14 @@ impl FileSystemWatcher for CrossPlatformWatcher {
             // The debouncer will be dropped here, stopping the watcher
             drop(debouncer);
         }
+        for (_, handle) in self
+            .pollers
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .drain()
+        {
+            handle.abort();
+        }
 
         let mut watched_paths = self
             .watched_paths
@@ -640,12 +817,26 @@ impl FileSystemWatcher for CrossPlatformWatcher {
                 return Ok(());
             };
 
+            if registration.polled {
+                self.stop_poller(&key);
+                watched_paths.remove(&key);
+                info!("Removed poll path: {}", path.display());
+                return Ok(());
+            }
+
             match debouncer.unwatch(&registration.path) {
                 Ok(()) => {
                     watched_paths.remove(&key);
                     info!("Removed watch path: {}", path.display());
                     Ok(())
                 }
+                // The OS dropped the watch with the directory; forget it so
+                // the path can be watched again when it returns.
+                Err(_) if !registration.path.exists() => {
+                    watched_paths.remove(&key);
+                    info!("Forgot watch on missing path: {}", path.display());
+                    Ok(())
+                }
                 Err(e) => {
                     error!("Failed to remove watch path {}: {}", path.display(), e);
                     Err(e.into())
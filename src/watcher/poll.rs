@@ -0,0 +1,289 @@
+//! Polling change detection for mounts where native notifications never
+//! arrive (NFS, SMB/CIFS). Each pass compares directory modification times
+//! first and lists only directories whose mtime moved, so an idle library
+//! costs one `stat` per directory.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tracing::debug;
+
+use super::{path_is_relevant, FileSystemEvent};
+use crate::media::ScanPolicy;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileState {
+    size: u64,
+    modified: Option<SystemTime>,
+}
+
+#[derive(Debug, Default)]
+struct DirectoryState {
+    modified: Option<SystemTime>,
+    files: HashMap<PathBuf, FileState>,
+    subdirectories: HashSet<PathBuf>,
+}
+
+/// Last observed state of one polled root.
+#[derive(Debug)]
+pub(super) struct PollSnapshot {
+    root: PathBuf,
+    recursive: bool,
+    directories: HashMap<PathBuf, DirectoryState>,
+    /// Directories that changed on the previous pass. They are listed once
+    /// more so files still being copied are reported when their size settles,
+    /// even though an in-place write does not move the directory mtime.
+    unsettled: HashSet<PathBuf>,
+}
+
+impl PollSnapshot {
+    pub(super) fn capture(root: &Path, recursive: bool) -> Self {
+        let mut snapshot = Self {
+            root: root.to_path_buf(),
+            recursive,
+            directories: HashMap::new(),
+            unsettled: HashSet::new(),
+        };
+        snapshot.capture_tree(root);
+        snapshot
+    }
+
+    fn capture_tree(&mut self, directory: &Path) {
+        let mut pending = vec![directory.to_path_buf()];
+        while let Some(current) = pending.pop() {
+            let state = list_directory(&current);
+            if self.recursive {
+                pending.extend(state.subdirectories.iter().cloned());
+            }
+            self.directories.insert(current, state);
+        }
+    }
+
+    /// Compare the filesystem with the last pass and return synthesized events.
+    pub(super) fn refresh(&mut self, policies: &[ScanPolicy]) -> Vec<FileSystemEvent> {
+        let mut events = Vec::new();
+
+        let mut vanished = Vec::new();
+        let mut changed = Vec::new();
+        for (directory, state) in &self.directories {
+            match std::fs::metadata(directory) {
+                Ok(metadata) if metadata.is_dir() => {
+                    if metadata.modified().ok() != state.modified
+                        || self.unsettled.contains(directory)
+                    {
+                        changed.push(directory.clone());
+                    }
+                }
+                _ => vanished.push(directory.clone()),
+            }
+        }
+
+        vanished.sort();
+        for directory in &vanished {
+            self.directories.remove(directory);
+            self.unsettled.remove(directory);
+            if directory == &self.root {
+                // An unreachable mount is handled by root availability
+                // tracking, not by deleting the whole library.
+                continue;
+            }
+            let parent_vanished = vanished
+                .iter()
+                .any(|other| other != directory && directory.starts_with(other));
+            if !parent_vanished {
+                debug!(
+                    "Directory removed (detected by polling): {}",
+                    directory.display()
+                );
+                events.push(FileSystemEvent::Deleted {
+                    path: directory.clone(),
+                    is_directory: Some(true),
+                });
+            }
+        }
+
+        let mut unsettled = HashSet::new();
+        for directory in changed {
+            if !self.directories.contains_key(&directory) {
+                continue;
+            }
+            let current = list_directory(&directory);
+            let previous = self
+                .directories
+                .insert(directory.clone(), current)
+                .unwrap_or_default();
+            let current = &self.directories[&directory];
+            let mut directory_changed = false;
+
+            for (path, state) in &current.files {
+                match previous.files.get(path) {
+                    None if path_is_relevant(path, policies) => {
+                        debug!(
+                            "Media file created (detected by polling): {}",
+                            path.display()
+                        );
+                        events.push(FileSystemEvent::Created(path.clone()));
+                        directory_changed = true;
+                    }
+                    Some(previous) if previous != state && path_is_relevant(path, policies) => {
+                        debug!(
+                            "Media file modified (detected by polling): {}",
+                            path.display()
+                        );
+                        events.push(FileSystemEvent::Modified(path.clone()));
+                        directory_changed = true;
+                    }
+                    _ => {}
+                }
+            }
+            for path in previous.files.keys() {
+                if !current.files.contains_key(path) {
+                    debug!("Path deleted (detected by polling): {}", path.display());
+                    events.push(FileSystemEvent::Deleted {
+                        path: path.clone(),
+                        is_directory: Some(false),
+                    });
+                    directory_changed = true;
+                }
+            }
+
+            let created = if self.recursive {
+                current
+                    .subdirectories
+                    .difference(&previous.subdirectories)
+                    .cloned()
+                    .collect::<Vec<_>>()
+            } else {
+                Vec::new()
+            };
+            for subdirectory in created {
+                debug!(
+                    "Directory created (detected by polling): {}",
+                    subdirectory.display()
+                );
+                self.capture_tree(&subdirectory);
+                events.push(FileSystemEvent::Created(subdirectory));
+                directory_changed = true;
+            }
+
+            if directory_changed {
+                unsettled.insert(directory);
+            }
+        }
+        self.unsettled = unsettled;
+
+        events
+    }
+}
+
+fn list_directory(directory: &Path) -> DirectoryState {
+    let mut state = DirectoryState {
+        modified: std::fs::metadata(directory)
+            .and_then(|metadata| metadata.modified())
+            .ok(),
+        ..DirectoryState::default()
+    };
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return state;
+    };
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let path = entry.path();
+        if file_type.is_dir() {
+            state.subdirectories.insert(path);
+        } else if file_type.is_file() {
+            let metadata = entry.metadata().ok();
+            state.files.insert(
+                path,
+                FileState {
+                    size: metadata.as_ref().map_or(0, |metadata| metadata.len()),
+                    modified: metadata.and_then(|metadata| metadata.modified().ok()),
+                },
+            );
+        }
+    }
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn created_paths(events: &[FileSystemEvent]) -> Vec<PathBuf> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                FileSystemEvent::Created(path) => Some(path.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn touch_later(path: &Path) {
+        // Coarse mtime filesystems would otherwise hide a change made within
+        // the same tick as the capture.
+        let later = SystemTime::now() + std::time::Duration::from_secs(5);
+        std::fs::File::open(path)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+    }
+
+    #[test]
+    fn polling_reports_created_deleted_and_new_directories() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().to_path_buf();
+        std::fs::write(root.join("old.mp4"), b"old").unwrap();
+        let policies = vec![ScanPolicy::platform_default(&root, true)];
+        let mut snapshot = PollSnapshot::capture(&root, true);
+        assert!(snapshot.refresh(&policies).is_empty());
+
+        std::fs::write(root.join("new.mp4"), b"new").unwrap();
+        std::fs::write(root.join("notes.txt"), b"ignored").unwrap();
+        std::fs::remove_file(root.join("old.mp4")).unwrap();
+        std::fs::create_dir(root.join("season")).unwrap();
+        std::fs::write(root.join("season").join("episode.mkv"), b"ep").unwrap();
+        touch_later(&root);
+
+        let events = snapshot.refresh(&policies);
+        let mut created = created_paths(&events);
+        created.sort();
+        assert_eq!(created, vec![root.join("new.mp4"), root.join("season")]);
+        assert!(events.iter().any(|event| matches!(
+            event,
+            FileSystemEvent::Deleted { path, is_directory: Some(false) } if path == &root.join("old.mp4")
+        )));
+
+        // Files inside the new directory were captured with it and are not
+        // reported a second time.
+        let events = snapshot.refresh(&policies);
+        assert!(created_paths(&events).is_empty());
+    }
+
+    #[test]
+    fn polling_reports_growing_files_in_recently_changed_directories() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().to_path_buf();
+        let policies = vec![ScanPolicy::platform_default(&root, true)];
+        let mut snapshot = PollSnapshot::capture(&root, true);
+
+        let copy = root.join("copy.mkv");
+        std::fs::write(&copy, b"partial").unwrap();
+        touch_later(&root);
+        assert_eq!(
+            created_paths(&snapshot.refresh(&policies)),
+            vec![copy.clone()]
+        );
+
+        // The copy finishes without touching the directory entry.
+        std::fs::write(&copy, b"partial and complete").unwrap();
+        touch_later(&copy);
+        let events = snapshot.refresh(&policies);
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, FileSystemEvent::Modified(path) if path == &copy)));
+    }
+}
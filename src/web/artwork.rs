@@ -0,0 +1,180 @@
+//! Album-art downscaling for `media.art_max_dimension`.
+
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// Scaled covers kept in memory. The cache is emptied when it fills up.
+const CACHE_ENTRIES: usize = 256;
+const JPEG_QUALITY: u8 = 85;
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct ArtKey {
+    /// The image file, or the audio file for embedded art.
+    source: PathBuf,
+    modified: Option<SystemTime>,
+    max_dimension: u32,
+}
+
+static SCALED: Mutex<Option<HashMap<ArtKey, ScaledArt>>> = Mutex::new(None);
+
+#[derive(Clone, Debug)]
+pub(crate) struct ScaledArt {
+    pub data: Vec<u8>,
+    pub content_type: &'static str,
+}
+
+/// A scaled copy of `source`'s art made earlier for the same modification
+/// time and size limit.
+pub(crate) fn cached(
+    source: &Path,
+    modified: Option<SystemTime>,
+    max_dimension: u32,
+) -> Option<ScaledArt> {
+    let key = ArtKey {
+        source: source.to_path_buf(),
+        modified,
+        max_dimension,
+    };
+    SCALED
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .as_ref()?
+        .get(&key)
+        .cloned()
+}
+
+/// Scale `data`, the art of `source`, to fit `max_dimension` and remember the
+/// result. `None` means the art should be served as it is: it already fits,
+/// or its format cannot be decoded.
+pub(crate) async fn scale(
+    source: &Path,
+    modified: Option<SystemTime>,
+    data: axum::body::Bytes,
+    max_dimension: u32,
+) -> Option<ScaledArt> {
+    let scaled = tokio::task::spawn_blocking(move || downscale(&data, max_dimension))
+        .await
+        .ok()??;
+    let key = ArtKey {
+        source: source.to_path_buf(),
+        modified,
+        max_dimension,
+    };
+    let mut cache = SCALED
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let cache = cache.get_or_insert_with(HashMap::new);
+    if cache.len() >= CACHE_ENTRIES {
+        cache.clear();
+    }
+    cache.insert(key, scaled.clone());
+    Some(scaled)
+}
+
+/// Shrink an encoded image so neither side exceeds `max_dimension`, keeping
+/// its aspect ratio. Images with transparency stay PNG; the rest become
+/// JPEG.
+pub(crate) fn downscale(data: &[u8], max_dimension: u32) -> Option<ScaledArt> {
+    if max_dimension == 0 {
+        return None;
+    }
+    let reader = image::ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .ok()?;
+    let (width, height) = reader.into_dimensions().ok()?;
+    if width <= max_dimension && height <= max_dimension {
+        return None;
+    }
+
+    let decoded = image::load_from_memory(data)
+        .inspect_err(|error| tracing::debug!("Serving album art unscaled: {}", error))
+        .ok()?;
+    let thumbnail = decoded.thumbnail(max_dimension, max_dimension);
+    let mut encoded = Cursor::new(Vec::new());
+    let content_type = if thumbnail.color().has_alpha() {
+        thumbnail
+            .write_to(&mut encoded, image::ImageFormat::Png)
+            .ok()?;
+        "image/png"
+    } else {
+        image::codecs::jpeg::JpegEncoder::new_with_quality(&mut encoded, JPEG_QUALITY)
+            .encode_image(&thumbnail.to_rgb8())
+            .ok()?;
+        "image/jpeg"
+    };
+    Some(ScaledArt {
+        data: encoded.into_inner(),
+        content_type,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encoded(image: image::DynamicImage, format: image::ImageFormat) -> Vec<u8> {
+        let mut data = Cursor::new(Vec::new());
+        image.write_to(&mut data, format).unwrap();
+        data.into_inner()
+    }
+
+    #[test]
+    fn large_covers_are_scaled_to_fit_and_keep_their_aspect_ratio() {
+        let cover = encoded(
+            image::DynamicImage::new_rgb8(3000, 1500),
+            image::ImageFormat::Jpeg,
+        );
+
+        let scaled = downscale(&cover, 500).unwrap();
+
+        assert_eq!(scaled.content_type, "image/jpeg");
+        let decoded = image::load_from_memory(&scaled.data).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (500, 250));
+        assert!(scaled.data.len() < cover.len());
+    }
+
+    #[test]
+    fn transparent_covers_stay_png() {
+        let cover = encoded(
+            image::DynamicImage::new_rgba8(800, 1600),
+            image::ImageFormat::Png,
+        );
+
+        let scaled = downscale(&cover, 400).unwrap();
+
+        assert_eq!(scaled.content_type, "image/png");
+        let decoded = image::load_from_memory(&scaled.data).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (200, 400));
+    }
+
+    #[test]
+    fn small_undecodable_or_unlimited_covers_are_served_as_they_are() {
+        let cover = encoded(
+            image::DynamicImage::new_rgb8(300, 300),
+            image::ImageFormat::Png,
+        );
+        assert!(downscale(&cover, 300).is_none());
+        assert!(downscale(&cover, 0).is_none());
+        assert!(downscale(b"not an image", 100).is_none());
+    }
+
+    #[tokio::test]
+    async fn scaled_covers_are_cached_per_source_version_and_limit() {
+        let source = Path::new("/music/cached-cover-test.mp3");
+        let modified = Some(SystemTime::UNIX_EPOCH);
+        let cover = encoded(
+            image::DynamicImage::new_rgb8(1200, 1200),
+            image::ImageFormat::Jpeg,
+        );
+
+        assert!(cached(source, modified, 100).is_none());
+        let scaled = scale(source, modified, cover.into(), 100).await.unwrap();
+
+        assert_eq!(cached(source, modified, 100).unwrap().data, scaled.data);
+        assert!(cached(source, modified, 200).is_none());
+        assert!(cached(source, Some(SystemTime::now()), 100).is_none());
+    }
+}
@@ -0,0 +1,332 @@
+//! Append-only JSON-lines audit log of media streaming sessions.
+//!
+//! Enabled by `logging.stream_audit_file`. Each completed or aborted
+//! `/media/{id}` body produces one line, independent of the tracing log level.
+//! Records are handed to a dedicated writer thread through a bounded queue so
+//! the streaming path never waits on the disk; when the queue is full or a
+//! write fails the record is dropped and counted instead.
+
+use chrono::{DateTime, Local, NaiveDate};
+use serde::Serialize;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+const AUDIT_QUEUE_CAPACITY: usize = 1_024;
+
+/// One line of the stream audit log.
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamAuditRecord {
+    /// When the session ended, in RFC 3339 local time.
+    pub timestamp: String,
+    pub client_ip: String,
+    pub user_agent: Option<String>,
+    /// Canonical path of the streamed file.
+    pub path: String,
+    /// First and last byte offsets requested (inclusive).
+    pub range_start: u64,
+    pub range_end: u64,
+    pub bytes_served: u64,
+    pub duration_ms: u64,
+    /// Share of the requested range that was delivered.
+    pub completion_percent: f64,
+    /// `false` when the client disconnected before the range was delivered.
+    pub completed: bool,
+}
+
+enum AuditCommand {
+    Write(Box<StreamAuditRecord>),
+    Flush(std::sync::mpsc::Sender<()>),
+    Shutdown,
+}
+
+pub struct StreamAuditLog {
+    sender: std::sync::mpsc::SyncSender<AuditCommand>,
+    dropped: Arc<AtomicU64>,
+    worker: Mutex<Option<std::thread::JoinHandle<()>>>,
+}
+
+impl StreamAuditLog {
+    /// Open (or create) the audit file and start its writer thread.
+    pub fn open(path: PathBuf) -> std::io::Result<Self> {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = DailyFile::open(path)?;
+        let dropped = Arc::new(AtomicU64::new(0));
+        let worker_dropped = dropped.clone();
+        let (sender, receiver) = std::sync::mpsc::sync_channel(AUDIT_QUEUE_CAPACITY);
+        let worker = std::thread::Builder::new()
+            .name("vuio-audit-writer".to_owned())
+            .spawn(move || {
+                while let Ok(command) = receiver.recv() {
+                    match command {
+                        AuditCommand::Write(record) => {
+                            if let Err(error) = file.append(&record) {
+                                worker_dropped.fetch_add(1, Ordering::Relaxed);
+                                tracing::debug!("Dropped stream audit record: {}", error);
+                            }
+                        }
+                        AuditCommand::Flush(done) => {
+                            let _ = file.sync();
+                            let _ = done.send(());
+                        }
+                        AuditCommand::Shutdown => {
+                            let _ = file.sync();
+                            break;
+                        }
+                    }
+                }
+            })?;
+        Ok(Self {
+            sender,
+            dropped,
+            worker: Mutex::new(Some(worker)),
+        })
+    }
+
+    /// Queue a record without blocking; it is counted as dropped if the
+    /// writer is behind.
+    pub fn record(&self, record: StreamAuditRecord) {
+        if self
+            .sender
+            .try_send(AuditCommand::Write(Box::new(record)))
+            .is_err()
+        {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Block until every queued record has been written and synced.
+    pub fn flush(&self) {
+        let (done, wait) = std::sync::mpsc::channel();
+        if self.sender.send(AuditCommand::Flush(done)).is_ok() {
+            let _ = wait.recv();
+        }
+    }
+
+    pub fn dropped_records(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for StreamAuditLog {
+    fn drop(&mut self) {
+        let _ = self.sender.send(AuditCommand::Shutdown);
+        if let Some(worker) = self
+            .worker
+            .get_mut()
+            .unwrap_or_else(|error| error.into_inner())
+            .take()
+        {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Tracks the bytes delivered for one streamed body and writes its audit
+/// record when the body is dropped, whether it finished or was aborted.
+pub(crate) struct StreamAuditSession {
+    log: Arc<StreamAuditLog>,
+    client_ip: String,
+    user_agent: Option<String>,
+    path: String,
+    range_start: u64,
+    range_end: u64,
+    expected: u64,
+    bytes_served: u64,
+    started: Instant,
+}
+
+impl StreamAuditSession {
+    pub(crate) fn new(
+        log: Arc<StreamAuditLog>,
+        client_ip: String,
+        user_agent: Option<String>,
+        path: &Path,
+        range: (u64, u64),
+        expected: u64,
+    ) -> Self {
+        Self {
+            log,
+            client_ip,
+            user_agent,
+            path: path.to_string_lossy().into_owned(),
+            range_start: range.0,
+            range_end: range.1,
+            expected,
+            bytes_served: 0,
+            started: Instant::now(),
+        }
+    }
+
+    pub(crate) fn add_bytes(&mut self, bytes: u64) {
+        self.bytes_served = self.bytes_served.saturating_add(bytes);
+    }
+}
+
+impl Drop for StreamAuditSession {
+    fn drop(&mut self) {
+        let completed = self.bytes_served >= self.expected;
+        let completion_percent = if self.expected == 0 {
+            100.0
+        } else {
+            (self.bytes_served as f64 / self.expected as f64 * 1000.0).round() / 10.0
+        };
+        self.log.record(StreamAuditRecord {
+            timestamp: Local::now().to_rfc3339(),
+            client_ip: std::mem::take(&mut self.client_ip),
+            user_agent: self.user_agent.take(),
+            path: std::mem::take(&mut self.path),
+            range_start: self.range_start,
+            range_end: self.range_end,
+            bytes_served: self.bytes_served,
+            duration_ms: self.started.elapsed().as_millis() as u64,
+            completion_percent,
+            completed,
+        });
+    }
+}
+
+/// The active audit file, rotated to `<stem>.<YYYY-MM-DD>.<ext>` when the
+/// local date changes. Rotated files are synced before being renamed and
+/// are never deleted.
+struct DailyFile {
+    path: PathBuf,
+    file: std::fs::File,
+    day: NaiveDate,
+}
+
+impl DailyFile {
+    fn open(path: PathBuf) -> std::io::Result<Self> {
+        let file = Self::open_append(&path)?;
+        let day = file
+            .metadata()
+            .ok()
+            .filter(|metadata| metadata.len() > 0)
+            .and_then(|metadata| metadata.modified().ok())
+            .map(|modified| DateTime::<Local>::from(modified).date_naive())
+            .unwrap_or_else(|| Local::now().date_naive());
+        Ok(Self { path, file, day })
+    }
+
+    fn open_append(path: &Path) -> std::io::Result<std::fs::File> {
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+    }
+
+    fn append(&mut self, record: &StreamAuditRecord) -> std::io::Result<()> {
+        let today = Local::now().date_naive();
+        if today != self.day {
+            self.rotate(today)?;
+        }
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        self.file.write_all(&line)
+    }
+
+    fn rotate(&mut self, today: NaiveDate) -> std::io::Result<()> {
+        self.file.sync_all()?;
+        std::fs::rename(&self.path, rotated_path(&self.path, self.day))?;
+        self.file = Self::open_append(&self.path)?;
+        self.day = today;
+        Ok(())
+    }
+
+    fn sync(&mut self) -> std::io::Result<()> {
+        self.file.sync_data()
+    }
+}
+
+fn rotated_path(path: &Path, day: NaiveDate) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "stream-audit".to_owned());
+    let name = match path.extension() {
+        Some(extension) => format!("{stem}.{day}.{}", extension.to_string_lossy()),
+        None => format!("{stem}.{day}"),
+    };
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotation_keeps_the_extension_after_the_date() {
+        let day = NaiveDate::from_ymd_opt(2024, 3, 9).unwrap();
+        assert_eq!(
+            rotated_path(Path::new("/var/log/vuio/streams.jsonl"), day),
+            PathBuf::from("/var/log/vuio/streams.2024-03-09.jsonl")
+        );
+        assert_eq!(
+            rotated_path(Path::new("audit"), day),
+            PathBuf::from("audit.2024-03-09")
+        );
+    }
+
+    #[test]
+    fn rotating_syncs_the_previous_day_to_a_dated_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("streams.jsonl");
+        let mut file = DailyFile::open(path.clone()).unwrap();
+        let yesterday = file.day.pred_opt().unwrap();
+        file.day = yesterday;
+        file.file.write_all(b"{}\n").unwrap();
+
+        let record = StreamAuditRecord {
+            timestamp: Local::now().to_rfc3339(),
+            client_ip: "192.168.1.20".to_owned(),
+            user_agent: None,
+            path: "/media/song.mp3".to_owned(),
+            range_start: 0,
+            range_end: 9,
+            bytes_served: 10,
+            duration_ms: 1,
+            completion_percent: 100.0,
+            completed: true,
+        };
+        file.append(&record).unwrap();
+
+        let rotated = std::fs::read_to_string(rotated_path(&path, yesterday)).unwrap();
+        assert_eq!(rotated, "{}\n");
+        let current = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(current.lines().count(), 1);
+        assert!(current.contains("\"client_ip\":\"192.168.1.20\""));
+    }
+
+    #[test]
+    fn a_dropped_session_writes_one_record_with_its_completion() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("streams.jsonl");
+        let log = Arc::new(StreamAuditLog::open(path.clone()).unwrap());
+
+        let mut session = StreamAuditSession::new(
+            log.clone(),
+            "10.0.0.5".to_owned(),
+            Some("VLC/3.0".to_owned()),
+            Path::new("/media/film.mkv"),
+            (0, 199),
+            200,
+        );
+        session.add_bytes(50);
+        drop(session);
+        log.flush();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let line: serde_json::Value = serde_json::from_str(content.trim()).unwrap();
+        assert_eq!(line["bytes_served"], 50);
+        assert_eq!(line["completion_percent"], 25.0);
+        assert_eq!(line["completed"], false);
+        assert_eq!(log.dropped_records(), 0);
+    }
+}
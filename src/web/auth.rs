@@ -153,6 +153,16 @@ impl AuthState {
         self.auth_enabled
     }
 
+    /// Whether a request from `peer` carries what [`require_management`]
+    /// accepts, without counting it against the management rate limit.
+    pub fn is_management_caller(&self, headers: &HeaderMap, peer: IpAddr) -> bool {
+        self.enabled
+            && self.network_allowed(peer)
+            && (!self.auth_enabled
+                || self.bearer_valid(headers)
+                || self.session_from_headers(headers, peer).is_some())
+    }
+
     fn network_allowed(&self, address: IpAddr) -> bool {
         address.is_loopback()
             || self
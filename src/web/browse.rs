@@ -0,0 +1,280 @@
+//! Read-only JSON view of the ContentDirectory folder tree for web front-ends.
+//!
+//! `GET /api/browse?path=<objectId>` lists the same containers and items a
+//! renderer sees when browsing `video`, `audio/folders` and `image`, and
+//! `GET /api/item/{id}` returns one item. Container and item IDs are the
+//! ContentDirectory object IDs, so either API can continue where the other
+//! left off.
+
+use crate::{
+    database::{DatabaseManager, MediaDirectory, MediaFile},
+    error::AppError,
+    platform::filesystem::{path_from_lossless_string, path_to_lossless_string},
+    state::AppState,
+    web::{
+        soap::{hidden_files, root_folders, FolderBrowseTarget},
+        xml::{media_object_id, parse_media_object_id},
+    },
+};
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+
+/// Folder-browse roots and the MIME family each one lists.
+const FOLDER_ROOTS: [(&str, &str, &str); 3] = [
+    ("video", "Video", "video/"),
+    ("audio/folders", "Music", "audio/"),
+    ("image", "Pictures", "image/"),
+];
+
+#[derive(serde::Deserialize)]
+pub struct BrowseQuery {
+    path: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct BrowseListing {
+    pub id: String,
+    pub parent: Option<String>,
+    pub containers: Vec<BrowseContainer>,
+    pub items: Vec<BrowseItem>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct BrowseContainer {
+    pub id: String,
+    pub title: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct BrowseItem {
+    pub id: String,
+    pub title: String,
+    pub mime_type: String,
+    pub size: u64,
+    pub duration_seconds: Option<u64>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub album_artist: Option<String>,
+    pub genre: Option<String>,
+    pub track_number: Option<u32>,
+    pub year: Option<u32>,
+    pub stream_url: String,
+    pub album_art_url: Option<String>,
+    pub subtitle_url: Option<String>,
+}
+
+impl BrowseItem {
+    fn from_media_file(file: &MediaFile, iso_mime_type: &str) -> Self {
+        let id = media_object_id(&path_to_lossless_string(&file.path));
+        let stream_url = format!("/media/{id}");
+        Self {
+            title: file.title.clone().unwrap_or_else(|| file.filename.clone()),
+            mime_type: crate::dvd::served_mime_type(&file.mime_type, iso_mime_type).to_string(),
+            size: file.size,
+            duration_seconds: file.duration.map(|duration| duration.as_secs()),
+            artist: file.artist.clone(),
+            album: file.album.clone(),
+            album_artist: file.album_artist.clone(),
+            genre: file.genre.clone(),
+            track_number: file.track_number,
+            year: file.year,
+            // `/media/{id}/cover` only serves art for audio files.
+            album_art_url: file
+                .mime_type
+                .starts_with("audio/")
+                .then(|| format!("{stream_url}/cover")),
+            subtitle_url: file
+                .subtitle_available
+                .then(|| format!("{stream_url}/subtitle")),
+            stream_url,
+            id,
+        }
+    }
+}
+
+/// Split a folder-browse object ID into its MIME family and the folder path
+/// below the family root; `None` for IDs outside the folder tree.
+fn folder_object(object_id: &str) -> Option<(&'static str, &str)> {
+    FOLDER_ROOTS.iter().find_map(|&(root, _, family)| {
+        let rest = object_id.strip_prefix(root)?;
+        if rest.is_empty() {
+            Some((family, rest))
+        } else {
+            rest.strip_prefix('/').map(|rest| (family, rest))
+        }
+    })
+}
+
+fn parent_object_id(object_id: &str) -> String {
+    if FOLDER_ROOTS.iter().any(|&(root, _, _)| root == object_id) {
+        return "0".to_string();
+    }
+    object_id
+        .rsplit_once('/')
+        .map(|(parent, _)| parent.to_string())
+        .unwrap_or_else(|| "0".to_string())
+}
+
+pub async fn browse_handler<D: DatabaseManager + 'static>(
+    State(state): State<AppState<D>>,
+    Query(query): Query<BrowseQuery>,
+) -> Result<Json<BrowseListing>, AppError> {
+    let object_id = query
+        .path
+        .as_deref()
+        .map(|path| path.trim_end_matches('/'))
+        .filter(|path| !path.is_empty())
+        .unwrap_or("0");
+
+    if object_id == "0" {
+        return Ok(Json(BrowseListing {
+            id: "0".to_string(),
+            parent: None,
+            containers: FOLDER_ROOTS
+                .iter()
+                .map(|&(id, title, _)| BrowseContainer {
+                    id: id.to_string(),
+                    title: title.to_string(),
+                })
+                .collect(),
+            items: Vec::new(),
+        }));
+    }
+    let (family, folder) = folder_object(object_id).ok_or(AppError::NotFound)?;
+
+    let monitored_dirs = state.media_directories.read().await.clone();
+    let unavailable_roots = state.unavailable_roots.read().await.clone();
+    let (directories, files) = if folder.is_empty() && monitored_dirs.len() > 1 {
+        (
+            root_folders(&monitored_dirs, &unavailable_roots, family),
+            Vec::new(),
+        )
+    } else {
+        let target = FolderBrowseTarget::resolve(
+            &monitored_dirs,
+            &unavailable_roots,
+            state.current_config().get_primary_media_dir(),
+            folder,
+        );
+        let parents = if target.merged_parents.len() > 1 {
+            target.merged_parents
+        } else if target.browse_path.is_dir()
+            && !unavailable_roots
+                .iter()
+                .any(|root| target.browse_path.starts_with(root))
+        {
+            vec![target.browse_path]
+        } else {
+            Vec::new()
+        };
+
+        let mut directories = Vec::<MediaDirectory>::new();
+        let mut files = Vec::new();
+        for parent in parents {
+            let canonical_parent = state
+                .filesystem_manager
+                .get_canonical_path(&parent)
+                .map(|canonical| path_from_lossless_string(&canonical))
+                .unwrap_or_else(|_| state.filesystem_manager.normalize_path(&parent));
+            let (subdirectories, listed) = state
+                .database
+                .get_directory_listing(&canonical_parent, family)
+                .await?;
+            // Merge group members contribute folders of the same name once.
+            for directory in subdirectories {
+                if !directories
+                    .iter()
+                    .any(|existing| existing.name == directory.name)
+                {
+                    directories.push(directory);
+                }
+            }
+            files.extend(listed);
+        }
+        let hidden = hidden_files(&state).await;
+        files.retain(|file: &MediaFile| !file.id.is_some_and(|id| hidden.contains(&id)));
+        (directories, files)
+    };
+
+    let iso_mime_type = state.current_config().media.iso_mime_type.clone();
+    Ok(Json(BrowseListing {
+        id: object_id.to_string(),
+        parent: Some(parent_object_id(object_id)),
+        containers: directories
+            .into_iter()
+            .map(|directory| {
+                let path = path_to_lossless_string(&directory.path);
+                // Root folders are addressed by their `dN` index, other
+                // folders by name, exactly as in the DIDL-Lite listing.
+                let child =
+                    if path.starts_with('d') && path[1..].bytes().all(|b| b.is_ascii_digit()) {
+                        path.into_owned()
+                    } else {
+                        directory.name.clone()
+                    };
+                BrowseContainer {
+                    id: format!("{object_id}/{child}"),
+                    title: path_from_lossless_string(&directory.name)
+                        .to_string_lossy()
+                        .into_owned(),
+                }
+            })
+            .collect(),
+        items: files
+            .iter()
+            .map(|file| BrowseItem::from_media_file(file, &iso_mime_type))
+            .collect(),
+    }))
+}
+
+pub async fn item_handler<D: DatabaseManager + 'static>(
+    State(state): State<AppState<D>>,
+    Path(id): Path<String>,
+) -> Result<Json<BrowseItem>, AppError> {
+    let file_id = if let Some(key) = parse_media_object_id(&id) {
+        state
+            .database
+            .get_file_location_by_object_key(key)
+            .await?
+            .ok_or(AppError::NotFound)?
+            .id
+    } else {
+        id.parse::<i64>().map_err(|_| AppError::NotFound)?
+    };
+    let file = state
+        .database
+        .get_file_by_id(file_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    Ok(Json(BrowseItem::from_media_file(
+        &file,
+        &state.current_config().media.iso_mime_type,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folder_object_ids_map_to_their_mime_family() {
+        assert_eq!(folder_object("video"), Some(("video/", "")));
+        assert_eq!(
+            folder_object("video/d1/Films"),
+            Some(("video/", "d1/Films"))
+        );
+        assert_eq!(
+            folder_object("audio/folders/Jazz"),
+            Some(("audio/", "Jazz"))
+        );
+        assert_eq!(folder_object("audio/artists"), None);
+        assert_eq!(folder_object("videos"), None);
+        assert_eq!(folder_object("radio"), None);
+
+        assert_eq!(parent_object_id("audio/folders"), "0");
+        assert_eq!(parent_object_id("audio/folders/d0"), "audio/folders");
+        assert_eq!(parent_object_id("image/d2/Trips/2024"), "image/d2/Trips");
+    }
+}
@@ -1,6 +1,7 @@
 use axum::http::HeaderMap;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum DlnaClientProfile {
     Xbox,
     PlayStation,
@@ -0,0 +1,203 @@
+//! `ETag` / `Last-Modified` validators and `304 Not Modified` answers to
+//! conditional GETs, so renderers refetching unchanged media, art and browse
+//! results get an empty response instead of the same bytes again.
+
+use std::path::Path;
+use std::time::SystemTime;
+
+use axum::{
+    body::Body,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::Response,
+};
+use chrono::{DateTime, Utc};
+
+/// Validators of one representation of a resource.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Validators {
+    etag: String,
+    last_modified: Option<DateTime<Utc>>,
+}
+
+impl Validators {
+    /// A file identified by its path, size and modification time. `variant`
+    /// separates representations of the same file, such as art scaled to
+    /// different sizes.
+    pub(super) fn for_file(
+        path: &Path,
+        size: u64,
+        modified: Option<SystemTime>,
+        variant: u64,
+    ) -> Self {
+        let modified = modified.map(DateTime::<Utc>::from);
+        let mut bytes = path.to_string_lossy().into_owned().into_bytes();
+        bytes.extend_from_slice(&size.to_le_bytes());
+        if let Some(modified) = modified {
+            bytes.extend_from_slice(
+                &modified
+                    .timestamp_nanos_opt()
+                    .unwrap_or_default()
+                    .to_le_bytes(),
+            );
+        }
+        bytes.extend_from_slice(&variant.to_le_bytes());
+        Self {
+            etag: format!("\"{:016x}\"", crate::database::fnv1a(&bytes)),
+            last_modified: modified,
+        }
+    }
+
+    /// A ContentDirectory response, current until `SystemUpdateID` changes.
+    /// `request` covers everything else the response depends on.
+    pub(super) fn for_update_id(update_id: u32, request: &[u8]) -> Self {
+        Self {
+            etag: format!("\"{update_id}-{:016x}\"", crate::database::fnv1a(request)),
+            last_modified: None,
+        }
+    }
+
+    /// A generated document such as `description.xml`, identified by its
+    /// bytes so that renaming the server changes the tag.
+    pub(super) fn for_content(content: &[u8]) -> Self {
+        Self {
+            etag: format!("\"{:016x}\"", crate::database::fnv1a(content)),
+            last_modified: None,
+        }
+    }
+
+    /// Whether the request's preconditions say the client's copy is current.
+    /// `If-None-Match` takes precedence over `If-Modified-Since`.
+    pub(super) fn not_modified(&self, headers: &HeaderMap) -> bool {
+        if let Some(value) = headers.get(header::IF_NONE_MATCH) {
+            let Ok(value) = value.to_str() else {
+                return false;
+            };
+            let ours = self.etag.trim_start_matches("W/");
+            return value
+                .split(',')
+                .map(str::trim)
+                .any(|tag| tag == "*" || tag.trim_start_matches("W/") == ours);
+        }
+        let (Some(last_modified), Some(since)) = (
+            self.last_modified,
+            headers
+                .get(header::IF_MODIFIED_SINCE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| DateTime::parse_from_rfc2822(value).ok()),
+        ) else {
+            return false;
+        };
+        // HTTP dates have whole-second resolution.
+        last_modified.timestamp() <= since.timestamp()
+    }
+
+    /// Add `ETag` and `Last-Modified` to a response's headers.
+    pub(super) fn insert_into(&self, headers: &mut HeaderMap) {
+        if let Ok(etag) = HeaderValue::from_str(&self.etag) {
+            headers.insert(header::ETAG, etag);
+        }
+        if let Some(last_modified) = self.last_modified {
+            let date = last_modified
+                .format("%a, %d %b %Y %H:%M:%S GMT")
+                .to_string();
+            if let Ok(date) = HeaderValue::from_str(&date) {
+                headers.insert(header::LAST_MODIFIED, date);
+            }
+        }
+    }
+
+    /// An empty `304 Not Modified` carrying the validators.
+    pub(super) fn not_modified_response(&self) -> Response {
+        let mut response = Response::new(Body::empty());
+        *response.status_mut() = StatusCode::NOT_MODIFIED;
+        self.insert_into(response.headers_mut());
+        response
+    }
+}
+
+/// `Cache-Control` letting clients reuse a response for `max_age` seconds,
+/// on full and `304` responses alike. Zero makes them revalidate every time.
+pub(super) fn cache_control(max_age: u64) -> HeaderValue {
+    if max_age == 0 {
+        return HeaderValue::from_static("no-cache");
+    }
+    HeaderValue::from_str(&format!("public, max-age={max_age}"))
+        .unwrap_or_else(|_| HeaderValue::from_static("no-cache"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn modified() -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_millis(1_700_000_000_250)
+    }
+
+    fn request(name: header::HeaderName, value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn file_validators_change_with_size_time_and_variant() {
+        let path = Path::new("/media/song.flac");
+        let base = Validators::for_file(path, 10, Some(modified()), 0);
+        assert_eq!(base, Validators::for_file(path, 10, Some(modified()), 0));
+        assert_ne!(base, Validators::for_file(path, 11, Some(modified()), 0));
+        assert_ne!(
+            base,
+            Validators::for_file(path, 10, Some(modified() + Duration::from_secs(1)), 0)
+        );
+        assert_ne!(base, Validators::for_file(path, 10, Some(modified()), 512));
+    }
+
+    #[test]
+    fn if_none_match_compares_weakly_and_wins_over_dates() {
+        let validators = Validators::for_file(Path::new("/a"), 1, Some(modified()), 0);
+        let mut headers = HeaderMap::new();
+        validators.insert_into(&mut headers);
+        let etag = headers[header::ETAG].to_str().unwrap().to_owned();
+        assert_eq!(
+            headers[header::LAST_MODIFIED],
+            "Tue, 14 Nov 2023 22:13:20 GMT"
+        );
+
+        let matching = format!("\"other\", W/{etag}");
+        assert!(validators.not_modified(&request(header::IF_NONE_MATCH, &matching)));
+        assert!(validators.not_modified(&request(header::IF_NONE_MATCH, "*")));
+        let mut stale = request(header::IF_NONE_MATCH, "\"other\"");
+        stale.insert(
+            header::IF_MODIFIED_SINCE,
+            "Tue, 14 Nov 2023 22:13:20 GMT".parse().unwrap(),
+        );
+        assert!(!validators.not_modified(&stale));
+    }
+
+    #[test]
+    fn if_modified_since_ignores_sub_second_precision() {
+        let validators = Validators::for_file(Path::new("/a"), 1, Some(modified()), 0);
+        let since = |date| request(header::IF_MODIFIED_SINCE, date);
+        assert!(validators.not_modified(&since("Tue, 14 Nov 2023 22:13:20 GMT")));
+        assert!(!validators.not_modified(&since("Tue, 14 Nov 2023 22:13:19 GMT")));
+        assert!(!validators.not_modified(&since("yesterday")));
+        let undated = Validators::for_update_id(7, b"browse");
+        assert!(!undated.not_modified(&since("Tue, 14 Nov 2023 22:13:20 GMT")));
+    }
+
+    #[test]
+    fn content_validators_follow_the_document() {
+        let document = Validators::for_content(b"<friendlyName>VuIO</friendlyName>");
+        assert_eq!(
+            document,
+            Validators::for_content(b"<friendlyName>VuIO</friendlyName>")
+        );
+        assert_ne!(
+            document,
+            Validators::for_content(b"<friendlyName>Den</friendlyName>")
+        );
+        assert_eq!(cache_control(0), "no-cache");
+        assert_eq!(cache_control(60), "public, max-age=60");
+    }
+}
@@ -2,12 +2,14 @@
 
 use crate::{database::DatabaseManager, state::AppState};
 use axum::{
-    extract::State,
-    http::{header, StatusCode},
+    extract::{ConnectInfo, State},
+    http::{header, HeaderMap, StatusCode},
     response::IntoResponse,
+    Json,
 };
+use std::net::SocketAddr;
 use std::sync::atomic::{AtomicU64, Ordering};
-use tracing::error;
+use tracing::{error, info};
 
 async fn collect_runtime_diagnostics<D: DatabaseManager>(
     state: &AppState<D>,
@@ -136,6 +138,89 @@ pub struct WebHandlerStats {
     pub gigabytes_transferred: f64,
 }
 
+#[derive(serde::Serialize)]
+pub struct LibraryStatsResponse {
+    backend: &'static str,
+    database: crate::database::DatabaseStats,
+    media_types: crate::database::MediaTypeCounts,
+    last_scan: Option<ScanSummary>,
+    watcher: WatcherStats,
+}
+
+#[derive(serde::Serialize)]
+struct ScanSummary {
+    running: bool,
+    status: crate::scan_progress::ScanStatus,
+    /// Unix seconds.
+    started_at: u64,
+    finished_at: Option<u64>,
+    /// Until `finished_at`, or until the last progress update while running.
+    duration_secs: u64,
+    files_scanned: u64,
+    changes: u64,
+}
+
+#[derive(serde::Serialize)]
+struct WatcherStats {
+    events_handled: u64,
+    files_processed: u64,
+    directories_scanned: u64,
+    errors: u64,
+    /// Unix seconds.
+    last_activity: u64,
+}
+
+/// Library size, media-type counts, the last scan and watcher activity, for
+/// graphing growth over time. Everything comes from maintained counters and
+/// stored scan state; nothing walks the library.
+pub async fn get_stats_handler<D: DatabaseManager>(
+    State(state): State<AppState<D>>,
+) -> Result<Json<LibraryStatsResponse>, crate::error::AppError> {
+    let (database, media_types, scan) = tokio::try_join!(
+        state.database.get_stats(),
+        state.database.count_by_media_type(),
+        crate::scan_progress::ScanState::load(state.database.as_ref())
+    )?;
+    let running = state.scan_control.is_running();
+    let last_scan = scan.map(|scan| {
+        let status = if scan.status == crate::scan_progress::ScanStatus::Running && !running {
+            crate::scan_progress::ScanStatus::Interrupted
+        } else {
+            scan.status
+        };
+        ScanSummary {
+            running,
+            status,
+            started_at: scan.started_at,
+            finished_at: scan.finished_at,
+            duration_secs: scan
+                .finished_at
+                .unwrap_or(scan.updated_at)
+                .saturating_sub(scan.started_at),
+            files_scanned: scan.files_scanned,
+            changes: scan.changes,
+        }
+    });
+    let (files_processed, directories_scanned, events_handled, errors, last_activity) =
+        state.lifecycle_stats.snapshot();
+    Ok(Json(LibraryStatsResponse {
+        backend: state.database.backend_name(),
+        database,
+        media_types,
+        last_scan,
+        watcher: WatcherStats {
+            events_handled,
+            files_processed,
+            directories_scanned,
+            errors,
+            last_activity: last_activity
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        },
+    }))
+}
+
 /// Get web handler performance metrics for monitoring
 pub async fn get_web_metrics<D: DatabaseManager>(
     State(state): State<AppState<D>>,
@@ -179,6 +264,10 @@ pub async fn get_web_metrics<D: DatabaseManager>(
             "cache_hit_rate_percent": stats.cache_hit_rate,
             "directory_listings": stats.directory_listings,
             "dropped_file_log_records": crate::logging::dropped_file_logs(),
+            "dropped_stream_audit_records": state
+                .stream_audit
+                .as_ref()
+                .map_or(0, |audit| audit.dropped_records()),
             "file_serves": stats.file_serves,
             "errors": stats.errors,
             "average_response_time_ms": stats.average_response_time_ms,
@@ -203,6 +292,7 @@ pub async fn get_web_metrics<D: DatabaseManager>(
             "platform": state.platform_info.os_type.display_name(),
             "architecture": std::env::consts::ARCH,
             "unavailable_or_incomplete_roots": unavailable_roots,
+            "log_filter": crate::logging::log_filter().map(|filter| filter.current()),
         },
         "configuration": {
             "desired": {
@@ -263,11 +353,87 @@ async fn read_last_log_lines(
 pub async fn healthz_handler() -> impl IntoResponse {
     (
         StatusCode::OK,
-        [(header::CONTENT_TYPE, "application/json")],
-        r#"{"status":"healthy"}"#,
+        Json(serde_json::json!({
+            "status": "healthy",
+        })),
     )
 }
 
+/// Container probe: `200` once the database is initialized and HTTP is
+/// serving, `503` while starting or after the database health check found
+/// corruption it could not repair. Reads two flags and nothing else.
+/// Management callers also get the active `log_filter`.
+pub async fn health_handler<D: DatabaseManager>(
+    State(state): State<AppState<D>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Some(reason) = state.health.database_failure() {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({ "status": "unhealthy", "reason": reason })),
+        );
+    }
+    if !state.health.is_serving() {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({ "status": "starting" })),
+        );
+    }
+    let mut body = serde_json::json!({
+        "status": "ok",
+        "database": state.database.backend_name(),
+        "scanning": state.scan_control.is_running(),
+    });
+    if state.auth.load().is_management_caller(&headers, peer.ip()) {
+        body["log_filter"] =
+            serde_json::json!(crate::logging::log_filter().map(|filter| filter.current()));
+    }
+    (StatusCode::OK, Json(body))
+}
+
+#[derive(serde::Deserialize)]
+pub struct LogFilterRequest {
+    pub filter: String,
+}
+
+/// Current log filter, e.g. `{"filter":"info,vuio::ssdp=debug"}`.
+pub async fn get_log_filter_handler() -> impl IntoResponse {
+    match crate::logging::log_filter() {
+        Some(log_filter) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "filter": log_filter.current() })),
+        ),
+        None => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({ "error": "Logging is not initialized" })),
+        ),
+    }
+}
+
+/// Replace the log filter until the next restart or `logging.filter` change.
+pub async fn set_log_filter_handler(Json(request): Json<LogFilterRequest>) -> impl IntoResponse {
+    let Some(log_filter) = crate::logging::log_filter() else {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({ "error": "Logging is not initialized" })),
+        );
+    };
+    match log_filter.set(&request.filter) {
+        Ok(()) => {
+            info!("Log filter changed to '{}'", log_filter.current());
+            (
+                StatusCode::OK,
+                Json(serde_json::json!({ "filter": log_filter.current() })),
+            )
+        }
+        Err(error) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": format!("Invalid log filter: {error}") })),
+        ),
+    }
+}
+
 /// Readiness probe to check if the database is accessible
 pub async fn readyz_handler<D: DatabaseManager>(
     State(state): State<AppState<D>>,
@@ -16,8 +16,9 @@ const MIN_NOTIFICATION_INTERVAL: std::time::Duration = std::time::Duration::from
 
 /// Publish one externally visible ContentDirectory mutation.
 ///
-/// The revision, browse-response invalidation, and UPnP notification are kept
-/// together so callers cannot update one without the others.
+/// The revision, browse-response invalidation, UPnP notification and playlist
+/// mirror refresh are kept together so callers cannot update one without the
+/// others.
 pub async fn publish_content_change<D: DatabaseManager + 'static>(state: &AppState<D>) {
     let old_id = state
         .content_update_id
@@ -27,6 +28,7 @@ pub async fn publish_content_change<D: DatabaseManager + 'static>(state: &AppSta
     info!(old_id, new_id, "ContentDirectory revision published");
 
     state.content_change_notify.notify_one();
+    state.playlist_mirror_notify.notify_one();
 }
 
 /// One coalescing publisher owns all mutation notifications. Every burst is
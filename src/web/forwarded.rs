@@ -0,0 +1,357 @@
+//! `server.trusted_proxies`: recover the client behind a reverse proxy.
+//!
+//! Requests whose TCP peer is a trusted proxy have their `ConnectInfo`
+//! replaced by the client address from `Forwarded` (RFC 7239) or
+//! `X-Forwarded-For`, so the management allowlist, rate limits and logs
+//! see the real client. The scheme and host the client used become the
+//! origin of resource links in SOAP responses. Forwarding headers from any
+//! other peer are ignored.
+
+use std::net::{IpAddr, SocketAddr};
+
+use anyhow::{Context, Result};
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, State},
+    http::{HeaderMap, Request},
+    middleware::Next,
+    response::Response,
+};
+use ipnet::IpNet;
+use tracing::debug;
+
+use crate::database::DatabaseManager;
+use crate::state::AppState;
+use crate::web::tls::ServedOverTls;
+
+tokio::task_local! {
+    /// `scheme://host` the client reached the server at: as reported by a
+    /// trusted proxy, or the announced interface nearest to the client.
+    pub static REQUEST_ORIGIN: String;
+}
+
+/// Origin for absolute resource links in this request's response:
+/// `server.base_url`, else [`REQUEST_ORIGIN`], else the address advertised
+/// over SSDP.
+pub fn resource_origin<D: DatabaseManager>(state: &AppState<D>) -> String {
+    if let Some(base_url) = state.configured_base_url() {
+        return base_url;
+    }
+    REQUEST_ORIGIN
+        .try_with(Clone::clone)
+        .unwrap_or_else(|_| state.advertised_http_origin())
+}
+
+/// Parse `server.trusted_proxies` entries: CIDR networks or single addresses.
+pub fn parse_trusted_proxies(entries: &[String]) -> Result<Vec<IpNet>> {
+    entries
+        .iter()
+        .map(|entry| {
+            let entry = entry.trim();
+            entry
+                .parse::<IpNet>()
+                .or_else(|_| entry.parse::<IpAddr>().map(IpNet::from))
+                .with_context(|| format!("Invalid trusted proxy network: {entry}"))
+        })
+        .collect()
+}
+
+/// What a trusted proxy said about the original request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForwardedRequest {
+    pub client: IpAddr,
+    pub proto: Option<String>,
+    pub host: Option<String>,
+}
+
+impl ForwardedRequest {
+    fn origin(&self) -> Option<String> {
+        let host = self.host.as_deref()?;
+        Some(format!(
+            "{}://{host}",
+            self.proto.as_deref().unwrap_or("http")
+        ))
+    }
+}
+
+/// Resolve the original request from forwarding headers sent by `peer`.
+/// Returns `None` when the peer is not trusted. The client is the rightmost
+/// hop that is not itself a trusted proxy, so entries a client prepends to
+/// the header cannot displace the address the proxy observed. Scheme and
+/// host come from that same hop, the one a trusted proxy appended, for the
+/// same reason.
+pub fn resolve(peer: IpAddr, headers: &HeaderMap, trusted: &[IpNet]) -> Option<ForwardedRequest> {
+    let is_trusted = |ip: &IpAddr| trusted.iter().any(|net| net.contains(&canonical(*ip)));
+    if !is_trusted(&peer) {
+        return None;
+    }
+    let hops = forwarded_hops(headers);
+    let mut client = peer;
+    let mut reporting_hop = None;
+    for (index, hop) in hops.iter().enumerate().rev() {
+        reporting_hop = Some(index);
+        // An obfuscated or unknown hop ends the chain at the proxy that
+        // reported it.
+        let Some(ip) = hop.for_ip else { break };
+        client = ip;
+        if !is_trusted(&ip) {
+            break;
+        }
+    }
+    let hop = reporting_hop.map(|index| &hops[index]);
+    let proto = hop
+        .and_then(|hop| hop.proto.clone())
+        .or_else(|| hop_value(headers, "x-forwarded-proto", reporting_hop, hops.len()))
+        .map(|proto| proto.to_ascii_lowercase())
+        .filter(|proto| proto == "http" || proto == "https");
+    let host = hop
+        .and_then(|hop| hop.host.clone())
+        .or_else(|| hop_value(headers, "x-forwarded-host", reporting_hop, hops.len()))
+        .filter(|host| valid_host(host));
+    Some(ForwardedRequest {
+        client,
+        proto,
+        host,
+    })
+}
+
+/// Router-wide middleware applying [`resolve`] to each request, and scoping
+/// [`REQUEST_ORIGIN`] around it.
+pub async fn apply_forwarded<D: DatabaseManager>(
+    State(state): State<AppState<D>>,
+    mut request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(ConnectInfo(peer)) = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .copied()
+    else {
+        return next.run(request).await;
+    };
+    let config = state.current_config();
+    let trusted = parse_trusted_proxies(&config.server.trusted_proxies).unwrap_or_default();
+    let Some(forwarded) = resolve(peer.ip(), request.headers(), &trusted) else {
+        // Clients on another subnet than the primary one get links through
+        // the interface they can reach.
+        let origin = state.http_origin_for_peer(peer.ip());
+        return REQUEST_ORIGIN.scope(origin, next.run(request)).await;
+    };
+    debug!(
+        peer = %peer,
+        client = %forwarded.client,
+        proto = forwarded.proto.as_deref().unwrap_or("-"),
+        host = forwarded.host.as_deref().unwrap_or("-"),
+        "Request forwarded by trusted proxy"
+    );
+    request
+        .extensions_mut()
+        .insert(ConnectInfo(SocketAddr::new(forwarded.client, 0)));
+    if forwarded.proto.as_deref() == Some("https") {
+        request.extensions_mut().insert(ServedOverTls);
+    }
+    let origin = forwarded
+        .origin()
+        .unwrap_or_else(|| state.http_origin_for_peer(peer.ip()));
+    REQUEST_ORIGIN.scope(origin, next.run(request)).await
+}
+
+#[derive(Debug, Default)]
+struct Hop {
+    for_ip: Option<IpAddr>,
+    proto: Option<String>,
+    host: Option<String>,
+}
+
+/// Hops from `Forwarded`, or from `X-Forwarded-For` when that is absent,
+/// ordered client first.
+fn forwarded_hops(headers: &HeaderMap) -> Vec<Hop> {
+    let forwarded: Vec<&str> = headers
+        .get_all("forwarded")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .collect();
+    if !forwarded.is_empty() {
+        return forwarded
+            .iter()
+            .flat_map(|value| value.split(','))
+            .map(parse_forwarded_element)
+            .collect();
+    }
+    headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|node| Hop {
+            for_ip: parse_node(node.trim()),
+            ..Hop::default()
+        })
+        .collect()
+}
+
+fn parse_forwarded_element(element: &str) -> Hop {
+    let mut hop = Hop::default();
+    for pair in element.split(';') {
+        let Some((key, value)) = pair.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().trim_matches('"');
+        match key.trim().to_ascii_lowercase().as_str() {
+            "for" => hop.for_ip = parse_node(value),
+            "proto" => hop.proto = Some(value.to_owned()),
+            "host" => hop.host = Some(value.to_owned()),
+            _ => {}
+        }
+    }
+    hop
+}
+
+/// Parse a node as `ip`, `ip:port`, `[ipv6]` or `[ipv6]:port`. `unknown`
+/// and obfuscated identifiers yield `None`.
+fn parse_node(node: &str) -> Option<IpAddr> {
+    if let Some(rest) = node.strip_prefix('[') {
+        return rest.split_once(']')?.0.parse().ok();
+    }
+    node.parse::<IpAddr>()
+        .ok()
+        .or_else(|| node.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+}
+
+/// The `X-Forwarded-Proto`/`-Host` value for hop `index` of `hop_count`
+/// when proxies appended one value per hop, else the last value, which
+/// the trusted peer set.
+fn hop_value(
+    headers: &HeaderMap,
+    name: &str,
+    index: Option<usize>,
+    hop_count: usize,
+) -> Option<String> {
+    let values = headers
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .collect::<Vec<_>>();
+    let value = match index {
+        Some(index) if values.len() == hop_count => values.get(index),
+        _ => values.last(),
+    };
+    value.map(|value| (*value).to_owned())
+}
+
+/// Hosts end up verbatim in XML attributes and URLs, so only accept the
+/// characters a hostname, address literal and port can contain.
+fn valid_host(host: &str) -> bool {
+    !host.is_empty()
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':' | '[' | ']'))
+}
+
+fn canonical(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+        IpAddr::V4(_) => ip,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trusted() -> Vec<IpNet> {
+        parse_trusted_proxies(&["10.0.0.0/8".to_owned(), "192.0.2.7".to_owned()]).unwrap()
+    }
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn untrusted_peers_cannot_forge_the_client() {
+        let headers = headers(&[
+            ("x-forwarded-for", "198.51.100.1"),
+            ("x-forwarded-host", "evil.example"),
+        ]);
+        assert_eq!(
+            resolve("203.0.113.5".parse().unwrap(), &headers, &trusted()),
+            None
+        );
+    }
+
+    #[test]
+    fn x_forwarded_for_yields_the_rightmost_untrusted_hop() {
+        // The client prepended a fake entry; the proxies appended the rest.
+        let headers = headers(&[
+            ("x-forwarded-for", "127.0.0.1, 198.51.100.1, 10.1.2.3"),
+            ("x-forwarded-proto", "https"),
+            ("x-forwarded-host", "media.example:443"),
+        ]);
+        let resolved = resolve("10.0.0.1".parse().unwrap(), &headers, &trusted()).unwrap();
+        assert_eq!(resolved.client, "198.51.100.1".parse::<IpAddr>().unwrap());
+        assert_eq!(
+            resolved.origin().as_deref(),
+            Some("https://media.example:443")
+        );
+    }
+
+    #[test]
+    fn forwarded_header_takes_precedence() {
+        let headers = headers(&[
+            (
+                "forwarded",
+                r#"for="[2001:db8::1]:4711";proto=https;host=media.example, for=192.0.2.7"#,
+            ),
+            ("x-forwarded-for", "198.51.100.9"),
+        ]);
+        let resolved = resolve("192.0.2.7".parse().unwrap(), &headers, &trusted()).unwrap();
+        assert_eq!(resolved.client, "2001:db8::1".parse::<IpAddr>().unwrap());
+        assert_eq!(resolved.proto.as_deref(), Some("https"));
+        assert_eq!(resolved.host.as_deref(), Some("media.example"));
+    }
+
+    #[test]
+    fn scheme_and_host_come_from_the_hop_the_proxy_appended() {
+        // The client claims HTTPS and its own host in a leftmost element.
+        let headers = headers(&[(
+            "forwarded",
+            "for=127.0.0.1;proto=https;host=evil.example, for=198.51.100.1;proto=http;host=media.example",
+        )]);
+        let resolved = resolve("10.0.0.1".parse().unwrap(), &headers, &trusted()).unwrap();
+        assert_eq!(resolved.client, "198.51.100.1".parse::<IpAddr>().unwrap());
+        assert_eq!(resolved.proto.as_deref(), Some("http"));
+        assert_eq!(resolved.host.as_deref(), Some("media.example"));
+
+        let headers = self::headers(&[
+            ("x-forwarded-for", "127.0.0.1, 198.51.100.1"),
+            ("x-forwarded-proto", "https, http"),
+            ("x-forwarded-host", "evil.example, media.example"),
+        ]);
+        let resolved = resolve("10.0.0.1".parse().unwrap(), &headers, &trusted()).unwrap();
+        assert_eq!(resolved.proto.as_deref(), Some("http"));
+        assert_eq!(resolved.origin().as_deref(), Some("http://media.example"));
+    }
+
+    #[test]
+    fn unknown_hops_and_bad_hosts_fall_back_to_the_proxy() {
+        let headers = headers(&[("forwarded", "for=unknown;host=\"a\\\"b\";proto=gopher")]);
+        let resolved = resolve("10.0.0.1".parse().unwrap(), &headers, &trusted()).unwrap();
+        assert_eq!(resolved.client, "10.0.0.1".parse::<IpAddr>().unwrap());
+        assert_eq!(resolved.proto, None);
+        assert_eq!(resolved.host, None);
+        assert_eq!(resolved.origin(), None);
+    }
+
+    #[test]
+    fn invalid_trusted_proxy_entries_are_rejected() {
+        assert!(parse_trusted_proxies(&["10.0.0.0/33".to_owned()]).is_err());
+        assert!(parse_trusted_proxies(&["proxy.lan".to_owned()]).is_err());
+    }
+}
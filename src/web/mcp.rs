@@ -221,7 +221,7 @@ fn get_tools_list() -> serde_json::Value {
             },
             {
                 "name": "add_to_playlist",
-                "description": "Add one or more media files to a playlist in bulk.",
+                "description": "Append one or more media files to the end of a playlist in bulk.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
@@ -704,12 +704,9 @@ async fn tool_get_server_stats<D: DatabaseManager>(
         .await
         .map_err(|e| format!("Database error: {}", e))?;
 
-    let server_ip = state.get_server_ip();
-    let port = state.current_config().server.port;
-
     Ok(serde_json::json!({
         "server_name": state.current_config().server.name,
-        "server_url": format!("http://{}:{}", server_ip, port),
+        "server_url": state.advertised_http_origin(),
         "total_files": stats.total_files,
         "total_size_bytes": stats.total_size,
         "total_size_human": format_bytes(stats.total_size),
@@ -790,9 +787,7 @@ async fn tool_cast_media_to_renderer<D: DatabaseManager + 'static>(
         ))?;
 
     // Build the media URL
-    let server_ip = state.get_server_ip();
-    let port = state.current_config().server.port;
-    let media_url = format!("http://{}:{}/media/{}", server_ip, port, file.id);
+    let media_url = format!("{}/media/{}", state.advertised_http_origin(), file.id);
 
     let title = file.title.as_deref().unwrap_or(&file.filename);
 
@@ -875,13 +870,22 @@ async fn tool_list_playlists<D: DatabaseManager>(
         .await
         .map_err(|e| format!("Database error: {}", e))?;
 
+    let sources = state
+        .database
+        .get_playlist_sources()
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
     let list: Vec<serde_json::Value> = playlists
         .into_iter()
         .map(|p| {
+            let source_path = p.id.and_then(|id| sources.get(&id));
             serde_json::json!({
                 "id": p.id,
                 "name": p.name,
                 "description": p.description,
+                "source": if source_path.is_some() { "file" } else { "user" },
+                "source_path": source_path.map(|path| path.to_string_lossy()),
                 "created_at": p.created_at.duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
                 "updated_at": p.updated_at.duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
             })
@@ -956,16 +960,18 @@ async fn tool_add_to_playlist<D: DatabaseManager + 'static>(
         .ok_or("Missing 'media_file_ids' parameter")?;
 
     let mut ids_to_add = Vec::new();
-    for (pos, val) in media_file_ids.iter().enumerate() {
+    for val in media_file_ids {
         let id = val
             .as_i64()
             .ok_or("Invalid media_file_id, must be integer")?;
-        ids_to_add.push((id, pos as u32));
+        ids_to_add.push(id);
     }
 
+    // Appended in one transaction, so concurrent adds never overwrite each
+    // other's entries.
     let entry_ids = state
         .database
-        .batch_add_to_playlist(playlist_id, &ids_to_add)
+        .append_to_playlist(playlist_id, &ids_to_add)
         .await
         .map_err(|e| format!("Database error: {}", e))?;
     if !entry_ids.is_empty() {
@@ -1121,9 +1127,8 @@ pub async fn cast_playlist_helper<D: DatabaseManager + 'static>(
         ))?;
 
     // Build the media URL
-    let server_ip = state.get_server_ip();
-    let port = state.current_config().server.port;
-    let media_url = format!("http://{}:{}/media/{}", server_ip, port, file_id);
+    let origin = state.advertised_http_origin();
+    let media_url = format!("{}/media/{}", origin, file_id);
 
     let title = selected_track
         .title
@@ -1163,7 +1168,7 @@ pub async fn cast_playlist_helper<D: DatabaseManager + 'static>(
     if track_index + 1 < tracks.len() {
         let next_track = &tracks[track_index + 1];
         {
-            let next_media_url = format!("http://{}:{}/media/{}", server_ip, port, next_track.id);
+            let next_media_url = format!("{}/media/{}", origin, next_track.id);
             let next_title = next_track.title.as_deref().unwrap_or(&next_track.filename);
 
             // Queue on the TV and log/ignore failures on non-compliant devices
@@ -1207,7 +1212,7 @@ pub async fn cast_playlist_helper<D: DatabaseManager + 'static>(
 
     let state_clone = state.clone();
     let control_url_clone = matched_tv.control_url.clone();
-    let server_ip_clone = server_ip.clone();
+    let origin_clone = origin.clone();
     let matched_tv_friendly_name = matched_tv.friendly_name.clone();
     let matched_renderer_id = matched_tv.id.clone();
 
@@ -1262,7 +1267,7 @@ pub async fn cast_playlist_helper<D: DatabaseManager + 'static>(
             let mut matched_any = false;
             for (idx, track) in latest_tracks.iter().enumerate() {
                 {
-                    let track_media_url = format!("http://{}:{}/media/{}", server_ip_clone, port, track.id);
+                    let track_media_url = format!("{}/media/{}", origin_clone, track.id);
                     if current_uri == track_media_url {
                         matched_any = true;
                         if idx != current_idx {
@@ -1283,7 +1288,7 @@ pub async fn cast_playlist_helper<D: DatabaseManager + 'static>(
                             if current_idx + 1 < latest_tracks.len() {
                                 let next_track = &latest_tracks[current_idx + 1];
                                 {
-                                    let next_media_url = format!("http://{}:{}/media/{}", server_ip_clone, port, next_track.id);
+                                    let next_media_url = format!("{}/media/{}", origin_clone, next_track.id);
                                     let next_title = next_track.title.as_deref().unwrap_or(&next_track.filename);
                                     let queue_result = tokio::select! {
                                         _ = monitor_cancellation.cancelled() => break 'monitor,
@@ -1426,34 +1431,40 @@ async fn query_media_page<D: DatabaseManager + 'static>(
     text: Option<String>,
     limit: usize,
 ) -> Result<(Vec<serde_json::Value>, Option<String>), String> {
+    let subject = text.clone().unwrap_or_default();
     let query = MediaFileQuery::Filtered {
         after_id,
         mime_family,
         text,
     };
     let fetch_limit = limit.saturating_add(1);
-    let mut files = state
-        .database
-        .clone()
-        .read(move |session| {
-            let mut page = Vec::with_capacity(fetch_limit);
-            session.visit_files(&query, 0, fetch_limit, |file| {
-                page.push(media_file_view_to_json(&file));
+    let hidden = crate::web::soap::hidden_files(state).await;
+    crate::web::timed_backend_call(
+        state.database.as_ref(),
+        "read:filtered_files",
+        &subject,
+        state.database.clone().read(move |session| {
+            let mut page = Vec::with_capacity(limit.min(fetch_limit));
+            let mut last_id = None;
+            let summary = session.visit_files(&query, 0, fetch_limit, |file| {
+                if page.len() >= limit {
+                    return Ok(());
+                }
+                // Hidden duplicates are skipped but still advance the cursor.
+                last_id = file.id();
+                if !file.id().is_some_and(|id| hidden.contains(&id)) {
+                    page.push(media_file_view_to_json(&file));
+                }
                 Ok(())
             })?;
-            Ok(page)
-        })
-        .await
-        .map_err(|error| format!("Database error: {error}"))?;
-
-    let has_more = files.len() > limit;
-    if has_more {
-        files.pop();
-    }
-    let next_cursor = has_more
-        .then(|| files.last()?.get("id")?.as_i64().map(|id| id.to_string()))
-        .flatten();
-    Ok((files, next_cursor))
+            let next_cursor = (summary.visited > limit)
+                .then(|| last_id.map(|id| id.to_string()))
+                .flatten();
+            Ok((page, next_cursor))
+        }),
+    )
+    .await
+    .map_err(|error| format!("Database error: {error}"))
 }
 
 #[cfg(test)]
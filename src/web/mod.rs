@@ -1,12 +1,18 @@
+mod artwork;
+pub mod audit;
 pub mod auth;
+pub mod browse;
 pub mod casting;
 pub mod client;
+mod conditional;
 pub mod diagnostics;
 pub mod eventing;
 mod format;
+pub mod forwarded;
 pub mod mcp;
 pub mod soap;
 pub mod streaming;
+pub mod tls;
 pub mod ui;
 pub mod xml;
 
@@ -41,15 +47,39 @@ pub fn create_router<D: DatabaseManager + 'static>(state: AppState<D>) -> Router
     let json_routes = Router::new()
         .route("/api/cast/playlist", post(casting::api_cast_playlist::<D>))
         .route("/mcp/message", post(mcp::message_handler::<D>))
+        .route(
+            "/api/playlists/smart",
+            get(ui::smart_playlists_handler::<D>).post(ui::create_smart_playlist_handler::<D>),
+        )
+        .route(
+            "/api/playlists/smart/{id}",
+            get(ui::smart_playlist_handler::<D>)
+                .put(ui::update_smart_playlist_handler::<D>)
+                .delete(ui::delete_smart_playlist_handler::<D>),
+        )
         .layer(DefaultBodyLimit::max(JSON_BODY_LIMIT));
 
     let management_routes = Router::new()
-        .route("/", get(ui::root_handler))
+        .route("/", get(ui::root_handler::<D>))
         .route("/api/server-info", get(ui::server_info_handler::<D>))
         .route("/api/media", get(ui::media_page_handler::<D>))
+        .route("/api/browse", get(browse::browse_handler::<D>))
+        .route("/api/item/{id}", get(browse::item_handler::<D>))
+        .route("/api/admin/scan/status", get(ui::scan_status_handler::<D>))
+        .route("/api/admin/scan/cancel", post(ui::scan_cancel_handler::<D>))
+        .route("/api/library/duplicates", get(ui::duplicates_handler::<D>))
+        .route(
+            "/api/playlists/{id}/export",
+            get(ui::playlist_export_handler::<D>),
+        )
         .route("/metrics", get(diagnostics::get_prometheus_metrics::<D>))
         .route("/metrics/json", get(diagnostics::get_web_metrics::<D>))
+        .route("/api/stats", get(diagnostics::get_stats_handler::<D>))
         .route("/logs", get(diagnostics::get_logs_handler::<D>))
+        .route(
+            "/api/log-level",
+            get(diagnostics::get_log_filter_handler).put(diagnostics::set_log_filter_handler),
+        )
         .route("/api/renderers", get(casting::api_list_renderers::<D>))
         .route("/api/targets", get(casting::api_list_targets::<D>))
         .route("/api/cast/to", post(casting::api_cast_to_target::<D>))
@@ -60,10 +90,20 @@ pub fn create_router<D: DatabaseManager + 'static>(state: AppState<D>) -> Router
         .route_layer(middleware::from_fn_with_state(
             state.clone(),
             auth::require_management::<D>,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            tls::require_tls_for_api::<D>,
         ));
 
-    Router::new()
+    let login_routes = Router::new()
         .route("/login", get(auth::login_page).post(auth::login::<D>))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            tls::require_tls_for_api::<D>,
+        ));
+
+    Router::new()
         .route("/description.xml", get(soap::description_handler::<D>))
         .route("/ContentDirectory.xml", get(soap::content_directory_scpd))
         .route(
@@ -81,9 +121,46 @@ pub fn create_router<D: DatabaseManager + 'static>(state: AppState<D>) -> Router
         )
         .route("/media/{id}/cover", get(streaming::serve_cover::<D>))
         .route("/media/{id}/subtitle", get(streaming::serve_subtitle::<D>))
+        .route("/health", get(diagnostics::health_handler::<D>))
         .route("/healthz", get(diagnostics::healthz_handler))
         .route("/readyz", get(diagnostics::readyz_handler::<D>))
         .merge(soap_routes)
+        .merge(login_routes)
         .merge(management_routes)
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            forwarded::apply_forwarded::<D>,
+        ))
         .with_state(state)
 }
+
+/// Tracing target for per-request backend timings. `--debug` enables it along
+/// with everything else; `RUST_LOG=vuio::backend=debug` enables it alone.
+pub const BACKEND_TIMING_TARGET: &str = "vuio::backend";
+
+/// Await one backend operation issued for a client request and log which
+/// backend served it and how long it took.
+pub(crate) async fn timed_backend_call<D, T>(
+    database: &D,
+    operation: &str,
+    subject: &str,
+    call: impl std::future::Future<Output = T>,
+) -> T
+where
+    D: DatabaseManager + ?Sized,
+{
+    if !tracing::enabled!(target: BACKEND_TIMING_TARGET, tracing::Level::DEBUG) {
+        return call.await;
+    }
+    let started = std::time::Instant::now();
+    let output = call.await;
+    tracing::debug!(
+        target: BACKEND_TIMING_TARGET,
+        backend = database.backend_name(),
+        operation,
+        subject,
+        elapsed_us = started.elapsed().as_micros() as u64,
+        "Backend operation finished"
+    );
+    output
+}
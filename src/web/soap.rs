@@ -1,39 +1,74 @@
 //! UPnP device/service descriptions and SOAP control handlers.
 
 use crate::{
-    database::{DatabaseManager, MediaDirectory},
+    database::{
+        duplicates::hidden_duplicate_ids, DatabaseManager, DatabaseReadSession, MediaDirectory,
+        MediaFileQuery, MediaFileView,
+    },
+    platform::filesystem::{path_from_lossless_string, path_to_lossless_string},
     state::AppState,
-    web::xml::{generate_description_xml, generate_scpd_xml},
+    web::{
+        conditional::{cache_control, Validators},
+        timed_backend_call,
+        xml::{generate_description_xml, generate_scpd_xml, parse_media_object_id},
+    },
 };
 use axum::{
     extract::State,
-    http::{header, HeaderMap, StatusCode},
+    http::{header, HeaderMap, Method, StatusCode},
     response::{IntoResponse, Response},
 };
-use std::{path::PathBuf, sync::atomic::Ordering, time::Instant};
+use std::{
+    collections::HashSet,
+    path::PathBuf,
+    sync::{atomic::Ordering, Arc},
+    time::Instant,
+};
 use tracing::{debug, error, info, warn};
 
 mod parser;
 use parser::*;
 
+/// Seconds renderers may reuse `description.xml` before revalidating. Short,
+/// so a renamed server shows up within a minute.
+const DESCRIPTION_MAX_AGE_SECONDS: u64 = 60;
+
+/// Seconds renderers may reuse an SCPD. They only change between releases.
+const SCPD_MAX_AGE_SECONDS: u64 = 86_400;
+
+/// `xml` with an `ETag` of its content and `Cache-Control`, or an empty
+/// `304` when the request's `If-None-Match` already names it.
+fn cached_xml_response(headers: &HeaderMap, xml: String, max_age: u64) -> Response {
+    let validators = Validators::for_content(xml.as_bytes());
+    let mut response = if validators.not_modified(headers) {
+        validators.not_modified_response()
+    } else {
+        let mut response = (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "text/xml; charset=utf-8")],
+            xml,
+        )
+            .into_response();
+        validators.insert_into(response.headers_mut());
+        response
+    };
+    response
+        .headers_mut()
+        .insert(header::CACHE_CONTROL, cache_control(max_age));
+    response
+}
+
 pub async fn description_handler<D: DatabaseManager>(
     State(state): State<AppState<D>>,
-) -> impl IntoResponse {
-    let xml = generate_description_xml(&state).await;
-    (
-        StatusCode::OK,
-        [(header::CONTENT_TYPE, "text/xml; charset=utf-8")],
-        xml,
-    )
+    axum::extract::ConnectInfo(peer): axum::extract::ConnectInfo<std::net::SocketAddr>,
+    headers: HeaderMap,
+) -> Response {
+    let xml = generate_description_xml(&state, peer.ip()).await;
+    cached_xml_response(&headers, xml, DESCRIPTION_MAX_AGE_SECONDS)
 }
 
-pub async fn content_directory_scpd() -> impl IntoResponse {
-    let xml = generate_scpd_xml();
-    (
-        StatusCode::OK,
-        [(header::CONTENT_TYPE, "text/xml; charset=utf-8")],
-        xml,
-    )
+pub async fn content_directory_scpd(headers: HeaderMap) -> Response {
+    cached_xml_response(&headers, generate_scpd_xml(), SCPD_MAX_AGE_SECONDS)
 }
 
 /// Content Directory Handler struct to encapsulate specialized browse handlers
@@ -93,6 +128,8 @@ impl ContentDirectoryHandler {
             client_profile: client,
             content_update_id: current_update_id,
             browse_epoch,
+            sort_criteria: params.sort_criteria.clone(),
+            origin: crate::web::forwarded::resource_origin(state),
         };
 
         // Cache lookup
@@ -109,8 +146,10 @@ impl ContentDirectoryHandler {
                 state.web_metrics.record_browse_request(response_time, true);
                 state.web_metrics.record_directory_listing(response_time);
                 debug!(
+                    target: crate::logging::targets::BROWSE,
                     "Browse Cache Hit for Folder ObjectID: {} ({}ms)",
-                    params.object_id, response_time
+                    params.object_id,
+                    response_time
                 );
                 return (
                     StatusCode::OK,
@@ -129,46 +168,110 @@ impl ContentDirectoryHandler {
         let monitored_dirs = state.media_directories.read().await.clone();
         let unavailable_roots = state.unavailable_roots.read().await.clone();
 
-        // Parse directory index prefix (e.g. "d0/movies" -> index 0, relative path "movies")
-        let (dir_index_opt, relative_path) = parse_dir_index_prefix(path_prefix_str);
-
-        let browse_path = match dir_index_opt {
-            Some(idx) if idx < monitored_dirs.len() => {
-                let base_path = PathBuf::from(&monitored_dirs[idx].path);
-                if relative_path.is_empty() {
-                    base_path
-                } else {
-                    base_path.join(relative_path)
-                }
-            }
-            _ => {
-                let media_root = state.current_config().get_primary_media_dir();
-                if path_prefix_str.is_empty() {
-                    media_root
-                } else {
-                    media_root.join(path_prefix_str)
-                }
-            }
-        };
+        let target = FolderBrowseTarget::resolve(
+            &monitored_dirs,
+            &unavailable_roots,
+            state.current_config().get_primary_media_dir(),
+            path_prefix_str,
+        );
+        let browse_path = target.browse_path;
+        let merged_parents = target.merged_parents;
 
         // If there are multiple monitored directories and we are at the root, return virtual folders
         let (subdirectories, files) = if path_prefix_str.is_empty() && monitored_dirs.len() > 1 {
-            let mut subdirs = Vec::new();
-            for (idx, dir) in monitored_dirs.iter().enumerate() {
-                let path = PathBuf::from(&dir.path);
-                if !path.is_dir() || unavailable_roots.contains(&path) {
-                    continue;
+            (
+                root_folders(&monitored_dirs, &unavailable_roots, media_type_filter),
+                Vec::<crate::database::MediaFile>::new(),
+            )
+        } else if merged_parents.len() > 1 {
+            let canonical_parents = merged_parents
+                .iter()
+                .map(|parent| {
+                    state
+                        .filesystem_manager
+                        .get_canonical_path(parent)
+                        .unwrap_or_else(|_| {
+                            path_to_lossless_string(
+                                &state.filesystem_manager.normalize_path(parent),
+                            )
+                            .into_owned()
+                        })
+                })
+                .collect::<Vec<_>>();
+            let requested_count = browse_page_limit(params);
+            let context = crate::web::xml::BrowseRenderContext {
+                client,
+                origin: crate::web::forwarded::resource_origin(state),
+                autoplay_enabled: crate::web::xml::autoplay_for_client(
+                    &state.current_config(),
+                    client,
+                ),
+                update_id: current_update_id,
+                bookmarks: resume_bookmarks(state, client).await,
+                bitrate_limit: crate::web::xml::BitrateLimit::for_client(
+                    &state.current_config(),
+                    client,
+                ),
+                iso_mime_type: state.current_config().media.iso_mime_type.clone(),
+                sort: crate::database::SortSpec::parse(&params.sort_criteria),
+                hidden: hidden_files(state).await,
+                container_update_ids: state.container_update_ids.clone(),
+                entry_info: Default::default(),
+            };
+            let mime_family = media_type_filter.to_owned();
+            let object_id = params.object_id.clone();
+            let starting_index = params.starting_index as usize;
+            let database = state.database.clone();
+            let response = match timed_backend_call(
+                state.database.as_ref(),
+                "read:merged_directory_listing",
+                &params.object_id,
+                database.read(move |session| {
+                    crate::web::xml::generate_overlay_browse_response(
+                        session,
+                        &canonical_parents,
+                        &mime_family,
+                        &object_id,
+                        starting_index,
+                        requested_count,
+                        context,
+                    )
+                }),
+            )
+            .await
+            {
+                Ok(response) => response,
+                Err(error) => {
+                    error!(
+                        target: crate::logging::targets::BROWSE,
+                        "Merged browse failed for {}: {}",
+                        params.object_id,
+                        error
+                    );
+                    state.web_metrics.record_error();
+                    return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error")
+                        .into_response();
                 }
-                let name = path
-                    .file_name()
-                    .map(|n| n.to_string_lossy().to_string())
-                    .unwrap_or_else(|| dir.path.clone());
-                subdirs.push(MediaDirectory {
-                    path: PathBuf::from(format!("d{}", idx)),
-                    name,
-                });
-            }
-            (subdirs, Vec::<crate::database::MediaFile>::new())
+            };
+            let response_time = start_time.elapsed().as_micros() as u64;
+            state
+                .web_metrics
+                .record_browse_request(response_time, false);
+            state.web_metrics.record_directory_listing(response_time);
+            state
+                .browse_cache
+                .lock()
+                .await
+                .insert(cache_key, response.clone());
+            return (
+                StatusCode::OK,
+                [
+                    (header::CONTENT_TYPE, "text/xml; charset=utf-8"),
+                    (header::HeaderName::from_static("ext"), ""),
+                ],
+                response,
+            )
+                .into_response();
         } else if !browse_path.is_dir()
             || unavailable_roots
                 .iter()
@@ -183,60 +286,82 @@ impl ContentDirectoryHandler {
                 .filesystem_manager
                 .get_canonical_path(&browse_path)
             {
-                Ok(canonical) => std::path::PathBuf::from(canonical),
+                Ok(canonical) => path_from_lossless_string(&canonical),
                 Err(e) => {
-                    warn!("Failed to get canonical path for browse request '{}': {}, using basic normalization", browse_path.display(), e);
+                    warn!(
+                        target: crate::logging::targets::BROWSE,
+                        "Failed to get canonical path for browse request '{}': {}, using basic normalization",
+                        browse_path.display(),
+                        e
+                    );
                     state.web_metrics.record_error();
                     state.filesystem_manager.normalize_path(&browse_path)
                 }
             };
 
             let requested_count = browse_page_limit(params);
-            let bookmarks = if matches!(
-                client,
-                crate::web::client::DlnaClientProfile::SamsungTv
-                    | crate::web::client::DlnaClientProfile::SamsungTvQ
-            ) {
-                state.bookmarks.lock().await.snapshot()
-            } else {
-                std::collections::HashMap::new()
-            };
+            let bookmarks = resume_bookmarks(state, client).await;
             let context = crate::web::xml::BrowseRenderContext {
                 client,
-                server_ip: state.get_server_ip(),
-                server_port: state.current_config().server.port,
-                autoplay_enabled: state.current_config().media.autoplay_enabled,
+                origin: crate::web::forwarded::resource_origin(state),
+                autoplay_enabled: crate::web::xml::autoplay_for_client(
+                    &state.current_config(),
+                    client,
+                ),
                 update_id: current_update_id,
                 bookmarks,
+                bitrate_limit: crate::web::xml::BitrateLimit::for_client(
+                    &state.current_config(),
+                    client,
+                ),
+                iso_mime_type: state.current_config().media.iso_mime_type.clone(),
+                sort: crate::database::SortSpec::parse(&params.sort_criteria),
+                hidden: hidden_files(state).await,
+                container_update_ids: state.container_update_ids.clone(),
+                entry_info: Default::default(),
             };
-            let canonical_parent = canonical_browse_path.to_string_lossy().into_owned();
+            let canonical_parent = path_to_lossless_string(&canonical_browse_path).into_owned();
             let mime_family = media_type_filter.to_owned();
             let object_id = params.object_id.clone();
             let starting_index = params.starting_index as usize;
             let database = state.database.clone();
-            let query = database.read(move |session| {
-                crate::web::xml::generate_indexed_browse_response(
-                    session,
-                    &canonical_parent,
-                    &mime_family,
-                    &object_id,
-                    starting_index,
-                    requested_count,
-                    context,
-                )
-            });
+            let query = timed_backend_call(
+                state.database.as_ref(),
+                "read:directory_listing",
+                &params.object_id,
+                database.read(move |session| {
+                    crate::web::xml::generate_indexed_browse_response(
+                        session,
+                        &canonical_parent,
+                        &mime_family,
+                        &object_id,
+                        starting_index,
+                        requested_count,
+                        context,
+                    )
+                }),
+            );
 
             let response =
                 match tokio::time::timeout(std::time::Duration::from_secs(30), query).await {
                     Ok(Ok(response)) => response,
                     Ok(Err(error)) => {
-                        error!("ReDB browse failed for {}: {}", params.object_id, error);
+                        error!(
+                            target: crate::logging::targets::BROWSE,
+                            "ReDB browse failed for {}: {}",
+                            params.object_id,
+                            error
+                        );
                         state.web_metrics.record_error();
                         return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error")
                             .into_response();
                     }
                     Err(_) => {
-                        error!("Database query timed out for {}", params.object_id);
+                        error!(
+                            target: crate::logging::targets::BROWSE,
+                            "Database query timed out for {}",
+                            params.object_id
+                        );
                         state.web_metrics.record_error();
                         return (
                             StatusCode::REQUEST_TIMEOUT,
@@ -268,6 +393,7 @@ impl ContentDirectoryHandler {
         };
 
         debug!(
+            target: crate::logging::targets::BROWSE,
             "ReDB browse request for '{}' (filter: '{}') returned {} subdirs, {} files",
             browse_path.display(),
             media_type_filter,
@@ -284,6 +410,7 @@ impl ContentDirectoryHandler {
         let paginated_subdirs = &subdirectories[page];
 
         debug!(
+            target: crate::logging::targets::BROWSE,
             "ReDB returning paginated results: {} subdirs, {} files (index {}-{} of {})",
             paginated_subdirs.len(),
             0,
@@ -299,13 +426,13 @@ impl ContentDirectoryHandler {
             .record_browse_request(response_time, cache_hit);
         state.web_metrics.record_directory_listing(response_time);
 
-        let server_ip = state.get_server_ip();
+        let origin = crate::web::forwarded::resource_origin(state);
         let response = generate_browse_response(
             &params.object_id,
             paginated_subdirs,
             &[],
             state,
-            &server_ip,
+            &origin,
             total_matches,
         )
         .await;
@@ -359,13 +486,13 @@ impl ContentDirectoryHandler {
             },
         ];
         let page = browse_page_bounds(params, containers.len());
-        let server_ip = state.get_server_ip();
+        let origin = crate::web::forwarded::resource_origin(state);
         let response = generate_browse_response(
             "0",
             &containers[page],
             &[],
             state,
-            &server_ip,
+            &origin,
             containers.len(),
         )
         .await;
@@ -380,27 +507,123 @@ impl ContentDirectoryHandler {
             .into_response()
     }
 
+    /// Handle a browse of a single media item addressed by its stable object ID.
+    /// Items have no children, so only BrowseMetadata returns a result.
+    async fn handle_item_browse<D: DatabaseManager + 'static>(
+        params: &BrowseParams,
+        state: &AppState<D>,
+        key: u64,
+    ) -> Response {
+        if params.browse_flag != BrowseFlag::BrowseMetadata {
+            let origin = crate::web::forwarded::resource_origin(state);
+            let response = crate::web::xml::generate_browse_response(
+                &params.object_id,
+                &[],
+                &[],
+                state,
+                &origin,
+                0,
+            )
+            .await;
+            return (
+                StatusCode::OK,
+                [
+                    (header::CONTENT_TYPE, "text/xml; charset=utf-8"),
+                    (header::HeaderName::from_static("ext"), ""),
+                ],
+                response,
+            )
+                .into_response();
+        }
+
+        let client = crate::web::client::CURRENT_CLIENT
+            .try_with(|client| *client)
+            .unwrap_or(crate::web::client::DlnaClientProfile::Standard);
+        let context = crate::web::xml::BrowseRenderContext {
+            client,
+            origin: crate::web::forwarded::resource_origin(state),
+            autoplay_enabled: crate::web::xml::autoplay_for_client(&state.current_config(), client),
+            update_id: state.content_update_id.load(Ordering::SeqCst),
+            bookmarks: resume_bookmarks(state, client).await,
+            bitrate_limit: crate::web::xml::BitrateLimit::for_client(
+                &state.current_config(),
+                client,
+            ),
+            iso_mime_type: state.current_config().media.iso_mime_type.clone(),
+            sort: crate::database::SortSpec::parse(&params.sort_criteria),
+            hidden: Default::default(),
+            container_update_ids: state.container_update_ids.clone(),
+            entry_info: Default::default(),
+        };
+        let response = match timed_backend_call(
+            state.database.as_ref(),
+            "read:item_metadata",
+            &params.object_id,
+            state.database.clone().read(move |session| {
+                crate::web::xml::generate_indexed_items_response(
+                    session,
+                    crate::database::MediaFileQuery::ObjectKey(key),
+                    "0",
+                    0,
+                    1,
+                    context,
+                )
+            }),
+        )
+        .await
+        {
+            Ok(response) => response,
+            Err(error) => {
+                error!(
+                    target: crate::logging::targets::BROWSE,
+                    %error,
+                    "Item metadata query failed"
+                );
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        };
+        (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, "text/xml; charset=utf-8"),
+                (header::HeaderName::from_static("ext"), ""),
+            ],
+            response,
+        )
+            .into_response()
+    }
+
     /// Handle radio browse request
     async fn handle_radio_browse<D: DatabaseManager + 'static>(
         params: &BrowseParams,
         state: &AppState<D>,
     ) -> Response {
+        let client = crate::web::client::CURRENT_CLIENT
+            .try_with(|client| *client)
+            .unwrap_or(crate::web::client::DlnaClientProfile::Standard);
         let context = crate::web::xml::BrowseRenderContext {
-            client: crate::web::client::CURRENT_CLIENT
-                .try_with(|client| *client)
-                .unwrap_or(crate::web::client::DlnaClientProfile::Standard),
-            server_ip: state.get_server_ip(),
-            server_port: state.current_config().server.port,
-            autoplay_enabled: state.current_config().media.autoplay_enabled,
+            client,
+            origin: crate::web::forwarded::resource_origin(state),
+            autoplay_enabled: crate::web::xml::autoplay_for_client(&state.current_config(), client),
             update_id: state.content_update_id.load(Ordering::SeqCst),
-            bookmarks: state.bookmarks.lock().await.snapshot(),
+            bookmarks: resume_bookmarks(state, client).await,
+            bitrate_limit: crate::web::xml::BitrateLimit::for_client(
+                &state.current_config(),
+                client,
+            ),
+            iso_mime_type: state.current_config().media.iso_mime_type.clone(),
+            sort: crate::database::SortSpec::parse(&params.sort_criteria),
+            hidden: Default::default(),
+            container_update_ids: state.container_update_ids.clone(),
+            entry_info: Default::default(),
         };
         let starting_index = params.starting_index as usize;
         let requested_count = browse_page_limit(params);
-        let response = match state
-            .database
-            .clone()
-            .read(move |session| {
+        let response = match timed_backend_call(
+            state.database.as_ref(),
+            "read:radio_items",
+            &params.object_id,
+            state.database.clone().read(move |session| {
                 crate::web::xml::generate_indexed_items_response(
                     session,
                     crate::database::MediaFileQuery::Filtered {
@@ -413,12 +636,17 @@ impl ContentDirectoryHandler {
                     requested_count,
                     context,
                 )
-            })
-            .await
+            }),
+        )
+        .await
         {
             Ok(response) => response,
             Err(error) => {
-                error!(%error, "Radio browse query failed");
+                error!(
+                    target: crate::logging::targets::BROWSE,
+                    %error,
+                    "Radio browse query failed"
+                );
                 return StatusCode::INTERNAL_SERVER_ERROR.into_response();
             }
         };
@@ -434,8 +662,220 @@ impl ContentDirectoryHandler {
     }
 }
 
+/// Dispatch a Browse to the handler for its object ID.
+async fn browse<D: DatabaseManager + 'static>(
+    params: &BrowseParams,
+    state: &AppState<D>,
+) -> Response {
+    // Handle root browse request (ObjectID "0")
+    if params.object_id == "0" {
+        return ContentDirectoryHandler::handle_root_browse(params, state).await;
+    }
+
+    if let Some(key) = parse_media_object_id(&params.object_id) {
+        return ContentDirectoryHandler::handle_item_browse(params, state, key).await;
+    }
+
+    // Determine media type and delegate to specialized handlers
+    if params.object_id.starts_with("video") {
+        let path_prefix_str = params
+            .object_id
+            .strip_prefix("video")
+            .unwrap_or("")
+            .trim_start_matches('/');
+        ContentDirectoryHandler::handle_video_browse(params, state, path_prefix_str).await
+    } else if params.object_id.starts_with("audio") {
+        // Handle music categorization within audio section
+        let audio_path = params
+            .object_id
+            .strip_prefix("audio")
+            .unwrap_or("")
+            .trim_start_matches('/');
+
+        // Check for music categorization paths
+        if audio_path.is_empty() {
+            // Root audio container - return categorization containers
+            handle_audio_root_browse(params, state).await
+        } else if audio_path.starts_with("artists") {
+            ContentDirectoryHandler::handle_artist_browse(params, state, audio_path).await
+        } else if audio_path.starts_with("albums") {
+            ContentDirectoryHandler::handle_album_browse(params, state, audio_path).await
+        } else if audio_path.starts_with("genres") {
+            handle_genres_browse(params, state, audio_path).await
+        } else if audio_path.starts_with("years") {
+            handle_years_browse(params, state, audio_path).await
+        } else if audio_path.starts_with("playlists") {
+            handle_playlists_browse(params, state, audio_path).await
+        } else if audio_path.starts_with("folders") {
+            let folder_path = audio_path
+                .strip_prefix("folders")
+                .unwrap_or("")
+                .trim_start_matches('/');
+            ContentDirectoryHandler::handle_music_browse(params, state, folder_path).await
+        } else {
+            // Traditional folder browsing within audio
+            ContentDirectoryHandler::handle_music_browse(params, state, audio_path).await
+        }
+    } else if params.object_id.starts_with("image") {
+        let path_prefix_str = params
+            .object_id
+            .strip_prefix("image")
+            .unwrap_or("")
+            .trim_start_matches('/');
+        ContentDirectoryHandler::handle_image_browse(params, state, path_prefix_str).await
+    } else if params.object_id.starts_with("radio") {
+        ContentDirectoryHandler::handle_radio_browse(params, state).await
+    } else {
+        // This case might happen for deeper browsing or custom object IDs.
+        // Assume no specific type filter for the database query, and the object_id itself
+        // represents the path relative to the media root.
+        ContentDirectoryHandler::handle_folder_browse(params, state, "", params.object_id.as_str())
+            .await
+    }
+}
+
+/// Playlist editing through `CreateObject`, `CreateReference` and
+/// `DestroyObject`. Only stored playlists can be created or destroyed, and
+/// only references created here, `audio/playlists/{id}/{item}`, removed
+/// from them; everything else is a restricted object.
+async fn edit_playlists<D: DatabaseManager + 'static>(
+    action: &str,
+    body: &str,
+    state: &AppState<D>,
+) -> Response {
+    const SERVICE: &str = "urn:schemas-upnp-org:service:ContentDirectory:1";
+
+    let result = match action {
+        "CreateObject" => {
+            let container_id = xml_element_text(body, "ContainerID").unwrap_or_default();
+            if container_id.trim() != "audio/playlists" {
+                return upnp_error(713, "Restricted parent object");
+            }
+            let Some(elements) = xml_element_text(body, "Elements") else {
+                return upnp_error(402, "Invalid Args");
+            };
+            let class = xml_element_text(&elements, "class").unwrap_or_default();
+            if class.trim() != "object.container.playlistContainer" {
+                return upnp_error(712, "Bad metadata");
+            }
+            let Some(title) = xml_element_text(&elements, "title")
+                .map(|title| title.trim().to_owned())
+                .filter(|title| !title.is_empty())
+            else {
+                return upnp_error(712, "Bad metadata");
+            };
+            state
+                .database
+                .create_playlist(&title, None)
+                .await
+                .map(|playlist_id| {
+                    info!(playlist_id, %title, "DLNA control point created a playlist");
+                    format!(
+                        "<ObjectID>audio/playlists/{playlist_id}</ObjectID><Result>{}</Result>",
+                        crate::web::xml::created_playlist_result(playlist_id, &title)
+                    )
+                })
+        }
+        "CreateReference" => {
+            let container_id = xml_element_text(body, "ContainerID").unwrap_or_default();
+            let Some(playlist_id) = stored_playlist_id(container_id.trim()) else {
+                return upnp_error(710, "No such container");
+            };
+            match state.database.get_playlist(playlist_id).await {
+                Ok(Some(_)) => {}
+                Ok(None) => return upnp_error(710, "No such container"),
+                Err(error) => return playlist_edit_failed(action, error),
+            }
+            let object_id = xml_element_text(body, "ObjectID").unwrap_or_default();
+            let object_id = object_id.trim();
+            let file_id = match media_file_id(state, object_id).await {
+                Ok(Some(file_id)) => file_id,
+                Ok(None) => return upnp_error(701, "No such object"),
+                Err(error) => return playlist_edit_failed(action, error),
+            };
+            state
+                .database
+                .append_to_playlist(playlist_id, &[file_id])
+                .await
+                .map(|_| {
+                    info!(playlist_id, file_id, "DLNA control point added a track");
+                    format!("<NewID>{}/{object_id}</NewID>", container_id.trim())
+                })
+        }
+        _ => {
+            let object_id = xml_element_text(body, "ObjectID").unwrap_or_default();
+            let object_id = object_id.trim();
+            let removed = if let Some(playlist_id) = stored_playlist_id(object_id) {
+                state.database.delete_playlist(playlist_id).await
+            } else if let Some((playlist_id, item)) = object_id
+                .strip_prefix("audio/playlists/")
+                .and_then(|reference| reference.split_once('/'))
+                .and_then(|(playlist, item)| Some((playlist.parse::<i64>().ok()?, item)))
+            {
+                match media_file_id(state, item).await {
+                    Ok(Some(file_id)) => {
+                        state
+                            .database
+                            .remove_from_playlist(playlist_id, file_id)
+                            .await
+                    }
+                    Ok(None) => Ok(false),
+                    Err(error) => Err(error),
+                }
+            } else {
+                return upnp_error(711, "Restricted object");
+            };
+            match removed {
+                Ok(false) => return upnp_error(701, "No such object"),
+                Ok(true) => {
+                    info!(object_id, "DLNA control point destroyed a playlist object");
+                    Ok(String::new())
+                }
+                Err(error) => Err(error),
+            }
+        }
+    };
+    match result {
+        Ok(content) => {
+            crate::web::eventing::publish_content_change(state).await;
+            build_soap_response(action, SERVICE, &content)
+        }
+        Err(error) => playlist_edit_failed(action, error),
+    }
+}
+
+/// The ID of the stored playlist `object_id` names, `audio/playlists/{id}`.
+fn stored_playlist_id(object_id: &str) -> Option<i64> {
+    object_id
+        .strip_prefix("audio/playlists/")
+        .filter(|_| crate::web::xml::is_editable_container(object_id))?
+        .parse()
+        .ok()
+}
+
+/// The file behind a media item ID, `None` when there is no such item.
+async fn media_file_id<D: DatabaseManager>(
+    state: &AppState<D>,
+    object_id: &str,
+) -> anyhow::Result<Option<i64>> {
+    let Some(key) = parse_media_object_id(object_id) else {
+        return Ok(None);
+    };
+    Ok(state
+        .database
+        .get_file_location_by_object_key(key)
+        .await?
+        .map(|location| location.id))
+}
+
+fn playlist_edit_failed(action: &str, error: anyhow::Error) -> Response {
+    error!(%error, action, "Playlist edit from a DLNA control point failed");
+    upnp_error(720, "Cannot process the request")
+}
+
 pub async fn content_directory_control<D: DatabaseManager + 'static>(
     State(state): State<AppState<D>>,
+    method: Method,
     headers: HeaderMap,
     body: String,
 ) -> Response {
@@ -446,61 +886,42 @@ pub async fn content_directory_control<D: DatabaseManager + 'static>(
             Err(response) => return *response,
         };
         if action == "Browse" {
-            let params = parse_browse_params(&body);
-            info!("Browse request - ObjectID: {}, StartingIndex: {}, RequestedCount: {}",
-                  params.object_id, params.starting_index, params.requested_count);
-
-            // Handle root browse request (ObjectID "0")
-            if params.object_id == "0" {
-                return ContentDirectoryHandler::handle_root_browse(&params, &state).await;
-            }
+            let params =
+                parse_browse_params(&body, state.current_config().media.browse_default_count);
+            info!(
+                target: crate::logging::targets::BROWSE,
+                "Browse request - ObjectID: {}, StartingIndex: {}, RequestedCount: {}",
+                params.object_id,
+                params.starting_index,
+                params.requested_count
+            );
 
-            // Determine media type and delegate to specialized handlers
-            if params.object_id.starts_with("video") {
-                let path_prefix_str = params.object_id.strip_prefix("video").unwrap_or("").trim_start_matches('/');
-                return ContentDirectoryHandler::handle_video_browse(&params, &state, path_prefix_str).await;
-            } else if params.object_id.starts_with("audio") {
-                // Handle music categorization within audio section
-                let audio_path = params.object_id.strip_prefix("audio").unwrap_or("").trim_start_matches('/');
-
-                // Check for music categorization paths
-                if audio_path.is_empty() {
-                    // Root audio container - return categorization containers
-                    return handle_audio_root_browse(&params, &state).await;
-                } else if audio_path.starts_with("artists") {
-                    return ContentDirectoryHandler::handle_artist_browse(&params, &state, audio_path).await;
-                } else if audio_path.starts_with("albums") {
-                    return ContentDirectoryHandler::handle_album_browse(&params, &state, audio_path).await;
-                } else if audio_path.starts_with("genres") {
-                    return handle_genres_browse(&params, &state, audio_path).await;
-                } else if audio_path.starts_with("years") {
-                    return handle_years_browse(&params, &state, audio_path).await;
-                } else if audio_path.starts_with("playlists") {
-                    return handle_playlists_browse(&params, &state, audio_path).await;
-                } else if audio_path.starts_with("folders") {
-                    let folder_path = audio_path.strip_prefix("folders").unwrap_or("").trim_start_matches('/');
-                    return ContentDirectoryHandler::handle_music_browse(&params, &state, folder_path).await;
-                } else {
-                    // Traditional folder browsing within audio
-                    return ContentDirectoryHandler::handle_music_browse(&params, &state, audio_path).await;
-                }
-            } else if params.object_id.starts_with("image") {
-                let path_prefix_str = params.object_id.strip_prefix("image").unwrap_or("").trim_start_matches('/');
-                return ContentDirectoryHandler::handle_image_browse(&params, &state, path_prefix_str).await;
-            } else if params.object_id.starts_with("radio") {
-                return ContentDirectoryHandler::handle_radio_browse(&params, &state).await;
+            // Only a GET Browse can be answered from the client's cache; a
+            // POST is not a conditional request.
+            let validators = if method == Method::GET {
+                let update_id = state.content_update_id.load(Ordering::SeqCst);
+                let mut request = state.browse_cache.lock().await.epoch().to_le_bytes().to_vec();
+                request.extend_from_slice(format!("{client:?}").as_bytes());
+                request.extend_from_slice(crate::web::forwarded::resource_origin(&state).as_bytes());
+                request.extend_from_slice(body.as_bytes());
+                Some(Validators::for_update_id(update_id, &request))
             } else {
-                // This case might happen for deeper browsing or custom object IDs.
-                // Assume no specific type filter for the database query, and the object_id itself
-                // represents the path relative to the media root.
-                return ContentDirectoryHandler::handle_folder_browse(&params, &state, "", params.object_id.as_str()).await;
+                None
+            };
+            if let Some(validators) = validators.as_ref().filter(|v| v.not_modified(&headers)) {
+                return validators.not_modified_response();
+            }
+            let mut response = browse(&params, &state).await;
+            if let Some(validators) = validators.as_ref().filter(|_| response.status() == StatusCode::OK) {
+                validators.insert_into(response.headers_mut());
             }
+            response
         } else if action == "GetSearchCapabilities" {
             let content = "<SearchCaps>dc:creator,dc:date,dc:title,upnp:album,upnp:actor,upnp:artist,upnp:class,upnp:genre,@refID</SearchCaps>";
             build_soap_response("GetSearchCapabilities", "urn:schemas-upnp-org:service:ContentDirectory:1", content)
         } else if action == "GetSortCapabilities" {
-            let content = "<SortCaps>dc:title,dc:date,upnp:class,upnp:album,upnp:originalTrackNumber</SortCaps>";
-            build_soap_response("GetSortCapabilities", "urn:schemas-upnp-org:service:ContentDirectory:1", content)
+            let content = format!("<SortCaps>{}</SortCaps>", crate::database::sort::sort_capabilities());
+            build_soap_response("GetSortCapabilities", "urn:schemas-upnp-org:service:ContentDirectory:1", &content)
         } else if action == "GetSystemUpdateID" {
             let update_id = state.content_update_id.load(Ordering::SeqCst);
             let content = format!("<Id>{}</Id>", update_id);
@@ -509,13 +930,23 @@ pub async fn content_directory_control<D: DatabaseManager + 'static>(
             let content = r#"<FeatureList>&lt;?xml version="1.0" encoding="utf-8"?&gt;&lt;Features xmlns="urn:schemas-upnp-org:av:avs" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="urn:schemas-upnp-org:av:avs http://www.upnp.org/schemas/av/avs.xsd"&gt;&lt;Feature name="samsung.com_BASICVIEW" version="1"&gt;&lt;container id="1" type="object.item.audioItem"/&gt;&lt;container id="2" type="object.item.videoItem"/&gt;&lt;container id="3" type="object.item.imageItem"/&gt;&lt;/Feature&gt;&lt;/Features&gt;</FeatureList>"#;
             build_soap_response("X_GetFeatureList", "urn:schemas-upnp-org:service:ContentDirectory:1", content)
         } else if action == "X_SetBookmark" {
+            if !state.current_config().media.samsung_bookmarks {
+                return build_soap_response("X_SetBookmark", "urn:schemas-upnp-org:service:ContentDirectory:1", "");
+            }
             let object_id = xml_element_text(&body, "ObjectID");
             let pos_second = xml_element_text(&body, "PosSecond");
             if let (Some(object_id), Some(pos_second)) = (object_id, pos_second) {
-              if let (Ok(file_id), Ok(pos)) = (object_id.parse::<i64>(), pos_second.parse::<u32>()) {
-                if state.database.get_file_location_by_id(file_id).await.ok().flatten().is_none() {
+              let location = if let Some(key) = parse_media_object_id(&object_id) {
+                  Some(state.database.get_file_location_by_object_key(key).await)
+              } else if let Ok(file_id) = object_id.parse::<i64>() {
+                  Some(state.database.get_file_location_by_id(file_id).await)
+              } else {
+                  None
+              };
+              if let (Some(location), Ok(pos)) = (location, pos_second.parse::<u32>()) {
+                let Some(file_id) = location.ok().flatten().map(|location| location.id) else {
                     return (StatusCode::BAD_REQUEST, "Unknown media ID").into_response();
-                }
+                };
                 let mut bookmarks_guard = state.bookmarks.lock().await;
                 bookmarks_guard.insert(file_id, pos);
                 drop(bookmarks_guard);
@@ -523,6 +954,10 @@ pub async fn content_directory_control<D: DatabaseManager + 'static>(
               }
             }
             build_soap_response("X_SetBookmark", "urn:schemas-upnp-org:service:ContentDirectory:1", "")
+        } else if matches!(action.as_str(), "CreateObject" | "CreateReference" | "DestroyObject")
+            && state.current_config().media.dlna_playlist_editing
+        {
+            edit_playlists(&action, &body, &state).await
         } else {
             (
                 StatusCode::NOT_IMPLEMENTED,
@@ -564,13 +999,13 @@ async fn handle_audio_root_browse<D: DatabaseManager>(
 
     let total_matches = subdirectories.len();
     let page = browse_page_bounds(params, total_matches);
-    let server_ip = state.get_server_ip();
+    let origin = crate::web::forwarded::resource_origin(state);
     let response = generate_browse_response(
         &params.object_id,
         &subdirectories[page],
         &[],
         state,
-        &server_ip,
+        &origin,
         total_matches,
     )
     .await;
@@ -671,27 +1106,129 @@ async fn handle_years_browse<D: DatabaseManager + 'static>(
     .await
 }
 
-/// Handle browsing playlists with atomic performance tracking and ReDB operations
+/// Handle browsing playlists with atomic performance tracking and ReDB operations.
+/// Smart playlists are listed after the stored ones as `smart-{id}`.
 async fn handle_playlists_browse<D: DatabaseManager + 'static>(
     params: &BrowseParams,
     state: &AppState<D>,
     audio_path: &str,
 ) -> Response {
+    if let Some(smart_id) = audio_path.strip_prefix("playlists/smart-") {
+        return handle_smart_playlist_browse(params, state, smart_id).await;
+    }
     let database = state.database.clone();
     handle_generic_category_browse(
         params,
         state,
         audio_path,
         "playlists",
-        move || async move { database.get_playlists().await },
-        |playlist| crate::database::MediaDirectory {
-            path: std::path::PathBuf::from(format!("audio/playlists/{}", playlist.id.unwrap_or(0))),
-            name: playlist.name,
+        move || async move {
+            let mut containers: Vec<MediaDirectory> = database
+                .get_playlists()
+                .await?
+                .into_iter()
+                .map(|playlist| MediaDirectory {
+                    path: PathBuf::from(format!("audio/playlists/{}", playlist.id.unwrap_or(0))),
+                    name: playlist.name,
+                })
+                .collect();
+            containers.extend(
+                crate::database::smart_playlists::list(database.as_ref())
+                    .await?
+                    .into_iter()
+                    .map(|playlist| MediaDirectory {
+                        path: PathBuf::from(format!("audio/playlists/smart-{}", playlist.id)),
+                        name: playlist.name,
+                    }),
+            );
+            Ok(containers)
         },
+        |container| container,
     )
     .await
 }
 
+/// Evaluate a smart playlist's rules and return one page of the result.
+/// Nothing is cached, so the next Browse after a library change sees it.
+async fn handle_smart_playlist_browse<D: DatabaseManager + 'static>(
+    params: &BrowseParams,
+    state: &AppState<D>,
+    smart_id: &str,
+) -> Response {
+    use crate::database::smart_playlists;
+
+    let start_time = Instant::now();
+    let Ok(id) = smart_id.parse() else {
+        return (StatusCode::BAD_REQUEST, "Invalid playlist ID").into_response();
+    };
+    let files = match smart_playlists::get(state.database.as_ref(), id).await {
+        Ok(Some(playlist)) => {
+            timed_backend_call(
+                state.database.as_ref(),
+                "read:smart_playlist_items",
+                &params.object_id,
+                smart_playlists::evaluate(state.database.as_ref(), &playlist),
+            )
+            .await
+        }
+        Ok(None) => {
+            state.web_metrics.record_error();
+            return (
+                StatusCode::NOT_FOUND,
+                [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+                "Unknown smart playlist",
+            )
+                .into_response();
+        }
+        Err(e) => Err(e),
+    };
+    let response_time = start_time.elapsed().as_micros() as u64;
+    match files {
+        Ok(files) => {
+            state
+                .web_metrics
+                .record_browse_request(response_time, !files.is_empty());
+            let page = browse_page_bounds(params, files.len());
+            let response = crate::web::xml::generate_browse_response(
+                &params.object_id,
+                &[],
+                &files[page],
+                state,
+                &crate::web::forwarded::resource_origin(state),
+                files.len(),
+            )
+            .await;
+            (
+                StatusCode::OK,
+                [
+                    (header::CONTENT_TYPE, "text/xml; charset=utf-8"),
+                    (header::HeaderName::from_static("ext"), ""),
+                ],
+                response,
+            )
+                .into_response()
+        }
+        Err(e) => {
+            error!(
+                target: crate::logging::targets::BROWSE,
+                "Error evaluating smart playlist {}: {}",
+                id,
+                e
+            );
+            state.web_metrics.record_error();
+            state
+                .web_metrics
+                .record_browse_request(response_time, false);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+                "Internal Server Error",
+            )
+                .into_response()
+        }
+    }
+}
+
 /// Helper function to perform generic music category browsing
 async fn handle_generic_category_browse<D, C, F, FFuture>(
     params: &BrowseParams,
@@ -724,6 +1261,8 @@ where
         client_profile: client,
         content_update_id: current_update_id,
         browse_epoch,
+        sort_criteria: params.sort_criteria.clone(),
+        origin: crate::web::forwarded::resource_origin(state),
     };
 
     // Cache lookup
@@ -739,8 +1278,10 @@ where
             let response_time = start_time.elapsed().as_micros() as u64;
             state.web_metrics.record_browse_request(response_time, true);
             debug!(
+                target: crate::logging::targets::BROWSE,
                 "Browse Cache Hit for Category ObjectID: {} ({}ms)",
-                params.object_id, response_time
+                params.object_id,
+                response_time
             );
             return (
                 StatusCode::OK,
@@ -766,7 +1307,15 @@ where
     };
 
     if is_category_list {
-        match list_categories_fn().await {
+        let operation = format!("list:{category_name}");
+        let categories = timed_backend_call(
+            state.database.as_ref(),
+            &operation,
+            &params.object_id,
+            list_categories_fn(),
+        )
+        .await;
+        match categories {
             Ok(categories) => {
                 let has_data = !categories.is_empty();
                 let subdirectories: Vec<crate::database::MediaDirectory> =
@@ -780,19 +1329,20 @@ where
                     .record_browse_request(response_time, has_data);
 
                 debug!(
+                    target: crate::logging::targets::BROWSE,
                     "ReDB retrieved {} {} in {}ms",
                     subdirectories.len(),
                     category_name,
                     response_time
                 );
 
-                let server_ip = state.get_server_ip();
+                let origin = crate::web::forwarded::resource_origin(state);
                 let response = generate_browse_response(
                     &params.object_id,
                     &subdirectories[page],
                     &[],
                     state,
-                    &server_ip,
+                    &origin,
                     total_matches,
                 )
                 .await;
@@ -820,7 +1370,12 @@ where
                     .into_response()
             }
             Err(e) => {
-                error!("ReDB error getting {}: {}", category_name, e);
+                error!(
+                    target: crate::logging::targets::BROWSE,
+                    "ReDB error getting {}: {}",
+                    category_name,
+                    e
+                );
 
                 let response_time = start_time.elapsed().as_micros() as u64;
                 state.web_metrics.record_error();
@@ -855,28 +1410,54 @@ where
             _ => return (StatusCode::BAD_REQUEST, "Unknown category").into_response(),
         };
         let requested_count = browse_page_limit(params);
-        let bookmarks = if matches!(
-            client,
-            crate::web::client::DlnaClientProfile::SamsungTv
-                | crate::web::client::DlnaClientProfile::SamsungTvQ
-        ) {
-            state.bookmarks.lock().await.snapshot()
-        } else {
-            std::collections::HashMap::new()
-        };
+        let bookmarks = resume_bookmarks(state, client).await;
         let context = crate::web::xml::BrowseRenderContext {
             client,
-            server_ip: state.get_server_ip(),
-            server_port: state.current_config().server.port,
-            autoplay_enabled: state.current_config().media.autoplay_enabled,
+            origin: crate::web::forwarded::resource_origin(state),
+            autoplay_enabled: crate::web::xml::autoplay_for_client(&state.current_config(), client),
             update_id: current_update_id,
             bookmarks,
+            bitrate_limit: crate::web::xml::BitrateLimit::for_client(
+                &state.current_config(),
+                client,
+            ),
+            iso_mime_type: state.current_config().media.iso_mime_type.clone(),
+            sort: crate::database::SortSpec::parse(&params.sort_criteria),
+            // Playlists list exactly what was added to them.
+            hidden: if matches!(query, crate::database::MediaFileQuery::Playlist(_)) {
+                Default::default()
+            } else {
+                hidden_files(state).await
+            },
+            container_update_ids: state.container_update_ids.clone(),
+            entry_info: match &query {
+                crate::database::MediaFileQuery::Playlist(playlist_id) => std::sync::Arc::new(
+                    state
+                        .database
+                        .get_playlist_entry_info(*playlist_id)
+                        .await
+                        .unwrap_or_else(|error| {
+                            warn!(
+                                target: crate::logging::targets::BROWSE,
+                                "Entry titles of playlist {} unavailable: {}",
+                                playlist_id,
+                                error
+                            );
+                            Default::default()
+                        }),
+                ),
+                _ => Default::default(),
+            },
         };
         let object_id = params.object_id.clone();
         let starting_index = params.starting_index as usize;
         let database = state.database.clone();
-        match database
-            .read(move |session| {
+        let operation = format!("read:{category_name}_items");
+        let items = timed_backend_call(
+            state.database.as_ref(),
+            &operation,
+            &params.object_id,
+            database.read(move |session| {
                 crate::web::xml::generate_indexed_items_response(
                     session,
                     query,
@@ -885,16 +1466,21 @@ where
                     requested_count,
                     context,
                 )
-            })
-            .await
-        {
+            }),
+        )
+        .await;
+        match items {
             Ok(response) => {
                 let response_time = start_time.elapsed().as_micros() as u64;
                 state.web_metrics.record_browse_request(response_time, true);
 
                 debug!(
+                    target: crate::logging::targets::BROWSE,
                     "ReDB retrieved {} tracks for {} '{}' in {}ms",
-                    "zero-copy", category_name, key_str, response_time
+                    "zero-copy",
+                    category_name,
+                    key_str,
+                    response_time
                 );
 
                 // Cache insert
@@ -921,8 +1507,11 @@ where
             }
             Err(e) => {
                 error!(
+                    target: crate::logging::targets::BROWSE,
                     "ReDB error getting music by {} {}: {}",
-                    category_name, key_str, e
+                    category_name,
+                    key_str,
+                    e
                 );
 
                 let response_time = start_time.elapsed().as_micros() as u64;
@@ -955,6 +1544,110 @@ where
     }
 }
 
+/// Filesystem folders addressed by a folder-browse path such as `d0/movies`.
+pub(crate) struct FolderBrowseTarget {
+    /// Folder the path names, resolved against the primary media directory
+    /// when it carries no `dN` root prefix.
+    pub browse_path: PathBuf,
+    /// The same relative folder under every available member of the root's
+    /// merge group; empty when the root is not merged.
+    pub merged_parents: Vec<PathBuf>,
+}
+
+impl FolderBrowseTarget {
+    pub(crate) fn resolve(
+        monitored_dirs: &[crate::config::MonitoredDirectoryConfig],
+        unavailable_roots: &std::collections::HashSet<PathBuf>,
+        media_root: PathBuf,
+        path_prefix_str: &str,
+    ) -> Self {
+        // Parse directory index prefix (e.g. "d0/movies" -> index 0, relative path "movies").
+        // Folder names in object IDs keep raw filename bytes escaped.
+        let (dir_index_opt, relative_path) = parse_dir_index_prefix(path_prefix_str);
+        let under = |idx: usize| {
+            let base_path = PathBuf::from(&monitored_dirs[idx].path);
+            if relative_path.is_empty() {
+                base_path
+            } else {
+                base_path.join(path_from_lossless_string(relative_path))
+            }
+        };
+
+        let browse_path = match dir_index_opt {
+            Some(idx) if idx < monitored_dirs.len() => under(idx),
+            _ => {
+                if path_prefix_str.is_empty() {
+                    media_root
+                } else {
+                    media_root.join(path_from_lossless_string(path_prefix_str))
+                }
+            }
+        };
+
+        // Other members of a merge group contribute the same relative folder.
+        let merge_group = dir_index_opt
+            .and_then(|idx| crate::overlay::MergeGroup::for_directory(monitored_dirs, idx));
+        let merged_parents = merge_group
+            .iter()
+            .flat_map(|group| group.members.iter())
+            .map(|&member| under(member))
+            .filter(|path| {
+                path.is_dir() && !unavailable_roots.iter().any(|root| path.starts_with(root))
+            })
+            .collect();
+
+        Self {
+            browse_path,
+            merged_parents,
+        }
+    }
+}
+
+/// Virtual `dN` folders listed at the root of the `media_type_filter` family
+/// when more than one directory is monitored. Unavailable roots and roots
+/// whose `media_types` leave out the family are hidden, and a merge group
+/// appears once, as its best-ranked available member.
+pub(crate) fn root_folders(
+    monitored_dirs: &[crate::config::MonitoredDirectoryConfig],
+    unavailable_roots: &std::collections::HashSet<PathBuf>,
+    media_type_filter: &str,
+) -> Vec<MediaDirectory> {
+    let listed = |dir: &crate::config::MonitoredDirectoryConfig| {
+        let root = PathBuf::from(&dir.path);
+        root.is_dir()
+            && !unavailable_roots.contains(&root)
+            && (media_type_filter.is_empty() || dir.admits_mime_type(media_type_filter))
+    };
+    let mut subdirs = Vec::new();
+    for (idx, dir) in monitored_dirs.iter().enumerate() {
+        if !listed(dir) {
+            continue;
+        }
+        let path = PathBuf::from(&dir.path);
+        let group = crate::overlay::MergeGroup::for_directory(monitored_dirs, idx);
+        if let Some(group) = &group {
+            let representative = group
+                .members
+                .iter()
+                .copied()
+                .find(|&member| listed(&monitored_dirs[member]));
+            if representative != Some(idx) {
+                continue;
+            }
+        }
+        let name = group.map(|group| group.name).unwrap_or_else(|| {
+            path.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| dir.path.clone())
+        });
+        subdirs.push(MediaDirectory {
+            path: PathBuf::from(format!("d{}", idx)),
+            name,
+        });
+    }
+    subdirs
+}
+
 fn parse_dir_index_prefix(path_prefix_str: &str) -> (Option<usize>, &str) {
     if path_prefix_str.starts_with('d') {
         let chars = path_prefix_str.chars().skip(1);
@@ -986,6 +1679,116 @@ fn parse_dir_index_prefix(path_prefix_str: &str) -> (Option<usize>, &str) {
     }
 }
 
+/// Resume positions to render for `client`: only Samsung TVs read
+/// `sec:dcmInfo`, and only while `media.samsung_bookmarks` is on.
+async fn resume_bookmarks<D: DatabaseManager>(
+    state: &AppState<D>,
+    client: crate::web::client::DlnaClientProfile,
+) -> Option<std::collections::HashMap<i64, u32>> {
+    let samsung = matches!(
+        client,
+        crate::web::client::DlnaClientProfile::SamsungTv
+            | crate::web::client::DlnaClientProfile::SamsungTvQ
+    );
+    if !samsung || !state.current_config().media.samsung_bookmarks {
+        return None;
+    }
+    Some(state.bookmarks.lock().await.snapshot())
+}
+
+/// File IDs left out of listings: files missing from disk during
+/// `media.deletion_grace_minutes`, and the copies `media.hide_duplicates`
+/// hides. The set is recomputed at most once per content update. The whole
+/// library is only searched for missing files after files were marked
+/// missing; otherwise the known ones are looked up again, as they may have
+/// come back or been removed.
+pub(crate) async fn hidden_files<D: DatabaseManager + 'static>(
+    state: &AppState<D>,
+) -> Arc<HashSet<i64>> {
+    let hide_duplicates = state.current_config().media.hide_duplicates;
+    let update_id = state.content_update_id.load(Ordering::SeqCst);
+    let mut index = state.hidden_files.lock().await;
+    if index.update_id == Some(update_id) && index.duplicates == hide_duplicates {
+        return index.hidden.clone();
+    }
+    let missing_marked = match state
+        .database
+        .get_setting(crate::media::MISSING_FILES_MARKED_SETTING)
+        .await
+    {
+        Ok(marked) => marked,
+        Err(error) => {
+            warn!(
+                target: crate::logging::targets::BROWSE,
+                %error,
+                "Could not find hidden files; keeping the previous list"
+            );
+            return index.hidden.clone();
+        }
+    };
+    let known_missing = (index.update_id.is_some() && index.missing_marked == missing_marked)
+        .then(|| index.missing.clone());
+    let directories = state.media_directories.read().await.clone();
+    match state
+        .database
+        .clone()
+        .read(move |session| {
+            let mut missing = HashSet::new();
+            let queries = match known_missing {
+                Some(known) => known.into_iter().map(MediaFileQuery::Id).collect(),
+                None => vec![MediaFileQuery::All],
+            };
+            for query in &queries {
+                session.visit_files(query, 0, usize::MAX, |file| {
+                    if file.missing_since_secs().is_some() {
+                        missing.extend(file.id());
+                    }
+                    Ok(())
+                })?;
+            }
+            let duplicates = if hide_duplicates {
+                hidden_duplicate_ids(session, &directories)?
+            } else {
+                HashSet::new()
+            };
+            Ok((missing, duplicates))
+        })
+        .await
+    {
+        Ok((missing, mut hidden)) => {
+            hidden.extend(&missing);
+            index.update_id = Some(update_id);
+            index.duplicates = hide_duplicates;
+            index.hidden = Arc::new(hidden);
+            index.missing = missing;
+            index.missing_marked = missing_marked;
+        }
+        Err(error) => warn!(
+            target: crate::logging::targets::BROWSE,
+            %error,
+            "Could not find hidden files; keeping the previous list"
+        ),
+    }
+    index.hidden.clone()
+}
+
+/// UPnP error response: a SOAP fault carrying `UPnPError` `code`.
+fn upnp_error(code: u16, description: &str) -> Response {
+    let xml = format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/" s:encodingStyle="http://schemas.xmlsoap.org/soap/encoding/"><s:Body><s:Fault><faultcode>s:Client</faultcode><faultstring>UPnPError</faultstring><detail><UPnPError xmlns="urn:schemas-upnp-org:control-1-0"><errorCode>{code}</errorCode><errorDescription>{description}</errorDescription></UPnPError></detail></s:Fault></s:Body></s:Envelope>"#
+    );
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        [
+            (header::CONTENT_TYPE, "text/xml; charset=utf-8"),
+            (header::HeaderName::from_static("ext"), ""),
+        ],
+        xml,
+    )
+        .into_response()
+}
+
 fn build_soap_response(action: &str, service_type: &str, content: &str) -> Response {
     let mut xml =
         String::with_capacity(300 + action.len() * 2 + service_type.len() + content.len());
@@ -1017,26 +1820,24 @@ fn build_soap_response(action: &str, service_type: &str, content: &str) -> Respo
         .into_response()
 }
 
-pub async fn connection_manager_scpd() -> impl IntoResponse {
-    let xml = crate::web::xml::generate_connection_manager_scpd();
-    (
-        StatusCode::OK,
-        [(header::CONTENT_TYPE, "text/xml; charset=utf-8")],
-        xml,
+pub async fn connection_manager_scpd(headers: HeaderMap) -> Response {
+    cached_xml_response(
+        &headers,
+        crate::web::xml::generate_connection_manager_scpd(),
+        SCPD_MAX_AGE_SECONDS,
     )
 }
 
-pub async fn media_receiver_registrar_scpd() -> impl IntoResponse {
-    let xml = crate::web::xml::generate_registrar_scpd();
-    (
-        StatusCode::OK,
-        [(header::CONTENT_TYPE, "text/xml; charset=utf-8")],
-        xml,
+pub async fn media_receiver_registrar_scpd(headers: HeaderMap) -> Response {
+    cached_xml_response(
+        &headers,
+        crate::web::xml::generate_registrar_scpd(),
+        SCPD_MAX_AGE_SECONDS,
     )
 }
 
 pub async fn connection_manager_control<D: DatabaseManager>(
-    State(_state): State<AppState<D>>,
+    State(state): State<AppState<D>>,
     headers: HeaderMap,
     body: String,
 ) -> Response {
@@ -1045,11 +1846,19 @@ pub async fn connection_manager_control<D: DatabaseManager>(
         Err(response) => return *response,
     };
     if action == "GetProtocolInfo" {
-        let content = r#"<Source>http-get:*:video/x-msvideo:*,http-get:*:video/mp4:*,http-get:*:video/x-matroska:*,http-get:*:video/x-mkv:*,http-get:*:video/mpeg:*,http-get:*:video/divx:*,http-get:*:audio/mpeg:*,http-get:*:audio/x-flac:*,http-get:*:audio/wav:*,http-get:*:audio/mp4:*,http-get:*:image/jpeg:*,http-get:*:image/png:*,http-get:*:image/gif:*</Source><Sink></Sink>"#;
+        // Announce exactly the types and flags Browse results use for this client.
+        let client = crate::web::client::detect_client(&headers);
+        let config = state.current_config();
+        let source = crate::web::xml::source_protocol_info(
+            client,
+            crate::web::xml::autoplay_for_client(&config, client),
+            &config.media.iso_mime_type,
+        );
+        let content = format!("<Source>{source}</Source><Sink></Sink>");
         build_soap_response(
             "GetProtocolInfo",
             "urn:schemas-upnp-org:service:ConnectionManager:1",
-            content,
+            &content,
         )
     } else if action == "GetCurrentConnectionIDs" {
         let content = "<ConnectionIDs>0</ConnectionIDs>";
@@ -1059,7 +1868,12 @@ pub async fn connection_manager_control<D: DatabaseManager>(
             content,
         )
     } else if action == "GetCurrentConnectionInfo" {
-        let content = r#"<RcsID>-1</RcsID><AVTransportID>-1</AVTransportID><ProtocolInfo></ProtocolInfo><PeerConnectionManager></PeerConnectionManager><PeerConnectionID>-1</PeerConnectionID><Direction>Output</Direction><Status>Unknown</Status>"#;
+        // Only the default connection 0 exists without PrepareForConnection.
+        let connection_id = xml_element_text(&body, "ConnectionID");
+        if connection_id.as_deref().map(str::trim) != Some("0") {
+            return upnp_error(706, "Invalid connection reference");
+        }
+        let content = r#"<RcsID>-1</RcsID><AVTransportID>-1</AVTransportID><ProtocolInfo></ProtocolInfo><PeerConnectionManager></PeerConnectionManager><PeerConnectionID>-1</PeerConnectionID><Direction>Output</Direction><Status>OK</Status>"#;
         build_soap_response(
             "GetCurrentConnectionInfo",
             "urn:schemas-upnp-org:service:ConnectionManager:1",
@@ -1112,6 +1926,81 @@ pub async fn media_receiver_registrar_control<D: DatabaseManager>(
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn service_descriptions_answer_revalidation_with_not_modified() {
+        let response = content_directory_scpd(HeaderMap::new()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CACHE_CONTROL],
+            "public, max-age=86400"
+        );
+        let etag = response.headers()[header::ETAG].clone();
+
+        let mut revalidation = HeaderMap::new();
+        revalidation.insert(header::IF_NONE_MATCH, etag.clone());
+        let response = content_directory_scpd(revalidation.clone()).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], etag);
+        assert!(response.headers().contains_key(header::CACHE_CONTROL));
+
+        // Another document, such as a renamed server's description, does
+        // not match the old tag.
+        let response = cached_xml_response(
+            &revalidation,
+            "<root/>".to_owned(),
+            DESCRIPTION_MAX_AGE_SECONDS,
+        );
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_ne!(response.headers()[header::ETAG], etag);
+        assert_eq!(
+            response.headers()[header::CACHE_CONTROL],
+            "public, max-age=60"
+        );
+    }
+
+    #[test]
+    fn backend_timings_name_the_backend_and_operation() {
+        #[derive(Clone, Default)]
+        struct Captured(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+        impl std::io::Write for Captured {
+            fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(bytes);
+                Ok(bytes.len())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let database = crate::database::memory::InMemoryDatabase::new();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let output = tracing::subscriber::with_default(subscriber, || {
+            runtime.block_on(timed_backend_call(
+                &database,
+                "list:artists",
+                "audio/artists",
+                async { 7 },
+            ))
+        });
+
+        assert_eq!(output, 7);
+        let logged = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        assert!(logged.contains(crate::web::BACKEND_TIMING_TARGET));
+        assert!(logged.contains("backend=\"memory\""));
+        assert!(logged.contains("operation=\"list:artists\""));
+        assert!(logged.contains("subject=\"audio/artists\""));
+        assert!(logged.contains("elapsed_us="));
+    }
+
     #[test]
     fn soap_action_ignores_action_names_in_comments() {
         let headers = HeaderMap::new();
@@ -1148,17 +2037,18 @@ mod tests {
     </s:Body>
 </s:Envelope>"#;
 
-        let params = parse_browse_params(xml_body);
+        let params = parse_browse_params(xml_body, 0);
         assert_eq!(params.object_id, "video/movies");
         assert_eq!(params.starting_index, 10);
         assert_eq!(params.requested_count, 25);
+        assert!(params.sort_criteria.is_empty());
     }
 
     #[test]
     fn test_parse_browse_params_minimal_xml() {
         let xml_body = r#"<ObjectID>0</ObjectID><StartingIndex>0</StartingIndex><RequestedCount>0</RequestedCount>"#;
 
-        let params = parse_browse_params(xml_body);
+        let params = parse_browse_params(xml_body, 0);
         assert_eq!(params.object_id, "0");
         assert_eq!(params.starting_index, 0);
         assert_eq!(params.requested_count, 0);
@@ -1168,27 +2058,62 @@ mod tests {
     fn test_parse_browse_params_missing_elements() {
         let xml_body = r#"<ObjectID>audio/artists</ObjectID>"#;
 
-        let params = parse_browse_params(xml_body);
+        let params = parse_browse_params(xml_body, 0);
         assert_eq!(params.object_id, "audio/artists");
         assert_eq!(params.starting_index, 0); // Default value
         assert_eq!(params.requested_count, 0); // Default value
+
+        let params = parse_browse_params(xml_body, 40);
+        assert_eq!(params.requested_count, 40);
+        assert_eq!(browse_page_limit(&params), 40);
+        let params = parse_browse_params(
+            r#"<ObjectID>0</ObjectID><RequestedCount></RequestedCount>"#,
+            40,
+        );
+        assert_eq!(params.requested_count, 40);
     }
 
     #[test]
     fn test_parse_browse_params_invalid_numbers() {
         let xml_body = r#"<ObjectID>test</ObjectID><StartingIndex>invalid</StartingIndex><RequestedCount>not_a_number</RequestedCount>"#;
 
-        let params = parse_browse_params(xml_body);
+        let params = parse_browse_params(xml_body, 25);
         assert_eq!(params.object_id, "test");
         assert_eq!(params.starting_index, 0); // Falls back to default
-        assert_eq!(params.requested_count, 0); // Falls back to default
+        assert_eq!(params.requested_count, 25); // Falls back to default
+
+        for (starting_index, requested_count, expected) in [
+            ("-5", "-1", (0, 25)),
+            ("1.5", "1e3", (0, 25)),
+            ("+7", "+3", (7, 3)),
+            ("99999999999999999999", "4294967296", (u32::MAX, u32::MAX)),
+        ] {
+            let params = parse_browse_params(
+                &format!(
+                    "<StartingIndex>{starting_index}</StartingIndex>\
+                     <RequestedCount>{requested_count}</RequestedCount>"
+                ),
+                25,
+            );
+            assert_eq!(
+                (params.starting_index, params.requested_count),
+                expected,
+                "{starting_index} / {requested_count}"
+            );
+        }
+
+        // An oversized index is past the end, never back at the start.
+        let params = parse_browse_params("<StartingIndex>4294967296</StartingIndex>", 25);
+        assert!(browse_page_bounds(&params, 10).is_empty());
+        let params = parse_browse_params("<RequestedCount>4294967296</RequestedCount>", 25);
+        assert_eq!(browse_page_limit(&params), 2_000);
     }
 
     #[test]
     fn test_parse_browse_params_empty_xml() {
         let xml_body = "";
 
-        let params = parse_browse_params(xml_body);
+        let params = parse_browse_params(xml_body, 0);
         assert_eq!(params.object_id, "0"); // Default value
         assert_eq!(params.starting_index, 0); // Default value
         assert_eq!(params.requested_count, 0); // Default value
@@ -1199,7 +2124,7 @@ mod tests {
         let xml_body =
             r#"<ObjectID>test</ObjectID><StartingIndex>5<RequestedCount>10</RequestedCount>"#;
 
-        let params = parse_browse_params(xml_body);
+        let params = parse_browse_params(xml_body, 0);
         // Should handle malformed XML gracefully and extract what it can
         assert_eq!(params.object_id, "test");
         // The parser should still work despite the malformed StartingIndex tag
@@ -1213,7 +2138,7 @@ mod tests {
         <RequestedCount>  15  </RequestedCount>
         "#;
 
-        let params = parse_browse_params(xml_body);
+        let params = parse_browse_params(xml_body, 0);
         assert_eq!(params.object_id, "video/series"); // Should be trimmed
         assert_eq!(params.starting_index, 5);
         assert_eq!(params.requested_count, 15);
@@ -1238,10 +2163,11 @@ mod tests {
     </s:Body>
 </s:Envelope>"#;
 
-        let params = parse_browse_params(complex_xml);
+        let params = parse_browse_params(complex_xml, 0);
         assert_eq!(params.object_id, "video/movies/action");
         assert_eq!(params.starting_index, 100);
         assert_eq!(params.requested_count, 50);
+        assert_eq!(params.sort_criteria, "+dc:title");
     }
 
     #[test]
@@ -16,6 +16,8 @@ pub(super) struct BrowseParams {
     pub(super) starting_index: u32,
     pub(super) requested_count: u32,
     pub(super) browse_flag: BrowseFlag,
+    /// Raw `SortCriteria`, empty when the renderer did not ask for an order.
+    pub(super) sort_criteria: String,
 }
 
 const MAX_BROWSE_ITEMS_PER_RESPONSE: usize = 2_000;
@@ -102,26 +104,48 @@ fn body_soap_action(body: &str) -> Result<String, &'static str> {
     Err("SOAP body has no action element")
 }
 
+/// Text of the first non-empty `expected_name` element, with entity and
+/// character references resolved. Arguments such as `Elements` carry a whole
+/// escaped DIDL-Lite document, which the reader splits at every reference.
 pub(super) fn xml_element_text(body: &str, expected_name: &str) -> Option<String> {
-    use quick_xml::{events::Event, Reader};
+    use quick_xml::{escape::resolve_predefined_entity, events::Event, Reader};
 
     let mut reader = Reader::from_str(body);
     let mut buffer = Vec::new();
-    let mut capture = false;
+    let mut captured: Option<String> = None;
     loop {
-        match reader.read_event_into(&mut buffer).ok()? {
-            Event::Start(element) => {
-                capture = local_xml_name(element.name().as_ref()) == expected_name;
+        match (reader.read_event_into(&mut buffer).ok()?, captured.as_mut()) {
+            (Event::Start(element), _) => {
+                captured =
+                    (local_xml_name(element.name().as_ref()) == expected_name).then(String::new);
+            }
+            (Event::Text(text), Some(value)) => {
+                value.push_str(&reader.decoder().decode(text.as_ref()).ok()?);
             }
-            Event::Text(text) if capture => {
-                return reader
-                    .decoder()
-                    .decode(text.as_ref())
-                    .ok()
-                    .map(|value| value.into_owned());
+            (Event::CData(text), Some(value)) => {
+                value.push_str(&reader.decoder().decode(text.as_ref()).ok()?);
             }
-            Event::End(_) => capture = false,
-            Event::Eof => return None,
+            (Event::GeneralRef(reference), Some(value)) => {
+                if let Ok(Some(character)) = reference.resolve_char_ref() {
+                    value.push(character);
+                } else {
+                    let name = reference.decode().ok()?;
+                    match resolve_predefined_entity(&name) {
+                        Some(resolved) => value.push_str(resolved),
+                        None => {
+                            value.push('&');
+                            value.push_str(&name);
+                            value.push(';');
+                        }
+                    }
+                }
+            }
+            (Event::End(_), _) => {
+                if let Some(value) = captured.take().filter(|value| !value.is_empty()) {
+                    return Some(value);
+                }
+            }
+            (Event::Eof, _) => return None,
             _ => {}
         }
         buffer.clear();
@@ -146,7 +170,22 @@ fn invalid_soap_request(message: &'static str) -> Response {
         .into_response()
 }
 
-pub(super) fn parse_browse_params(body: &str) -> BrowseParams {
+/// A paging argument such as `StartingIndex`. Numbers past `u32::MAX`
+/// saturate, so an oversized index lands past the end rather than at 0.
+fn parse_paging_number(text: &str) -> Option<u32> {
+    let text = text.trim();
+    let digits = text.strip_prefix('+').unwrap_or(text);
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    Some(digits.parse().unwrap_or(u32::MAX))
+}
+
+/// Browse arguments from a SOAP body. A missing or malformed
+/// `RequestedCount` becomes `default_count`, and a malformed
+/// `StartingIndex` 0; neither fails the request.
+#[allow(clippy::collapsible_match)] // One arm per SOAP argument reads more clearly.
+pub(super) fn parse_browse_params(body: &str, default_count: u32) -> BrowseParams {
     use quick_xml::events::Event;
     use quick_xml::Reader;
 
@@ -154,8 +193,9 @@ pub(super) fn parse_browse_params(body: &str) -> BrowseParams {
     reader.config_mut().trim_text(true);
     let mut object_id = "0".to_string();
     let mut starting_index = 0_u32;
-    let mut requested_count = 0_u32;
+    let mut requested_count = None;
     let mut browse_flag = BrowseFlag::BrowseDirectChildren;
+    let mut sort_criteria = String::new();
     let mut buffer = Vec::new();
     let mut current_element = String::new();
 
@@ -178,39 +218,59 @@ pub(super) fn parse_browse_params(body: &str) -> BrowseParams {
                             browse_flag = BrowseFlag::BrowseMetadata;
                         }
                     }
+                    "SortCriteria" => sort_criteria = text.trim().to_string(),
                     "StartingIndex" => {
-                        starting_index = text.trim().parse().unwrap_or_else(|error| {
-                            warn!("Failed to parse StartingIndex '{}': {}", text, error);
+                        starting_index = parse_paging_number(&text).unwrap_or_else(|| {
+                            warn!(
+                                target: crate::logging::targets::BROWSE,
+                                "Ignoring StartingIndex '{}', starting at 0",
+                                text
+                            );
                             0
                         });
                     }
                     "RequestedCount" => {
-                        requested_count = text.trim().parse().unwrap_or_else(|error| {
-                            warn!("Failed to parse RequestedCount '{}': {}", text, error);
-                            0
-                        });
+                        requested_count = parse_paging_number(&text);
+                        if requested_count.is_none() {
+                            warn!(
+                                target: crate::logging::targets::BROWSE,
+                                "Ignoring RequestedCount '{}', using {}",
+                                text,
+                                default_count
+                            );
+                        }
                     }
                     _ => {}
                 }
             }
             Ok(Event::Eof) => break,
             Err(error) => {
-                warn!("Error parsing XML: {}, falling back to defaults", error);
+                warn!(
+                    target: crate::logging::targets::BROWSE,
+                    "Error parsing XML: {}, falling back to defaults",
+                    error
+                );
                 break;
             }
             _ => {}
         }
         buffer.clear();
     }
+    let requested_count = requested_count.unwrap_or(default_count);
 
     debug!(
+        target: crate::logging::targets::BROWSE,
         "Parsed BrowseParams: object_id={}, flag={:?}, start={}, count={}",
-        object_id, browse_flag, starting_index, requested_count
+        object_id,
+        browse_flag,
+        starting_index,
+        requested_count
     );
     BrowseParams {
         object_id,
         starting_index,
         requested_count,
         browse_flag,
+        sort_criteria,
     }
 }
@@ -1,6 +1,11 @@
 //! Media, radio, subtitle, and cover-art streaming handlers.
 
-use crate::{database::DatabaseManager, error::AppError, state::AppState};
+use crate::{
+    database::{DatabaseManager, FileLocation},
+    error::AppError,
+    state::AppState,
+    web::xml::parse_media_object_id,
+};
 use axum::{
     body::Body,
     extract::{Path, State},
@@ -10,9 +15,14 @@ use axum::{
 use std::{path::PathBuf, sync::atomic::Ordering, time::Instant};
 use tokio::io::{AsyncReadExt, AsyncSeekExt};
 use tokio_util::io::ReaderStream;
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 
-use super::diagnostics::WebHandlerMetrics;
+use super::{
+    artwork,
+    audit::StreamAuditSession,
+    conditional::{self, Validators},
+    diagnostics::WebHandlerMetrics,
+};
 
 async fn secure_indexed_path<D: DatabaseManager>(
     state: &AppState<D>,
@@ -34,15 +44,132 @@ async fn secure_indexed_path<D: DatabaseManager>(
     Err(AppError::NotFound)
 }
 
+/// Bounds `media.stream_chunk_kb` is clamped to.
+const STREAM_CHUNK_KB_MIN: u32 = 4;
+const STREAM_CHUNK_KB_MAX: u32 = 4096;
+
+/// Seconds renderers are asked to wait when `media.max_concurrent_streams`
+/// streams are already running.
+const STREAM_RETRY_AFTER_SECONDS: u64 = 10;
+
+/// A response body read from `reader` `chunk_size` bytes at a time. Only
+/// the chunk being sent is held in memory, whatever the file size.
+fn chunked_body(reader: impl tokio::io::AsyncRead + Send + 'static, chunk_size: usize) -> Body {
+    Body::from_stream(ReaderStream::with_capacity(reader, chunk_size))
+}
+
 async fn open_read_only_no_follow(path: &std::path::Path) -> std::io::Result<tokio::fs::File> {
     let mut options = tokio::fs::OpenOptions::new();
     options.read(true).write(false);
     #[cfg(unix)]
     options.custom_flags(libc::O_NOFOLLOW);
-    options.open(path).await
+    options
+        .open(crate::platform::filesystem::extended_length_path(path))
+        .await
+}
+
+/// What a `/media/{id}` response reads from.
+enum MediaSource {
+    File(tokio::fs::File),
+    /// The main title of a `VIDEO_TS` folder, read part after part.
+    Dvd(crate::dvd::DvdTitle),
+    /// A file of a remote root, proxied with range requests.
+    Remote(crate::remote::RemoteMedia),
 }
 
-fn content_disposition(filename: &str) -> String {
+impl MediaSource {
+    async fn open(path: &std::path::Path) -> Result<Self, AppError> {
+        if crate::dvd::is_video_ts_dir(path) {
+            let title = crate::dvd::load_main_title(path)
+                .await
+                .map_err(AppError::Io)?
+                .ok_or(AppError::NotFound)?;
+            return Ok(Self::Dvd(title));
+        }
+        // Enforce read-only access to media files
+        let file = open_read_only_no_follow(path).await.map_err(AppError::Io)?;
+        Ok(Self::File(file))
+    }
+
+    /// A file of a configured remote root. Other URLs are not found, so an
+    /// indexed location alone never makes the server fetch anything.
+    async fn open_remote<D: DatabaseManager>(
+        state: &AppState<D>,
+        location: &str,
+    ) -> Result<Self, AppError> {
+        let owned = crate::remote::owning_root(
+            &state.current_config(),
+            &state.media_directories.read().await,
+            location,
+        )
+        .is_some();
+        if !owned {
+            state.web_metrics.record_error();
+            return Err(AppError::NotFound);
+        }
+        let media = crate::remote::RemoteMedia::open(location)
+            .await
+            .map_err(AppError::Io)?;
+        Ok(Self::Remote(media))
+    }
+
+    /// Size on disk now, not the indexed size, so a stale database value
+    /// cannot cause range mismatches.
+    async fn size(&self) -> std::io::Result<u64> {
+        match self {
+            Self::File(file) => Ok(file.metadata().await?.len()),
+            Self::Dvd(title) => Ok(title.size()),
+            Self::Remote(media) => Ok(media.size()),
+        }
+    }
+
+    /// Modification time for cache validators. Remote files have none, so
+    /// they are always sent in full.
+    async fn modified(&self) -> Option<std::time::SystemTime> {
+        match self {
+            Self::File(file) => file.metadata().await.ok()?.modified().ok(),
+            Self::Dvd(title) => Some(title.modified),
+            Self::Remote(_) => None,
+        }
+    }
+
+    /// `len` bytes starting at `start`. A DVD range may span several parts.
+    async fn read_range(
+        self,
+        start: u64,
+        len: u64,
+    ) -> std::io::Result<Box<dyn tokio::io::AsyncRead + Send + Unpin>> {
+        match self {
+            Self::File(mut file) => {
+                file.seek(std::io::SeekFrom::Start(start)).await?;
+                Ok(Box::new(file.take(len)))
+            }
+            Self::Dvd(title) => {
+                let Some((first, offset)) = title.locate(start).filter(|_| len > 0) else {
+                    return Ok(Box::new(tokio::io::empty()));
+                };
+                let mut reader: Box<dyn tokio::io::AsyncRead + Send + Unpin> =
+                    Box::new(tokio::io::empty());
+                let mut covered = 0;
+                for (index, (path, size)) in title.segments.iter().enumerate().skip(first) {
+                    let mut part = open_read_only_no_follow(path).await?;
+                    let skip = if index == first { offset } else { 0 };
+                    part.seek(std::io::SeekFrom::Start(skip)).await?;
+                    reader = Box::new(reader.chain(part.take(size - skip)));
+                    covered += size - skip;
+                    if covered >= len {
+                        break;
+                    }
+                }
+                Ok(Box::new(reader.take(len)))
+            }
+            Self::Remote(media) => media.read_range(start, len).await,
+        }
+    }
+}
+
+/// `disposition` is `inline` or `attachment`; non-ASCII names travel in `filename*`.
+pub(super) fn content_disposition(disposition: &str, filename: &str) -> String {
     let mut fallback = String::with_capacity(filename.len().min(255));
     for character in filename.chars() {
         if character.is_ascii_alphanumeric() || matches!(character, ' ' | '.' | '_' | '-') {
@@ -59,12 +186,16 @@ fn content_disposition(filename: &str) -> String {
     };
     let encoded =
         percent_encoding::utf8_percent_encode(filename, percent_encoding::NON_ALPHANUMERIC);
-    format!("inline; filename=\"{fallback}\"; filename*=UTF-8''{encoded}")
+    format!("{disposition}; filename=\"{fallback}\"; filename*=UTF-8''{encoded}")
 }
 
 struct MetricsTrackingReader<R> {
     inner: R,
     metrics: std::sync::Arc<WebHandlerMetrics>,
+    audit: Option<StreamAuditSession>,
+    /// Counts the stream against `media.max_concurrent_streams` until the
+    /// body is dropped.
+    _session: Option<crate::runtime_state::StreamSession>,
 }
 
 impl<R: tokio::io::AsyncRead + Unpin> tokio::io::AsyncRead for MetricsTrackingReader<R> {
@@ -82,6 +213,9 @@ impl<R: tokio::io::AsyncRead + Unpin> tokio::io::AsyncRead for MetricsTrackingRe
                     self.metrics
                         .bytes_transferred
                         .fetch_add(bytes_read as u64, Ordering::Relaxed);
+                    if let Some(audit) = self.audit.as_mut() {
+                        audit.add_bytes(bytes_read as u64);
+                    }
                 }
                 std::task::Poll::Ready(Ok(()))
             }
@@ -90,6 +224,34 @@ impl<R: tokio::io::AsyncRead + Unpin> tokio::io::AsyncRead for MetricsTrackingRe
     }
 }
 
+/// Resolve the `{id}` of a `/media/{id}` route. ContentDirectory items use the
+/// path-derived [`crate::web::xml::media_object_id`]; the web UI, casting and MCP still link by
+/// numeric row id, so both forms are accepted.
+async fn resolve_media_location<D: DatabaseManager>(
+    state: &AppState<D>,
+    id: &str,
+) -> Result<FileLocation, AppError> {
+    let lookup = if let Some(key) = parse_media_object_id(id) {
+        state.database.get_file_location_by_object_key(key).await
+    } else if let Ok(file_id) = id.parse::<i64>() {
+        state.database.get_file_location_by_id(file_id).await
+    } else {
+        state.web_metrics.record_error();
+        return Err(AppError::NotFound);
+    };
+    lookup
+        .map_err(|e| {
+            error!("Database error resolving media ID {}: {}", id, e);
+            state.web_metrics.record_error();
+            AppError::NotFound
+        })?
+        .ok_or_else(|| {
+            debug!("Media ID {} not found", id);
+            state.web_metrics.record_error();
+            AppError::NotFound
+        })
+}
+
 pub async fn serve_media<D: DatabaseManager>(
     State(state): State<AppState<D>>,
     axum::extract::ConnectInfo(client_addr): axum::extract::ConnectInfo<std::net::SocketAddr>,
@@ -99,26 +261,7 @@ pub async fn serve_media<D: DatabaseManager>(
 ) -> Result<Response, AppError> {
     let start_time = Instant::now();
 
-    let file_id = id.parse::<i64>().map_err(|_| {
-        state.web_metrics.record_error();
-        AppError::NotFound
-    })?;
-
-    // Use ReDB database with atomic cache lookup
-    let file_info = state
-        .database
-        .get_file_location_by_id(file_id)
-        .await
-        .map_err(|e| {
-            error!("ReDB database error getting file by ID {}: {}", file_id, e);
-            state.web_metrics.record_error();
-            AppError::NotFound
-        })?
-        .ok_or_else(|| {
-            debug!("ReDB database: file ID {} not found", file_id);
-            state.web_metrics.record_error();
-            AppError::NotFound
-        })?;
+    let file_info = resolve_media_location(&state, &id).await?;
 
     if file_info.mime_type == "audio/radio" {
         return Ok(
@@ -126,7 +269,55 @@ pub async fn serve_media<D: DatabaseManager>(
                 .into_response(),
         );
     }
-    let media_path = secure_indexed_path(&state, &file_info.path).await?;
+    // Files in a merge group stream from the preferred copy, falling back to
+    // the other members when it cannot be opened.
+    let directories = state.media_directories.read().await.clone();
+    let copies = crate::overlay::copies(&directories, &file_info.path).await;
+    let (_, (media_path, source)) = crate::overlay::open_first(copies, |candidate| {
+        let state = &state;
+        async move {
+            let location = candidate.to_string_lossy();
+            if crate::remote::is_remote_root(&location) {
+                let source = MediaSource::open_remote(state, &location).await?;
+                return Ok((candidate.clone(), source));
+            }
+            let path = secure_indexed_path(state, &candidate).await?;
+            let source = MediaSource::open(&path).await?;
+            Ok::<_, AppError>((path, source))
+        }
+    })
+    .await?;
+
+    let file_size = source.size().await.map_err(AppError::Io)?;
+    let validators = source
+        .modified()
+        .await
+        .map(|modified| Validators::for_file(&media_path, file_size, Some(modified), 0));
+    if let Some(validators) = validators.as_ref() {
+        if validators.not_modified(&headers) {
+            return Ok(validators.not_modified_response());
+        }
+    }
+
+    // HEAD requests send no body, so only GETs count as streams.
+    let session = if method == Method::GET {
+        let limit = state.current_config().media.max_concurrent_streams;
+        let Some(session) = state.active_streams.try_start(client_addr.ip(), &id, limit) else {
+            warn!(
+                client = %client_addr.ip(),
+                limit,
+                "Refusing media stream: media.max_concurrent_streams reached; raise it if this is too low"
+            );
+            return Ok((
+                StatusCode::SERVICE_UNAVAILABLE,
+                [(header::RETRY_AFTER, STREAM_RETRY_AFTER_SECONDS.to_string())],
+            )
+                .into_response());
+        };
+        Some(session)
+    } else {
+        None
+    };
 
     // Record dynamic client telemetry for GET requests (playing)
     if method == Method::GET {
@@ -164,15 +355,6 @@ pub async fn serve_media<D: DatabaseManager>(
         }
     }
 
-    // Enforce read-only access to media files
-    let mut file = open_read_only_no_follow(&media_path)
-        .await
-        .map_err(AppError::Io)?;
-
-    // Use actual file size from disk to avoid stale DB values causing range mismatches
-    let metadata = file.metadata().await.map_err(AppError::Io)?;
-    let file_size = metadata.len();
-
     let client = crate::web::client::detect_client(&headers);
 
     let mime_override = match client {
@@ -196,13 +378,19 @@ pub async fn serve_media<D: DatabaseManager>(
         crate::web::client::DlnaClientProfile::Xbox if file_info.mime_type == "video/x-msvideo" => {
             "video/avi".to_string()
         }
-        _ => file_info.mime_type.clone(),
+        _ => crate::dvd::served_mime_type(
+            &file_info.mime_type,
+            &state.current_config().media.iso_mime_type,
+        )
+        .to_string(),
     };
 
-    let content_disposition = content_disposition(&file_info.filename);
+    let content_disposition = content_disposition("inline", &file_info.filename);
 
+    // Streams revalidate against their ETag rather than being cached.
     let mut response_builder = Response::builder()
         .header(header::CONTENT_TYPE, &mime_override)
+        .header(header::CACHE_CONTROL, "no-cache")
         .header(header::ACCEPT_RANGES, "bytes")
         .header(header::CONTENT_DISPOSITION, &content_disposition)
         .header("transferMode.dlna.org", "Streaming")
@@ -210,6 +398,11 @@ pub async fn serve_media<D: DatabaseManager>(
             "contentFeatures.dlna.org",
             "DLNA.ORG_OP=11;DLNA.ORG_CI=0;DLNA.ORG_FLAGS=01700000000000000000000000000000",
         );
+    if let (Some(validators), Some(response_headers)) =
+        (validators.as_ref(), response_builder.headers_mut())
+    {
+        validators.insert_into(response_headers);
+    }
 
     // CaptionInfo.sec injection for Samsung TVs when subtitles exist
     if let Some(caption_req) = headers
@@ -219,8 +412,8 @@ pub async fn serve_media<D: DatabaseManager>(
         if caption_req == "1" && file_info.subtitle_available {
             let srt_url = format!(
                 "{}/media/{}/subtitle",
-                state.advertised_http_origin(),
-                file_id,
+                crate::web::forwarded::resource_origin(&state),
+                id
             );
             debug!(
                 "Injecting Samsung subtitle header CaptionInfo.sec: {}",
@@ -264,7 +457,7 @@ pub async fn serve_media<D: DatabaseManager>(
     if method == Method::HEAD {
         debug!(
             "HEAD request for media file ID {} (size: {})",
-            file_id, file_size
+            id, file_size
         );
         let response_time = start_time.elapsed().as_micros() as u64;
         state.web_metrics.record_file_serve(response_time, false);
@@ -273,13 +466,32 @@ pub async fn serve_media<D: DatabaseManager>(
             .body(Body::empty())?);
     }
 
-    file.seek(std::io::SeekFrom::Start(start)).await?;
+    let reader = source.read_range(start, len).await?;
+    let audit = state.stream_audit.clone().map(|log| {
+        StreamAuditSession::new(
+            log,
+            client_addr.ip().to_string(),
+            headers
+                .get(header::USER_AGENT)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_owned),
+            &media_path,
+            (start, end),
+            len,
+        )
+    });
     let tracking_reader = MetricsTrackingReader {
-        inner: file.take(len),
+        inner: reader,
         metrics: state.web_metrics.clone(),
+        audit,
+        _session: session,
     };
-    let stream = ReaderStream::with_capacity(tracking_reader, 64 * 1024);
-    let body = Body::from_stream(stream);
+    let chunk_kb = state
+        .current_config()
+        .media
+        .stream_chunk_kb
+        .clamp(STREAM_CHUNK_KB_MIN, STREAM_CHUNK_KB_MAX);
+    let body = chunked_body(tracking_reader, chunk_kb as usize * 1024);
 
     // Record atomic performance metrics for file serving
     let response_time = start_time.elapsed().as_micros() as u64;
@@ -290,7 +502,7 @@ pub async fn serve_media<D: DatabaseManager>(
 
     debug!(
         "Served media file ID {} ({} bytes from offset {}) in {}ms",
-        file_id, len, start, response_time
+        id, len, start, response_time
     );
 
     Ok(response_builder.status(response_status).body(body)?)
@@ -354,25 +566,9 @@ fn parse_range_header(range_str: &str, file_size: u64) -> Result<(u64, u64), App
 pub async fn serve_subtitle<D: DatabaseManager>(
     State(state): State<AppState<D>>,
     Path(id): Path<String>,
+    headers: HeaderMap,
 ) -> Result<Response, AppError> {
-    let file_id = id.parse::<i64>().map_err(|_| {
-        state.web_metrics.record_error();
-        AppError::NotFound
-    })?;
-
-    let file_info = state
-        .database
-        .get_file_location_by_id(file_id)
-        .await
-        .map_err(|e| {
-            error!("Error getting file by ID for subtitle {}: {}", file_id, e);
-            state.web_metrics.record_error();
-            AppError::NotFound
-        })?
-        .ok_or_else(|| {
-            state.web_metrics.record_error();
-            AppError::NotFound
-        })?;
+    let file_info = resolve_media_location(&state, &id).await?;
 
     let media_path = secure_indexed_path(&state, &file_info.path).await?;
     let srt_path = secure_indexed_path(&state, &media_path.with_extension("srt")).await?;
@@ -380,43 +576,48 @@ pub async fn serve_subtitle<D: DatabaseManager>(
     let file = open_read_only_no_follow(&srt_path)
         .await
         .map_err(AppError::Io)?;
+    let metadata = file.metadata().await.map_err(AppError::Io)?;
+    let validators = Validators::for_file(&srt_path, metadata.len(), metadata.modified().ok(), 0);
+    if validators.not_modified(&headers) {
+        return Ok(validators.not_modified_response());
+    }
 
     let stream = tokio_util::io::ReaderStream::new(file);
     let body = Body::from_stream(stream);
 
-    Response::builder()
+    let mut response = Response::builder()
         .header(header::CONTENT_TYPE, "text/srt")
         .body(body)
-        .map_err(|_| AppError::NotFound)
+        .map_err(|_| AppError::NotFound)?;
+    validators.insert_into(response.headers_mut());
+    Ok(response)
 }
 
 pub async fn serve_cover<D: DatabaseManager>(
     State(state): State<AppState<D>>,
     Path(id): Path<String>,
+    headers: HeaderMap,
 ) -> Result<Response, AppError> {
-    let file_id = id.parse::<i64>().map_err(|_| {
-        state.web_metrics.record_error();
-        AppError::NotFound
-    })?;
+    let max_age = state.current_config().media.art_cache_max_age_seconds;
+    let mut response = find_cover(state, id, headers).await?;
+    response
+        .headers_mut()
+        .insert(header::CACHE_CONTROL, conditional::cache_control(max_age));
+    Ok(response)
+}
 
-    let file_info = state
-        .database
-        .get_file_location_by_id(file_id)
-        .await
-        .map_err(|e| {
-            error!("Error getting file by ID for cover {}: {}", file_id, e);
-            state.web_metrics.record_error();
-            AppError::NotFound
-        })?
-        .ok_or_else(|| {
-            state.web_metrics.record_error();
-            AppError::NotFound
-        })?;
+async fn find_cover<D: DatabaseManager>(
+    state: AppState<D>,
+    id: String,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let file_info = resolve_media_location(&state, &id).await?;
 
     if !file_info.mime_type.starts_with("audio/") {
         return Err(AppError::NotFound);
     }
     let media_path = secure_indexed_path(&state, &file_info.path).await?;
+    let max_dimension = state.current_config().media.art_max_dimension;
 
     // 1. Primary: Search parent directory for cover images (fast)
     if let Some(parent) = media_path.parent() {
@@ -443,14 +644,59 @@ pub async fn serve_cover<D: DatabaseManager>(
                             let Ok(img_path) = secure_indexed_path(&state, &img_path).await else {
                                 continue;
                             };
+                            let content_type =
+                                crate::platform::filesystem::get_mime_type_for_extension(ext);
+                            let modified = metadata.modified().ok();
+                            let validators = Validators::for_file(
+                                &img_path,
+                                size,
+                                modified,
+                                u64::from(max_dimension),
+                            );
+                            if validators.not_modified(&headers) {
+                                return Ok(validators.not_modified_response());
+                            }
+                            if max_dimension > 0 {
+                                if let Some(scaled) =
+                                    artwork::cached(&img_path, modified, max_dimension)
+                                {
+                                    return cover_response(
+                                        scaled.content_type,
+                                        scaled.data,
+                                        &validators,
+                                    );
+                                }
+                                let Ok(mut file) = open_read_only_no_follow(&img_path).await else {
+                                    continue;
+                                };
+                                let mut data = Vec::with_capacity(size as usize);
+                                if file.read_to_end(&mut data).await.is_err() {
+                                    continue;
+                                }
+                                let data = axum::body::Bytes::from(data);
+                                return match artwork::scale(
+                                    &img_path,
+                                    modified,
+                                    data.clone(),
+                                    max_dimension,
+                                )
+                                .await
+                                {
+                                    Some(scaled) => cover_response(
+                                        scaled.content_type,
+                                        scaled.data,
+                                        &validators,
+                                    ),
+                                    None => cover_response(&content_type, data, &validators),
+                                };
+                            }
                             if let Ok(file) = open_read_only_no_follow(&img_path).await {
-                                let content_type =
-                                    crate::platform::filesystem::get_mime_type_for_extension(ext);
                                 let stream = tokio_util::io::ReaderStream::new(file);
-                                return Response::builder()
-                                    .header(header::CONTENT_TYPE, content_type)
-                                    .body(Body::from_stream(stream))
-                                    .map_err(|_| AppError::NotFound);
+                                return cover_response(
+                                    &content_type,
+                                    Body::from_stream(stream),
+                                    &validators,
+                                );
                             }
                         }
                     }
@@ -460,7 +706,25 @@ pub async fn serve_cover<D: DatabaseManager>(
     }
 
     // 2. Secondary: Extract embedded artwork from audio tags using audiotags (blocking task)
-    let path = media_path;
+    let metadata = tokio::fs::metadata(&media_path)
+        .await
+        .map_err(AppError::Io)?;
+    let modified = metadata.modified().ok();
+    let validators = Validators::for_file(
+        &media_path,
+        metadata.len(),
+        modified,
+        u64::from(max_dimension),
+    );
+    if validators.not_modified(&headers) {
+        return Ok(validators.not_modified_response());
+    }
+    if max_dimension > 0 {
+        if let Some(scaled) = artwork::cached(&media_path, modified, max_dimension) {
+            return cover_response(scaled.content_type, scaled.data, &validators);
+        }
+    }
+    let path = media_path.clone();
     let tag_result =
         tokio::task::spawn_blocking(move || audiotags::Tag::new().read_from_path(&path)).await;
 
@@ -471,23 +735,41 @@ pub async fn serve_cover<D: DatabaseManager>(
                 audiotags::MimeType::Png => "image/png",
                 _ => "image/jpeg",
             };
-            return Response::builder()
-                .header(header::CONTENT_TYPE, content_type)
-                .body(Body::from(cover.data.to_vec()))
-                .map_err(|_| AppError::NotFound);
+            let data = axum::body::Bytes::copy_from_slice(cover.data);
+            if max_dimension > 0 {
+                if let Some(scaled) =
+                    artwork::scale(&media_path, modified, data.clone(), max_dimension).await
+                {
+                    return cover_response(scaled.content_type, scaled.data, &validators);
+                }
+            }
+            return cover_response(content_type, data, &validators);
         }
     }
 
     Err(AppError::NotFound)
 }
 
+fn cover_response(
+    content_type: &str,
+    data: impl Into<Body>,
+    validators: &Validators,
+) -> Result<Response, AppError> {
+    let mut response = Response::builder()
+        .header(header::CONTENT_TYPE, content_type)
+        .body(data.into())
+        .map_err(|_| AppError::NotFound)?;
+    validators.insert_into(response.headers_mut());
+    Ok(response)
+}
+
 #[cfg(test)]
 mod range_tests {
     use super::*;
 
     #[test]
     fn content_disposition_has_safe_ascii_and_utf8_names() {
-        let value = content_disposition("résumé\"\r\n.mkv");
+        let value = content_disposition("inline", "résumé\"\r\n.mkv");
         assert!(value.starts_with("inline; filename=\"r_sum__.mkv\""));
         assert!(value.contains("filename*=UTF-8''r%C3%A9sum%C3%A9%22%0D%0A%2Emkv"));
         assert!(!value.split("filename*=",).next().unwrap().contains('\r'));
@@ -524,6 +806,70 @@ mod range_tests {
         ));
     }
 
+    #[tokio::test]
+    async fn dvd_ranges_span_the_title_parts_in_order() {
+        let temp = tempfile::tempdir().unwrap();
+        let video_ts = temp.path().join("Movie").join("VIDEO_TS");
+        std::fs::create_dir_all(&video_ts).unwrap();
+        std::fs::write(video_ts.join("VTS_01_0.VOB"), b"menu").unwrap();
+        std::fs::write(video_ts.join("VTS_01_1.VOB"), b"abcd").unwrap();
+        std::fs::write(video_ts.join("VTS_01_2.VOB"), b"").unwrap();
+        std::fs::write(video_ts.join("VTS_01_3.VOB"), b"efg").unwrap();
+        std::fs::write(video_ts.join("VTS_01_4.VOB"), b"hi").unwrap();
+
+        async fn read(video_ts: &std::path::Path, range: Option<&str>) -> (u64, String) {
+            let source = MediaSource::open(video_ts).await.unwrap();
+            let size = source.size().await.unwrap();
+            let (start, end) = match range {
+                Some(range) => parse_range_header(range, size).unwrap(),
+                None => (0, size - 1),
+            };
+            let mut reader = source.read_range(start, end - start + 1).await.unwrap();
+            let mut body = String::new();
+            reader.read_to_string(&mut body).await.unwrap();
+            (size, body)
+        }
+
+        assert_eq!(read(&video_ts, None).await, (9, "abcdefghi".to_owned()));
+        for (range, expected) in [
+            ("bytes=0-0", "a"),
+            ("bytes=3-4", "de"),
+            ("bytes=2-7", "cdefgh"),
+            ("bytes=4-6", "efg"),
+            ("bytes=7-", "hi"),
+            ("bytes=-4", "fghi"),
+            ("bytes=1-99", "bcdefghi"),
+        ] {
+            assert_eq!(read(&video_ts, Some(range)).await.1, expected, "{range}");
+        }
+    }
+
+    #[tokio::test]
+    async fn large_ranges_stream_in_chunks_without_reading_the_file_whole() {
+        use futures_util::StreamExt;
+
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("movie.mkv");
+        // Sparse, so the test writes nothing to disk.
+        let size = 256 * 1024 * 1024;
+        std::fs::File::create(&path).unwrap().set_len(size).unwrap();
+        let chunk_size = 256 * 1024;
+
+        for (start, len) in [(0, size), (size - 5 * 1024 * 1024 - 3, 5 * 1024 * 1024)] {
+            let source = MediaSource::open(&path).await.unwrap();
+            let reader = source.read_range(start, len).await.unwrap();
+            let mut frames = chunked_body(reader, chunk_size).into_data_stream();
+            let (mut streamed, mut largest) = (0u64, 0usize);
+            while let Some(frame) = frames.next().await {
+                let frame = frame.unwrap();
+                largest = largest.max(frame.len());
+                streamed += frame.len() as u64;
+            }
+            assert_eq!(streamed, len);
+            assert!(largest <= chunk_size, "{largest}");
+        }
+    }
+
     #[cfg(unix)]
     #[tokio::test]
     async fn no_follow_open_rejects_a_last_moment_symlink() {
@@ -2,13 +2,22 @@
 
 use crate::web::format::format_bytes;
 use crate::{
-    database::{DatabaseManager, DatabaseReadSession, MediaFileQuery, MediaFileView},
+    database::{
+        duplicates::duplicate_groups,
+        playlist_formats::{
+            PlaylistEncoding, PlaylistExportOptions, PlaylistFileManager, PlaylistFormat,
+            PlaylistPathMode,
+        },
+        smart_playlists::{self, SmartPlaylist},
+        DatabaseManager, DatabaseReadSession, MediaFileQuery, MediaFileView,
+    },
     error::AppError,
+    scan_progress::{ScanState, ScanStatus},
     state::AppState,
 };
 use axum::{
     body::Bytes,
-    extract::{Query, State},
+    extract::{Path, Query, State},
     http::{header, StatusCode},
     response::{IntoResponse, Response},
     Json,
@@ -17,11 +26,17 @@ use std::io::Write as _;
 
 const DASHBOARD_TEMPLATE: &str = include_str!("ui/dashboard.html");
 
-pub async fn root_handler() -> impl IntoResponse {
-    (
+pub async fn root_handler<D: DatabaseManager>(
+    State(state): State<AppState<D>>,
+) -> Result<Response, AppError> {
+    if !state.current_config().server.web_dashboard {
+        return Err(AppError::NotFound);
+    }
+    Ok((
         [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
         DASHBOARD_TEMPLATE,
     )
+        .into_response())
 }
 
 #[derive(serde::Serialize)]
@@ -46,6 +61,49 @@ pub async fn server_info_handler<D: DatabaseManager>(
     })
 }
 
+#[derive(serde::Serialize)]
+pub struct ScanStatusResponse {
+    running: bool,
+    cancel_requested: bool,
+    #[serde(flatten)]
+    state: Option<ScanState>,
+}
+
+/// Progress of the current or most recent startup media scan.
+pub async fn scan_status_handler<D: DatabaseManager>(
+    State(state): State<AppState<D>>,
+) -> Result<Json<ScanStatusResponse>, AppError> {
+    let running = state.scan_control.is_running();
+    let mut scan = ScanState::load(state.database.as_ref()).await?;
+    if let Some(scan) = scan.as_mut() {
+        if scan.status == ScanStatus::Running && !running {
+            scan.status = ScanStatus::Interrupted;
+        }
+    }
+    Ok(Json(ScanStatusResponse {
+        running,
+        cancel_requested: running && state.scan_control.is_cancel_requested(),
+        state: scan,
+    }))
+}
+
+/// Ask the running media scan to stop at its next batch boundary.
+pub async fn scan_cancel_handler<D: DatabaseManager>(
+    State(state): State<AppState<D>>,
+) -> impl IntoResponse {
+    if state.scan_control.request_cancel() {
+        (
+            StatusCode::ACCEPTED,
+            Json(serde_json::json!({ "status": "cancelling" })),
+        )
+    } else {
+        (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({ "error": "No media scan is running" })),
+        )
+    }
+}
+
 #[derive(serde::Deserialize)]
 pub struct MediaPageQuery {
     cursor: Option<String>,
@@ -80,6 +138,7 @@ pub async fn media_page_handler<D: DatabaseManager + 'static>(
         text,
     };
     let fetch_limit = limit + 1;
+    let hidden = crate::web::soap::hidden_files(&state).await;
     let response = state
         .database
         .clone()
@@ -92,6 +151,11 @@ pub async fn media_page_handler<D: DatabaseManager + 'static>(
                 if emitted >= limit {
                     return Ok(());
                 }
+                if file.id().is_some_and(|id| hidden.contains(&id)) {
+                    // Skipped copies still advance the cursor.
+                    last_id = file.id();
+                    return Ok(());
+                }
                 if emitted > 0 {
                     output.push(b',');
                 }
@@ -119,6 +183,169 @@ pub async fn media_page_handler<D: DatabaseManager + 'static>(
         .into_response())
 }
 
+/// Groups of indexed files with the same content hash, listed copy first, so
+/// redundant copies can be cleaned up. Works whether or not
+/// `media.hide_duplicates` is on, but only files hashed while it was on
+/// are compared.
+pub async fn duplicates_handler<D: DatabaseManager + 'static>(
+    State(state): State<AppState<D>>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let directories = state.media_directories.read().await.clone();
+    let groups = state
+        .database
+        .clone()
+        .read(move |session| duplicate_groups(session, &directories))
+        .await
+        .map_err(AppError::Internal)?;
+    Ok(Json(serde_json::json!({ "groups": groups })))
+}
+
+#[derive(serde::Deserialize)]
+pub struct PlaylistExportQuery {
+    format: Option<String>,
+    #[serde(default)]
+    paths: PlaylistPathMode,
+    /// Shorthand for `paths=relative`.
+    #[serde(default)]
+    relative: bool,
+    base: Option<String>,
+    #[serde(default)]
+    encoding: PlaylistEncoding,
+}
+
+/// Download a playlist as `.m3u`, `.m3u8`, `.pls` or `.xspf`. `paths=relative`
+/// (or `relative=true`) writes entries relative to the absolute `base`
+/// directory instead of library paths; `encoding=latin1` writes ISO-8859-1.
+pub async fn playlist_export_handler<D: DatabaseManager + 'static>(
+    State(state): State<AppState<D>>,
+    Path(playlist_id): Path<i64>,
+    Query(params): Query<PlaylistExportQuery>,
+) -> Result<Response, AppError> {
+    let format = match params.format.as_deref().unwrap_or("m3u8") {
+        "m3u" => PlaylistFormat::M3U,
+        "m3u8" => PlaylistFormat::M3U8,
+        "pls" => PlaylistFormat::PLS,
+        "xspf" => PlaylistFormat::XSPF,
+        _ => {
+            return Err(AppError::InvalidInput(
+                "Unknown playlist format".to_string(),
+            ))
+        }
+    };
+    let base_dir = params
+        .base
+        .filter(|value| !value.is_empty())
+        .map(std::path::PathBuf::from);
+    let path_mode = if params.relative {
+        PlaylistPathMode::Relative
+    } else {
+        params.paths
+    };
+    if path_mode == PlaylistPathMode::Relative
+        && !base_dir
+            .as_deref()
+            .is_some_and(std::path::Path::is_absolute)
+    {
+        return Err(AppError::InvalidInput(
+            "Relative playlist export needs an absolute base directory".to_string(),
+        ));
+    }
+    if params.encoding == PlaylistEncoding::Latin1 && format == PlaylistFormat::M3U8 {
+        return Err(AppError::InvalidInput(
+            "m3u8 playlists are always UTF-8; use format=m3u for Latin-1".to_string(),
+        ));
+    }
+    let playlist = state
+        .database
+        .get_playlist(playlist_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    let options = PlaylistExportOptions {
+        path_mode,
+        base_dir,
+        encoding: params.encoding,
+    };
+    let content =
+        PlaylistFileManager::render_playlist(&*state.database, playlist_id, format, &options)
+            .await?;
+    let mut content_type = match format {
+        PlaylistFormat::M3U => "audio/x-mpegurl",
+        PlaylistFormat::M3U8 => "audio/x-mpegurl; charset=utf-8",
+        PlaylistFormat::PLS => "audio/x-scpls",
+        PlaylistFormat::XSPF => "application/xspf+xml",
+    }
+    .to_string();
+    if options.encoding == PlaylistEncoding::Latin1 && format != PlaylistFormat::XSPF {
+        content_type.push_str("; charset=iso-8859-1");
+    }
+    let filename = PlaylistFileManager::get_output_filename(&playlist.name, format);
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, content_type),
+            (
+                header::CONTENT_DISPOSITION,
+                super::streaming::content_disposition("attachment", &filename),
+            ),
+        ],
+        content,
+    )
+        .into_response())
+}
+
+/// List smart playlist definitions.
+pub async fn smart_playlists_handler<D: DatabaseManager + 'static>(
+    State(state): State<AppState<D>>,
+) -> Result<Json<Vec<SmartPlaylist>>, AppError> {
+    Ok(Json(smart_playlists::list(state.database.as_ref()).await?))
+}
+
+/// Define a smart playlist. It appears in the ContentDirectory playlists
+/// container straight away.
+pub async fn create_smart_playlist_handler<D: DatabaseManager + 'static>(
+    State(state): State<AppState<D>>,
+    Json(playlist): Json<SmartPlaylist>,
+) -> Result<Response, AppError> {
+    playlist.validate().map_err(AppError::InvalidInput)?;
+    let created = smart_playlists::create(state.database.as_ref(), playlist).await?;
+    crate::web::eventing::publish_content_change(&state).await;
+    Ok((StatusCode::CREATED, Json(created)).into_response())
+}
+
+pub async fn smart_playlist_handler<D: DatabaseManager + 'static>(
+    State(state): State<AppState<D>>,
+    Path(id): Path<i64>,
+) -> Result<Json<SmartPlaylist>, AppError> {
+    smart_playlists::get(state.database.as_ref(), id)
+        .await?
+        .map(Json)
+        .ok_or(AppError::NotFound)
+}
+
+pub async fn update_smart_playlist_handler<D: DatabaseManager + 'static>(
+    State(state): State<AppState<D>>,
+    Path(id): Path<i64>,
+    Json(playlist): Json<SmartPlaylist>,
+) -> Result<Json<SmartPlaylist>, AppError> {
+    playlist.validate().map_err(AppError::InvalidInput)?;
+    let updated = smart_playlists::update(state.database.as_ref(), id, playlist)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    crate::web::eventing::publish_content_change(&state).await;
+    Ok(Json(updated))
+}
+
+pub async fn delete_smart_playlist_handler<D: DatabaseManager + 'static>(
+    State(state): State<AppState<D>>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, AppError> {
+    if !smart_playlists::delete(state.database.as_ref(), id).await? {
+        return Err(AppError::NotFound);
+    }
+    crate::web::eventing::publish_content_change(&state).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
 fn write_web_media_file(output: &mut Vec<u8>, file: &impl MediaFileView) -> anyhow::Result<()> {
     let mime_type = file.mime_type();
     let category = if mime_type == "audio/radio" {
@@ -126,7 +353,8 @@ fn write_web_media_file(output: &mut Vec<u8>, file: &impl MediaFileView) -> anyh
     } else {
         mime_type.split('/').next().unwrap_or("file")
     };
-    let extension = std::path::Path::new(file.path())
+    let path = crate::platform::filesystem::path_from_lossless_string(&file.path());
+    let extension = path
         .extension()
         .and_then(|value| value.to_str())
         .unwrap_or("")
@@ -136,7 +364,7 @@ fn write_web_media_file(output: &mut Vec<u8>, file: &impl MediaFileView) -> anyh
         "{{\"id\":{},\"path\":",
         file.id().unwrap_or_default()
     )?;
-    serde_json::to_writer(&mut *output, file.path())?;
+    serde_json::to_writer(&mut *output, &path.to_string_lossy())?;
     output.extend_from_slice(b",\"name\":");
     serde_json::to_writer(&mut *output, file.filename())?;
     output.extend_from_slice(b",\"title\":");
@@ -164,4 +392,13 @@ mod tests {
         assert!(!DASHBOARD_TEMPLATE.contains("__VUIO_"));
         assert!(DASHBOARD_TEMPLATE.contains("/api/server-info"));
     }
+
+    #[test]
+    fn dashboard_is_self_contained() {
+        assert!(DASHBOARD_TEMPLATE.contains("/api/stats"));
+        assert!(DASHBOARD_TEMPLATE.contains("/api/browse"));
+        for external in ["<script src", "<link rel=\"stylesheet\"", "@import"] {
+            assert!(!DASHBOARD_TEMPLATE.contains(external), "{external}");
+        }
+    }
 }
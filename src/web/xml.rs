@@ -1,9 +1,11 @@
 // src\web\xml.rs
 use crate::{
+    config::AppConfig,
     database::{
-        DatabaseManager, DatabaseReadSession, DirectoryView, MediaDirectory, MediaFile,
-        MediaFileQuery, MediaFileView,
+        media_object_key, DatabaseManager, DatabaseReadSession, DirectoryView, MediaDirectory,
+        MediaFile, MediaFileQuery, MediaFileView,
     },
+    platform::filesystem::{path_from_lossless_string, path_to_lossless_string},
     state::AppState,
 };
 use anyhow::Result;
@@ -99,6 +101,42 @@ fn get_upnp_class(mime_type: &str) -> &str {
     }
 }
 
+/// Get the UPnP class for a container. Stored and smart playlists, the
+/// direct children of `audio/playlists`, are playlist containers.
+fn get_container_class(container_id: &str) -> &'static str {
+    match container_id.strip_prefix("audio/playlists/") {
+        Some(playlist) if !playlist.is_empty() && !playlist.contains('/') => {
+            "object.container.playlistContainer"
+        }
+        _ => "object.container",
+    }
+}
+
+/// Whether `media.dlna_playlist_editing` lets control points change a
+/// container: the playlist list and each stored playlist. Smart playlists
+/// are computed from rules and stay restricted.
+pub fn is_editable_container(container_id: &str) -> bool {
+    match container_id.strip_prefix("audio/playlists") {
+        Some("") => true,
+        Some(rest) => rest
+            .strip_prefix('/')
+            .is_some_and(|id| !id.is_empty() && id.bytes().all(|byte| byte.is_ascii_digit())),
+        None => false,
+    }
+}
+
+/// Escaped DIDL-Lite `Result` describing a playlist created by `CreateObject`.
+pub fn created_playlist_result(playlist_id: i64, title: &str) -> String {
+    let mut result = String::new();
+    let mut didl = SoapResultWriter(&mut result);
+    let _ = write!(
+        didl,
+        r#"<DIDL-Lite xmlns="urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:upnp="urn:schemas-upnp-org:metadata-1-0/upnp/"><container id="audio/playlists/{playlist_id}" parentID="audio/playlists" restricted="0" childCount="0"><dc:title>{}</dc:title><upnp:class>object.container.playlistContainer</upnp:class></container></DIDL-Lite>"#,
+        xml_escape(title)
+    );
+    result
+}
+
 /// Format duration in seconds to HH:MM:SS format for DLNA
 fn format_duration(duration_seconds: u64) -> String {
     let hours = duration_seconds / 3600;
@@ -110,11 +148,182 @@ fn format_duration(duration_seconds: u64) -> String {
 #[derive(Clone)]
 pub struct BrowseRenderContext {
     pub client: crate::web::client::DlnaClientProfile,
-    pub server_ip: String,
-    pub server_port: u16,
+    /// `scheme://host[:port]` prefixed to resource links.
+    pub origin: String,
     pub autoplay_enabled: bool,
     pub update_id: u32,
-    pub bookmarks: HashMap<i64, u32>,
+    /// Resume positions in seconds by file ID, rendered as `sec:dcmInfo` on
+    /// video items. `None` unless the client is a Samsung TV and
+    /// `media.samsung_bookmarks` is on.
+    pub bookmarks: Option<HashMap<i64, u32>>,
+    pub bitrate_limit: Option<BitrateLimit>,
+    /// `media.iso_mime_type`, announced for `.iso` images.
+    pub iso_mime_type: String,
+    /// Order of the items in the page, from the Browse `SortCriteria`.
+    pub sort: crate::database::SortSpec,
+    /// Files left out of the listing: missing files and hidden duplicates.
+    pub hidden: std::sync::Arc<std::collections::HashSet<i64>>,
+    pub container_update_ids: std::sync::Arc<crate::runtime_state::ContainerUpdateIds>,
+    /// Titles and durations the browsed playlist gives its entries, by file
+    /// ID; used where the files have none of their own.
+    pub entry_info: std::sync::Arc<HashMap<i64, crate::database::PlaylistEntryInfo>>,
+}
+
+impl BrowseRenderContext {
+    /// `UpdateID` of container `object_id`, whose listing has `revision`.
+    fn container_update_id(&self, object_id: &str, revision: u64) -> u32 {
+        self.container_update_ids
+            .update_id(object_id, revision, self.update_id)
+    }
+}
+
+/// Bitrate ceiling declared for a renderer profile under `[[renderers]]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BitrateLimit {
+    pub max_bits_per_second: u64,
+    pub strict: bool,
+}
+
+impl BitrateLimit {
+    pub fn for_client(
+        config: &AppConfig,
+        client: crate::web::client::DlnaClientProfile,
+    ) -> Option<Self> {
+        let renderer = config
+            .renderers
+            .iter()
+            .find(|renderer| renderer.profile == client)?;
+        let mbps = renderer
+            .max_bitrate_mbps
+            .filter(|mbps| mbps.is_finite() && *mbps > 0.0)?;
+        Some(Self {
+            max_bits_per_second: (mbps * 1_000_000.0) as u64,
+            strict: renderer.strict_bitrate,
+        })
+    }
+}
+
+/// Whether items announced to `client` carry the autoplay DLNA flags:
+/// the renderer's `autoplay` override, else `media.autoplay_enabled`.
+pub fn autoplay_for_client(
+    config: &AppConfig,
+    client: crate::web::client::DlnaClientProfile,
+) -> bool {
+    config
+        .renderers
+        .iter()
+        .find(|renderer| renderer.profile == client)
+        .and_then(|renderer| renderer.autoplay)
+        .unwrap_or(config.media.autoplay_enabled)
+}
+
+/// `protocolInfo` fourth field of served media: byte seeking, no
+/// transcoding, streaming transfer mode, plus background/interactive
+/// transfer when autoplay is on.
+pub fn dlna_flags(autoplay: bool) -> &'static str {
+    if autoplay {
+        "DLNA.ORG_OP=11;DLNA.ORG_CI=0;DLNA.ORG_FLAGS=01700000000000000000000000000000"
+    } else {
+        "DLNA.ORG_OP=11;DLNA.ORG_CI=0;DLNA.ORG_FLAGS=00D00000000000000000000000000000"
+    }
+}
+
+/// MIME type announced to `client` for a stored `mime` type. Some renderers
+/// only play a container under the type their firmware expects.
+pub fn wire_mime_type<'a>(
+    client: crate::web::client::DlnaClientProfile,
+    mime: &'a str,
+    iso_mime_type: &'a str,
+) -> &'a str {
+    use crate::web::client::DlnaClientProfile;
+
+    match client {
+        _ if mime == "audio/radio" => "audio/mpeg",
+        DlnaClientProfile::SamsungTv | DlnaClientProfile::SamsungTvQ
+            if mime == "video/x-matroska" =>
+        {
+            "video/x-mkv"
+        }
+        DlnaClientProfile::SamsungTv | DlnaClientProfile::SamsungTvQ
+            if mime == "video/x-msvideo" =>
+        {
+            "video/mpeg"
+        }
+        DlnaClientProfile::SonyBdp if mime == "video/x-matroska" || mime == "video/mpeg" => {
+            "video/divx"
+        }
+        DlnaClientProfile::Xbox if mime == "video/x-msvideo" => "video/avi",
+        _ => crate::dvd::served_mime_type(mime, iso_mime_type),
+    }
+}
+
+/// ConnectionManager `Source` protocolInfo list for `client`: one entry per
+/// type the DIDL-Lite `<res>` elements can announce to it, with the same
+/// flags, so the two never disagree.
+pub fn source_protocol_info(
+    client: crate::web::client::DlnaClientProfile,
+    autoplay: bool,
+    iso_mime_type: &str,
+) -> String {
+    let flags = dlna_flags(autoplay);
+    let mut announced = Vec::new();
+    for &(_, mime) in crate::platform::filesystem::SUPPORTED_MEDIA_TYPES {
+        let wire = wire_mime_type(client, mime, iso_mime_type);
+        if !announced.contains(&wire) {
+            announced.push(wire);
+        }
+    }
+    let mut protocols = announced
+        .iter()
+        .map(|mime| format!("http-get:*:{mime}:{flags}"))
+        .collect::<Vec<_>>();
+    if client == crate::web::client::DlnaClientProfile::LgTv {
+        protocols.push("http-get:*:text/srt:*".to_string());
+    }
+    protocols.join(",")
+}
+
+/// One playable `<res>` candidate of an item.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ResVariant<'a> {
+    wire_mime: &'a str,
+    size: u64,
+    /// Bits per second, when known.
+    bitrate: Option<u64>,
+}
+
+/// Conservative average bitrate in bits per second derived from the file size.
+/// No probe data is stored for indexed files, so this is the only source.
+fn estimate_bitrate(size: u64, duration_seconds: u64) -> Option<u64> {
+    (duration_seconds > 0 && size > 0).then(|| size.saturating_mul(8) / duration_seconds)
+}
+
+/// Many renderers play the first `<res>` they understand, so the best variant
+/// within the renderer's limit is moved to the front: highest bitrate within
+/// the limit first, then variants of unknown bitrate, then over-limit variants
+/// from the lowest bitrate up. Strict limits drop over-limit variants instead,
+/// except the lowest one when nothing else is left, so an item never loses
+/// its last `<res>`. Without a limit the original order is kept.
+fn rank_res_variants(variants: &mut Vec<ResVariant<'_>>, limit: Option<BitrateLimit>) {
+    let Some(limit) = limit else {
+        return;
+    };
+    variants.sort_by_key(|variant| match variant.bitrate {
+        Some(bitrate) if bitrate <= limit.max_bits_per_second => (0, u64::MAX - bitrate),
+        None => (1, 0),
+        Some(bitrate) => (2, bitrate),
+    });
+    if limit.strict {
+        let within_limit = variants
+            .iter()
+            .filter(|variant| {
+                variant
+                    .bitrate
+                    .is_none_or(|bitrate| bitrate <= limit.max_bits_per_second)
+            })
+            .count();
+        variants.truncate(within_limit.max(1));
+    }
 }
 
 fn write_directory<W: std::fmt::Write, D: DirectoryView>(
@@ -133,7 +342,7 @@ fn write_directory<W: std::fmt::Write, D: DirectoryView>(
         || path == "image"
         || path == "radio"
     {
-        path.to_owned()
+        path.to_string()
     } else if path.starts_with('d') && path[1..].chars().all(|c| c.is_ascii_digit()) {
         format!("{}/{}", object_id.trim_end_matches('/'), path)
     } else {
@@ -141,10 +350,12 @@ fn write_directory<W: std::fmt::Write, D: DirectoryView>(
     };
     write!(
         output,
-        r#"<container id="{}" parentID="{}" restricted="1"><dc:title>{}</dc:title><upnp:class>object.container</upnp:class>"#,
+        r#"<container id="{}" parentID="{}" restricted="1"><dc:title>{}</dc:title><upnp:class>{}</upnp:class>"#,
         xml_escape(&container_id),
         xml_escape(object_id),
-        xml_escape(container.name())
+        // IDs keep raw filename bytes escaped; the title is for people
+        xml_escape(&path_from_lossless_string(container.name()).to_string_lossy()),
+        get_container_class(&container_id)
     )?;
     if matches!(
         client,
@@ -167,6 +378,22 @@ fn write_directory<W: std::fmt::Write, D: DirectoryView>(
     output.write_str("</container>")
 }
 
+/// ContentDirectory object ID of a media item: `m` and the 16-digit hex
+/// [`media_object_key`] of its stored path. The same ID addresses the item's
+/// `/media/{id}` resources, so it stays valid after the index is rebuilt.
+pub fn media_object_id(path: &str) -> String {
+    format!("m{:016x}", media_object_key(path))
+}
+
+/// Inverse of [`media_object_id`]; `None` for container and numeric IDs.
+pub fn parse_media_object_id(object_id: &str) -> Option<u64> {
+    let hex = object_id.strip_prefix('m')?;
+    if hex.len() != 16 || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    u64::from_str_radix(hex, 16).ok()
+}
+
 fn write_media_view<W: std::fmt::Write, V: MediaFileView>(
     output: &mut W,
     object_id: &str,
@@ -176,14 +403,19 @@ fn write_media_view<W: std::fmt::Write, V: MediaFileView>(
     let Some(file_id) = file.id().filter(|id| *id > 0) else {
         return Ok(());
     };
+    let item_id = media_object_id(&file.path());
     let mime = file.mime_type();
     let is_radio = mime == "audio/radio";
     let has_srt = file.subtitle_available();
-    let title = file.title().unwrap_or(file.filename());
+    let entry_info = context.entry_info.get(&file_id);
+    let title = file
+        .title()
+        .or_else(|| entry_info.and_then(|info| info.title.as_deref()))
+        .unwrap_or(file.filename());
     write!(
         output,
         r#"<item id="{}" parentID="{}" restricted="1"><dc:title>{}"#,
-        file_id,
+        item_id,
         xml_escape(object_id),
         xml_escape(title)
     )?;
@@ -220,49 +452,38 @@ fn write_media_view<W: std::fmt::Write, V: MediaFileView>(
         }
         write!(
             output,
-            "<upnp:albumArtURI>http://{}:{}/media/{}/cover</upnp:albumArtURI>",
-            context.server_ip, context.server_port, file_id
+            "<upnp:albumArtURI>{}/media/{}/cover</upnp:albumArtURI>",
+            context.origin, item_id
         )?;
     }
     write!(output, "<upnp:class>{}</upnp:class>", get_upnp_class(mime))?;
 
-    let flags = if context.autoplay_enabled {
-        "DLNA.ORG_OP=11;DLNA.ORG_CI=0;DLNA.ORG_FLAGS=01700000000000000000000000000000"
-    } else {
-        "DLNA.ORG_OP=11;DLNA.ORG_CI=0;DLNA.ORG_FLAGS=00D00000000000000000000000000000"
-    };
-    let wire_mime = if is_radio {
-        "audio/mpeg"
+    let flags = dlna_flags(context.autoplay_enabled);
+    let wire_mime = wire_mime_type(context.client, mime, &context.iso_mime_type);
+    let duration_secs = if !is_radio && (mime.starts_with("video/") || mime.starts_with("audio/")) {
+        file.duration_secs()
+            .or_else(|| {
+                entry_info
+                    .and_then(|info| info.duration)
+                    .map(|value| value.as_secs_f64())
+            })
+            .map(|value| value as u64)
     } else {
-        match context.client {
-            crate::web::client::DlnaClientProfile::SamsungTv
-            | crate::web::client::DlnaClientProfile::SamsungTvQ
-                if mime == "video/x-matroska" =>
-            {
-                "video/x-mkv"
-            }
-            crate::web::client::DlnaClientProfile::SamsungTv
-            | crate::web::client::DlnaClientProfile::SamsungTvQ
-                if mime == "video/x-msvideo" =>
-            {
-                "video/mpeg"
-            }
-            crate::web::client::DlnaClientProfile::SonyBdp
-                if mime == "video/x-matroska" || mime == "video/mpeg" =>
-            {
-                "video/divx"
-            }
-            crate::web::client::DlnaClientProfile::Xbox if mime == "video/x-msvideo" => "video/avi",
-            _ => mime,
-        }
+        None
     };
-    write!(
-        output,
-        r#"<res protocolInfo="http-get:*:{wire_mime}:{flags}" size="{}""#,
-        if is_radio { 0 } else { file.size() }
-    )?;
-    if !is_radio && (mime.starts_with("video/") || mime.starts_with("audio/")) {
-        if let Some(seconds) = file.duration_secs().map(|value| value as u64) {
+    let mut variants = vec![ResVariant {
+        wire_mime,
+        size: if is_radio { 0 } else { file.size() },
+        bitrate: duration_secs.and_then(|seconds| estimate_bitrate(file.size(), seconds)),
+    }];
+    rank_res_variants(&mut variants, context.bitrate_limit);
+    for variant in &variants {
+        write!(
+            output,
+            r#"<res protocolInfo="http-get:*:{}:{flags}" size="{}""#,
+            variant.wire_mime, variant.size
+        )?;
+        if let Some(seconds) = duration_secs {
             write!(
                 output,
                 r#" duration="{:02}:{:02}:{:02}""#,
@@ -271,29 +492,29 @@ fn write_media_view<W: std::fmt::Write, V: MediaFileView>(
                 seconds % 60
             )?;
         }
+        // DIDL-Lite expresses res@bitrate in bytes per second.
+        if let Some(bitrate) = variant.bitrate {
+            write!(output, r#" bitrate="{}""#, bitrate / 8)?;
+        }
+        if matches!(
+            context.client,
+            crate::web::client::DlnaClientProfile::LgTv
+                | crate::web::client::DlnaClientProfile::PanasonicTv
+        ) && has_srt
+        {
+            write!(
+                output,
+                r#" pv:subtitleFileUri="{}/media/{}/subtitle" pv:subtitleFileType="SRT""#,
+                context.origin, item_id
+            )?;
+        }
+        write!(output, ">{}/media/{}</res>", context.origin, item_id)?;
     }
-    if matches!(
-        context.client,
-        crate::web::client::DlnaClientProfile::LgTv
-            | crate::web::client::DlnaClientProfile::PanasonicTv
-    ) && has_srt
-    {
-        write!(
-            output,
-            r#" pv:subtitleFileUri="http://{}:{}/media/{}/subtitle" pv:subtitleFileType="SRT""#,
-            context.server_ip, context.server_port, file_id
-        )?;
-    }
-    write!(
-        output,
-        ">http://{}:{}/media/{}</res>",
-        context.server_ip, context.server_port, file_id
-    )?;
     if context.client == crate::web::client::DlnaClientProfile::LgTv && has_srt {
         write!(
             output,
-            r#"<res protocolInfo="http-get:*:text/srt:*">http://{}:{}/media/{}/subtitle</res>"#,
-            context.server_ip, context.server_port, file_id
+            r#"<res protocolInfo="http-get:*:text/srt:*">{}/media/{}/subtitle</res>"#,
+            context.origin, item_id
         )?;
     }
     if matches!(
@@ -304,16 +525,16 @@ fn write_media_view<W: std::fmt::Write, V: MediaFileView>(
     {
         write!(
             output,
-            r#"<sec:CaptionInfoEx sec:type="srt">http://{}:{}/media/{}/subtitle</sec:CaptionInfoEx>"#,
-            context.server_ip, context.server_port, file_id
+            r#"<sec:CaptionInfoEx sec:type="srt">{}/media/{}/subtitle</sec:CaptionInfoEx>"#,
+            context.origin, item_id
         )?;
     }
-    if matches!(
-        context.client,
-        crate::web::client::DlnaClientProfile::SamsungTv
-            | crate::web::client::DlnaClientProfile::SamsungTvQ
-    ) {
-        let mut bookmark = context.bookmarks.get(&file_id).copied().unwrap_or(0);
+    if let Some(bookmarks) = context
+        .bookmarks
+        .as_ref()
+        .filter(|_| file.mime_type().starts_with("video/"))
+    {
+        let mut bookmark = bookmarks.get(&file_id).copied().unwrap_or(0);
         if context.client == crate::web::client::DlnaClientProfile::SamsungTvQ {
             bookmark = bookmark.saturating_mul(1000);
         }
@@ -335,15 +556,12 @@ pub fn generate_indexed_browse_response<S: DatabaseReadSession>(
     requested_count: usize,
     context: BrowseRenderContext,
 ) -> Result<Bytes> {
-    let directory_count = session
-        .visit_direct_subdirectories(
-            canonical_parent,
-            (!mime_family.is_empty()).then_some(mime_family),
-            0,
-            0,
-            |_| Ok(()),
-        )?
-        .matched;
+    let count = session.count_directory_entries(
+        canonical_parent,
+        (!mime_family.is_empty()).then_some(mime_family),
+        &context.hidden,
+    )?;
+    let directory_count = count.directories;
     let directory_limit = requested_count.min(directory_count.saturating_sub(starting_index));
     let file_offset = starting_index.saturating_sub(directory_count);
     let file_limit = requested_count.saturating_sub(directory_limit);
@@ -368,14 +586,83 @@ pub fn generate_indexed_browse_response<S: DatabaseReadSession>(
         path: canonical_parent.to_owned(),
         mime_family: (!mime_family.is_empty()).then(|| mime_family.to_owned()),
     };
-    let summary = session.visit_files(&query, file_offset, file_limit, |file| {
-        write_media_view(&mut result, object_id, &file, &context)
-            .map_err(|_| anyhow::anyhow!("failed to construct browse XML"))
-    })?;
+    let summary = crate::database::sort::visit_sorted_files(
+        session,
+        &query,
+        &context.sort,
+        &context.hidden,
+        file_offset,
+        file_limit,
+        |file| {
+            write_media_view(&mut result, object_id, &file, &context)
+                .map_err(|_| anyhow::anyhow!("failed to construct browse XML"))
+        },
+    )?;
     result.push_str("</DIDL-Lite>");
     let returned = directory_summary.visited + summary.visited;
-    let total = directory_count + summary.matched;
-    write!(&mut response, "</Result><NumberReturned>{returned}</NumberReturned><TotalMatches>{total}</TotalMatches><UpdateID>{}</UpdateID></u:BrowseResponse></s:Body></s:Envelope>", context.update_id)?;
+    let total = count.total();
+    let update_id = context.container_update_id(object_id, count.revision);
+    write!(&mut response, "</Result><NumberReturned>{returned}</NumberReturned><TotalMatches>{total}</TotalMatches><UpdateID>{update_id}</UpdateID></u:BrowseResponse></s:Body></s:Envelope>")?;
+    Ok(response.into_bytes())
+}
+
+/// Browse one folder of a merge group: the same relative folder in every
+/// member root, overlaid by [`crate::overlay::overlay_directory`].
+pub fn generate_overlay_browse_response<S: DatabaseReadSession>(
+    session: &mut S,
+    canonical_parents: &[String],
+    mime_family: &str,
+    object_id: &str,
+    starting_index: usize,
+    requested_count: usize,
+    context: BrowseRenderContext,
+) -> Result<Bytes> {
+    let listing = crate::overlay::overlay_directory(
+        session,
+        canonical_parents,
+        (!mime_family.is_empty()).then_some(mime_family),
+        &context.hidden,
+    )?;
+    let total = listing.directories.len() + listing.files.len();
+    let revision = listing
+        .directories
+        .iter()
+        .map(|directory| path_to_lossless_string(&directory.path))
+        .chain(listing.files.iter().map(|path| path.as_str().into()))
+        .fold(0, |revision, path| {
+            crate::database::fnv1a_extend(revision, path.as_bytes())
+        });
+    let directory_start = starting_index.min(listing.directories.len());
+    let directory_end = starting_index
+        .saturating_add(requested_count)
+        .min(listing.directories.len());
+    let file_start = starting_index.saturating_sub(listing.directories.len());
+    let file_limit = requested_count.saturating_sub(directory_end - directory_start);
+
+    let mut response = ByteBuffer::with_capacity(750 + requested_count.saturating_mul(500));
+    response.write_str(r#"<?xml version="1.0" encoding="UTF-8"?>
+<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/" s:encodingStyle="http://schemas.xmlsoap.org/soap/encoding/">
+    <s:Body><u:BrowseResponse xmlns:u="urn:schemas-upnp-org:service:ContentDirectory:1"><Result>"#)?;
+    let mut result = SoapResultWriter(&mut response);
+    result.push_str(r#"<DIDL-Lite xmlns="urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:upnp="urn:schemas-upnp-org:metadata-1-0/upnp/" xmlns:pv="http://www.pv.com/pvplay/" xmlns:sec="http://www.sec.co.kr/">"#);
+    let mut returned = 0;
+    for directory in &listing.directories[directory_start..directory_end] {
+        write_directory(&mut result, object_id, directory, context.client)
+            .map_err(|_| anyhow::anyhow!("failed to construct directory XML"))?;
+        returned += 1;
+    }
+    for path in listing.files.iter().skip(file_start).take(file_limit) {
+        let query = MediaFileQuery::ObjectKey(crate::database::media_object_key(path));
+        returned += session
+            .visit_files(&query, 0, 1, |file| {
+                write_media_view(&mut result, object_id, &file, &context)
+                    .map_err(|_| anyhow::anyhow!("failed to construct browse XML"))
+            })?
+            .visited;
+    }
+    result.push_str("</DIDL-Lite>");
+    let update_id = context.container_update_id(object_id, revision);
+    write!(&mut response, "</Result><NumberReturned>{returned}</NumberReturned><TotalMatches>{total}</TotalMatches><UpdateID>{update_id}</UpdateID></u:BrowseResponse></s:Body></s:Envelope>")?;
     Ok(response.into_bytes())
 }
 
@@ -393,26 +680,59 @@ pub fn generate_indexed_items_response<S: DatabaseReadSession>(
     <s:Body><u:BrowseResponse xmlns:u="urn:schemas-upnp-org:service:ContentDirectory:1"><Result>"#)?;
     let mut result = SoapResultWriter(&mut response);
     result.push_str(r#"<DIDL-Lite xmlns="urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:upnp="urn:schemas-upnp-org:metadata-1-0/upnp/" xmlns:pv="http://www.pv.com/pvplay/" xmlns:sec="http://www.sec.co.kr/">"#);
-    let summary = session.visit_files(&query, starting_index, requested_count, |file| {
-        write_media_view(&mut result, object_id, &file, &context)
-            .map_err(|_| anyhow::anyhow!("failed to construct browse XML"))
-    })?;
+    let known = context
+        .container_update_ids
+        .known(object_id, context.update_id);
+    let mut revision = known.is_none().then_some(0);
+    let summary = crate::database::sort::visit_sorted_files_with_revision(
+        session,
+        &query,
+        &context.sort,
+        &context.hidden,
+        starting_index,
+        requested_count,
+        revision.as_mut(),
+        |file| {
+            write_media_view(&mut result, object_id, &file, &context)
+                .map_err(|_| anyhow::anyhow!("failed to construct browse XML"))
+        },
+    )?;
     result.push_str("</DIDL-Lite>");
-    write!(&mut response, "</Result><NumberReturned>{}</NumberReturned><TotalMatches>{}</TotalMatches><UpdateID>{}</UpdateID></u:BrowseResponse></s:Body></s:Envelope>", summary.visited, summary.matched, context.update_id)?;
+    let update_id = known
+        .unwrap_or_else(|| context.container_update_id(object_id, revision.unwrap_or_default()));
+    write!(&mut response, "</Result><NumberReturned>{}</NumberReturned><TotalMatches>{}</TotalMatches><UpdateID>{update_id}</UpdateID></u:BrowseResponse></s:Body></s:Envelope>", summary.visited, summary.matched)?;
     Ok(response.into_bytes())
 }
 
-pub async fn generate_description_xml<D: DatabaseManager>(state: &AppState<D>) -> String {
+/// Device description for the client at `peer`, which picks the
+/// per-interface friendly name.
+pub async fn generate_description_xml<D: DatabaseManager>(
+    state: &AppState<D>,
+    peer: std::net::IpAddr,
+) -> String {
+    let config = state.current_config();
+    let friendly_name = if config.network.friendly_names.is_empty() {
+        config.server.name.clone()
+    } else {
+        let interfaces = state
+            .selected_interfaces()
+            .unwrap_or_else(|| state.detected_interfaces());
+        config.friendly_name_for(&interfaces, peer).to_owned()
+    };
+    let url_base = state
+        .configured_base_url()
+        .map(|base_url| format!("\n    <URLBase>{}/</URLBase>", xml_escape(&base_url)))
+        .unwrap_or_default();
     format!(
         r#"<?xml version="1.0" encoding="UTF-8"?>
 <root xmlns="urn:schemas-upnp-org:device-1-0">
-    <specVersion><major>1</major><minor>0</minor></specVersion>
+    <specVersion><major>1</major><minor>0</minor></specVersion>{}
     <device>
         <deviceType>urn:schemas-upnp-org:device:MediaServer:1</deviceType>
         <friendlyName>{}</friendlyName>
         <manufacturer>VuIO</manufacturer>
         <modelName>VuIO Server</modelName>
-        <UDN>uuid:{}</UDN>
+        <UDN>{}</UDN>
         <serviceList>
             <service>
                 <serviceType>urn:schemas-upnp-org:service:ContentDirectory:1</serviceType>
@@ -438,8 +758,9 @@ pub async fn generate_description_xml<D: DatabaseManager>(state: &AppState<D>) -
         </serviceList>
     </device>
 </root>"#,
-        xml_escape(&state.current_config().server.name),
-        state.current_config().server.uuid
+        url_base,
+        xml_escape(&friendly_name),
+        xml_escape(&config.server.udn())
     )
 }
 
@@ -464,6 +785,29 @@ pub fn generate_scpd_xml() -> String {
                 <argument><name>UpdateID</name><direction>out</direction><relatedStateVariable>A_ARG_TYPE_UpdateID</relatedStateVariable></argument>
             </argumentList>
         </action>
+        <action>
+            <name>CreateObject</name>
+            <argumentList>
+                <argument><name>ContainerID</name><direction>in</direction><relatedStateVariable>A_ARG_TYPE_ObjectID</relatedStateVariable></argument>
+                <argument><name>Elements</name><direction>in</direction><relatedStateVariable>A_ARG_TYPE_Result</relatedStateVariable></argument>
+                <argument><name>ObjectID</name><direction>out</direction><relatedStateVariable>A_ARG_TYPE_ObjectID</relatedStateVariable></argument>
+                <argument><name>Result</name><direction>out</direction><relatedStateVariable>A_ARG_TYPE_Result</relatedStateVariable></argument>
+            </argumentList>
+        </action>
+        <action>
+            <name>DestroyObject</name>
+            <argumentList>
+                <argument><name>ObjectID</name><direction>in</direction><relatedStateVariable>A_ARG_TYPE_ObjectID</relatedStateVariable></argument>
+            </argumentList>
+        </action>
+        <action>
+            <name>CreateReference</name>
+            <argumentList>
+                <argument><name>ContainerID</name><direction>in</direction><relatedStateVariable>A_ARG_TYPE_ObjectID</relatedStateVariable></argument>
+                <argument><name>ObjectID</name><direction>in</direction><relatedStateVariable>A_ARG_TYPE_ObjectID</relatedStateVariable></argument>
+                <argument><name>NewID</name><direction>out</direction><relatedStateVariable>A_ARG_TYPE_ObjectID</relatedStateVariable></argument>
+            </argumentList>
+        </action>
     </actionList>
     <serviceStateTable>
         <stateVariable sendEvents="no"><name>A_ARG_TYPE_ObjectID</name><dataType>string</dataType></stateVariable>
@@ -521,8 +865,8 @@ pub fn generate_connection_manager_scpd() -> String {
         <stateVariable sendEvents="no"><name>A_ARG_TYPE_AVTransportID</name><dataType>i4</dataType></stateVariable>
         <stateVariable sendEvents="no"><name>A_ARG_TYPE_ProtocolInfo</name><dataType>string</dataType></stateVariable>
         <stateVariable sendEvents="no"><name>A_ARG_TYPE_ConnectionManager</name><dataType>string</dataType></stateVariable>
-        <stateVariable sendEvents="no"><name>A_ARG_TYPE_Direction</name><dataType>string</dataType></stateVariable>
-        <stateVariable sendEvents="no"><name>A_ARG_TYPE_ConnectionStatus</name><dataType>string</dataType></stateVariable>
+        <stateVariable sendEvents="no"><name>A_ARG_TYPE_Direction</name><dataType>string</dataType><allowedValueList><allowedValue>Input</allowedValue><allowedValue>Output</allowedValue></allowedValueList></stateVariable>
+        <stateVariable sendEvents="no"><name>A_ARG_TYPE_ConnectionStatus</name><dataType>string</dataType><allowedValueList><allowedValue>OK</allowedValue><allowedValue>ContentFormatMismatch</allowedValue><allowedValue>InsufficientBandwidth</allowedValue><allowedValue>UnreliableChannel</allowedValue><allowedValue>Unknown</allowedValue></allowedValueList></stateVariable>
     </serviceStateTable>
 </scpd>"#.to_string()
 }
@@ -564,7 +908,7 @@ pub async fn generate_browse_response(
     subdirectories: &[MediaDirectory],
     files: &[MediaFile],
     state: &AppState<impl DatabaseManager>,
-    server_ip: &str,
+    origin: &str,
     total_matches: usize,
 ) -> String {
     use std::fmt::Write;
@@ -574,6 +918,8 @@ pub async fn generate_browse_response(
         .try_with(|c| *c)
         .unwrap_or(crate::web::client::DlnaClientProfile::Standard);
 
+    let playlist_editing = state.current_config().media.dlna_playlist_editing;
+
     debug!(
         "Generating browse response for object_id: '{}', {} subdirs, {} files, client: {:?}",
         object_id,
@@ -607,7 +953,7 @@ pub async fn generate_browse_response(
                 );
             }
 
-            let path_str = container.path.to_string_lossy();
+            let path_str = path_to_lossless_string(&container.path);
             let container_id = if path_str.starts_with("audio/")
                 || path_str.starts_with("video/")
                 || path_str.starts_with("image/")
@@ -627,10 +973,12 @@ pub async fn generate_browse_response(
 
             let _ = write!(
                 &mut didl,
-                r#"<container id="{}" parentID="{}" restricted="1"><dc:title>{}</dc:title><upnp:class>object.container</upnp:class>"#,
+                r#"<container id="{}" parentID="{}" restricted="{}"><dc:title>{}</dc:title><upnp:class>{}</upnp:class>"#,
                 xml_escape(&container_id),
                 xml_escape(object_id),
-                xml_escape(&container.name)
+                u8::from(!(playlist_editing && is_editable_container(&container_id))),
+                xml_escape(&container.name),
+                get_container_class(&container_id)
             );
 
             if client == crate::web::client::DlnaClientProfile::SonyBdp
@@ -654,8 +1002,10 @@ pub async fn generate_browse_response(
             didl.push_str("</container>");
         }
 
-        let mut bookmarks_guard = if client == crate::web::client::DlnaClientProfile::SamsungTv
-            || client == crate::web::client::DlnaClientProfile::SamsungTvQ
+        let iso_mime_type = state.current_config().media.iso_mime_type.clone();
+        let mut bookmarks_guard = if (client == crate::web::client::DlnaClientProfile::SamsungTv
+            || client == crate::web::client::DlnaClientProfile::SamsungTvQ)
+            && state.current_config().media.samsung_bookmarks
         {
             Some(state.bookmarks.lock().await)
         } else {
@@ -685,6 +1035,7 @@ pub async fn generate_browse_response(
                     continue;
                 }
             };
+            let item_id = media_object_id(&path_to_lossless_string(&file.path));
 
             // Log files with potentially problematic characters
             if file.filename.chars().any(|c| c as u32 > 127) {
@@ -709,7 +1060,7 @@ pub async fn generate_browse_response(
                 r#"<item id="{}" parentID="{}" restricted="1">
                 <dc:title>{}</dc:title>
                 "#,
-                file_id,
+                item_id,
                 xml_escape(object_id),
                 title_escaped
             );
@@ -754,10 +1105,8 @@ pub async fn generate_browse_response(
 
                 let _ = write!(
                     &mut didl,
-                    "<upnp:albumArtURI>http://{}:{}/media/{}/cover</upnp:albumArtURI>",
-                    server_ip,
-                    state.current_config().server.port,
-                    file_id
+                    "<upnp:albumArtURI>{}/media/{}/cover</upnp:albumArtURI>",
+                    origin, item_id
                 );
             }
 
@@ -782,42 +1131,8 @@ pub async fn generate_browse_response(
             };
 
             // Use enhanced DLNA flags that support autoplay and streaming
-            let dlna_flags = if state.current_config().media.autoplay_enabled {
-                "DLNA.ORG_OP=11;DLNA.ORG_CI=0;DLNA.ORG_FLAGS=01700000000000000000000000000000"
-            } else {
-                "DLNA.ORG_OP=11;DLNA.ORG_CI=0;DLNA.ORG_FLAGS=00D00000000000000000000000000000"
-            };
-
-            let mime_override = if is_radio {
-                "audio/mpeg"
-            } else {
-                match client {
-                    crate::web::client::DlnaClientProfile::SamsungTv
-                    | crate::web::client::DlnaClientProfile::SamsungTvQ
-                        if file.mime_type == "video/x-matroska" =>
-                    {
-                        "video/x-mkv"
-                    }
-                    crate::web::client::DlnaClientProfile::SamsungTv
-                    | crate::web::client::DlnaClientProfile::SamsungTvQ
-                        if file.mime_type == "video/x-msvideo" =>
-                    {
-                        "video/mpeg"
-                    }
-                    crate::web::client::DlnaClientProfile::SonyBdp
-                        if file.mime_type == "video/x-matroska"
-                            || file.mime_type == "video/mpeg" =>
-                    {
-                        "video/divx"
-                    }
-                    crate::web::client::DlnaClientProfile::Xbox
-                        if file.mime_type == "video/x-msvideo" =>
-                    {
-                        "video/avi"
-                    }
-                    _ => &file.mime_type,
-                }
-            };
+            let dlna_flags = dlna_flags(autoplay_for_client(&state.current_config(), client));
+            let mime_override = wire_mime_type(client, &file.mime_type, &iso_mime_type);
 
             let size_val = if is_radio {
                 "0".to_string()
@@ -843,29 +1158,19 @@ pub async fn generate_browse_response(
             {
                 let _ = write!(
                     &mut didl,
-                    r#" pv:subtitleFileUri="http://{}:{}/media/{}/subtitle" pv:subtitleFileType="SRT""#,
-                    server_ip,
-                    state.current_config().server.port,
-                    file_id
+                    r#" pv:subtitleFileUri="{}/media/{}/subtitle" pv:subtitleFileType="SRT""#,
+                    origin, item_id
                 );
             }
 
-            let _ = write!(
-                &mut didl,
-                r#">http://{}:{}/media/{}</res>"#,
-                server_ip,
-                state.current_config().server.port,
-                file_id
-            );
+            let _ = write!(&mut didl, r#">{}/media/{}</res>"#, origin, item_id);
 
             if client == crate::web::client::DlnaClientProfile::LgTv && has_srt {
                 let _ = write!(
                     &mut didl,
                     r#"
-                <res protocolInfo="http-get:*:text/srt:*">http://{}:{}/media/{}/subtitle</res>"#,
-                    server_ip,
-                    state.current_config().server.port,
-                    file_id
+                <res protocolInfo="http-get:*:text/srt:*">{}/media/{}/subtitle</res>"#,
+                    origin, item_id
                 );
             }
 
@@ -876,20 +1181,16 @@ pub async fn generate_browse_response(
                 let _ = write!(
                     &mut didl,
                     r#"
-                <sec:CaptionInfoEx sec:type="srt">http://{}:{}/media/{}/subtitle</sec:CaptionInfoEx>"#,
-                    server_ip,
-                    state.current_config().server.port,
-                    file_id
+                <sec:CaptionInfoEx sec:type="srt">{}/media/{}/subtitle</sec:CaptionInfoEx>"#,
+                    origin, item_id
                 );
             }
 
-            if client == crate::web::client::DlnaClientProfile::SamsungTv
-                || client == crate::web::client::DlnaClientProfile::SamsungTvQ
+            if let Some(bookmarks) = bookmarks_guard
+                .as_mut()
+                .filter(|_| file.mime_type.starts_with("video/"))
             {
-                let bookmark_sec = bookmarks_guard
-                    .as_mut()
-                    .and_then(|g| g.get(&file_id).copied())
-                    .unwrap_or(0);
+                let bookmark_sec = bookmarks.get(&file_id).copied().unwrap_or(0);
                 let bookmark_val = if client == crate::web::client::DlnaClientProfile::SamsungTvQ {
                     bookmark_sec * 1000
                 } else {
@@ -967,4 +1268,306 @@ mod tests {
             .expect("write nested XML");
         assert_eq!(output, "A&amp;amp;B");
     }
+
+    #[test]
+    fn playlists_are_playlist_containers_and_other_folders_are_not() {
+        assert_eq!(
+            get_container_class("audio/playlists/7"),
+            "object.container.playlistContainer"
+        );
+        assert_eq!(
+            get_container_class("audio/playlists/smart-3"),
+            "object.container.playlistContainer"
+        );
+        assert_eq!(get_container_class("audio/playlists"), "object.container");
+        assert_eq!(
+            get_container_class("audio/artists/Queen"),
+            "object.container"
+        );
+        assert_eq!(get_container_class("d12"), "object.container");
+    }
+
+    fn variant(wire_mime: &str, bitrate: Option<u64>) -> ResVariant<'_> {
+        ResVariant {
+            wire_mime,
+            size: 0,
+            bitrate,
+        }
+    }
+
+    #[test]
+    fn res_variants_keep_their_order_without_a_renderer_limit() {
+        let mut variants = vec![
+            variant("remux", Some(80_000_000)),
+            variant("transcode", Some(8_000_000)),
+        ];
+        rank_res_variants(&mut variants, None);
+        assert_eq!(variants[0].wire_mime, "remux");
+        assert_eq!(variants[1].wire_mime, "transcode");
+    }
+
+    #[test]
+    fn res_variants_put_the_best_fit_first_and_over_limit_last() {
+        let limit = BitrateLimit {
+            max_bits_per_second: 20_000_000,
+            strict: false,
+        };
+        let mut variants = vec![
+            variant("remux", Some(80_000_000)),
+            variant("unknown", None),
+            variant("low", Some(4_000_000)),
+            variant("medium", Some(15_000_000)),
+            variant("high", Some(30_000_000)),
+        ];
+        rank_res_variants(&mut variants, Some(limit));
+        let order = variants
+            .iter()
+            .map(|variant| variant.wire_mime)
+            .collect::<Vec<_>>();
+        assert_eq!(order, ["medium", "low", "unknown", "high", "remux"]);
+    }
+
+    #[test]
+    fn strict_limits_omit_over_limit_variants_but_keep_the_last_res() {
+        let limit = BitrateLimit {
+            max_bits_per_second: 20_000_000,
+            strict: true,
+        };
+        let mut variants = vec![
+            variant("remux", Some(80_000_000)),
+            variant("unknown", None),
+            variant("transcode", Some(8_000_000)),
+        ];
+        rank_res_variants(&mut variants, Some(limit));
+        let order = variants
+            .iter()
+            .map(|variant| variant.wire_mime)
+            .collect::<Vec<_>>();
+        assert_eq!(order, ["transcode", "unknown"]);
+
+        let mut over_limit = vec![
+            variant("remux", Some(80_000_000)),
+            variant("high", Some(40_000_000)),
+        ];
+        rank_res_variants(&mut over_limit, Some(limit));
+        let order = over_limit
+            .iter()
+            .map(|variant| variant.wire_mime)
+            .collect::<Vec<_>>();
+        assert_eq!(order, ["high"]);
+    }
+
+    #[tokio::test]
+    async fn item_object_ids_are_stable_across_a_full_rescan() {
+        use crate::database::{memory::InMemoryDatabase, MediaRepository};
+        use std::{path::PathBuf, sync::Arc};
+
+        async fn browse(paths: &[&str]) -> String {
+            let database = Arc::new(InMemoryDatabase::new());
+            let files = paths
+                .iter()
+                .map(|path| MediaFile::new(PathBuf::from(path), 1, "video/mp4".to_owned()))
+                .collect::<Vec<_>>();
+            database.bulk_store_media_files(&files).await.unwrap();
+            let context = BrowseRenderContext {
+                client: crate::web::client::DlnaClientProfile::Standard,
+                origin: "http://192.0.2.1:8080".to_owned(),
+                autoplay_enabled: false,
+                update_id: 1,
+                bookmarks: None,
+                bitrate_limit: None,
+                iso_mime_type: "application/x-iso9660-image".to_owned(),
+                sort: Default::default(),
+                hidden: Default::default(),
+                container_update_ids: Default::default(),
+                entry_info: Default::default(),
+            };
+            let response = database
+                .read(move |session| {
+                    generate_indexed_items_response(
+                        session,
+                        MediaFileQuery::Path("/library/movie.mp4".to_owned()),
+                        "video",
+                        0,
+                        10,
+                        context,
+                    )
+                })
+                .await
+                .unwrap();
+            String::from_utf8(response.to_vec()).unwrap()
+        }
+
+        // The second scan indexes an extra file first, so the row id differs.
+        let before = browse(&["/library/movie.mp4"]).await;
+        let after = browse(&["/library/intro.mp4", "/library/movie.mp4"]).await;
+        let object_id = media_object_id("/library/movie.mp4");
+        for response in [&before, &after] {
+            assert!(response.contains(&format!("item id=&quot;{object_id}&quot;")));
+            assert!(response.contains(&format!("http://192.0.2.1:8080/media/{object_id}&lt;")));
+        }
+        assert_eq!(
+            parse_media_object_id(&object_id),
+            Some(media_object_key("/library/movie.mp4"))
+        );
+        assert_eq!(parse_media_object_id("video/d0"), None);
+        assert_eq!(parse_media_object_id("42"), None);
+    }
+
+    #[tokio::test]
+    async fn samsung_resume_info_is_rendered_only_on_video_items() {
+        use crate::database::{memory::InMemoryDatabase, MediaRepository};
+        use std::{path::PathBuf, sync::Arc};
+
+        let database = Arc::new(InMemoryDatabase::new());
+        let ids = database
+            .bulk_store_media_files(&[
+                MediaFile::new(
+                    PathBuf::from("/library/movie.mp4"),
+                    1,
+                    "video/mp4".to_owned(),
+                ),
+                MediaFile::new(
+                    PathBuf::from("/library/song.mp3"),
+                    1,
+                    "audio/mpeg".to_owned(),
+                ),
+            ])
+            .await
+            .unwrap();
+
+        let browse = |bookmarks: Option<HashMap<i64, u32>>| {
+            let database = database.clone();
+            async move {
+                let context = BrowseRenderContext {
+                    client: crate::web::client::DlnaClientProfile::SamsungTv,
+                    origin: "http://192.0.2.1:8080".to_owned(),
+                    autoplay_enabled: false,
+                    update_id: 1,
+                    bookmarks,
+                    bitrate_limit: None,
+                    iso_mime_type: "application/x-iso9660-image".to_owned(),
+                    sort: Default::default(),
+                    hidden: Default::default(),
+                    container_update_ids: Default::default(),
+                    entry_info: Default::default(),
+                };
+                let response = database
+                    .read(move |session| {
+                        generate_indexed_items_response(
+                            session,
+                            MediaFileQuery::Directory {
+                                path: "/library".to_owned(),
+                                mime_family: None,
+                            },
+                            "video",
+                            0,
+                            10,
+                            context,
+                        )
+                    })
+                    .await
+                    .unwrap();
+                String::from_utf8(response.to_vec()).unwrap()
+            }
+        };
+
+        let enabled = browse(Some(ids.iter().map(|id| (*id, 42)).collect())).await;
+        assert_eq!(enabled.matches("&lt;sec:dcmInfo&gt;").count(), 1);
+        assert_eq!(enabled.matches("BM=42").count(), 1);
+        let disabled = browse(None).await;
+        assert!(!disabled.contains("dcmInfo"));
+    }
+
+    #[test]
+    fn bitrate_estimate_requires_size_and_duration() {
+        assert_eq!(estimate_bitrate(10_000_000, 10), Some(8_000_000));
+        assert_eq!(estimate_bitrate(10_000_000, 0), None);
+        assert_eq!(estimate_bitrate(0, 10), None);
+    }
+
+    #[test]
+    fn renderer_autoplay_overrides_the_media_default_for_its_profile_only() {
+        use crate::config::RendererProfileConfig;
+        use crate::web::client::DlnaClientProfile;
+
+        let mut config = AppConfig::default_for_platform();
+        config.media.autoplay_enabled = true;
+        config.renderers = vec![RendererProfileConfig {
+            profile: DlnaClientProfile::SamsungTv,
+            max_bitrate_mbps: None,
+            strict_bitrate: false,
+            autoplay: Some(false),
+        }];
+        assert!(!autoplay_for_client(&config, DlnaClientProfile::SamsungTv));
+        assert!(autoplay_for_client(&config, DlnaClientProfile::LgTv));
+
+        config.media.autoplay_enabled = false;
+        config.renderers[0].autoplay = Some(true);
+        assert!(autoplay_for_client(&config, DlnaClientProfile::SamsungTv));
+        assert!(!autoplay_for_client(&config, DlnaClientProfile::LgTv));
+    }
+
+    #[test]
+    fn protocol_info_lists_every_type_browse_can_announce() {
+        use crate::web::client::DlnaClientProfile;
+
+        for client in [
+            DlnaClientProfile::Standard,
+            DlnaClientProfile::SamsungTv,
+            DlnaClientProfile::SonyBdp,
+            DlnaClientProfile::Xbox,
+            DlnaClientProfile::LgTv,
+        ] {
+            let source = source_protocol_info(client, true, "video/mpeg");
+            let entries = source.split(',').collect::<Vec<_>>();
+            for &(_, mime) in crate::platform::filesystem::SUPPORTED_MEDIA_TYPES {
+                let expected = format!(
+                    "http-get:*:{}:{}",
+                    wire_mime_type(client, mime, "video/mpeg"),
+                    dlna_flags(true)
+                );
+                assert!(
+                    entries.contains(&expected.as_str()),
+                    "{client:?}: {expected}"
+                );
+            }
+            let unique = entries.iter().collect::<std::collections::HashSet<_>>();
+            assert_eq!(unique.len(), entries.len(), "{client:?} repeats an entry");
+        }
+
+        let samsung = source_protocol_info(DlnaClientProfile::SamsungTv, false, "video/mpeg");
+        assert!(samsung.contains("http-get:*:video/x-mkv:DLNA.ORG_OP=11"));
+        assert!(!samsung.contains(":video/x-matroska:"));
+        assert!(!samsung.contains("text/srt"));
+        assert!(
+            source_protocol_info(DlnaClientProfile::LgTv, false, "video/mpeg")
+                .ends_with(",http-get:*:text/srt:*")
+        );
+    }
+
+    #[test]
+    fn bitrate_limit_applies_only_to_the_configured_profile() {
+        use crate::config::RendererProfileConfig;
+        use crate::web::client::DlnaClientProfile;
+
+        let mut config = AppConfig::default_for_platform();
+        config.renderers = vec![RendererProfileConfig {
+            profile: DlnaClientProfile::LgTv,
+            max_bitrate_mbps: Some(20.0),
+            strict_bitrate: true,
+            autoplay: None,
+        }];
+        assert_eq!(
+            BitrateLimit::for_client(&config, DlnaClientProfile::LgTv),
+            Some(BitrateLimit {
+                max_bits_per_second: 20_000_000,
+                strict: true,
+            })
+        );
+        assert_eq!(
+            BitrateLimit::for_client(&config, DlnaClientProfile::SamsungTv),
+            None
+        );
+    }
 }
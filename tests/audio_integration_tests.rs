@@ -314,12 +314,17 @@ async fn test_cover_art_retrieval_and_xml() {
         auth: Arc::new(vuio::web::auth::AuthState::testing()),
         auth_forced: false,
         platform_info,
+        network_interfaces: Arc::default(),
         filesystem_manager,
         content_update_id,
         content_change_notify: Arc::new(tokio::sync::Notify::new()),
         http_rebind_notify: Arc::new(tokio::sync::Notify::new()),
         ssdp_reload_notify: Arc::new(tokio::sync::Notify::new()),
+        playlist_mirror_notify: Arc::new(tokio::sync::Notify::new()),
         web_metrics,
+        stream_audit: None,
+        scan_control: Arc::new(vuio::scan_progress::ScanControl::new()),
+        health: Arc::new(vuio::state::ServiceHealth::default()),
         runtime_diagnostics: Arc::new(vuio::platform::diagnostics::SystemDiagnosticsSampler::new()),
         lifecycle_stats: Arc::new(vuio::lifecycle::ApplicationStats::new()),
         bookmarks: Arc::new(tokio::sync::Mutex::new(
@@ -329,6 +334,9 @@ async fn test_cover_art_retrieval_and_xml() {
         browse_cache: Arc::new(tokio::sync::Mutex::new(
             vuio::runtime_state::BrowseResponseCache::new(),
         )),
+        hidden_files: Arc::default(),
+        active_streams: Arc::default(),
+        container_update_ids: Arc::default(),
         mcp_clients: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
         active_monitors: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
         active_casts: Arc::new(tokio::sync::Mutex::new(
@@ -350,14 +358,15 @@ async fn test_cover_art_retrieval_and_xml() {
         &[],
         std::slice::from_ref(&db_file),
         &app_state,
-        "127.0.0.1",
+        &format!("http://127.0.0.1:{}", app_state.config.server.port),
         1,
     )
     .await;
 
+    let object_id = vuio::web::xml::media_object_id(&db_file.path.to_string_lossy());
     let expected_url = format!(
         "http://127.0.0.1:{}/media/{}/cover",
-        app_state.config.server.port, file_id
+        app_state.config.server.port, object_id
     );
     assert!(
         xml_response.contains("upnp:albumArtURI"),
@@ -369,11 +378,41 @@ async fn test_cover_art_retrieval_and_xml() {
         xml_response
     );
 
-    // 8. Test serve_cover endpoint directly
-    let response = serve_cover(State(app_state.clone()), Path(file_id.to_string()))
+    // 8. Test serve_cover endpoint directly, by object ID and by library ID
+    let response = serve_cover(
+        State(app_state.clone()),
+        Path(object_id.clone()),
+        axum::http::HeaderMap::new(),
+    )
+    .await
+    .unwrap()
+    .into_response();
+    assert_eq!(response.status(), StatusCode::OK);
+    let etag = response.headers().get("etag").unwrap().clone();
+    assert!(response.headers().contains_key("last-modified"));
+    assert_eq!(
+        response.headers().get("cache-control").unwrap(),
+        "public, max-age=86400"
+    );
+
+    // A client holding the current ETag gets an empty 304
+    let mut conditional = axum::http::HeaderMap::new();
+    conditional.insert("if-none-match", etag);
+    let response = serve_cover(State(app_state.clone()), Path(object_id), conditional)
         .await
         .unwrap()
         .into_response();
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert!(response.headers().contains_key("cache-control"));
+
+    let response = serve_cover(
+        State(app_state.clone()),
+        Path(file_id.to_string()),
+        axum::http::HeaderMap::new(),
+    )
+    .await
+    .unwrap()
+    .into_response();
 
     assert_eq!(response.status(), StatusCode::OK);
 
@@ -480,12 +519,17 @@ https://cast1.asurahosting.com/proxy/julien/stream
         auth: Arc::new(vuio::web::auth::AuthState::testing()),
         auth_forced: false,
         platform_info,
+        network_interfaces: Arc::default(),
         filesystem_manager,
         content_update_id,
         content_change_notify: Arc::new(tokio::sync::Notify::new()),
         http_rebind_notify: Arc::new(tokio::sync::Notify::new()),
         ssdp_reload_notify: Arc::new(tokio::sync::Notify::new()),
+        playlist_mirror_notify: Arc::new(tokio::sync::Notify::new()),
         web_metrics,
+        stream_audit: None,
+        scan_control: Arc::new(vuio::scan_progress::ScanControl::new()),
+        health: Arc::new(vuio::state::ServiceHealth::default()),
         runtime_diagnostics: Arc::new(vuio::platform::diagnostics::SystemDiagnosticsSampler::new()),
         lifecycle_stats: Arc::new(vuio::lifecycle::ApplicationStats::new()),
         bookmarks: Arc::new(tokio::sync::Mutex::new(
@@ -495,6 +539,9 @@ https://cast1.asurahosting.com/proxy/julien/stream
         browse_cache: Arc::new(tokio::sync::Mutex::new(
             vuio::runtime_state::BrowseResponseCache::new(),
         )),
+        hidden_files: Arc::default(),
+        active_streams: Arc::default(),
+        container_update_ids: Arc::default(),
         mcp_clients: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
         active_monitors: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
         active_casts: Arc::new(tokio::sync::Mutex::new(
@@ -512,7 +559,7 @@ https://cast1.asurahosting.com/proxy/julien/stream
 
     // 5. Test UPnP XML Browse response
     // Root container browse (ObjectID "0")
-    let server_ip = app_state.get_server_ip();
+    let origin = app_state.advertised_http_origin();
     let root_containers = [
         ("video", "Video"),
         ("audio", "Music"),
@@ -528,7 +575,7 @@ https://cast1.asurahosting.com/proxy/julien/stream
         &root_containers,
         &[],
         &app_state,
-        &server_ip,
+        &origin,
         root_containers.len(),
     )
     .await;
@@ -544,7 +591,7 @@ https://cast1.asurahosting.com/proxy/julien/stream
         &[],
         &radio_files,
         &app_state,
-        &server_ip,
+        &origin,
         radio_files.len(),
     )
     .await;
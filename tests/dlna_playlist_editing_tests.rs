@@ -0,0 +1,237 @@
+use axum::{
+    body::to_bytes,
+    extract::State,
+    http::{HeaderMap, HeaderValue, Method, StatusCode},
+};
+use std::sync::Arc;
+use tempfile::tempdir;
+use vuio::{
+    config::{AppConfig, MonitoredDirectoryConfig, ValidationMode, WatchMode},
+    database::{
+        redb::RedbDatabase, DatabaseManager, MediaFile, MediaRepository, PlaylistRepository,
+    },
+    lifecycle::ApplicationStats,
+    platform::{
+        diagnostics::SystemDiagnosticsSampler, filesystem::create_platform_filesystem_manager,
+        PlatformInfo,
+    },
+    runtime_state::{
+        ActiveCastRegistry, BookmarkRegistry, BrowseResponseCache, RendererCache,
+        BOOKMARK_MAX_ENTRIES,
+    },
+    state::AppState,
+    web::{diagnostics::WebHandlerMetrics, soap::content_directory_control, xml::media_object_id},
+};
+
+async fn control(state: &AppState, action: &str, arguments: &str) -> (StatusCode, String) {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "soapaction",
+        HeaderValue::from_str(&format!(
+            "\"urn:schemas-upnp-org:service:ContentDirectory:1#{action}\""
+        ))
+        .unwrap(),
+    );
+    let body = format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/">
+  <s:Body>
+    <u:{action} xmlns:u="urn:schemas-upnp-org:service:ContentDirectory:1">{arguments}</u:{action}>
+  </s:Body>
+</s:Envelope>"#
+    );
+    let response =
+        content_directory_control(State(state.clone()), Method::POST, headers, body).await;
+    let status = response.status();
+    let body = to_bytes(response.into_body(), 128 * 1024).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+fn element<'a>(response: &'a str, name: &str) -> &'a str {
+    let start = response.find(&format!("<{name}>")).unwrap() + name.len() + 2;
+    let end = response[start..].find(&format!("</{name}>")).unwrap();
+    &response[start..start + end]
+}
+
+#[tokio::test]
+async fn control_points_edit_stored_playlists() {
+    let temp = tempdir().unwrap();
+    let media_root = temp.path().join("music");
+    tokio::fs::create_dir(&media_root).await.unwrap();
+    let track_path = media_root.canonicalize().unwrap().join("song.mp3");
+    tokio::fs::write(&track_path, b"song").await.unwrap();
+
+    let database = Arc::new(
+        RedbDatabase::new(temp.path().join("media.redb"))
+            .await
+            .unwrap(),
+    );
+    database.initialize().await.unwrap();
+    let track_id = database
+        .store_media_file(&MediaFile::new(
+            track_path.clone(),
+            4,
+            "audio/mpeg".to_string(),
+        ))
+        .await
+        .unwrap();
+    let track_object = media_object_id(&track_path.to_string_lossy());
+
+    let monitored_directory = MonitoredDirectoryConfig {
+        path: media_root.to_string_lossy().into_owned(),
+        recursive: false,
+        case_sensitive: None,
+        extensions: Some(vec!["mp3".to_string()]),
+        exclude_patterns: None,
+        validation_mode: ValidationMode::Warn,
+        watch_mode: WatchMode::Auto,
+        merge_group: None,
+        priority: 0,
+        media_types: None,
+        min_file_size: None,
+        min_file_age_seconds: None,
+    };
+    let mut config = AppConfig::default();
+    config.server.ip = Some("127.0.0.1".to_string());
+    config.media.directories = vec![monitored_directory.clone()];
+    config.media.dlna_playlist_editing = true;
+    let config = Arc::new(config);
+    let state = AppState {
+        config: config.clone(),
+        live_config: Arc::new(vuio::state::LiveConfig::new(config.clone())),
+        desired_config: Arc::new(vuio::state::LiveConfig::new(config.clone())),
+        config_reload_errors: Arc::new(std::sync::RwLock::new(Vec::new())),
+        pending_restart_fields: Arc::new(std::sync::RwLock::new(Vec::new())),
+        media_directories: Arc::new(tokio::sync::RwLock::new(vec![monitored_directory])),
+        unavailable_roots: Arc::new(tokio::sync::RwLock::new(std::collections::HashSet::new())),
+        database: database.clone(),
+        auth: Arc::new(vuio::web::auth::AuthState::testing()),
+        auth_forced: false,
+        platform_info: Arc::new(PlatformInfo::detect().await.unwrap()),
+        network_interfaces: Arc::default(),
+        filesystem_manager: Arc::from(create_platform_filesystem_manager()),
+        content_update_id: Arc::new(std::sync::atomic::AtomicU32::new(1)),
+        content_change_notify: Arc::new(tokio::sync::Notify::new()),
+        http_rebind_notify: Arc::new(tokio::sync::Notify::new()),
+        ssdp_reload_notify: Arc::new(tokio::sync::Notify::new()),
+        playlist_mirror_notify: Arc::new(tokio::sync::Notify::new()),
+        web_metrics: Arc::new(WebHandlerMetrics::new()),
+        stream_audit: None,
+        scan_control: Arc::new(vuio::scan_progress::ScanControl::new()),
+        health: Arc::new(vuio::state::ServiceHealth::default()),
+        runtime_diagnostics: Arc::new(SystemDiagnosticsSampler::new()),
+        lifecycle_stats: Arc::new(ApplicationStats::new()),
+        bookmarks: Arc::new(tokio::sync::Mutex::new(BookmarkRegistry::new(
+            BOOKMARK_MAX_ENTRIES,
+        ))),
+        log_file_path: temp.path().join("vuio.log"),
+        browse_cache: Arc::new(tokio::sync::Mutex::new(BrowseResponseCache::new())),
+        hidden_files: Arc::default(),
+        active_streams: Arc::default(),
+        container_update_ids: Arc::default(),
+        mcp_clients: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
+        active_monitors: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
+        active_casts: Arc::new(tokio::sync::Mutex::new(ActiveCastRegistry::new())),
+        cast_sessions: Arc::new(tokio::sync::Mutex::new(
+            vuio::runtime_state::CastSessionRegistry::new(),
+        )),
+        discovered_tvs: Arc::new(RendererCache::new()),
+        discovery_service: Arc::new(vuio::discovery::DiscoveryService::default()),
+        upnp_subscriptions: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
+        cancellation: tokio_util::sync::CancellationToken::new(),
+        background_tasks: tokio_util::task::TaskTracker::new(),
+    };
+
+    let didl = |class: &str| {
+        format!(
+            "<ContainerID>audio/playlists</ContainerID><Elements>&lt;DIDL-Lite xmlns=&quot;urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/&quot; xmlns:dc=&quot;http://purl.org/dc/elements/1.1/&quot; xmlns:upnp=&quot;urn:schemas-upnp-org:metadata-1-0/upnp/&quot;&gt;&lt;container id=&quot;&quot; parentID=&quot;audio/playlists&quot; restricted=&quot;0&quot;&gt;&lt;dc:title&gt;Rock &amp;amp; Roll&lt;/dc:title&gt;&lt;upnp:class&gt;{class}&lt;/upnp:class&gt;&lt;/container&gt;&lt;/DIDL-Lite&gt;</Elements>"
+        )
+    };
+    let (status, response) = control(&state, "CreateObject", &didl("object.item.audioItem")).await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(response.contains("<errorCode>712</errorCode>"));
+
+    let (status, response) = control(
+        &state,
+        "CreateObject",
+        &didl("object.container.playlistContainer"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let playlist = element(&response, "ObjectID").to_owned();
+    let playlist_id: i64 = playlist
+        .strip_prefix("audio/playlists/")
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert_eq!(
+        database
+            .get_playlist(playlist_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .name,
+        "Rock & Roll"
+    );
+    assert!(response.contains("restricted=&quot;0&quot;"));
+
+    let (status, response) = control(
+        &state,
+        "CreateReference",
+        &format!("<ContainerID>{playlist}</ContainerID><ObjectID>{track_object}</ObjectID>"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let reference = element(&response, "NewID").to_owned();
+    assert_eq!(reference, format!("{playlist}/{track_object}"));
+    let tracks = database.get_playlist_tracks(playlist_id).await.unwrap();
+    assert_eq!(
+        tracks.iter().map(|track| track.id).collect::<Vec<_>>(),
+        [Some(track_id)]
+    );
+
+    // Media files themselves cannot be destroyed.
+    let (status, response) = control(
+        &state,
+        "DestroyObject",
+        &format!("<ObjectID>{track_object}</ObjectID>"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(response.contains("<errorCode>711</errorCode>"));
+
+    let (status, _) = control(
+        &state,
+        "DestroyObject",
+        &format!("<ObjectID>{reference}</ObjectID>"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(database
+        .get_playlist_tracks(playlist_id)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let (status, _) = control(
+        &state,
+        "DestroyObject",
+        &format!("<ObjectID>{playlist}</ObjectID>"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(database.get_playlist(playlist_id).await.unwrap().is_none());
+    let (_, response) = control(
+        &state,
+        "DestroyObject",
+        &format!("<ObjectID>{playlist}</ObjectID>"),
+    )
+    .await;
+    assert!(response.contains("<errorCode>701</errorCode>"));
+    assert_eq!(
+        state
+            .content_update_id
+            .load(std::sync::atomic::Ordering::SeqCst),
+        5
+    );
+}
@@ -1,12 +1,12 @@
 use axum::{
     body::to_bytes,
     extract::State,
-    http::{header::USER_AGENT, HeaderMap, HeaderValue, StatusCode},
+    http::{header::USER_AGENT, HeaderMap, HeaderValue, Method, StatusCode},
 };
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 use tempfile::tempdir;
 use vuio::{
-    config::{AppConfig, MonitoredDirectoryConfig, ValidationMode},
+    config::{AppConfig, MonitoredDirectoryConfig, ValidationMode, WatchMode},
     database::{redb::RedbDatabase, DatabaseManager, MediaFile, MediaRepository},
     lifecycle::ApplicationStats,
     platform::{
@@ -51,6 +51,7 @@ async fn browse(state: AppState, object_id: &str, start: u32, count: u32) -> Str
     );
     let response = content_directory_control(
         State(state),
+        Method::POST,
         headers,
         browse_request(object_id, start, count),
     )
@@ -65,42 +66,7 @@ async fn browse(state: AppState, object_id: &str, start: u32, count: u32) -> Str
     .expect("Browse response is UTF-8")
 }
 
-#[tokio::test]
-async fn issue_24_philips_probe_reports_full_total_and_supports_followup_pages() {
-    let temp = tempdir().expect("temporary test directory");
-    let media_root = temp.path().join("mediatest");
-    tokio::fs::create_dir(&media_root)
-        .await
-        .expect("create media directory");
-    let canonical_root = media_root
-        .canonicalize()
-        .expect("canonical media directory");
-    let first_path = canonical_root.join("first.mkv");
-    let second_path = canonical_root.join("second.mkv");
-    tokio::fs::write(&first_path, b"first")
-        .await
-        .expect("write first video");
-    tokio::fs::write(&second_path, b"second")
-        .await
-        .expect("write second video");
-
-    let database = Arc::new(
-        RedbDatabase::new(temp.path().join("media.redb"))
-            .await
-            .expect("create database"),
-    );
-    database.initialize().await.expect("initialize database");
-    for path in [&first_path, &second_path] {
-        database
-            .store_media_file(&MediaFile::new(
-                path.to_path_buf(),
-                5,
-                "video/x-matroska".to_string(),
-            ))
-            .await
-            .expect("index video");
-    }
-
+async fn test_state(temp: &Path, media_root: &Path, database: Arc<RedbDatabase>) -> AppState {
     let monitored_directory = MonitoredDirectoryConfig {
         path: media_root.to_string_lossy().into_owned(),
         recursive: false,
@@ -108,12 +74,18 @@ async fn issue_24_philips_probe_reports_full_total_and_supports_followup_pages()
         extensions: Some(vec!["mkv".to_string()]),
         exclude_patterns: None,
         validation_mode: ValidationMode::Warn,
+        watch_mode: WatchMode::Auto,
+        merge_group: None,
+        priority: 0,
+        media_types: None,
+        min_file_size: None,
+        min_file_age_seconds: None,
     };
     let mut config = AppConfig::default();
     config.server.ip = Some("127.0.0.1".to_string());
     config.media.directories = vec![monitored_directory.clone()];
     let config = Arc::new(config);
-    let state = AppState {
+    AppState {
         config: config.clone(),
         live_config: Arc::new(vuio::state::LiveConfig::new(config.clone())),
         desired_config: Arc::new(vuio::state::LiveConfig::new(config.clone())),
@@ -125,19 +97,27 @@ async fn issue_24_philips_probe_reports_full_total_and_supports_followup_pages()
         auth: Arc::new(vuio::web::auth::AuthState::testing()),
         auth_forced: false,
         platform_info: Arc::new(PlatformInfo::detect().await.expect("detect platform")),
+        network_interfaces: Arc::default(),
         filesystem_manager: Arc::from(create_platform_filesystem_manager()),
         content_update_id: Arc::new(std::sync::atomic::AtomicU32::new(1)),
         content_change_notify: Arc::new(tokio::sync::Notify::new()),
         http_rebind_notify: Arc::new(tokio::sync::Notify::new()),
         ssdp_reload_notify: Arc::new(tokio::sync::Notify::new()),
+        playlist_mirror_notify: Arc::new(tokio::sync::Notify::new()),
         web_metrics: Arc::new(WebHandlerMetrics::new()),
+        stream_audit: None,
+        scan_control: Arc::new(vuio::scan_progress::ScanControl::new()),
+        health: Arc::new(vuio::state::ServiceHealth::default()),
         runtime_diagnostics: Arc::new(SystemDiagnosticsSampler::new()),
         lifecycle_stats: Arc::new(ApplicationStats::new()),
         bookmarks: Arc::new(tokio::sync::Mutex::new(BookmarkRegistry::new(
             BOOKMARK_MAX_ENTRIES,
         ))),
-        log_file_path: temp.path().join("vuio.log"),
+        log_file_path: temp.join("vuio.log"),
         browse_cache: Arc::new(tokio::sync::Mutex::new(BrowseResponseCache::new())),
+        hidden_files: Arc::default(),
+        active_streams: Arc::default(),
+        container_update_ids: Arc::default(),
         mcp_clients: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
         active_monitors: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
         active_casts: Arc::new(tokio::sync::Mutex::new(ActiveCastRegistry::new())),
@@ -149,7 +129,46 @@ async fn issue_24_philips_probe_reports_full_total_and_supports_followup_pages()
         upnp_subscriptions: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
         cancellation: tokio_util::sync::CancellationToken::new(),
         background_tasks: tokio_util::task::TaskTracker::new(),
-    };
+    }
+}
+
+#[tokio::test]
+async fn issue_24_philips_probe_reports_full_total_and_supports_followup_pages() {
+    let temp = tempdir().expect("temporary test directory");
+    let media_root = temp.path().join("mediatest");
+    tokio::fs::create_dir(&media_root)
+        .await
+        .expect("create media directory");
+    let canonical_root = media_root
+        .canonicalize()
+        .expect("canonical media directory");
+    let first_path = canonical_root.join("first.mkv");
+    let second_path = canonical_root.join("second.mkv");
+    tokio::fs::write(&first_path, b"first")
+        .await
+        .expect("write first video");
+    tokio::fs::write(&second_path, b"second")
+        .await
+        .expect("write second video");
+
+    let database = Arc::new(
+        RedbDatabase::new(temp.path().join("media.redb"))
+            .await
+            .expect("create database"),
+    );
+    database.initialize().await.expect("initialize database");
+    for path in [&first_path, &second_path] {
+        database
+            .store_media_file(&MediaFile::new(
+                path.to_path_buf(),
+                5,
+                "video/x-matroska".to_string(),
+            ))
+            .await
+            .expect("index video");
+    }
+
+    let state = test_state(temp.path(), &media_root, database).await;
 
     let first_page = browse(state.clone(), "video", 0, 1).await;
     assert!(first_page.contains("<NumberReturned>1</NumberReturned>"));
@@ -173,3 +192,115 @@ async fn issue_24_philips_probe_reports_full_total_and_supports_followup_pages()
     assert!(root_probe.contains("<NumberReturned>1</NumberReturned>"));
     assert!(root_probe.contains("<TotalMatches>4</TotalMatches>"));
 }
+
+/// `(TotalMatches, UpdateID, item IDs)` of one Browse response.
+fn page_summary(response: &str) -> (usize, u32, Vec<String>) {
+    let element = |name: &str| {
+        response
+            .split(&format!("<{name}>"))
+            .nth(1)
+            .and_then(|rest| rest.split(&format!("</{name}>")).next())
+            .expect("Browse response element")
+            .to_owned()
+    };
+    let items = response
+        .split("&lt;item id=&quot;")
+        .skip(1)
+        .map(|rest| rest.split("&quot;").next().unwrap().to_owned())
+        .collect();
+    (
+        element("TotalMatches").parse().unwrap(),
+        element("UpdateID").parse().unwrap(),
+        items,
+    )
+}
+
+#[tokio::test]
+async fn paging_a_large_container_sees_every_item_once_under_a_stable_total() {
+    let temp = tempdir().expect("temporary test directory");
+    let media_root = temp.path().join("mediatest");
+    tokio::fs::create_dir_all(media_root.join("extras"))
+        .await
+        .expect("create media directories");
+    let canonical_root = media_root
+        .canonicalize()
+        .expect("canonical media directory");
+    let database = Arc::new(
+        RedbDatabase::new(temp.path().join("media.redb"))
+            .await
+            .expect("create database"),
+    );
+    database.initialize().await.expect("initialize database");
+    let mut files = (0..1000)
+        .map(|index| {
+            MediaFile::new(
+                canonical_root.join(format!("video{index:04}.mkv")),
+                5,
+                "video/x-matroska".to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+    files.push(MediaFile::new(
+        canonical_root.join("extras").join("making-of.mkv"),
+        5,
+        "video/x-matroska".to_string(),
+    ));
+    database
+        .bulk_store_media_files(&files)
+        .await
+        .expect("index videos");
+    let state = test_state(temp.path(), &media_root, database.clone()).await;
+
+    // 1000 videos and the `extras` folder, paged in uneven steps.
+    let mut seen = std::collections::HashSet::new();
+    let mut update_ids = std::collections::HashSet::new();
+    let mut start = 0;
+    loop {
+        let (total, update_id, items) =
+            page_summary(&browse(state.clone(), "video", start, 37).await);
+        assert_eq!(total, 1001);
+        update_ids.insert(update_id);
+        if start as usize >= total {
+            assert!(items.is_empty());
+            break;
+        }
+        for item in items {
+            assert!(seen.insert(item), "item listed twice");
+        }
+        start += 37;
+    }
+    assert_eq!(seen.len(), 1000);
+    assert_eq!(update_ids.len(), 1);
+    let update_id = update_ids.into_iter().next().unwrap();
+
+    // A change inside `extras` leaves the parent's UpdateID alone.
+    database
+        .store_media_file(&MediaFile::new(
+            canonical_root.join("extras").join("trailer.mkv"),
+            5,
+            "video/x-matroska".to_string(),
+        ))
+        .await
+        .expect("index trailer");
+    state
+        .content_update_id
+        .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    let (total, unchanged, _) = page_summary(&browse(state.clone(), "video", 0, 10).await);
+    assert_eq!((total, unchanged), (1001, update_id));
+
+    // A new file in the container itself changes it.
+    database
+        .store_media_file(&MediaFile::new(
+            canonical_root.join("video1000.mkv"),
+            5,
+            "video/x-matroska".to_string(),
+        ))
+        .await
+        .expect("index video");
+    state
+        .content_update_id
+        .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    let (total, changed, _) = page_summary(&browse(state, "video", 0, 10).await);
+    assert_eq!(total, 1002);
+    assert_ne!(changed, update_id);
+}
@@ -0,0 +1,126 @@
+//! One flat folder holding 100k files. Ignored by default because creating
+//! the files takes a while; run it with
+//! `cargo test --release --test large_directory_stress -- --ignored`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tempfile::tempdir;
+
+use vuio::database::redb::RedbDatabase;
+use vuio::database::{DatabaseManager, DatabaseReadSession, MediaFileQuery, MediaRepository};
+use vuio::media::MediaScanner;
+
+const FILE_COUNT: usize = 100_000;
+
+/// Heap the scan may hold on top of what it returns and what the database
+/// keeps cached. Listing the whole folder before processing it exceeds this
+/// for 100k files.
+const SCAN_WORKING_SET_BUDGET: usize = 20 * 1024 * 1024;
+
+const BROWSE_PAGE_BUDGET: Duration = Duration::from_millis(500);
+
+static LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+static PEAK_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let pointer = unsafe { System.alloc(layout) };
+        if !pointer.is_null() {
+            let live = LIVE_BYTES.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK_BYTES.fetch_max(live, Ordering::Relaxed);
+        }
+        pointer
+    }
+
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(pointer, layout) };
+        LIVE_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, pointer: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_pointer = unsafe { System.realloc(pointer, layout, new_size) };
+        if !new_pointer.is_null() {
+            LIVE_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+            let live = LIVE_BYTES.fetch_add(new_size, Ordering::Relaxed) + new_size;
+            PEAK_BYTES.fetch_max(live, Ordering::Relaxed);
+        }
+        new_pointer
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[tokio::test]
+#[ignore = "creates 100k files; run with --ignored"]
+async fn a_flat_folder_of_100k_files_scans_in_bounded_memory_and_browses_quickly() {
+    let temp = tempdir().unwrap();
+    let photos = std::fs::canonicalize(temp.path()).unwrap().join("photos");
+    std::fs::create_dir(&photos).unwrap();
+    for index in 0..FILE_COUNT {
+        std::fs::File::create(photos.join(format!("photo-{index:06}.jpg"))).unwrap();
+    }
+
+    let database = Arc::new(
+        RedbDatabase::new(temp.path().join("stress.redb"))
+            .await
+            .unwrap(),
+    );
+    database.initialize().await.unwrap();
+    let scanner = MediaScanner::with_database(database.clone());
+
+    PEAK_BYTES.store(LIVE_BYTES.load(Ordering::Relaxed), Ordering::Relaxed);
+    let result = scanner.scan_directory_recursive(&photos).await.unwrap();
+    let peak = PEAK_BYTES.load(Ordering::Relaxed);
+    let retained = LIVE_BYTES.load(Ordering::Relaxed);
+    assert_eq!(result.new_files.len(), FILE_COUNT);
+    assert!(result.complete);
+    let working_set = peak.saturating_sub(retained);
+    assert!(
+        working_set < SCAN_WORKING_SET_BUDGET,
+        "scan held {working_set} bytes beyond its result"
+    );
+    drop(result);
+
+    // A rescan compares every file with its stored fingerprint.
+    PEAK_BYTES.store(LIVE_BYTES.load(Ordering::Relaxed), Ordering::Relaxed);
+    let rescan = scanner.scan_directory_recursive(&photos).await.unwrap();
+    let working_set = PEAK_BYTES
+        .load(Ordering::Relaxed)
+        .saturating_sub(LIVE_BYTES.load(Ordering::Relaxed));
+    assert_eq!(rescan.unchanged_files.len(), FILE_COUNT);
+    assert!(
+        working_set < SCAN_WORKING_SET_BUDGET,
+        "rescan held {working_set} bytes beyond its result"
+    );
+    drop(rescan);
+
+    let directory = photos.to_string_lossy().into_owned();
+    for (offset, mime_family) in [
+        (0, None),
+        (FILE_COUNT / 2, Some("image/")),
+        (FILE_COUNT - 10, None),
+    ] {
+        let query = MediaFileQuery::Directory {
+            path: directory.clone(),
+            mime_family: mime_family.map(str::to_owned),
+        };
+        let started = Instant::now();
+        let summary = database
+            .clone()
+            .read(move |session| session.visit_files(&query, offset, 100, |_| Ok(())))
+            .await
+            .unwrap();
+        let elapsed = started.elapsed();
+        assert_eq!(summary.matched, FILE_COUNT);
+        assert_eq!(summary.visited, 100.min(FILE_COUNT - offset));
+        assert!(
+            elapsed < BROWSE_PAGE_BUDGET,
+            "browse page at {offset} took {elapsed:?}"
+        );
+    }
+}
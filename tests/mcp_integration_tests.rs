@@ -73,12 +73,17 @@ async fn make_test_state() -> (TempDir, AppState) {
         auth: Arc::new(vuio::web::auth::AuthState::testing()),
         auth_forced: false,
         platform_info: Arc::new(PlatformInfo::detect().await.unwrap()),
+        network_interfaces: Arc::default(),
         filesystem_manager: Arc::from(create_platform_filesystem_manager()),
         content_update_id: Arc::new(std::sync::atomic::AtomicU32::new(1)),
         content_change_notify: Arc::new(tokio::sync::Notify::new()),
         http_rebind_notify: Arc::new(tokio::sync::Notify::new()),
         ssdp_reload_notify: Arc::new(tokio::sync::Notify::new()),
+        playlist_mirror_notify: Arc::new(tokio::sync::Notify::new()),
         web_metrics: Arc::new(WebHandlerMetrics::new()),
+        stream_audit: None,
+        scan_control: Arc::new(vuio::scan_progress::ScanControl::new()),
+        health: Arc::new(vuio::state::ServiceHealth::default()),
         runtime_diagnostics: Arc::new(vuio::platform::diagnostics::SystemDiagnosticsSampler::new()),
         lifecycle_stats: Arc::new(vuio::lifecycle::ApplicationStats::new()),
         bookmarks: Arc::new(tokio::sync::Mutex::new(
@@ -88,6 +93,9 @@ async fn make_test_state() -> (TempDir, AppState) {
         browse_cache: Arc::new(tokio::sync::Mutex::new(
             vuio::runtime_state::BrowseResponseCache::new(),
         )),
+        hidden_files: Arc::default(),
+        active_streams: Arc::default(),
+        container_update_ids: Arc::default(),
         mcp_clients: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
         active_monitors: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
         active_casts: Arc::new(tokio::sync::Mutex::new(
@@ -122,6 +130,8 @@ async fn content_publication_and_cache_only_invalidation_have_distinct_revisions
         client_profile: DlnaClientProfile::Standard,
         content_update_id: 1,
         browse_epoch: epoch,
+        sort_criteria: String::new(),
+        origin: String::new(),
     };
     state
         .browse_cache
@@ -165,11 +175,14 @@ async fn test_mcp_initialize_and_tools_list() {
         track_number: Some(4),
         year: Some(1971),
         album_artist: None,
+        compilation: false,
+        content_hash: None,
+        missing_since: None,
         subtitle_available: false,
         created_at: std::time::SystemTime::now(),
         updated_at: std::time::SystemTime::now(),
     };
-    db.store_media_file(&audio_file).await.unwrap();
+    let song_id = db.store_media_file(&audio_file).await.unwrap();
 
     // 2. Setup mock AppState
     let config = Arc::new(AppConfig::default());
@@ -190,12 +203,17 @@ async fn test_mcp_initialize_and_tools_list() {
         auth: Arc::new(vuio::web::auth::AuthState::testing()),
         auth_forced: false,
         platform_info,
+        network_interfaces: Arc::default(),
         filesystem_manager,
         content_update_id,
         content_change_notify: Arc::new(tokio::sync::Notify::new()),
         http_rebind_notify: Arc::new(tokio::sync::Notify::new()),
         ssdp_reload_notify: Arc::new(tokio::sync::Notify::new()),
+        playlist_mirror_notify: Arc::new(tokio::sync::Notify::new()),
         web_metrics,
+        stream_audit: None,
+        scan_control: Arc::new(vuio::scan_progress::ScanControl::new()),
+        health: Arc::new(vuio::state::ServiceHealth::default()),
         runtime_diagnostics: Arc::new(vuio::platform::diagnostics::SystemDiagnosticsSampler::new()),
         lifecycle_stats: Arc::new(vuio::lifecycle::ApplicationStats::new()),
         bookmarks: Arc::new(tokio::sync::Mutex::new(
@@ -205,6 +223,9 @@ async fn test_mcp_initialize_and_tools_list() {
         browse_cache: Arc::new(tokio::sync::Mutex::new(
             vuio::runtime_state::BrowseResponseCache::new(),
         )),
+        hidden_files: Arc::default(),
+        active_streams: Arc::default(),
+        container_update_ids: Arc::default(),
         mcp_clients: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
         active_monitors: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
         active_casts: Arc::new(tokio::sync::Mutex::new(
@@ -350,7 +371,7 @@ async fn test_mcp_initialize_and_tools_list() {
             "name": "add_to_playlist",
             "arguments": {
                 "playlist_id": playlist_id,
-                "media_file_ids": [1] // The song we added has ID 1 (inserted into database)
+                "media_file_ids": [song_id]
             }
         }
     });
@@ -516,3 +537,196 @@ async fn post_endpoints_enforce_tiered_body_limits() {
         .unwrap();
     assert_ne!(small_soap.status(), StatusCode::PAYLOAD_TOO_LARGE);
 }
+
+#[tokio::test]
+async fn api_https_only_keeps_the_api_off_the_plain_http_listener() {
+    let (_temp, state) = make_test_state().await;
+    let mut config = (*state.current_config()).clone();
+    config.server.api_https_only = true;
+    state.live_config.store(Arc::new(config));
+    let router = create_router(state);
+
+    let request = |path: &str, over_tls: bool| {
+        let mut request = Request::get(path)
+            .extension(ConnectInfo(test_peer()))
+            .header(
+                "authorization",
+                "Bearer test-management-token-which-is-long-enough",
+            );
+        if over_tls {
+            request = request.extension(vuio::web::tls::ServedOverTls);
+        }
+        request.body(Body::empty()).unwrap()
+    };
+
+    for path in ["/api/stats", "/login"] {
+        let plain = router.clone().oneshot(request(path, false)).await.unwrap();
+        assert_eq!(plain.status(), StatusCode::FORBIDDEN, "{path}");
+        let tls = router.clone().oneshot(request(path, true)).await.unwrap();
+        assert_eq!(tls.status(), StatusCode::OK, "{path}");
+    }
+    let description = router
+        .oneshot(request("/description.xml", false))
+        .await
+        .unwrap();
+    assert_eq!(description.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn trusted_proxies_forward_the_client_address_to_the_allowlist() {
+    let (_temp, state) = make_test_state().await;
+    let request = |peer: &str, forwarded_for: &str| {
+        Request::get("/api/stats")
+            .extension(ConnectInfo(peer.parse::<SocketAddr>().unwrap()))
+            .header("x-forwarded-for", forwarded_for)
+            .header(
+                "authorization",
+                "Bearer test-management-token-which-is-long-enough",
+            )
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    // Without trusted proxies the header is ignored and the loopback peer is
+    // allowed through.
+    let router = create_router(state.clone());
+    let response = router
+        .oneshot(request("127.0.0.1:40000", "203.0.113.9"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mut config = (*state.current_config()).clone();
+    config.server.trusted_proxies = vec!["127.0.0.0/8".to_string()];
+    state.live_config.store(Arc::new(config));
+    let router = create_router(state);
+
+    let proxied = router
+        .clone()
+        .oneshot(request("127.0.0.1:40000", "203.0.113.9"))
+        .await
+        .unwrap();
+    assert_eq!(proxied.status(), StatusCode::FORBIDDEN);
+
+    // An untrusted peer cannot claim to be loopback.
+    let spoofed = router
+        .oneshot(request("203.0.113.9:40000", "127.0.0.1"))
+        .await
+        .unwrap();
+    assert_eq!(spoofed.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn base_url_overrides_the_advertised_origin() {
+    let (_temp, state) = make_test_state().await;
+    let mut config = (*state.current_config()).clone();
+    config.server.base_url = Some("http://192.168.1.20:8088/".to_string());
+    state.live_config.store(Arc::new(config));
+    assert_eq!(state.advertised_http_origin(), "http://192.168.1.20:8088");
+
+    let response = create_router(state)
+        .oneshot(
+            Request::get("/description.xml")
+                .extension(ConnectInfo(test_peer()))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert!(String::from_utf8_lossy(&body).contains("<URLBase>http://192.168.1.20:8088/</URLBase>"));
+}
+
+#[tokio::test]
+async fn browse_gets_revalidate_until_the_system_update_id_changes() {
+    let (_temp, state) = make_test_state().await;
+    let router = create_router(state.clone());
+    let browse = |etag: Option<&str>| {
+        let mut request = Request::get("/control/ContentDirectory")
+            .extension(ConnectInfo(test_peer()))
+            .header(
+                "soapaction",
+                "\"urn:schemas-upnp-org:service:ContentDirectory:1#Browse\"",
+            );
+        if let Some(etag) = etag {
+            request = request.header("if-none-match", etag);
+        }
+        request
+            .body(Body::from(
+                "<s:Envelope><s:Body><u:Browse><ObjectID>0</ObjectID>\
+                 <BrowseFlag>BrowseDirectChildren</BrowseFlag></u:Browse></s:Body></s:Envelope>",
+            ))
+            .unwrap()
+    };
+
+    let first = router.clone().oneshot(browse(None)).await.unwrap();
+    assert_eq!(first.status(), StatusCode::OK);
+    let etag = first.headers()["etag"].to_str().unwrap().to_owned();
+    assert!(etag.starts_with("\"1-"), "{etag}");
+
+    let cached = router.clone().oneshot(browse(Some(&etag))).await.unwrap();
+    assert_eq!(cached.status(), StatusCode::NOT_MODIFIED);
+
+    state
+        .content_update_id
+        .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    let changed = router.oneshot(browse(Some(&etag))).await.unwrap();
+    assert_eq!(changed.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn duplicates_are_listed_for_cleanup_and_hidden_from_search() {
+    let (_temp, state) = make_test_state().await;
+    let copy = |path: &str| {
+        let mut file = MediaFile::new(PathBuf::from(path), 10, "audio/flac".to_string());
+        file.content_hash = Some(42);
+        file
+    };
+    let ids = state
+        .database
+        .bulk_store_media_files(&[copy("/a/song.flac"), copy("/b/song.flac")])
+        .await
+        .unwrap();
+    let get = |uri: &str| {
+        Request::get(uri)
+            .extension(ConnectInfo(test_peer()))
+            .header(
+                "authorization",
+                "Bearer test-management-token-which-is-long-enough",
+            )
+            .body(Body::empty())
+            .unwrap()
+    };
+    let json = |response: axum::response::Response| async move {
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+    };
+
+    let router = create_router(state.clone());
+    let visible = json(router.clone().oneshot(get("/api/media")).await.unwrap()).await;
+    assert_eq!(visible["files"].as_array().unwrap().len(), 2);
+
+    let mut config = (*state.current_config()).clone();
+    config.media.hide_duplicates = true;
+    state.live_config.store(Arc::new(config));
+    let deduplicated = json(router.clone().oneshot(get("/api/media")).await.unwrap()).await;
+    let files = deduplicated["files"].as_array().unwrap();
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0]["id"], ids[0]);
+
+    let groups = json(
+        router
+            .oneshot(get("/api/library/duplicates"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(groups["groups"][0]["content_hash"], "000000000000002a");
+    assert_eq!(groups["groups"][0]["files"][1]["path"], "/b/song.flac");
+}
@@ -1,17 +1,19 @@
-use axum::extract::State;
-use axum::http::StatusCode;
+use axum::extract::{ConnectInfo, State};
+use axum::http::{HeaderMap, StatusCode};
 use axum::response::IntoResponse;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tempfile::tempdir;
 
-use vuio::config::{AppConfig, MonitoredDirectoryConfig, ValidationMode};
+use vuio::config::{AppConfig, MonitoredDirectoryConfig, ValidationMode, WatchMode};
 use vuio::database::redb::RedbDatabase;
 use vuio::database::{DatabaseManager, MediaFile, MediaRepository, PlaylistRepository};
 use vuio::platform::filesystem::create_platform_filesystem_manager;
 use vuio::platform::PlatformInfo;
 use vuio::state::AppState;
-use vuio::web::diagnostics::{get_prometheus_metrics, get_web_metrics, WebHandlerMetrics};
+use vuio::web::diagnostics::{
+    get_prometheus_metrics, get_stats_handler, get_web_metrics, health_handler, WebHandlerMetrics,
+};
 
 #[tokio::test]
 async fn test_metrics_endpoints_data() {
@@ -57,6 +59,12 @@ async fn test_metrics_endpoints_data() {
         extensions: None,
         exclude_patterns: None,
         validation_mode: ValidationMode::Warn,
+        watch_mode: WatchMode::Auto,
+        merge_group: None,
+        priority: 0,
+        media_types: None,
+        min_file_size: None,
+        min_file_age_seconds: None,
     }];
     let config = Arc::new(config);
     let platform_info = Arc::new(PlatformInfo::detect().await.unwrap());
@@ -84,12 +92,17 @@ async fn test_metrics_endpoints_data() {
         auth: Arc::new(vuio::web::auth::AuthState::testing()),
         auth_forced: false,
         platform_info,
+        network_interfaces: Arc::default(),
         filesystem_manager,
         content_update_id,
         content_change_notify: Arc::new(tokio::sync::Notify::new()),
         http_rebind_notify: Arc::new(tokio::sync::Notify::new()),
         ssdp_reload_notify: Arc::new(tokio::sync::Notify::new()),
+        playlist_mirror_notify: Arc::new(tokio::sync::Notify::new()),
         web_metrics,
+        stream_audit: None,
+        scan_control: Arc::new(vuio::scan_progress::ScanControl::new()),
+        health: Arc::new(vuio::state::ServiceHealth::default()),
         runtime_diagnostics: Arc::new(vuio::platform::diagnostics::SystemDiagnosticsSampler::new()),
         lifecycle_stats: Arc::new(vuio::lifecycle::ApplicationStats::new()),
         bookmarks: Arc::new(tokio::sync::Mutex::new(
@@ -99,6 +112,9 @@ async fn test_metrics_endpoints_data() {
         browse_cache: Arc::new(tokio::sync::Mutex::new(
             vuio::runtime_state::BrowseResponseCache::new(),
         )),
+        hidden_files: Arc::default(),
+        active_streams: Arc::default(),
+        container_update_ids: Arc::default(),
         mcp_clients: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
         active_monitors: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
         active_casts: Arc::new(tokio::sync::Mutex::new(
@@ -150,7 +166,83 @@ async fn test_metrics_endpoints_data() {
     assert_eq!(runtime["snapshot"]["process"]["pid"], std::process::id());
     assert!(!body_str.contains(private_media_path.to_string_lossy().as_ref()));
 
-    // 4. Test get_prometheus_metrics handler (exposition text format)
+    // 4. Test the library stats endpoint. A scan recorded as running by a
+    // process that is gone is reported as interrupted.
+    let mut scan = vuio::scan_progress::ScanState::new(vec!["/media".to_string()]);
+    scan.files_scanned = 3;
+    scan.save(app_state.database.as_ref()).await.unwrap();
+    let stats_resp = get_stats_handler(State(app_state.clone()))
+        .await
+        .into_response();
+    assert_eq!(stats_resp.status(), StatusCode::OK);
+    let stats_bytes = axum::body::to_bytes(stats_resp.into_body(), 10000)
+        .await
+        .unwrap();
+    let stats: serde_json::Value = serde_json::from_slice(&stats_bytes).unwrap();
+    assert_eq!(stats["backend"], "redb");
+    assert_eq!(stats["database"]["total_files"], 3);
+    assert_eq!(
+        stats["media_types"],
+        serde_json::json!({"videos": 1, "songs": 1, "pictures": 1, "other": 0})
+    );
+    assert_eq!(stats["last_scan"]["status"], "interrupted");
+    assert_eq!(stats["last_scan"]["files_scanned"], 3);
+    assert_eq!(stats["watcher"]["events_handled"], 0);
+
+    // 5. Test the container health probe through startup and a failed
+    // database check.
+    let health_body = |state, peer: &str, headers| {
+        let peer = peer.parse().unwrap();
+        async move {
+            let response = health_handler(State(state), ConnectInfo(peer), headers)
+                .await
+                .into_response();
+            let status = response.status();
+            let body = axum::body::to_bytes(response.into_body(), 1000)
+                .await
+                .unwrap();
+            (
+                status,
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+            )
+        }
+    };
+    let health_status = |state| async move {
+        let (status, body) = health_body(state, "192.0.2.10:40000", HeaderMap::new()).await;
+        (status, body["status"].as_str().unwrap().to_owned())
+    };
+    assert_eq!(
+        health_status(app_state.clone()).await,
+        (StatusCode::SERVICE_UNAVAILABLE, "starting".to_owned())
+    );
+    app_state.health.mark_serving();
+    assert_eq!(
+        health_status(app_state.clone()).await,
+        (StatusCode::OK, "ok".to_owned())
+    );
+    // Only management callers see the log filter.
+    let mut management = HeaderMap::new();
+    management.insert(
+        axum::http::header::AUTHORIZATION,
+        "Bearer test-management-token-which-is-long-enough"
+            .parse()
+            .unwrap(),
+    );
+    let (_, anonymous) = health_body(app_state.clone(), "127.0.0.1:40000", HeaderMap::new()).await;
+    assert!(anonymous.get("log_filter").is_none());
+    let (_, remote) = health_body(app_state.clone(), "192.0.2.10:40000", management.clone()).await;
+    assert!(remote.get("log_filter").is_none());
+    let (_, local) = health_body(app_state.clone(), "127.0.0.1:40000", management).await;
+    assert!(local.get("log_filter").is_some());
+    app_state
+        .health
+        .record_database_failure("media table unreadable".to_owned());
+    assert_eq!(
+        health_status(app_state.clone()).await,
+        (StatusCode::SERVICE_UNAVAILABLE, "unhealthy".to_owned())
+    );
+
+    // 6. Test get_prometheus_metrics handler (exposition text format)
     let prom_resp = get_prometheus_metrics(State(app_state))
         .await
         .into_response();
@@ -5,7 +5,8 @@
 
 use std::path::Path;
 use vuio::platform::filesystem::{
-    create_platform_path_normalizer, FileSystemManager, PathNormalizer, WindowsPathNormalizer,
+    create_platform_path_normalizer, display_path, extended_length_path, FileSystemManager,
+    PathNormalizer, WindowsPathNormalizer,
 };
 
 #[cfg(test)]
@@ -91,9 +92,10 @@ mod path_normalization_integration_tests {
         let normalizer = WindowsPathNormalizer::new();
 
         let extended_test_cases = vec![
-            // Extended-length paths - current implementation may not handle these specially
+            // Extended-length and device paths share the plain form
             (r"\\?\C:\Users\Media", "c:/users/media"),
             (r"\\?\c:\users\media", "c:/users/media"),
+            (r"\\.\C:\Users\Media", "c:/users/media"),
             (
                 r"\\?\D:\Very\Long\Path\To\Media\Files",
                 "d:/very/long/path/to/media/files",
@@ -101,32 +103,129 @@ mod path_normalization_integration_tests {
             // Extended-length UNC paths
             (r"\\?\UNC\Server\Share\Media", "//server/share/media"),
             (r"\\?\UNC\server\share\media", "//server/share/media"),
+            (r"\\?\unc\Server\Share/Media", "//server/share/media"),
         ];
 
-        for (input, _expected) in extended_test_cases {
+        for (input, expected) in extended_test_cases {
             let result = normalizer.to_canonical(Path::new(input));
+            assert!(
+                result.is_ok(),
+                "Failed to normalize extended-length path: {}",
+                input
+            );
+            assert_eq!(
+                result.unwrap(),
+                expected,
+                "Extended-length path normalization mismatch for: {}",
+                input
+            );
+        }
 
-            // Current implementation may not handle extended-length paths specially
-            // This test documents the expected behavior for future implementation
-            match result {
-                Ok(canonical) => {
-                    println!(
-                        "Extended-length path normalized: {} -> {}",
-                        input, canonical
-                    );
-                    // If normalization succeeds, verify it produces reasonable output
-                    assert!(!canonical.is_empty());
-                    // Note: May not match expected exactly if extended-length handling isn't implemented
-                }
-                Err(e) => {
-                    println!(
-                        "Extended-length path normalization failed (may be expected): {} -> {:?}",
-                        input, e
-                    );
-                    // This is acceptable if extended-length path support isn't implemented yet
-                }
-            }
+        // `?` is only accepted as part of the prefix
+        assert!(normalizer
+            .to_canonical(Path::new(r"\\?\C:\Media?"))
+            .is_err());
+    }
+
+    /// Test that every spelling of a share lands under the same prefix, the
+    /// way the database compares paths when removing a deleted directory
+    #[test]
+    fn test_mixed_slash_paths_share_a_prefix() {
+        let normalizer = WindowsPathNormalizer::new();
+        let root = normalizer
+            .to_canonical(Path::new(r"\\NAS\Share\Movies"))
+            .unwrap();
+
+        for input in [
+            r"\\?\UNC\nas\share\Movies\Film.mkv",
+            r"//NAS/Share/Movies\Film.mkv",
+            r"\\nas/share\\movies//film.mkv",
+        ] {
+            let canonical = normalizer.to_canonical(Path::new(input)).unwrap();
+            assert_eq!(canonical, "//nas/share/movies/film.mkv", "{}", input);
+            assert!(canonical.starts_with(&format!("{root}/")));
+        }
+        let sibling = normalizer
+            .to_canonical(Path::new(r"\\NAS\Share\Movies2\Film.mkv"))
+            .unwrap();
+        assert!(!sibling.starts_with(&format!("{root}/")));
+    }
+
+    /// Test that extended-length spellings round-trip to the plain form shown
+    /// in logs and DIDL output
+    #[test]
+    fn test_extended_length_spelling_round_trip() {
+        let normalizer = WindowsPathNormalizer::new();
+        let cases = [
+            (
+                r"C:\Anime\Series",
+                r"\\?\C:\Anime\Series",
+                r"C:\Anime\Series",
+            ),
+            (
+                r"d:/Anime/./Series//",
+                r"\\?\d:\Anime\Series",
+                r"d:\Anime\Series",
+            ),
+            (
+                r"\\nas\share\Anime",
+                r"\\?\UNC\nas\share\Anime",
+                r"\\nas\share\Anime",
+            ),
+            (
+                r"//nas/share/Anime",
+                r"\\?\UNC\nas\share\Anime",
+                r"\\nas\share\Anime",
+            ),
+        ];
+
+        for (input, extended, display) in cases {
+            let extended_path = extended_length_path(Path::new(input));
+            assert_eq!(extended_path, Path::new(extended), "{}", input);
+            assert_eq!(extended_length_path(&extended_path), extended_path);
+            assert_eq!(
+                display_path(&extended_path),
+                Path::new(display),
+                "{}",
+                input
+            );
+            assert_eq!(
+                normalizer.to_canonical(&extended_path).unwrap(),
+                normalizer.to_canonical(Path::new(display)).unwrap()
+            );
+        }
+
+        assert_eq!(
+            display_path(Path::new(r"\\?\unc\nas\share")),
+            Path::new(r"\\nas\share")
+        );
+        assert_eq!(
+            display_path(Path::new(r"\\.\C:\Anime")),
+            Path::new(r"C:\Anime")
+        );
+
+        // Relative paths and paths without a plain spelling are left alone
+        for unchanged in [
+            r"Anime\Series",
+            r"\\?\Volume{0b1d5c5e-0000-0000-0000-100000000000}\Anime",
+            r"\\.\PhysicalDrive0",
+        ] {
+            assert_eq!(display_path(Path::new(unchanged)), Path::new(unchanged));
         }
+        assert_eq!(
+            extended_length_path(Path::new(r"Anime\Series")),
+            Path::new(r"Anime\Series")
+        );
+
+        // The canonical form of a long extended-length path converts back to
+        // the plain spelling
+        let long_share = format!(r"\\?\UNC\nas\share{}", r"\Season 01".repeat(30));
+        assert!(long_share.len() > 300);
+        let canonical = normalizer.to_canonical(Path::new(&long_share)).unwrap();
+        assert_eq!(
+            normalizer.canonical_to_platform(&canonical).unwrap(),
+            Path::new(&format!(r"\\nas\share{}", r"\season 01".repeat(30)))
+        );
     }
 
     /// Test Unicode character handling in paths
@@ -668,6 +767,45 @@ mod filesystem_integration_tests {
         }
     }
 
+    /// Test that a directory past the Windows MAX_PATH limit scans and reads
+    /// like any other, and reports plain paths
+    #[tokio::test]
+    async fn test_paths_longer_than_max_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let fs_manager = create_platform_filesystem_manager();
+
+        let mut deep_path = temp_dir.path().to_path_buf();
+        for season in 1..=12 {
+            deep_path.push(format!("[Fansub] Series Title - Season {season:02}"));
+        }
+        fs::create_dir_all(&deep_path).unwrap();
+        let test_file = deep_path.join("[Fansub] Series Title - 01 [1080p].mkv");
+        fs::write(&test_file, b"long path content").unwrap();
+        assert!(test_file.to_string_lossy().len() > 300);
+
+        fs_manager.validate_path(&deep_path).unwrap();
+        assert!(fs_manager.is_accessible(&deep_path).await);
+
+        let media_files = fs_manager.scan_media_directory(&deep_path).await.unwrap();
+        assert_eq!(media_files.len(), 1);
+        assert_eq!(media_files[0].path, test_file);
+        assert_eq!(
+            media_files[0].filename,
+            "[Fansub] Series Title - 01 [1080p].mkv"
+        );
+
+        let info = fs_manager.get_file_info(&test_file).await.unwrap();
+        assert_eq!(info.size, b"long path content".len() as u64);
+
+        let canonical = fs_manager.canonicalize_path(&test_file).await.unwrap();
+        assert!(!canonical.contains('?'), "{}", canonical);
+        let platform_path = create_platform_path_normalizer()
+            .canonical_to_platform(&canonical)
+            .unwrap();
+        assert!(!platform_path.to_string_lossy().starts_with(r"\\?\"));
+        assert!(platform_path.exists());
+    }
+
     /// Test path normalization with special characters in filenames
     #[tokio::test]
     async fn test_special_characters_in_paths() {
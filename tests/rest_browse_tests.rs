@@ -0,0 +1,364 @@
+use axum::extract::{Path, Query, State};
+use std::sync::Arc;
+use tempfile::tempdir;
+use vuio::{
+    config::{AppConfig, MonitoredDirectoryConfig, ValidationMode, WatchMode},
+    database::{redb::RedbDatabase, DatabaseManager, MediaFile, MediaRepository},
+    error::AppError,
+    lifecycle::ApplicationStats,
+    platform::{
+        diagnostics::SystemDiagnosticsSampler, filesystem::create_platform_filesystem_manager,
+        PlatformInfo,
+    },
+    runtime_state::{
+        ActiveCastRegistry, BookmarkRegistry, BrowseResponseCache, RendererCache,
+        BOOKMARK_MAX_ENTRIES,
+    },
+    state::AppState,
+    web::{
+        browse::{browse_handler, item_handler, BrowseQuery},
+        diagnostics::WebHandlerMetrics,
+    },
+};
+
+fn monitored(path: &std::path::Path) -> MonitoredDirectoryConfig {
+    MonitoredDirectoryConfig {
+        path: path.to_string_lossy().into_owned(),
+        recursive: true,
+        case_sensitive: None,
+        extensions: None,
+        exclude_patterns: None,
+        validation_mode: ValidationMode::Warn,
+        watch_mode: WatchMode::Auto,
+        merge_group: None,
+        priority: 0,
+        media_types: None,
+        min_file_size: None,
+        min_file_age_seconds: None,
+    }
+}
+
+async fn browse(state: &AppState, path: &str) -> vuio::web::browse::BrowseListing {
+    let query: BrowseQuery = serde_json::from_value(serde_json::json!({ "path": path })).unwrap();
+    browse_handler(State(state.clone()), Query(query))
+        .await
+        .expect("browse listing")
+        .0
+}
+
+async fn test_state(
+    temp: &std::path::Path,
+    directories: Vec<MonitoredDirectoryConfig>,
+    database: Arc<RedbDatabase>,
+) -> AppState {
+    let mut config = AppConfig::default();
+    config.media.directories = directories.clone();
+    let config = Arc::new(config);
+    AppState {
+        config: config.clone(),
+        live_config: Arc::new(vuio::state::LiveConfig::new(config.clone())),
+        desired_config: Arc::new(vuio::state::LiveConfig::new(config.clone())),
+        config_reload_errors: Arc::new(std::sync::RwLock::new(Vec::new())),
+        pending_restart_fields: Arc::new(std::sync::RwLock::new(Vec::new())),
+        media_directories: Arc::new(tokio::sync::RwLock::new(directories)),
+        unavailable_roots: Arc::new(tokio::sync::RwLock::new(std::collections::HashSet::new())),
+        database,
+        auth: Arc::new(vuio::web::auth::AuthState::testing()),
+        auth_forced: false,
+        platform_info: Arc::new(PlatformInfo::detect().await.expect("detect platform")),
+        network_interfaces: Arc::default(),
+        filesystem_manager: Arc::from(create_platform_filesystem_manager()),
+        content_update_id: Arc::new(std::sync::atomic::AtomicU32::new(1)),
+        content_change_notify: Arc::new(tokio::sync::Notify::new()),
+        http_rebind_notify: Arc::new(tokio::sync::Notify::new()),
+        ssdp_reload_notify: Arc::new(tokio::sync::Notify::new()),
+        playlist_mirror_notify: Arc::new(tokio::sync::Notify::new()),
+        web_metrics: Arc::new(WebHandlerMetrics::new()),
+        stream_audit: None,
+        scan_control: Arc::new(vuio::scan_progress::ScanControl::new()),
+        health: Arc::new(vuio::state::ServiceHealth::default()),
+        runtime_diagnostics: Arc::new(SystemDiagnosticsSampler::new()),
+        lifecycle_stats: Arc::new(ApplicationStats::new()),
+        bookmarks: Arc::new(tokio::sync::Mutex::new(BookmarkRegistry::new(
+            BOOKMARK_MAX_ENTRIES,
+        ))),
+        log_file_path: temp.join("vuio.log"),
+        browse_cache: Arc::new(tokio::sync::Mutex::new(BrowseResponseCache::new())),
+        hidden_files: Arc::default(),
+        active_streams: Arc::default(),
+        container_update_ids: Arc::default(),
+        mcp_clients: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
+        active_monitors: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
+        active_casts: Arc::new(tokio::sync::Mutex::new(ActiveCastRegistry::new())),
+        cast_sessions: Arc::new(tokio::sync::Mutex::new(
+            vuio::runtime_state::CastSessionRegistry::new(),
+        )),
+        discovered_tvs: Arc::new(RendererCache::new()),
+        discovery_service: Arc::new(vuio::discovery::DiscoveryService::default()),
+        upnp_subscriptions: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
+        cancellation: tokio_util::sync::CancellationToken::new(),
+        background_tasks: tokio_util::task::TaskTracker::new(),
+    }
+}
+
+#[tokio::test]
+async fn rest_browse_mirrors_the_folder_tree_with_stream_urls() {
+    let temp = tempdir().expect("temporary test directory");
+    let music = temp.path().join("music");
+    let videos = temp.path().join("videos");
+    tokio::fs::create_dir_all(music.join("Jazz")).await.unwrap();
+    tokio::fs::create_dir_all(&videos).await.unwrap();
+    let track_path = music
+        .canonicalize()
+        .unwrap()
+        .join("Jazz")
+        .join("so-what.mp3");
+    tokio::fs::write(&track_path, b"mp3").await.unwrap();
+
+    let database = Arc::new(
+        RedbDatabase::new(temp.path().join("media.redb"))
+            .await
+            .expect("create database"),
+    );
+    database.initialize().await.expect("initialize database");
+    let mut track = MediaFile::new(track_path.clone(), 3, "audio/mpeg".to_string());
+    track.title = Some("So What".to_string());
+    track.artist = Some("Miles Davis".to_string());
+    let track_id = database.store_media_file(&track).await.unwrap();
+
+    let directories = vec![monitored(&music), monitored(&videos)];
+    let state = test_state(temp.path(), directories, database).await;
+
+    let root = browse(&state, "0").await;
+    let root_ids = root
+        .containers
+        .iter()
+        .map(|container| container.id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(root_ids, ["video", "audio/folders", "image"]);
+
+    let music_root = browse(&state, "audio/folders").await;
+    assert_eq!(music_root.parent.as_deref(), Some("0"));
+    assert_eq!(music_root.containers[0].id, "audio/folders/d0");
+    assert_eq!(music_root.containers[0].title, "music");
+    assert_eq!(music_root.containers[1].id, "audio/folders/d1");
+
+    let first_root = browse(&state, "audio/folders/d0").await;
+    assert_eq!(first_root.containers.len(), 1);
+    assert_eq!(first_root.containers[0].id, "audio/folders/d0/Jazz");
+    assert!(first_root.items.is_empty());
+
+    let jazz = browse(&state, "audio/folders/d0/Jazz").await;
+    assert_eq!(jazz.parent.as_deref(), Some("audio/folders/d0"));
+    assert_eq!(jazz.items.len(), 1);
+    let item = &jazz.items[0];
+    assert_eq!(item.title, "So What");
+    assert_eq!(item.artist.as_deref(), Some("Miles Davis"));
+    assert_eq!(item.stream_url, format!("/media/{}", item.id));
+    assert_eq!(
+        item.album_art_url.as_deref(),
+        Some(format!("/media/{}/cover", item.id).as_str())
+    );
+    assert!(item.subtitle_url.is_none());
+
+    // The video family does not list the audio track.
+    assert!(browse(&state, "video/d0/Jazz").await.items.is_empty());
+
+    let by_object_id = item_handler(State(state.clone()), Path(item.id.clone()))
+        .await
+        .expect("item by object id");
+    assert_eq!(by_object_id.stream_url, item.stream_url);
+    let by_file_id = item_handler(State(state.clone()), Path(track_id.to_string()))
+        .await
+        .expect("item by file id");
+    assert_eq!(by_file_id.id, item.id);
+
+    assert!(matches!(
+        item_handler(State(state.clone()), Path("missing".to_string())).await,
+        Err(AppError::NotFound)
+    ));
+    let query: BrowseQuery =
+        serde_json::from_value(serde_json::json!({ "path": "audio/artists" })).unwrap();
+    assert!(matches!(
+        browse_handler(State(state), Query(query)).await,
+        Err(AppError::NotFound)
+    ));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn filenames_that_are_not_utf8_are_scanned_browsed_and_streamed() {
+    use axum::{
+        extract::ConnectInfo,
+        http::{HeaderMap, Method, StatusCode},
+        response::IntoResponse,
+    };
+    use std::{ffi::OsString, os::unix::ffi::OsStringExt};
+    use vuio::{media::MediaScanner, web::streaming::serve_media};
+
+    let temp = tempdir().expect("temporary test directory");
+    let music = temp.path().join("music");
+    // Latin-1 spellings, as left behind by old rippers and Samba shares.
+    let album = music.join(OsString::from_vec(b"Caf\xe9 Society".to_vec()));
+    tokio::fs::create_dir_all(&album).await.unwrap();
+    let track_path = album.join(OsString::from_vec(b"d\xe9j\xe0 vu.mp3".to_vec()));
+    tokio::fs::write(&track_path, b"not really an mp3")
+        .await
+        .unwrap();
+
+    let database = Arc::new(
+        RedbDatabase::new(temp.path().join("media.redb"))
+            .await
+            .expect("create database"),
+    );
+    database.initialize().await.expect("initialize database");
+    MediaScanner::with_database(database.clone())
+        .scan_directory_recursive(&music)
+        .await
+        .expect("scan music");
+    let state = test_state(temp.path(), vec![monitored(&music)], database.clone()).await;
+
+    let stored = database.collect_all_media_files().await.unwrap();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].path, track_path.canonicalize().unwrap());
+    assert_eq!(stored[0].filename, "d\u{FFFD}j\u{FFFD} vu.mp3");
+
+    let root = browse(&state, "audio/folders/d0").await;
+    assert_eq!(root.containers.len(), 1);
+    assert_eq!(root.containers[0].title, "Caf\u{FFFD} Society");
+    let listing = browse(&state, &root.containers[0].id).await;
+    assert_eq!(listing.items.len(), 1);
+    let item = &listing.items[0];
+    assert_eq!(item.stream_url, format!("/media/{}", item.id));
+
+    let response = serve_media(
+        State(state.clone()),
+        ConnectInfo("127.0.0.1:50123".parse().unwrap()),
+        Path(item.id.clone()),
+        Method::GET,
+        HeaderMap::new(),
+    )
+    .await
+    .expect("stream by object id")
+    .into_response();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(&body[..], b"not really an mp3");
+}
+
+#[tokio::test]
+async fn the_dashboard_can_be_turned_off_without_losing_the_api() {
+    use axum::{http::StatusCode, response::IntoResponse};
+    use vuio::web::ui::root_handler;
+
+    let temp = tempdir().expect("temporary test directory");
+    let music = temp.path().join("music");
+    tokio::fs::create_dir_all(&music).await.unwrap();
+    let database = Arc::new(
+        RedbDatabase::new(temp.path().join("media.redb"))
+            .await
+            .expect("create database"),
+    );
+    database.initialize().await.expect("initialize database");
+    let state = test_state(temp.path(), vec![monitored(&music)], database).await;
+
+    let page = root_handler(State(state.clone())).await.into_response();
+    assert_eq!(page.status(), StatusCode::OK);
+
+    let mut config = (*state.current_config()).clone();
+    config.server.web_dashboard = false;
+    state.live_config.store(Arc::new(config));
+    assert!(matches!(
+        root_handler(State(state.clone())).await,
+        Err(AppError::NotFound)
+    ));
+    assert_eq!(browse(&state, "0").await.containers.len(), 3);
+}
+
+#[tokio::test]
+async fn playlist_export_route_downloads_an_attachment() {
+    use tower::ServiceExt;
+    use vuio::database::PlaylistRepository;
+
+    let temp = tempdir().expect("temporary test directory");
+    let music = temp.path().join("music");
+    tokio::fs::create_dir_all(&music).await.unwrap();
+    let track_path = music.canonicalize().unwrap().join("café.mp3");
+    tokio::fs::write(&track_path, b"mp3").await.unwrap();
+
+    let database = Arc::new(
+        RedbDatabase::new(temp.path().join("media.redb"))
+            .await
+            .expect("create database"),
+    );
+    database.initialize().await.expect("initialize database");
+    let mut track = MediaFile::new(track_path.clone(), 3, "audio/mpeg".to_string());
+    track.title = Some("Café".to_string());
+    track.artist = Some("Miles Davis".to_string());
+    track.duration = Some(std::time::Duration::from_secs(215));
+    let track_id = database.store_media_file(&track).await.unwrap();
+    let playlist_id = database.create_playlist("Road Trip", None).await.unwrap();
+    database
+        .add_to_playlist(playlist_id, track_id, None)
+        .await
+        .unwrap();
+
+    let state = test_state(temp.path(), vec![monitored(&music)], database).await;
+    let router = vuio::web::create_router(state);
+    let export = |query: &str| {
+        axum::http::Request::get(format!("/api/playlists/{playlist_id}/export{query}"))
+            .extension(axum::extract::ConnectInfo(
+                "127.0.0.1:43123".parse::<std::net::SocketAddr>().unwrap(),
+            ))
+            .header(
+                "authorization",
+                "Bearer test-management-token-which-is-long-enough",
+            )
+            .body(axum::body::Body::empty())
+            .unwrap()
+    };
+
+    let response = router.clone().oneshot(export("")).await.unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let headers = response.headers().clone();
+    assert_eq!(
+        headers[axum::http::header::CONTENT_TYPE],
+        "audio/x-mpegurl; charset=utf-8"
+    );
+    assert_eq!(
+        headers[axum::http::header::CONTENT_DISPOSITION],
+        "attachment; filename=\"Road Trip.m3u8\"; filename*=UTF-8''Road%20Trip%2Em3u8"
+    );
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(
+        std::str::from_utf8(&body).unwrap(),
+        format!(
+            "#EXTM3U\n#EXTINF:215,Miles Davis - Café\n{}\n",
+            track_path.display()
+        )
+    );
+
+    let latin1 = router
+        .clone()
+        .oneshot(export("?format=m3u&encoding=latin1"))
+        .await
+        .unwrap();
+    assert_eq!(latin1.status(), axum::http::StatusCode::OK);
+    assert_eq!(
+        latin1.headers()[axum::http::header::CONTENT_TYPE],
+        "audio/x-mpegurl; charset=iso-8859-1"
+    );
+    let body = axum::body::to_bytes(latin1.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert!(body
+        .windows(b"Caf\xe9\n".len())
+        .any(|window| window == b"Caf\xe9\n"));
+
+    let unknown = router.oneshot(export("?format=wpl")).await.unwrap();
+    assert_eq!(unknown.status(), axum::http::StatusCode::BAD_REQUEST);
+}
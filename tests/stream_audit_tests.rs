@@ -0,0 +1,220 @@
+use axum::extract::{ConnectInfo, Path, State};
+use axum::http::{header, HeaderMap, HeaderValue, Method, StatusCode};
+use axum::response::IntoResponse;
+use std::sync::Arc;
+use tempfile::tempdir;
+
+use vuio::config::{AppConfig, MonitoredDirectoryConfig, ValidationMode, WatchMode};
+use vuio::database::redb::RedbDatabase;
+use vuio::database::{DatabaseManager, MediaFile, MediaRepository};
+use vuio::platform::filesystem::create_platform_filesystem_manager;
+use vuio::platform::PlatformInfo;
+use vuio::state::AppState;
+use vuio::web::audit::StreamAuditLog;
+use vuio::web::diagnostics::WebHandlerMetrics;
+use vuio::web::streaming::serve_media;
+
+struct Fixture {
+    app_state: AppState<RedbDatabase>,
+    file_id: i64,
+    media_path: std::path::PathBuf,
+    audit_path: std::path::PathBuf,
+    stream_audit: Arc<StreamAuditLog>,
+}
+
+async fn fixture(temp_dir: &std::path::Path, max_concurrent_streams: usize) -> Fixture {
+    let media_dir = std::fs::canonicalize(temp_dir).unwrap().join("media");
+    std::fs::create_dir_all(&media_dir).unwrap();
+    let media_path = media_dir.join("song.mp3");
+    std::fs::write(&media_path, vec![7u8; 4096]).unwrap();
+
+    let db = Arc::new(
+        RedbDatabase::new(temp_dir.join("audit.redb"))
+            .await
+            .unwrap(),
+    );
+    db.initialize().await.unwrap();
+    let file_id = db
+        .store_media_file(&MediaFile::new(
+            media_path.clone(),
+            4096,
+            "audio/mpeg".to_string(),
+        ))
+        .await
+        .unwrap();
+
+    let mut config = AppConfig::default();
+    config.media.directories = vec![MonitoredDirectoryConfig {
+        path: media_dir.to_string_lossy().into_owned(),
+        recursive: true,
+        case_sensitive: None,
+        extensions: None,
+        exclude_patterns: None,
+        validation_mode: ValidationMode::Warn,
+        watch_mode: WatchMode::Auto,
+        merge_group: None,
+        priority: 0,
+        media_types: None,
+        min_file_size: None,
+        min_file_age_seconds: None,
+    }];
+    let audit_path = temp_dir.join("audit").join("streams.jsonl");
+    config.logging.stream_audit_file = Some(audit_path.clone());
+    config.media.max_concurrent_streams = max_concurrent_streams;
+    let config = Arc::new(config);
+    let stream_audit = Arc::new(StreamAuditLog::open(audit_path.clone()).unwrap());
+
+    let app_state = AppState {
+        media_directories: Arc::new(tokio::sync::RwLock::new(config.media.directories.clone())),
+        unavailable_roots: Arc::new(tokio::sync::RwLock::new(std::collections::HashSet::new())),
+        config: config.clone(),
+        live_config: Arc::new(vuio::state::LiveConfig::new(config.clone())),
+        desired_config: Arc::new(vuio::state::LiveConfig::new(config.clone())),
+        config_reload_errors: Arc::new(std::sync::RwLock::new(Vec::new())),
+        pending_restart_fields: Arc::new(std::sync::RwLock::new(Vec::new())),
+        database: db,
+        auth: Arc::new(vuio::web::auth::AuthState::testing()),
+        auth_forced: false,
+        platform_info: Arc::new(PlatformInfo::detect().await.unwrap()),
+        network_interfaces: Arc::default(),
+        filesystem_manager: Arc::from(create_platform_filesystem_manager()),
+        content_update_id: Arc::new(std::sync::atomic::AtomicU32::new(1)),
+        content_change_notify: Arc::new(tokio::sync::Notify::new()),
+        http_rebind_notify: Arc::new(tokio::sync::Notify::new()),
+        ssdp_reload_notify: Arc::new(tokio::sync::Notify::new()),
+        playlist_mirror_notify: Arc::new(tokio::sync::Notify::new()),
+        web_metrics: Arc::new(WebHandlerMetrics::new()),
+        stream_audit: Some(stream_audit.clone()),
+        scan_control: Arc::new(vuio::scan_progress::ScanControl::new()),
+        health: Arc::new(vuio::state::ServiceHealth::default()),
+        runtime_diagnostics: Arc::new(vuio::platform::diagnostics::SystemDiagnosticsSampler::new()),
+        lifecycle_stats: Arc::new(vuio::lifecycle::ApplicationStats::new()),
+        bookmarks: Arc::new(tokio::sync::Mutex::new(
+            vuio::runtime_state::BookmarkRegistry::new(vuio::runtime_state::BOOKMARK_MAX_ENTRIES),
+        )),
+        log_file_path: temp_dir.join("vuio.log"),
+        browse_cache: Arc::new(tokio::sync::Mutex::new(
+            vuio::runtime_state::BrowseResponseCache::new(),
+        )),
+        hidden_files: Arc::default(),
+        active_streams: Arc::default(),
+        container_update_ids: Arc::default(),
+        mcp_clients: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
+        active_monitors: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
+        active_casts: Arc::new(tokio::sync::Mutex::new(
+            vuio::runtime_state::ActiveCastRegistry::new(),
+        )),
+        cast_sessions: Arc::new(tokio::sync::Mutex::new(
+            vuio::runtime_state::CastSessionRegistry::new(),
+        )),
+        discovered_tvs: Arc::new(vuio::runtime_state::RendererCache::new()),
+        discovery_service: Arc::new(vuio::discovery::DiscoveryService::default()),
+        upnp_subscriptions: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
+        cancellation: tokio_util::sync::CancellationToken::new(),
+        background_tasks: tokio_util::task::TaskTracker::new(),
+    };
+    Fixture {
+        app_state,
+        file_id,
+        media_path,
+        audit_path,
+        stream_audit,
+    }
+}
+
+#[tokio::test]
+async fn streaming_a_file_appends_one_audit_line() {
+    let temp_dir = tempdir().unwrap();
+    let Fixture {
+        app_state,
+        file_id,
+        media_path,
+        audit_path,
+        stream_audit,
+    } = fixture(temp_dir.path(), 0).await;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::USER_AGENT, HeaderValue::from_static("VLC/3.0.20"));
+    headers.insert(header::RANGE, HeaderValue::from_static("bytes=1024-"));
+    let response = serve_media(
+        State(app_state),
+        ConnectInfo("192.168.1.44:50123".parse().unwrap()),
+        Path(file_id.to_string()),
+        Method::GET,
+        headers,
+    )
+    .await
+    .unwrap()
+    .into_response();
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body.len(), 3072);
+    stream_audit.flush();
+    let content = std::fs::read_to_string(&audit_path).unwrap();
+    let lines = content.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 1, "{content}");
+
+    let record: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+    assert!(chrono::DateTime::parse_from_rfc3339(record["timestamp"].as_str().unwrap()).is_ok());
+    assert_eq!(record["client_ip"], "192.168.1.44");
+    assert_eq!(record["user_agent"], "VLC/3.0.20");
+    assert_eq!(record["path"], media_path.to_string_lossy().as_ref());
+    assert_eq!(record["range_start"], 1024);
+    assert_eq!(record["range_end"], 4095);
+    assert_eq!(record["bytes_served"], 3072);
+    assert_eq!(record["completion_percent"], 100.0);
+    assert_eq!(record["completed"], true);
+    assert!(record["duration_ms"].is_u64());
+    assert_eq!(stream_audit.dropped_records(), 0);
+}
+
+#[tokio::test]
+async fn concurrent_streams_are_limited_per_client_and_object() {
+    let temp_dir = tempdir().unwrap();
+    let Fixture {
+        app_state, file_id, ..
+    } = fixture(temp_dir.path(), 1).await;
+    let stream = |client: &str, headers: HeaderMap| {
+        serve_media(
+            State(app_state.clone()),
+            ConnectInfo(client.parse().unwrap()),
+            Path(file_id.to_string()),
+            Method::GET,
+            headers,
+        )
+    };
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::RANGE, HeaderValue::from_static("bytes=1024-"));
+    let response = stream("192.168.1.44:50123", headers)
+        .await
+        .unwrap()
+        .into_response();
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+
+    // The same renderer seeking within its stream is not a second stream.
+    let seek = stream("192.168.1.44:50125", HeaderMap::new())
+        .await
+        .unwrap()
+        .into_response();
+    assert_eq!(seek.status(), StatusCode::OK);
+    assert_eq!(app_state.active_streams.active(), 1);
+
+    // The first stream's body is still open, so another client is refused.
+    let refused = stream("192.168.1.45:50124", HeaderMap::new())
+        .await
+        .unwrap()
+        .into_response();
+    assert_eq!(refused.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(refused.headers()[header::RETRY_AFTER], "10");
+    assert_eq!(app_state.active_streams.active(), 1);
+
+    drop(seek);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body.len(), 3072);
+    assert_eq!(app_state.active_streams.active(), 0);
+}